@@ -21,9 +21,13 @@
 
 pub use bp_polkadot_core::*;
 
+use bp_beefy::{decl_bridge_beefy_finality_runtime_apis, ChainWithBeefy};
 use bp_header_chain::ChainWithGrandpa;
 use bp_runtime::{decl_bridge_finality_runtime_apis, Chain, ChainId};
-use frame_support::{sp_runtime::StateVersion, weights::Weight};
+use frame_support::{
+	sp_runtime::{traits::Keccak256, StateVersion},
+	weights::Weight,
+};
 
 /// Rococo Chain
 pub struct Rococo;
@@ -61,6 +65,17 @@ impl ChainWithGrandpa for Rococo {
 	const AVERAGE_HEADER_SIZE: u32 = AVERAGE_HEADER_SIZE;
 }
 
+impl ChainWithBeefy for Rococo {
+	const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str = WITH_ROCOCO_BEEFY_PALLET_NAME;
+
+	type CommitmentHasher = Keccak256;
+	type MmrHashing = Keccak256;
+	type MmrHash = Hash;
+	type BeefyMmrLeafExtra = Hash;
+	type AuthorityId = bp_beefy::EcdsaValidatorId;
+	type AuthorityIdToMerkleLeaf = bp_beefy::BeefyEcdsaToEthereum;
+}
+
 // The SignedExtension used by Rococo.
 pub use bp_polkadot_core::CommonSignedExtension as SignedExtension;
 
@@ -69,6 +84,8 @@ pub const PARAS_PALLET_NAME: &str = "Paras";
 
 /// Name of the With-Rococo GRANDPA pallet instance that is deployed at bridged chains.
 pub const WITH_ROCOCO_GRANDPA_PALLET_NAME: &str = "BridgeRococoGrandpa";
+/// Name of the With-Rococo BEEFY pallet instance that is deployed at bridged chains.
+pub const WITH_ROCOCO_BEEFY_PALLET_NAME: &str = "BridgeRococoBeefy";
 /// Name of the With-Rococo parachains pallet instance that is deployed at bridged chains.
 pub const WITH_ROCOCO_BRIDGE_PARACHAINS_PALLET_NAME: &str = "BridgeRococoParachains";
 
@@ -80,3 +97,4 @@ pub const WITH_ROCOCO_BRIDGE_PARACHAINS_PALLET_NAME: &str = "BridgeRococoParacha
 pub const MAX_NESTED_PARACHAIN_HEAD_DATA_SIZE: u32 = 128;
 
 decl_bridge_finality_runtime_apis!(rococo, grandpa);
+decl_bridge_beefy_finality_runtime_apis!(rococo, Rococo);
@@ -21,9 +21,13 @@
 
 pub use bp_polkadot_core::*;
 
+use bp_beefy::{decl_bridge_beefy_finality_runtime_apis, ChainWithBeefy};
 use bp_header_chain::ChainWithGrandpa;
 use bp_runtime::{decl_bridge_finality_runtime_apis, Chain, ChainId};
-use frame_support::{sp_runtime::StateVersion, weights::Weight};
+use frame_support::{
+	sp_runtime::{traits::Keccak256, StateVersion},
+	weights::Weight,
+};
 
 /// Westend Chain
 pub struct Westend;
@@ -61,6 +65,17 @@ impl ChainWithGrandpa for Westend {
 	const AVERAGE_HEADER_SIZE: u32 = AVERAGE_HEADER_SIZE;
 }
 
+impl ChainWithBeefy for Westend {
+	const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str = WITH_WESTEND_BEEFY_PALLET_NAME;
+
+	type CommitmentHasher = Keccak256;
+	type MmrHashing = Keccak256;
+	type MmrHash = Hash;
+	type BeefyMmrLeafExtra = Hash;
+	type AuthorityId = bp_beefy::EcdsaValidatorId;
+	type AuthorityIdToMerkleLeaf = bp_beefy::BeefyEcdsaToEthereum;
+}
+
 // The SignedExtension used by Westend.
 pub use bp_polkadot_core::CommonSignedExtension as SignedExtension;
 
@@ -69,6 +84,8 @@ pub const PARAS_PALLET_NAME: &str = "Paras";
 
 /// Name of the With-Westend GRANDPA pallet instance that is deployed at bridged chains.
 pub const WITH_WESTEND_GRANDPA_PALLET_NAME: &str = "BridgeWestendGrandpa";
+/// Name of the With-Westend BEEFY pallet instance that is deployed at bridged chains.
+pub const WITH_WESTEND_BEEFY_PALLET_NAME: &str = "BridgeWestendBeefy";
 /// Name of the With-Westend parachains pallet instance that is deployed at bridged chains.
 pub const WITH_WESTEND_BRIDGE_PARACHAINS_PALLET_NAME: &str = "BridgeWestendParachains";
 
@@ -80,3 +97,4 @@ pub const WITH_WESTEND_BRIDGE_PARACHAINS_PALLET_NAME: &str = "BridgeWestendParac
 pub const MAX_NESTED_PARACHAIN_HEAD_DATA_SIZE: u32 = 128;
 
 decl_bridge_finality_runtime_apis!(westend, grandpa);
+decl_bridge_beefy_finality_runtime_apis!(westend, Westend);
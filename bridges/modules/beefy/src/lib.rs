@@ -32,7 +32,9 @@
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use bp_beefy::{ChainWithBeefy, InitializationData};
+use bp_beefy::{
+	ChainWithBeefy, ImportedCommitmentsInfoData, InitializationData, StoredHeaderBeefyInfoOf,
+};
 use sp_std::{boxed::Box, prelude::*};
 
 // Re-export in crate namespace for `construct_runtime!`
@@ -78,6 +80,8 @@ pub type BridgedBeefyMmrLeafExtra<T, I> = bp_beefy::BeefyMmrLeafExtraOf<BridgedC
 pub type BridgedMmrProof<T, I> = bp_beefy::MmrProofOf<BridgedChain<T, I>>;
 /// MMR leaf type, used by configured bridged chain.
 pub type BridgedBeefyMmrLeaf<T, I> = bp_beefy::BeefyMmrLeafOf<BridgedChain<T, I>>;
+/// BEEFY-related info associated to a header, used by configured bridged chain.
+pub type StoredHeaderBeefyInfo<T, I> = StoredHeaderBeefyInfoOf<BridgedChain<T, I>>;
 /// Imported commitment data, stored by the pallet.
 pub type ImportedCommitment<T, I> = bp_beefy::ImportedCommitment<
 	BridgedBlockNumber<T, I>,
@@ -85,16 +89,6 @@ pub type ImportedCommitment<T, I> = bp_beefy::ImportedCommitment<
 	BridgedMmrHash<T, I>,
 >;
 
-/// Some high level info about the imported commitments.
-#[derive(codec::Encode, codec::Decode, scale_info::TypeInfo)]
-pub struct ImportedCommitmentsInfoData<BlockNumber> {
-	/// Best known block number, provided in a BEEFY commitment. However this is not
-	/// the best proven block. The best proven block is this block's parent.
-	best_block_number: BlockNumber,
-	/// The head of the `ImportedBlockNumbers` ring buffer.
-	next_block_number_index: u32,
-}
-
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
 	use super::*;
@@ -104,6 +98,10 @@ pub mod pallet {
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
 		/// The upper bound on the number of requests allowed by the pallet.
 		///
 		/// A request refers to an action which writes a header to storage.
@@ -234,9 +232,13 @@ pub mod pallet {
 			// Update request count.
 			RequestCount::<T, I>::mutate(|count| *count += 1);
 			// Update authority set if needed.
-			if mmr_leaf.beefy_next_authority_set.id > current_authority_set_info.id {
-				CurrentAuthoritySetInfo::<T, I>::put(mmr_leaf.beefy_next_authority_set);
-			}
+			let new_authority_set_info =
+				if mmr_leaf.beefy_next_authority_set.id > current_authority_set_info.id {
+					CurrentAuthoritySetInfo::<T, I>::put(mmr_leaf.beefy_next_authority_set.clone());
+					Some(mmr_leaf.beefy_next_authority_set.clone())
+				} else {
+					None
+				};
 
 			// Import commitment.
 			let block_number_index = commitments_info.next_block_number_index;
@@ -271,6 +273,18 @@ pub mod pallet {
 				commitment.commitment.block_number,
 			);
 
+			Self::deposit_event(Event::UpdatedBestFinalizedCommitment {
+				number: commitment.commitment.block_number,
+				beefy_info: StoredHeaderBeefyInfo::<T, I> {
+					finality_proof: bp_beefy::BeefyFinalityProof {
+						parent_number_and_hash: mmr_leaf.parent_number_and_hash,
+						signed_commitment: commitment,
+						validator_set,
+					},
+					new_verification_context: new_authority_set_info,
+				},
+			});
+
 			Ok(())
 		}
 	}
@@ -355,6 +369,18 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Best finalized commitment has been updated to the commitment for given block number.
+		UpdatedBestFinalizedCommitment {
+			/// Number of the block, that the new best commitment is signed for.
+			number: BridgedBlockNumber<T, I>,
+			/// The BEEFY info associated to the new best commitment.
+			beefy_info: StoredHeaderBeefyInfo<T, I>,
+		},
+	}
+
 	#[pallet::error]
 	pub enum Error<T, I = ()> {
 		/// The pallet has not been initialized yet.
@@ -406,6 +432,25 @@ pub mod pallet {
 	}
 }
 
+impl<T: Config<I>, I: 'static> Pallet<T, I>
+where
+	<T as frame_system::Config>::RuntimeEvent: TryInto<Event<T, I>>,
+{
+	/// Get the BEEFY commitments accepted in the current block.
+	pub fn synced_headers_beefy_info() -> Vec<StoredHeaderBeefyInfo<T, I>> {
+		frame_system::Pallet::<T>::read_events_no_consensus()
+			.filter_map(|event| {
+				if let Event::<T, I>::UpdatedBestFinalizedCommitment { beefy_info, .. } =
+					event.event.try_into().ok()?
+				{
+					return Some(beefy_info)
+				}
+				None
+			})
+			.collect()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -647,5 +692,119 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn submit_commitment_deposits_beefy_info() {
+		run_test_with_initialize(8, || {
+			next_block();
+
+			let chain = ChainBuilder::new(8).append_finalized_header().append_handoff_header(4);
+			let header = chain.header(1);
+			assert_ok!(import_commitment(header.clone()));
+			assert_eq!(
+				Pallet::<TestRuntime>::synced_headers_beefy_info(),
+				vec![StoredHeaderBeefyInfo::<TestRuntime, ()> {
+					finality_proof: bp_beefy::BeefyFinalityProof {
+						parent_number_and_hash: header.leaf.parent_number_and_hash,
+						signed_commitment: header.commitment.unwrap(),
+						validator_set: header.validator_set,
+					},
+					new_verification_context: None,
+				}],
+			);
+
+			next_block();
+			frame_system::Pallet::<TestRuntime>::reset_events();
+			let header = chain.header(2);
+			assert_ok!(import_commitment(header.clone()));
+			assert_eq!(
+				Pallet::<TestRuntime>::synced_headers_beefy_info(),
+				vec![StoredHeaderBeefyInfo::<TestRuntime, ()> {
+					finality_proof: bp_beefy::BeefyFinalityProof {
+						parent_number_and_hash: header.leaf.parent_number_and_hash,
+						signed_commitment: header.commitment.unwrap(),
+						validator_set: header.validator_set,
+					},
+					new_verification_context: Some(header.leaf.beefy_next_authority_set),
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn equivocations_finder_detects_double_voting() {
+		use bp_beefy::{
+			BeefyEquivocationsFinder, BeefyFinalityProof, BeefyPayload, MMR_ROOT_PAYLOAD_ID,
+		};
+		use bp_header_chain::FindEquivocations;
+
+		let validators = validator_pairs(0, 4);
+		let authority_set = authority_set_info(0, &validator_ids(0, 4));
+		let header = ChainBuilder::new(4).append_finalized_header().to_header();
+		let commitment = header.commitment.clone().unwrap().commitment;
+		let synced_proof = BeefyFinalityProof {
+			parent_number_and_hash: header.leaf.parent_number_and_hash,
+			signed_commitment: sign_commitment(commitment.clone(), &validators, 4),
+			validator_set: header.validator_set.clone(),
+		};
+
+		// the same commitment, signed by the same validators is not an equivocation
+		let mut same_proof = synced_proof.clone();
+		same_proof.signed_commitment = sign_commitment(commitment.clone(), &validators, 4);
+		assert_eq!(
+			BeefyEquivocationsFinder::<TestBridgedChain>::find_equivocations(
+				&authority_set,
+				&synced_proof,
+				&[same_proof],
+			),
+			Ok(vec![]),
+		);
+
+		// the commitment for the same block, but with different payload is an equivocation
+		let mut forged_commitment = commitment.clone();
+		forged_commitment.payload =
+			BeefyPayload::from_single_entry(MMR_ROOT_PAYLOAD_ID, vec![42u8; 32]);
+		let mut forged_proof = synced_proof.clone();
+		forged_proof.signed_commitment = sign_commitment(forged_commitment, &validators, 4);
+		let equivocations = BeefyEquivocationsFinder::<TestBridgedChain>::find_equivocations(
+			&authority_set,
+			&synced_proof,
+			&[forged_proof.clone(), forged_proof],
+		)
+		.unwrap();
+		assert_eq!(equivocations.len(), 4);
+		assert_eq!(
+			equivocations.iter().map(|e| e.offender_id().clone()).collect::<Vec<_>>(),
+			validator_ids(0, 4),
+		);
+
+		// synced proof, signed by unknown validator set is rejected
+		assert_eq!(
+			BeefyEquivocationsFinder::<TestBridgedChain>::find_equivocations(
+				&authority_set_info(1, &validator_ids(0, 4)),
+				&synced_proof,
+				&[],
+			),
+			Err(bp_beefy::BeefyEquivocationsFinderError::InvalidValidatorSetId),
+		);
+	}
+
+	#[test]
+	fn storage_keys_computed_properly() {
+		use bp_runtime::StorageMapKeyProvider;
+
+		assert_eq!(
+			ImportedCommitmentsInfo::<TestRuntime>::hashed_key().to_vec(),
+			bp_beefy::storage_keys::imported_commitments_info_key("Beefy").0,
+		);
+		assert_eq!(
+			ImportedCommitments::<TestRuntime>::hashed_key_for(42),
+			bp_beefy::ImportedCommitmentsKeyProvider::<TestBridgedChain>::final_key("Beefy", &42).0,
+		);
+		assert_eq!(
+			CurrentAuthoritySetInfo::<TestRuntime>::hashed_key().to_vec(),
+			bp_beefy::storage_keys::current_authority_set_info_key("Beefy").0,
+		);
+	}
+
 	generate_owned_bridge_module_tests!(BasicOperatingMode::Normal, BasicOperatingMode::Halted);
 }
@@ -142,7 +142,7 @@ impl InspectMessageQueues for TestToBridgeHubSender {
 				.map(|(location, message)| {
 					(
 						VersionedLocation::V4(location.clone()),
-						vec![VersionedXcm::V4(message.clone())],
+						vec![VersionedXcm::V5(message.clone())],
 					)
 				})
 				.collect()
@@ -31,20 +31,23 @@ pub use sp_consensus_beefy::{
 	},
 	known_payloads::MMR_ROOT_ID as MMR_ROOT_PAYLOAD_ID,
 	mmr::{BeefyAuthoritySet, MmrLeafVersion},
-	BeefyAuthorityId, Commitment, Payload as BeefyPayload, SignedCommitment, ValidatorSet,
-	ValidatorSetId, BEEFY_ENGINE_ID,
+	BeefyAuthorityId, Commitment, DoubleVotingProof, Payload as BeefyPayload, SignedCommitment,
+	ValidatorSet, ValidatorSetId, VersionedFinalityProof, VoteMessage, BEEFY_ENGINE_ID,
 };
 
-use bp_runtime::{BasicOperatingMode, BlockNumberOf, Chain, HashOf};
+use bp_header_chain::{FinalityProof, FindEquivocations, HeaderFinalityInfo};
+use bp_runtime::{BasicOperatingMode, BlockNumberOf, Chain, HashOf, StorageMapKeyProvider};
 use codec::{Decode, Encode};
-use frame_support::Parameter;
+use frame_support::{Blake2_128Concat, Parameter};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_runtime::{
 	traits::{Convert, MaybeSerializeDeserialize},
 	RuntimeAppPublic, RuntimeDebug,
 };
-use sp_std::prelude::*;
+use sp_std::{collections::btree_set::BTreeSet, marker::PhantomData, prelude::*};
+
+pub mod storage_keys;
 
 /// Substrate-based chain with BEEFY && MMR pallets deployed.
 ///
@@ -52,6 +55,13 @@ use sp_std::prelude::*;
 /// primitives. Some of types can be configured in low-level pallets, but are constrained
 /// when BEEFY+MMR bundle is used.
 pub trait ChainWithBeefy: Chain {
+	/// Name of the bridge BEEFY pallet (used in `construct_runtime` macro call) that is deployed
+	/// at some other chain to bridge with this `ChainWithBeefy`.
+	///
+	/// We assume that all chains that are bridging with this `ChainWithBeefy` are using
+	/// the same name.
+	const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str;
+
 	/// The hashing algorithm used to compute the digest of the BEEFY commitment.
 	///
 	/// Corresponds to the hashing algorithm, used by `sc_consensus_beefy::BeefyKeystore`.
@@ -126,6 +136,20 @@ pub type BeefyMmrLeafOf<C> = sp_consensus_beefy::mmr::MmrLeaf<
 	BeefyMmrLeafExtraOf<C>,
 >;
 
+/// BEEFY finality proof used by given Substrate chain.
+pub type BeefyFinalityProofOf<C> = BeefyFinalityProof<
+	BlockNumberOf<C>,
+	HashOf<C>,
+	BeefyAuthorityIdOf<C>,
+	BeefyValidatorSignatureOf<C>,
+>;
+/// BEEFY double voting proof used by given Substrate chain.
+pub type BeefyEquivocationProofOf<C> =
+	DoubleVotingProof<BlockNumberOf<C>, BeefyAuthorityIdOf<C>, BeefyValidatorSignatureOf<C>>;
+/// BEEFY-related info associated to a header, which is saved to events.
+pub type StoredHeaderBeefyInfoOf<C> =
+	HeaderFinalityInfo<BeefyFinalityProofOf<C>, BeefyAuthoritySetInfoOf<C>>;
+
 /// Data required for initializing the BEEFY pallet.
 ///
 /// Provides the initial context that the bridge needs in order to know
@@ -149,3 +173,212 @@ pub struct ImportedCommitment<BlockNumber, BlockHash, MmrHash> {
 	/// MMR root at the imported block.
 	pub mmr_root: MmrHash,
 }
+
+/// Imported commitment data of given Substrate chain.
+pub type ImportedCommitmentOf<C> = ImportedCommitment<BlockNumberOf<C>, HashOf<C>, MmrHashOf<C>>;
+
+/// Some high level info about the imported commitments.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, TypeInfo)]
+pub struct ImportedCommitmentsInfoData<BlockNumber> {
+	/// Best known block number, provided in a BEEFY commitment. However this is not
+	/// the best proven block. The best proven block is this block's parent.
+	pub best_block_number: BlockNumber,
+	/// The head of the `ImportedBlockNumbers` ring buffer.
+	pub next_block_number_index: u32,
+}
+
+/// Can be use to access the runtime storage key of the imported commitments at the target chain.
+///
+/// The commitments are stored by the `pallet-bridge-beefy` pallet in the `ImportedCommitments`
+/// map.
+pub struct ImportedCommitmentsKeyProvider<C>(PhantomData<C>);
+impl<C: ChainWithBeefy> StorageMapKeyProvider for ImportedCommitmentsKeyProvider<C> {
+	const MAP_NAME: &'static str = "ImportedCommitments";
+
+	type Hasher = Blake2_128Concat;
+	type Key = BlockNumberOf<C>;
+	type Value = ImportedCommitmentOf<C>;
+}
+
+/// Convenience macro that declares the bridge BEEFY finality runtime api and related constants
+/// for a chain.
+///
+/// The `<ThisChain>BeefyFinalityApi` is implemented by runtimes that have the bridge BEEFY
+/// pallet, bridging with this chain, deployed. Its only method has the following name:
+/// - `<ThisChain>BeefyFinalityApi_synced_headers_beefy_info`, stored in the
+///   `<THIS_CHAIN>_SYNCED_HEADERS_BEEFY_INFO_METHOD` constant.
+#[macro_export]
+macro_rules! decl_bridge_beefy_finality_runtime_apis {
+	($chain: ident, $chain_type: ty) => {
+		bp_runtime::paste::item! {
+			mod [<$chain _beefy_finality_api>] {
+				use super::*;
+
+				/// Name of the `<ThisChain>BeefyFinalityApi::synced_headers_beefy_info` runtime
+				/// method.
+				pub const [<$chain:upper _SYNCED_HEADERS_BEEFY_INFO_METHOD>]: &str =
+					stringify!([<$chain:camel BeefyFinalityApi_synced_headers_beefy_info>]);
+
+				sp_api::decl_runtime_apis! {
+					/// API for querying information about the BEEFY commitments of this chain,
+					/// accepted by the bridge BEEFY pallet.
+					///
+					/// This API is implemented by runtimes that are bridging with this chain, not
+					/// by this chain's runtime itself.
+					pub trait [<$chain:camel BeefyFinalityApi>] {
+						/// Returns the BEEFY info of the commitments accepted in the current block.
+						fn synced_headers_beefy_info(
+						) -> sp_std::vec::Vec<$crate::StoredHeaderBeefyInfoOf<$chain_type>>;
+					}
+				}
+			}
+
+			pub use [<$chain _beefy_finality_api>]::*;
+		}
+	};
+}
+
+/// Signed BEEFY commitment together with the data required to verify it.
+///
+/// BEEFY commitments are not referencing block hashes directly. The best header that we can
+/// prove using the commitment is the parent of the commitment block (its number and hash are
+/// stored in the MMR leaf), so this parent is treated as the header that the proof is
+/// generated for.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Clone, TypeInfo)]
+pub struct BeefyFinalityProof<BlockNumber, BlockHash, AuthorityId, Signature> {
+	/// Block number and hash of the commitment block parent.
+	pub parent_number_and_hash: (BlockNumber, BlockHash),
+	/// The signed commitment.
+	pub signed_commitment: SignedCommitment<BlockNumber, Signature>,
+	/// Validator set that has signed the commitment.
+	pub validator_set: ValidatorSet<AuthorityId>,
+}
+
+impl<BlockNumber, BlockHash, AuthorityId, Signature> FinalityProof<BlockHash, BlockNumber>
+	for BeefyFinalityProof<BlockNumber, BlockHash, AuthorityId, Signature>
+where
+	BlockNumber: Copy + Send + Sync + core::fmt::Debug,
+	BlockHash: Copy + Send + Sync + core::fmt::Debug,
+	AuthorityId: Clone + Send + Sync + core::fmt::Debug,
+	Signature: Clone + Send + Sync + core::fmt::Debug,
+{
+	fn target_header_hash(&self) -> BlockHash {
+		self.parent_number_and_hash.1
+	}
+
+	fn target_header_number(&self) -> BlockNumber {
+		self.parent_number_and_hash.0
+	}
+}
+
+/// Errors that may happen when looking for equivocations in BEEFY finality proofs.
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub enum BeefyEquivocationsFinderError {
+	/// The id of the validator set that has signed the synced commitment is not matching
+	/// the id of the authority set, known to the bridge pallet.
+	InvalidValidatorSetId,
+	/// The number of validators in the set that has signed the synced commitment is not
+	/// matching the length of the authority set, known to the bridge pallet.
+	InvalidValidatorSetLen,
+	/// The validators that have signed the synced commitment are not matching the merkle
+	/// root of the authority set, known to the bridge pallet.
+	InvalidValidatorSetRoot,
+	/// The number of signatures in the synced commitment is invalid.
+	InvalidCommitmentSignaturesLen,
+}
+
+/// Helper struct for finding equivocations in BEEFY finality proofs.
+///
+/// The synced commitment (the one that has been accepted by the bridge pallet) is compared
+/// with commitments that are produced at the source chain. If some validator has signed both
+/// commitments for the same block, but with different payloads, it is a double voting
+/// equivocation.
+pub struct BeefyEquivocationsFinder<C>(PhantomData<C>);
+
+impl<C: ChainWithBeefy>
+	FindEquivocations<
+		BeefyFinalityProofOf<C>,
+		BeefyAuthoritySetInfoOf<C>,
+		BeefyEquivocationProofOf<C>,
+	> for BeefyEquivocationsFinder<C>
+{
+	type Error = BeefyEquivocationsFinderError;
+
+	fn find_equivocations(
+		verification_context: &BeefyAuthoritySetInfoOf<C>,
+		synced_proof: &BeefyFinalityProofOf<C>,
+		source_proofs: &[BeefyFinalityProofOf<C>],
+	) -> Result<Vec<BeefyEquivocationProofOf<C>>, Self::Error> {
+		let validator_set = &synced_proof.validator_set;
+		if validator_set.id() != verification_context.id {
+			return Err(BeefyEquivocationsFinderError::InvalidValidatorSetId)
+		}
+		if validator_set.len() != verification_context.len as usize {
+			return Err(BeefyEquivocationsFinderError::InvalidValidatorSetLen)
+		}
+		let validators_root = merkle_root::<MmrHashingOf<C>, _>(
+			validator_set
+				.validators()
+				.iter()
+				.cloned()
+				.map(BeefyAuthorityIdToMerkleLeafOf::<C>::convert),
+		);
+		if validators_root != verification_context.keyset_commitment {
+			return Err(BeefyEquivocationsFinderError::InvalidValidatorSetRoot)
+		}
+
+		let synced_commitment = &synced_proof.signed_commitment;
+		if synced_commitment.signatures.len() != validator_set.len() {
+			return Err(BeefyEquivocationsFinderError::InvalidCommitmentSignaturesLen)
+		}
+
+		let mut equivocations = vec![];
+		let mut equivocators = BTreeSet::new();
+		let conflicting_commitments = source_proofs
+			.iter()
+			.map(|source_proof| &source_proof.signed_commitment)
+			.filter(|source_commitment| {
+				source_commitment.commitment.block_number ==
+					synced_commitment.commitment.block_number &&
+					source_commitment.commitment.validator_set_id ==
+						synced_commitment.commitment.validator_set_id &&
+					source_commitment.commitment.payload != synced_commitment.commitment.payload
+			});
+		for source_commitment in conflicting_commitments {
+			for (idx, validator) in validator_set.validators().iter().enumerate() {
+				if equivocators.contains(&idx) {
+					continue
+				}
+
+				let (Some(Some(first_signature)), Some(Some(second_signature))) =
+					(synced_commitment.signatures.get(idx), source_commitment.signatures.get(idx))
+				else {
+					continue
+				};
+
+				let equivocation = DoubleVotingProof {
+					first: VoteMessage {
+						commitment: synced_commitment.commitment.clone(),
+						id: validator.clone(),
+						signature: first_signature.clone(),
+					},
+					second: VoteMessage {
+						commitment: source_commitment.commitment.clone(),
+						id: validator.clone(),
+						signature: second_signature.clone(),
+					},
+				};
+				// we only care about valid votes - the invalid ones can't be used to prove
+				// anything
+				if sp_consensus_beefy::check_double_voting_proof::<_, _, BeefyCommitmentHasher<C>>(
+					&equivocation,
+				) {
+					equivocators.insert(idx);
+					equivocations.push(equivocation);
+				}
+			}
+		}
+
+		Ok(equivocations)
+	}
+}
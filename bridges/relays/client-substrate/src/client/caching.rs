@@ -363,6 +363,15 @@ impl<C: Chain, B: Client<C>> Client<C> for CachingClient<C, B> {
 		.await
 	}
 
+	async fn generate_beefy_key_ownership_proof<AuthorityId: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		set_id: sp_consensus_beefy::ValidatorSetId,
+		authority_id: AuthorityId,
+	) -> Result<Option<sp_consensus_beefy::OpaqueKeyOwnershipProof>> {
+		self.backend.generate_beefy_key_ownership_proof(at, set_id, authority_id).await
+	}
+
 	async fn token_decimals(&self) -> Result<Option<u64>> {
 		self.backend.token_decimals().await
 	}
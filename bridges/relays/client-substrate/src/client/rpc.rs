@@ -66,6 +66,7 @@ const SUB_API_TXPOOL_VALIDATE_TRANSACTION: &str = "TaggedTransactionQueue_valida
 const SUB_API_TX_PAYMENT_QUERY_INFO: &str = "TransactionPaymentApi_query_info";
 const SUB_API_GRANDPA_GENERATE_KEY_OWNERSHIP_PROOF: &str =
 	"GrandpaApi_generate_key_ownership_proof";
+const SUB_API_BEEFY_GENERATE_KEY_OWNERSHIP_PROOF: &str = "BeefyApi_generate_key_ownership_proof";
 
 /// Client implementation that connects to the Substrate node over `ws`/`wss` connection
 /// and is using RPC methods to get required data and submit transactions.
@@ -432,6 +433,20 @@ impl<C: Chain> Client<C> for RpcClient<C> {
 		.await
 	}
 
+	async fn generate_beefy_key_ownership_proof<AuthorityId: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		set_id: sp_consensus_beefy::ValidatorSetId,
+		authority_id: AuthorityId,
+	) -> Result<Option<sp_consensus_beefy::OpaqueKeyOwnershipProof>> {
+		self.state_call(
+			at,
+			SUB_API_BEEFY_GENERATE_KEY_OWNERSHIP_PROOF.into(),
+			(set_id, authority_id),
+		)
+		.await
+	}
+
 	async fn token_decimals(&self) -> Result<Option<u64>> {
 		self.jsonrpsee_execute(move |client| async move {
 			let system_properties = SubstrateSystemClient::<C>::properties(&*client).await?;
@@ -95,6 +95,14 @@ pub trait Client<C: Chain>: 'static + Send + Sync + Clone + Debug {
 	/// Subscribe to BEEFY finality justifications.
 	async fn subscribe_beefy_finality_justifications(&self) -> Result<Subscription<Bytes>>;
 
+	/// Generates a proof of key ownership for the given BEEFY authority in the given set.
+	async fn generate_beefy_key_ownership_proof<AuthorityId: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		set_id: sp_consensus_beefy::ValidatorSetId,
+		authority_id: AuthorityId,
+	) -> Result<Option<sp_consensus_beefy::OpaqueKeyOwnershipProof>>;
+
 	/// Return `tokenDecimals` property from the set of chain properties.
 	async fn token_decimals(&self) -> Result<Option<u64>>;
 	/// Get runtime version of the connected chain.
@@ -28,12 +28,14 @@ pub mod calls;
 pub mod guard;
 pub mod metrics;
 pub mod test_chain;
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod test_client;
 
 use std::time::Duration;
 
 pub use crate::{
 	chain::{
-		AccountKeyPairOf, BlockWithJustification, CallOf, Chain, ChainWithBalances,
+		AccountKeyPairOf, BlockWithJustification, CallOf, Chain, ChainWithBalances, ChainWithBeefy,
 		ChainWithGrandpa, ChainWithMessages, ChainWithRuntimeVersion, ChainWithTransactions,
 		ChainWithUtilityPallet, FullRuntimeUtilityPallet, MockedRuntimeUtilityPallet, Parachain,
 		RelayChain, SignParam, SignedBlockOf, TransactionStatusOf, UnsignedTransaction,
@@ -21,7 +21,8 @@
 
 #![cfg(any(feature = "test-helpers", test))]
 
-use crate::{Chain, ChainWithBalances, ChainWithMessages};
+use crate::{Chain, ChainWithBalances, ChainWithGrandpa, ChainWithMessages};
+use bp_header_chain::ChainWithGrandpa as ChainWithGrandpaBase;
 use bp_messages::{ChainWithMessages as ChainWithMessagesBase, MessageNonce};
 use bp_runtime::ChainId;
 use frame_support::{sp_runtime::StateVersion, weights::Weight};
@@ -73,6 +74,20 @@ impl ChainWithBalances for TestChain {
 	}
 }
 
+impl ChainWithGrandpaBase for TestChain {
+	const WITH_CHAIN_GRANDPA_PALLET_NAME: &'static str = "Test";
+	const MAX_AUTHORITIES_COUNT: u32 = 16;
+	const REASONABLE_HEADERS_IN_JUSTIFICATION_ANCESTRY: u32 = 8;
+	const MAX_MANDATORY_HEADER_SIZE: u32 = 256;
+	const AVERAGE_HEADER_SIZE: u32 = 64;
+}
+
+impl ChainWithGrandpa for TestChain {
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str = "TestMethod";
+
+	type KeyOwnerProof = ();
+}
+
 impl ChainWithMessagesBase for TestChain {
 	const WITH_CHAIN_MESSAGES_PALLET_NAME: &'static str = "Test";
 	const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 0;
@@ -0,0 +1,246 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test client implementation to use in tests.
+//!
+//! The client only serves runtime storage and runtime calls, which are the same at every
+//! block. All other methods are panicking.
+
+use crate::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, Chain, ChainWithGrandpa, ChainWithTransactions,
+	Client, Error, HashOf, HeaderIdOf, HeaderOf, NonceOf, Result, SignedBlockOf,
+	SimpleRuntimeVersion, Subscription, TransactionTracker, UnsignedTransaction,
+};
+
+use async_trait::async_trait;
+use codec::Encode;
+use frame_support::weights::Weight;
+use sp_core::{
+	storage::{StorageData, StorageKey},
+	Bytes, Pair,
+};
+use sp_runtime::transaction_validity::TransactionValidity;
+use sp_trie::StorageProof;
+use sp_version::RuntimeVersion;
+use std::{collections::HashMap, fmt::Debug, marker::PhantomData};
+
+/// Client that may be used in tests.
+#[derive(Clone)]
+pub struct TestClient<C> {
+	/// Runtime storage.
+	pub storage: HashMap<StorageKey, Vec<u8>>,
+	/// Encoded results of runtime calls, by the method name.
+	pub state_calls: HashMap<String, Vec<u8>>,
+	_phantom: PhantomData<C>,
+}
+
+impl<C> Default for TestClient<C> {
+	fn default() -> Self {
+		Self { storage: HashMap::new(), state_calls: HashMap::new(), _phantom: PhantomData }
+	}
+}
+
+impl<C> Debug for TestClient<C> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("TestClient")
+			.field("storage", &self.storage)
+			.field("state_calls", &self.state_calls)
+			.finish()
+	}
+}
+
+impl<C> TestClient<C> {
+	/// Put encoded `value` under the `key` into the runtime storage.
+	pub fn with_storage_value(mut self, key: StorageKey, value: impl Encode) -> Self {
+		self.storage.insert(key, value.encode());
+		self
+	}
+
+	/// Make runtime `method` return encoded `result`.
+	pub fn with_state_call(mut self, method: &str, result: impl Encode) -> Self {
+		self.state_calls.insert(method.into(), result.encode());
+		self
+	}
+}
+
+#[async_trait]
+impl<C: Chain> Client<C> for TestClient<C> {
+	async fn ensure_synced(&self) -> Result<()> {
+		Ok(())
+	}
+
+	async fn reconnect(&self) -> Result<()> {
+		Ok(())
+	}
+
+	fn genesis_hash(&self) -> HashOf<C> {
+		unimplemented!()
+	}
+
+	async fn header_hash_by_number(&self, _number: BlockNumberOf<C>) -> Result<HashOf<C>> {
+		unimplemented!()
+	}
+
+	async fn header_by_hash(&self, _hash: HashOf<C>) -> Result<HeaderOf<C>> {
+		unimplemented!()
+	}
+
+	async fn block_by_hash(&self, _hash: HashOf<C>) -> Result<SignedBlockOf<C>> {
+		unimplemented!()
+	}
+
+	async fn best_finalized_header_hash(&self) -> Result<HashOf<C>> {
+		unimplemented!()
+	}
+
+	async fn best_header(&self) -> Result<HeaderOf<C>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_finalized_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
+	where
+		C: ChainWithGrandpa,
+	{
+		unimplemented!()
+	}
+
+	async fn generate_grandpa_key_ownership_proof(
+		&self,
+		_at: HashOf<C>,
+		_set_id: sp_consensus_grandpa::SetId,
+		_authority_id: sp_consensus_grandpa::AuthorityId,
+	) -> Result<Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_beefy_finality_justifications(&self) -> Result<Subscription<Bytes>> {
+		unimplemented!()
+	}
+
+	async fn generate_beefy_key_ownership_proof<AuthorityId: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_set_id: sp_consensus_beefy::ValidatorSetId,
+		_authority_id: AuthorityId,
+	) -> Result<Option<sp_consensus_beefy::OpaqueKeyOwnershipProof>> {
+		unimplemented!()
+	}
+
+	async fn token_decimals(&self) -> Result<Option<u64>> {
+		unimplemented!()
+	}
+
+	async fn runtime_version(&self) -> Result<RuntimeVersion> {
+		unimplemented!()
+	}
+
+	async fn simple_runtime_version(&self) -> Result<SimpleRuntimeVersion> {
+		unimplemented!()
+	}
+
+	fn can_start_version_guard(&self) -> bool {
+		false
+	}
+
+	async fn raw_storage_value(
+		&self,
+		_at: HashOf<C>,
+		storage_key: StorageKey,
+	) -> Result<Option<StorageData>> {
+		Ok(self.storage.get(&storage_key).cloned().map(StorageData))
+	}
+
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
+		unimplemented!()
+	}
+
+	async fn submit_unsigned_extrinsic(&self, _transaction: Bytes) -> Result<HashOf<C>> {
+		unimplemented!()
+	}
+
+	async fn submit_signed_extrinsic(
+		&self,
+		_signer: &AccountKeyPairOf<C>,
+		_prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<HashOf<C>>
+	where
+		C: ChainWithTransactions,
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		unimplemented!()
+	}
+
+	async fn submit_and_watch_signed_extrinsic(
+		&self,
+		_signer: &AccountKeyPairOf<C>,
+		_prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<TransactionTracker<C, Self>>
+	where
+		C: ChainWithTransactions,
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		unimplemented!()
+	}
+
+	async fn validate_transaction<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_transaction: SignedTransaction,
+	) -> Result<TransactionValidity> {
+		unimplemented!()
+	}
+
+	async fn estimate_extrinsic_weight<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_transaction: SignedTransaction,
+	) -> Result<Weight> {
+		unimplemented!()
+	}
+
+	async fn raw_state_call<Args: Encode + Send>(
+		&self,
+		_at: HashOf<C>,
+		method: String,
+		_arguments: Args,
+	) -> Result<Bytes> {
+		self.state_calls
+			.get(&method)
+			.cloned()
+			.map(Bytes)
+			.ok_or_else(|| Error::Custom(format!("Unexpected runtime call: {method}")))
+	}
+
+	async fn prove_storage(
+		&self,
+		_at: HashOf<C>,
+		_keys: Vec<StorageKey>,
+	) -> Result<(StorageProof, HashOf<C>)> {
+		unimplemented!()
+	}
+}
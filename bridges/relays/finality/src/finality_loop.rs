@@ -31,8 +31,9 @@ use backoff::{backoff::Backoff, ExponentialBackoff};
 use futures::{future::Fuse, select, Future, FutureExt};
 use num_traits::{Saturating, Zero};
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, retry_backoff, FailedClient,
-	HeaderId, MaybeConnectionError, TrackedTransactionStatus, TransactionTracker,
+	metrics::MetricsParams, relay_loop::Client as RelayClient, retry_backoff,
+	state_store::PipelineStateStore, FailedClient, HeaderId, MaybeConnectionError,
+	TrackedTransactionStatus, TransactionTracker, UniqueSaturatedInto,
 };
 use std::{
 	fmt::Debug,
@@ -75,6 +76,9 @@ pub struct FinalitySyncParams {
 	pub stall_timeout: Duration,
 	/// If true, only mandatory headers are relayed.
 	pub headers_to_relay: HeadersToRelay,
+	/// Persistent state store. If `Some(_)`, submitted transactions are recorded there, so
+	/// that the relay doesn't resubmit the same header after restart.
+	pub state_store: Option<PipelineStateStore>,
 }
 
 /// Source client used in finality synchronization loop.
@@ -264,6 +268,8 @@ struct FinalityLoop<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClie
 	finality_proofs_stream: FinalityProofsStream<P, SC>,
 	finality_proofs_buf: FinalityProofsBuf<P>,
 	best_submitted_number: Option<P::Number>,
+	/// Header number and stall deadline of the transaction, submitted before restart.
+	resumed_submission: Option<(u64, Instant)>,
 }
 
 impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> FinalityLoop<P, SC, TC> {
@@ -273,6 +279,18 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		sync_params: FinalitySyncParams,
 		metrics_sync: Option<SyncLoopMetrics>,
 	) -> Self {
+		let resumed_submission = sync_params.state_store.as_ref().and_then(|state_store| {
+			state_store.pending_transaction().map(|pending| {
+				log::info!(
+					target: "bridge",
+					"Waiting for previously submitted finality proof of {} header #{} at {}",
+					P::SOURCE_NAME,
+					pending.last_submitted(),
+					P::TARGET_NAME,
+				);
+				(pending.last_submitted(), Instant::now() + pending.remaining)
+			})
+		});
 		Self {
 			source_client,
 			target_client,
@@ -283,6 +301,44 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 			finality_proofs_stream: FinalityProofsStream::new(),
 			finality_proofs_buf: FinalityProofsBuf::new(vec![]),
 			best_submitted_number: None,
+			resumed_submission,
+		}
+	}
+
+	/// Returns true if we're still waiting for the transaction, submitted before restart.
+	fn is_waiting_for_resumed_submission(&mut self, info: &SyncInfo<P>) -> bool {
+		let Some((header_number, deadline)) = self.resumed_submission else { return false };
+		let best_number_at_target: u64 = info.best_number_at_target.unique_saturated_into();
+		if best_number_at_target >= header_number {
+			log::info!(
+				target: "bridge",
+				"Previously submitted finality proof of {} header #{} has been accepted by {}",
+				P::SOURCE_NAME,
+				header_number,
+				P::TARGET_NAME,
+			);
+		} else if Instant::now() >= deadline {
+			log::warn!(
+				target: "bridge",
+				"Previously submitted finality proof of {} header #{} has not been accepted by {}. \
+				Treating it as lost",
+				P::SOURCE_NAME,
+				header_number,
+				P::TARGET_NAME,
+			);
+		} else {
+			return true
+		}
+
+		self.resumed_submission = None;
+		self.transaction_completed();
+		false
+	}
+
+	/// Forget about submitted transaction in the state store.
+	fn transaction_completed(&self) {
+		if let Some(ref state_store) = self.sync_params.state_store {
+			state_store.transaction_completed();
 		}
 	}
 
@@ -379,6 +435,10 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 		if Some(info.best_number_at_target) < self.best_submitted_number {
 			return Ok(None)
 		}
+		// same, but for the transaction that has been submitted before restart
+		if self.is_waiting_for_resumed_submission(&info) {
+			return Ok(None)
+		}
 
 		// submit new header if we have something new
 		match self.select_header_to_submit(&info, free_headers_interval).await? {
@@ -392,6 +452,10 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 				.await
 				.map_err(Error::Target)?;
 				self.best_submitted_number = Some(transaction.header_number);
+				if let Some(ref state_store) = self.sync_params.state_store {
+					let header_number: u64 = transaction.header_number.unique_saturated_into();
+					state_store.transaction_submitted(header_number..=header_number);
+				}
 				Ok(Some(transaction))
 			},
 			None => Ok(None),
@@ -446,6 +510,7 @@ impl<P: FinalitySyncPipeline, SC: SourceClient<P>, TC: TargetClient<P>> Finality
 			// wait till exit signal, or new source block
 			select! {
 				proof_submission_result = proof_submission_tx_tracker => {
+					self.transaction_completed();
 					if let Err(e) = proof_submission_result {
 						log::error!(
 							target: "bridge",
@@ -598,6 +663,7 @@ mod tests {
 			recent_finality_proofs_limit: 1024,
 			stall_timeout: Duration::from_secs(1),
 			headers_to_relay: HeadersToRelay::All,
+			state_store: None,
 		}
 	}
 
@@ -708,6 +774,7 @@ mod tests {
 					recent_finality_proofs_limit: 0,
 					stall_timeout: Duration::from_secs(0),
 					headers_to_relay,
+					state_store: None,
 				},
 				None,
 			);
@@ -795,4 +862,54 @@ mod tests {
 
 		assert!(!metrics_sync.is_using_same_fork());
 	}
+
+	#[test]
+	fn transaction_submitted_before_restart_is_not_resubmitted() {
+		let state_dir = tempfile::tempdir().unwrap();
+		let state_store = relay_utils::state_store::RelayStateStore::open(state_dir.path())
+			.unwrap()
+			.pipeline("TestSource_to_TestTarget_Sync", Duration::from_secs(60));
+		state_store.transaction_submitted(8..=8);
+
+		let (exit_sender, _exit_receiver) = futures::channel::mpsc::unbounded();
+		let (source_client, target_client) = prepare_test_clients(
+			exit_sender,
+			|_| false,
+			vec![
+				(5, (TestSourceHeader(false, 5, 5), None)),
+				(8, (TestSourceHeader(true, 8, 8), Some(TestFinalityProof(8)))),
+				(9, (TestSourceHeader(false, 9, 9), None)),
+				(10, (TestSourceHeader(false, 10, 10), Some(TestFinalityProof(10)))),
+			]
+			.into_iter()
+			.collect(),
+		);
+		let clients_data = source_client.data.clone();
+
+		async_std::task::block_on(async {
+			let mut finality_loop = FinalityLoop::new(
+				source_client,
+				target_client,
+				FinalitySyncParams { state_store: Some(state_store.clone()), ..test_sync_params() },
+				None,
+			);
+
+			// header#8 has been submitted before restart => we're waiting for it
+			assert!(finality_loop.run_iteration(None).await.unwrap().is_none());
+			assert!(clients_data.lock().target_headers.is_empty());
+			assert!(state_store.pending_transaction().is_some());
+
+			// header#8 is accepted by the target chain => we may submit next header
+			clients_data.lock().target_best_block_id = HeaderId(8, 8);
+			assert!(finality_loop.run_iteration(None).await.unwrap().is_some());
+			assert_eq!(
+				clients_data.lock().target_headers,
+				vec![(TestSourceHeader(false, 10, 10), TestFinalityProof(10))],
+			);
+			assert_eq!(
+				state_store.pending_transaction().map(|pending| pending.submitted),
+				Some(10..=10),
+			);
+		});
+	}
 }
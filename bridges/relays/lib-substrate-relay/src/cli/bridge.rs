@@ -17,7 +17,9 @@
 //! Basic traits for exposing bridges in the CLI.
 
 use crate::{
-	equivocation::SubstrateEquivocationDetectionPipeline,
+	equivocation::{
+		beefy::SubstrateBeefyEquivocationDetectionPipeline, SubstrateEquivocationDetectionPipeline,
+	},
 	finality::SubstrateFinalitySyncPipeline,
 	messages::{MessagesRelayLimits, SubstrateMessageLane},
 	parachains::SubstrateParachainsPipeline,
@@ -72,6 +74,18 @@ pub trait RelayToRelayEquivocationDetectionCliBridge:
 	>;
 }
 
+/// Bridge representation that can be used from the CLI for detecting BEEFY equivocations
+/// in the commitments synchronized from a relay chain to some other chain.
+pub trait RelayToRelayBeefyEquivocationDetectionCliBridge:
+	RelayToRelayEquivocationDetectionCliBridgeBase
+{
+	/// BEEFY equivocation detection pipeline.
+	type BeefyEquivocation: SubstrateBeefyEquivocationDetectionPipeline<
+		SourceChain = Self::Source,
+		TargetChain = Self::Target,
+	>;
+}
+
 /// Bridge representation that can be used from the CLI for relaying headers
 /// from a parachain to a relay chain.
 pub trait ParachainToRelayHeadersCliBridge: CliBridgeBase
@@ -19,7 +19,9 @@
 use crate::{
 	cli::{bridge::*, chain_schema::*, PrometheusParams},
 	equivocation,
-	equivocation::SubstrateEquivocationDetectionPipeline,
+	equivocation::{
+		beefy::SubstrateBeefyEquivocationDetectionPipeline, SubstrateEquivocationDetectionPipeline,
+	},
 };
 
 use async_trait::async_trait;
@@ -63,3 +65,28 @@ where
 		.await
 	}
 }
+
+/// Trait used for starting the BEEFY equivocation detection loop between 2 chains.
+#[async_trait]
+pub trait BeefyEquivocationsDetector: RelayToRelayBeefyEquivocationDetectionCliBridge
+where
+	Self::Source: ChainWithTransactions,
+{
+	/// Start the BEEFY equivocation detection loop.
+	async fn start(data: DetectEquivocationsParams) -> anyhow::Result<()> {
+		let source_client = data.source.into_client::<Self::Source>().await?;
+		Self::BeefyEquivocation::start_relay_guards(
+			&source_client,
+			source_client.can_start_version_guard(),
+		)
+		.await?;
+
+		equivocation::beefy::run::<Self::BeefyEquivocation>(
+			source_client,
+			data.target.into_client::<Self::Target>().await?,
+			data.source_sign.transaction_params::<Self::Source>()?,
+			data.prometheus_params.into_metrics_params()?,
+		)
+		.await
+	}
+}
@@ -18,9 +18,10 @@
 
 use bp_messages::LaneId;
 use rbtag::BuildInfo;
+use relay_utils::state_store::RelayStateStore;
 use sp_core::H256;
 use sp_runtime::Either;
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 use structopt::StructOpt;
 use strum::{EnumString, VariantNames};
 
@@ -90,6 +91,26 @@ pub struct PrometheusParams {
 	pub prometheus_port: u16,
 }
 
+/// Persistent relay state params.
+#[derive(Clone, Debug, PartialEq, StructOpt)]
+pub struct RelayerStateParams {
+	/// Directory where the relayer keeps its state (e.g. submitted transactions), so that it
+	/// doesn't resubmit the same transactions after restart. If not passed, the state is not
+	/// persisted.
+	#[structopt(long)]
+	pub relayer_state_dir: Option<PathBuf>,
+}
+
+impl RelayerStateParams {
+	/// Opens relay state store, if it is configured.
+	pub fn into_state_store(self) -> anyhow::Result<Option<RelayStateStore>> {
+		self.relayer_state_dir
+			.map(RelayStateStore::open)
+			.transpose()
+			.map_err(|e| anyhow::format_err!("{:?}", e))
+	}
+}
+
 /// Struct to get git commit info and build time.
 #[derive(BuildInfo)]
 struct SubstrateRelayBuildInfo;
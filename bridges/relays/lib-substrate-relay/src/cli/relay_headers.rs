@@ -25,7 +25,7 @@ use relay_utils::{
 };
 
 use crate::{
-	cli::{bridge::*, chain_schema::*, PrometheusParams},
+	cli::{bridge::*, chain_schema::*, PrometheusParams, RelayerStateParams},
 	finality::SubstrateFinalitySyncPipeline,
 	HeadersToRelay,
 };
@@ -50,6 +50,8 @@ pub struct RelayHeadersParams {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	relayer_state_params: RelayerStateParams,
 }
 
 /// Single header relaying params.
@@ -87,6 +89,7 @@ pub trait HeadersRelayer: RelayToRelayHeadersCliBridge {
 		let target_client = data.target.into_client::<Self::Target>().await?;
 		let target_transactions_mortality = data.target_sign.target_transactions_mortality;
 		let target_sign = data.target_sign.to_keypair::<Self::Target>()?;
+		let state_store = data.relayer_state_params.into_state_store()?;
 
 		let metrics_params: relay_utils::metrics::MetricsParams =
 			data.prometheus_params.into_metrics_params()?;
@@ -105,6 +108,7 @@ pub trait HeadersRelayer: RelayToRelayHeadersCliBridge {
 			headers_to_relay,
 			target_transactions_params,
 			metrics_params,
+			state_store,
 		)
 		.await
 	}
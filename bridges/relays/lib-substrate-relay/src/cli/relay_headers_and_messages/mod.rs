@@ -37,7 +37,9 @@ use structopt::StructOpt;
 use futures::{FutureExt, TryFutureExt};
 
 use crate::{
-	cli::{bridge::MessagesCliBridge, DefaultClient, HexLaneId, PrometheusParams},
+	cli::{
+		bridge::MessagesCliBridge, DefaultClient, HexLaneId, PrometheusParams, RelayerStateParams,
+	},
 	messages::{MessagesRelayLimits, MessagesRelayParams},
 	on_demand::OnDemandRelay,
 	HeadersToRelay, TaggedAccount, TransactionParams,
@@ -48,7 +50,7 @@ use relay_substrate_client::{
 	AccountIdOf, AccountKeyPairOf, Chain, ChainWithBalances, ChainWithMessages,
 	ChainWithRuntimeVersion, ChainWithTransactions,
 };
-use relay_utils::metrics::MetricsParams;
+use relay_utils::{metrics::MetricsParams, state_store::RelayStateStore};
 use sp_core::Pair;
 
 /// Parameters that have the same names across all bridges.
@@ -68,6 +70,9 @@ pub struct HeadersAndMessagesSharedParams {
 	#[structopt(flatten)]
 	/// Prometheus metrics params.
 	pub prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	/// Persistent relay state params.
+	pub relayer_state_params: RelayerStateParams,
 }
 
 impl HeadersAndMessagesSharedParams {
@@ -94,6 +99,8 @@ pub struct Full2WayBridgeCommonParams<
 
 	/// Common metric parameters.
 	pub metrics_params: MetricsParams,
+	/// Persistent relay state store.
+	pub state_store: Option<RelayStateStore>,
 }
 
 impl<
@@ -110,8 +117,10 @@ impl<
 		// Create metrics registry.
 		let metrics_params = shared.prometheus_params.clone().into_metrics_params()?;
 		let metrics_params = relay_utils::relay_metrics(metrics_params).into_params();
+		// Open persistent state store.
+		let state_store = shared.relayer_state_params.clone().into_state_store()?;
 
-		Ok(Self { shared, left, right, metrics_params })
+		Ok(Self { shared, left, right, metrics_params, state_store })
 	}
 }
 
@@ -135,6 +144,7 @@ pub struct FullBridge<
 	source: &'a mut BridgeEndCommonParams<Source>,
 	target: &'a mut BridgeEndCommonParams<Target>,
 	metrics_params: &'a MetricsParams,
+	state_store: &'a Option<RelayStateStore>,
 	_phantom_data: PhantomData<Bridge>,
 }
 
@@ -154,8 +164,9 @@ where
 		source: &'a mut BridgeEndCommonParams<Source>,
 		target: &'a mut BridgeEndCommonParams<Target>,
 		metrics_params: &'a MetricsParams,
+		state_store: &'a Option<RelayStateStore>,
 	) -> Self {
-		Self { source, target, metrics_params, _phantom_data: Default::default() }
+		Self { source, target, metrics_params, state_store, _phantom_data: Default::default() }
 	}
 
 	/// Returns message relay parameters.
@@ -176,6 +187,7 @@ where
 			lane_id,
 			limits: maybe_limits,
 			metrics_params: self.metrics_params.clone().disable(),
+			state_store: self.state_store.clone(),
 		}
 	}
 }
@@ -254,6 +266,7 @@ where
 			&mut common.left,
 			&mut common.right,
 			&common.metrics_params,
+			&common.state_store,
 		)
 	}
 
@@ -264,6 +277,7 @@ where
 			&mut common.right,
 			&mut common.left,
 			&common.metrics_params,
+			&common.state_store,
 		)
 	}
 
@@ -427,6 +441,8 @@ mod tests {
 			"0000000000000000000000000000000000000000000000000000000000000000",
 			"--prometheus-host",
 			"0.0.0.0",
+			"--relayer-state-dir",
+			"/var/lib/substrate-relay",
 		]);
 
 		// then
@@ -442,6 +458,9 @@ mod tests {
 						prometheus_host: "0.0.0.0".into(),
 						prometheus_port: 9616,
 					},
+					relayer_state_params: RelayerStateParams {
+						relayer_state_dir: Some("/var/lib/substrate-relay".into()),
+					},
 				},
 				left: BridgeHubKusamaConnectionParams {
 					bridge_hub_kusama_uri: None,
@@ -174,6 +174,7 @@ where
 				self.common.right.tx_params.clone(),
 				self.common.shared.headers_to_relay(),
 				None,
+				self.common.state_store.clone(),
 			);
 		let right_relay_to_left_on_demand_headers = OnDemandHeadersRelay::<
 			<R2L as ParachainToRelayHeadersCliBridge>::RelayFinality,
@@ -185,6 +186,7 @@ where
 			self.common.left.tx_params.clone(),
 			self.common.shared.headers_to_relay(),
 			Some(self.common.metrics_params.clone()),
+			self.common.state_store.clone(),
 		);
 		let right_to_left_on_demand_parachains = OnDemandParachainsRelay::<
 			<R2L as ParachainToRelayHeadersCliBridge>::ParachainFinality,
@@ -195,6 +197,7 @@ where
 			self.common.left.client.clone(),
 			self.common.left.tx_params.clone(),
 			Arc::new(right_relay_to_left_on_demand_headers),
+			self.common.state_store.clone(),
 		);
 
 		Ok((
@@ -154,6 +154,7 @@ where
 				self.common.right.tx_params.clone(),
 				self.common.shared.headers_to_relay(),
 				None,
+				self.common.state_store.clone(),
 			);
 		let right_to_left_on_demand_headers =
 			OnDemandHeadersRelay::<<R2L as RelayToRelayHeadersCliBridge>::Finality, _, _>::new(
@@ -162,6 +163,7 @@ where
 				self.common.left.tx_params.clone(),
 				self.common.shared.headers_to_relay(),
 				None,
+				self.common.state_store.clone(),
 			);
 
 		Ok((Arc::new(left_to_right_on_demand_headers), Arc::new(right_to_left_on_demand_headers)))
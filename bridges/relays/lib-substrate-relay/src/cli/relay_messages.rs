@@ -17,7 +17,7 @@
 //! Primitives for exposing the messages relaying functionality in the CLI.
 
 use crate::{
-	cli::{bridge::*, chain_schema::*, HexLaneId, PrometheusParams},
+	cli::{bridge::*, chain_schema::*, HexLaneId, PrometheusParams, RelayerStateParams},
 	messages::MessagesRelayParams,
 	TransactionParams,
 };
@@ -50,6 +50,8 @@ pub struct RelayMessagesParams {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	relayer_state_params: RelayerStateParams,
 }
 
 /// Messages range relaying params.
@@ -133,6 +135,7 @@ where
 			lane_id: data.lane.into(),
 			limits: Self::maybe_messages_limits(),
 			metrics_params: data.prometheus_params.into_metrics_params()?,
+			state_store: data.relayer_state_params.into_state_store()?,
 		})
 		.await
 		.map_err(|e| anyhow::format_err!("{}", e))
@@ -21,7 +21,7 @@ use async_trait::async_trait;
 use bp_polkadot_core::BlockNumber as RelayBlockNumber;
 use bp_runtime::HeaderIdProvider;
 use parachains_relay::parachains_loop::{AvailableHeader, SourceClient, TargetClient};
-use relay_substrate_client::{Client, Parachain};
+use relay_substrate_client::{transaction_stall_timeout, Chain, Client, Parachain};
 use relay_utils::metrics::{GlobalMetrics, StandaloneMetric};
 use std::sync::Arc;
 use structopt::StructOpt;
@@ -30,7 +30,7 @@ use crate::{
 	cli::{
 		bridge::{CliBridgeBase, ParachainToRelayHeadersCliBridge},
 		chain_schema::*,
-		DefaultClient, PrometheusParams,
+		DefaultClient, PrometheusParams, RelayerStateParams,
 	},
 	parachains::{source::ParachainsSource, target::ParachainsTarget, ParachainsPipelineAdapter},
 	TransactionParams,
@@ -51,6 +51,8 @@ pub struct RelayParachainsParams {
 	only_free_headers: bool,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	relayer_state_params: RelayerStateParams,
 }
 
 /// Single parachains head relaying params.
@@ -93,12 +95,25 @@ where
 			signer: data.target_sign.to_keypair::<Self::Target>()?,
 			mortality: data.target_sign.target_transactions_mortality,
 		};
+		let stall_timeout = transaction_stall_timeout(
+			target_transaction_params.mortality,
+			<Self::Target as Chain>::AVERAGE_BLOCK_INTERVAL,
+			relay_utils::STALL_TIMEOUT,
+		);
 		let target_chain_client = data.target.into_client::<Self::Target>().await?;
 		let target_client = ParachainsTarget::<Self::ParachainFinality, _, _>::new(
 			source_chain_client,
 			target_chain_client,
 			target_transaction_params,
 		);
+		let state_store = data.relayer_state_params.into_state_store()?.map(|state_store| {
+			state_store.pipeline(
+				&parachains_relay::parachains_loop::metrics_prefix::<
+					ParachainsPipelineAdapter<Self::ParachainFinality>,
+				>(),
+				stall_timeout,
+			)
+		});
 
 		let metrics_params: relay_utils::metrics::MetricsParams =
 			data.prometheus_params.into_metrics_params()?;
@@ -109,6 +124,7 @@ where
 			target_client,
 			metrics_params,
 			data.only_free_headers,
+			state_store,
 			futures::future::pending(),
 		)
 		.await
@@ -0,0 +1,206 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types and functions intended to ease adding of new Substrate -> Substrate
+//! BEEFY equivocation detection pipelines.
+//!
+//! The BEEFY commitments, accepted by the bridge BEEFY pallet at the target chain, are
+//! compared with the commitments that are produced at the source chain. If some validator
+//! has signed conflicting commitments, the `report_double_voting` call is submitted to the
+//! source chain.
+
+mod source;
+mod target;
+
+use crate::{
+	equivocation::beefy::{
+		source::SubstrateBeefyEquivocationSource, target::SubstrateBeefyEquivocationTarget,
+	},
+	TransactionParams,
+};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	BeefyAuthorityIdOf, BeefyAuthoritySetInfoOf, BeefyEquivocationProofOf,
+	BeefyEquivocationsFinder, BeefyFinalityProofOf,
+};
+use bp_runtime::{BlockNumberOf, HashOf};
+use equivocation_detector::EquivocationDetectionPipeline;
+use finality_relay::FinalityPipeline;
+use pallet_beefy::{Call as BeefyCall, Config as BeefyConfig};
+use relay_substrate_client::{
+	AccountKeyPairOf, CallOf, Chain, ChainWithBeefy, ChainWithTransactions, Client,
+};
+use relay_utils::metrics::MetricsParams;
+use sp_core::Pair;
+use sp_runtime::traits::{Block, Header};
+use std::{fmt::Debug, marker::PhantomData};
+
+/// The type of the key owner proof used by the `SubstrateBeefyEquivocationDetectionPipeline`.
+pub type BeefyKeyOwnerProofOf<P> =
+	<<P as SubstrateBeefyEquivocationDetectionPipeline>::SourceChain as ChainWithBeefy>::BeefyKeyOwnerProof;
+/// The type of the equivocation proof used by the `SubstrateBeefyEquivocationDetectionPipeline`.
+pub type BeefyEquivocationProofOfPipeline<P> =
+	BeefyEquivocationProofOf<<P as SubstrateBeefyEquivocationDetectionPipeline>::SourceChain>;
+
+/// Substrate -> Substrate BEEFY equivocation detection pipeline.
+#[async_trait]
+pub trait SubstrateBeefyEquivocationDetectionPipeline:
+	'static + Clone + Debug + Send + Sync
+{
+	/// Commitments of this chain are submitted to the `TargetChain`.
+	type SourceChain: ChainWithBeefy + ChainWithTransactions<AccountId = Self::SourceChainAccountId>;
+	/// Bounded `AccountIdOf<Self::SourceChain>`.
+	type SourceChainAccountId: From<<AccountKeyPairOf<Self::SourceChain> as Pair>::Public> + Send;
+	/// Commitments of the `SourceChain` are submitted to this chain.
+	type TargetChain: Chain;
+
+	/// How the `report_double_voting` call is built ?
+	type ReportDoubleVotingCallBuilder: ReportDoubleVotingCallBuilder<Self>;
+
+	/// Add relay guards if required.
+	async fn start_relay_guards(
+		source_client: &impl Client<Self::SourceChain>,
+		enable_version_guard: bool,
+	) -> relay_substrate_client::Result<()> {
+		if enable_version_guard {
+			relay_substrate_client::guard::abort_on_spec_version_change(
+				source_client.clone(),
+				source_client.simple_runtime_version().await?.spec_version,
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Adapter that allows a `SubstrateBeefyEquivocationDetectionPipeline` to act as an
+/// `EquivocationDetectionPipeline`.
+#[derive(Clone, Debug)]
+pub struct BeefyEquivocationDetectionPipelineAdapter<P: SubstrateBeefyEquivocationDetectionPipeline>
+{
+	_phantom: PhantomData<P>,
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline> FinalityPipeline
+	for BeefyEquivocationDetectionPipelineAdapter<P>
+{
+	const SOURCE_NAME: &'static str = P::SourceChain::NAME;
+	const TARGET_NAME: &'static str = P::TargetChain::NAME;
+
+	type Hash = HashOf<P::SourceChain>;
+	type Number = BlockNumberOf<P::SourceChain>;
+	type FinalityProof = BeefyFinalityProofOf<P::SourceChain>;
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline> EquivocationDetectionPipeline
+	for BeefyEquivocationDetectionPipelineAdapter<P>
+{
+	type TargetNumber = BlockNumberOf<P::TargetChain>;
+	type FinalityVerificationContext = BeefyAuthoritySetInfoOf<P::SourceChain>;
+	type EquivocationProof = BeefyEquivocationProofOf<P::SourceChain>;
+	type EquivocationsFinder = BeefyEquivocationsFinder<P::SourceChain>;
+}
+
+/// Different ways of building `report_double_voting` calls.
+pub trait ReportDoubleVotingCallBuilder<P: SubstrateBeefyEquivocationDetectionPipeline> {
+	/// Build a `report_double_voting` call to be executed on the source chain.
+	fn build_report_double_voting_call(
+		equivocation_proof: BeefyEquivocationProofOfPipeline<P>,
+		key_owner_proof: BeefyKeyOwnerProofOf<P>,
+	) -> CallOf<P::SourceChain>;
+}
+
+/// Building the `report_double_voting` call when having direct access to the source chain
+/// runtime.
+pub struct DirectReportBeefyDoubleVotingCallBuilder<P, R> {
+	_phantom: PhantomData<(P, R)>,
+}
+
+impl<P, R> ReportDoubleVotingCallBuilder<P> for DirectReportBeefyDoubleVotingCallBuilder<P, R>
+where
+	P: SubstrateBeefyEquivocationDetectionPipeline,
+	R: frame_system::Config
+		+ BeefyConfig<
+			BeefyId = BeefyAuthorityIdOf<P::SourceChain>,
+			KeyOwnerProof = BeefyKeyOwnerProofOf<P>,
+		>,
+	<R::Block as Block>::Header: Header<Number = BlockNumberOf<P::SourceChain>>,
+	CallOf<P::SourceChain>: From<BeefyCall<R>>,
+{
+	fn build_report_double_voting_call(
+		equivocation_proof: BeefyEquivocationProofOfPipeline<P>,
+		key_owner_proof: BeefyKeyOwnerProofOf<P>,
+	) -> CallOf<P::SourceChain> {
+		BeefyCall::<R>::report_double_voting {
+			equivocation_proof: Box::new(equivocation_proof),
+			key_owner_proof,
+		}
+		.into()
+	}
+}
+
+/// Macro that generates `ReportDoubleVotingCallBuilder` implementation for the case where
+/// we only have access to the mocked version of the source chain runtime.
+#[rustfmt::skip]
+#[macro_export]
+macro_rules! generate_report_double_voting_call_builder {
+	($pipeline:ident, $mocked_builder:ident, $beefy:path, $report_double_voting:path) => {
+		pub struct $mocked_builder;
+
+		impl $crate::equivocation::beefy::ReportDoubleVotingCallBuilder<$pipeline>
+			for $mocked_builder
+		{
+			fn build_report_double_voting_call(
+				equivocation_proof: $crate::equivocation::beefy::BeefyEquivocationProofOfPipeline<$pipeline>,
+				key_owner_proof: $crate::equivocation::beefy::BeefyKeyOwnerProofOf<$pipeline>,
+			) -> relay_substrate_client::CallOf<
+				<$pipeline as $crate::equivocation::beefy::SubstrateBeefyEquivocationDetectionPipeline>::SourceChain
+			> {
+				bp_runtime::paste::item! {
+					$beefy($report_double_voting {
+						equivocation_proof: Box::new(equivocation_proof),
+						key_owner_proof: key_owner_proof
+					})
+				}
+			}
+		}
+	};
+}
+
+/// Run Substrate-to-Substrate BEEFY equivocations detection loop.
+pub async fn run<P: SubstrateBeefyEquivocationDetectionPipeline>(
+	source_client: impl Client<P::SourceChain>,
+	target_client: impl Client<P::TargetChain>,
+	source_transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
+	metrics_params: MetricsParams,
+) -> anyhow::Result<()> {
+	log::info!(
+		target: "bridge",
+		"Starting {} -> {} BEEFY equivocations detection loop",
+		P::SourceChain::NAME,
+		P::TargetChain::NAME,
+	);
+
+	equivocation_detector::run(
+		SubstrateBeefyEquivocationSource::<P, _>::new(source_client, source_transaction_params),
+		SubstrateBeefyEquivocationTarget::<P, _>::new(target_client),
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+		metrics_params,
+		futures::future::pending(),
+	)
+	.await
+	.map_err(|e| anyhow::format_err!("{}", e))
+}
@@ -0,0 +1,222 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Default generic implementation of BEEFY equivocation source for basic Substrate client.
+
+use crate::{
+	equivocation::beefy::{
+		BeefyEquivocationDetectionPipelineAdapter, BeefyEquivocationProofOfPipeline,
+		ReportDoubleVotingCallBuilder, SubstrateBeefyEquivocationDetectionPipeline,
+	},
+	TransactionParams,
+};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	BeefyAuthoritySetOf, BeefyFinalityProof, BeefyFinalityProofOf, BeefyValidatorSignatureOf,
+	VersionedFinalityProof,
+};
+use bp_runtime::{BlockNumberOf, HashOf, TransactionEra};
+use codec::Decode;
+use equivocation_detector::SourceClient;
+use finality_relay::SourceClientBase;
+use futures::{stream::unfold, Stream, StreamExt};
+use num_traits::One;
+use relay_substrate_client::{
+	AccountKeyPairOf, Chain, Client, Error, TransactionTracker, UnsignedTransaction,
+};
+use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::{
+	scale_info::TypeInfo,
+	traits::{Header, Saturating},
+};
+use std::pin::Pin;
+
+/// Name of the runtime API method that returns the current BEEFY validator set.
+const SUB_API_BEEFY_VALIDATOR_SET: &str = "BeefyApi_validator_set";
+
+/// BEEFY finality proofs stream.
+pub type SubstrateBeefyFinalityProofsStream<C> =
+	Pin<Box<dyn Stream<Item = BeefyFinalityProofOf<C>> + Send>>;
+
+/// Substrate node as BEEFY equivocation source.
+pub struct SubstrateBeefyEquivocationSource<
+	P: SubstrateBeefyEquivocationDetectionPipeline,
+	SourceClnt,
+> {
+	client: SourceClnt,
+	transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChain>>
+	SubstrateBeefyEquivocationSource<P, SourceClnt>
+{
+	/// Create new instance of `SubstrateBeefyEquivocationSource`.
+	pub fn new(
+		client: SourceClnt,
+		transaction_params: TransactionParams<AccountKeyPairOf<P::SourceChain>>,
+	) -> Self {
+		Self { client, transaction_params }
+	}
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChain>> Clone
+	for SubstrateBeefyEquivocationSource<P, SourceClnt>
+{
+	fn clone(&self) -> Self {
+		Self { client: self.client.clone(), transaction_params: self.transaction_params.clone() }
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChain>> RelayClient
+	for SubstrateBeefyEquivocationSource<P, SourceClnt>
+{
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		self.client.reconnect().await
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChain>>
+	SourceClientBase<BeefyEquivocationDetectionPipelineAdapter<P>>
+	for SubstrateBeefyEquivocationSource<P, SourceClnt>
+{
+	type FinalityProofsStream = SubstrateBeefyFinalityProofsStream<P::SourceChain>;
+
+	async fn finality_proofs(&self) -> Result<Self::FinalityProofsStream, Error> {
+		beefy_finality_proofs::<P::SourceChain>(self.client.clone()).await
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, SourceClnt: Client<P::SourceChain>>
+	SourceClient<BeefyEquivocationDetectionPipelineAdapter<P>>
+	for SubstrateBeefyEquivocationSource<P, SourceClnt>
+{
+	type TransactionTracker = TransactionTracker<P::SourceChain, SourceClnt>;
+
+	async fn report_equivocation(
+		&self,
+		at: HashOf<P::SourceChain>,
+		equivocation: BeefyEquivocationProofOfPipeline<P>,
+	) -> Result<Self::TransactionTracker, Self::Error> {
+		let set_id = equivocation.set_id();
+		let offender = equivocation.offender_id().clone();
+
+		let opaque_key_owner_proof = self
+			.client
+			.generate_beefy_key_ownership_proof(at, set_id, offender.clone())
+			.await?
+			.ok_or(Error::Custom(format!(
+				"Couldn't get BEEFY key ownership proof from {} at block: {at} \
+				for offender: {:?}, set_id: {set_id} ",
+				P::SourceChain::NAME,
+				offender,
+			)))?;
+
+		let key_owner_proof =
+			opaque_key_owner_proof.decode().ok_or(Error::Custom(format!(
+				"Couldn't decode BEEFY `OpaqueKeyOwnershipProof` from {} at block: {at} \
+				to `{:?}` for offender: {:?}, set_id: {set_id}",
+				P::SourceChain::NAME,
+				<<P::SourceChain as relay_substrate_client::ChainWithBeefy>::BeefyKeyOwnerProof as TypeInfo>::type_info().path,
+				offender,
+			)))?;
+
+		let mortality = self.transaction_params.mortality;
+		let call = P::ReportDoubleVotingCallBuilder::build_report_double_voting_call(
+			equivocation,
+			key_owner_proof,
+		);
+		self.client
+			.submit_and_watch_signed_extrinsic(
+				&self.transaction_params.signer,
+				move |best_block_id, transaction_nonce| {
+					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+						.era(TransactionEra::new(best_block_id, mortality)))
+				},
+			)
+			.await
+	}
+}
+
+/// Subscribe to BEEFY justifications of the source chain.
+///
+/// Every justification is accompanied by the data that is required to compare it with
+/// commitments, accepted by the bridge BEEFY pallet: number and hash of the commitment block
+/// parent and the validator set that has signed the commitment.
+async fn beefy_finality_proofs<C: relay_substrate_client::ChainWithBeefy>(
+	client: impl Client<C>,
+) -> Result<SubstrateBeefyFinalityProofsStream<C>, Error> {
+	Ok(unfold(
+		(client.subscribe_beefy_finality_justifications().await?, client),
+		move |(mut subscription, client)| async move {
+			loop {
+				let next_justification = subscription.next().await?;
+				match beefy_finality_proof::<C>(&client, &next_justification[..]).await {
+					Ok(proof) => return Some((proof, (subscription, client))),
+					Err(err) => {
+						log::error!(
+							target: "bridge",
+							"Failed to read BEEFY justification from the {} justifications stream: {:?}",
+							C::NAME,
+							err,
+						);
+						continue
+					},
+				}
+			}
+		},
+	)
+	.boxed())
+}
+
+/// Decode BEEFY justification and read data required to build `BeefyFinalityProof`.
+async fn beefy_finality_proof<C: relay_substrate_client::ChainWithBeefy>(
+	client: &impl Client<C>,
+	mut encoded_justification: &[u8],
+) -> Result<BeefyFinalityProofOf<C>, Error> {
+	let VersionedFinalityProof::V1(signed_commitment) = VersionedFinalityProof::<
+		BlockNumberOf<C>,
+		BeefyValidatorSignatureOf<C>,
+	>::decode(&mut encoded_justification)
+	.map_err(|e| Error::Custom(format!("decode failed with error {e:?}")))?;
+
+	let commitment_block_number = signed_commitment.commitment.block_number;
+	let commitment_block = client.header_by_number(commitment_block_number).await?;
+	let commitment_block_hash = commitment_block.hash();
+	let validator_set: Option<BeefyAuthoritySetOf<C>> = client
+		.state_call(commitment_block_hash, SUB_API_BEEFY_VALIDATOR_SET.into(), ())
+		.await?;
+	let validator_set = validator_set.ok_or_else(|| {
+		Error::Custom(format!(
+			"BEEFY validator set is missing from {} at block {commitment_block_hash}",
+			C::NAME,
+		))
+	})?;
+
+	Ok(BeefyFinalityProof {
+		parent_number_and_hash: (
+			commitment_block_number.saturating_sub(One::one()),
+			*commitment_block.parent_hash(),
+		),
+		signed_commitment,
+		validator_set,
+	})
+}
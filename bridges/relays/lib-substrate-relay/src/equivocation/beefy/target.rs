@@ -0,0 +1,246 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Default generic implementation of BEEFY equivocation target for basic Substrate client.
+
+use crate::equivocation::beefy::{
+	BeefyEquivocationDetectionPipelineAdapter, SubstrateBeefyEquivocationDetectionPipeline,
+};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	BeefyAuthoritySetInfoOf, ChainWithBeefy as _, ImportedCommitmentsInfoData,
+	ImportedCommitmentsKeyProvider, StoredHeaderBeefyInfoOf,
+};
+use bp_runtime::{BlockNumberOf, HashOf};
+use equivocation_detector::TargetClient;
+use relay_substrate_client::{Chain, ChainWithBeefy, Client, Error};
+use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::traits::Header;
+use std::marker::PhantomData;
+
+/// Substrate node as BEEFY equivocation target.
+pub struct SubstrateBeefyEquivocationTarget<
+	P: SubstrateBeefyEquivocationDetectionPipeline,
+	TargetClnt,
+> {
+	client: TargetClnt,
+
+	_phantom: PhantomData<P>,
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, TargetClnt: Client<P::TargetChain>>
+	SubstrateBeefyEquivocationTarget<P, TargetClnt>
+{
+	/// Create new instance of `SubstrateBeefyEquivocationTarget`.
+	pub fn new(client: TargetClnt) -> Self {
+		Self { client, _phantom: Default::default() }
+	}
+}
+
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, TargetClnt: Client<P::TargetChain>> Clone
+	for SubstrateBeefyEquivocationTarget<P, TargetClnt>
+{
+	fn clone(&self) -> Self {
+		Self { client: self.client.clone(), _phantom: Default::default() }
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, TargetClnt: Client<P::TargetChain>> RelayClient
+	for SubstrateBeefyEquivocationTarget<P, TargetClnt>
+{
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		self.client.reconnect().await
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefyEquivocationDetectionPipeline, TargetClnt: Client<P::TargetChain>>
+	TargetClient<BeefyEquivocationDetectionPipelineAdapter<P>>
+	for SubstrateBeefyEquivocationTarget<P, TargetClnt>
+{
+	async fn best_finalized_header_number(
+		&self,
+	) -> Result<BlockNumberOf<P::TargetChain>, Self::Error> {
+		self.client.best_finalized_header_number().await
+	}
+
+	async fn best_synced_header_hash(
+		&self,
+		at: BlockNumberOf<P::TargetChain>,
+	) -> Result<Option<HashOf<P::SourceChain>>, Self::Error> {
+		best_synced_header_hash::<P::SourceChain, P::TargetChain>(
+			&self.client,
+			self.client.header_by_number(at).await?.hash(),
+		)
+		.await
+	}
+
+	async fn finality_verification_context(
+		&self,
+		at: BlockNumberOf<P::TargetChain>,
+	) -> Result<BeefyAuthoritySetInfoOf<P::SourceChain>, Self::Error> {
+		let current_authority_set_info_key = bp_beefy::storage_keys::current_authority_set_info_key(
+			P::SourceChain::WITH_CHAIN_BEEFY_PALLET_NAME,
+		);
+		self.client
+			.storage_value(
+				self.client.header_by_number(at).await?.hash(),
+				current_authority_set_info_key,
+			)
+			.await?
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"{} `CurrentAuthoritySetInfo` is missing from the {} storage",
+					P::SourceChain::NAME,
+					P::TargetChain::NAME,
+				))
+			})
+	}
+
+	async fn synced_headers_finality_info(
+		&self,
+		at: BlockNumberOf<P::TargetChain>,
+	) -> Result<Vec<StoredHeaderBeefyInfoOf<P::SourceChain>>, Self::Error> {
+		self.client
+			.state_call(
+				self.client.header_by_number(at).await?.hash(),
+				P::SourceChain::SYNCED_HEADERS_BEEFY_INFO_METHOD.to_string(),
+				(),
+			)
+			.await
+	}
+}
+
+/// Get the hash of the best `SourceChain` header known to the bridge BEEFY pallet at the
+/// provided `TargetChain` block.
+///
+/// The pallet is not exposing this header through a runtime API, so it is read from its storage.
+/// The best proven header is the parent of the best imported commitment block.
+pub async fn best_synced_header_hash<SourceChain, TargetChain>(
+	target_client: &impl Client<TargetChain>,
+	at: HashOf<TargetChain>,
+) -> Result<Option<HashOf<SourceChain>>, Error>
+where
+	SourceChain: bp_beefy::ChainWithBeefy,
+	TargetChain: Chain,
+{
+	let pallet_name = SourceChain::WITH_CHAIN_BEEFY_PALLET_NAME;
+	let commitments_info: Option<ImportedCommitmentsInfoData<BlockNumberOf<SourceChain>>> =
+		target_client
+			.storage_value(at, bp_beefy::storage_keys::imported_commitments_info_key(pallet_name))
+			.await?;
+	let Some(commitments_info) = commitments_info else { return Ok(None) };
+
+	Ok(target_client
+		.storage_map_value::<ImportedCommitmentsKeyProvider<SourceChain>>(
+			at,
+			pallet_name,
+			&commitments_info.best_block_number,
+		)
+		.await?
+		.map(|commitment| commitment.parent_number_and_hash.1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bp_beefy::{ImportedCommitment, ImportedCommitmentOf};
+	use bp_runtime::{ChainId, StorageMapKeyProvider};
+	use frame_support::weights::Weight;
+	use relay_substrate_client::{test_chain::TestChain, test_client::TestClient};
+	use sp_core::H256;
+	use sp_runtime::{
+		traits::{BlakeTwo256, Keccak256},
+		StateVersion,
+	};
+
+	struct TestBeefyChain;
+
+	impl bp_runtime::Chain for TestBeefyChain {
+		const ID: ChainId = *b"tbfy";
+
+		type BlockNumber = u32;
+		type Hash = H256;
+		type Hasher = BlakeTwo256;
+		type Header = sp_runtime::generic::Header<u32, BlakeTwo256>;
+
+		type AccountId = u32;
+		type Balance = u32;
+		type Nonce = u32;
+		type Signature = sp_runtime::testing::TestSignature;
+
+		const STATE_VERSION: StateVersion = StateVersion::V1;
+
+		fn max_extrinsic_size() -> u32 {
+			unreachable!()
+		}
+
+		fn max_extrinsic_weight() -> Weight {
+			unreachable!()
+		}
+	}
+
+	impl bp_beefy::ChainWithBeefy for TestBeefyChain {
+		const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str = "BridgeTestBeefy";
+
+		type CommitmentHasher = Keccak256;
+		type MmrHashing = Keccak256;
+		type MmrHash = H256;
+		type BeefyMmrLeafExtra = ();
+		type AuthorityId = bp_beefy::EcdsaValidatorId;
+		type AuthorityIdToMerkleLeaf = bp_beefy::BeefyEcdsaToEthereum;
+	}
+
+	#[async_std::test]
+	async fn best_synced_header_hash_is_read_from_pallet_storage() {
+		// the pallet is not initialized
+		let client = TestClient::<TestChain>::default();
+		assert_eq!(
+			best_synced_header_hash::<TestBeefyChain, TestChain>(&client, Default::default())
+				.await
+				.unwrap(),
+			None,
+		);
+
+		// the best commitment is for block 10, so the best synced header is its parent
+		let commitment: ImportedCommitmentOf<TestBeefyChain> = ImportedCommitment {
+			parent_number_and_hash: (9, H256::repeat_byte(9)),
+			mmr_root: H256::repeat_byte(10),
+		};
+		let client = TestClient::<TestChain>::default()
+			.with_storage_value(
+				bp_beefy::storage_keys::imported_commitments_info_key("BridgeTestBeefy"),
+				ImportedCommitmentsInfoData {
+					best_block_number: 10u32,
+					next_block_number_index: 1,
+				},
+			)
+			.with_storage_value(
+				ImportedCommitmentsKeyProvider::<TestBeefyChain>::final_key("BridgeTestBeefy", &10),
+				commitment,
+			);
+		assert_eq!(
+			best_synced_header_hash::<TestBeefyChain, TestChain>(&client, Default::default())
+				.await
+				.unwrap(),
+			Some(H256::repeat_byte(9)),
+		);
+	}
+}
@@ -17,6 +17,8 @@
 //! Types and functions intended to ease adding of new Substrate -> Substrate
 //! equivocation detection pipelines.
 
+pub mod beefy;
+
 mod source;
 mod target;
 
@@ -33,7 +33,10 @@ use relay_substrate_client::{
 	transaction_stall_timeout, AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain,
 	ChainWithTransactions, Client, HashOf, HeaderOf, SyncHeader,
 };
-use relay_utils::{metrics::MetricsParams, TrackedTransactionStatus, TransactionTracker};
+use relay_utils::{
+	metrics::MetricsParams, state_store::RelayStateStore, TrackedTransactionStatus,
+	TransactionTracker,
+};
 use sp_core::Pair;
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -245,6 +248,7 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 	headers_to_relay: HeadersToRelay,
 	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
 	metrics_params: MetricsParams,
+	state_store: Option<RelayStateStore>,
 ) -> anyhow::Result<()> {
 	log::info!(
 		target: "bridge",
@@ -254,6 +258,11 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 		headers_to_relay,
 	);
 
+	let stall_timeout = transaction_stall_timeout(
+		transaction_params.mortality,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+		relay_utils::STALL_TIMEOUT,
+	);
 	finality_relay::run(
 		SubstrateFinalitySource::<P, _>::new(source_client, None),
 		SubstrateFinalityTarget::<P, _>::new(target_client, transaction_params.clone()),
@@ -263,12 +272,14 @@ pub async fn run<P: SubstrateFinalitySyncPipeline>(
 				P::TargetChain::AVERAGE_BLOCK_INTERVAL,
 			),
 			recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
-			stall_timeout: transaction_stall_timeout(
-				transaction_params.mortality,
-				P::TargetChain::AVERAGE_BLOCK_INTERVAL,
-				relay_utils::STALL_TIMEOUT,
-			),
+			stall_timeout,
 			headers_to_relay,
+			state_store: state_store.map(|state_store| {
+				state_store.pipeline(
+					&finality_relay::metrics_prefix::<FinalitySyncPipelineAdapter<P>>(),
+					stall_timeout,
+				)
+			}),
 		},
 		metrics_params,
 		futures::future::pending(),
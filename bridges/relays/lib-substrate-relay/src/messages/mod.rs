@@ -43,6 +43,7 @@ use relay_substrate_client::{
 };
 use relay_utils::{
 	metrics::{GlobalMetrics, MetricsParams, StandaloneMetric},
+	state_store::RelayStateStore,
 	STALL_TIMEOUT,
 };
 use sp_core::Pair;
@@ -115,6 +116,8 @@ pub struct MessagesRelayParams<P: SubstrateMessageLane, SourceClnt, TargetClnt>
 	pub limits: Option<MessagesRelayLimits>,
 	/// Metrics parameters.
 	pub metrics_params: MetricsParams,
+	/// Persistent relay state store.
+	pub state_store: Option<RelayStateStore>,
 }
 
 /// Delivery transaction limits.
@@ -214,6 +217,18 @@ where
 	let target_client = params.target_client;
 	let relayer_id_at_source: AccountIdOf<P::SourceChain> =
 		params.source_transaction_params.signer.public().into();
+	let source_stall_timeout = transaction_stall_timeout(
+		params.source_transaction_params.mortality,
+		P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+		STALL_TIMEOUT,
+	);
+	let target_stall_timeout = transaction_stall_timeout(
+		params.target_transaction_params.mortality,
+		P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+		STALL_TIMEOUT,
+	);
+	let metrics_prefix =
+		messages_relay::message_lane_loop::metrics_prefix::<MessageLaneAdapter<P>>(&params.lane_id);
 
 	log::info!(
 		target: "bridge",
@@ -231,17 +246,9 @@ where
 		max_messages_size_in_single_batch,
 		max_messages_weight_in_single_batch,
 		params.source_transaction_params.mortality,
-		transaction_stall_timeout(
-			params.source_transaction_params.mortality,
-			P::SourceChain::AVERAGE_BLOCK_INTERVAL,
-			STALL_TIMEOUT,
-		).as_secs_f64() / 60.0f64,
+		source_stall_timeout.as_secs_f64() / 60.0f64,
 		params.target_transaction_params.mortality,
-		transaction_stall_timeout(
-			params.target_transaction_params.mortality,
-			P::TargetChain::AVERAGE_BLOCK_INTERVAL,
-			STALL_TIMEOUT,
-		).as_secs_f64() / 60.0f64,
+		target_stall_timeout.as_secs_f64() / 60.0f64,
 	);
 
 	messages_relay::message_lane_loop::run(
@@ -259,6 +266,12 @@ where
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
 			},
+			delivery_state_store: params.state_store.as_ref().map(|state_store| {
+				state_store.pipeline(&format!("{metrics_prefix}_Delivery"), target_stall_timeout)
+			}),
+			receiving_state_store: params.state_store.as_ref().map(|state_store| {
+				state_store.pipeline(&format!("{metrics_prefix}_Receiving"), source_stall_timeout)
+			}),
 		},
 		SubstrateMessagesSource::<P, _, _>::new(
 			source_client.clone(),
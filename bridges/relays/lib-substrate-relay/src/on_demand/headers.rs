@@ -25,6 +25,7 @@ use bp_runtime::HeaderIdProvider;
 use futures::{select, FutureExt};
 use num_traits::{One, Saturating, Zero};
 use sp_runtime::traits::Header;
+use std::time::Duration;
 
 use finality_relay::{FinalitySyncParams, HeadersToRelay, TargetClient as FinalityTargetClient};
 use relay_substrate_client::{
@@ -32,15 +33,15 @@ use relay_substrate_client::{
 	HeaderIdOf,
 };
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, FailedClient, MaybeConnectionError,
-	STALL_TIMEOUT,
+	metrics::MetricsParams, relay_loop::Client as RelayClient, state_store::RelayStateStore,
+	FailedClient, MaybeConnectionError, STALL_TIMEOUT,
 };
 
 use crate::{
 	finality::{
 		source::{RequiredHeaderNumberRef, SubstrateFinalitySource},
 		target::SubstrateFinalityTarget,
-		SubstrateFinalitySyncPipeline, RECENT_FINALITY_PROOFS_LIMIT,
+		FinalitySyncPipelineAdapter, SubstrateFinalitySyncPipeline, RECENT_FINALITY_PROOFS_LIMIT,
 	},
 	finality_base::engine::Engine,
 	on_demand::OnDemandRelay,
@@ -74,12 +75,16 @@ impl<
 	///
 	/// If `metrics_params` is `Some(_)`, the metrics of the finality relay are registered.
 	/// Otherwise, all required metrics must be exposed outside of this method.
+	///
+	/// If `state_store` is `Some(_)`, submitted transactions are recorded there, so that the
+	/// relay doesn't resubmit the same header after restart.
 	pub fn new(
 		source_client: SourceClnt,
 		target_client: TargetClnt,
 		target_transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
 		headers_to_relay: HeadersToRelay,
 		metrics_params: Option<MetricsParams>,
+		state_store: Option<RelayStateStore>,
 	) -> Self
 	where
 		AccountIdOf<P::TargetChain>:
@@ -100,6 +105,7 @@ impl<
 				headers_to_relay,
 				required_header_number,
 				metrics_params,
+				state_store,
 			)
 			.await;
 		});
@@ -213,6 +219,7 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 	headers_to_relay: HeadersToRelay,
 	required_header_number: RequiredHeaderNumberRef<P::SourceChain>,
 	metrics_params: Option<MetricsParams>,
+	state_store: Option<RelayStateStore>,
 ) where
 	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as sp_core::Pair>::Public>,
 {
@@ -367,15 +374,11 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 				finality_relay::run(
 					finality_source.clone(),
 					finality_target.clone(),
-					FinalitySyncParams {
-						tick: std::cmp::max(
-							P::SourceChain::AVERAGE_BLOCK_INTERVAL,
-							P::TargetChain::AVERAGE_BLOCK_INTERVAL,
-						),
-						recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
-						stall_timeout,
+					finality_sync_params::<P>(
 						headers_to_relay,
-					},
+						stall_timeout,
+						state_store.as_ref(),
+					),
 					metrics_params.clone().unwrap_or_else(MetricsParams::disabled),
 					futures::future::pending(),
 				)
@@ -387,6 +390,32 @@ async fn background_task<P: SubstrateFinalitySyncPipeline>(
 	}
 }
 
+/// Returns parameters of the finality relay, started by the on-demand relay.
+///
+/// The finality relay is using the same state store pipeline as the standalone finality relay of
+/// the same bridge, so switching between them doesn't lead to resubmission of the same header.
+fn finality_sync_params<P: SubstrateFinalitySyncPipeline>(
+	headers_to_relay: HeadersToRelay,
+	stall_timeout: Duration,
+	state_store: Option<&RelayStateStore>,
+) -> FinalitySyncParams {
+	FinalitySyncParams {
+		tick: std::cmp::max(
+			P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+			P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+		),
+		recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
+		stall_timeout,
+		headers_to_relay,
+		state_store: state_store.map(|state_store| {
+			state_store.pipeline(
+				&finality_relay::metrics_prefix::<FinalitySyncPipelineAdapter<P>>(),
+				stall_timeout,
+			)
+		}),
+	}
+}
+
 /// Returns `Some()` with inclusive range of headers which must be scanned for mandatory headers
 /// and the first of such headers must be submitted to the target node.
 async fn mandatory_headers_scan_range<C: Chain>(
@@ -550,11 +579,131 @@ fn on_demand_headers_relay_name<SourceChain: Chain, TargetChain: Chain>() -> Str
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use relay_substrate_client::test_chain::TestChain;
+	use crate::finality_base::{
+		engine::Grandpa, SubstrateFinalityPipeline, SubstrateFinalityProof,
+	};
+	use bp_runtime::ChainId;
+	use frame_support::weights::Weight;
+	use relay_substrate_client::{
+		test_chain::TestChain, ChainWithTransactions, HeaderOf, SignParam, SyncHeader,
+		UnsignedTransaction,
+	};
+	use sp_runtime::StateVersion;
 
 	const AT_SOURCE: Option<BlockNumberOf<TestChain>> = Some(10);
 	const AT_TARGET: Option<BlockNumberOf<TestChain>> = Some(1);
 
+	#[derive(Clone, Debug)]
+	struct TestTargetChain;
+
+	impl bp_runtime::Chain for TestTargetChain {
+		const ID: ChainId = *b"tgtc";
+
+		type BlockNumber = u32;
+		type Hash = sp_core::H256;
+		type Hasher = sp_runtime::traits::BlakeTwo256;
+		type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+
+		type AccountId = sp_runtime::AccountId32;
+		type Balance = u32;
+		type Nonce = u32;
+		type Signature = sp_runtime::MultiSignature;
+
+		const STATE_VERSION: StateVersion = StateVersion::V1;
+
+		fn max_extrinsic_size() -> u32 {
+			unreachable!()
+		}
+
+		fn max_extrinsic_weight() -> Weight {
+			unreachable!()
+		}
+	}
+
+	impl Chain for TestTargetChain {
+		const NAME: &'static str = "TestTarget";
+		const BEST_FINALIZED_HEADER_ID_METHOD: &'static str = "TestTargetMethod";
+		const FREE_HEADERS_INTERVAL_METHOD: &'static str = "TestTargetMethod";
+		const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_millis(0);
+
+		type SignedBlock = sp_runtime::generic::SignedBlock<
+			sp_runtime::generic::Block<Self::Header, sp_runtime::OpaqueExtrinsic>,
+		>;
+		type Call = ();
+	}
+
+	impl ChainWithTransactions for TestTargetChain {
+		type AccountKeyPair = sp_core::sr25519::Pair;
+		type SignedTransaction = ();
+
+		fn sign_transaction(
+			_: SignParam<Self>,
+			_: UnsignedTransaction<Self>,
+		) -> Result<Self::SignedTransaction, SubstrateError> {
+			unreachable!()
+		}
+	}
+
+	#[derive(Clone, Debug)]
+	struct TestPipeline;
+
+	impl SubstrateFinalityPipeline for TestPipeline {
+		type SourceChain = TestChain;
+		type TargetChain = TestTargetChain;
+
+		type FinalityEngine = Grandpa<TestChain>;
+	}
+
+	impl SubstrateFinalitySyncPipeline for TestPipeline {
+		type SubmitFinalityProofCallBuilder = TestPipeline;
+	}
+
+	impl SubmitFinalityProofCallBuilder<TestPipeline> for TestPipeline {
+		fn build_submit_finality_proof_call(
+			_: SyncHeader<HeaderOf<TestChain>>,
+			_: SubstrateFinalityProof<TestPipeline>,
+			_: bool,
+			_: <Grandpa<TestChain> as Engine<TestChain>>::FinalityVerificationContext,
+		) -> CallOf<TestTargetChain> {
+			unreachable!()
+		}
+	}
+
+	#[test]
+	fn finality_relay_uses_state_store_of_standalone_finality_relay() {
+		let state_dir = tempfile::tempdir().unwrap();
+		let state_store = RelayStateStore::open(state_dir.path()).unwrap();
+		let stall_timeout = Duration::from_secs(60);
+
+		// without the store, nothing is recorded
+		let params =
+			finality_sync_params::<TestPipeline>(HeadersToRelay::Mandatory, stall_timeout, None);
+		assert!(params.state_store.is_none());
+
+		// header #5 has been submitted by the standalone finality relay before restart
+		state_store
+			.pipeline(
+				&finality_relay::metrics_prefix::<FinalitySyncPipelineAdapter<TestPipeline>>(),
+				stall_timeout,
+			)
+			.transaction_submitted(5..=5);
+
+		// => on-demand relay sees that it is still pending
+		let params = finality_sync_params::<TestPipeline>(
+			HeadersToRelay::Mandatory,
+			stall_timeout,
+			Some(&state_store),
+		);
+		assert_eq!(
+			params
+				.state_store
+				.unwrap()
+				.pending_transaction()
+				.map(|pending| pending.submitted),
+			Some(5..=5),
+		);
+	}
+
 	#[async_std::test]
 	async fn mandatory_headers_scan_range_selects_range_if_some_headers_are_missing() {
 		assert_eq!(
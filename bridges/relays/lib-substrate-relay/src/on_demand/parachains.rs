@@ -42,8 +42,8 @@ use relay_substrate_client::{
 	Error as SubstrateError, HashOf, HeaderIdOf, ParachainBase,
 };
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, BlockNumberBase, FailedClient,
-	HeaderId, UniqueSaturatedInto,
+	metrics::MetricsParams, relay_loop::Client as RelayClient, state_store::RelayStateStore,
+	BlockNumberBase, FailedClient, HeaderId, UniqueSaturatedInto,
 };
 use std::fmt::Debug;
 
@@ -78,6 +78,9 @@ impl<
 	/// Note that the argument is the source relay chain client, not the parachain client.
 	/// That's because parachain finality is determined by the relay chain and we don't
 	/// need to connect to the parachain itself here.
+	///
+	/// If `state_store` is `Some(_)`, submitted transactions are recorded there, so that the
+	/// relay doesn't resubmit the same parachain heads after restart.
 	pub fn new(
 		source_relay_client: SourceRelayClnt,
 		target_client: TargetClnt,
@@ -85,6 +88,7 @@ impl<
 		on_demand_source_relay_to_target_headers: Arc<
 			dyn OnDemandRelay<P::SourceRelayChain, P::TargetChain>,
 		>,
+		state_store: Option<RelayStateStore>,
 	) -> Self
 	where
 		P::SourceParachain: Chain<Hash = ParaHash>,
@@ -110,6 +114,7 @@ impl<
 				target_transaction_params,
 				on_demand_source_relay_to_target_headers,
 				required_header_number_receiver,
+				state_store,
 			)
 			.await;
 		});
@@ -246,6 +251,7 @@ async fn background_task<P: SubstrateParachainsPipeline>(
 		dyn OnDemandRelay<P::SourceRelayChain, P::TargetChain>,
 	>,
 	required_parachain_header_number_receiver: Receiver<BlockNumberOf<P::SourceParachain>>,
+	state_store: Option<RelayStateStore>,
 ) where
 	P::SourceParachain: Chain<Hash = ParaHash>,
 	P::SourceRelayChain:
@@ -408,6 +414,14 @@ async fn background_task<P: SubstrateParachainsPipeline>(
 					MetricsParams::disabled(),
 					// we do not support free parachain headers relay in on-demand relays
 					false,
+					state_store.as_ref().map(|state_store| {
+						state_store.pipeline(
+							&parachains_relay::parachains_loop::metrics_prefix::<
+								ParachainsPipelineAdapter<P>,
+							>(),
+							stall_timeout,
+						)
+					}),
 					futures::future::pending(),
 				)
 				.fuse(),
@@ -32,7 +32,7 @@ use futures::{channel::mpsc::unbounded, future::FutureExt, stream::StreamExt};
 use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, Weight};
 use relay_utils::{
 	interval, metrics::MetricsParams, process_future_result, relay_loop::Client as RelayClient,
-	retry_backoff, FailedClient, TransactionTracker,
+	retry_backoff, state_store::PipelineStateStore, FailedClient, TransactionTracker,
 };
 
 use crate::{
@@ -55,6 +55,12 @@ pub struct Params {
 	pub reconnect_delay: Duration,
 	/// Message delivery race parameters.
 	pub delivery_params: MessageDeliveryParams,
+	/// Persistent state store of the message delivery race. If `Some(_)`, submitted delivery
+	/// transactions are recorded there, so that the relay doesn't resubmit the same messages
+	/// after restart.
+	pub delivery_state_store: Option<PipelineStateStore>,
+	/// Persistent state store of the receiving (delivery confirmation) race.
+	pub receiving_state_store: Option<PipelineStateStore>,
 }
 
 /// Message delivery race parameters.
@@ -340,6 +346,7 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 		delivery_target_state_receiver,
 		metrics_msg.clone(),
 		params.delivery_params,
+		params.delivery_state_store,
 	)
 	.fuse();
 
@@ -353,6 +360,7 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 		target_client.clone(),
 		receiving_target_state_receiver,
 		metrics_msg.clone(),
+		params.receiving_state_store,
 	)
 	.fuse();
 
@@ -474,7 +482,9 @@ pub(crate) mod tests {
 	use futures::stream::StreamExt;
 	use parking_lot::Mutex;
 
-	use relay_utils::{HeaderId, MaybeConnectionError, TrackedTransactionStatus};
+	use relay_utils::{
+		state_store::RelayStateStore, HeaderId, MaybeConnectionError, TrackedTransactionStatus,
+	};
 
 	use super::*;
 
@@ -943,6 +953,26 @@ pub(crate) mod tests {
 		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		exit_signal: impl Future<Output = ()> + 'static + Send,
+	) -> TestClientData {
+		run_loop_test_with_state_store(
+			data,
+			source_tick,
+			source_post_tick,
+			target_tick,
+			target_post_tick,
+			None,
+			exit_signal,
+		)
+	}
+
+	fn run_loop_test_with_state_store(
+		data: Arc<Mutex<TestClientData>>,
+		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		source_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_post_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		delivery_state_store: Option<PipelineStateStore>,
+		exit_signal: impl Future<Output = ()> + 'static + Send,
 	) -> TestClientData {
 		async_std::task::block_on(async {
 			let source_client = TestSourceClient {
@@ -968,6 +998,8 @@ pub(crate) mod tests {
 						max_messages_weight_in_single_batch: Weight::from_parts(4, 0),
 						max_messages_size_in_single_batch: 4,
 					},
+					delivery_state_store,
+					receiving_state_store: None,
 				},
 				source_client,
 				target_client,
@@ -1204,6 +1236,81 @@ pub(crate) mod tests {
 		assert!(!result.source_to_target_header_requirements.is_empty());
 	}
 
+	#[test]
+	fn message_lane_loop_resumes_delivery_transaction_submitted_before_restart() {
+		// messages 1..=4 have been submitted by the previous relay instance
+		let state_dir = tempfile::tempdir().unwrap();
+		let delivery_state_store = RelayStateStore::open(state_dir.path())
+			.unwrap()
+			.pipeline("TestSource_to_TestTarget_Delivery", Duration::from_secs(3600));
+		delivery_state_store.transaction_submitted(1..=4);
+
+		let (exit_sender, exit_receiver) = unbounded();
+		let target_delivery_state_store = delivery_state_store.clone();
+		let result = run_loop_test_with_state_store(
+			Arc::new(Mutex::new(TestClientData {
+				source_state: ClientState {
+					best_self: HeaderId(10, 10),
+					best_finalized_self: HeaderId(10, 10),
+					best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+					actual_best_finalized_peer_at_best_self: Some(HeaderId(0, 0)),
+				},
+				source_latest_generated_nonce: 4,
+				target_state: ClientState {
+					best_self: HeaderId(0, 0),
+					best_finalized_self: HeaderId(0, 0),
+					best_finalized_peer_at_best_self: Some(HeaderId(10, 10)),
+					actual_best_finalized_peer_at_best_self: Some(HeaderId(10, 10)),
+				},
+				target_latest_received_nonce: 0,
+				..Default::default()
+			})),
+			Arc::new(|data: &mut TestClientData| {
+				// blocks are produced on every tick
+				data.source_state.best_self =
+					HeaderId(data.source_state.best_self.0 + 1, data.source_state.best_self.1 + 1);
+				data.source_state.best_finalized_self = data.source_state.best_self;
+				// syncing target headers -> source chain
+				if let Some(last_requirement) = data.target_to_source_header_requirements.last() {
+					if *last_requirement !=
+						data.source_state.best_finalized_peer_at_best_self.unwrap()
+					{
+						data.source_state.best_finalized_peer_at_best_self =
+							Some(*last_requirement);
+					}
+				}
+			}),
+			Arc::new(|_| {}),
+			Arc::new(move |data: &mut TestClientData| {
+				// blocks are produced on every tick
+				data.target_state.best_self =
+					HeaderId(data.target_state.best_self.0 + 1, data.target_state.best_self.1 + 1);
+				data.target_state.best_finalized_self = data.target_state.best_self;
+				// the transaction, submitted before restart, is mined at the third block
+				if data.target_state.best_self.0 == 3 {
+					data.target_latest_received_nonce = 4;
+				}
+				// if the transaction is no longer tracked and delivery is confirmed => stop. Do not
+				// wait for the stall timeout if it is still tracked
+				let is_tracked = target_delivery_state_store.pending_transaction().is_some();
+				if (!is_tracked && data.source_latest_confirmed_received_nonce == 4) ||
+					data.target_state.best_self.0 > 100
+				{
+					exit_sender.unbounded_send(()).unwrap();
+				}
+			}),
+			Arc::new(|_| {}),
+			Some(delivery_state_store.clone()),
+			exit_receiver.into_future().map(|(_, _)| ()),
+		);
+
+		// messages have not been submitted again and the transaction is not tracked after being
+		// applied
+		assert_eq!(result.submitted_messages_proofs, vec![]);
+		assert_eq!(result.source_latest_confirmed_received_nonce, 4);
+		assert_eq!(delivery_state_store.pending_transaction(), None);
+	}
+
 	#[test]
 	fn message_lane_loop_works_with_batch_transactions() {
 		let (exit_sender, exit_receiver) = unbounded();
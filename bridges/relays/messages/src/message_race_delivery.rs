@@ -19,7 +19,9 @@ use async_trait::async_trait;
 use futures::stream::FusedStream;
 
 use bp_messages::{MessageNonce, UnrewardedRelayersState, Weight};
-use relay_utils::{FailedClient, TrackedTransactionStatus, TransactionTracker};
+use relay_utils::{
+	state_store::PipelineStateStore, FailedClient, TrackedTransactionStatus, TransactionTracker,
+};
 
 use crate::{
 	message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf},
@@ -45,6 +47,7 @@ pub async fn run<P: MessageLane>(
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
 	params: MessageDeliveryParams,
+	state_store: Option<PipelineStateStore>,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		MessageDeliveryRaceSource {
@@ -73,6 +76,7 @@ pub async fn run<P: MessageLane>(
 			strategy: BasicStrategy::new(),
 			metrics_msg,
 		},
+		state_store,
 	)
 	.await
 }
@@ -29,8 +29,8 @@ use futures::{
 	stream::{FusedStream, StreamExt},
 };
 use relay_utils::{
-	process_future_result, retry_backoff, FailedClient, MaybeConnectionError,
-	TrackedTransactionStatus, TransactionTracker,
+	process_future_result, retry_backoff, state_store::PipelineStateStore, FailedClient,
+	MaybeConnectionError, TrackedTransactionStatus, TransactionTracker,
 };
 use std::{
 	fmt::Debug,
@@ -344,6 +344,7 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		ProofParameters = SC::ProofParameters,
 		TargetNoncesData = TC::TargetNoncesData,
 	>,
+	state_store: Option<PipelineStateStore>,
 ) -> Result<(), FailedClient> {
 	let mut progress_context = Instant::now();
 	let mut race_state = RaceStateImpl::default();
@@ -382,6 +383,32 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 		target_go_offline_future,
 	);
 
+	// if we have submitted transaction before restart, let's wait until it is either mined or
+	// stalled, to avoid submitting the same nonces again
+	let mut resumed_nonces_submitted = None;
+	if let Some(pending) =
+		state_store.as_ref().and_then(|state_store| state_store.pending_transaction())
+	{
+		log::info!(
+			target: "bridge",
+			"Waiting for previously submitted {} -> {} race transaction with nonces {:?}",
+			P::source_name(),
+			P::target_name(),
+			pending.submitted,
+		);
+
+		race_state.nonces_submitted = Some(pending.submitted.clone());
+		resumed_nonces_submitted = Some(pending.submitted);
+		target_tx_tracker.set(
+			async move {
+				async_std::task::sleep(pending.remaining).await;
+				TrackedTransactionStatus::Lost
+			}
+			.boxed()
+			.fuse(),
+		);
+	}
+
 	loop {
 		futures::select! {
 			// when headers ids are updated
@@ -464,6 +491,33 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 					async_std::task::sleep,
 					|| format!("Error retrieving best nonces from {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
+
+				// we can't track transaction that has been submitted before restart, so we are
+				// treating it as finalized once its nonces are delivered to the target node
+				let resumed_at_block = match (
+					resumed_nonces_submitted.as_ref(),
+					strategy.best_at_target(),
+					race_state.best_target_header_id.clone(),
+				) {
+					(Some(nonces), Some(best_at_target), Some(at_block))
+						if best_at_target >= *nonces.end() => Some(at_block),
+					_ => None,
+				};
+				if let Some(at_block) = resumed_at_block {
+					log::info!(
+						target: "bridge",
+						"Previously submitted {} -> {} race transaction with nonces {:?} has been applied",
+						P::source_name(),
+						P::target_name(),
+						resumed_nonces_submitted.take(),
+					);
+
+					target_tx_tracker.set(
+						futures::future::ready(TrackedTransactionStatus::Finalized(at_block))
+							.boxed()
+							.fuse(),
+					);
+				}
 			},
 			nonces = target_finalized_nonces => {
 				target_finalized_nonces_required = false;
@@ -545,6 +599,10 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 							P::target_name(),
 						);
 
+						if let Some(ref state_store) = state_store {
+							state_store.transaction_submitted(artifacts.nonces.clone());
+						}
+						resumed_nonces_submitted = None;
 						race_state.nonces_submitted = Some(artifacts.nonces);
 						target_tx_tracker.set(artifacts.tx_tracker.wait().fuse());
 					},
@@ -563,6 +621,10 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 				}
 			},
 			target_transaction_status = target_tx_tracker => {
+				if let Some(ref state_store) = state_store {
+					state_store.transaction_completed();
+				}
+				resumed_nonces_submitted = None;
 				match (target_transaction_status, race_state.nonces_submitted.as_ref()) {
 					(TrackedTransactionStatus::Finalized(at_block), Some(nonces_submitted)) => {
 						// our transaction has been mined, but was it successful or not? let's check the best
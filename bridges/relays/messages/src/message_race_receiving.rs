@@ -30,7 +30,9 @@ use crate::{
 use async_trait::async_trait;
 use bp_messages::MessageNonce;
 use futures::stream::FusedStream;
-use relay_utils::{FailedClient, TrackedTransactionStatus, TransactionTracker};
+use relay_utils::{
+	state_store::PipelineStateStore, FailedClient, TrackedTransactionStatus, TransactionTracker,
+};
 use std::{marker::PhantomData, ops::RangeInclusive};
 
 /// Message receiving confirmations delivery strategy.
@@ -50,6 +52,7 @@ pub async fn run<P: MessageLane>(
 	target_client: impl MessageLaneTargetClient<P>,
 	target_state_updates: impl FusedStream<Item = TargetClientState<P>>,
 	metrics_msg: Option<MessageLaneLoopMetrics>,
+	state_store: Option<PipelineStateStore>,
 ) -> Result<(), FailedClient> {
 	crate::message_race_loop::run(
 		ReceivingConfirmationsRaceSource {
@@ -65,6 +68,7 @@ pub async fn run<P: MessageLane>(
 		},
 		source_state_updates,
 		ReceivingConfirmationsBasicStrategy::<P>::new(),
+		state_store,
 	)
 	.await
 }
@@ -27,8 +27,11 @@ use futures::{
 };
 use relay_substrate_client::{BlockNumberOf, Chain, HeaderIdOf, ParachainBase};
 use relay_utils::{
-	metrics::MetricsParams, relay_loop::Client as RelayClient, FailedClient,
-	TrackedTransactionStatus, TransactionTracker,
+	metrics::MetricsParams,
+	relay_loop::Client as RelayClient,
+	state_store::{PendingTransaction, PipelineStateStore},
+	FailedClient, TrackedTransactionStatus, TransactionTracker, UniqueSaturatedFrom,
+	UniqueSaturatedInto,
 };
 use std::{future::Future, pin::Pin, task::Poll};
 
@@ -172,6 +175,7 @@ pub async fn run<P: ParachainsPipeline>(
 	target_client: impl TargetClient<P>,
 	metrics_params: MetricsParams,
 	only_free_headers: bool,
+	state_store: Option<PipelineStateStore>,
 	exit_signal: impl Future<Output = ()> + 'static + Send,
 ) -> Result<(), relay_utils::Error>
 where
@@ -189,6 +193,7 @@ where
 				target_client,
 				metrics,
 				only_free_headers,
+				state_store.clone(),
 				exit_signal.clone(),
 			)
 		})
@@ -201,6 +206,7 @@ async fn run_until_connection_lost<P: ParachainsPipeline>(
 	target_client: impl TargetClient<P>,
 	metrics: Option<ParachainsLoopMetrics>,
 	only_free_headers: bool,
+	state_store: Option<PipelineStateStore>,
 	exit_signal: impl Future<Output = ()> + Send,
 ) -> Result<(), FailedClient>
 where
@@ -253,7 +259,23 @@ where
 		0
 	};
 
-	let mut submitted_heads_tracker: Option<SubmittedHeadsTracker<P>> = None;
+	// if we have submitted head before restart, let's wait until it is either updated at the
+	// target chain or stalled, to avoid submitting the same head again
+	let mut submitted_heads_tracker: Option<SubmittedHeadsTracker<P>> = state_store
+		.as_ref()
+		.and_then(|state_store| state_store.pending_transaction())
+		.map(|pending| {
+			log::info!(
+				target: "bridge",
+				"Waiting for previously submitted {} parachain ParaId({}) head #{} at {}",
+				P::SourceRelayChain::NAME,
+				P::SourceParachain::PARACHAIN_ID,
+				pending.last_submitted(),
+				P::TargetChain::NAME,
+			);
+
+			SubmittedHeadsTracker::resumed(pending)
+		});
 
 	futures::pin_mut!(exit_signal);
 
@@ -303,7 +325,11 @@ where
 
 		// check if our transaction has been mined
 		if let Some(tracker) = submitted_heads_tracker.take() {
-			match tracker.update(&best_target_block, &head_at_target).await {
+			let status = tracker.update(&best_target_block, &head_at_target).await;
+			if let (SubmittedHeadStatus::Final(_), Some(state_store)) = (&status, &state_store) {
+				state_store.transaction_completed();
+			}
+			match status {
 				SubmittedHeadStatus::Waiting(tracker) => {
 					// no news about our transaction and we shall keep waiting
 					submitted_heads_tracker = Some(tracker);
@@ -395,6 +421,12 @@ where
 				only_free_headers,
 			)
 			.await?;
+			if let (AvailableHeader::Available(head_at_source), Some(state_store)) =
+				(head_at_source, &state_store)
+			{
+				let submitted_number: u64 = head_at_source.number().unique_saturated_into();
+				state_store.transaction_submitted(submitted_number..=submitted_number);
+			}
 			submitted_heads_tracker =
 				Some(SubmittedHeadsTracker::<P>::new(head_at_source, transaction_tracker));
 		}
@@ -612,8 +644,8 @@ type SharedTransactionTracker<P> = Shared<
 
 /// Submitted parachain heads transaction.
 struct SubmittedHeadsTracker<P: ParachainsPipeline> {
-	/// Parachain header id that we have submitted.
-	submitted_head: AvailableHeader<HeaderIdOf<P::SourceParachain>>,
+	/// Number of the parachain header that we have submitted.
+	submitted_head: AvailableHeader<BlockNumberOf<P::SourceParachain>>,
 	/// Future that waits for submitted transaction finality or loss.
 	///
 	/// It needs to be shared because of `poll` macro and our consuming `update` method.
@@ -627,11 +659,37 @@ impl<P: ParachainsPipeline> SubmittedHeadsTracker<P> {
 		transaction_tracker: impl TransactionTracker<HeaderId = HeaderIdOf<P::TargetChain>> + 'static,
 	) -> Self {
 		SubmittedHeadsTracker {
-			submitted_head,
+			submitted_head: match submitted_head {
+				AvailableHeader::Unavailable => AvailableHeader::Unavailable,
+				AvailableHeader::Missing => AvailableHeader::Missing,
+				AvailableHeader::Available(head) => AvailableHeader::Available(head.number()),
+			},
 			transaction_tracker: transaction_tracker.wait().fuse().boxed().shared(),
 		}
 	}
 
+	/// Creates tracker for the parachain head transaction that has been submitted before
+	/// restart.
+	///
+	/// We can't track the transaction itself, so it is considered lost once the remaining stall
+	/// timeout elapses.
+	pub fn resumed(pending: PendingTransaction) -> Self {
+		let remaining = pending.remaining;
+		SubmittedHeadsTracker {
+			submitted_head: AvailableHeader::Available(
+				BlockNumberOf::<P::SourceParachain>::unique_saturated_from(
+					pending.last_submitted(),
+				),
+			),
+			transaction_tracker: async move {
+				async_std::task::sleep(remaining).await;
+				TrackedTransactionStatus::Lost
+			}
+			.boxed()
+			.shared(),
+		}
+	}
+
 	/// Returns `None` if all submitted parachain heads have been updated.
 	pub async fn update(
 		self,
@@ -641,7 +699,7 @@ impl<P: ParachainsPipeline> SubmittedHeadsTracker<P> {
 		// check if our head has been updated
 		let is_head_updated = match (self.submitted_head, head_at_target) {
 			(AvailableHeader::Available(submitted_head), Some(head_at_target))
-				if head_at_target.number() >= submitted_head.number() =>
+				if head_at_target.number() >= submitted_head =>
 				true,
 			(AvailableHeader::Missing, None) => true,
 			_ => false,
@@ -682,9 +740,9 @@ mod tests {
 	use async_std::sync::{Arc, Mutex};
 	use futures::{SinkExt, StreamExt};
 	use relay_substrate_client::test_chain::{TestChain, TestParachain};
-	use relay_utils::{HeaderId, MaybeConnectionError};
+	use relay_utils::{state_store::RelayStateStore, HeaderId, MaybeConnectionError};
 	use sp_core::H256;
-	use std::collections::HashMap;
+	use std::{collections::HashMap, time::Duration};
 
 	const PARA_10_HASH: ParaHash = H256([10u8; 32]);
 	const PARA_20_HASH: ParaHash = H256([20u8; 32]);
@@ -884,6 +942,7 @@ mod tests {
 				TestClient::from(TestClientData::minimal()),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Source),
@@ -901,6 +960,7 @@ mod tests {
 				TestClient::from(test_target_client),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Target),
@@ -918,6 +978,7 @@ mod tests {
 				TestClient::from(test_target_client),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Target),
@@ -935,6 +996,7 @@ mod tests {
 				TestClient::from(test_target_client),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Target),
@@ -952,6 +1014,7 @@ mod tests {
 				TestClient::from(TestClientData::minimal()),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Source),
@@ -969,6 +1032,7 @@ mod tests {
 				TestClient::from(TestClientData::minimal()),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Source),
@@ -986,6 +1050,7 @@ mod tests {
 				TestClient::from(test_target_client),
 				None,
 				false,
+				None,
 				futures::future::pending(),
 			)),
 			Err(FailedClient::Target),
@@ -1001,6 +1066,7 @@ mod tests {
 				TestClient::from(TestClientData::with_exit_signal_sender(exit_signal_sender)),
 				None,
 				false,
+				None,
 				exit_signal.into_future().map(|(_, _)| ()),
 			)),
 			Ok(()),
@@ -1048,6 +1114,7 @@ mod tests {
 				target_client.clone(),
 				None,
 				true,
+				None,
 				exit_signal.into_future().map(|(_, _)| ()),
 			)
 			.await,
@@ -1085,6 +1152,7 @@ mod tests {
 				target_client.clone(),
 				None,
 				true,
+				None,
 				async_std::task::sleep(std::time::Duration::from_millis(100)),
 			)
 			.await,
@@ -1102,6 +1170,106 @@ mod tests {
 		);
 	}
 
+	fn test_state_store_with_pending_head(
+		state_dir: &tempfile::TempDir,
+		pending_head: u64,
+	) -> PipelineStateStore {
+		let state_store = RelayStateStore::open(state_dir.path())
+			.unwrap()
+			.pipeline(&metrics_prefix::<TestParachainsPipeline>(), Duration::from_secs(3600));
+		state_store.transaction_submitted(pending_head..=pending_head);
+		state_store
+	}
+
+	#[async_std::test]
+	async fn head_submitted_before_restart_is_not_submitted_again() {
+		// head #20 has been submitted by the previous relay instance, but it is not yet known
+		// to the target chain
+		let state_dir = tempfile::tempdir().unwrap();
+		let state_store = test_state_store_with_pending_head(&state_dir, 20);
+
+		let target_client = TestClient::from(TestClientData::minimal());
+		assert_eq!(
+			run_until_connection_lost(
+				TestClient::from(TestClientData::minimal()),
+				target_client.clone(),
+				None,
+				false,
+				Some(state_store.clone()),
+				async_std::task::sleep(Duration::from_millis(100)),
+			)
+			.await,
+			Ok(()),
+		);
+
+		// we are still waiting for the head to be updated
+		assert_eq!(target_client.data.lock().await.submitted_proof_at_source_relay_block, None);
+		assert_eq!(
+			state_store.pending_transaction().map(|pending| pending.submitted),
+			Some(20..=20)
+		);
+	}
+
+	#[async_std::test]
+	async fn head_submitted_before_restart_is_completed_when_updated_at_target() {
+		// head #20 has been submitted by the previous relay instance and it is already known
+		// to the target chain
+		let state_dir = tempfile::tempdir().unwrap();
+		let state_store = test_state_store_with_pending_head(&state_dir, 20);
+
+		let mut target_client_data = TestClientData::minimal();
+		target_client_data.target_head =
+			Ok(Some((HeaderId(0, Default::default()), HeaderId(20, PARA_20_HASH))));
+		let target_client = TestClient::from(target_client_data);
+		assert_eq!(
+			run_until_connection_lost(
+				TestClient::from(TestClientData::minimal()),
+				target_client.clone(),
+				None,
+				false,
+				Some(state_store.clone()),
+				async_std::task::sleep(Duration::from_millis(100)),
+			)
+			.await,
+			Ok(()),
+		);
+
+		// the head is not submitted again and we are no longer tracking it
+		assert_eq!(target_client.data.lock().await.submitted_proof_at_source_relay_block, None);
+		assert_eq!(state_store.pending_transaction(), None);
+	}
+
+	#[async_std::test]
+	async fn submitted_head_is_stored_until_updated_at_target() {
+		let state_dir = tempfile::tempdir().unwrap();
+		let state_store = RelayStateStore::open(state_dir.path())
+			.unwrap()
+			.pipeline(&metrics_prefix::<TestParachainsPipeline>(), Duration::from_secs(3600));
+
+		let mut source_client_data = TestClientData::minimal();
+		source_client_data
+			.source_head
+			.insert(0, Ok(AvailableHeader::Available(HeaderId(20, PARA_20_HASH))));
+		let (exit_signal_sender, exit_signal) = futures::channel::mpsc::unbounded();
+		assert_eq!(
+			run_until_connection_lost(
+				TestClient::from(source_client_data),
+				TestClient::from(TestClientData::with_exit_signal_sender(exit_signal_sender)),
+				None,
+				false,
+				Some(state_store.clone()),
+				exit_signal.into_future().map(|(_, _)| ()),
+			)
+			.await,
+			Ok(()),
+		);
+
+		assert_eq!(
+			state_store.pending_transaction().map(|pending| pending.submitted),
+			Some(20..=20)
+		);
+	}
+
 	fn test_tx_tracker() -> SubmittedHeadsTracker<TestParachainsPipeline> {
 		SubmittedHeadsTracker::new(
 			AvailableHeader::Available(HeaderId(20, PARA_20_HASH)),
@@ -11,7 +11,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::net::AddrParseError;
+use std::{net::AddrParseError, path::PathBuf};
 use thiserror::Error;
 
 /// Result type used by relay utilities.
@@ -43,4 +43,10 @@ pub enum Error {
 	/// Prometheus error.
 	#[error("{0}")]
 	Prometheus(#[from] prometheus_endpoint::prometheus::Error),
+	/// Failed to read or write relay state file.
+	#[error("Failed to access relay state at {0:?}: {1}")]
+	StateStoreIo(PathBuf, std::io::Error),
+	/// Failed to encode or decode relay state file contents.
+	#[error("Failed to decode relay state at {0:?}: {1}")]
+	StateStoreFormat(PathBuf, serde_json::Error),
 }
@@ -48,6 +48,7 @@ pub mod error;
 pub mod initialize;
 pub mod metrics;
 pub mod relay_loop;
+pub mod state_store;
 
 /// Block number traits shared by all chains that relay is able to serve.
 pub trait BlockNumberBase:
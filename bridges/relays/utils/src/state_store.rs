@@ -0,0 +1,318 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Optional persistent relay state.
+//!
+//! Relay loops are keeping all their state in memory. When relayer is restarted, it has no
+//! idea that it has already submitted some transaction, which is still in the transaction
+//! pool. So it may submit the same headers or messages again, paying fees for (likely)
+//! failing duplicate transactions. The state store solves that by recording every submitted
+//! transaction in a file, until it is either finalized or treated as lost. On startup, the loop
+//! reads the recorded transaction and waits until the last submitted header or nonce is applied
+//! at the target chain, or the stall timeout (that is started at the moment of original
+//! submission) expires.
+
+use crate::error::Error;
+
+use serde::{Deserialize, Serialize};
+use std::{
+	ops::RangeInclusive,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Extension of the files where pipeline state is stored.
+const STATE_FILE_EXTENSION: &str = "json";
+
+/// Directory-backed store of the relay state.
+///
+/// Every pipeline (e.g. finality relay or message race) is using its own file in the
+/// directory, so multiple relay loops may share the same store.
+#[derive(Debug, Clone)]
+pub struct RelayStateStore {
+	dir: PathBuf,
+}
+
+impl RelayStateStore {
+	/// Open state store at given directory. Directory is created if it doesn't exist.
+	pub fn open(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+		let dir = dir.into();
+		std::fs::create_dir_all(&dir).map_err(|e| Error::StateStoreIo(dir.clone(), e))?;
+		Ok(RelayStateStore { dir })
+	}
+
+	/// Return path to the store directory.
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	/// Return store of the pipeline with given name.
+	///
+	/// The `stall_timeout` is the timeout after which pipeline transactions are treated as lost.
+	pub fn pipeline(&self, name: &str, stall_timeout: Duration) -> PipelineStateStore {
+		let file_name: String = name
+			.chars()
+			.map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+			.collect();
+		PipelineStateStore {
+			name: name.into(),
+			path: self.dir.join(file_name).with_extension(STATE_FILE_EXTENSION),
+			stall_timeout,
+		}
+	}
+}
+
+/// Persistent state of the single relay pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineState {
+	/// Transaction that has been submitted, but not yet finalized or lost.
+	pub in_flight: Option<InFlightTransaction>,
+	/// Last header number or message nonce that has been submitted by the pipeline.
+	///
+	/// If there's an in-flight transaction, it is the last header number or message nonce
+	/// submitted by that transaction.
+	pub last_submitted: Option<u64>,
+}
+
+/// Transaction that has been submitted by the relay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightTransaction {
+	/// First header number or message nonce, submitted by the transaction.
+	pub begin: u64,
+	/// Unix timestamp (in seconds) of the transaction submission.
+	pub submitted_at: u64,
+}
+
+/// Transaction that has been submitted by previous relay instance and is not yet stalled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransaction {
+	/// Range of header numbers or message nonces, submitted by the transaction.
+	pub submitted: RangeInclusive<u64>,
+	/// Time that is left until the transaction is treated as lost.
+	pub remaining: Duration,
+}
+
+impl PendingTransaction {
+	/// Return last header number or message nonce, submitted by the transaction. Once it is
+	/// applied at the target chain, the transaction is no longer pending.
+	pub fn last_submitted(&self) -> u64 {
+		*self.submitted.end()
+	}
+}
+
+/// State store of the single relay pipeline.
+///
+/// Relay loops are not supposed to fail if the state can't be read or written, so methods
+/// that are used by loops are only logging errors.
+#[derive(Debug, Clone)]
+pub struct PipelineStateStore {
+	name: String,
+	path: PathBuf,
+	stall_timeout: Duration,
+}
+
+impl PipelineStateStore {
+	/// Return pipeline name.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Read pipeline state from the file. If file is missing, default state is returned.
+	pub fn load(&self) -> Result<PipelineState, Error> {
+		let data = match std::fs::read(&self.path) {
+			Ok(data) => data,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+			Err(e) => return Err(Error::StateStoreIo(self.path.clone(), e)),
+		};
+		serde_json::from_slice(&data).map_err(|e| Error::StateStoreFormat(self.path.clone(), e))
+	}
+
+	/// Write pipeline state to the file.
+	///
+	/// The state is written to the temporary file first, which is then renamed, so the
+	/// state file is never left in partially written state.
+	pub fn save(&self, state: &PipelineState) -> Result<(), Error> {
+		let data = serde_json::to_vec_pretty(state)
+			.map_err(|e| Error::StateStoreFormat(self.path.clone(), e))?;
+		let tmp_path = self.path.with_extension("tmp");
+		std::fs::write(&tmp_path, data).map_err(|e| Error::StateStoreIo(tmp_path.clone(), e))?;
+		std::fs::rename(&tmp_path, &self.path)
+			.map_err(|e| Error::StateStoreIo(self.path.clone(), e))
+	}
+
+	/// Return transaction that has been submitted (probably by previous relay instance), so
+	/// that the loop may resume tracking it on startup.
+	///
+	/// Returns `None` if there's no such transaction or if it is already stalled.
+	pub fn pending_transaction(&self) -> Option<PendingTransaction> {
+		let state = self.load().map_err(|e| self.log_error(e)).ok()?;
+		let (in_flight, last_submitted) = match (state.in_flight, state.last_submitted) {
+			(Some(in_flight), Some(last_submitted)) => (in_flight, last_submitted),
+			(Some(_), None) => {
+				log::warn!(
+					target: "bridge",
+					"{}: ignoring recorded transaction without last submitted header or nonce",
+					self.name,
+				);
+				return None
+			},
+			(None, _) => return None,
+		};
+		let submitted = in_flight.begin..=last_submitted;
+		let age = now().saturating_sub(in_flight.submitted_at);
+		let remaining = self.stall_timeout.saturating_sub(Duration::from_secs(age));
+		if remaining.is_zero() {
+			log::debug!(
+				target: "bridge",
+				"{}: ignoring recorded transaction with {:?}, submitted {}s ago",
+				self.name,
+				submitted,
+				age,
+			);
+			return None
+		}
+
+		Some(PendingTransaction { submitted, remaining })
+	}
+
+	/// Record submitted transaction.
+	pub fn transaction_submitted(&self, submitted: RangeInclusive<u64>) {
+		self.update(|state| {
+			state.last_submitted = Some(*submitted.end());
+			state.in_flight =
+				Some(InFlightTransaction { begin: *submitted.start(), submitted_at: now() });
+		})
+	}
+
+	/// Forget about submitted transaction, because it is either finalized or lost.
+	pub fn transaction_completed(&self) {
+		self.update(|state| state.in_flight = None)
+	}
+
+	/// Read, update and write pipeline state.
+	fn update(&self, f: impl FnOnce(&mut PipelineState)) {
+		let result = self.load().and_then(|mut state| {
+			f(&mut state);
+			self.save(&state)
+		});
+		if let Err(e) = result {
+			self.log_error(e);
+		}
+	}
+
+	/// Log state store error.
+	fn log_error(&self, error: Error) {
+		log::warn!(target: "bridge", "{}: relay state store error: {}", self.name, error);
+	}
+}
+
+/// Return current Unix timestamp in seconds.
+fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+	fn test_pipeline(dir: &Path) -> PipelineStateStore {
+		RelayStateStore::open(dir)
+			.unwrap()
+			.pipeline("Source_to_Target_Sync", STALL_TIMEOUT)
+	}
+
+	#[test]
+	fn pipeline_state_survives_restart() {
+		let dir = tempfile::tempdir().unwrap();
+
+		test_pipeline(dir.path()).transaction_submitted(5..=10);
+
+		let pending = test_pipeline(dir.path()).pending_transaction().unwrap();
+		assert_eq!(pending.submitted, 5..=10);
+		assert_eq!(pending.last_submitted(), 10);
+		assert!(pending.remaining <= STALL_TIMEOUT);
+	}
+
+	#[test]
+	fn completed_transaction_is_not_pending() {
+		let dir = tempfile::tempdir().unwrap();
+		let pipeline = test_pipeline(dir.path());
+
+		pipeline.transaction_submitted(5..=10);
+		pipeline.transaction_completed();
+
+		assert_eq!(pipeline.pending_transaction(), None);
+		assert_eq!(
+			pipeline.load().unwrap(),
+			PipelineState { in_flight: None, last_submitted: Some(10) },
+		);
+	}
+
+	#[test]
+	fn stalled_transaction_is_not_pending() {
+		let dir = tempfile::tempdir().unwrap();
+		let pipeline = test_pipeline(dir.path());
+
+		pipeline
+			.save(&PipelineState {
+				in_flight: Some(InFlightTransaction {
+					begin: 5,
+					submitted_at: now() - STALL_TIMEOUT.as_secs() - 1,
+				}),
+				last_submitted: Some(10),
+			})
+			.unwrap();
+
+		assert_eq!(pipeline.pending_transaction(), None);
+	}
+
+	#[test]
+	fn pending_transaction_ends_at_last_submitted() {
+		let dir = tempfile::tempdir().unwrap();
+		let pipeline = test_pipeline(dir.path());
+
+		pipeline
+			.save(&PipelineState {
+				in_flight: Some(InFlightTransaction { begin: 5, submitted_at: now() }),
+				last_submitted: Some(7),
+			})
+			.unwrap();
+		assert_eq!(pipeline.pending_transaction().map(|pending| pending.submitted), Some(5..=7));
+
+		pipeline
+			.save(&PipelineState {
+				in_flight: Some(InFlightTransaction { begin: 5, submitted_at: now() }),
+				last_submitted: None,
+			})
+			.unwrap();
+		assert_eq!(pipeline.pending_transaction(), None);
+	}
+
+	#[test]
+	fn pipelines_are_not_sharing_state() {
+		let dir = tempfile::tempdir().unwrap();
+		let store = RelayStateStore::open(dir.path()).unwrap();
+
+		store.pipeline("A_to_B_Sync", STALL_TIMEOUT).transaction_submitted(1..=1);
+
+		assert_eq!(store.pipeline("B_to_A_Sync", STALL_TIMEOUT).pending_transaction(), None);
+	}
+}
@@ -15,7 +15,7 @@ use sp_io::hashing::blake2_256;
 use sp_runtime::{traits::MaybeEquivalence, MultiAddress};
 use sp_std::prelude::*;
 use xcm::prelude::{Junction::AccountKey20, *};
-use xcm_executor::traits::ConvertLocation;
+use xcm_executor::traits::{ConvertLocation, ConvertLocationSteps};
 
 const MINIMUM_DEPOSIT: u128 = 1;
 
@@ -452,6 +452,11 @@ where
 	}
 }
 
+impl<AccountId: From<[u8; 32]> + Clone> ConvertLocationSteps<AccountId>
+	for GlobalConsensusEthereumConvertsFor<AccountId>
+{
+}
+
 impl<AccountId> GlobalConsensusEthereumConvertsFor<AccountId> {
 	pub fn from_chain_id(chain_id: &u64) -> [u8; 32] {
 		(b"ethereum-chain", chain_id).using_encoded(blake2_256)
@@ -0,0 +1,221 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block authoring for in-process parachain nodes.
+
+use crate::node::{BlockAuthor, FullClient, NodeRuntimeApi, RuntimeApiCollection};
+
+use async_std::sync::Arc;
+use codec::Encode;
+use cumulus_client_parachain_inherent::MockXcmConfig;
+use cumulus_primitives_core::{
+	relay_chain, InboundDownwardMessage, InboundHrmpMessage, ParaId, PersistedValidationData,
+};
+use cumulus_primitives_parachain_inherent::{MessageQueueChain, ParachainInherentData};
+use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
+use relay_substrate_client::Error;
+use sc_client_api::HeaderBackend;
+use sc_consensus_manual_seal::{
+	consensus::{aura::AuraConsensusDataProvider, timestamp::SlotTimestampProvider},
+	ConsensusDataProvider,
+};
+use sp_api::ConstructRuntimeApi;
+use sp_consensus_aura::{sr25519::AuthorityId as AuraId, AuraApi};
+use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+use sp_timestamp::TimestampInherentData;
+use std::collections::BTreeMap;
+
+/// Duration of the relay chain slot, used to build relay chain state proofs.
+pub const RELAY_CHAIN_SLOT_DURATION_MILLIS: u64 = 6_000;
+
+/// Maximal PoV size, reported in the persisted validation data.
+const MAX_POV_SIZE: u32 = 5 * 1024 * 1024;
+
+/// Messages that are waiting for inclusion into the next parachain block.
+#[derive(Debug, Default)]
+struct PendingMessages {
+	downward: Vec<Vec<u8>>,
+	horizontal: Vec<(ParaId, Vec<u8>)>,
+}
+
+/// Author of Aura-based parachain blocks.
+///
+/// Every block is authored at the next parachain slot, so the node is able to produce
+/// blocks as fast as the test wants. The relay chain state proof is built by the
+/// [`RelayStateSproofBuilder`], so that the relay chain slot matches the parachain slot and
+/// the parent block is always considered included. Downward and horizontal messages, queued
+/// with [`ParachainBlockAuthor::send_downward_message`] and
+/// [`ParachainBlockAuthor::send_horizontal_message`], are delivered in the next block.
+#[derive(Clone, Debug)]
+pub struct ParachainBlockAuthor {
+	para_id: ParaId,
+	pending_messages: Arc<std::sync::Mutex<PendingMessages>>,
+}
+
+impl ParachainBlockAuthor {
+	/// Create new parachain block author.
+	pub fn new(para_id: ParaId) -> Self {
+		ParachainBlockAuthor { para_id, pending_messages: Default::default() }
+	}
+
+	/// Queue message from the relay chain.
+	pub fn send_downward_message(&self, message: Vec<u8>) {
+		self.pending_messages().downward.push(message);
+	}
+
+	/// Queue message from the sibling parachain.
+	pub fn send_horizontal_message(&self, sender: ParaId, message: Vec<u8>) {
+		self.pending_messages().horizontal.push((sender, message));
+	}
+
+	fn pending_messages(&self) -> std::sync::MutexGuard<PendingMessages> {
+		self.pending_messages
+			.lock()
+			.expect("pending messages mutex is never poisoned; qed")
+	}
+}
+
+impl<Block, RuntimeApi> BlockAuthor<Block, RuntimeApi> for ParachainBlockAuthor
+where
+	Block: BlockT,
+	RuntimeApi: NodeRuntimeApi<Block>,
+	<RuntimeApi as ConstructRuntimeApi<Block, FullClient<Block, RuntimeApi>>>::RuntimeApi:
+		RuntimeApiCollection<Block> + AuraApi<Block, AuraId>,
+{
+	type InherentDataProviders = (SlotTimestampProvider, ParachainInherentDataProvider);
+
+	fn inherent_data_providers(
+		&self,
+		client: &Arc<FullClient<Block, RuntimeApi>>,
+		parent: Block::Hash,
+	) -> Result<Self::InherentDataProviders, Error> {
+		let parent_header = client
+			.header(parent)
+			.map_err(|e| Error::Custom(format!("{e:?}")))?
+			.ok_or_else(|| Error::Custom(format!("Missing parent header {parent}")))?;
+		let timestamp = SlotTimestampProvider::new_aura(client.clone())
+			.map_err(|e| Error::Custom(format!("Failed to create timestamp provider: {e:?}")))?;
+		let xcm_config = MockXcmConfig::new(&**client, parent, Default::default());
+		let PendingMessages { downward, horizontal } =
+			std::mem::take(&mut *self.pending_messages());
+
+		Ok((
+			timestamp,
+			ParachainInherentDataProvider {
+				para_id: self.para_id,
+				parent_head: relay_chain::HeadData(parent_header.encode()),
+				relay_parent_number: UniqueSaturatedInto::<u32>::unique_saturated_into(
+					*parent_header.number(),
+				) + 1,
+				xcm_config,
+				downward_messages: downward,
+				horizontal_messages: horizontal,
+			},
+		))
+	}
+
+	fn consensus_data_provider(
+		&self,
+		client: &Arc<FullClient<Block, RuntimeApi>>,
+	) -> Option<Box<dyn ConsensusDataProvider<Block, Proof = ()>>> {
+		Some(Box::new(AuraConsensusDataProvider::new(client.clone())))
+	}
+}
+
+/// Provider of the parachain inherent data, built on top of the mocked relay chain state.
+///
+/// Must be called after the timestamp inherent data provider, because the relay chain slot
+/// is computed from the block timestamp.
+pub struct ParachainInherentDataProvider {
+	para_id: ParaId,
+	parent_head: relay_chain::HeadData,
+	relay_parent_number: relay_chain::BlockNumber,
+	xcm_config: MockXcmConfig,
+	downward_messages: Vec<Vec<u8>>,
+	horizontal_messages: Vec<(ParaId, Vec<u8>)>,
+}
+
+#[async_trait::async_trait]
+impl InherentDataProvider for ParachainInherentDataProvider {
+	async fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		let timestamp = inherent_data.timestamp_inherent_data()?.ok_or_else(|| {
+			sp_inherents::Error::Application("Timestamp inherent data is missing".into())
+		})?;
+
+		let mut sproof_builder = RelayStateSproofBuilder {
+			para_id: self.para_id,
+			current_slot: (timestamp.as_millis() / RELAY_CHAIN_SLOT_DURATION_MILLIS).into(),
+			included_para_head: Some(self.parent_head.clone()),
+			..Default::default()
+		};
+
+		let mut downward_messages = Vec::new();
+		let mut dmq_mqc = MessageQueueChain::new(self.xcm_config.starting_dmq_mqc_head);
+		for msg in &self.downward_messages {
+			let msg =
+				InboundDownwardMessage { sent_at: self.relay_parent_number, msg: msg.clone() };
+			dmq_mqc.extend_downward(&msg);
+			downward_messages.push(msg);
+		}
+		sproof_builder.dmq_mqc_head = Some(dmq_mqc.head());
+
+		let mut horizontal_messages = BTreeMap::<ParaId, Vec<InboundHrmpMessage>>::new();
+		for (sender, data) in &self.horizontal_messages {
+			horizontal_messages
+				.entry(*sender)
+				.or_default()
+				.push(InboundHrmpMessage { sent_at: self.relay_parent_number, data: data.clone() });
+		}
+		for (sender, messages) in &horizontal_messages {
+			let mut channel_mqc = MessageQueueChain::new(
+				self.xcm_config.starting_hrmp_mqc_heads.get(sender).cloned().unwrap_or_default(),
+			);
+			for message in messages {
+				channel_mqc.extend_hrmp(message);
+			}
+			sproof_builder.upsert_inbound_channel(*sender).mqc_head = Some(channel_mqc.head());
+		}
+
+		let (relay_parent_storage_root, relay_chain_state) =
+			sproof_builder.into_state_root_and_proof();
+		inherent_data.put_data(
+			cumulus_primitives_parachain_inherent::INHERENT_IDENTIFIER,
+			&ParachainInherentData {
+				validation_data: PersistedValidationData {
+					parent_head: self.parent_head.clone(),
+					relay_parent_number: self.relay_parent_number,
+					relay_parent_storage_root,
+					max_pov_size: MAX_POV_SIZE,
+				},
+				relay_chain_state,
+				downward_messages,
+				horizontal_messages,
+			},
+		)
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		None
+	}
+}
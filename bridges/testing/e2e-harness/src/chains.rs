@@ -0,0 +1,661 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chains that are participating in the in-process Rococo <> Westend bridge.
+//!
+//! Both bridge hubs are running the real Rococo and Westend Bridge Hub runtimes. There are
+//! no validators and no backing in the harness, so relay chains are emulated by the
+//! `substrate-test-runtime`: their headers are finalized with GRANDPA justifications, signed
+//! by the test keyring, and finalized heads of bridge hubs are mirrored to the `Paras::Heads`
+//! storage map of the emulated relay chain by the [`follow_parachain_heads`] task. Asset Hubs
+//! are not running either - their messages are injected directly into the inbound XCMP queue
+//! of the bridge hub.
+
+use crate::{
+	authoring::ParachainBlockAuthor,
+	client::{recode, InProcessClient},
+	node::{
+		Finality, FullClient, InProcessNode, NoInherents, NodeRuntimeApi, RuntimeApiCollection,
+		RuntimeGenesis, Sealing,
+	},
+};
+
+use bp_header_chain::InitializationData;
+use bp_messages::LaneId;
+use bp_polkadot_core::parachains::{ParaHead, ParaId};
+use bp_runtime::{BasicOperatingMode, UnderlyingChainProvider};
+use bp_test_utils::{
+	authority_list, make_justification_for_header, JustificationGeneratorParams,
+	TEST_GRANDPA_SET_ID,
+};
+use bp_xcm_bridge_hub::BridgeLocations;
+use codec::Encode;
+use cumulus_primitives_core::XcmpMessageFormat;
+use frame_support::{traits::Get, weights::Weight};
+use futures::StreamExt;
+use relay_substrate_client::{
+	Chain, ChainWithGrandpa, ChainWithMessages, ChainWithTransactions, Error as SubstrateError,
+	RelayChain, SignParam, UnsignedTransaction,
+};
+use sc_client_api::{BlockchainEvents, HeaderBackend};
+use sp_api::ConstructRuntimeApi;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::{sr25519, Pair};
+use sp_keyring::AccountKeyring;
+use sp_runtime::{
+	generic::SignedPayload,
+	traits::{Block as BlockT, Header as HeaderT},
+	MultiAddress, MultiSignature,
+};
+use std::time::Duration;
+use xcm::{latest::prelude::*, VersionedXcm};
+use xcm_executor::traits::ConvertLocation;
+
+/// Average block interval of in-process chains.
+///
+/// Blocks are authored on demand, so this value only affects how often relay loops are
+/// polling nodes.
+pub const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Identifier of the Asset Hub parachain at both Rococo and Westend.
+pub const ASSET_HUB_PARACHAIN_ID: u32 = 1000;
+
+/// Amount of relay chain tokens, given to every endowed account at bridge hubs.
+const ENDOWMENT: u128 = 1 << 60;
+
+/// Amount of relay chain tokens that the Asset Hub pays for the message export.
+const EXPORT_FEE: u128 = 1_000_000_000_000;
+
+/// Maximal weight of the `Transact` instruction in XCM programs, sent by the harness.
+const TRANSACT_WEIGHT: Weight = Weight::from_parts(1_000_000_000, 200_000);
+
+/// Emulated Rococo relay chain.
+#[derive(Debug, Clone, Copy)]
+pub struct Rococo;
+
+impl UnderlyingChainProvider for Rococo {
+	type Chain = bp_rococo::Rococo;
+}
+
+impl Chain for Rococo {
+	const NAME: &'static str = "Rococo";
+	const BEST_FINALIZED_HEADER_ID_METHOD: &'static str =
+		bp_rococo::BEST_FINALIZED_ROCOCO_HEADER_METHOD;
+	const FREE_HEADERS_INTERVAL_METHOD: &'static str =
+		bp_rococo::FREE_HEADERS_INTERVAL_FOR_ROCOCO_METHOD;
+	const AVERAGE_BLOCK_INTERVAL: Duration = AVERAGE_BLOCK_INTERVAL;
+
+	type SignedBlock = bp_rococo::SignedBlock;
+	type Call = ();
+}
+
+impl ChainWithGrandpa for Rococo {
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_rococo::ROCOCO_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
+
+	type KeyOwnerProof = ();
+}
+
+impl RelayChain for Rococo {
+	const PARAS_PALLET_NAME: &'static str = bp_rococo::PARAS_PALLET_NAME;
+	const WITH_CHAIN_BRIDGE_PARACHAINS_PALLET_NAME: &'static str =
+		bp_rococo::WITH_ROCOCO_BRIDGE_PARACHAINS_PALLET_NAME;
+}
+
+/// Emulated Westend relay chain.
+#[derive(Debug, Clone, Copy)]
+pub struct Westend;
+
+impl UnderlyingChainProvider for Westend {
+	type Chain = bp_westend::Westend;
+}
+
+impl Chain for Westend {
+	const NAME: &'static str = "Westend";
+	const BEST_FINALIZED_HEADER_ID_METHOD: &'static str =
+		bp_westend::BEST_FINALIZED_WESTEND_HEADER_METHOD;
+	const FREE_HEADERS_INTERVAL_METHOD: &'static str =
+		bp_westend::FREE_HEADERS_INTERVAL_FOR_WESTEND_METHOD;
+	const AVERAGE_BLOCK_INTERVAL: Duration = AVERAGE_BLOCK_INTERVAL;
+
+	type SignedBlock = bp_westend::SignedBlock;
+	type Call = ();
+}
+
+impl ChainWithGrandpa for Westend {
+	const SYNCED_HEADERS_GRANDPA_INFO_METHOD: &'static str =
+		bp_westend::WESTEND_SYNCED_HEADERS_GRANDPA_INFO_METHOD;
+
+	type KeyOwnerProof = ();
+}
+
+impl RelayChain for Westend {
+	const PARAS_PALLET_NAME: &'static str = bp_westend::PARAS_PALLET_NAME;
+	const WITH_CHAIN_BRIDGE_PARACHAINS_PALLET_NAME: &'static str =
+		bp_westend::WITH_WESTEND_BRIDGE_PARACHAINS_PALLET_NAME;
+}
+
+/// Rococo Bridge Hub, running the real runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeHubRococo;
+
+impl UnderlyingChainProvider for BridgeHubRococo {
+	type Chain = bp_bridge_hub_rococo::BridgeHubRococo;
+}
+
+impl Chain for BridgeHubRococo {
+	const NAME: &'static str = "BridgeHubRococo";
+	const BEST_FINALIZED_HEADER_ID_METHOD: &'static str =
+		bp_bridge_hub_rococo::BEST_FINALIZED_BRIDGE_HUB_ROCOCO_HEADER_METHOD;
+	const FREE_HEADERS_INTERVAL_METHOD: &'static str =
+		bp_bridge_hub_rococo::FREE_HEADERS_INTERVAL_FOR_BRIDGE_HUB_ROCOCO_METHOD;
+	const AVERAGE_BLOCK_INTERVAL: Duration = AVERAGE_BLOCK_INTERVAL;
+
+	type SignedBlock = bp_polkadot_core::SignedBlock;
+	type Call = bridge_hub_rococo_runtime::RuntimeCall;
+}
+
+impl ChainWithMessages for BridgeHubRococo {
+	const WITH_CHAIN_RELAYERS_PALLET_NAME: Option<&'static str> =
+		Some(bp_bridge_hub_rococo::WITH_BRIDGE_HUB_ROCOCO_RELAYERS_PALLET_NAME);
+
+	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
+		bp_bridge_hub_rococo::TO_BRIDGE_HUB_ROCOCO_MESSAGE_DETAILS_METHOD;
+	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
+		bp_bridge_hub_rococo::FROM_BRIDGE_HUB_ROCOCO_MESSAGE_DETAILS_METHOD;
+}
+
+impl ChainWithTransactions for BridgeHubRococo {
+	type AccountKeyPair = sr25519::Pair;
+	type SignedTransaction = bridge_hub_rococo_runtime::UncheckedExtrinsic;
+
+	fn sign_transaction(
+		param: SignParam<Self>,
+		unsigned: UnsignedTransaction<Self>,
+	) -> Result<Self::SignedTransaction, SubstrateError> {
+		use bridge_hub_rococo_runtime::{
+			bridge_to_bulletin_config, bridge_to_westend_config,
+			BridgeRejectObsoleteHeadersAndMessages, SignedExtra,
+		};
+
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::new(),
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::from(unsigned.nonce),
+			frame_system::CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(unsigned.tip),
+			BridgeRejectObsoleteHeadersAndMessages,
+			(
+				bridge_to_westend_config::OnBridgeHubRococoRefundBridgeHubWestendMessages::default(
+				),
+				bridge_to_bulletin_config::OnBridgeHubRococoRefundRococoBulletinMessages::default(),
+			),
+			cumulus_primitives_storage_weight_reclaim::StorageWeightReclaim::new(),
+			frame_metadata_hash_extension::CheckMetadataHash::new(false),
+		);
+		let additional_signed = (
+			(),
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned.era.signed_payload(param.genesis_hash),
+			(),
+			(),
+			(),
+			(),
+			((), ()),
+			(),
+			None,
+		);
+		let raw_payload =
+			SignedPayload::from_raw(unsigned.call.into_decoded()?, extra, additional_signed);
+		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
+		let (call, extra, _) = raw_payload.deconstruct();
+
+		Ok(bridge_hub_rococo_runtime::UncheckedExtrinsic::new_signed(
+			call,
+			MultiAddress::Id(param.signer.public().into()),
+			MultiSignature::Sr25519(signature),
+			extra,
+		))
+	}
+}
+
+/// Westend Bridge Hub, running the real runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeHubWestend;
+
+impl UnderlyingChainProvider for BridgeHubWestend {
+	type Chain = bp_bridge_hub_westend::BridgeHubWestend;
+}
+
+impl Chain for BridgeHubWestend {
+	const NAME: &'static str = "BridgeHubWestend";
+	const BEST_FINALIZED_HEADER_ID_METHOD: &'static str =
+		bp_bridge_hub_westend::BEST_FINALIZED_BRIDGE_HUB_WESTEND_HEADER_METHOD;
+	const FREE_HEADERS_INTERVAL_METHOD: &'static str =
+		bp_bridge_hub_westend::FREE_HEADERS_INTERVAL_FOR_BRIDGE_HUB_WESTEND_METHOD;
+	const AVERAGE_BLOCK_INTERVAL: Duration = AVERAGE_BLOCK_INTERVAL;
+
+	type SignedBlock = bp_polkadot_core::SignedBlock;
+	type Call = bridge_hub_westend_runtime::RuntimeCall;
+}
+
+impl ChainWithMessages for BridgeHubWestend {
+	const WITH_CHAIN_RELAYERS_PALLET_NAME: Option<&'static str> =
+		Some(bp_bridge_hub_westend::WITH_BRIDGE_HUB_WESTEND_RELAYERS_PALLET_NAME);
+
+	const TO_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
+		bp_bridge_hub_westend::TO_BRIDGE_HUB_WESTEND_MESSAGE_DETAILS_METHOD;
+	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str =
+		bp_bridge_hub_westend::FROM_BRIDGE_HUB_WESTEND_MESSAGE_DETAILS_METHOD;
+}
+
+impl ChainWithTransactions for BridgeHubWestend {
+	type AccountKeyPair = sr25519::Pair;
+	type SignedTransaction = bridge_hub_westend_runtime::UncheckedExtrinsic;
+
+	fn sign_transaction(
+		param: SignParam<Self>,
+		unsigned: UnsignedTransaction<Self>,
+	) -> Result<Self::SignedTransaction, SubstrateError> {
+		use bridge_hub_westend_runtime::{
+			bridge_to_rococo_config, BridgeRejectObsoleteHeadersAndMessages, SignedExtra,
+		};
+
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::new(),
+			frame_system::CheckSpecVersion::new(),
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(unsigned.era.frame_era()),
+			frame_system::CheckNonce::from(unsigned.nonce),
+			frame_system::CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(unsigned.tip),
+			BridgeRejectObsoleteHeadersAndMessages,
+			(bridge_to_rococo_config::OnBridgeHubWestendRefundBridgeHubRococoMessages::default(),),
+			cumulus_primitives_storage_weight_reclaim::StorageWeightReclaim::new(),
+			frame_metadata_hash_extension::CheckMetadataHash::new(false),
+		);
+		let additional_signed = (
+			(),
+			param.spec_version,
+			param.transaction_version,
+			param.genesis_hash,
+			unsigned.era.signed_payload(param.genesis_hash),
+			(),
+			(),
+			(),
+			(),
+			((),),
+			(),
+			None,
+		);
+		let raw_payload =
+			SignedPayload::from_raw(unsigned.call.into_decoded()?, extra, additional_signed);
+		let signature = raw_payload.using_encoded(|payload| param.signer.sign(payload));
+		let (call, extra, _) = raw_payload.deconstruct();
+
+		Ok(bridge_hub_westend_runtime::UncheckedExtrinsic::new_signed(
+			call,
+			MultiAddress::Id(param.signer.public().into()),
+			MultiSignature::Sr25519(signature),
+			extra,
+		))
+	}
+}
+
+/// Runtime API of emulated relay chain nodes.
+pub type RelayChainRuntimeApi = substrate_test_runtime::RuntimeApi;
+/// In-process node of the emulated relay chain.
+pub type RelayChainNode = InProcessNode<substrate_test_runtime::Block, RelayChainRuntimeApi>;
+/// Relay client of the emulated Rococo relay chain.
+pub type RococoClient =
+	InProcessClient<Rococo, substrate_test_runtime::Block, RelayChainRuntimeApi>;
+/// Relay client of the emulated Westend relay chain.
+pub type WestendClient =
+	InProcessClient<Westend, substrate_test_runtime::Block, RelayChainRuntimeApi>;
+
+/// Runtime API of the Rococo Bridge Hub node.
+pub type BridgeHubRococoRuntimeApi = bridge_hub_rococo_runtime::RuntimeApi;
+/// In-process node of the Rococo Bridge Hub.
+pub type BridgeHubRococoNode =
+	InProcessNode<bridge_hub_rococo_runtime::Block, BridgeHubRococoRuntimeApi>;
+/// Relay client of the Rococo Bridge Hub.
+pub type BridgeHubRococoClient =
+	InProcessClient<BridgeHubRococo, bridge_hub_rococo_runtime::Block, BridgeHubRococoRuntimeApi>;
+
+/// Runtime API of the Westend Bridge Hub node.
+pub type BridgeHubWestendRuntimeApi = bridge_hub_westend_runtime::RuntimeApi;
+/// In-process node of the Westend Bridge Hub.
+pub type BridgeHubWestendNode =
+	InProcessNode<bridge_hub_westend_runtime::Block, BridgeHubWestendRuntimeApi>;
+/// Relay client of the Westend Bridge Hub.
+pub type BridgeHubWestendClient = InProcessClient<
+	BridgeHubWestend,
+	bridge_hub_westend_runtime::Block,
+	BridgeHubWestendRuntimeApi,
+>;
+
+/// Start the emulated relay chain node.
+///
+/// Every block is finalized with the GRANDPA justification, signed by the
+/// `bp_test_utils::test_keyring()` authorities. Blocks are only authored when the test asks
+/// for that.
+pub fn start_relay_chain_node(name: &'static str) -> RelayChainNode {
+	InProcessNode::new(
+		name,
+		substrate_test_runtime_client::GenesisParameters::default(),
+		NoInherents,
+		Finality::Justified(Box::new(|header| {
+			let header: bp_polkadot_core::Header = recode(header)
+				.expect("test runtime header has the same encoding as the relay chain header; qed");
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header,
+				ancestors: 0,
+				..Default::default()
+			});
+			(sp_consensus_grandpa::GRANDPA_ENGINE_ID, justification.encode())
+		})),
+		Sealing::Manual,
+	)
+}
+
+/// Start the Rococo Bridge Hub node, with the bridge GRANDPA pallet initialized by the best
+/// finalized header of the given emulated Westend node.
+///
+/// Returns the node and the handle that may be used to send XCM messages to the bridge hub.
+pub fn start_bridge_hub_rococo_node(
+	westend: &RelayChainNode,
+) -> Result<(BridgeHubRococoNode, ParachainBlockAuthor), SubstrateError> {
+	use bridge_hub_rococo_runtime::{xcm_config::LocationToAccountId, SessionKeys};
+
+	let alice_aura: AuraId = AccountKeyring::Alice.public().into();
+	let asset_hub_sovereign_account = LocationToAccountId::convert_location(&Location::new(
+		1,
+		[Parachain(ASSET_HUB_PARACHAIN_ID)],
+	))
+	.expect("sibling parachain location is always converted to account; qed");
+	let patch = bridge_hub_genesis_patch(
+		bp_bridge_hub_rococo::BRIDGE_HUB_ROCOCO_PARACHAIN_ID,
+		serde_json::json!(SessionKeys { aura: alice_aura }),
+		asset_hub_sovereign_account,
+		relay_chain_initialization_data(westend)?,
+		NetworkId::Westend,
+		("bridgeWestendGrandpa", "bridgeWestendMessages", "xcmOverBridgeHubWestend"),
+	);
+	let author =
+		ParachainBlockAuthor::new(bp_bridge_hub_rococo::BRIDGE_HUB_ROCOCO_PARACHAIN_ID.into());
+
+	Ok((
+		InProcessNode::new(
+			BridgeHubRococo::NAME,
+			RuntimeGenesis::new(
+				bridge_hub_rococo_runtime::WASM_BINARY
+					.expect("Rococo Bridge Hub runtime is built with the `std` feature; qed"),
+				patch,
+			),
+			author.clone(),
+			Finality::Instant,
+			Sealing::Instant,
+		),
+		author,
+	))
+}
+
+/// Start the Westend Bridge Hub node, with the bridge GRANDPA pallet initialized by the best
+/// finalized header of the given emulated Rococo node.
+///
+/// Returns the node and the handle that may be used to send XCM messages to the bridge hub.
+pub fn start_bridge_hub_westend_node(
+	rococo: &RelayChainNode,
+) -> Result<(BridgeHubWestendNode, ParachainBlockAuthor), SubstrateError> {
+	use bridge_hub_westend_runtime::{xcm_config::LocationToAccountId, SessionKeys};
+
+	let alice_aura: AuraId = AccountKeyring::Alice.public().into();
+	let asset_hub_sovereign_account = LocationToAccountId::convert_location(&Location::new(
+		1,
+		[Parachain(ASSET_HUB_PARACHAIN_ID)],
+	))
+	.expect("sibling parachain location is always converted to account; qed");
+	let patch = bridge_hub_genesis_patch(
+		bp_bridge_hub_westend::BRIDGE_HUB_WESTEND_PARACHAIN_ID,
+		serde_json::json!(SessionKeys { aura: alice_aura }),
+		asset_hub_sovereign_account,
+		relay_chain_initialization_data(rococo)?,
+		NetworkId::Rococo,
+		("bridgeRococoGrandpa", "bridgeRococoMessages", "xcmOverBridgeHubRococo"),
+	);
+	let author =
+		ParachainBlockAuthor::new(bp_bridge_hub_westend::BRIDGE_HUB_WESTEND_PARACHAIN_ID.into());
+
+	Ok((
+		InProcessNode::new(
+			BridgeHubWestend::NAME,
+			RuntimeGenesis::new(
+				bridge_hub_westend_runtime::WASM_BINARY
+					.expect("Westend Bridge Hub runtime is built with the `std` feature; qed"),
+				patch,
+			),
+			author.clone(),
+			Finality::Instant,
+			Sealing::Instant,
+		),
+		author,
+	))
+}
+
+/// Return GRANDPA pallet initialization data, built from the best header of the emulated relay
+/// chain.
+fn relay_chain_initialization_data(
+	relay_chain: &RelayChainNode,
+) -> Result<InitializationData<bp_polkadot_core::Header>, SubstrateError> {
+	Ok(InitializationData {
+		header: Box::new(recode(&relay_chain.best_header()?)?),
+		authority_list: authority_list(),
+		set_id: TEST_GRANDPA_SET_ID,
+		operating_mode: BasicOperatingMode::Normal,
+	})
+}
+
+/// Build the `RuntimeGenesisConfig` patch of the bridge hub.
+///
+/// `Alice` is the only collator and the owner of bridge pallets. `Alice`, `Bob`, `Charlie`,
+/// `Dave` and the sovereign account of the sibling Asset Hub are endowed. The bridge between
+/// Asset Hubs is opened at genesis.
+fn bridge_hub_genesis_patch(
+	para_id: u32,
+	session_keys: serde_json::Value,
+	asset_hub_sovereign_account: bp_polkadot_core::AccountId,
+	init_data: InitializationData<bp_polkadot_core::Header>,
+	bridged_network: NetworkId,
+	(grandpa_pallet, messages_pallet, xcm_bridge_hub_pallet): (&str, &str, &str),
+) -> serde_json::Value {
+	let alice = AccountKeyring::Alice.to_account_id();
+	let endowed_accounts = [
+		alice.clone(),
+		AccountKeyring::Bob.to_account_id(),
+		AccountKeyring::Charlie.to_account_id(),
+		AccountKeyring::Dave.to_account_id(),
+		asset_hub_sovereign_account,
+	];
+	let opened_bridges = vec![(
+		Location::new(1, [Parachain(ASSET_HUB_PARACHAIN_ID)]),
+		InteriorLocation::from([
+			GlobalConsensus(bridged_network),
+			Parachain(ASSET_HUB_PARACHAIN_ID),
+		]),
+	)];
+
+	let mut patch = serde_json::json!({
+		"balances": {
+			"balances": endowed_accounts
+				.into_iter()
+				.map(|account| (account, ENDOWMENT))
+				.collect::<Vec<_>>(),
+		},
+		"parachainInfo": {
+			"parachainId": para_id,
+		},
+		"collatorSelection": {
+			"invulnerables": [alice.clone()],
+		},
+		"session": {
+			"keys": [(alice.clone(), alice.clone(), session_keys)],
+		},
+		"polkadotXcm": {
+			"safeXcmVersion": Some(xcm::latest::VERSION),
+		},
+	});
+	patch[grandpa_pallet] = serde_json::json!({
+		"owner": Some(alice.clone()),
+		"initData": Some(init_data),
+	});
+	patch[messages_pallet] = serde_json::json!({
+		"owner": Some(alice),
+	});
+	patch[xcm_bridge_hub_pallet] = serde_json::json!({
+		"openedBridges": opened_bridges,
+	});
+	patch
+}
+
+/// Return identifier of the lane that is used by the bridge between Rococo and Westend Asset
+/// Hubs.
+pub fn asset_hubs_lane_id() -> LaneId {
+	BridgeLocations::bridge_locations(
+		[
+			GlobalConsensus(NetworkId::Rococo),
+			Parachain(bp_bridge_hub_rococo::BRIDGE_HUB_ROCOCO_PARACHAIN_ID),
+		]
+		.into(),
+		Location::new(1, [Parachain(ASSET_HUB_PARACHAIN_ID)]),
+		[GlobalConsensus(NetworkId::Westend), Parachain(ASSET_HUB_PARACHAIN_ID)].into(),
+		NetworkId::Westend,
+	)
+	.and_then(|locations| locations.calculate_lane_id(xcm::latest::VERSION))
+	.expect("bridge locations are valid; qed")
+}
+
+/// Queue the downward message that tells the Rococo Bridge Hub that the Westend Bridge Hub
+/// supports the latest XCM version.
+///
+/// The Rococo Bridge Hub refuses to export messages to bridge hubs with unknown XCM version.
+pub fn force_bridge_hub_westend_xcm_version(bridge_hub_rococo: &ParachainBlockAuthor) {
+	let call =
+		bridge_hub_rococo_runtime::RuntimeCall::PolkadotXcm(pallet_xcm::Call::force_xcm_version {
+			location: Box::new(
+				bridge_hub_rococo_runtime::bridge_to_westend_config::BridgeHubWestendLocation::get(
+				),
+			),
+			version: xcm::latest::VERSION,
+		});
+	let message = Xcm::<()>(vec![
+		UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+		Transact {
+			origin_kind: OriginKind::Superuser,
+			require_weight_at_most: TRANSACT_WEIGHT,
+			call: call.encode().into(),
+		},
+	]);
+	bridge_hub_rococo.send_downward_message(VersionedXcm::from(message).encode());
+}
+
+/// Queue the message from the Rococo Asset Hub that asks the Rococo Bridge Hub to export
+/// `xcm` to the Westend Asset Hub.
+///
+/// Execution and export are paid from the Asset Hub sovereign account at the bridge hub.
+pub fn export_message_from_rococo_asset_hub(
+	bridge_hub_rococo: &ParachainBlockAuthor,
+	xcm: Xcm<()>,
+) {
+	let message = Xcm::<()>(vec![
+		WithdrawAsset((Parent, EXPORT_FEE).into()),
+		BuyExecution { fees: (Parent, EXPORT_FEE).into(), weight_limit: Unlimited },
+		ExportMessage {
+			network: NetworkId::Westend,
+			destination: [Parachain(ASSET_HUB_PARACHAIN_ID)].into(),
+			xcm,
+		},
+	]);
+	let mut data = XcmpMessageFormat::ConcatenatedVersionedXcm.encode();
+	data.extend(VersionedXcm::from(message).encode());
+	bridge_hub_rococo.send_horizontal_message(ASSET_HUB_PARACHAIN_ID.into(), data);
+}
+
+/// Write head of the parachain to the `Paras::Heads` map of the emulated relay chain and
+/// author a block with this change.
+///
+/// Returns hash of the authored relay chain block.
+pub async fn set_parachain_head(
+	relay_chain: &RelayChainNode,
+	para_id: u32,
+	head: &bp_polkadot_core::Header,
+) -> Result<bp_polkadot_core::Hash, SubstrateError> {
+	// both emulated relay chains are using the same name for the `Paras` pallet
+	let storage_key = bp_parachains::parachain_head_storage_key_at_source(
+		Westend::PARAS_PALLET_NAME,
+		ParaId(para_id),
+	);
+	let storage_value = ParaHead(head.encode()).encode();
+	relay_chain
+		.submit_extrinsic(
+			substrate_test_runtime::ExtrinsicBuilder::new_storage_change(
+				storage_key.0,
+				Some(storage_value),
+			)
+			.build(),
+		)
+		.await?;
+	relay_chain.produce_block().await
+}
+
+/// Mirror finalized heads of the parachain to the emulated relay chain.
+///
+/// In production, heads are included into the relay chain by validators. There are no
+/// validators in the harness, so the task writes every finalized parachain head directly to
+/// the relay chain storage (see [`set_parachain_head`]). The future only resolves if the
+/// parachain node has stopped or the relay chain block can't be authored.
+pub async fn follow_parachain_heads<Block, RuntimeApi>(
+	relay_chain: RelayChainNode,
+	para_id: u32,
+	parachain: InProcessNode<Block, RuntimeApi>,
+) -> anyhow::Result<()>
+where
+	Block: BlockT,
+	RuntimeApi: NodeRuntimeApi<Block>,
+	<RuntimeApi as ConstructRuntimeApi<Block, FullClient<Block, RuntimeApi>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+{
+	let mut finalized_headers = parachain.client().finality_notification_stream();
+	let best_finalized_hash = parachain.client().info().finalized_hash;
+	let best_finalized_header = parachain
+		.client()
+		.header(best_finalized_hash)?
+		.ok_or_else(|| anyhow::format_err!("Missing finalized {} header", parachain.name()))?;
+	set_parachain_head(&relay_chain, para_id, &recode(&best_finalized_header)?).await?;
+
+	while let Some(notification) = finalized_headers.next().await {
+		if *notification.header.number() > *best_finalized_header.number() {
+			set_parachain_head(&relay_chain, para_id, &recode(&notification.header)?).await?;
+		}
+	}
+
+	Err(anyhow::format_err!("{} has stopped finalizing blocks", parachain.name()))
+}
@@ -0,0 +1,472 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Relay client, connected to the in-process node.
+//!
+//! The client implements the same [`Client`] trait as the RPC client, used by relays in
+//! production. So all relay loops may be started on top of in-process nodes without any
+//! changes.
+
+use crate::node::{FullClient, InProcessNode, NodeRuntimeApi, RuntimeApiCollection};
+
+use async_std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use bp_runtime::HeaderIdProvider;
+use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use futures::StreamExt;
+use pallet_transaction_payment::RuntimeDispatchInfo;
+use relay_substrate_client::{
+	transaction_stall_timeout, AccountIdOf, AccountKeyPairOf, BalanceOf, BlockNumberOf, Chain,
+	ChainWithGrandpa, ChainWithTransactions, Client, Error, HashOf, HeaderIdOf, HeaderOf, NonceOf,
+	Result, SignParam, SignedBlockOf, SimpleRuntimeVersion, StreamDescription, Subscription,
+	TransactionTracker, UnsignedTransaction,
+};
+use relay_utils::STALL_TIMEOUT;
+use sc_client_api::{
+	BlockBackend, BlockchainEvents, CallExecutor, ExecutorProvider, HeaderBackend, ProofProvider,
+	StorageProvider,
+};
+use sp_api::ConstructRuntimeApi;
+use sp_core::{
+	storage::{StorageData, StorageKey},
+	traits::CallContext,
+	Bytes, Pair,
+};
+use sp_runtime::{
+	generic::SignedBlock,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, UniqueSaturatedInto},
+	transaction_validity::{TransactionSource, TransactionValidity},
+};
+use sp_trie::StorageProof;
+use sp_version::RuntimeVersion;
+use std::marker::PhantomData;
+
+const SUB_API_ACCOUNT_NONCE: &str = "AccountNonceApi_account_nonce";
+const SUB_API_TXPOOL_VALIDATE_TRANSACTION: &str = "TaggedTransactionQueue_validate_transaction";
+const SUB_API_TX_PAYMENT_QUERY_INFO: &str = "TransactionPaymentApi_query_info";
+
+/// Relay client that is connected to the in-process node.
+///
+/// The chain `C` and the node `Block` types must share the same hashing. All other types
+/// (headers, block numbers and blocks) are converted using SCALE encoding, so e.g. chain
+/// with `u32` block numbers may be backed by the node with `u64` block numbers.
+pub struct InProcessClient<C, Block: BlockT, RuntimeApi> {
+	node: InProcessNode<Block, RuntimeApi>,
+	/// Prevents nonce collisions when several tasks are submitting transactions concurrently.
+	submit_signed_extrinsic_lock: Arc<Mutex<()>>,
+	_phantom: PhantomData<fn() -> C>,
+}
+
+impl<C, Block: BlockT, RuntimeApi> InProcessClient<C, Block, RuntimeApi> {
+	/// Create new client, connected to given node.
+	pub fn new(node: InProcessNode<Block, RuntimeApi>) -> Self {
+		InProcessClient {
+			node,
+			submit_signed_extrinsic_lock: Arc::new(Mutex::new(())),
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Return reference to the node this client is connected to.
+	pub fn node(&self) -> &InProcessNode<Block, RuntimeApi> {
+		&self.node
+	}
+}
+
+impl<C, Block: BlockT, RuntimeApi> Clone for InProcessClient<C, Block, RuntimeApi> {
+	fn clone(&self) -> Self {
+		InProcessClient {
+			node: self.node.clone(),
+			submit_signed_extrinsic_lock: self.submit_signed_extrinsic_lock.clone(),
+			_phantom: Default::default(),
+		}
+	}
+}
+
+impl<C: Chain, Block: BlockT, RuntimeApi> std::fmt::Debug
+	for InProcessClient<C, Block, RuntimeApi>
+{
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.write_fmt(format_args!("InProcessClient<{}>", C::NAME))
+	}
+}
+
+impl<C, Block, RuntimeApi> InProcessClient<C, Block, RuntimeApi>
+where
+	C: Chain,
+	Block: BlockT<Hash = HashOf<C>>,
+	RuntimeApi: NodeRuntimeApi<Block>,
+	<RuntimeApi as ConstructRuntimeApi<Block, FullClient<Block, RuntimeApi>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+	SignedBlockOf<C>: Decode,
+{
+	fn client(&self) -> &FullClient<Block, RuntimeApi> {
+		self.node.client()
+	}
+
+	fn header_at(&self, hash: HashOf<C>) -> Result<HeaderOf<C>> {
+		let header = self
+			.client()
+			.header(hash)
+			.map_err(|e| Error::Custom(format!("{e:?}")))?
+			.ok_or_else(|| Error::Custom(format!("Header {hash} is missing")))?;
+		recode(&header)
+	}
+
+	async fn next_account_index(&self, account: AccountIdOf<C>) -> Result<NonceOf<C>> {
+		let best_hash = self.client().info().best_hash;
+		self.state_call(best_hash, SUB_API_ACCOUNT_NONCE.into(), account).await
+	}
+
+	async fn build_sign_params(&self, signer: AccountKeyPairOf<C>) -> Result<SignParam<C>>
+	where
+		C: ChainWithTransactions,
+	{
+		let runtime_version = self.simple_runtime_version().await?;
+		Ok(SignParam::<C> {
+			spec_version: runtime_version.spec_version,
+			transaction_version: runtime_version.transaction_version,
+			genesis_hash: self.genesis_hash(),
+			signer,
+		})
+	}
+
+	async fn sign_and_validate(
+		&self,
+		signer: &AccountKeyPairOf<C>,
+		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>,
+	) -> Result<(Block::Extrinsic, std::time::Duration)>
+	where
+		C: ChainWithTransactions,
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		let signing_data = self.build_sign_params(signer.clone()).await?;
+		let transaction_nonce = self.next_account_index(signer.public().into()).await?;
+		let best_header_id = self.best_header().await?.id();
+
+		let extrinsic = prepare_extrinsic(best_header_id, transaction_nonce)?;
+		let stall_timeout = transaction_stall_timeout(
+			extrinsic.era.mortality_period(),
+			C::AVERAGE_BLOCK_INTERVAL,
+			STALL_TIMEOUT,
+		);
+		let signed_extrinsic = C::sign_transaction(signing_data, extrinsic)?.encode();
+
+		self.validate_transaction(best_header_id.hash(), PreEncoded(signed_extrinsic.clone()))
+			.await?
+			.map_err(Error::TransactionInvalid)?;
+
+		Ok((Decode::decode(&mut &signed_extrinsic[..])?, stall_timeout))
+	}
+}
+
+#[async_trait]
+impl<C, Block, RuntimeApi> Client<C> for InProcessClient<C, Block, RuntimeApi>
+where
+	C: Chain,
+	Block: BlockT<Hash = HashOf<C>>,
+	RuntimeApi: NodeRuntimeApi<Block>,
+	<RuntimeApi as ConstructRuntimeApi<Block, FullClient<Block, RuntimeApi>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+	SignedBlockOf<C>: Decode,
+{
+	async fn ensure_synced(&self) -> Result<()> {
+		// the node is always synced - it is the only node of the chain
+		Ok(())
+	}
+
+	async fn reconnect(&self) -> Result<()> {
+		Ok(())
+	}
+
+	fn genesis_hash(&self) -> HashOf<C> {
+		self.client().info().genesis_hash
+	}
+
+	async fn header_hash_by_number(&self, number: BlockNumberOf<C>) -> Result<HashOf<C>> {
+		let node_number: u64 = number.unique_saturated_into();
+		let node_number: NumberFor<Block> = node_number.unique_saturated_into();
+		self.client()
+			.hash(node_number)
+			.map_err(|e| Error::Custom(format!("{e:?}")))
+			.and_then(|hash| hash.ok_or_else(|| Error::Custom("Unknown block".into())))
+			.map_err(|e| Error::failed_to_read_header_hash_by_number::<C>(number, e))
+	}
+
+	async fn header_by_hash(&self, hash: HashOf<C>) -> Result<HeaderOf<C>> {
+		self.header_at(hash)
+			.map_err(|e| Error::failed_to_read_header_by_hash::<C>(hash, e))
+	}
+
+	async fn block_by_hash(&self, hash: HashOf<C>) -> Result<SignedBlockOf<C>> {
+		self.client()
+			.block(hash)
+			.map_err(|e| Error::Custom(format!("{e:?}")))
+			.and_then(|block| block.ok_or_else(|| Error::Custom("Unknown block".into())))
+			.and_then(|block: SignedBlock<Block>| recode(&block))
+			.map_err(|e| Error::failed_to_read_block_by_hash::<C>(hash, e))
+	}
+
+	async fn best_finalized_header_hash(&self) -> Result<HashOf<C>> {
+		Ok(self.client().info().finalized_hash)
+	}
+
+	async fn best_header(&self) -> Result<HeaderOf<C>> {
+		self.header_at(self.client().info().best_hash)
+			.map_err(|e| Error::failed_to_read_best_header::<C>(e))
+	}
+
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		Ok(Subscription::new_broadcasted(
+			StreamDescription::new("best headers".into(), C::NAME.into()),
+			self.client().import_notification_stream().filter_map(|notification| {
+				futures::future::ready(recode(&notification.header).ok())
+			}),
+		))
+	}
+
+	async fn subscribe_finalized_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		Ok(Subscription::new_broadcasted(
+			StreamDescription::new("finalized headers".into(), C::NAME.into()),
+			self.client().finality_notification_stream().filter_map(|notification| {
+				futures::future::ready(recode(&notification.header).ok())
+			}),
+		))
+	}
+
+	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
+	where
+		C: ChainWithGrandpa,
+	{
+		Ok(Subscription::new_broadcasted(
+			StreamDescription::new("GRANDPA justifications".into(), C::NAME.into()),
+			self.node
+				.subscribe_justifications(sp_consensus_grandpa::GRANDPA_ENGINE_ID)
+				.map(Bytes),
+		))
+	}
+
+	async fn generate_grandpa_key_ownership_proof(
+		&self,
+		_at: HashOf<C>,
+		_set_id: sp_consensus_grandpa::SetId,
+		_authority_id: sp_consensus_grandpa::AuthorityId,
+	) -> Result<Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof>> {
+		// there are no sessions at in-process nodes
+		Ok(None)
+	}
+
+	async fn subscribe_beefy_finality_justifications(&self) -> Result<Subscription<Bytes>> {
+		Err(Error::Custom(format!("BEEFY is not supported by in-process {} node", C::NAME)))
+	}
+
+	async fn generate_beefy_key_ownership_proof<AuthorityId: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_set_id: sp_consensus_beefy::ValidatorSetId,
+		_authority_id: AuthorityId,
+	) -> Result<Option<sp_consensus_beefy::OpaqueKeyOwnershipProof>> {
+		Ok(None)
+	}
+
+	async fn token_decimals(&self) -> Result<Option<u64>> {
+		Ok(None)
+	}
+
+	async fn runtime_version(&self) -> Result<RuntimeVersion> {
+		let best_hash = self.client().info().best_hash;
+		self.client().runtime_version_at(best_hash).map_err(|e| {
+			Error::failed_to_read_runtime_version::<C>(Error::Custom(format!("{e:?}")))
+		})
+	}
+
+	async fn simple_runtime_version(&self) -> Result<SimpleRuntimeVersion> {
+		Ok(SimpleRuntimeVersion::from_runtime_version(&self.runtime_version().await?))
+	}
+
+	fn can_start_version_guard(&self) -> bool {
+		false
+	}
+
+	async fn raw_storage_value(
+		&self,
+		at: HashOf<C>,
+		storage_key: StorageKey,
+	) -> Result<Option<StorageData>> {
+		self.client().storage(at, &storage_key).map_err(|e| {
+			Error::failed_to_read_storage_value::<C>(
+				at,
+				storage_key.clone(),
+				Error::Custom(format!("{e:?}")),
+			)
+		})
+	}
+
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
+		Ok(self
+			.node
+			.pending_extrinsics()
+			.await
+			.into_iter()
+			.map(|xt| Bytes(xt.encode()))
+			.collect())
+	}
+
+	async fn submit_unsigned_extrinsic(&self, transaction: Bytes) -> Result<HashOf<C>> {
+		let transaction = Decode::decode(&mut &transaction.0[..])
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(Error::ResponseParseFailed(e)))?;
+		self.node
+			.submit_extrinsic(transaction)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))
+	}
+
+	async fn submit_signed_extrinsic(
+		&self,
+		signer: &AccountKeyPairOf<C>,
+		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<HashOf<C>>
+	where
+		C: ChainWithTransactions,
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		let _guard = self.submit_signed_extrinsic_lock.lock().await;
+		let (transaction, _) = self
+			.sign_and_validate(signer, prepare_extrinsic)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?;
+		self.node
+			.submit_extrinsic(transaction)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))
+	}
+
+	async fn submit_and_watch_signed_extrinsic(
+		&self,
+		signer: &AccountKeyPairOf<C>,
+		prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<TransactionTracker<C, Self>>
+	where
+		C: ChainWithTransactions,
+		AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		let _guard = self.submit_signed_extrinsic_lock.lock().await;
+		let (transaction, stall_timeout) = self
+			.sign_and_validate(signer, prepare_extrinsic)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?;
+		let (tx_hash, statuses) = self
+			.node
+			.submit_and_watch_extrinsic(transaction)
+			.await
+			.map_err(|e| Error::failed_to_submit_transaction::<C>(e))?;
+		Ok(TransactionTracker::new(
+			self.clone(),
+			stall_timeout,
+			tx_hash,
+			Subscription::new_broadcasted(
+				StreamDescription::new("transaction events".into(), C::NAME.into()),
+				statuses,
+			),
+		))
+	}
+
+	async fn validate_transaction<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		transaction: SignedTransaction,
+	) -> Result<TransactionValidity> {
+		self.state_call(
+			at,
+			SUB_API_TXPOOL_VALIDATE_TRANSACTION.into(),
+			(TransactionSource::External, transaction, at),
+		)
+		.await
+	}
+
+	async fn estimate_extrinsic_weight<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		at: HashOf<C>,
+		transaction: SignedTransaction,
+	) -> Result<Weight> {
+		let transaction_len = transaction.encoded_size() as u32;
+		let dispatch_info: RuntimeDispatchInfo<BalanceOf<C>> = self
+			.state_call(at, SUB_API_TX_PAYMENT_QUERY_INFO.into(), (transaction, transaction_len))
+			.await?;
+
+		Ok(dispatch_info.weight)
+	}
+
+	async fn raw_state_call<Args: Encode + Send>(
+		&self,
+		at: HashOf<C>,
+		method: String,
+		arguments: Args,
+	) -> Result<Bytes> {
+		let arguments = arguments.encode();
+		self.client()
+			.executor()
+			.call(at, &method, &arguments, CallContext::Offchain)
+			.map(Bytes)
+			.map_err(|e| {
+				Error::failed_state_call::<C>(
+					at,
+					method.clone(),
+					Bytes(arguments.clone()),
+					Error::Custom(format!("{e:?}")),
+				)
+			})
+	}
+
+	async fn prove_storage(
+		&self,
+		at: HashOf<C>,
+		keys: Vec<StorageKey>,
+	) -> Result<(StorageProof, HashOf<C>)> {
+		let state_root = *self.header_by_hash(at).await?.state_root();
+		let read_proof = self
+			.client()
+			.read_proof(at, &mut keys.iter().map(|key| key.0.as_slice()))
+			.map_err(|e| {
+				Error::failed_to_prove_storage::<C>(
+					at,
+					keys.clone(),
+					Error::Custom(format!("{e:?}")),
+				)
+			})?;
+
+		Ok((read_proof, state_root))
+	}
+}
+
+/// Convert value of one type into the value of other type with the same SCALE encoding.
+pub fn recode<T: Encode, R: Decode>(value: &T) -> Result<R> {
+	R::decode(&mut &value.encode()[..]).map_err(Into::into)
+}
+
+/// Already encoded value.
+struct PreEncoded(Vec<u8>);
+
+impl Encode for PreEncoded {
+	fn encode(&self) -> Vec<u8> {
+		self.0.clone()
+	}
+}
@@ -0,0 +1,78 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-process end-to-end test harness for the bridge relays.
+//!
+//! Unlike zombienet-based tests, the harness doesn't spawn any external processes and
+//! doesn't need any binaries. Bridged chains are running as in-process nodes (see
+//! [`node::InProcessNode`]), which are authoring blocks on demand. Relays are connected to
+//! these nodes using the [`client::InProcessClient`], which implements the same
+//! `relay_substrate_client::Client` trait as the RPC client. So the relay code that is
+//! tested by the harness is the same code that is running in production.
+
+#![warn(missing_docs)]
+
+pub mod authoring;
+pub mod chains;
+pub mod client;
+pub mod node;
+pub mod pipelines;
+
+use futures::{future::Either, Future, FutureExt};
+use std::time::Duration;
+
+/// Interval between two checks of the `run_until` condition.
+const CONDITION_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run relays future until the `condition` is satisfied.
+///
+/// Returns error if relays have stopped or the condition has not been satisfied within the
+/// `timeout`.
+pub async fn run_until<Condition, ConditionFuture>(
+	relays: impl Future<Output = anyhow::Result<()>>,
+	timeout: Duration,
+	mut condition: Condition,
+) -> anyhow::Result<()>
+where
+	Condition: FnMut() -> ConditionFuture,
+	ConditionFuture: Future<Output = anyhow::Result<bool>>,
+{
+	let wait_for_condition = async move {
+		loop {
+			if condition().await? {
+				return Ok::<_, anyhow::Error>(())
+			}
+			async_std::task::sleep(CONDITION_CHECK_INTERVAL).await;
+		}
+	};
+
+	let relays = relays.fuse();
+	let wait_for_condition = wait_for_condition.fuse();
+	futures::pin_mut!(relays, wait_for_condition);
+	let result =
+		async_std::future::timeout(timeout, futures::future::select(relays, wait_for_condition))
+			.await
+			.map_err(|_| {
+				anyhow::format_err!("Condition has not been satisfied within {timeout:?}")
+			})?;
+
+	match result {
+		Either::Left((relays_result, _)) => Err(anyhow::format_err!(
+			"Relays have stopped before the condition has been satisfied: {relays_result:?}"
+		)),
+		Either::Right((condition_result, _)) => condition_result,
+	}
+}
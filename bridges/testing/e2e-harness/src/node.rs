@@ -0,0 +1,485 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-process chain node, built from the regular `sc-service` components.
+//!
+//! The node is using the `sc-service` test client, the full transaction pool, the basic block
+//! proposer and the manual seal consensus engine. It has no networking and no RPC server.
+//! Blocks are authored on demand (or right after every transaction submission), using the
+//! [`BlockAuthor`] to provide inherents and pre-runtime digests. Finality is decided by the
+//! harness: every authored block is finalized immediately, optionally with a justification
+//! that is produced by the [`Finality`] callback.
+
+use async_std::sync::{Arc, Mutex};
+use futures::{
+	channel::{
+		mpsc::{channel, unbounded, Sender, UnboundedReceiver, UnboundedSender},
+		oneshot,
+	},
+	FutureExt, SinkExt, Stream,
+};
+use relay_substrate_client::Error;
+use sc_basic_authorship::ProposerFactory;
+use sc_chain_spec::GenesisConfigBuilderRuntimeCaller;
+use sc_client_api::{Finalizer, HeaderBackend};
+use sc_consensus_manual_seal::{
+	ConsensusDataProvider, CreatedBlock, EngineCommand, ManualSealParams,
+};
+use sc_executor::WasmExecutor;
+use sc_transaction_pool::FullPool;
+use sc_transaction_pool_api::{
+	InPoolTransaction, TransactionPool, TransactionSource, TransactionStatus,
+};
+use sp_api::ConstructRuntimeApi;
+use sp_core::{testing::TaskExecutor, traits::SpawnNamed};
+use sp_inherents::InherentDataProvider;
+use sp_runtime::{traits::Block as BlockT, ConsensusEngineId, Justification, Storage};
+use std::pin::Pin;
+use substrate_test_client::{
+	client::{Client, LocalCallExecutor},
+	GenesisInit, TestClientBuilder,
+};
+
+/// Host functions, available to runtimes of in-process nodes.
+pub type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+);
+
+/// Database backend of in-process nodes.
+pub type Backend<Block> = substrate_test_client::Backend<Block>;
+
+/// Call executor of in-process nodes.
+pub type Executor<Block> = LocalCallExecutor<Block, Backend<Block>, WasmExecutor<HostFunctions>>;
+
+/// Full client of in-process nodes.
+pub type FullClient<Block, RuntimeApi> = Client<Backend<Block>, Executor<Block>, Block, RuntimeApi>;
+
+/// Transaction pool of in-process nodes.
+pub type NodeTransactionPool<Block, RuntimeApi> = FullPool<Block, FullClient<Block, RuntimeApi>>;
+
+/// Status of transaction, submitted to the in-process node.
+pub type NodeTransactionStatus<Block> =
+	TransactionStatus<<Block as BlockT>::Hash, <Block as BlockT>::Hash>;
+
+/// Stream of statuses of transaction, submitted to the in-process node.
+pub type NodeTransactionStatusStream<Block> =
+	Pin<Box<dyn Stream<Item = NodeTransactionStatus<Block>> + Send>>;
+
+/// Set of runtime APIs that is required to author blocks at the in-process node.
+pub trait RuntimeApiCollection<Block: BlockT>:
+	sp_api::ApiExt<Block>
+	+ sp_api::Core<Block>
+	+ sp_block_builder::BlockBuilder<Block>
+	+ sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>
+{
+}
+
+impl<Block, T> RuntimeApiCollection<Block> for T
+where
+	Block: BlockT,
+	T: sp_api::ApiExt<Block>
+		+ sp_api::Core<Block>
+		+ sp_block_builder::BlockBuilder<Block>
+		+ sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+}
+
+/// Runtime API that may be used by in-process nodes.
+pub trait NodeRuntimeApi<Block: BlockT>:
+	ConstructRuntimeApi<Block, FullClient<Block, Self>> + Send + Sync + Sized + 'static
+where
+	<Self as ConstructRuntimeApi<Block, FullClient<Block, Self>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+{
+}
+
+impl<Block, T> NodeRuntimeApi<Block> for T
+where
+	Block: BlockT,
+	T: ConstructRuntimeApi<Block, FullClient<Block, T>> + Send + Sync + 'static,
+	<T as ConstructRuntimeApi<Block, FullClient<Block, T>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+{
+}
+
+/// Genesis storage, built from the runtime code and the JSON patch for its default
+/// `RuntimeGenesisConfig`.
+#[derive(Default)]
+pub struct RuntimeGenesis {
+	code: Vec<u8>,
+	patch: serde_json::Value,
+}
+
+impl RuntimeGenesis {
+	/// Create genesis for given runtime code and `RuntimeGenesisConfig` patch.
+	pub fn new(code: &[u8], patch: serde_json::Value) -> Self {
+		RuntimeGenesis { code: code.to_vec(), patch }
+	}
+}
+
+impl GenesisInit for RuntimeGenesis {
+	fn genesis_storage(&self) -> Storage {
+		let mut storage = GenesisConfigBuilderRuntimeCaller::<
+			cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+		>::new(&self.code)
+		.get_storage_for_patch(self.patch.clone())
+		.expect("genesis config patch is provided by the harness and is valid; qed");
+		storage
+			.top
+			.insert(sp_core::storage::well_known_keys::CODE.to_vec(), self.code.clone());
+		storage
+	}
+}
+
+/// Something that provides inherents and pre-runtime digests of blocks, authored by the
+/// in-process node.
+pub trait BlockAuthor<Block: BlockT, RuntimeApi>: Send + Sync + 'static {
+	/// Inherent data providers of authored blocks.
+	type InherentDataProviders: InherentDataProvider + 'static;
+
+	/// Return inherent data providers for the block, built on top of the `parent` block.
+	fn inherent_data_providers(
+		&self,
+		client: &Arc<FullClient<Block, RuntimeApi>>,
+		parent: Block::Hash,
+	) -> Result<Self::InherentDataProviders, Error>;
+
+	/// Return provider of pre-runtime digests of authored blocks.
+	fn consensus_data_provider(
+		&self,
+		client: &Arc<FullClient<Block, RuntimeApi>>,
+	) -> Option<Box<dyn ConsensusDataProvider<Block, Proof = ()>>>;
+}
+
+/// Block author for runtimes that do not require any inherents and digests.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoInherents;
+
+impl<Block: BlockT, RuntimeApi: Send + Sync + 'static> BlockAuthor<Block, RuntimeApi>
+	for NoInherents
+{
+	type InherentDataProviders = ();
+
+	fn inherent_data_providers(
+		&self,
+		_client: &Arc<FullClient<Block, RuntimeApi>>,
+		_parent: Block::Hash,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn consensus_data_provider(
+		&self,
+		_client: &Arc<FullClient<Block, RuntimeApi>>,
+	) -> Option<Box<dyn ConsensusDataProvider<Block, Proof = ()>>> {
+		None
+	}
+}
+
+/// Callback that produces justification for the given block header.
+pub type JustificationBuilder<Block> =
+	Box<dyn Fn(&<Block as BlockT>::Header) -> Justification + Send + Sync>;
+
+/// How the in-process node finalizes authored blocks.
+pub enum Finality<Block: BlockT> {
+	/// Every block is finalized right after it is authored, without any justifications.
+	Instant,
+	/// Every block is finalized right after it is authored, with justification produced by
+	/// the callback. Justification is stored in the database and broadcasted to subscribers.
+	Justified(JustificationBuilder<Block>),
+}
+
+/// When the in-process node authors blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sealing {
+	/// New block is authored right after every transaction submission.
+	Instant,
+	/// New block is authored only when [`InProcessNode::produce_block`] is called.
+	Manual,
+}
+
+/// In-process chain node.
+pub struct InProcessNode<Block: BlockT, RuntimeApi> {
+	name: &'static str,
+	client: Arc<FullClient<Block, RuntimeApi>>,
+	pool: Arc<NodeTransactionPool<Block, RuntimeApi>>,
+	commands: Sender<EngineCommand<Block::Hash>>,
+	finality: Arc<Finality<Block>>,
+	sealing: Sealing,
+	/// Guards block authoring, so that blocks are finalized in the order they're authored.
+	authoring_lock: Arc<Mutex<()>>,
+	justification_subscribers: Arc<std::sync::Mutex<Vec<JustificationSubscriber>>>,
+}
+
+/// Subscriber of justifications of given consensus engine.
+struct JustificationSubscriber {
+	engine_id: ConsensusEngineId,
+	sender: UnboundedSender<Vec<u8>>,
+}
+
+impl<Block: BlockT, RuntimeApi> Clone for InProcessNode<Block, RuntimeApi> {
+	fn clone(&self) -> Self {
+		InProcessNode {
+			name: self.name,
+			client: self.client.clone(),
+			pool: self.pool.clone(),
+			commands: self.commands.clone(),
+			finality: self.finality.clone(),
+			sealing: self.sealing,
+			authoring_lock: self.authoring_lock.clone(),
+			justification_subscribers: self.justification_subscribers.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT, RuntimeApi> std::fmt::Debug for InProcessNode<Block, RuntimeApi> {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.write_fmt(format_args!("InProcessNode<{}>", self.name))
+	}
+}
+
+impl<Block, RuntimeApi> InProcessNode<Block, RuntimeApi>
+where
+	Block: BlockT,
+	RuntimeApi: NodeRuntimeApi<Block>,
+	<RuntimeApi as ConstructRuntimeApi<Block, FullClient<Block, RuntimeApi>>>::RuntimeApi:
+		RuntimeApiCollection<Block>,
+{
+	/// Start new in-process node with given genesis.
+	pub fn new<G: GenesisInit>(
+		name: &'static str,
+		genesis: G,
+		author: impl BlockAuthor<Block, RuntimeApi>,
+		finality: Finality<Block>,
+		sealing: Sealing,
+	) -> Self {
+		let mut builder =
+			TestClientBuilder::<Block, Executor<Block>, Backend<Block>, G>::with_default_backend();
+		*builder.genesis_init_mut() = genesis;
+		let (client, select_chain) = builder.build_with_native_executor::<RuntimeApi, _>(None);
+		let client = Arc::new(client);
+
+		let spawner = TaskExecutor::new();
+		let pool = NodeTransactionPool::<Block, RuntimeApi>::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		);
+		spawner.spawn(
+			"txpool-notifications",
+			None,
+			sc_transaction_pool::notification_future(client.clone(), pool.clone()).boxed(),
+		);
+
+		let (commands, commands_stream) = channel(1024);
+		let consensus_data_provider = author.consensus_data_provider(&client);
+		let create_inherent_data_providers = {
+			let client = client.clone();
+			move |parent: Block::Hash, ()| {
+				let providers = author.inherent_data_providers(&client, parent).map_err(
+					|e| -> Box<dyn std::error::Error + Send + Sync> { format!("{e:?}").into() },
+				);
+				async move { providers }
+			}
+		};
+		spawner.spawn(
+			"manual-seal",
+			None,
+			sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+				block_import: client.clone(),
+				env: ProposerFactory::new(
+					spawner.clone(),
+					client.clone(),
+					pool.clone(),
+					None,
+					None,
+				),
+				client: client.clone(),
+				pool: pool.clone(),
+				commands_stream,
+				select_chain,
+				consensus_data_provider,
+				create_inherent_data_providers,
+				backend: None::<std::sync::Arc<Backend<Block>>>,
+				aux_revert: None,
+			})
+			.boxed(),
+		);
+
+		InProcessNode {
+			name,
+			client,
+			pool,
+			commands,
+			finality: Arc::new(finality),
+			sealing,
+			authoring_lock: Arc::new(Mutex::new(())),
+			justification_subscribers: Arc::new(std::sync::Mutex::new(Vec::new())),
+		}
+	}
+
+	/// Return node name.
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Return reference to the underlying client.
+	pub fn client(&self) -> &Arc<FullClient<Block, RuntimeApi>> {
+		&self.client
+	}
+
+	/// Return header of the best block.
+	pub fn best_header(&self) -> Result<Block::Header, Error> {
+		let best_hash = self.client.info().best_hash;
+		self.client
+			.header(best_hash)
+			.map_err(|e| Error::Custom(format!("{e:?}")))?
+			.ok_or_else(|| Error::Custom(format!("Missing best {} header {best_hash}", self.name)))
+	}
+
+	/// Return extrinsics that are waiting for inclusion into the next block.
+	pub async fn pending_extrinsics(&self) -> Vec<Block::Extrinsic> {
+		self.pool.ready().map(|tx| tx.data().clone()).collect()
+	}
+
+	/// Submit extrinsic to the transaction pool.
+	pub async fn submit_extrinsic(&self, xt: Block::Extrinsic) -> Result<Block::Hash, Error> {
+		let tx_hash = self
+			.pool
+			.submit_one(self.client.info().best_hash, TransactionSource::External, xt)
+			.await
+			.map_err(|e| self.pool_error(e))?;
+		self.seal_if_instant().await?;
+		Ok(tx_hash)
+	}
+
+	/// Submit extrinsic to the transaction pool and watch its status.
+	pub async fn submit_and_watch_extrinsic(
+		&self,
+		xt: Block::Extrinsic,
+	) -> Result<(Block::Hash, NodeTransactionStatusStream<Block>), Error> {
+		let tx_hash = self.pool.hash_of(&xt);
+		let statuses = self
+			.pool
+			.submit_and_watch(self.client.info().best_hash, TransactionSource::External, xt)
+			.await
+			.map_err(|e| self.pool_error(e))?;
+		self.seal_if_instant().await?;
+		Ok((tx_hash, statuses))
+	}
+
+	/// Subscribe to justifications of given consensus engine.
+	pub fn subscribe_justifications(
+		&self,
+		engine_id: ConsensusEngineId,
+	) -> UnboundedReceiver<Vec<u8>> {
+		let (sender, receiver) = unbounded();
+		self.justification_subscribers
+			.lock()
+			.expect("justification subscribers mutex is never poisoned; qed")
+			.push(JustificationSubscriber { engine_id, sender });
+		receiver
+	}
+
+	/// Author new block on top of the current best block, import and finalize it.
+	///
+	/// All ready transactions are included into the block.
+	pub async fn produce_block(&self) -> Result<Block::Hash, Error> {
+		let _guard = self.authoring_lock.lock().await;
+		let instant_finality = matches!(*self.finality, Finality::Instant);
+		let (sender, receiver) = oneshot::channel();
+		self.commands
+			.clone()
+			.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: instant_finality,
+				parent_hash: None,
+				timestamp: None,
+				slot: None,
+				set_best: false,
+				sender: Some(sender),
+			})
+			.await
+			.map_err(|e| {
+				Error::Custom(format!("{} authoring task has stopped: {e:?}", self.name))
+			})?;
+		let CreatedBlock { hash: block_hash, .. } = receiver
+			.await
+			.map_err(|e| Error::Custom(format!("{} authoring task has stopped: {e:?}", self.name)))?
+			.map_err(|e| Error::Custom(format!("Failed to author {} block: {e:?}", self.name)))?;
+
+		if let Finality::Justified(ref build_justification) = *self.finality {
+			let header = self
+				.client
+				.header(block_hash)
+				.map_err(|e| Error::Custom(format!("{e:?}")))?
+				.ok_or_else(|| {
+					Error::Custom(format!("Missing authored {} header {block_hash}", self.name))
+				})?;
+			let justification = build_justification(&header);
+			self.client
+				.finalize_block(block_hash, Some(justification.clone()), true)
+				.map_err(|e| {
+					Error::Custom(format!(
+						"Failed to finalize {} block {block_hash}: {e:?}",
+						self.name
+					))
+				})?;
+			self.broadcast_justification(justification);
+		}
+
+		log::trace!(target: "bridge", "Authored {} block {block_hash}", self.name);
+
+		Ok(block_hash)
+	}
+
+	/// Author and import `count` blocks.
+	pub async fn produce_blocks(&self, count: usize) -> Result<Block::Hash, Error> {
+		let mut best_hash = self.client.info().best_hash;
+		for _ in 0..count {
+			best_hash = self.produce_block().await?;
+		}
+		Ok(best_hash)
+	}
+
+	async fn seal_if_instant(&self) -> Result<(), Error> {
+		if self.sealing == Sealing::Instant {
+			self.produce_block().await?;
+		}
+		Ok(())
+	}
+
+	fn pool_error(&self, e: impl std::fmt::Debug) -> Error {
+		Error::Custom(format!("{} transaction has been rejected by the pool: {e:?}", self.name))
+	}
+
+	fn broadcast_justification(&self, (engine_id, justification): Justification) {
+		let mut subscribers = self
+			.justification_subscribers
+			.lock()
+			.expect("justification subscribers mutex is never poisoned; qed");
+		subscribers.retain(|subscriber| {
+			if subscriber.engine_id == engine_id {
+				subscriber.sender.unbounded_send(justification.clone()).is_ok()
+			} else {
+				!subscriber.sender.is_closed()
+			}
+		});
+	}
+}
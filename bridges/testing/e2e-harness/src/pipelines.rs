@@ -0,0 +1,281 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Relay pipelines of the in-process Rococo <> Westend bridge.
+//!
+//! Every relay is signing transactions with its own key, so that relays that are running
+//! concurrently never compete for the same account nonce:
+//!
+//! - finality relays are using [`finality_relayer_key`];
+//! - parachains relays are using [`parachains_relayer_key`];
+//! - messages relay is using [`messages_relayer_key`] at both bridge hubs.
+
+use crate::chains::{
+	asset_hubs_lane_id, BridgeHubRococo, BridgeHubRococoClient, BridgeHubWestend,
+	BridgeHubWestendClient, Rococo, RococoClient, Westend, WestendClient,
+};
+
+use async_std::sync::{Arc, Mutex};
+use parachains_relay::parachains_loop::AvailableHeader;
+use relay_utils::metrics::MetricsParams;
+use sp_core::sr25519;
+use substrate_relay_helper::{
+	finality::SubstrateFinalitySyncPipeline,
+	finality_base::{engine::Grandpa, SubstrateFinalityPipeline},
+	messages::{
+		DirectReceiveMessagesDeliveryProofCallBuilder, DirectReceiveMessagesProofCallBuilder,
+		MessagesRelayParams, SubstrateMessageLane,
+	},
+	parachains::{
+		source::ParachainsSource, target::ParachainsTarget, DirectSubmitParachainHeadsCallBuilder,
+		SubstrateParachainsPipeline,
+	},
+	HeadersToRelay, TransactionParams,
+};
+
+/// Westend-to-BridgeHubRococo finality sync pipeline.
+#[derive(Clone, Debug)]
+pub struct WestendFinalityToBridgeHubRococo;
+
+substrate_relay_helper::generate_submit_finality_proof_ex_call_builder!(
+	WestendFinalityToBridgeHubRococo,
+	SubmitWestendFinalityProofCallBuilder,
+	bridge_hub_rococo_runtime::RuntimeCall::BridgeWestendGrandpa,
+	pallet_bridge_grandpa::Call::submit_finality_proof_ex
+);
+
+impl SubstrateFinalityPipeline for WestendFinalityToBridgeHubRococo {
+	type SourceChain = Westend;
+	type TargetChain = BridgeHubRococo;
+
+	type FinalityEngine = Grandpa<Self::SourceChain>;
+}
+
+impl SubstrateFinalitySyncPipeline for WestendFinalityToBridgeHubRococo {
+	type SubmitFinalityProofCallBuilder = SubmitWestendFinalityProofCallBuilder;
+}
+
+/// Rococo-to-BridgeHubWestend finality sync pipeline.
+#[derive(Clone, Debug)]
+pub struct RococoFinalityToBridgeHubWestend;
+
+substrate_relay_helper::generate_submit_finality_proof_ex_call_builder!(
+	RococoFinalityToBridgeHubWestend,
+	SubmitRococoFinalityProofCallBuilder,
+	bridge_hub_westend_runtime::RuntimeCall::BridgeRococoGrandpa,
+	pallet_bridge_grandpa::Call::submit_finality_proof_ex
+);
+
+impl SubstrateFinalityPipeline for RococoFinalityToBridgeHubWestend {
+	type SourceChain = Rococo;
+	type TargetChain = BridgeHubWestend;
+
+	type FinalityEngine = Grandpa<Self::SourceChain>;
+}
+
+impl SubstrateFinalitySyncPipeline for RococoFinalityToBridgeHubWestend {
+	type SubmitFinalityProofCallBuilder = SubmitRococoFinalityProofCallBuilder;
+}
+
+/// BridgeHubWestend-to-BridgeHubRococo parachain sync pipeline.
+#[derive(Clone, Debug)]
+pub struct BridgeHubWestendToBridgeHubRococo;
+
+impl SubstrateParachainsPipeline for BridgeHubWestendToBridgeHubRococo {
+	type SourceParachain = BridgeHubWestend;
+	type SourceRelayChain = Westend;
+	type TargetChain = BridgeHubRococo;
+
+	type SubmitParachainHeadsCallBuilder = DirectSubmitParachainHeadsCallBuilder<
+		Self,
+		bridge_hub_rococo_runtime::Runtime,
+		bridge_hub_rococo_runtime::bridge_common_config::BridgeParachainWestendInstance,
+	>;
+}
+
+/// BridgeHubRococo-to-BridgeHubWestend parachain sync pipeline.
+#[derive(Clone, Debug)]
+pub struct BridgeHubRococoToBridgeHubWestend;
+
+impl SubstrateParachainsPipeline for BridgeHubRococoToBridgeHubWestend {
+	type SourceParachain = BridgeHubRococo;
+	type SourceRelayChain = Rococo;
+	type TargetChain = BridgeHubWestend;
+
+	type SubmitParachainHeadsCallBuilder = DirectSubmitParachainHeadsCallBuilder<
+		Self,
+		bridge_hub_westend_runtime::Runtime,
+		bridge_hub_westend_runtime::bridge_to_rococo_config::BridgeParachainRococoInstance,
+	>;
+}
+
+/// BridgeHubRococo-to-BridgeHubWestend messages lane.
+#[derive(Clone, Debug)]
+pub struct BridgeHubRococoMessagesToBridgeHubWestend;
+
+impl SubstrateMessageLane for BridgeHubRococoMessagesToBridgeHubWestend {
+	type SourceChain = BridgeHubRococo;
+	type TargetChain = BridgeHubWestend;
+
+	type ReceiveMessagesProofCallBuilder = DirectReceiveMessagesProofCallBuilder<
+		Self,
+		bridge_hub_westend_runtime::Runtime,
+		bridge_hub_westend_runtime::bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance,
+	>;
+	type ReceiveMessagesDeliveryProofCallBuilder = DirectReceiveMessagesDeliveryProofCallBuilder<
+		Self,
+		bridge_hub_rococo_runtime::Runtime,
+		bridge_hub_rococo_runtime::bridge_to_westend_config::WithBridgeHubWestendMessagesInstance,
+	>;
+
+	type SourceBatchCallBuilder = ();
+	type TargetBatchCallBuilder = ();
+}
+
+/// Run Westend-to-BridgeHubRococo finality relay, signing transactions with given key.
+pub async fn run_westend_finality_relay(
+	westend: WestendClient,
+	bridge_hub_rococo: BridgeHubRococoClient,
+	signer: sr25519::Pair,
+) -> anyhow::Result<()> {
+	substrate_relay_helper::finality::run::<WestendFinalityToBridgeHubRococo>(
+		westend,
+		bridge_hub_rococo,
+		HeadersToRelay::All,
+		TransactionParams { signer, mortality: None },
+		MetricsParams::disabled(),
+		None,
+	)
+	.await
+}
+
+/// Run Rococo-to-BridgeHubWestend finality relay, signing transactions with given key.
+pub async fn run_rococo_finality_relay(
+	rococo: RococoClient,
+	bridge_hub_westend: BridgeHubWestendClient,
+	signer: sr25519::Pair,
+) -> anyhow::Result<()> {
+	substrate_relay_helper::finality::run::<RococoFinalityToBridgeHubWestend>(
+		rococo,
+		bridge_hub_westend,
+		HeadersToRelay::All,
+		TransactionParams { signer, mortality: None },
+		MetricsParams::disabled(),
+		None,
+	)
+	.await
+}
+
+/// Run BridgeHubWestend-to-BridgeHubRococo parachains relay, signing transactions with given
+/// key.
+pub async fn run_bridge_hub_westend_parachains_relay(
+	westend: WestendClient,
+	bridge_hub_rococo: BridgeHubRococoClient,
+	signer: sr25519::Pair,
+) -> anyhow::Result<()> {
+	let source_client = ParachainsSource::<BridgeHubWestendToBridgeHubRococo, _>::new(
+		westend.clone(),
+		Arc::new(Mutex::new(AvailableHeader::Missing)),
+	);
+	let target_client = ParachainsTarget::<BridgeHubWestendToBridgeHubRococo, _, _>::new(
+		westend,
+		bridge_hub_rococo,
+		TransactionParams { signer, mortality: None },
+	);
+
+	parachains_relay::parachains_loop::run(
+		source_client,
+		target_client,
+		MetricsParams::disabled(),
+		false,
+		None,
+		futures::future::pending(),
+	)
+	.await
+	.map_err(|e| anyhow::format_err!("{}", e))
+}
+
+/// Run BridgeHubRococo-to-BridgeHubWestend parachains relay, signing transactions with given
+/// key.
+pub async fn run_bridge_hub_rococo_parachains_relay(
+	rococo: RococoClient,
+	bridge_hub_westend: BridgeHubWestendClient,
+	signer: sr25519::Pair,
+) -> anyhow::Result<()> {
+	let source_client = ParachainsSource::<BridgeHubRococoToBridgeHubWestend, _>::new(
+		rococo.clone(),
+		Arc::new(Mutex::new(AvailableHeader::Missing)),
+	);
+	let target_client = ParachainsTarget::<BridgeHubRococoToBridgeHubWestend, _, _>::new(
+		rococo,
+		bridge_hub_westend,
+		TransactionParams { signer, mortality: None },
+	);
+
+	parachains_relay::parachains_loop::run(
+		source_client,
+		target_client,
+		MetricsParams::disabled(),
+		false,
+		None,
+		futures::future::pending(),
+	)
+	.await
+	.map_err(|e| anyhow::format_err!("{}", e))
+}
+
+/// Run BridgeHubRococo-to-BridgeHubWestend messages relay, serving the lane between Asset Hubs.
+///
+/// The same key is used to sign transactions at both bridge hubs.
+pub async fn run_bridge_hub_rococo_messages_relay(
+	bridge_hub_rococo: BridgeHubRococoClient,
+	bridge_hub_westend: BridgeHubWestendClient,
+	signer: sr25519::Pair,
+) -> anyhow::Result<()> {
+	substrate_relay_helper::messages::run::<BridgeHubRococoMessagesToBridgeHubWestend, _, _>(
+		MessagesRelayParams {
+			source_client: bridge_hub_rococo,
+			source_transaction_params: TransactionParams {
+				signer: signer.clone(),
+				mortality: None,
+			},
+			target_client: bridge_hub_westend,
+			target_transaction_params: TransactionParams { signer, mortality: None },
+			source_to_target_headers_relay: None,
+			target_to_source_headers_relay: None,
+			lane_id: asset_hubs_lane_id(),
+			limits: None,
+			metrics_params: MetricsParams::disabled(),
+			state_store: None,
+		},
+	)
+	.await
+}
+
+/// Return key of the finality relayer account at both bridge hubs.
+pub fn finality_relayer_key() -> sr25519::Pair {
+	sp_keyring::AccountKeyring::Bob.pair()
+}
+
+/// Return key of the parachains relayer account at both bridge hubs.
+pub fn parachains_relayer_key() -> sr25519::Pair {
+	sp_keyring::AccountKeyring::Charlie.pair()
+}
+
+/// Return key of the messages relayer account at both bridge hubs.
+pub fn messages_relayer_key() -> sr25519::Pair {
+	sp_keyring::AccountKeyring::Dave.pair()
+}
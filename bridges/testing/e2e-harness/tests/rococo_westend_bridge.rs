@@ -0,0 +1,264 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! End-to-end tests of Rococo <> Westend bridge relays.
+
+use bp_messages::{InboundLaneData, OutboundLaneData};
+use bp_runtime::HeaderId;
+use bridge_e2e_harness::{
+	chains::{
+		asset_hubs_lane_id, export_message_from_rococo_asset_hub, follow_parachain_heads,
+		force_bridge_hub_westend_xcm_version, start_bridge_hub_rococo_node,
+		start_bridge_hub_westend_node, start_relay_chain_node, BridgeHubRococo,
+		BridgeHubRococoClient, BridgeHubWestend, BridgeHubWestendClient, Rococo, RococoClient,
+		Westend, WestendClient,
+	},
+	pipelines::{
+		finality_relayer_key, messages_relayer_key, parachains_relayer_key,
+		run_bridge_hub_rococo_messages_relay, run_bridge_hub_rococo_parachains_relay,
+		run_bridge_hub_westend_parachains_relay, run_rococo_finality_relay,
+		run_westend_finality_relay,
+	},
+	run_until,
+};
+use futures::{future::BoxFuture, FutureExt};
+use relay_substrate_client::{Chain, Client};
+use sp_runtime::traits::Header as _;
+use std::time::Duration;
+use xcm::latest::prelude::*;
+
+/// Maximal time that a single test is allowed to take.
+const TEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+async fn best_finalized_header_id_at_bridge_hub_rococo(
+	client: &BridgeHubRococoClient,
+	method: &str,
+) -> anyhow::Result<Option<HeaderId<bp_westend::Hash, bp_westend::BlockNumber>>> {
+	let best_hash = client.best_header_hash().await?;
+	Ok(client.state_call(best_hash, method.into(), ()).await?)
+}
+
+async fn outbound_lane_at_bridge_hub_rococo(
+	client: &BridgeHubRococoClient,
+) -> anyhow::Result<OutboundLaneData> {
+	let best_hash = client.best_header_hash().await?;
+	client
+		.storage_value(
+			best_hash,
+			bp_messages::storage_keys::outbound_lane_data_key(
+				bp_bridge_hub_westend::WITH_BRIDGE_HUB_WESTEND_MESSAGES_PALLET_NAME,
+				&asset_hubs_lane_id(),
+			),
+		)
+		.await?
+		.ok_or_else(|| anyhow::format_err!("Missing outbound lane at {}", BridgeHubRococo::NAME))
+}
+
+async fn inbound_lane_at_bridge_hub_westend(
+	client: &BridgeHubWestendClient,
+) -> anyhow::Result<InboundLaneData<bp_polkadot_core::AccountId>> {
+	let best_hash = client.best_header_hash().await?;
+	client
+		.storage_value(
+			best_hash,
+			bp_messages::storage_keys::inbound_lane_data_key(
+				bp_bridge_hub_rococo::WITH_BRIDGE_HUB_ROCOCO_MESSAGES_PALLET_NAME,
+				&asset_hubs_lane_id(),
+			),
+		)
+		.await?
+		.ok_or_else(|| anyhow::format_err!("Missing inbound lane at {}", BridgeHubWestend::NAME))
+}
+
+#[async_std::test]
+async fn westend_headers_are_relayed_to_bridge_hub_rococo() {
+	let westend = start_relay_chain_node(Westend::NAME);
+	let (bridge_hub_rococo, _) = start_bridge_hub_rococo_node(&westend).unwrap();
+
+	westend.produce_blocks(5).await.unwrap();
+	let westend_best_header = westend.best_header().unwrap();
+	let expected_header_id = HeaderId(
+		*westend_best_header.number() as bp_westend::BlockNumber,
+		westend_best_header.hash(),
+	);
+
+	let bridge_hub_rococo_client = BridgeHubRococoClient::new(bridge_hub_rococo);
+	run_until(
+		run_westend_finality_relay(
+			WestendClient::new(westend),
+			bridge_hub_rococo_client.clone(),
+			finality_relayer_key(),
+		),
+		TEST_TIMEOUT,
+		|| {
+			let bridge_hub_rococo_client = bridge_hub_rococo_client.clone();
+			async move {
+				Ok(best_finalized_header_id_at_bridge_hub_rococo(
+					&bridge_hub_rococo_client,
+					bp_westend::BEST_FINALIZED_WESTEND_HEADER_METHOD,
+				)
+				.await? == Some(expected_header_id))
+			}
+		},
+	)
+	.await
+	.unwrap();
+}
+
+#[async_std::test]
+async fn bridge_hub_westend_heads_are_relayed_to_bridge_hub_rococo() {
+	let rococo = start_relay_chain_node(Rococo::NAME);
+	let westend = start_relay_chain_node(Westend::NAME);
+	let (bridge_hub_rococo, _) = start_bridge_hub_rococo_node(&westend).unwrap();
+	let (bridge_hub_westend, _) = start_bridge_hub_westend_node(&rococo).unwrap();
+
+	let bridge_hub_westend_best_hash = bridge_hub_westend.produce_blocks(2).await.unwrap();
+	let bridge_hub_westend_best_header = bridge_hub_westend.best_header().unwrap();
+	assert_eq!(bridge_hub_westend_best_header.hash(), bridge_hub_westend_best_hash);
+	let expected_header_id =
+		HeaderId(*bridge_hub_westend_best_header.number(), bridge_hub_westend_best_hash);
+
+	let westend_client = WestendClient::new(westend.clone());
+	let bridge_hub_rococo_client = BridgeHubRococoClient::new(bridge_hub_rococo);
+	let relays: Vec<BoxFuture<anyhow::Result<()>>> = vec![
+		follow_parachain_heads(
+			westend,
+			bp_bridge_hub_westend::BRIDGE_HUB_WESTEND_PARACHAIN_ID,
+			bridge_hub_westend,
+		)
+		.boxed(),
+		run_westend_finality_relay(
+			westend_client.clone(),
+			bridge_hub_rococo_client.clone(),
+			finality_relayer_key(),
+		)
+		.boxed(),
+		run_bridge_hub_westend_parachains_relay(
+			westend_client,
+			bridge_hub_rococo_client.clone(),
+			parachains_relayer_key(),
+		)
+		.boxed(),
+	];
+	run_until(futures::future::try_join_all(relays).map(|r| r.map(drop)), TEST_TIMEOUT, || {
+		let bridge_hub_rococo_client = bridge_hub_rococo_client.clone();
+		async move {
+			Ok(best_finalized_header_id_at_bridge_hub_rococo(
+				&bridge_hub_rococo_client,
+				bp_bridge_hub_westend::BEST_FINALIZED_BRIDGE_HUB_WESTEND_HEADER_METHOD,
+			)
+			.await? == Some(expected_header_id))
+		}
+	})
+	.await
+	.unwrap();
+}
+
+#[async_std::test]
+async fn message_from_rococo_is_delivered_to_westend_and_confirmed() {
+	let rococo = start_relay_chain_node(Rococo::NAME);
+	let westend = start_relay_chain_node(Westend::NAME);
+	let (bridge_hub_rococo, bridge_hub_rococo_author) =
+		start_bridge_hub_rococo_node(&westend).unwrap();
+	let (bridge_hub_westend, _) = start_bridge_hub_westend_node(&rococo).unwrap();
+	let bridge_hub_rococo_client = BridgeHubRococoClient::new(bridge_hub_rococo.clone());
+	let bridge_hub_westend_client = BridgeHubWestendClient::new(bridge_hub_westend.clone());
+
+	// the relay chain tells Rococo Bridge Hub which XCM version is supported by the remote
+	// bridge hub and then the Rococo Asset Hub asks to export the message to Westend
+	force_bridge_hub_westend_xcm_version(&bridge_hub_rococo_author);
+	bridge_hub_rococo.produce_blocks(2).await.unwrap();
+	export_message_from_rococo_asset_hub(&bridge_hub_rococo_author, Xcm(vec![ClearOrigin]));
+	bridge_hub_rococo.produce_blocks(2).await.unwrap();
+	let outbound_lane =
+		outbound_lane_at_bridge_hub_rococo(&bridge_hub_rococo_client).await.unwrap();
+	assert_eq!(outbound_lane.latest_generated_nonce, 1);
+	assert_eq!(outbound_lane.latest_received_nonce, 0);
+
+	// run all relays until the message is delivered to Westend and the delivery is confirmed
+	// at Rococo
+	let rococo_client = RococoClient::new(rococo.clone());
+	let westend_client = WestendClient::new(westend.clone());
+	let relays: Vec<BoxFuture<anyhow::Result<()>>> = vec![
+		follow_parachain_heads(
+			rococo,
+			bp_bridge_hub_rococo::BRIDGE_HUB_ROCOCO_PARACHAIN_ID,
+			bridge_hub_rococo,
+		)
+		.boxed(),
+		follow_parachain_heads(
+			westend,
+			bp_bridge_hub_westend::BRIDGE_HUB_WESTEND_PARACHAIN_ID,
+			bridge_hub_westend,
+		)
+		.boxed(),
+		run_rococo_finality_relay(
+			rococo_client.clone(),
+			bridge_hub_westend_client.clone(),
+			finality_relayer_key(),
+		)
+		.boxed(),
+		run_westend_finality_relay(
+			westend_client.clone(),
+			bridge_hub_rococo_client.clone(),
+			finality_relayer_key(),
+		)
+		.boxed(),
+		run_bridge_hub_rococo_parachains_relay(
+			rococo_client,
+			bridge_hub_westend_client.clone(),
+			parachains_relayer_key(),
+		)
+		.boxed(),
+		run_bridge_hub_westend_parachains_relay(
+			westend_client,
+			bridge_hub_rococo_client.clone(),
+			parachains_relayer_key(),
+		)
+		.boxed(),
+		run_bridge_hub_rococo_messages_relay(
+			bridge_hub_rococo_client.clone(),
+			bridge_hub_westend_client.clone(),
+			messages_relayer_key(),
+		)
+		.boxed(),
+	];
+	run_until(futures::future::try_join_all(relays).map(|r| r.map(drop)), TEST_TIMEOUT, || {
+		let bridge_hub_rococo_client = bridge_hub_rococo_client.clone();
+		let bridge_hub_westend_client = bridge_hub_westend_client.clone();
+		async move {
+			let inbound_lane =
+				inbound_lane_at_bridge_hub_westend(&bridge_hub_westend_client).await?;
+			let outbound_lane =
+				outbound_lane_at_bridge_hub_rococo(&bridge_hub_rococo_client).await?;
+			Ok(inbound_lane.last_delivered_nonce() == 1 && outbound_lane.latest_received_nonce == 1)
+		}
+	})
+	.await
+	.unwrap();
+
+	// the message has been delivered by the messages relayer
+	let inbound_lane =
+		inbound_lane_at_bridge_hub_westend(&bridge_hub_westend_client).await.unwrap();
+	assert_eq!(
+		inbound_lane
+			.relayers
+			.iter()
+			.map(|entry| entry.relayer.clone())
+			.collect::<Vec<_>>(),
+		vec![sp_keyring::AccountKeyring::Dave.to_account_id()],
+	);
+}
@@ -151,7 +151,7 @@ fn multi_hop_works() {
 
 	// We get them from the PenpalA closure.
 	let mut delivery_fees_amount = 0;
-	let mut remote_message = VersionedXcm::V4(Xcm(Vec::new()));
+	let mut remote_message = VersionedXcm::V5(Xcm(Vec::new()));
 	<PenpalA as TestExt>::execute_with(|| {
 		type Runtime = <PenpalA as Chain>::Runtime;
 		type OriginCaller = <PenpalA as Chain>::OriginCaller;
@@ -178,7 +178,7 @@ fn multi_hop_works() {
 	// These are set in the AssetHub closure.
 	let mut intermediate_execution_fees = 0;
 	let mut intermediate_delivery_fees_amount = 0;
-	let mut intermediate_remote_message = VersionedXcm::V4(Xcm::<()>(Vec::new()));
+	let mut intermediate_remote_message = VersionedXcm::V5(Xcm::<()>(Vec::new()));
 	<AssetHubRococo as TestExt>::execute_with(|| {
 		type Runtime = <AssetHubRococo as Chain>::Runtime;
 		type RuntimeCall = <AssetHubRococo as Chain>::RuntimeCall;
@@ -193,7 +193,7 @@ fn multi_hop_works() {
 
 		// We have to do this to turn `VersionedXcm<()>` into `VersionedXcm<RuntimeCall>`.
 		let xcm_program =
-			VersionedXcm::V4(Xcm::<RuntimeCall>::from(remote_message.clone().try_into().unwrap()));
+			VersionedXcm::V5(Xcm::<RuntimeCall>::from(remote_message.clone().try_into().unwrap()));
 
 		// Now we get the delivery fees to the final destination.
 		let result =
@@ -16,12 +16,23 @@
 mod pallet_xcm_benchmarks_fungible;
 mod pallet_xcm_benchmarks_generic;
 
-use crate::{xcm_config::MaxAssetsIntoHolding, Runtime};
+use crate::{
+	weights::{
+		pallet_asset_conversion::WeightInfo as AssetConversionWeight,
+		pallet_nfts::WeightInfo as NftsWeight,
+	},
+	xcm_config::{MaxAssetsIntoHolding, PoolAssetsExchanger},
+	Runtime,
+};
 use alloc::vec::Vec;
-use frame_support::weights::Weight;
+use frame_support::{traits::Get, weights::Weight, BoundedVec};
+use pallet_nfts::WeightInfo as _;
 use pallet_xcm_benchmarks_fungible::WeightInfo as XcmFungibleWeight;
 use pallet_xcm_benchmarks_generic::WeightInfo as XcmGeneric;
-use xcm::{latest::prelude::*, DoubleEncoded};
+use xcm::{
+	latest::{prelude::*, MaxAssetTransferFilters},
+	DoubleEncoded,
+};
 
 trait WeighAssets {
 	fn weigh_assets(&self, weight: Weight) -> Weight;
@@ -119,8 +130,11 @@ impl<Call> XcmWeightInfo<Call> for AssetHubWestendXcmWeight<Call> {
 	fn deposit_reserve_asset(assets: &AssetFilter, _dest: &Location, _xcm: &Xcm<()>) -> Weight {
 		assets.weigh_assets(XcmFungibleWeight::<Runtime>::deposit_reserve_asset())
 	}
-	fn exchange_asset(_give: &AssetFilter, _receive: &Assets, _maximal: &bool) -> Weight {
-		Weight::MAX
+	fn exchange_asset(_give: &AssetFilter, _receive: &Assets, maximal: &bool) -> Weight {
+		PoolAssetsExchanger::exchange_weight::<
+			AssetConversionWeight<Runtime>,
+			<Runtime as frame_system::Config>::DbWeight,
+		>(*maximal)
 	}
 	fn initiate_reserve_withdraw(
 		assets: &AssetFilter,
@@ -201,11 +215,27 @@ impl<Call> XcmWeightInfo<Call> for AssetHubWestendXcmWeight<Call> {
 	fn export_message(_: &NetworkId, _: &Junctions, _: &Xcm<()>) -> Weight {
 		Weight::MAX
 	}
-	fn lock_asset(_: &Asset, _: &Location) -> Weight {
-		Weight::MAX
+	fn lock_asset(asset: &Asset, _: &Location) -> Weight {
+		match asset.fun {
+			// Disable the transfer of the `Nfts` item, record the lock and notify the unlocker.
+			NonFungible(_) => NftsWeight::<Runtime>::lock_item_transfer()
+				.saturating_add(XcmGeneric::<Runtime>::report_error())
+				.saturating_add(
+					<Runtime as frame_system::Config>::DbWeight::get().reads_writes(1, 1),
+				),
+			// Fungibles can't be locked.
+			Fungible(_) => Weight::MAX,
+		}
 	}
-	fn unlock_asset(_: &Asset, _: &Location) -> Weight {
-		Weight::MAX
+	fn unlock_asset(asset: &Asset, _: &Location) -> Weight {
+		match asset.fun {
+			// Enable the transfer of the `Nfts` item again and remove the lock.
+			NonFungible(_) => NftsWeight::<Runtime>::unlock_item_transfer().saturating_add(
+				<Runtime as frame_system::Config>::DbWeight::get().reads_writes(1, 1),
+			),
+			// Fungibles can't be locked.
+			Fungible(_) => Weight::MAX,
+		}
 	}
 	fn note_unlockable(_: &Asset, _: &Location) -> Weight {
 		Weight::MAX
@@ -229,4 +259,31 @@ impl<Call> XcmWeightInfo<Call> for AssetHubWestendXcmWeight<Call> {
 	fn unpaid_execution(_: &WeightLimit, _: &Option<Location>) -> Weight {
 		XcmGeneric::<Runtime>::unpaid_execution()
 	}
+	fn pay_fees(asset: &Asset) -> Weight {
+		Self::buy_execution(asset, &Unlimited)
+	}
+	fn initiate_transfer(
+		dest: &Location,
+		remote_fees: &Option<AssetTransferFilter>,
+		_preserve_origin: &bool,
+		assets: &BoundedVec<AssetTransferFilter, MaxAssetTransferFilters>,
+		xcm: &Xcm<()>,
+	) -> Weight {
+		// Every transferred asset is weighed as the corresponding single-type transfer.
+		remote_fees.iter().chain(assets.iter()).fold(Weight::zero(), |weight, filter| {
+			weight.saturating_add(match filter {
+				AssetTransferFilter::Teleport(assets) => Self::initiate_teleport(assets, dest, xcm),
+				AssetTransferFilter::ReserveDeposit(assets) =>
+					Self::deposit_reserve_asset(assets, dest, xcm),
+				AssetTransferFilter::ReserveWithdraw(assets) =>
+					Self::initiate_reserve_withdraw(assets, dest, xcm),
+			})
+		})
+	}
+	fn execute_with_origin(_: &Option<InteriorLocation>, _: &Xcm<Call>) -> Weight {
+		XcmGeneric::<Runtime>::descend_origin()
+	}
+	fn set_asset_claimer(_location: &Location) -> Weight {
+		XcmGeneric::<Runtime>::set_topic()
+	}
 }
@@ -15,7 +15,7 @@
 
 use super::{
 	AccountId, AllPalletsWithSystem, Assets, Authorship, Balance, Balances, BaseDeliveryFee,
-	CollatorSelection, FeeAssetId, ForeignAssets, ForeignAssetsInstance, ParachainInfo,
+	CollatorSelection, FeeAssetId, ForeignAssets, ForeignAssetsInstance, Nfts, ParachainInfo,
 	ParachainSystem, PolkadotXcm, PoolAssets, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin,
 	ToRococoXcmRouter, TransactionByteFee, TrustBackedAssetsInstance, Uniques, WeightToFee,
 	XcmpQueue,
@@ -51,13 +51,14 @@ use xcm_builder::{
 	DenyReserveTransferToRelayChain, DenyThenTry, DescribeFamily, DescribePalletTerminal,
 	EnsureXcmOrigin, FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter,
 	GlobalConsensusParachainConvertsFor, HashedDescription, IsConcrete, LocalMint,
-	MatchedConvertedConcreteId, NetworkExportTableItem, NoChecking, NonFungiblesAdapter,
-	ParentAsSuperuser, ParentIsPreset, RelayChainAsNative, SendXcmFeeToAccount,
-	SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountId32AsNative,
-	SignedToAccountId32, SingleAssetExchangeAdapter, SovereignPaidRemoteExporter,
-	SovereignSignedViaLocation, StartsWith, StartsWithExplicitGlobalConsensus, TakeWeightCredit,
-	TrailingSetTopicAsId, UsingComponents, WeightInfoBounds, WithComputedOrigin,
-	WithLatestLocationConverter, WithUniqueTopic, XcmFeeManagerFromComponents,
+	MatchedConvertedConcreteId, MultiHopExchangeAdapter, NetworkExportTableItem, NoChecking,
+	NonFungiblesAdapter, NonFungiblesLockAdapter, ParentAsSuperuser, ParentIsPreset,
+	RelayChainAsNative, ReturnTrappedAssets, SendXcmFeeToAccount, SiblingParachainAsNative,
+	SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
+	SovereignPaidRemoteExporter, SovereignSignedViaLocation, StartsWith,
+	StartsWithExplicitGlobalConsensus, TakeWeightCredit, TrailingSetTopicAsId, UsingComponents,
+	WeightInfoBounds, WithComputedOrigin, WithLatestLocationConverter, WithReturnedAssetsWeight,
+	WithUniqueTopic, XcmFeeManagerFromComponents,
 };
 use xcm_executor::XcmExecutor;
 
@@ -77,6 +78,8 @@ parameter_types! {
 		PalletInstance(<PoolAssets as PalletInfoAccess>::index() as u8).into();
 	pub UniquesPalletLocation: Location =
 		PalletInstance(<Uniques as PalletInfoAccess>::index() as u8).into();
+	pub NftsPalletLocation: Location =
+		PalletInstance(<Nfts as PalletInfoAccess>::index() as u8).into();
 	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
 	pub StakingPot: AccountId = CollatorSelection::account_id();
 	pub TreasuryAccount: AccountId = TREASURY_PALLET_ID.into_account_truncating();
@@ -143,6 +146,9 @@ pub type FungiblesTransactor = FungiblesAdapter<
 pub type UniquesConvertedConcreteId =
 	assets_common::UniquesConvertedConcreteId<UniquesPalletLocation>;
 
+/// Matcher for converting `CollectionId`/`ItemId` into an nfts asset.
+pub type NftsConvertedConcreteId = assets_common::UniquesConvertedConcreteId<NftsPalletLocation>;
+
 /// Means for transacting unique assets.
 pub type UniquesTransactor = NonFungiblesAdapter<
 	// Use this non-fungibles implementation:
@@ -249,8 +255,27 @@ parameter_types! {
 	pub const MaxInstructions: u32 = 100;
 	pub const MaxAssetsIntoHolding: u32 = 64;
 	pub XcmAssetFeesReceiver: Option<AccountId> = Authorship::author();
+	/// The maximum number of dropped assets returned to their owner by a message.
+	pub const MaxReturnedAssets: u32 = 2;
+	/// The weight of returning a single dropped asset instead of trapping it.
+	pub ReturnTrappedAssetWeight: Weight =
+		<crate::weights::xcm::AssetHubWestendXcmWeight<RuntimeCall> as XcmWeightInfo<RuntimeCall>>::deposit_asset(
+			&Wild(AllCounted(1)),
+			&Location::here(),
+		);
 }
 
+/// Weighs messages, including the weight of returning their dropped assets.
+pub type XcmWeigher = WithReturnedAssetsWeight<
+	WeightInfoBounds<
+		crate::weights::xcm::AssetHubWestendXcmWeight<RuntimeCall>,
+		RuntimeCall,
+		MaxInstructions,
+	>,
+	MaxReturnedAssets,
+	ReturnTrappedAssetWeight,
+>;
+
 pub struct ParentOrParentsPlurality;
 impl Contains<Location> for ParentOrParentsPlurality {
 	fn contains(location: &Location) -> bool {
@@ -351,11 +376,19 @@ pub type TrustedTeleporters = (
 	IsForeignConcreteAsset<FromSiblingParachain<parachain_info::Pallet<Runtime>>>,
 );
 
+parameter_types! {
+	/// Assets through which pool assets are exchanged when there is no pool between them.
+	pub ExchangeIntermediates: alloc::vec::Vec<xcm::v4::Location> =
+		alloc::vec![xcm::v4::Location::parent()];
+	pub const MaxExchangeHops: u32 = 2;
+}
+
 /// Asset converter for pool assets.
-/// Used to convert one asset to another, when there is a pool available between the two.
-/// This type thus allows paying fees with any asset as long as there is a pool between said
-/// asset and the asset required for fee payment.
-pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
+/// Used to convert one asset to another, when there is a pool available between the two, or
+/// between each of them and the relay token.
+/// This type thus allows paying fees with any asset as long as there is such a path of pools
+/// between said asset and the asset required for fee payment.
+pub type PoolAssetsExchanger = MultiHopExchangeAdapter<
 	crate::AssetConversion,
 	crate::NativeAndAssets,
 	(
@@ -370,6 +403,8 @@ pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
 			TryConvertInto,
 		>,
 	),
+	ExchangeIntermediates,
+	MaxExchangeHops,
 	AccountId,
 >;
 
@@ -390,11 +425,7 @@ impl xcm_executor::Config for XcmConfig {
 	type IsTeleporter = TrustedTeleporters;
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
-	type Weigher = WeightInfoBounds<
-		crate::weights::xcm::AssetHubWestendXcmWeight<RuntimeCall>,
-		RuntimeCall,
-		MaxInstructions,
-	>;
+	type Weigher = XcmWeigher;
 	type Trader = (
 		UsingComponents<
 			WeightToFee,
@@ -447,12 +478,20 @@ impl xcm_executor::Config for XcmConfig {
 		>,
 	);
 	type ResponseHandler = PolkadotXcm;
-	type AssetTrap = PolkadotXcm;
+	// Dropped assets are returned to their owner, only trapping the ones which can't be deposited.
+	type AssetTrap = ReturnTrappedAssets<
+		AssetTransactors,
+		Everything,
+		PolkadotXcm,
+		FrameTransactionalProcessor,
+		MaxReturnedAssets,
+	>;
 	type AssetClaims = PolkadotXcm;
 	type SubscriptionService = PolkadotXcm;
 	type PalletInstancesInfo = AllPalletsWithSystem;
 	type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
-	type AssetLocker = ();
+	// Local `Nfts` items can be locked in favour of remote chains.
+	type AssetLocker = PolkadotXcm;
 	type AssetExchanger = PoolAssetsExchanger;
 	type FeeManager = XcmFeeManagerFromComponents<
 		WaivedLocations,
@@ -510,11 +549,7 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
-	type Weigher = WeightInfoBounds<
-		crate::weights::xcm::AssetHubWestendXcmWeight<RuntimeCall>,
-		RuntimeCall,
-		MaxInstructions,
-	>;
+	type Weigher = XcmWeigher;
 	type UniversalLocation = UniversalLocation;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
@@ -522,6 +557,7 @@ impl pallet_xcm::Config for Runtime {
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
 	type Currency = Balances;
 	type CurrencyMatcher = ();
+	type NonFungibleLocker = NonFungiblesLockAdapter<Nfts, NftsConvertedConcreteId, AccountId>;
 	type TrustedLockers = ();
 	type SovereignAccountOf = LocationToAccountId;
 	type MaxLockers = ConstU32<8>;
@@ -22,11 +22,11 @@ use asset_hub_westend_runtime::{
 	xcm_config::{
 		bridging, AssetFeeAsExistentialDepositMultiplierFeeCharger, CheckingAccount,
 		ForeignAssetFeeAsExistentialDepositMultiplierFeeCharger, ForeignCreatorsSovereignAccountOf,
-		LocationToAccountId, StakingPot, TrustBackedAssetsPalletLocation, WestendLocation,
-		XcmConfig,
+		LocationToAccountId, NftsPalletLocation, StakingPot, TrustBackedAssetsPalletLocation,
+		WestendLocation, XcmConfig,
 	},
 	AllPalletsWithoutSystem, Assets, Balances, ExistentialDeposit, ForeignAssets,
-	ForeignAssetsInstance, MetadataDepositBase, MetadataDepositPerByte, ParachainSystem,
+	ForeignAssetsInstance, MetadataDepositBase, MetadataDepositPerByte, Nfts, ParachainSystem,
 	PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, SessionKeys,
 	TrustBackedAssetsInstance, XcmpQueue,
 };
@@ -44,6 +44,7 @@ use frame_support::{
 		fungibles::{
 			Create, Inspect as FungiblesInspect, InspectEnumerable, Mutate as FungiblesMutate,
 		},
+		nonfungibles_v2::Inspect as NonFungiblesInspect,
 	},
 	weights::{Weight, WeightToFee as WeightToFeeT},
 };
@@ -1329,3 +1330,76 @@ fn reserve_transfer_native_asset_to_non_teleport_para_works() {
 		WeightLimit::Unlimited,
 	);
 }
+
+#[test]
+fn nfts_items_can_be_locked_and_unlocked_through_xcm() {
+	use xcm_executor::traits::{AssetLock, Enact, LockError};
+
+	let alice = AccountId::from(ALICE);
+	let bob = AccountId::from([2u8; 32]);
+	ExtBuilder::<Runtime>::default()
+		.with_collators(vec![alice.clone()])
+		.with_session_keys(vec![(
+			alice.clone(),
+			alice.clone(),
+			SessionKeys { aura: AuraId::from(sp_core::sr25519::Public::from_raw(ALICE)) },
+		)])
+		.with_balances(vec![(alice.clone(), 100 * UNITS), (bob.clone(), 100 * UNITS)])
+		.build()
+		.execute_with(|| {
+			let (collection, item) = (0, 1);
+			assert_ok!(Nfts::force_create(
+				RuntimeHelper::root_origin(),
+				alice.clone().into(),
+				pallet_nfts::CollectionConfig {
+					settings: pallet_nfts::CollectionSettings::all_enabled(),
+					..Default::default()
+				},
+			));
+			assert_ok!(Nfts::force_mint(
+				RuntimeHelper::root_origin(),
+				collection,
+				item,
+				alice.clone().into(),
+				Default::default(),
+			));
+
+			let owner: Location = AccountId32 { network: None, id: ALICE }.into();
+			let unlocker: Location = (Parent, Parachain(2000)).into();
+			let nft: Asset = (
+				(NftsPalletLocation::get(), GeneralIndex(collection as u128)),
+				Index(item as u128),
+			)
+				.into();
+
+			// Only the owner of an item can lock it.
+			let bob_location: Location = AccountId32 { network: None, id: [2u8; 32] }.into();
+			assert!(matches!(
+				PolkadotXcm::prepare_lock(unlocker.clone(), nft.clone(), bob_location),
+				Err(LockError::AssetNotOwned)
+			));
+
+			assert_ok!(PolkadotXcm::prepare_lock(unlocker.clone(), nft.clone(), owner.clone())
+				.and_then(Enact::enact));
+			assert!(!<Nfts as NonFungiblesInspect<_>>::can_transfer(&collection, &item));
+			assert_noop!(
+				Nfts::transfer(
+					RuntimeHelper::origin_of(alice.clone()),
+					collection,
+					item,
+					bob.clone().into()
+				),
+				pallet_nfts::Error::<Runtime>::ItemLocked
+			);
+
+			assert_ok!(PolkadotXcm::prepare_unlock(unlocker, nft, owner).and_then(Enact::enact));
+			assert!(<Nfts as NonFungiblesInspect<_>>::can_transfer(&collection, &item));
+			assert_ok!(Nfts::transfer(
+				RuntimeHelper::origin_of(alice),
+				collection,
+				item,
+				bob.clone().into()
+			));
+			assert_eq!(<Nfts as NonFungiblesInspect<_>>::owner(&collection, &item), Some(bob));
+		})
+}
@@ -20,10 +20,13 @@ mod pallet_xcm_benchmarks_generic;
 use crate::{xcm_config::MaxAssetsIntoHolding, Runtime};
 use alloc::vec::Vec;
 use codec::Encode;
-use frame_support::weights::Weight;
+use frame_support::{weights::Weight, BoundedVec};
 use pallet_xcm_benchmarks_fungible::WeightInfo as XcmFungibleWeight;
 use pallet_xcm_benchmarks_generic::WeightInfo as XcmGeneric;
-use xcm::{latest::prelude::*, DoubleEncoded};
+use xcm::{
+	latest::{prelude::*, MaxAssetTransferFilters},
+	DoubleEncoded,
+};
 
 trait WeighAssets {
 	fn weigh_assets(&self, weight: Weight) -> Weight;
@@ -232,4 +235,31 @@ impl<Call> XcmWeightInfo<Call> for BridgeHubWestendXcmWeight<Call> {
 	fn unpaid_execution(_: &WeightLimit, _: &Option<Location>) -> Weight {
 		XcmGeneric::<Runtime>::unpaid_execution()
 	}
+	fn pay_fees(asset: &Asset) -> Weight {
+		Self::buy_execution(asset, &Unlimited)
+	}
+	fn initiate_transfer(
+		dest: &Location,
+		remote_fees: &Option<AssetTransferFilter>,
+		_preserve_origin: &bool,
+		assets: &BoundedVec<AssetTransferFilter, MaxAssetTransferFilters>,
+		xcm: &Xcm<()>,
+	) -> Weight {
+		// Every transferred asset is weighed as the corresponding single-type transfer.
+		remote_fees.iter().chain(assets.iter()).fold(Weight::zero(), |weight, filter| {
+			weight.saturating_add(match filter {
+				AssetTransferFilter::Teleport(assets) => Self::initiate_teleport(assets, dest, xcm),
+				AssetTransferFilter::ReserveDeposit(assets) =>
+					Self::deposit_reserve_asset(assets, dest, xcm),
+				AssetTransferFilter::ReserveWithdraw(assets) =>
+					Self::initiate_reserve_withdraw(assets, dest, xcm),
+			})
+		})
+	}
+	fn execute_with_origin(_: &Option<InteriorLocation>, _: &Xcm<Call>) -> Weight {
+		XcmGeneric::<Runtime>::descend_origin()
+	}
+	fn set_asset_claimer(_location: &Location) -> Weight {
+		XcmGeneric::<Runtime>::set_topic()
+	}
 }
@@ -0,0 +1,168 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzing of the XCM configuration of Asset Hub Westend.
+
+use crate::FuzzRuntime;
+use asset_hub_westend_runtime::{
+	xcm_config::{LocationToAccountId, TrustBackedAssetsPalletLocation, XcmConfig},
+	Assets, Balances, ForeignAssets, Runtime, RuntimeEvent, RuntimeOrigin,
+};
+use frame_support::{
+	assert_ok,
+	traits::{
+		fungible,
+		fungibles::{self, Mutate as _},
+	},
+};
+use parachains_common::AccountId;
+use parachains_runtimes_test_utils::ExtBuilder;
+use xcm::latest::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+
+/// Id of the trust-backed asset created at genesis.
+const TRUST_BACKED_ASSET_ID: u32 = 1;
+/// Sibling parachain whose asset is registered as a foreign asset at genesis.
+const FOREIGN_PARA_ID: u32 = 2000;
+/// Balance of every account, in every asset, at genesis.
+const INITIAL_BALANCE: u128 = 1_000_000_000_000_000;
+
+fn trust_backed_asset() -> Location {
+	TrustBackedAssetsPalletLocation::get()
+		.appended_with(GeneralIndex(TRUST_BACKED_ASSET_ID.into()))
+		.unwrap()
+}
+
+fn sibling_asset() -> Location {
+	Location::new(1, [Parachain(FOREIGN_PARA_ID), GeneralIndex(1)])
+}
+
+fn bridged_asset() -> Location {
+	Location::new(2, [GlobalConsensus(Rococo)])
+}
+
+fn accounts() -> Vec<AccountId> {
+	let locals = AssetHubWestend::beneficiaries().into_iter();
+	let sovereigns = AssetHubWestend::origins().into_iter();
+	locals
+		.chain(sovereigns)
+		.filter_map(|location| LocationToAccountId::convert_location(&location))
+		.collect()
+}
+
+/// Asset Hub Westend, with a trust-backed asset and foreign assets from a sibling parachain and
+/// from Rococo.
+pub struct AssetHubWestend;
+
+impl FuzzRuntime for AssetHubWestend {
+	type Runtime = Runtime;
+	type XcmConfig = XcmConfig;
+
+	fn new_ext() -> sp_io::TestExternalities {
+		let accounts = accounts();
+		let mut ext = ExtBuilder::<Runtime>::default()
+			.with_balances(accounts.iter().cloned().map(|who| (who, INITIAL_BALANCE)).collect())
+			.with_safe_xcm_version(XCM_VERSION)
+			.with_para_id(1000.into())
+			.build();
+		ext.execute_with(|| {
+			let owner = accounts[0].clone();
+			assert_ok!(Assets::force_create(
+				RuntimeOrigin::root(),
+				TRUST_BACKED_ASSET_ID.into(),
+				owner.clone().into(),
+				true,
+				1
+			));
+			for foreign_asset in [sibling_asset(), bridged_asset()] {
+				assert_ok!(ForeignAssets::force_create(
+					RuntimeOrigin::root(),
+					foreign_asset,
+					owner.clone().into(),
+					true,
+					1
+				));
+			}
+			for who in &accounts {
+				assert_ok!(Assets::mint_into(TRUST_BACKED_ASSET_ID, who, INITIAL_BALANCE));
+				assert_ok!(ForeignAssets::mint_into(sibling_asset(), who, INITIAL_BALANCE));
+				assert_ok!(ForeignAssets::mint_into(bridged_asset(), who, INITIAL_BALANCE));
+			}
+		});
+		ext
+	}
+
+	fn origins() -> Vec<Location> {
+		vec![
+			// Relay chain.
+			Location::parent(),
+			// Bridge Hub.
+			Location::new(1, [Parachain(1002)]),
+			// Non-system sibling parachain.
+			Location::new(1, [Parachain(FOREIGN_PARA_ID)]),
+			// Asset Hub Rococo.
+			Location::new(2, [GlobalConsensus(Rococo), Parachain(1000)]),
+			// Local account.
+			Location::new(0, [AccountId32 { network: None, id: [0; 32] }]),
+		]
+	}
+
+	fn assets() -> Vec<AssetId> {
+		vec![
+			AssetId(Location::parent()),
+			AssetId(trust_backed_asset()),
+			AssetId(sibling_asset()),
+			AssetId(bridged_asset()),
+			// Unknown asset.
+			AssetId(Location::new(1, [Parachain(3000)])),
+		]
+	}
+
+	fn beneficiaries() -> Vec<Location> {
+		(0..3u8).map(|i| AccountId32 { network: None, id: [i; 32] }.into()).collect()
+	}
+
+	fn destinations() -> Vec<Location> {
+		vec![
+			Location::parent(),
+			Location::new(1, [Parachain(1002)]),
+			Location::new(1, [Parachain(FOREIGN_PARA_ID)]),
+			Location::new(2, [GlobalConsensus(Rococo), Parachain(1000)]),
+		]
+	}
+
+	fn total_issuance(asset: &AssetId) -> Option<u128> {
+		let AssetId(location) = asset;
+		if *location == Location::parent() {
+			Some(<Balances as fungible::Inspect<_>>::total_issuance())
+		} else if *location == trust_backed_asset() {
+			Some(<Assets as fungibles::Inspect<_>>::total_issuance(TRUST_BACKED_ASSET_ID))
+		} else if *location == sibling_asset() || *location == bridged_asset() {
+			Some(<ForeignAssets as fungibles::Inspect<_>>::total_issuance(location.clone()))
+		} else {
+			None
+		}
+	}
+
+	fn xcm_events() -> Vec<pallet_xcm::Event<Runtime>> {
+		frame_system::Pallet::<Runtime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::PolkadotXcm(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,96 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fuzzing of the XCM configuration of Bridge Hub Westend.
+
+use crate::FuzzRuntime;
+use bridge_hub_westend_runtime::{
+	xcm_config::{LocationToAccountId, XcmConfig},
+	Balances, Runtime, RuntimeEvent,
+};
+use frame_support::traits::fungible;
+use parachains_runtimes_test_utils::ExtBuilder;
+use xcm::latest::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+
+/// Balance of every account at genesis.
+const INITIAL_BALANCE: u128 = 1_000_000_000_000_000;
+
+/// Bridge Hub Westend, which only knows about the relay chain token.
+pub struct BridgeHubWestend;
+
+impl FuzzRuntime for BridgeHubWestend {
+	type Runtime = Runtime;
+	type XcmConfig = XcmConfig;
+
+	fn new_ext() -> sp_io::TestExternalities {
+		let accounts = Self::beneficiaries()
+			.into_iter()
+			.chain(Self::origins())
+			.filter_map(|location| LocationToAccountId::convert_location(&location))
+			.map(|who| (who, INITIAL_BALANCE))
+			.collect();
+		ExtBuilder::<Runtime>::default()
+			.with_balances(accounts)
+			.with_safe_xcm_version(XCM_VERSION)
+			.with_para_id(1002.into())
+			.build()
+	}
+
+	fn origins() -> Vec<Location> {
+		vec![
+			// Relay chain.
+			Location::parent(),
+			// Asset Hub.
+			Location::new(1, [Parachain(1000)]),
+			// Non-system sibling parachain.
+			Location::new(1, [Parachain(2000)]),
+			// Local account.
+			Location::new(0, [AccountId32 { network: None, id: [0; 32] }]),
+		]
+	}
+
+	fn assets() -> Vec<AssetId> {
+		vec![
+			AssetId(Location::parent()),
+			// Unknown assets.
+			AssetId(Location::new(1, [Parachain(1000), PalletInstance(50), GeneralIndex(1)])),
+			AssetId(Location::new(2, [GlobalConsensus(Rococo)])),
+		]
+	}
+
+	fn beneficiaries() -> Vec<Location> {
+		(0..3u8).map(|i| AccountId32 { network: None, id: [i; 32] }.into()).collect()
+	}
+
+	fn destinations() -> Vec<Location> {
+		vec![Location::parent(), Location::new(1, [Parachain(1000)])]
+	}
+
+	fn total_issuance(asset: &AssetId) -> Option<u128> {
+		(asset.0 == Location::parent())
+			.then(|| <Balances as fungible::Inspect<_>>::total_issuance())
+	}
+
+	fn xcm_events() -> Vec<pallet_xcm::Event<Runtime>> {
+		frame_system::Pallet::<Runtime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::PolkadotXcm(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,429 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured fuzzing of the XCM configuration of system parachain runtimes.
+//!
+//! Programs are built out of the assets, origins and destinations a runtime knows about (see
+//! [`FuzzRuntime`]) and executed by the runtime's own `XcmExecutor`, barriers and asset
+//! transactors included. After every message the following invariants are checked:
+//!
+//! - the total issuance of tracked assets, together with the assets trapped so far, only grows by
+//!   the assets received from trusted reserves and teleporters;
+//! - every trapped asset is accounted for by `pallet-xcm` and claims never exceed what was trapped.
+
+use arbitrary::Arbitrary;
+use codec::{DecodeLimit, Encode};
+use frame_support::traits::ContainsPair;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::collections::BTreeMap;
+use xcm::{latest::prelude::*, VersionedAssets, MAX_XCM_DECODE_DEPTH};
+use xcm_executor::XcmExecutor;
+
+mod asset_hub_westend;
+mod bridge_hub_westend;
+
+pub use asset_hub_westend::AssetHubWestend;
+pub use bridge_hub_westend::BridgeHubWestend;
+
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of messages executed for a single input.
+pub const MAX_MESSAGES: usize = 8;
+
+/// A runtime whose XCM configuration is fuzzed.
+pub trait FuzzRuntime {
+	/// The runtime.
+	type Runtime: frame_system::Config + pallet_xcm::Config;
+	/// The XCM configuration of the runtime.
+	type XcmConfig: xcm_executor::Config;
+
+	/// Build the state every input starts from.
+	fn new_ext() -> sp_io::TestExternalities;
+
+	/// Locations messages are received from.
+	fn origins() -> Vec<Location>;
+
+	/// Assets used in messages, including some which are unknown to the runtime.
+	fn assets() -> Vec<AssetId>;
+
+	/// Locations assets are deposited to.
+	fn beneficiaries() -> Vec<Location>;
+
+	/// Locations assets are sent to.
+	fn destinations() -> Vec<Location>;
+
+	/// The total issuance of `asset` on the chain, or `None` if the runtime doesn't track it.
+	fn total_issuance(asset: &AssetId) -> Option<u128>;
+
+	/// The `pallet-xcm` events emitted since the beginning of the block.
+	fn xcm_events() -> Vec<pallet_xcm::Event<Self::Runtime>>;
+}
+
+/// An input of the fuzzer: messages executed one after the other.
+#[derive(Arbitrary, Debug)]
+pub struct FuzzInput {
+	messages: Vec<FuzzMessage>,
+}
+
+/// A message received from one of the origins of the runtime.
+#[derive(Arbitrary, Debug)]
+pub struct FuzzMessage {
+	origin: u8,
+	instructions: Vec<FuzzInstruction>,
+}
+
+/// An amount of one of the assets of the runtime.
+#[derive(Arbitrary, Debug)]
+pub struct FuzzAsset {
+	asset: u8,
+	amount: u64,
+}
+
+/// The building blocks of fuzzed programs.
+///
+/// Locations and assets are indices into the ones of the runtime, so that programs go past the
+/// barriers and asset matchers most of the time.
+#[derive(Arbitrary, Debug)]
+pub enum FuzzInstruction {
+	WithdrawAsset(FuzzAsset),
+	ReserveAssetDeposited(FuzzAsset),
+	ReceiveTeleportedAsset(FuzzAsset),
+	ClaimAsset(FuzzAsset),
+	BuyExecution(FuzzAsset),
+	PayFees(FuzzAsset),
+	ClearOrigin,
+	DescendOrigin {
+		beneficiary: u8,
+	},
+	DepositAsset {
+		beneficiary: u8,
+	},
+	TransferAsset {
+		asset: FuzzAsset,
+		beneficiary: u8,
+	},
+	DepositReserveAsset {
+		destination: u8,
+	},
+	InitiateReserveWithdraw {
+		destination: u8,
+	},
+	InitiateTeleport {
+		destination: u8,
+	},
+	ExchangeAsset {
+		give: FuzzAsset,
+		want: FuzzAsset,
+		maximal: bool,
+	},
+	BurnAsset(FuzzAsset),
+	RefundSurplus,
+	SetErrorHandler(Vec<FuzzInstruction>),
+	SetAppendix(Vec<FuzzInstruction>),
+	/// Any SCALE-encoded instruction, except `Transact`.
+	Raw(Vec<u8>),
+}
+
+/// Pick the item at `index`, wrapping around.
+fn pick<T: Clone>(items: &[T], index: u8) -> T {
+	items[index as usize % items.len()].clone()
+}
+
+impl FuzzAsset {
+	fn build<R: FuzzRuntime>(&self) -> Asset {
+		(pick(&R::assets(), self.asset), self.amount as u128).into()
+	}
+}
+
+impl FuzzInstruction {
+	/// Build the instruction for `R`, if it's allowed.
+	fn build<R: FuzzRuntime>(&self) -> Option<Instruction<()>> {
+		Some(match self {
+			Self::WithdrawAsset(asset) => WithdrawAsset(asset.build::<R>().into()),
+			Self::ReserveAssetDeposited(asset) => ReserveAssetDeposited(asset.build::<R>().into()),
+			Self::ReceiveTeleportedAsset(asset) =>
+				ReceiveTeleportedAsset(asset.build::<R>().into()),
+			Self::ClaimAsset(asset) =>
+				ClaimAsset { assets: asset.build::<R>().into(), ticket: Here.into() },
+			Self::BuyExecution(asset) =>
+				BuyExecution { fees: asset.build::<R>(), weight_limit: Unlimited },
+			Self::PayFees(asset) => PayFees { asset: asset.build::<R>() },
+			Self::ClearOrigin => ClearOrigin,
+			Self::DescendOrigin { beneficiary } =>
+				DescendOrigin(pick(&R::beneficiaries(), *beneficiary).interior().clone()),
+			Self::DepositAsset { beneficiary } => DepositAsset {
+				assets: AllCounted(u32::MAX).into(),
+				beneficiary: pick(&R::beneficiaries(), *beneficiary),
+			},
+			Self::TransferAsset { asset, beneficiary } => TransferAsset {
+				assets: asset.build::<R>().into(),
+				beneficiary: pick(&R::beneficiaries(), *beneficiary),
+			},
+			Self::DepositReserveAsset { destination } => DepositReserveAsset {
+				assets: AllCounted(u32::MAX).into(),
+				dest: pick(&R::destinations(), *destination),
+				xcm: Xcm(vec![ClearOrigin]),
+			},
+			Self::InitiateReserveWithdraw { destination } => InitiateReserveWithdraw {
+				assets: AllCounted(u32::MAX).into(),
+				reserve: pick(&R::destinations(), *destination),
+				xcm: Xcm(vec![ClearOrigin]),
+			},
+			Self::InitiateTeleport { destination } => InitiateTeleport {
+				assets: AllCounted(u32::MAX).into(),
+				dest: pick(&R::destinations(), *destination),
+				xcm: Xcm(vec![ClearOrigin]),
+			},
+			Self::ExchangeAsset { give, want, maximal } => ExchangeAsset {
+				give: Definite(give.build::<R>().into()),
+				want: want.build::<R>().into(),
+				maximal: *maximal,
+			},
+			Self::BurnAsset(asset) => BurnAsset(asset.build::<R>().into()),
+			Self::RefundSurplus => RefundSurplus,
+			Self::SetErrorHandler(instructions) =>
+				SetErrorHandler(build_program::<R>(instructions)),
+			Self::SetAppendix(instructions) => SetAppendix(build_program::<R>(instructions)),
+			Self::Raw(encoded) => {
+				let instruction = Instruction::<()>::decode_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &encoded[..],
+				)
+				.ok()?;
+				// `Transact` may dispatch privileged calls which legitimately mint assets.
+				if executes_any(&instruction, &|instruction| matches!(instruction, Transact { .. }))
+				{
+					return None
+				}
+				instruction
+			},
+		})
+	}
+}
+
+fn build_program<R: FuzzRuntime>(instructions: &[FuzzInstruction]) -> Xcm<()> {
+	Xcm(instructions.iter().filter_map(FuzzInstruction::build::<R>).collect())
+}
+
+/// Whether `instruction`, or any instruction of the programs it executes locally, matches
+/// `predicate`.
+fn executes_any(
+	instruction: &Instruction<()>,
+	predicate: &impl Fn(&Instruction<()>) -> bool,
+) -> bool {
+	predicate(instruction) ||
+		match instruction {
+			SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } =>
+				xcm.iter().any(|instruction| executes_any(instruction, predicate)),
+			_ => false,
+		}
+}
+
+/// The origin of a program as it gets executed.
+enum TrackedOrigin {
+	Known(Location),
+	Cleared,
+	/// The origin was changed in a way which isn't tracked.
+	Unknown,
+}
+
+/// Upper bound on the amount of every asset `message` can bring into existence when executed
+/// from `origin`, i.e. the assets received from trusted reserves and teleporters.
+///
+/// Returns `None` if it can't be determined.
+fn minting_allowance<R: FuzzRuntime>(
+	origin: &Location,
+	message: &Xcm<()>,
+) -> Option<BTreeMap<AssetId, u128>> {
+	use xcm_executor::Config;
+
+	let mut allowance = BTreeMap::new();
+	let mut origin = TrackedOrigin::Known(origin.clone());
+	for instruction in message.iter() {
+		let (assets, trusted): (_, fn(&Asset, &Location) -> bool) = match instruction {
+			ReserveAssetDeposited(assets) =>
+				(assets, <R::XcmConfig as Config>::IsReserve::contains),
+			ReceiveTeleportedAsset(assets) =>
+				(assets, <R::XcmConfig as Config>::IsTeleporter::contains),
+			ClearOrigin => {
+				origin = TrackedOrigin::Cleared;
+				continue
+			},
+			DescendOrigin(interior) => {
+				if let TrackedOrigin::Known(location) = &mut origin {
+					if location.append_with(interior.clone()).is_err() {
+						origin = TrackedOrigin::Cleared;
+					}
+				}
+				continue
+			},
+			AliasOrigin(_) | UniversalOrigin(_) => {
+				origin = TrackedOrigin::Unknown;
+				continue
+			},
+			// Nested programs are executed with whatever origin is current by then.
+			SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } => {
+				let mints = |instruction: &Instruction<()>| {
+					matches!(instruction, ReserveAssetDeposited(_) | ReceiveTeleportedAsset(_))
+				};
+				if xcm.iter().any(|instruction| executes_any(instruction, &mints)) {
+					return None
+				}
+				continue
+			},
+			_ => continue,
+		};
+		for asset in assets.inner() {
+			let trusted = match &origin {
+				TrackedOrigin::Known(location) => trusted(asset, location),
+				TrackedOrigin::Cleared => false,
+				TrackedOrigin::Unknown => return None,
+			};
+			if let (true, Fungible(amount)) = (trusted, &asset.fun) {
+				let entry = allowance.entry(asset.id.clone()).or_insert(0u128);
+				*entry = entry.saturating_add(*amount);
+			}
+		}
+	}
+	Some(allowance)
+}
+
+/// Assets trapped by the executor which weren't claimed yet.
+#[derive(Default)]
+struct TrappedAssets(BTreeMap<AssetId, u128>);
+
+impl TrappedAssets {
+	fn get(&self, asset: &AssetId) -> u128 {
+		self.0.get(asset).copied().unwrap_or_default()
+	}
+
+	fn fungibles(assets: &VersionedAssets) -> Vec<(AssetId, u128)> {
+		let assets =
+			Assets::try_from(assets.clone()).expect("trapped assets are in the latest version");
+		assets
+			.into_inner()
+			.into_iter()
+			.filter_map(|asset| match asset.fun {
+				Fungible(amount) => Some((asset.id, amount)),
+				NonFungible(_) => None,
+			})
+			.collect()
+	}
+
+	fn trap(&mut self, assets: &VersionedAssets) {
+		for (id, amount) in Self::fungibles(assets) {
+			let entry = self.0.entry(id).or_default();
+			*entry = entry.saturating_add(amount);
+		}
+	}
+
+	fn claim(&mut self, assets: &VersionedAssets) {
+		for (id, amount) in Self::fungibles(assets) {
+			let trapped = self.0.entry(id.clone()).or_default();
+			assert!(
+				*trapped >= amount,
+				"claimed {amount} of {id:?} while only {trapped} were trapped"
+			);
+			*trapped -= amount;
+		}
+	}
+}
+
+/// Execute `input` against `R`, checking the invariants after every message.
+///
+/// Returns the outcome of every executed message. Panics if any invariant is broken.
+pub fn run<R: FuzzRuntime>(input: FuzzInput) -> Vec<Outcome> {
+	let origins = R::origins();
+	let assets = R::assets();
+	let mut trapped = TrappedAssets::default();
+	let mut outcomes = Vec::new();
+
+	R::new_ext().execute_with(|| {
+		for message in input.messages.iter().take(MAX_MESSAGES) {
+			let origin = pick(&origins, message.origin);
+			let xcm = build_program::<R>(&message.instructions);
+			let allowance = minting_allowance::<R>(&origin, &xcm);
+			let issuance_before: Vec<_> = assets.iter().map(R::total_issuance).collect();
+			let trapped_before: Vec<_> = assets.iter().map(|asset| trapped.get(asset)).collect();
+			frame_system::Pallet::<R::Runtime>::reset_events();
+
+			let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+			let outcome = XcmExecutor::<R::XcmConfig>::prepare_and_execute(
+				origin.clone(),
+				Xcm::from(xcm.clone()),
+				&mut hash,
+				Weight::MAX,
+				Weight::zero(),
+			);
+			log::trace!(target: "xcm::fuzzer", "{origin:?} executed {xcm:?}: {outcome:?}");
+			outcomes.push(outcome);
+
+			check_trapped_assets::<R>(&mut trapped);
+			let Some(allowance) = allowance else { continue };
+			for (index, asset) in assets.iter().enumerate() {
+				let (Some(before), Some(after)) =
+					(issuance_before[index], R::total_issuance(asset))
+				else {
+					continue
+				};
+				let allowed = allowance.get(asset).copied().unwrap_or_default();
+				assert!(
+					after.saturating_add(trapped.get(asset)) <=
+						before.saturating_add(trapped_before[index]).saturating_add(allowed),
+					"{asset:?} was created out of thin air by {xcm:?} from {origin:?}: issuance went \
+					from {before} to {after} with {allowed} allowed",
+				);
+			}
+		}
+	});
+	outcomes
+}
+
+/// Check the assets trapped and claimed by the last message against `pallet-xcm`, and record them
+/// in `trapped`.
+fn check_trapped_assets<R: FuzzRuntime>(trapped: &mut TrappedAssets) {
+	let mut traps = BTreeMap::new();
+	for event in R::xcm_events() {
+		match event {
+			pallet_xcm::Event::AssetsTrapped { hash, origin, assets } => {
+				assert_eq!(hash, BlakeTwo256::hash_of(&(&origin, &assets)), "wrong trap hash");
+				*traps.entry(hash).or_insert(0u32) += 1;
+				trapped.trap(&assets);
+			},
+			pallet_xcm::Event::AssetsClaimed { hash, origin, assets } => {
+				assert_eq!(hash, BlakeTwo256::hash_of(&(&origin, &assets)), "wrong claim hash");
+				trapped.claim(&assets);
+			},
+			_ => {},
+		}
+	}
+	for (hash, count) in traps {
+		assert!(
+			pallet_xcm::Pallet::<R::Runtime>::asset_trap(hash) >= count,
+			"trapped assets {hash:?} aren't recorded",
+		);
+	}
+}
+
+/// Decode `data` into a [`FuzzInput`] and run it against `R`.
+///
+/// Meant to be used as the body of fuzz targets.
+pub fn fuzz<R: FuzzRuntime>(data: &[u8]) {
+	let mut unstructured = arbitrary::Unstructured::new(data);
+	if let Ok(input) = FuzzInput::arbitrary(&mut unstructured) {
+		run::<R>(input);
+	}
+}
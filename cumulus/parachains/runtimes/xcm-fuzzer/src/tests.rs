@@ -0,0 +1,130 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+
+fn wnd(amount: u128) -> Asset {
+	(Parent, amount).into()
+}
+
+fn message(origin: u8, instructions: Vec<FuzzInstruction>) -> FuzzMessage {
+	FuzzMessage { origin, instructions }
+}
+
+fn asset(asset: u8, amount: u64) -> FuzzAsset {
+	FuzzAsset { asset, amount }
+}
+
+#[test]
+fn allowance_only_counts_trusted_origins() {
+	let teleport = Xcm(vec![ReceiveTeleportedAsset(wnd(100).into())]);
+	let relay = Location::parent();
+	let sibling = Location::new(1, [Parachain(2000)]);
+
+	// The relay chain is a trusted teleporter of its token.
+	let allowance = minting_allowance::<AssetHubWestend>(&relay, &teleport).unwrap();
+	assert_eq!(allowance.get(&AssetId(Location::parent())), Some(&100));
+	// A non-system parachain isn't.
+	let allowance = minting_allowance::<AssetHubWestend>(&sibling, &teleport).unwrap();
+	assert!(allowance.is_empty());
+	// Neither is a cleared origin.
+	let cleared = Xcm(vec![ClearOrigin, ReceiveTeleportedAsset(wnd(100).into())]);
+	let allowance = minting_allowance::<AssetHubWestend>(&relay, &cleared).unwrap();
+	assert!(allowance.is_empty());
+	// Untracked origins can't be reasoned about.
+	let aliased = Xcm(vec![AliasOrigin(sibling), ReceiveTeleportedAsset(wnd(100).into())]);
+	assert!(minting_allowance::<AssetHubWestend>(&relay, &aliased).is_none());
+}
+
+#[test]
+fn asset_hub_westend_teleports_and_traps() {
+	let outcomes = run::<AssetHubWestend>(FuzzInput {
+		messages: vec![
+			// Teleport from the relay chain, deposited.
+			message(
+				0,
+				vec![
+					FuzzInstruction::ReceiveTeleportedAsset(asset(0, 10_000_000_000)),
+					FuzzInstruction::BuyExecution(asset(0, 10_000_000_000)),
+					FuzzInstruction::DepositAsset { beneficiary: 1 },
+				],
+			),
+			// Teleport from a non-system parachain, rejected.
+			message(
+				2,
+				vec![
+					FuzzInstruction::ReceiveTeleportedAsset(asset(0, 10_000_000_000)),
+					FuzzInstruction::BuyExecution(asset(0, 10_000_000_000)),
+					FuzzInstruction::DepositAsset { beneficiary: 1 },
+				],
+			),
+			// Withdrawn assets which are never deposited get trapped.
+			message(
+				4,
+				vec![
+					FuzzInstruction::WithdrawAsset(asset(1, 1_000_000)),
+					FuzzInstruction::WithdrawAsset(asset(0, 10_000_000_000)),
+					FuzzInstruction::BuyExecution(asset(0, 10_000_000_000)),
+				],
+			),
+		],
+	});
+	assert_eq!(outcomes.len(), 3);
+	assert_eq!(outcomes[0].clone().ensure_complete(), Ok(()));
+	assert_eq!(outcomes[1].clone().ensure_complete(), Err(XcmError::UntrustedTeleportLocation));
+}
+
+#[test]
+fn bridge_hub_westend_teleports_and_traps() {
+	run::<BridgeHubWestend>(FuzzInput {
+		messages: vec![
+			message(
+				1,
+				vec![
+					FuzzInstruction::ReceiveTeleportedAsset(asset(0, 10_000_000_000)),
+					FuzzInstruction::BuyExecution(asset(0, 10_000_000_000)),
+					FuzzInstruction::SetAppendix(vec![FuzzInstruction::DepositAsset {
+						beneficiary: 0,
+					}]),
+					FuzzInstruction::InitiateTeleport { destination: 1 },
+				],
+			),
+			message(
+				3,
+				vec![
+					FuzzInstruction::WithdrawAsset(asset(0, 10_000_000_000)),
+					FuzzInstruction::BuyExecution(asset(0, 10_000_000_000)),
+					FuzzInstruction::BurnAsset(asset(0, 1_000)),
+				],
+			),
+		],
+	});
+}
+
+#[test]
+fn arbitrary_inputs_keep_invariants() {
+	// A few deterministic pseudo-random inputs, to make sure fuzz targets run.
+	let mut seed = 42u64;
+	for _ in 0..8 {
+		let data: Vec<u8> = (0..512)
+			.map(|_| {
+				seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+				(seed >> 56) as u8
+			})
+			.collect();
+		fuzz::<AssetHubWestend>(&data);
+		fuzz::<BridgeHubWestend>(&data);
+	}
+}
@@ -46,7 +46,7 @@ impl<Block: BlockT, RuntimeApi>
 	BuildRpcExtensions<
 		ParachainClient<Block, RuntimeApi>,
 		ParachainBackend<Block>,
-		sc_transaction_pool::FullPool<Block, ParachainClient<Block, RuntimeApi>>,
+		sc_transaction_pool::TransactionPoolWrapper<Block, ParachainClient<Block, RuntimeApi>>,
 	> for BuildEmptyRpcExtensions<Block, RuntimeApi>
 where
 	RuntimeApi:
@@ -55,7 +55,9 @@ where
 	fn build_rpc_extensions(
 		_client: Arc<ParachainClient<Block, RuntimeApi>>,
 		_backend: Arc<ParachainBackend<Block>>,
-		_pool: Arc<sc_transaction_pool::FullPool<Block, ParachainClient<Block, RuntimeApi>>>,
+		_pool: Arc<
+			sc_transaction_pool::TransactionPoolWrapper<Block, ParachainClient<Block, RuntimeApi>>,
+		>,
 	) -> sc_service::error::Result<RpcExtension> {
 		Ok(RpcExtension::new(()))
 	}
@@ -67,7 +69,7 @@ impl<Block: BlockT, RuntimeApi>
 	BuildRpcExtensions<
 		ParachainClient<Block, RuntimeApi>,
 		ParachainBackend<Block>,
-		sc_transaction_pool::FullPool<Block, ParachainClient<Block, RuntimeApi>>,
+		sc_transaction_pool::TransactionPoolWrapper<Block, ParachainClient<Block, RuntimeApi>>,
 	> for BuildParachainRpcExtensions<Block, RuntimeApi>
 where
 	RuntimeApi:
@@ -78,7 +80,9 @@ where
 	fn build_rpc_extensions(
 		client: Arc<ParachainClient<Block, RuntimeApi>>,
 		backend: Arc<ParachainBackend<Block>>,
-		pool: Arc<sc_transaction_pool::FullPool<Block, ParachainClient<Block, RuntimeApi>>>,
+		pool: Arc<
+			sc_transaction_pool::TransactionPoolWrapper<Block, ParachainClient<Block, RuntimeApi>>,
+		>,
 	) -> sc_service::error::Result<RpcExtension> {
 		let build = || -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>> {
 			let mut module = RpcExtension::new(());
@@ -24,18 +24,23 @@ pub use polkadot_node_primitives::approval::criteria::{
 use polkadot_node_primitives::approval::{
 	self as approval_types,
 	v1::{AssignmentCert, AssignmentCertKind, DelayTranche, RelayVRFStory},
-	v2::{
-		AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, VrfPreOutput, VrfProof, VrfSignature,
-	},
+	v2::{AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, VrfSignature},
 };
 
 use polkadot_primitives::{
-	AssignmentPair, CandidateHash, CoreIndex, GroupIndex, IndexedVec, ValidatorIndex,
+	AssignmentId, CandidateHash, CoreIndex, GroupIndex, IndexedVec, ValidatorIndex,
+	ASSIGNMENT_KEY_TYPE_ID,
 };
 use rand::{seq::SliceRandom, SeedableRng};
 use rand_chacha::ChaCha20Rng;
-use sc_keystore::LocalKeystore;
-use sp_application_crypto::ByteArray;
+use sp_application_crypto::{
+	sr25519::{
+		self,
+		vrf::{VrfSignData, VrfTranscript},
+	},
+	ByteArray,
+};
+use sp_keystore::Keystore;
 
 use merlin::Transcript;
 use schnorrkel::vrf::VRFInOut;
@@ -189,12 +194,69 @@ fn assigned_core_transcript(core_index: CoreIndex) -> Transcript {
 	t
 }
 
+/// The assignment key of the local validator, held by a keystore.
+struct AssignmentKey<'a> {
+	keystore: &'a dyn Keystore,
+	public: sr25519::Public,
+	schnorrkel_public: schnorrkel::PublicKey,
+}
+
+impl<'a> AssignmentKey<'a> {
+	fn new(keystore: &'a dyn Keystore, public: &AssignmentId) -> Option<Self> {
+		let public: &sr25519::Public = public.as_ref();
+		let schnorrkel_public = schnorrkel::PublicKey::from_bytes(public.as_slice()).ok()?;
+		Some(AssignmentKey { keystore, public: *public, schnorrkel_public })
+	}
+
+	/// Sign `transcript` with extra data if `check` returns some, given the VRF output.
+	///
+	/// Mirrors `schnorrkel::Keypair::vrf_sign_extra_after_check`, with the secret key in the
+	/// keystore.
+	fn vrf_sign_extra_after_check(
+		&self,
+		transcript: Transcript,
+		check: impl FnOnce(&VRFInOut) -> Option<Transcript>,
+	) -> Option<(VRFInOut, VrfSignature)> {
+		let input = VrfTranscript(transcript);
+		let pre_output = self
+			.keystore
+			.sr25519_vrf_pre_output(ASSIGNMENT_KEY_TYPE_ID, &self.public, &input)
+			.map_err(|e| gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e))
+			.ok()
+			.flatten()?;
+		let vrf_in_out =
+			pre_output.0.attach_input_hash(&self.schnorrkel_public, input.0.clone()).ok()?;
+		let extra = check(&vrf_in_out)?;
+		let signature = self.sign(VrfSignData::new(input).with_extra(VrfTranscript(extra)))?;
+		Some((vrf_in_out, signature))
+	}
+
+	/// Sign `transcript`.
+	fn vrf_sign(&self, transcript: Transcript) -> Option<(VRFInOut, VrfSignature)> {
+		let signature = self.sign(VrfSignData::new(VrfTranscript(transcript.clone())))?;
+		let vrf_in_out = signature
+			.pre_output
+			.0
+			.attach_input_hash(&self.schnorrkel_public, transcript)
+			.ok()?;
+		Some((vrf_in_out, signature))
+	}
+
+	fn sign(&self, data: VrfSignData) -> Option<VrfSignature> {
+		self.keystore
+			.sr25519_vrf_sign(ASSIGNMENT_KEY_TYPE_ID, &self.public, &data)
+			.map_err(|e| gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e))
+			.ok()
+			.flatten()
+	}
+}
+
 pub struct RealAssignmentCriteria;
 
 impl AssignmentCriteria for RealAssignmentCriteria {
 	fn compute_assignments(
 		&self,
-		keystore: &LocalKeystore,
+		keystore: &dyn Keystore,
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
@@ -235,7 +297,7 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 ///
 /// This will not assign to anything the local validator was part of the backing group for.
 pub fn compute_assignments(
-	keystore: &LocalKeystore,
+	keystore: &dyn Keystore,
 	relay_vrf_story: RelayVRFStory,
 	config: &Config,
 	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex, GroupIndex)> + Clone,
@@ -256,15 +318,15 @@ pub fn compute_assignments(
 		return HashMap::new()
 	}
 
-	let (index, assignments_key): (ValidatorIndex, AssignmentPair) = {
+	let (index, assignments_key) = {
 		let key = config.assignment_keys.iter().enumerate().find_map(|(i, p)| {
-			match keystore.key_pair(p) {
-				Ok(Some(pair)) => Some((ValidatorIndex(i as _), pair)),
-				Ok(None) => None,
-				Err(sc_keystore::Error::Unavailable) => None,
-				Err(sc_keystore::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => None,
-				Err(e) => {
-					gum::warn!(target: LOG_TARGET, "Encountered keystore error: {:?}", e);
+			if !keystore.has_keys(&[(p.to_raw_vec(), ASSIGNMENT_KEY_TYPE_ID)]) {
+				return None
+			}
+			match AssignmentKey::new(keystore, p) {
+				Some(key) => Some((ValidatorIndex(i as _), key)),
+				None => {
+					gum::warn!(target: LOG_TARGET, "Invalid assignment key: {:?}", p);
 					None
 				},
 			}
@@ -292,9 +354,6 @@ pub fn compute_assignments(
 		"Assigning to candidates from different backing groups"
 	);
 
-	let assignments_key: &sp_application_crypto::sr25519::Pair = assignments_key.as_ref();
-	let assignments_key: &schnorrkel::Keypair = assignments_key.as_ref();
-
 	let mut assignments = HashMap::new();
 
 	// First run `RelayVRFModulo` for each sample.
@@ -332,7 +391,7 @@ pub fn compute_assignments(
 }
 
 fn compute_relay_vrf_modulo_assignments_v1(
-	assignments_key: &schnorrkel::Keypair,
+	assignments_key: &AssignmentKey,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -370,15 +429,12 @@ fn compute_relay_vrf_modulo_assignments_v1(
 			)
 		};
 
-		if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
+		if let Some((_, vrf)) = maybe_assignment {
 			// Sanity: `core` is always initialized to non-default here, as the closure above
 			// has been executed.
 			let cert = AssignmentCert {
 				kind: AssignmentCertKind::RelayVRFModulo { sample: rvm_sample },
-				vrf: VrfSignature {
-					pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-					proof: VrfProof(vrf_proof),
-				},
+				vrf,
 			};
 
 			// All assignments of type RelayVRFModulo have tranche 0.
@@ -399,7 +455,7 @@ fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> Transcript {
 }
 
 fn compute_relay_vrf_modulo_assignments_v2(
-	assignments_key: &schnorrkel::Keypair,
+	assignments_key: &AssignmentKey,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -445,7 +501,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 		)
 	};
 
-	if let Some(assignment) = maybe_assignment.map(|(vrf_in_out, vrf_proof, _)| {
+	if let Some(assignment) = maybe_assignment.map(|(_, vrf)| {
 		let assignment_bitfield: CoreBitfield = assigned_cores
 			.clone()
 			.try_into()
@@ -455,10 +511,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 			kind: AssignmentCertKindV2::RelayVRFModuloCompact {
 				core_bitfield: assignment_bitfield.clone(),
 			},
-			vrf: VrfSignature {
-				pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-				proof: VrfProof(vrf_proof),
-			},
+			vrf,
 		};
 
 		// All assignments of type RelayVRFModulo have tranche 0.
@@ -471,7 +524,7 @@ fn compute_relay_vrf_modulo_assignments_v2(
 }
 
 fn compute_relay_vrf_delay_assignments(
-	assignments_key: &schnorrkel::Keypair,
+	assignments_key: &AssignmentKey,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
@@ -479,8 +532,11 @@ fn compute_relay_vrf_delay_assignments(
 	assignments: &mut HashMap<CoreIndex, OurAssignment>,
 ) {
 	for (candidate_hash, core) in leaving_cores {
-		let (vrf_in_out, vrf_proof, _) =
-			assignments_key.vrf_sign(relay_vrf_delay_transcript(relay_vrf_story.clone(), core));
+		let Some((vrf_in_out, vrf)) =
+			assignments_key.vrf_sign(relay_vrf_delay_transcript(relay_vrf_story.clone(), core))
+		else {
+			continue
+		};
 
 		let tranche = relay_vrf_delay_tranche(
 			&vrf_in_out,
@@ -490,10 +546,7 @@ fn compute_relay_vrf_delay_assignments(
 
 		let cert = AssignmentCertV2 {
 			kind: AssignmentCertKindV2::RelayVRFDelay { core_index: core },
-			vrf: VrfSignature {
-				pre_output: VrfPreOutput(vrf_in_out.to_preout()),
-				proof: VrfProof(vrf_proof),
-			},
+			vrf,
 		};
 
 		let our_assignment = OurAssignment::new(cert, tranche, validator_index, false);
@@ -718,11 +771,10 @@ impl From<crate::approval_db::v1::OurAssignment> for OurAssignment {
 mod tests {
 	use super::*;
 	use crate::import::tests::garbage_vrf_signature;
-	use polkadot_primitives::{AssignmentId, Hash, ASSIGNMENT_KEY_TYPE_ID};
-	use sp_application_crypto::sr25519;
+	use polkadot_primitives::Hash;
+	use sc_keystore::LocalKeystore;
 	use sp_core::crypto::Pair as PairT;
 	use sp_keyring::sr25519::Keyring as Sr25519Keyring;
-	use sp_keystore::Keystore;
 
 	// sets up a keystore with the given keyring accounts.
 	fn make_keystore(accounts: &[Sr25519Keyring]) -> LocalKeystore {
@@ -49,8 +49,8 @@ use polkadot_primitives::{
 	node_features, BlockNumber, CandidateEvent, CandidateHash, CandidateReceipt, ConsensusLog,
 	CoreIndex, GroupIndex, Hash, Header, SessionIndex,
 };
-use sc_keystore::LocalKeystore;
 use sp_consensus_slots::Slot;
+use sp_keystore::Keystore;
 
 use bitvec::order::Lsb0 as BitOrderLsb0;
 use futures::{channel::oneshot, prelude::*};
@@ -83,7 +83,7 @@ struct ImportedBlockInfo {
 struct ImportedBlockInfoEnv<'a> {
 	runtime_info: &'a mut RuntimeInfo,
 	assignment_criteria: &'a (dyn AssignmentCriteria + Send + Sync),
-	keystore: &'a LocalKeystore,
+	keystore: &'a dyn Keystore,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -248,7 +248,7 @@ async fn imported_block_info<Sender: SubsystemSender<RuntimeApiMessage>>(
 				) {
 					Ok(relay_vrf) => {
 						let assignments = env.assignment_criteria.compute_assignments(
-							&env.keystore,
+							env.keystore,
 							relay_vrf.clone(),
 							&crate::criteria::Config::from(session_info),
 							included_candidates
@@ -639,6 +639,7 @@ pub(crate) mod tests {
 		SessionInfo, ValidatorId, ValidatorIndex,
 	};
 	use polkadot_primitives_test_helpers::{dummy_candidate_receipt, dummy_hash};
+	use sc_keystore::LocalKeystore;
 	use schnellru::{ByLength, LruMap};
 	pub(crate) use sp_consensus_babe::{
 		digests::{CompatibleDigestItem, PreDigest, SecondaryVRFPreDigest},
@@ -701,7 +702,7 @@ pub(crate) mod tests {
 	impl AssignmentCriteria for MockAssignmentCriteria {
 		fn compute_assignments(
 			&self,
-			_keystore: &LocalKeystore,
+			_keystore: &dyn Keystore,
 			_relay_vrf_story: polkadot_node_primitives::approval::v1::RelayVRFStory,
 			_config: &criteria::Config,
 			_leaving_cores: Vec<(
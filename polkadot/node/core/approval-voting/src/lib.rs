@@ -57,10 +57,9 @@ use polkadot_node_subsystem_util::{
 use polkadot_primitives::{
 	ApprovalVoteMultipleCandidates, ApprovalVotingParams, BlockNumber, CandidateHash,
 	CandidateIndex, CandidateReceipt, CoreIndex, ExecutorParams, GroupIndex, Hash, PvfExecKind,
-	SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
+	SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
-use sc_keystore::LocalKeystore;
-use sp_application_crypto::Pair;
+use sp_keystore::KeystorePtr;
 use sp_consensus::SyncOracle;
 use sp_consensus_slots::Slot;
 use std::time::Instant;
@@ -156,10 +155,8 @@ enum Mode {
 
 /// The approval voting subsystem.
 pub struct ApprovalVotingSubsystem {
-	/// `LocalKeystore` is needed for assignment keys, but not necessarily approval keys.
-	///
-	/// We do a lot of VRF signing and need the keys to have low latency.
-	keystore: Arc<LocalKeystore>,
+	/// Holds the assignment keys, used for a lot of VRF signing, and the approval keys.
+	keystore: KeystorePtr,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
 	db: Arc<dyn Database>,
@@ -482,7 +479,7 @@ impl ApprovalVotingSubsystem {
 	pub fn with_config(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		spawner: Arc<dyn overseer::gen::Spawner + 'static>,
@@ -502,7 +499,7 @@ impl ApprovalVotingSubsystem {
 	pub fn with_config_and_clock(
 		config: Config,
 		db: Arc<dyn Database>,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		clock: Arc<dyn Clock + Send + Sync>,
@@ -837,7 +834,7 @@ where
 }
 
 struct State {
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	slot_duration_millis: u64,
 	clock: Arc<dyn Clock + Send + Sync>,
 	assignment_criteria: Box<dyn AssignmentCriteria + Send + Sync>,
@@ -1384,7 +1381,7 @@ pub async fn start_approval_worker<
 	to_approval_distr: ADSender,
 	config: Config,
 	db: Arc<dyn Database>,
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	metrics: Metrics,
 	spawner: Arc<dyn overseer::gen::Spawner + 'static>,
@@ -3971,16 +3968,14 @@ async fn maybe_create_signature<
 
 // Sign an approval vote. Fails if the key isn't present in the store.
 fn sign_approval(
-	keystore: &LocalKeystore,
+	keystore: &KeystorePtr,
 	public: &ValidatorId,
 	candidate_hashes: &[CandidateHash],
 	session_index: SessionIndex,
 ) -> Option<ValidatorSignature> {
-	let key = keystore.key_pair::<ValidatorPair>(public).ok().flatten()?;
-
 	let payload = ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session_index);
 
-	Some(key.sign(&payload[..]))
+	polkadot_node_subsystem_util::sign(keystore, public, &payload[..]).ok().flatten()
 }
 
 /// Send `IssueLocalStatement` to dispute-coordinator.
@@ -50,6 +50,7 @@ use std::{cmp::max, time::Duration};
 use assert_matches::assert_matches;
 use async_trait::async_trait;
 use parking_lot::Mutex;
+use sc_keystore::LocalKeystore;
 use sp_keyring::sr25519::Keyring as Sr25519Keyring;
 use sp_keystore::Keystore;
 use std::{
@@ -242,7 +243,7 @@ where
 {
 	fn compute_assignments(
 		&self,
-		_keystore: &LocalKeystore,
+		_keystore: &dyn Keystore,
 		_relay_vrf_story: polkadot_node_primitives::approval::v1::RelayVRFStory,
 		_config: &criteria::Config,
 		_leaving_cores: Vec<(
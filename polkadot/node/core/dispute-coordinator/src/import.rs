@@ -36,9 +36,10 @@ use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::{
 	CandidateHash, CandidateReceipt, DisputeStatement, ExecutorParams, Hash, IndexedVec,
 	SessionIndex, SessionInfo, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
-	ValidatorPair, ValidatorSignature,
+	ValidatorSignature,
 };
-use sc_keystore::LocalKeystore;
+use sp_application_crypto::{AppCrypto, ByteArray};
+use sp_keystore::{Keystore, KeystorePtr};
 
 use crate::LOG_TARGET;
 
@@ -63,7 +64,7 @@ impl<'a> CandidateEnvironment<'a> {
 	///
 	/// Return: `None` in case session is outside of session window.
 	pub async fn new<Context>(
-		keystore: &LocalKeystore,
+		keystore: &KeystorePtr,
 		ctx: &mut Context,
 		runtime_info: &'a mut RuntimeInfo,
 		session_index: SessionIndex,
@@ -637,12 +638,12 @@ impl ImportResult {
 ///
 /// That is all `ValidatorIndex`es we have private keys for. Usually this will only be one.
 fn find_controlled_validator_indices(
-	keystore: &LocalKeystore,
+	keystore: &KeystorePtr,
 	validators: &IndexedVec<ValidatorIndex, ValidatorId>,
 ) -> HashSet<ValidatorIndex> {
 	let mut controlled = HashSet::new();
 	for (index, validator) in validators.iter().enumerate() {
-		if keystore.key_pair::<ValidatorPair>(validator).ok().flatten().is_none() {
+		if !keystore.has_keys(&[(validator.to_raw_vec(), ValidatorId::ID)]) {
 			continue
 		}
 
@@ -16,17 +16,14 @@
 
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
-use std::{
-	collections::{BTreeMap, VecDeque},
-	sync::Arc,
-};
+use std::collections::{BTreeMap, VecDeque};
 
 use futures::{
 	channel::{mpsc, oneshot},
 	FutureExt, StreamExt,
 };
 
-use sc_keystore::LocalKeystore;
+use sp_keystore::KeystorePtr;
 
 use polkadot_node_primitives::{
 	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
@@ -91,7 +88,7 @@ pub struct InitialData {
 /// statements for validity, we cannot query orderings, we have no valid `SessionInfo`,
 /// ...
 pub(crate) struct Initialized {
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	runtime_info: RuntimeInfo,
 	/// We have the onchain state of disabled validators as well as the offchain
 	/// state that is based on the lost disputes.
@@ -1467,9 +1464,8 @@ impl Initialized {
 				continue
 			}
 
-			let keystore = self.keystore.clone() as Arc<_>;
 			let res = SignedDisputeStatement::sign_explicit(
-				&keystore,
+				&self.keystore,
 				valid,
 				candidate_hash,
 				session,
@@ -31,7 +31,7 @@ use error::FatalError;
 use futures::FutureExt;
 
 use gum::CandidateHash;
-use sc_keystore::LocalKeystore;
+use sp_keystore::KeystorePtr;
 
 use polkadot_node_primitives::{
 	CandidateVotes, DisputeMessage, DisputeMessageCheckError, SignedDisputeStatement,
@@ -120,7 +120,7 @@ pub(crate) const LOG_TARGET: &str = "parachain::dispute-coordinator";
 pub struct DisputeCoordinatorSubsystem {
 	config: Config,
 	store: Arc<dyn Database>,
-	keystore: Arc<LocalKeystore>,
+	keystore: KeystorePtr,
 	metrics: Metrics,
 }
 
@@ -162,7 +162,7 @@ impl DisputeCoordinatorSubsystem {
 	pub fn new(
 		store: Arc<dyn Database>,
 		config: Config,
-		keystore: Arc<LocalKeystore>,
+		keystore: KeystorePtr,
 		metrics: Metrics,
 	) -> Self {
 		Self { store, config, keystore, metrics }
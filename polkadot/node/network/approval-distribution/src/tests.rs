@@ -514,7 +514,7 @@ struct MockAssignmentCriteria {
 impl AssignmentCriteria for MockAssignmentCriteria {
 	fn compute_assignments(
 		&self,
-		_keystore: &LocalKeystore,
+		_keystore: &dyn Keystore,
 		_relay_vrf_story: polkadot_node_primitives::approval::v1::RelayVRFStory,
 		_config: &criteria::Config,
 		_leaving_cores: Vec<(
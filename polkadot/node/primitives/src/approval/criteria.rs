@@ -24,7 +24,7 @@ use codec::{Decode, Encode};
 use polkadot_primitives::{
 	AssignmentId, CandidateHash, CoreIndex, GroupIndex, IndexedVec, SessionInfo, ValidatorIndex,
 };
-use sc_keystore::LocalKeystore;
+use sp_keystore::Keystore;
 
 use std::collections::HashMap;
 
@@ -117,7 +117,7 @@ pub trait AssignmentCriteria {
 	/// Compute the assignments for the given relay VRF story.
 	fn compute_assignments(
 		&self,
-		keystore: &LocalKeystore,
+		keystore: &dyn Keystore,
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
@@ -485,7 +485,7 @@ fn new_partial<ChainSelection>(
 		FullBackend,
 		ChainSelection,
 		sc_consensus::DefaultImportQueue<Block>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		sc_transaction_pool::TransactionPoolWrapper<Block, FullClient>,
 		(
 			impl Fn(
 				polkadot_rpc::SubscriptionTaskExecutor,
@@ -513,7 +513,7 @@ fn new_partial<ChainSelection>(
 where
 	ChainSelection: 'static + SelectChain<Block>,
 {
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::TransactionPoolWrapper::new_full(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -803,7 +803,7 @@ pub fn new_full<
 
 	let chain_spec = config.chain_spec.cloned_box();
 
-	let keystore = basics.keystore_container.local_keystore();
+	let keystore = basics.keystore_container.keystore();
 	let auth_or_collator = role.is_authority() || is_parachain_node.is_collator();
 
 	let select_chain = if auth_or_collator {
@@ -41,8 +41,8 @@ use polkadot_overseer::{
 use parking_lot::Mutex;
 use sc_authority_discovery::Service as AuthorityDiscoveryService;
 use sc_client_api::AuxStore;
-use sc_keystore::LocalKeystore;
 use sc_network::{NetworkStateInfo, NotificationService};
+use sp_keystore::KeystorePtr;
 use std::{collections::HashMap, sync::Arc};
 
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
@@ -110,7 +110,7 @@ where
 
 pub struct ExtendedOverseerGenArgs {
 	/// The keystore to use for i.e. validator keys.
-	pub keystore: Arc<LocalKeystore>,
+	pub keystore: KeystorePtr,
 	/// The underlying key value store for the parachains.
 	pub parachains_db: Arc<dyn polkadot_node_subsystem_util::database::Database>,
 	/// Configuration for the candidate validation subsystem.
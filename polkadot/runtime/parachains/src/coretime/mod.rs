@@ -31,8 +31,7 @@ use polkadot_primitives::{Balance, BlockNumber, CoreIndex, Id as ParaId};
 use sp_arithmetic::traits::SaturatedConversion;
 use sp_runtime::traits::TryConvert;
 use xcm::{
-	prelude::{send_xcm, Instruction, Junction, Location, OriginKind, SendXcm, WeightLimit, Xcm},
-	v4::{
+	latest::{
 		Asset,
 		AssetFilter::Wild,
 		AssetId, Assets, Error as XcmError,
@@ -43,6 +42,7 @@ use xcm::{
 		WildAsset::AllCounted,
 		XcmContext,
 	},
+	prelude::{send_xcm, Instruction, Junction, Location, OriginKind, SendXcm, WeightLimit, Xcm},
 };
 use xcm_executor::traits::TransactAsset;
 
@@ -55,8 +55,8 @@ use std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque, HashMap},
 };
 use xcm::{
+	latest::{Assets, InteriorLocation, Location, SendError, SendResult, SendXcm, Xcm, XcmHash},
 	prelude::XcmVersion,
-	v4::{Assets, InteriorLocation, Location, SendError, SendResult, SendXcm, Xcm, XcmHash},
 	IntoVersion, VersionedXcm, WrapVersion,
 };
 
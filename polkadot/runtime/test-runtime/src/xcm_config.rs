@@ -178,6 +178,7 @@ impl pallet_xcm::Config for crate::Runtime {
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
 	type Currency = crate::Balances;
 	type CurrencyMatcher = ();
+	type NonFungibleLocker = ();
 	type TrustedLockers = ();
 	type SovereignAccountOf = ();
 	type MaxLockers = frame_support::traits::ConstU32<8>;
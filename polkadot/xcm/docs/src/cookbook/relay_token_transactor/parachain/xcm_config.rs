@@ -180,6 +180,7 @@ impl pallet_xcm::Config for Runtime {
 	// A currency to pay for things and its matcher, we are using the relay token
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<RelayLocation>;
+	type NonFungibleLocker = ();
 	// Pallet benchmarks, no need for this recipe
 	type WeightInfo = pallet_xcm::TestWeightInfo;
 	// Runtime types
@@ -154,6 +154,7 @@ impl pallet_xcm::Config for Runtime {
 	// A currency to pay for things and its matcher, we are using the relay token
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<HereLocation>;
+	type NonFungibleLocker = ();
 	// Pallet benchmarks, no need for this example
 	type WeightInfo = pallet_xcm::TestWeightInfo;
 	// Runtime types
@@ -763,6 +763,51 @@ benchmarks! {
 		assert_eq!(executor.origin(), &Some(target));
 	}
 
+	pay_fees {
+		let mut executor = new_executor::<T>(Default::default());
+		let holding_assets = T::worst_case_holding(1);
+		executor.set_holding(holding_assets.into());
+		// Set some weight to be paid for.
+		executor.set_message_weight(Weight::from_parts(1337, 1337));
+
+		let asset_for_fees = T::fee_asset().unwrap();
+		let instruction = Instruction::<XcmCallOf<T>>::PayFees { asset: asset_for_fees };
+
+		let xcm = Xcm(vec![instruction]);
+	}: {
+		executor.bench_process(xcm)?;
+	} verify {
+		assert!(executor.already_paid_fees());
+	}
+
+	set_asset_claimer {
+		let mut executor = new_executor::<T>(Default::default());
+		let (_, sender_location) = account_and_location::<T>(1);
+
+		let instruction = Instruction::SetAssetClaimer { location: sender_location.clone() };
+		let xcm = Xcm(vec![instruction]);
+	}: {
+		executor.bench_process(xcm)?;
+	} verify {
+		assert_eq!(executor.asset_claimer(), &Some(sender_location));
+	}
+
+	execute_with_origin {
+		let mut executor = new_executor::<T>(Default::default());
+		let who = Junctions::from([OnlyChild, OnlyChild]);
+
+		let instruction = Instruction::ExecuteWithOrigin {
+			descendant_origin: Some(who),
+			xcm: Xcm(vec![]),
+		};
+		let xcm = Xcm(vec![instruction]);
+	}: {
+		executor.bench_process(xcm)?;
+	} verify {
+		// The origin is restored once the inner message is executed.
+		assert_eq!(executor.origin(), &Some(Here.into()));
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::generic::mock::new_test_ext(),
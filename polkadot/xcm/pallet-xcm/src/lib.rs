@@ -61,15 +61,18 @@ use xcm_builder::{
 use xcm_executor::{
 	traits::{
 		AssetTransferError, CheckSuspension, ClaimAssets, ConvertLocation, ConvertOrigin,
-		DropAssets, MatchesFungible, OnResponse, Properties, QueryHandler, QueryResponseStatus,
-		RecordXcm, TransactAsset, TransferType, VersionChangeNotifier, WeightBounds,
-		XcmAssetTransfers,
+		DropAssets, InstructionTrace, LockNonFungible, MatchesFungible, OnResponse, Properties,
+		QueryHandler, QueryResponseStatus, RecordXcm, TransactAsset, TransferType,
+		VersionChangeNotifier, WeightBounds, XcmAssetTransfers,
 	},
 	AssetsInHolding,
 };
 use xcm_runtime_apis::{
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, TracedDryRunEffects, XcmDryRunEffects,
+	},
 	fees::Error as XcmPaymentApiError,
+	trapped_assets::{Error as TrappedAssetsApiError, TrappedAssets, TrappedAssetsPage},
 };
 
 #[cfg(any(feature = "try-runtime", test))]
@@ -223,6 +226,9 @@ pub mod pallet {
 		/// The `Asset` matcher for `Currency`.
 		type CurrencyMatcher: MatchesFungible<BalanceOf<Self>>;
 
+		/// Means of locking non-fungible assets of local accounts on request of `LockAsset`.
+		type NonFungibleLocker: LockNonFungible<Self::AccountId>;
+
 		/// Required origin for sending XCM messages. If successful, it resolves to `Location`
 		/// which exists as an interior location within this chain's XCM context.
 		type SendXcmOrigin: EnsureOrigin<<Self as SysConfig>::RuntimeOrigin, Success = Location>;
@@ -657,6 +663,14 @@ pub mod pallet {
 	#[pallet::getter(fn asset_trap)]
 	pub(super) type AssetTraps<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
 
+	/// The origin and assets of the existing asset traps, so they can be enumerated.
+	///
+	/// Key is the same as in [`AssetTraps`]. Only populated for assets trapped since this was
+	/// introduced.
+	#[pallet::storage]
+	pub(super) type AssetTrapDetails<T: Config> =
+		StorageMap<_, Identity, H256, (VersionedLocation, VersionedAssets), OptionQuery>;
+
 	/// Default version to encode XCM when latest version of destination is unknown. If `None`,
 	/// then the destinations whose XCM version is unknown are considered unreachable.
 	#[pallet::storage]
@@ -769,6 +783,43 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(MaxConsumers))]
+	pub struct RemoteLockedNonFungibleRecord<ConsumerIdentifier, MaxConsumers: Get<u32>> {
+		/// The owner of the locked asset.
+		pub owner: VersionedLocation,
+		/// The location which holds the original lock.
+		pub locker: VersionedLocation,
+		/// Local consumers of the remote lock.
+		pub consumers: BoundedVec<ConsumerIdentifier, MaxConsumers>,
+	}
+
+	/// Non-fungible assets which we know are locked on a remote chain.
+	#[pallet::storage]
+	pub(super) type RemoteLockedNonFungibles<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, XcmVersion>,
+			NMapKey<Blake2_128Concat, T::AccountId>,
+			NMapKey<Blake2_128Concat, VersionedAsset>,
+		),
+		RemoteLockedNonFungibleRecord<T::RemoteLockConsumerIdentifier, T::MaxRemoteLockConsumers>,
+		OptionQuery,
+	>;
+
+	/// Non-fungible assets which we know are locked on this chain, together with the location
+	/// which is able to unlock them.
+	#[pallet::storage]
+	pub(super) type LockedNonFungibles<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		VersionedAsset,
+		VersionedLocation,
+		OptionQuery,
+	>;
+
 	/// Global suspension state of the XCM executor.
 	#[pallet::storage]
 	pub(super) type XcmExecutionSuspended<T: Config> = StorageValue<_, bool, ValueQuery>;
@@ -792,6 +843,22 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type RecordedXcm<T: Config> = StorageValue<_, Xcm<()>>;
 
+	/// Whether or not the instructions of XCMs executed locally should be traced.
+	/// Like [`ShouldRecordXcm`], this is meant to be used in runtime APIs only.
+	///
+	/// Only relevant if this pallet is being used as the [`xcm_executor::traits::RecordXcm`]
+	/// implementation in the XCM executor configuration.
+	#[pallet::storage]
+	pub(crate) type ShouldTraceXcm<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// If [`ShouldTraceXcm`] is set to true, the trace of every instruction executed locally since
+	/// it was set, in order of completion.
+	///
+	/// Only relevant if this pallet is being used as the [`xcm_executor::traits::RecordXcm`]
+	/// implementation in the XCM executor configuration.
+	#[pallet::storage]
+	pub(crate) type RecordedTrace<T: Config> = StorageValue<_, Vec<InstructionTrace>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		#[serde(skip)]
@@ -2035,17 +2102,14 @@ impl<T: Config> Pallet<T> {
 		let (fees_half_1, fees_half_2) = Self::halve_fees(fees)?;
 		// identifies fee item as seen by `reserve` - to be used at reserve chain
 		let reserve_fees = fees_half_1
+			.clone()
 			.reanchored(&reserve, &context)
 			.map_err(|_| Error::<T>::CannotReanchor)?;
 		// identifies fee item as seen by `dest` - to be used at destination chain
 		let dest_fees = fees_half_2
+			.clone()
 			.reanchored(&dest, &context)
 			.map_err(|_| Error::<T>::CannotReanchor)?;
-		// identifies `dest` as seen by `reserve`
-		let dest = dest.reanchored(&reserve, &context).map_err(|_| Error::<T>::CannotReanchor)?;
-		// xcm to be executed at dest
-		let mut xcm_on_dest =
-			Xcm(vec![BuyExecution { fees: dest_fees, weight_limit: weight_limit.clone() }]);
 		// Use custom XCM on remote chain, or just default to depositing everything to beneficiary.
 		let custom_xcm_on_dest = match beneficiary {
 			Either::Right(custom_xcm) => custom_xcm,
@@ -2054,6 +2118,47 @@ impl<T: Config> Pallet<T> {
 				Xcm(vec![DepositAsset { assets: Wild(AllCounted(max_assets)), beneficiary }])
 			},
 		};
+		// if both hops understand XCMv5, fees for each hop are explicitly paid with `PayFees`
+		// as part of `InitiateTransfer`. `PayFees` buys the weight of the whole message, so an
+		// explicit `weight_limit` is only honoured by the `BuyExecution` program below.
+		if weight_limit == Unlimited && Self::supports_v5(&reserve) && Self::supports_v5(&dest) {
+			// identifies `dest` as seen by `reserve`
+			let dest =
+				dest.reanchored(&reserve, &context).map_err(|_| Error::<T>::CannotReanchor)?;
+			// identifies fee item as seen by `reserve` - to be used for execution on `dest`
+			let dest_fees = fees_half_2
+				.reanchored(&reserve, &context)
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+			// xcm to be executed on reserve
+			let xcm_on_reserve = Xcm(vec![InitiateTransfer {
+				destination: dest,
+				remote_fees: Some(AssetTransferFilter::ReserveDeposit(Definite(dest_fees.into()))),
+				preserve_origin: false,
+				assets: BoundedVec::truncate_from(vec![AssetTransferFilter::ReserveDeposit(Wild(
+					AllCounted(max_assets),
+				))]),
+				remote_xcm: custom_xcm_on_dest,
+			}]);
+			return Ok(Xcm(vec![
+				WithdrawAsset(assets.into()),
+				InitiateTransfer {
+					destination: reserve,
+					remote_fees: Some(AssetTransferFilter::ReserveWithdraw(Definite(
+						fees_half_1.into(),
+					))),
+					preserve_origin: false,
+					assets: BoundedVec::truncate_from(vec![AssetTransferFilter::ReserveWithdraw(
+						Wild(AllCounted(max_assets)),
+					)]),
+					remote_xcm: xcm_on_reserve,
+				},
+			]))
+		}
+		// identifies `dest` as seen by `reserve`
+		let dest = dest.reanchored(&reserve, &context).map_err(|_| Error::<T>::CannotReanchor)?;
+		// xcm to be executed at dest
+		let mut xcm_on_dest =
+			Xcm(vec![BuyExecution { fees: dest_fees, weight_limit: weight_limit.clone() }]);
 		xcm_on_dest.0.extend(custom_xcm_on_dest.into_iter());
 		// xcm to be executed on reserve
 		let xcm_on_reserve = Xcm(vec![
@@ -2200,6 +2305,11 @@ impl<T: Config> Pallet<T> {
 		Ok((local_execute_xcm, xcm_on_dest))
 	}
 
+	/// Returns whether `dest` is known to understand XCMv5 instructions.
+	fn supports_v5(dest: &Location) -> bool {
+		Self::get_version_for(dest).map_or(false, |version| version >= 5)
+	}
+
 	/// Halve `fees` fungible amount.
 	pub(crate) fn halve_fees(fees: Asset) -> Result<(Asset, Asset), Error<T>> {
 		match fees.fun {
@@ -2450,6 +2560,45 @@ impl<T: Config> Pallet<T> {
 		origin: OriginCaller,
 		call: RuntimeCall,
 	) -> Result<CallDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>, XcmDryRunApiError>
+	where
+		Runtime: crate::Config,
+		Router: InspectMessageQueues,
+		RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>,
+		<RuntimeCall as Dispatchable>::RuntimeOrigin: From<OriginCaller>,
+	{
+		Self::do_dry_run_call::<Runtime, Router, OriginCaller, RuntimeCall>(origin, call, false)
+			.map(|(effects, _)| effects)
+	}
+
+	/// Like [`Self::dry_run_call`], but also returns the trace of every XCM instruction executed
+	/// locally.
+	///
+	/// Meant to be used in version 2 of the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_call_with_trace<Runtime, Router, OriginCaller, RuntimeCall>(
+		origin: OriginCaller,
+		call: RuntimeCall,
+	) -> Result<
+		TracedDryRunEffects<CallDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>>,
+		XcmDryRunApiError,
+	>
+	where
+		Runtime: crate::Config,
+		Router: InspectMessageQueues,
+		RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>,
+		<RuntimeCall as Dispatchable>::RuntimeOrigin: From<OriginCaller>,
+	{
+		Self::do_dry_run_call::<Runtime, Router, OriginCaller, RuntimeCall>(origin, call, true)
+			.map(|(effects, trace)| TracedDryRunEffects { effects, trace })
+	}
+
+	fn do_dry_run_call<Runtime, Router, OriginCaller, RuntimeCall>(
+		origin: OriginCaller,
+		call: RuntimeCall,
+		trace: bool,
+	) -> Result<
+		(CallDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>, Vec<InstructionTrace>),
+		XcmDryRunApiError,
+	>
 	where
 		Runtime: crate::Config,
 		Router: InspectMessageQueues,
@@ -2457,25 +2606,29 @@ impl<T: Config> Pallet<T> {
 		<RuntimeCall as Dispatchable>::RuntimeOrigin: From<OriginCaller>,
 	{
 		crate::Pallet::<Runtime>::set_record_xcm(true);
+		crate::Pallet::<Runtime>::set_trace_xcm(trace);
 		// Clear other messages in queues...
 		Router::clear_messages();
 		// ...and reset events to make sure we only record events from current call.
 		frame_system::Pallet::<Runtime>::reset_events();
 		let result = call.dispatch(origin.into());
 		crate::Pallet::<Runtime>::set_record_xcm(false);
+		crate::Pallet::<Runtime>::set_trace_xcm(false);
 		let local_xcm = crate::Pallet::<Runtime>::recorded_xcm();
+		let trace = crate::Pallet::<Runtime>::recorded_trace();
 		// Should only get messages from this call since we cleared previous ones.
 		let forwarded_xcms = Router::get_messages();
 		let events: Vec<<Runtime as frame_system::Config>::RuntimeEvent> =
 			frame_system::Pallet::<Runtime>::read_events_no_consensus()
 				.map(|record| record.event.clone())
 				.collect();
-		Ok(CallDryRunEffects {
+		let effects = CallDryRunEffects {
 			local_xcm: local_xcm.map(VersionedXcm::<()>::from),
 			forwarded_xcms,
 			emitted_events: events,
 			execution_result: result,
-		})
+		};
+		Ok((effects, trace))
 	}
 
 	/// Dry-runs `xcm` with the given `origin_location`.
@@ -2486,6 +2639,42 @@ impl<T: Config> Pallet<T> {
 		origin_location: VersionedLocation,
 		xcm: VersionedXcm<RuntimeCall>,
 	) -> Result<XcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>, XcmDryRunApiError>
+	where
+		Runtime: frame_system::Config,
+		Router: InspectMessageQueues,
+		XcmConfig: xcm_executor::Config<RuntimeCall = RuntimeCall>,
+	{
+		Self::do_dry_run_xcm::<Runtime, Router, RuntimeCall, XcmConfig>(origin_location, xcm, false)
+			.map(|(effects, _)| effects)
+	}
+
+	/// Like [`Self::dry_run_xcm`], but also returns the trace of every instruction executed.
+	///
+	/// Meant to be used in version 2 of the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_xcm_with_trace<Runtime, Router, RuntimeCall, XcmConfig>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<RuntimeCall>,
+	) -> Result<
+		TracedDryRunEffects<XcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>>,
+		XcmDryRunApiError,
+	>
+	where
+		Runtime: frame_system::Config,
+		Router: InspectMessageQueues,
+		XcmConfig: xcm_executor::Config<RuntimeCall = RuntimeCall>,
+	{
+		Self::do_dry_run_xcm::<Runtime, Router, RuntimeCall, XcmConfig>(origin_location, xcm, true)
+			.map(|(effects, trace)| TracedDryRunEffects { effects, trace })
+	}
+
+	fn do_dry_run_xcm<Runtime, Router, RuntimeCall, XcmConfig>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<RuntimeCall>,
+		trace: bool,
+	) -> Result<
+		(XcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>, Vec<InstructionTrace>),
+		XcmDryRunApiError,
+	>
 	where
 		Runtime: frame_system::Config,
 		Router: InspectMessageQueues,
@@ -2509,6 +2698,7 @@ impl<T: Config> Pallet<T> {
 		})?;
 		let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
 		frame_system::Pallet::<Runtime>::reset_events(); // To make sure we only record events from current call.
+		XcmConfig::XcmRecorder::set_trace_xcm(trace);
 		let result = xcm_executor::XcmExecutor::<XcmConfig>::prepare_and_execute(
 			origin_location,
 			xcm,
@@ -2516,12 +2706,60 @@ impl<T: Config> Pallet<T> {
 			Weight::MAX, // Max limit available for execution.
 			Weight::zero(),
 		);
+		XcmConfig::XcmRecorder::set_trace_xcm(false);
+		let trace = XcmConfig::XcmRecorder::recorded_trace();
 		let forwarded_xcms = Router::get_messages();
 		let events: Vec<<Runtime as frame_system::Config>::RuntimeEvent> =
 			frame_system::Pallet::<Runtime>::read_events_no_consensus()
 				.map(|record| record.event.clone())
 				.collect();
-		Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
+		let effects =
+			XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result };
+		Ok((effects, trace))
+	}
+
+	/// Returns the assets currently trapped, only for `origin` if given.
+	///
+	/// Looks at no more than `limit` traps, following the one with hash `start` if given.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::trapped_assets::TrappedAssetsApi` runtime API.
+	pub fn trapped_assets(
+		origin: Option<VersionedLocation>,
+		start: Option<[u8; 32]>,
+		limit: u32,
+	) -> Result<TrappedAssetsPage, TrappedAssetsApiError> {
+		let origin: Option<Location> = origin
+			.map(Location::try_from)
+			.transpose()
+			.map_err(|()| TrappedAssetsApiError::VersionedConversionFailed)?;
+		let mut traps = match start {
+			Some(start) => AssetTrapDetails::<T>::iter_from(AssetTrapDetails::<T>::hashed_key_for(
+				H256::from(start),
+			)),
+			None => AssetTrapDetails::<T>::iter(),
+		};
+		let mut assets = Vec::new();
+		let mut last = None;
+		for (hash, (trap_origin, trap_assets)) in traps.by_ref().take(limit as usize) {
+			last = Some(hash);
+			let matches = match &origin {
+				Some(origin) => Location::try_from(trap_origin.clone()).as_ref() == Ok(origin),
+				None => true,
+			};
+			if matches {
+				assets.push(TrappedAssets {
+					hash: hash.into(),
+					origin: trap_origin,
+					assets: trap_assets,
+					count: AssetTraps::<T>::get(hash),
+				});
+			}
+		}
+		let next_start = match traps.next() {
+			Some(_) => last.map(Into::into),
+			None => None,
+		};
+		Ok(TrappedAssetsPage { assets, next_start })
 	}
 
 	/// Given a list of asset ids, returns the correct API response for
@@ -2724,96 +2962,146 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
-pub struct LockTicket<T: Config> {
-	sovereign_account: T::AccountId,
-	amount: BalanceOf<T>,
-	unlocker: Location,
-	item_index: Option<usize>,
+pub enum LockTicket<T: Config> {
+	Fungible {
+		sovereign_account: T::AccountId,
+		amount: BalanceOf<T>,
+		unlocker: Location,
+		item_index: Option<usize>,
+	},
+	NonFungible {
+		sovereign_account: T::AccountId,
+		asset: Asset,
+		unlocker: Location,
+	},
 }
 
 impl<T: Config> xcm_executor::traits::Enact for LockTicket<T> {
 	fn enact(self) -> Result<(), xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::UnexpectedState;
-		let mut locks = LockedFungibles::<T>::get(&self.sovereign_account).unwrap_or_default();
-		match self.item_index {
-			Some(index) => {
-				ensure!(locks.len() > index, UnexpectedState);
-				ensure!(locks[index].1.try_as::<_>() == Ok(&self.unlocker), UnexpectedState);
-				locks[index].0 = locks[index].0.max(self.amount);
+		match self {
+			LockTicket::Fungible { sovereign_account, amount, unlocker, item_index } => {
+				let mut locks = LockedFungibles::<T>::get(&sovereign_account).unwrap_or_default();
+				match item_index {
+					Some(index) => {
+						ensure!(locks.len() > index, UnexpectedState);
+						ensure!(locks[index].1.try_as::<_>() == Ok(&unlocker), UnexpectedState);
+						locks[index].0 = locks[index].0.max(amount);
+					},
+					None => {
+						locks
+							.try_push((amount, unlocker.into()))
+							.map_err(|(_balance, _location)| UnexpectedState)?;
+					},
+				}
+				LockedFungibles::<T>::insert(&sovereign_account, locks);
+				T::Currency::extend_lock(
+					*b"py/xcmlk",
+					&sovereign_account,
+					amount,
+					WithdrawReasons::all(),
+				);
 			},
-			None => {
-				locks
-					.try_push((self.amount, self.unlocker.into()))
-					.map_err(|(_balance, _location)| UnexpectedState)?;
+			LockTicket::NonFungible { sovereign_account, asset, unlocker } => {
+				let key: VersionedAsset = asset.clone().into();
+				ensure!(
+					!LockedNonFungibles::<T>::contains_key(&sovereign_account, &key),
+					UnexpectedState
+				);
+				T::NonFungibleLocker::lock(&sovereign_account, &asset)?;
+				LockedNonFungibles::<T>::insert(&sovereign_account, key, unlocker.into_versioned());
 			},
 		}
-		LockedFungibles::<T>::insert(&self.sovereign_account, locks);
-		T::Currency::extend_lock(
-			*b"py/xcmlk",
-			&self.sovereign_account,
-			self.amount,
-			WithdrawReasons::all(),
-		);
 		Ok(())
 	}
 }
 
-pub struct UnlockTicket<T: Config> {
-	sovereign_account: T::AccountId,
-	amount: BalanceOf<T>,
-	unlocker: Location,
+pub enum UnlockTicket<T: Config> {
+	Fungible { sovereign_account: T::AccountId, amount: BalanceOf<T>, unlocker: Location },
+	NonFungible { sovereign_account: T::AccountId, asset: Asset, unlocker: Location },
 }
 
 impl<T: Config> xcm_executor::traits::Enact for UnlockTicket<T> {
 	fn enact(self) -> Result<(), xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::UnexpectedState;
-		let mut locks =
-			LockedFungibles::<T>::get(&self.sovereign_account).ok_or(UnexpectedState)?;
-		let mut maybe_remove_index = None;
-		let mut locked = BalanceOf::<T>::zero();
-		let mut found = false;
-		// We could just as well do with with an into_iter, filter_map and collect, however this way
-		// avoids making an allocation.
-		for (i, x) in locks.iter_mut().enumerate() {
-			if x.1.try_as::<_>().defensive() == Ok(&self.unlocker) {
-				x.0 = x.0.saturating_sub(self.amount);
-				if x.0.is_zero() {
-					maybe_remove_index = Some(i);
+		match self {
+			UnlockTicket::Fungible { sovereign_account, amount, unlocker } => {
+				let mut locks =
+					LockedFungibles::<T>::get(&sovereign_account).ok_or(UnexpectedState)?;
+				let mut maybe_remove_index = None;
+				let mut locked = BalanceOf::<T>::zero();
+				let mut found = false;
+				// We could just as well do with with an into_iter, filter_map and collect, however
+				// this way avoids making an allocation.
+				for (i, x) in locks.iter_mut().enumerate() {
+					if x.1.try_as::<_>().defensive() == Ok(&unlocker) {
+						x.0 = x.0.saturating_sub(amount);
+						if x.0.is_zero() {
+							maybe_remove_index = Some(i);
+						}
+						found = true;
+					}
+					locked = locked.max(x.0);
 				}
-				found = true;
-			}
-			locked = locked.max(x.0);
-		}
-		ensure!(found, UnexpectedState);
-		if let Some(remove_index) = maybe_remove_index {
-			locks.swap_remove(remove_index);
+				ensure!(found, UnexpectedState);
+				if let Some(remove_index) = maybe_remove_index {
+					locks.swap_remove(remove_index);
+				}
+				LockedFungibles::<T>::insert(&sovereign_account, locks);
+				let reasons = WithdrawReasons::all();
+				T::Currency::set_lock(*b"py/xcmlk", &sovereign_account, locked, reasons);
+			},
+			UnlockTicket::NonFungible { sovereign_account, asset, unlocker } => {
+				let key: VersionedAsset = asset.clone().into();
+				let lock = LockedNonFungibles::<T>::get(&sovereign_account, &key)
+					.ok_or(UnexpectedState)?;
+				ensure!(lock.try_as::<_>() == Ok(&unlocker), UnexpectedState);
+				// Only forget the lock once the asset is actually unlocked, the executor doesn't
+				// roll back a failed unlock.
+				T::NonFungibleLocker::unlock(&sovereign_account, &asset)?;
+				LockedNonFungibles::<T>::remove(&sovereign_account, &key);
+			},
 		}
-		LockedFungibles::<T>::insert(&self.sovereign_account, locks);
-		let reasons = WithdrawReasons::all();
-		T::Currency::set_lock(*b"py/xcmlk", &self.sovereign_account, locked, reasons);
 		Ok(())
 	}
 }
 
-pub struct ReduceTicket<T: Config> {
-	key: (u32, T::AccountId, VersionedAssetId),
-	amount: u128,
-	locker: VersionedLocation,
-	owner: VersionedLocation,
+pub enum ReduceTicket<T: Config> {
+	Fungible {
+		key: (u32, T::AccountId, VersionedAssetId),
+		amount: u128,
+		locker: VersionedLocation,
+		owner: VersionedLocation,
+	},
+	NonFungible {
+		key: (u32, T::AccountId, VersionedAsset),
+		locker: VersionedLocation,
+		owner: VersionedLocation,
+	},
 }
 
 impl<T: Config> xcm_executor::traits::Enact for ReduceTicket<T> {
 	fn enact(self) -> Result<(), xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::UnexpectedState;
-		let mut record = RemoteLockedFungibles::<T>::get(&self.key).ok_or(UnexpectedState)?;
-		ensure!(self.locker == record.locker && self.owner == record.owner, UnexpectedState);
-		let new_amount = record.amount.checked_sub(self.amount).ok_or(UnexpectedState)?;
-		ensure!(record.amount_held().map_or(true, |h| new_amount >= h), UnexpectedState);
-		if new_amount == 0 {
-			RemoteLockedFungibles::<T>::remove(&self.key);
-		} else {
-			record.amount = new_amount;
-			RemoteLockedFungibles::<T>::insert(&self.key, &record);
+		match self {
+			ReduceTicket::Fungible { key, amount, locker, owner } => {
+				let mut record = RemoteLockedFungibles::<T>::get(&key).ok_or(UnexpectedState)?;
+				ensure!(locker == record.locker && owner == record.owner, UnexpectedState);
+				let new_amount = record.amount.checked_sub(amount).ok_or(UnexpectedState)?;
+				ensure!(record.amount_held().map_or(true, |h| new_amount >= h), UnexpectedState);
+				if new_amount == 0 {
+					RemoteLockedFungibles::<T>::remove(&key);
+				} else {
+					record.amount = new_amount;
+					RemoteLockedFungibles::<T>::insert(&key, &record);
+				}
+			},
+			ReduceTicket::NonFungible { key, locker, owner } => {
+				let record = RemoteLockedNonFungibles::<T>::get(&key).ok_or(UnexpectedState)?;
+				ensure!(locker == record.locker && owner == record.owner, UnexpectedState);
+				ensure!(record.consumers.is_empty(), UnexpectedState);
+				RemoteLockedNonFungibles::<T>::remove(&key);
+			},
 		}
 		Ok(())
 	}
@@ -2831,12 +3119,19 @@ impl<T: Config> xcm_executor::traits::AssetLock for Pallet<T> {
 	) -> Result<LockTicket<T>, xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::*;
 		let sovereign_account = T::SovereignAccountOf::convert_location(&owner).ok_or(BadOwner)?;
+		if let NonFungible(_) = asset.fun {
+			// A non-fungible asset may only have a single lock at a time.
+			let key: VersionedAsset = asset.clone().into();
+			ensure!(!LockedNonFungibles::<T>::contains_key(&sovereign_account, &key), WouldClobber);
+			T::NonFungibleLocker::can_lock(&sovereign_account, &asset)?;
+			return Ok(LockTicket::NonFungible { sovereign_account, asset, unlocker })
+		}
 		let amount = T::CurrencyMatcher::matches_fungible(&asset).ok_or(UnknownAsset)?;
 		ensure!(T::Currency::free_balance(&sovereign_account) >= amount, AssetNotOwned);
 		let locks = LockedFungibles::<T>::get(&sovereign_account).unwrap_or_default();
 		let item_index = locks.iter().position(|x| x.1.try_as::<_>() == Ok(&unlocker));
 		ensure!(item_index.is_some() || locks.len() < T::MaxLockers::get() as usize, NoResources);
-		Ok(LockTicket { sovereign_account, amount, unlocker, item_index })
+		Ok(LockTicket::Fungible { sovereign_account, amount, unlocker, item_index })
 	}
 
 	fn prepare_unlock(
@@ -2846,13 +3141,19 @@ impl<T: Config> xcm_executor::traits::AssetLock for Pallet<T> {
 	) -> Result<UnlockTicket<T>, xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::*;
 		let sovereign_account = T::SovereignAccountOf::convert_location(&owner).ok_or(BadOwner)?;
+		if let NonFungible(_) = asset.fun {
+			let key: VersionedAsset = asset.clone().into();
+			let lock = LockedNonFungibles::<T>::get(&sovereign_account, &key).ok_or(NotLocked)?;
+			ensure!(lock.try_as::<_>() == Ok(&unlocker), NotLocked);
+			return Ok(UnlockTicket::NonFungible { sovereign_account, asset, unlocker })
+		}
 		let amount = T::CurrencyMatcher::matches_fungible(&asset).ok_or(UnknownAsset)?;
 		ensure!(T::Currency::free_balance(&sovereign_account) >= amount, AssetNotOwned);
 		let locks = LockedFungibles::<T>::get(&sovereign_account).unwrap_or_default();
 		let item_index =
 			locks.iter().position(|x| x.1.try_as::<_>() == Ok(&unlocker)).ok_or(NotLocked)?;
 		ensure!(locks[item_index].0 >= amount, NotLocked);
-		Ok(UnlockTicket { sovereign_account, amount, unlocker })
+		Ok(UnlockTicket::Fungible { sovereign_account, amount, unlocker })
 	}
 
 	fn note_unlockable(
@@ -2862,14 +3163,28 @@ impl<T: Config> xcm_executor::traits::AssetLock for Pallet<T> {
 	) -> Result<(), xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::*;
 		ensure!(T::TrustedLockers::contains(&locker, &asset), NotTrusted);
-		let amount = match asset.fun {
-			Fungible(a) => a,
-			NonFungible(_) => return Err(Unimplemented),
-		};
 		owner.remove_network_id();
 		let account = T::SovereignAccountOf::convert_location(&owner).ok_or(BadOwner)?;
 		let locker = locker.into();
 		let owner = owner.into();
+		let amount = match asset.fun {
+			Fungible(a) => a,
+			NonFungible(_) => {
+				let key = (XCM_VERSION, account, VersionedAsset::from(asset));
+				let mut record = RemoteLockedNonFungibleRecord {
+					owner,
+					locker,
+					consumers: BoundedVec::default(),
+				};
+				if let Some(old) = RemoteLockedNonFungibles::<T>::get(&key) {
+					// Make sure that the new record wouldn't clobber any old data.
+					ensure!(old.locker == record.locker && old.owner == record.owner, WouldClobber);
+					record.consumers = old.consumers;
+				}
+				RemoteLockedNonFungibles::<T>::insert(&key, record);
+				return Ok(())
+			},
+		};
 		let id: VersionedAssetId = asset.id.into();
 		let key = (XCM_VERSION, account, id);
 		let mut record =
@@ -2890,14 +3205,21 @@ impl<T: Config> xcm_executor::traits::AssetLock for Pallet<T> {
 		mut owner: Location,
 	) -> Result<Self::ReduceTicket, xcm_executor::traits::LockError> {
 		use xcm_executor::traits::LockError::*;
-		let amount = match asset.fun {
-			Fungible(a) => a,
-			NonFungible(_) => return Err(Unimplemented),
-		};
 		owner.remove_network_id();
 		let sovereign_account = T::SovereignAccountOf::convert_location(&owner).ok_or(BadOwner)?;
 		let locker = locker.into();
 		let owner = owner.into();
+		let amount = match asset.fun {
+			Fungible(a) => a,
+			NonFungible(_) => {
+				let key = (XCM_VERSION, sovereign_account, VersionedAsset::from(asset));
+				let record = RemoteLockedNonFungibles::<T>::get(&key).ok_or(NotLocked)?;
+				// Make sure that the record contains what we expect and it isn't in use.
+				ensure!(locker == record.locker && owner == record.owner, WouldClobber);
+				ensure!(record.consumers.is_empty(), InUse);
+				return Ok(ReduceTicket::NonFungible { key, locker, owner })
+			},
+		};
 		let id: VersionedAssetId = asset.id.into();
 		let key = (XCM_VERSION, sovereign_account, id);
 
@@ -2909,7 +3231,7 @@ impl<T: Config> xcm_executor::traits::AssetLock for Pallet<T> {
 			record.amount_held().map_or(true, |h| record.amount.saturating_sub(amount) >= h),
 			InUse
 		);
-		Ok(ReduceTicket { key, amount, locker, owner })
+		Ok(ReduceTicket::Fungible { key, amount, locker, owner })
 	}
 }
 
@@ -2997,6 +3319,10 @@ impl<T: Config> DropAssets for Pallet<T> {
 		let versioned = VersionedAssets::from(Assets::from(assets));
 		let hash = BlakeTwo256::hash_of(&(&origin, &versioned));
 		AssetTraps::<T>::mutate(hash, |n| *n += 1);
+		AssetTrapDetails::<T>::insert(
+			hash,
+			(VersionedLocation::from(origin.clone()), versioned.clone()),
+		);
 		Self::deposit_event(Event::AssetsTrapped {
 			hash,
 			origin: origin.clone(),
@@ -3027,7 +3353,10 @@ impl<T: Config> ClaimAssets for Pallet<T> {
 		let hash = BlakeTwo256::hash_of(&(origin.clone(), versioned.clone()));
 		match AssetTraps::<T>::get(hash) {
 			0 => return false,
-			1 => AssetTraps::<T>::remove(hash),
+			1 => {
+				AssetTraps::<T>::remove(hash);
+				AssetTrapDetails::<T>::remove(hash);
+			},
 			n => AssetTraps::<T>::insert(hash, n - 1),
 		}
 		Self::deposit_event(Event::AssetsClaimed {
@@ -3249,6 +3578,25 @@ impl<T: Config> RecordXcm for Pallet<T> {
 	fn record(xcm: Xcm<()>) {
 		RecordedXcm::<T>::put(xcm);
 	}
+
+	fn should_trace() -> bool {
+		ShouldTraceXcm::<T>::get()
+	}
+
+	fn set_trace_xcm(enabled: bool) {
+		if enabled {
+			RecordedTrace::<T>::kill();
+		}
+		ShouldTraceXcm::<T>::put(enabled);
+	}
+
+	fn recorded_trace() -> Vec<InstructionTrace> {
+		RecordedTrace::<T>::get()
+	}
+
+	fn record_instruction(trace: InstructionTrace) {
+		RecordedTrace::<T>::append(trace);
+	}
 }
 
 /// Ensure that the origin `o` represents an XCM (`Transact`) origin.
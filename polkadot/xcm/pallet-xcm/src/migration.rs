@@ -15,13 +15,16 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	pallet::CurrentMigration, Config, Pallet, VersionMigrationStage, VersionNotifyTargets,
+	pallet::CurrentMigration, Config, LockedFungibles, LockedNonFungibles, Pallet,
+	RemoteLockedFungibles, RemoteLockedNonFungibles, VersionMigrationStage, VersionNotifyTargets,
 };
+use alloc::vec::Vec;
 use frame_support::{
 	pallet_prelude::*,
 	traits::{OnRuntimeUpgrade, StorageVersion, UncheckedOnRuntimeUpgrade},
 	weights::Weight,
 };
+use xcm::{latest::VERSION as XCM_VERSION, IntoVersion};
 
 const DEFAULT_PROOF_SIZE: u64 = 64 * 1024;
 
@@ -88,3 +91,91 @@ impl<T: Config> OnRuntimeUpgrade for MigrateToLatestXcmVersion<T> {
 		T::DbWeight::get().writes(1)
 	}
 }
+
+/// Migrates the asset locks kept by [`Pallet`] to the latest `XCM_VERSION`.
+///
+/// Locks are looked up by assets and locations of the latest XCM version, so locks recorded
+/// before an XCM version bump can't be found (and thus never released) until they are migrated.
+/// Locks which can't be expressed in the latest XCM version are dropped.
+///
+/// NOTE: This migration should be run once after every XCM version bump by runtimes which use
+/// [`Pallet`] as the `AssetLocker` of their XCM executor.
+pub struct MigrateLocksToLatestXcmVersion<T>(core::marker::PhantomData<T>);
+impl<T: Config> OnRuntimeUpgrade for MigrateLocksToLatestXcmVersion<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = T::DbWeight::get();
+		let mut weight = Weight::zero();
+
+		LockedFungibles::<T>::translate_values(|locks: BoundedVec<_, T::MaxLockers>| {
+			weight.saturating_accrue(db_weight.reads_writes(1, 1));
+			let locks = locks
+				.into_iter()
+				.filter_map(|(amount, unlocker): (_, xcm::VersionedLocation)| {
+					unlocker.into_latest().ok().map(|unlocker| (amount, unlocker))
+				})
+				.collect::<Vec<_>>();
+			locks.try_into().ok()
+		});
+
+		let locks = LockedNonFungibles::<T>::drain().collect::<Vec<_>>();
+		for (account, asset, unlocker) in locks {
+			weight.saturating_accrue(db_weight.reads_writes(1, 2));
+			match (asset.into_latest(), unlocker.into_latest()) {
+				(Ok(asset), Ok(unlocker)) =>
+					LockedNonFungibles::<T>::insert(&account, asset, unlocker),
+				_ => log::warn!(
+					target: "runtime::xcm",
+					"Dropping lock of non-fungible asset by {:?} which can't be migrated to XCM v{}",
+					account,
+					XCM_VERSION,
+				),
+			}
+		}
+
+		for version in 0..XCM_VERSION {
+			for ((account, id), mut record) in RemoteLockedFungibles::<T>::drain_prefix((version,))
+			{
+				weight.saturating_accrue(db_weight.reads_writes(1, 2));
+				match (id.into_latest(), record.owner.into_latest(), record.locker.into_latest()) {
+					(Ok(id), Ok(owner), Ok(locker)) => {
+						record.owner = owner;
+						record.locker = locker;
+						RemoteLockedFungibles::<T>::insert((XCM_VERSION, account, id), record);
+					},
+					_ => log::warn!(
+						target: "runtime::xcm",
+						"Dropping remote lock of {:?} which can't be migrated from XCM v{} to v{}",
+						account,
+						version,
+						XCM_VERSION,
+					),
+				}
+			}
+			for ((account, asset), mut record) in
+				RemoteLockedNonFungibles::<T>::drain_prefix((version,))
+			{
+				weight.saturating_accrue(db_weight.reads_writes(1, 2));
+				match (asset.into_latest(), record.owner.into_latest(), record.locker.into_latest())
+				{
+					(Ok(asset), Ok(owner), Ok(locker)) => {
+						record.owner = owner;
+						record.locker = locker;
+						RemoteLockedNonFungibles::<T>::insert(
+							(XCM_VERSION, account, asset),
+							record,
+						);
+					},
+					_ => log::warn!(
+						target: "runtime::xcm",
+						"Dropping remote lock of non-fungible asset of {:?} which can't be migrated from XCM v{} to v{}",
+						account,
+						version,
+						XCM_VERSION,
+					),
+				}
+			}
+		}
+
+		weight
+	}
+}
@@ -17,10 +17,10 @@
 use codec::Encode;
 pub use core::cell::RefCell;
 use frame_support::{
-	construct_runtime, derive_impl, parameter_types,
+	construct_runtime, derive_impl, ensure, parameter_types,
 	traits::{
-		AsEnsureOriginWithArg, ConstU128, ConstU32, Contains, Equals, Everything, EverythingBut,
-		Nothing,
+		AsEnsureOriginWithArg, ConstU128, ConstU32, Contains, ContainsPair, Equals, Everything,
+		EverythingBut, Nothing,
 	},
 	weights::Weight,
 };
@@ -40,7 +40,7 @@ use xcm_builder::{
 	XcmFeeManagerFromComponents,
 };
 use xcm_executor::{
-	traits::{Identity, JustTry},
+	traits::{Identity, JustTry, LockError, LockNonFungible},
 	XcmExecutor,
 };
 
@@ -150,6 +150,7 @@ construct_runtime!(
 thread_local! {
 	pub static SENT_XCM: RefCell<Vec<(Location, Xcm<()>)>> = RefCell::new(Vec::new());
 	pub static FAIL_SEND_XCM: RefCell<bool> = RefCell::new(false);
+	pub static LOCKED_NON_FUNGIBLES: RefCell<Vec<(AccountId, Asset)>> = RefCell::new(Vec::new());
 }
 pub(crate) fn sent_xcm() -> Vec<(Location, Xcm<()>)> {
 	SENT_XCM.with(|q| (*q.borrow()).clone())
@@ -164,6 +165,35 @@ pub(crate) fn take_sent_xcm() -> Vec<(Location, Xcm<()>)> {
 pub(crate) fn set_send_xcm_artificial_failure(should_fail: bool) {
 	FAIL_SEND_XCM.with(|q| *q.borrow_mut() = should_fail);
 }
+pub(crate) fn locked_non_fungibles() -> Vec<(AccountId, Asset)> {
+	LOCKED_NON_FUNGIBLES.with(|q| (*q.borrow()).clone())
+}
+/// Non-fungible locker which considers every non-fungible asset to be owned by everyone.
+pub struct TestNonFungibleLocker;
+impl LockNonFungible<AccountId> for TestNonFungibleLocker {
+	fn can_lock(_: &AccountId, asset: &Asset) -> Result<(), LockError> {
+		ensure!(matches!(asset.fun, NonFungible(_)), LockError::UnknownAsset);
+		let locked = LOCKED_NON_FUNGIBLES.with(|q| q.borrow().iter().any(|(_, a)| a == asset));
+		ensure!(!locked, LockError::InUse);
+		Ok(())
+	}
+	fn lock(who: &AccountId, asset: &Asset) -> Result<(), LockError> {
+		Self::can_lock(who, asset)?;
+		LOCKED_NON_FUNGIBLES.with(|q| q.borrow_mut().push((who.clone(), asset.clone())));
+		Ok(())
+	}
+	fn unlock(who: &AccountId, asset: &Asset) -> Result<(), LockError> {
+		LOCKED_NON_FUNGIBLES.with(|q| {
+			let mut locks = q.borrow_mut();
+			let index = locks
+				.iter()
+				.position(|l| l == &(who.clone(), asset.clone()))
+				.ok_or(LockError::NotLocked)?;
+			locks.swap_remove(index);
+			Ok(())
+		})
+	}
+}
 /// Sender that never returns error.
 pub struct TestSendXcm;
 impl SendXcm for TestSendXcm {
@@ -332,6 +362,9 @@ pub const OTHER_PARA_ID: u32 = 2009;
 // This child parachain is used for filtered/disallowed assets.
 pub const FILTERED_PARA_ID: u32 = 2010;
 
+// This child parachain is trusted to lock assets on our behalf.
+pub const TRUSTED_LOCKER_PARA_ID: u32 = 2011;
+
 parameter_types! {
 	pub const RelayLocation: Location = Here.into_location();
 	pub const NativeAsset: Asset = Asset {
@@ -532,6 +565,13 @@ impl Contains<(Location, Vec<Asset>)> for XcmTeleportFiltered {
 	}
 }
 
+pub struct TrustedLockers;
+impl ContainsPair<Location, Asset> for TrustedLockers {
+	fn contains(locker: &Location, _: &Asset) -> bool {
+		locker == &Location::new(0, [Parachain(TRUSTED_LOCKER_PARA_ID)])
+	}
+}
+
 impl pallet_xcm::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
@@ -548,10 +588,11 @@ impl pallet_xcm::Config for Test {
 	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
 	type AdvertisedXcmVersion = AdvertisedXcmVersion;
 	type AdminOrigin = EnsureRoot<AccountId>;
-	type TrustedLockers = ();
+	type TrustedLockers = TrustedLockers;
 	type SovereignAccountOf = AccountId32Aliases<(), AccountId32>;
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<RelayLocation>;
+	type NonFungibleLocker = TestNonFungibleLocker;
 	type MaxLockers = frame_support::traits::ConstU32<8>;
 	type MaxRemoteLockConsumers = frame_support::traits::ConstU32<0>;
 	type RemoteLockConsumerIdentifier = ();
@@ -25,6 +25,7 @@ use frame_support::{
 	assert_err, assert_ok,
 	traits::{tokens::fungibles::Inspect, Currency},
 	weights::Weight,
+	BoundedVec,
 };
 use polkadot_parachain_primitives::primitives::Id as ParaId;
 use sp_runtime::traits::AccountIdConversion;
@@ -1487,6 +1488,194 @@ fn teleport_assets_with_remote_asset_reserve_and_remote_fee_reserve_disallowed()
 	);
 }
 
+/// Test `transfer_assets` with remote asset reserve and (same) remote fee reserve, when both the
+/// reserve and the destination support XCMv5.
+///
+/// Transferring foreign asset (`USDC_RESERVE_PARA_ID` remote reserve) to `OTHER_PARA_ID`, paying
+/// for execution on each hop with `PayFees` loaded by `InitiateTransfer`.
+///
+/// ```nocompile
+///    | chain `A`           |  chain `C`                      |  chain `B`
+///    | Here (source)       |  USDC_RESERVE_PARA_ID           |  OTHER_PARA_ID (destination)
+///    |                     |  `fees` reserve                 |
+///    |                     |  `assets` reserve               |
+///    |
+///    |  1. `A` executes `InitiateTransfer(ReserveWithdraw(both))` dest `C`
+///    |     -----------------> `C` executes `InitiateTransfer(ReserveDeposit(both))` dest `B`
+///    |                             --------------------------> `DepositAsset(both)`
+/// ```
+#[test]
+fn transfer_assets_with_remote_asset_reserve_and_remote_fee_reserve_v5_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDC
+		let usdc_initial_local_amount = 142;
+		let (usdc_chain, _, usdc_id_location) = set_up_foreign_asset(
+			USDC_RESERVE_PARA_ID,
+			Some(USDC_INNER_JUNCTION),
+			ALICE,
+			usdc_initial_local_amount,
+			true,
+		);
+
+		// transfer destination is some other parachain
+		let dest = RelayLocation::get().pushed_with_interior(Parachain(OTHER_PARA_ID)).unwrap();
+
+		// both reserve and destination understand XCMv5
+		for location in [usdc_chain.clone(), dest.clone()] {
+			assert_ok!(XcmPallet::force_xcm_version(RuntimeOrigin::root(), Box::new(location), 5));
+		}
+
+		let assets: Assets = vec![(usdc_id_location.clone(), SEND_AMOUNT).into()].into();
+		let fee_index = 0;
+
+		// reanchor according to test-case
+		let context = UniversalLocation::get();
+		let expected_dest_on_reserve = dest.clone().reanchored(&usdc_chain, &context).unwrap();
+		let fees = assets.get(fee_index as usize).unwrap().clone();
+		let (fees_half_1, fees_half_2) = XcmPallet::halve_fees(fees).unwrap();
+		let expected_fee_on_reserve = fees_half_1.reanchored(&usdc_chain, &context).unwrap();
+		let expected_remaining_on_reserve = fees_half_2.reanchored(&usdc_chain, &context).unwrap();
+
+		// do the transfer
+		assert_ok!(XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.clone().into()),
+			Box::new(beneficiary.clone().into()),
+			Box::new(assets.into()),
+			fee_index,
+			Unlimited,
+		));
+		assert!(matches!(
+			last_event(),
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete { .. } })
+		));
+
+		// Alice spent (transferred) amount
+		assert_eq!(
+			AssetsPallet::balance(usdc_id_location.clone(), ALICE),
+			usdc_initial_local_amount - SEND_AMOUNT
+		);
+
+		// Verify sent XCM program
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				// first message sent to reserve chain
+				usdc_chain,
+				Xcm(vec![
+					WithdrawAsset(expected_fee_on_reserve.clone().into()),
+					PayFees { asset: expected_fee_on_reserve },
+					WithdrawAsset(expected_remaining_on_reserve.clone().into()),
+					ClearOrigin,
+					InitiateTransfer {
+						// final destination is `dest` as seen by `reserve`
+						destination: expected_dest_on_reserve,
+						remote_fees: Some(AssetTransferFilter::ReserveDeposit(Definite(
+							expected_remaining_on_reserve.into()
+						))),
+						preserve_origin: false,
+						assets: BoundedVec::truncate_from(vec![
+							AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))
+						]),
+						// message executed on `dest`
+						remote_xcm: Xcm(vec![DepositAsset {
+							assets: AllCounted(1).into(),
+							beneficiary
+						}]),
+					}
+				])
+			)],
+		);
+	});
+}
+
+/// Test `transfer_assets` with remote asset reserve and (same) remote fee reserve, when both the
+/// reserve and the destination support XCMv5 but the weight is limited.
+///
+/// `PayFees` does not honour the weight limit, so the transfer falls back to the
+/// `BuyExecution`-based program.
+#[test]
+fn transfer_assets_with_remote_asset_reserve_and_limited_weight_v5_buys_execution() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	let beneficiary: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+	new_test_ext_with_balances(balances).execute_with(|| {
+		// create sufficient foreign asset USDC
+		let usdc_initial_local_amount = 142;
+		let (usdc_chain, _, usdc_id_location) = set_up_foreign_asset(
+			USDC_RESERVE_PARA_ID,
+			Some(USDC_INNER_JUNCTION),
+			ALICE,
+			usdc_initial_local_amount,
+			true,
+		);
+
+		// transfer destination is some other parachain
+		let dest = RelayLocation::get().pushed_with_interior(Parachain(OTHER_PARA_ID)).unwrap();
+
+		// both reserve and destination understand XCMv5
+		for location in [usdc_chain.clone(), dest.clone()] {
+			assert_ok!(XcmPallet::force_xcm_version(RuntimeOrigin::root(), Box::new(location), 5));
+		}
+
+		let assets: Assets = vec![(usdc_id_location.clone(), SEND_AMOUNT).into()].into();
+		let fee_index = 0;
+		let weight_limit = WeightLimit::Limited(Weight::from_parts(5000, 5000));
+
+		// reanchor according to test-case
+		let context = UniversalLocation::get();
+		let expected_dest_on_reserve = dest.clone().reanchored(&usdc_chain, &context).unwrap();
+		let fees = assets.get(fee_index as usize).unwrap().clone();
+		let (fees_half_1, fees_half_2) = XcmPallet::halve_fees(fees).unwrap();
+		let mut expected_assets_on_reserve = assets.clone();
+		expected_assets_on_reserve.reanchor(&usdc_chain, &context).unwrap();
+		let expected_fee_on_reserve = fees_half_1.reanchored(&usdc_chain, &context).unwrap();
+		let expected_fee_on_dest = fees_half_2.reanchored(&dest, &context).unwrap();
+
+		// do the transfer
+		assert_ok!(XcmPallet::transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(dest.clone().into()),
+			Box::new(beneficiary.clone().into()),
+			Box::new(assets.into()),
+			fee_index,
+			weight_limit.clone(),
+		));
+		assert!(matches!(
+			last_event(),
+			RuntimeEvent::XcmPallet(crate::Event::Attempted { outcome: Outcome::Complete { .. } })
+		));
+
+		// Verify sent XCM program
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				// first message sent to reserve chain
+				usdc_chain,
+				Xcm(vec![
+					WithdrawAsset(expected_assets_on_reserve),
+					ClearOrigin,
+					BuyExecution {
+						fees: expected_fee_on_reserve,
+						weight_limit: weight_limit.clone()
+					},
+					DepositReserveAsset {
+						assets: Wild(AllCounted(1)),
+						// final destination is `dest` as seen by `reserve`
+						dest: expected_dest_on_reserve,
+						// message sent onward to `dest`
+						xcm: Xcm(vec![
+							buy_limited_execution(expected_fee_on_dest, weight_limit),
+							DepositAsset { assets: AllCounted(1).into(), beneficiary }
+						])
+					}
+				])
+			)],
+		);
+	});
+}
+
 /// Test `tested_call` with local asset reserve and teleported fee.
 ///
 /// Transferring native asset (local reserve) to `USDT_PARA_ID`. Using teleport-trusted USDT for
@@ -19,24 +19,31 @@
 pub(crate) mod assets_transfer;
 
 use crate::{
-	mock::*, pallet::SupportedVersion, AssetTraps, Config, CurrentMigration, Error,
-	ExecuteControllerWeightInfo, LatestVersionedLocation, Pallet, Queries, QueryStatus,
-	RecordedXcm, ShouldRecordXcm, VersionDiscoveryQueue, VersionMigrationStage, VersionNotifiers,
-	VersionNotifyTargets, WeightInfo,
+	migration::MigrateLocksToLatestXcmVersion, mock::*, pallet::SupportedVersion, AssetTraps,
+	Config, CurrentMigration, Error, ExecuteControllerWeightInfo, LatestVersionedLocation,
+	LockedFungibles, LockedNonFungibles, Pallet, Queries, QueryStatus, RecordedTrace, RecordedXcm,
+	RemoteLockedFungibleRecord, RemoteLockedFungibles, RemoteLockedNonFungibleRecord,
+	RemoteLockedNonFungibles, ShouldRecordXcm, ShouldTraceXcm, VersionDiscoveryQueue,
+	VersionMigrationStage, VersionNotifiers, VersionNotifyTargets, WeightInfo,
 };
 use frame_support::{
 	assert_err_ignore_postinfo, assert_noop, assert_ok,
-	traits::{Currency, Hooks},
+	traits::{Currency, Hooks, OnRuntimeUpgrade},
 	weights::Weight,
+	BoundedVec,
 };
 use polkadot_parachain_primitives::primitives::Id as ParaId;
 use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, Hash};
 use xcm::{latest::QueryResponseInfo, prelude::*};
 use xcm_builder::AllowKnownQueryResponses;
 use xcm_executor::{
-	traits::{Properties, QueryHandler, QueryResponseStatus, ShouldExecute},
+	traits::{
+		AssetLock, DropAssets, Enact, InstructionTrace, LockError, Properties, QueryHandler,
+		QueryResponseStatus, RecordXcm, ShouldExecute,
+	},
 	XcmExecutor,
 };
+use xcm_runtime_apis::trapped_assets::{TrappedAssets, TrappedAssetsPage};
 
 const ALICE: AccountId = AccountId::new([0u8; 32]);
 const BOB: AccountId = AccountId::new([1u8; 32]);
@@ -436,6 +443,22 @@ fn trapped_assets_can_be_claimed() {
 		let expected = vec![(hash, 1u32)];
 		assert_eq!(trapped, expected);
 
+		// Trapped assets can be listed, for any or a given origin.
+		let source: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+		let expected = vec![TrappedAssets {
+			hash: hash.into(),
+			origin: source.clone().into(),
+			assets: VersionedAssets::from(Assets::from((Here, SEND_AMOUNT))),
+			count: 1,
+		}];
+		let page = |assets| TrappedAssetsPage { assets, next_start: None };
+		assert_eq!(XcmPallet::trapped_assets(None, None, 10), Ok(page(expected.clone())));
+		assert_eq!(XcmPallet::trapped_assets(Some(source.into()), None, 10), Ok(page(expected)));
+		assert_eq!(
+			XcmPallet::trapped_assets(Some(dest.clone().into()), None, 10),
+			Ok(page(vec![]))
+		);
+
 		let weight = BaseXcmWeight::get() * 3;
 		assert_ok!(XcmPallet::execute(
 			RuntimeOrigin::signed(ALICE),
@@ -450,6 +473,7 @@ fn trapped_assets_can_be_claimed() {
 		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - SEND_AMOUNT);
 		assert_eq!(Balances::total_balance(&BOB), INITIAL_BALANCE + SEND_AMOUNT);
 		assert_eq!(AssetTraps::<Test>::iter().collect::<Vec<_>>(), vec![]);
+		assert_eq!(XcmPallet::trapped_assets(None, None, 10), Ok(page(vec![])));
 
 		// Can't claim twice.
 		assert_err_ignore_postinfo!(
@@ -467,6 +491,46 @@ fn trapped_assets_can_be_claimed() {
 	});
 }
 
+#[test]
+fn trapped_assets_are_listed_in_pages() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let context = XcmContext::with_message_id([0; 32]);
+		let origins: Vec<Location> =
+			(0..3).map(|i| Parachain(OTHER_PARA_ID + i).into_location()).collect();
+		for origin in &origins {
+			XcmPallet::drop_assets(origin, Asset::from((Here, SEND_AMOUNT)).into(), &context);
+		}
+
+		// Traps are listed in pages of at most `limit` traps.
+		let first = XcmPallet::trapped_assets(None, None, 2).unwrap();
+		assert_eq!(first.assets.len(), 2);
+		let start = first.next_start.expect("one more trap to list");
+		assert_eq!(start, first.assets[1].hash);
+		let second = XcmPallet::trapped_assets(None, Some(start), 2).unwrap();
+		assert_eq!(second.assets.len(), 1);
+		assert_eq!(second.next_start, None);
+		for origin in &origins {
+			let origin = VersionedLocation::from(origin.clone());
+			assert!(first.assets.iter().chain(&second.assets).any(|t| t.origin == origin));
+		}
+
+		// The limit bounds the traps looked at, not the ones matching the origin.
+		let origin = origins[0].clone();
+		let mut matching = 0;
+		let mut start = None;
+		loop {
+			let page = XcmPallet::trapped_assets(Some(origin.clone().into()), start, 1).unwrap();
+			assert!(page.assets.len() <= 1);
+			matching += page.assets.len();
+			start = match page.next_start {
+				Some(next) => Some(next),
+				None => break,
+			};
+		}
+		assert_eq!(matching, 1);
+	});
+}
+
 // Like `trapped_assets_can_be_claimed` but using the `claim_assets` extrinsic.
 #[test]
 fn claim_assets_works() {
@@ -478,7 +542,7 @@ fn claim_assets_works() {
 		// Even though assets are trapped, the extrinsic returns success.
 		assert_ok!(XcmPallet::execute(
 			RuntimeOrigin::signed(ALICE),
-			Box::new(VersionedXcm::V4(trapping_program)),
+			Box::new(VersionedXcm::V5(trapping_program)),
 			BaseXcmWeight::get() * 2,
 		));
 		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - SEND_AMOUNT);
@@ -1289,3 +1353,281 @@ fn record_xcm_works() {
 		assert_eq!(RecordedXcm::<Test>::get(), Some(message.into()));
 	});
 }
+
+#[test]
+fn trace_xcm_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let beneficiary = Junction::AccountId32 { network: None, id: BOB.into() };
+		let message = Xcm::<RuntimeCall>::builder()
+			.withdraw_asset((Here, SEND_AMOUNT))
+			.buy_execution((Here, SEND_AMOUNT), Unlimited)
+			.deposit_asset(AllCounted(1), beneficiary.clone())
+			.build();
+		// Test default values.
+		assert_eq!(ShouldTraceXcm::<Test>::get(), false);
+		assert!(RecordedTrace::<Test>::get().is_empty());
+
+		// By default the message won't be traced.
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(message.clone())),
+			BaseXcmWeight::get() * 3,
+		));
+		assert!(RecordedTrace::<Test>::get().is_empty());
+
+		XcmPallet::set_trace_xcm(true);
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(message.clone())),
+			BaseXcmWeight::get() * 3,
+		));
+		let trace = |index, instruction, holding: Assets, error| InstructionTrace {
+			index,
+			instruction,
+			holding,
+			weight_used: BaseXcmWeight::get(),
+			fees_charged: Assets::new(),
+			error,
+		};
+		let withdrawn: Assets = (Here, SEND_AMOUNT).into();
+		assert_eq!(
+			XcmPallet::recorded_trace(),
+			vec![
+				trace(0, WithdrawAsset(withdrawn.clone()), withdrawn.clone(), None),
+				trace(
+					1,
+					BuyExecution { fees: (Here, SEND_AMOUNT).into(), weight_limit: Unlimited },
+					withdrawn.clone(),
+					None
+				),
+				trace(
+					2,
+					DepositAsset { assets: AllCounted(1).into(), beneficiary: beneficiary.into() },
+					Assets::new(),
+					None
+				),
+			]
+		);
+
+		// Failed instructions are traced with their error, following ones aren't executed.
+		XcmPallet::set_trace_xcm(true);
+		let too_much: Assets = (Here, INITIAL_BALANCE * 2).into();
+		let mut hash = [0; 32];
+		let outcome = XcmExecutor::<XcmConfig>::prepare_and_execute(
+			Junction::AccountId32 { network: None, id: ALICE.into() },
+			Xcm(vec![WithdrawAsset(too_much.clone()), ClearOrigin]),
+			&mut hash,
+			BaseXcmWeight::get() * 2,
+			BaseXcmWeight::get() * 2,
+		);
+		assert!(matches!(outcome, Outcome::Incomplete { .. }));
+		let recorded = XcmPallet::recorded_trace();
+		assert_eq!(recorded.len(), 1);
+		assert_eq!(recorded[0].index, 0);
+		assert_eq!(recorded[0].instruction, WithdrawAsset(too_much));
+		assert!(recorded[0].holding.is_none());
+		assert!(recorded[0].error.is_some());
+
+		// Disabling tracing stops recording, without clearing the trace.
+		XcmPallet::set_trace_xcm(false);
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(message.clone())),
+			BaseXcmWeight::get() * 3,
+		));
+		assert_eq!(XcmPallet::recorded_trace(), recorded);
+	});
+}
+
+#[test]
+fn non_fungible_lock_and_unlock_works() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let owner: Location = AccountId32 { network: None, id: ALICE.into() }.into();
+		let unlocker: Location = Parachain(OTHER_PARA_ID).into();
+		let other: Location = Parachain(USDT_PARA_ID).into();
+		let nft: Asset = (Here, Index(1)).into();
+		let key: VersionedAsset = nft.clone().into();
+
+		// Fungible locks are still handled by the currency.
+		assert!(matches!(
+			XcmPallet::prepare_lock(unlocker.clone(), (Here, SEND_AMOUNT).into(), owner.clone()),
+			Err(LockError::AssetNotOwned)
+		));
+
+		assert_ok!(XcmPallet::prepare_lock(unlocker.clone(), nft.clone(), owner.clone())
+			.and_then(Enact::enact));
+		assert_eq!(LockedNonFungibles::<Test>::get(&ALICE, &key), Some(unlocker.clone().into()));
+		assert_eq!(locked_non_fungibles(), vec![(ALICE, nft.clone())]);
+
+		// Only a single lock may exist on a non-fungible.
+		assert!(matches!(
+			XcmPallet::prepare_lock(other.clone(), nft.clone(), owner.clone()),
+			Err(LockError::WouldClobber)
+		));
+		// Only the unlocker may unlock it.
+		assert!(matches!(
+			XcmPallet::prepare_unlock(other, nft.clone(), owner.clone()),
+			Err(LockError::NotLocked)
+		));
+
+		assert_ok!(XcmPallet::prepare_unlock(unlocker, nft.clone(), owner).and_then(Enact::enact));
+		assert_eq!(LockedNonFungibles::<Test>::get(&ALICE, &key), None);
+		assert!(locked_non_fungibles().is_empty());
+	});
+}
+
+#[test]
+fn non_fungible_lock_is_kept_if_unlock_fails() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let owner: Location = AccountId32 { network: None, id: ALICE.into() }.into();
+		let unlocker: Location = Parachain(OTHER_PARA_ID).into();
+		let nft: Asset = (Here, Index(1)).into();
+		let key: VersionedAsset = nft.clone().into();
+
+		assert_ok!(XcmPallet::prepare_lock(unlocker.clone(), nft.clone(), owner.clone())
+			.and_then(Enact::enact));
+		// The locker lost track of the lock, so unlocking fails.
+		LOCKED_NON_FUNGIBLES.with(|q| q.borrow_mut().clear());
+
+		assert!(matches!(
+			XcmPallet::prepare_unlock(unlocker.clone(), nft.clone(), owner.clone())
+				.and_then(Enact::enact),
+			Err(LockError::NotLocked)
+		));
+		// The lock record is kept, the unlock may be retried.
+		assert_eq!(LockedNonFungibles::<Test>::get(&ALICE, &key), Some(unlocker.into()));
+	});
+}
+
+#[test]
+fn non_fungible_note_unlockable_and_reduce_works() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let owner: Location = AccountId32 { network: None, id: ALICE.into() }.into();
+		let locker: Location = Parachain(TRUSTED_LOCKER_PARA_ID).into();
+		let other: Location = Parachain(OTHER_PARA_ID).into();
+		let nft: Asset = ((Parachain(TRUSTED_LOCKER_PARA_ID), GeneralIndex(1)), Index(7)).into();
+		let key = (XCM_VERSION, ALICE, VersionedAsset::from(nft.clone()));
+
+		// Only trusted lockers are accepted.
+		assert!(matches!(
+			XcmPallet::note_unlockable(other.clone(), nft.clone(), owner.clone()),
+			Err(LockError::NotTrusted)
+		));
+
+		assert_ok!(XcmPallet::note_unlockable(locker.clone(), nft.clone(), owner.clone()));
+		let record = RemoteLockedNonFungibles::<Test>::get(&key).unwrap();
+		assert_eq!(record.locker, locker.clone().into());
+		assert_eq!(record.owner, owner.clone().into());
+		// Noting the same lock again is fine.
+		assert_ok!(XcmPallet::note_unlockable(locker.clone(), nft.clone(), owner.clone()));
+
+		// The lock can only be reduced by the locker.
+		assert!(matches!(
+			XcmPallet::prepare_reduce_unlockable(other, nft.clone(), owner.clone()),
+			Err(LockError::WouldClobber)
+		));
+		assert_ok!(XcmPallet::prepare_reduce_unlockable(
+			locker.clone(),
+			nft.clone(),
+			owner.clone()
+		)
+		.and_then(Enact::enact));
+		assert!(RemoteLockedNonFungibles::<Test>::get(&key).is_none());
+		assert!(matches!(
+			XcmPallet::prepare_reduce_unlockable(locker, nft, owner),
+			Err(LockError::NotLocked)
+		));
+	});
+}
+
+#[test]
+fn locks_are_migrated_to_latest_xcm_version() {
+	new_test_ext_with_balances(vec![(ALICE, INITIAL_BALANCE)]).execute_with(|| {
+		let owner: Location = AccountId32 { network: None, id: ALICE.into() }.into();
+		let unlocker: Location = Parachain(OTHER_PARA_ID).into();
+		let locker: Location = Parachain(TRUSTED_LOCKER_PARA_ID).into();
+		let nft: Asset = (Here, Index(1)).into();
+		let remote_asset: Asset =
+			((Parachain(TRUSTED_LOCKER_PARA_ID), GeneralIndex(1)), 50u128).into();
+		let remote_nft: Asset =
+			((Parachain(TRUSTED_LOCKER_PARA_ID), GeneralIndex(1)), Index(7)).into();
+		let old_version = XCM_VERSION - 1;
+		let old_location = |location: &Location| {
+			VersionedLocation::from(location.clone()).into_version(old_version).unwrap()
+		};
+
+		// Locks recorded before the XCM version bump.
+		LockedFungibles::<Test>::insert(
+			ALICE,
+			BoundedVec::truncate_from(vec![(SEND_AMOUNT, old_location(&unlocker))]),
+		);
+		LockedNonFungibles::<Test>::insert(
+			ALICE,
+			VersionedAsset::from(nft.clone()).into_version(old_version).unwrap(),
+			old_location(&unlocker),
+		);
+		RemoteLockedFungibles::<Test>::insert(
+			(
+				old_version,
+				ALICE,
+				VersionedAssetId::from(remote_asset.id.clone())
+					.into_version(old_version)
+					.unwrap(),
+			),
+			RemoteLockedFungibleRecord {
+				amount: 50,
+				owner: old_location(&owner),
+				locker: old_location(&locker),
+				consumers: BoundedVec::default(),
+			},
+		);
+		RemoteLockedNonFungibles::<Test>::insert(
+			(
+				old_version,
+				ALICE,
+				VersionedAsset::from(remote_nft.clone()).into_version(old_version).unwrap(),
+			),
+			RemoteLockedNonFungibleRecord {
+				owner: old_location(&owner),
+				locker: old_location(&locker),
+				consumers: BoundedVec::default(),
+			},
+		);
+		// They can't be found anymore.
+		assert!(matches!(
+			XcmPallet::prepare_unlock(unlocker.clone(), (Here, SEND_AMOUNT).into(), owner.clone()),
+			Err(LockError::NotLocked)
+		));
+		assert!(matches!(
+			XcmPallet::prepare_reduce_unlockable(
+				locker.clone(),
+				remote_asset.clone(),
+				owner.clone()
+			),
+			Err(LockError::NotLocked)
+		));
+
+		MigrateLocksToLatestXcmVersion::<Test>::on_runtime_upgrade();
+
+		assert_eq!(RemoteLockedFungibles::<Test>::iter_prefix((old_version,)).count(), 0);
+		assert_eq!(RemoteLockedNonFungibles::<Test>::iter_prefix((old_version,)).count(), 0);
+		assert_ok!(XcmPallet::prepare_unlock(
+			unlocker.clone(),
+			(Here, SEND_AMOUNT).into(),
+			owner.clone()
+		));
+		assert_ok!(XcmPallet::prepare_unlock(unlocker, nft, owner.clone()));
+		assert_ok!(XcmPallet::prepare_reduce_unlockable(
+			locker.clone(),
+			remote_asset,
+			owner.clone()
+		)
+		.and_then(Enact::enact));
+		assert!(RemoteLockedFungibles::<Test>::iter().next().is_none());
+		assert_ok!(
+			XcmPallet::prepare_reduce_unlockable(locker, remote_nft, owner).and_then(Enact::enact)
+		);
+		assert!(RemoteLockedNonFungibles::<Test>::iter().next().is_none());
+	});
+}
@@ -100,3 +100,22 @@ fn default_builder_allows_clear_origin_before_buy_execution() {
 		])
 	);
 }
+
+#[test]
+fn default_builder_allows_pay_fees_instead_of_buy_execution() {
+	let asset: Asset = (Here, 100u128).into();
+	let beneficiary: Location = [0u8; 32].into();
+	let message: Xcm<()> = Xcm::builder()
+		.withdraw_asset(asset.clone())
+		.pay_fees(asset.clone())
+		.deposit_asset(asset.clone(), beneficiary.clone())
+		.build();
+	assert_eq!(
+		message,
+		Xcm(vec![
+			WithdrawAsset(asset.clone().into()),
+			PayFees { asset: asset.clone() },
+			DepositAsset { assets: asset.into(), beneficiary },
+		])
+	);
+}
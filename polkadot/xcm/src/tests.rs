@@ -170,6 +170,45 @@ fn encode_decode_versioned_xcm_v3() {
 	assert_eq!(xcm, decoded);
 }
 
+#[test]
+fn encode_decode_versioned_xcm_v5() {
+	let xcm = VersionedXcm::V5(v5::Xcm::<()>::new());
+	let encoded = xcm.encode();
+
+	assert_eq!(encoded, hex_literal::hex!("0500"), "encode format changed");
+	assert_eq!(encoded[0], 5, "bad version number");
+
+	let decoded = VersionedXcm::decode(&mut &encoded[..]).unwrap();
+	assert_eq!(xcm, decoded);
+}
+
+#[test]
+fn versioned_xcm_v5_downgrades_only_without_new_instructions() {
+	use v5::prelude::*;
+
+	let xcm: Xcm<()> = Xcm(vec![ClearOrigin]);
+	let versioned = VersionedXcm::from(xcm.clone());
+	assert_eq!(versioned.identify_version(), 5);
+	assert_eq!(
+		versioned.into_version(4),
+		Ok(VersionedXcm::V4(v4::Xcm(vec![v4::Instruction::ClearOrigin])))
+	);
+	assert_eq!(
+		VersionedXcm::V4(v4::Xcm::<()>(vec![v4::Instruction::ClearOrigin])).into_version(5),
+		Ok(VersionedXcm::V5(xcm))
+	);
+
+	let xcm: Xcm<()> = Xcm(vec![PayFees { asset: (Here, 1u128).into() }]);
+	assert_eq!(VersionedXcm::from(xcm).into_version(4), Err(()));
+}
+
+#[test]
+fn versioned_data_types_accept_version_5() {
+	let location = VersionedLocation::from(latest::Location::parent());
+	assert_eq!(location.clone().into_version(5), Ok(location.clone()));
+	assert_eq!(location.into_latest().unwrap().identify_version(), v4::VERSION);
+}
+
 // With the renaming of the crate to `staging-xcm` the naming in the metadata changed as well and
 // this broke downstream users. This test ensures that the name in the metadata isn't changed.
 #[test]
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Mock to test [`SingleAssetExchangeAdapter`].
+//! Mock to test the asset exchange adapters.
 
 use core::marker::PhantomData;
 use frame_support::{
@@ -201,16 +201,33 @@ impl MaybeEquivalence<Location, NativeOrWithId<u32>> for LocationToAssetId {
 	}
 }
 
+/// Matches the native token and trust-backed assets.
+pub type PoolAssetsMatcher = MatchedConvertedConcreteId<
+	NativeOrWithId<u32>,
+	Balance,
+	(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
+	LocationToAssetId,
+	TryConvertInto,
+>;
+
 pub type PoolAssetsExchanger = crate::SingleAssetExchangeAdapter<
 	AssetConversion,
 	NativeAndAssets,
-	MatchedConvertedConcreteId<
-		NativeOrWithId<u32>,
-		Balance,
-		(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
-		LocationToAssetId,
-		TryConvertInto,
-	>,
+	PoolAssetsMatcher,
+	AccountId,
+>;
+
+parameter_types! {
+	pub ExchangeIntermediates: Vec<NativeOrWithId<u32>> = vec![NativeOrWithId::Native];
+	pub const MaxExchangeHops: u32 = 2;
+}
+
+pub type MultiHopPoolAssetsExchanger = crate::MultiHopExchangeAdapter<
+	AssetConversion,
+	NativeAndAssets,
+	PoolAssetsMatcher,
+	ExchangeIntermediates,
+	MaxExchangeHops,
 	AccountId,
 >;
 
@@ -324,6 +341,7 @@ impl pallet_xcm::Config for Runtime {
 	// A currency to pay for things and its matcher, we are using the relay token
 	type Currency = Balances;
 	type CurrencyMatcher = crate::IsConcrete<HereLocation>;
+	type NonFungibleLocker = ();
 	// Pallet benchmarks, no need for this recipe
 	type WeightInfo = pallet_xcm::TestWeightInfo;
 	// Runtime types
@@ -348,23 +366,29 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(|| {
 		System::set_block_number(1);
-		assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), 1, owner, false, 1,));
-		assert_ok!(AssetsPallet::mint_into(1, &owner, INITIAL_BALANCE,));
-		assert_ok!(AssetConversion::create_pool(
-			RuntimeOrigin::signed(owner),
-			Box::new(NativeOrWithId::Native),
-			Box::new(NativeOrWithId::WithId(1)),
-		));
-		assert_ok!(AssetConversion::add_liquidity(
-			RuntimeOrigin::signed(owner),
-			Box::new(NativeOrWithId::Native),
-			Box::new(NativeOrWithId::WithId(1)),
-			50_000_000,
-			100_000_000,
-			0,
-			0,
-			owner,
-		));
+		// Pools only exist between the native token and each asset, so swapping between assets 1
+		// and 2 requires going through the native token.
+		for (id, native_liquidity, asset_liquidity) in
+			[(1, 50_000_000, 100_000_000), (2, 40_000_000, 20_000_000)]
+		{
+			assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), id, owner, false, 1,));
+			assert_ok!(AssetsPallet::mint_into(id, &owner, INITIAL_BALANCE,));
+			assert_ok!(AssetConversion::create_pool(
+				RuntimeOrigin::signed(owner),
+				Box::new(NativeOrWithId::Native),
+				Box::new(NativeOrWithId::WithId(id)),
+			));
+			assert_ok!(AssetConversion::add_liquidity(
+				RuntimeOrigin::signed(owner),
+				Box::new(NativeOrWithId::Native),
+				Box::new(NativeOrWithId::WithId(id)),
+				native_liquidity,
+				asset_liquidity,
+				0,
+				0,
+				owner,
+			));
+		}
 	});
 	ext
 }
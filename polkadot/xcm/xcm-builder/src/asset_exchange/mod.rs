@@ -20,3 +20,9 @@
 
 mod single_asset_adapter;
 pub use single_asset_adapter::SingleAssetExchangeAdapter;
+
+mod multi_hop_adapter;
+pub use multi_hop_adapter::MultiHopExchangeAdapter;
+
+#[cfg(test)]
+mod mock;
@@ -0,0 +1,371 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-hop asset exchange adapter.
+
+extern crate alloc;
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use frame_support::{
+	ensure,
+	traits::{tokens::fungibles, Get},
+	weights::RuntimeDbWeight,
+};
+use pallet_asset_conversion::{QuotePrice, SwapCredit, WeightInfo};
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{AssetExchange, MatchesFungibles},
+	AssetsInHolding,
+};
+
+const LOG_TARGET: &str = "xcm::MultiHopExchangeAdapter";
+
+/// A route found between two assets together with its quoted amount.
+type Route<AssetId> = (Vec<AssetId>, u128);
+
+/// An adapter from [`pallet_asset_conversion::SwapCredit`] and
+/// [`pallet_asset_conversion::QuotePrice`] to [`xcm_executor::traits::AssetExchange`] which is
+/// able to swap over a path of pools when there's no direct pool between two assets.
+///
+/// Routes go through the assets returned by `Intermediates`, using at most `MaxHops` pools (and
+/// never more than the swap path length allowed by `AssetConversion`). Among all routes, the one
+/// yielding the most of `want` (when `maximal`) or requiring the least of `give` (otherwise) is
+/// used.
+///
+/// Like [`crate::SingleAssetExchangeAdapter`], this adapter takes just one fungible asset in `give`
+/// and allows only one fungible asset in `want`.
+///
+/// Use [`Self::exchange_weight`] to weigh the `ExchangeAsset` instruction when this adapter is
+/// the `AssetExchanger`.
+pub struct MultiHopExchangeAdapter<
+	AssetConversion,
+	Fungibles,
+	Matcher,
+	Intermediates,
+	MaxHops,
+	AccountId,
+>(PhantomData<(AssetConversion, Fungibles, Matcher, Intermediates, MaxHops, AccountId)>);
+
+impl<AssetConversion, Fungibles, Matcher, Intermediates, MaxHops, AccountId>
+	MultiHopExchangeAdapter<AssetConversion, Fungibles, Matcher, Intermediates, MaxHops, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediates: Get<Vec<Fungibles::AssetId>>,
+	MaxHops: Get<u32>,
+{
+	/// Worst-case weight of exchanging assets through this adapter.
+	///
+	/// Accounts for quoting every candidate route and swapping over the longest one, using the
+	/// benchmarked `ConversionWeights` of `pallet-asset-conversion`.
+	pub fn exchange_weight<ConversionWeights: WeightInfo, DbWeight: Get<RuntimeDbWeight>>(
+		maximal: bool,
+	) -> Weight {
+		let hops = Self::max_hops();
+		let swap = if maximal {
+			ConversionWeights::swap_exact_tokens_for_tokens(hops + 1)
+		} else {
+			ConversionWeights::swap_tokens_for_exact_tokens(hops + 1)
+		};
+		let quotes = Self::max_quotes(Intermediates::get().len() as u64, hops);
+		// Quoting a pool reads the balances of both of its assets.
+		swap.saturating_add(DbWeight::get().reads(quotes.saturating_mul(2)))
+	}
+
+	/// The maximum number of pools a swap goes through.
+	fn max_hops() -> u32 {
+		let max_path_len = <AssetConversion as SwapCredit<AccountId>>::max_path_len();
+		MaxHops::get().min(max_path_len.saturating_sub(1))
+	}
+
+	/// Upper bound on the number of quotes needed to search all routes of at most `hops` pools
+	/// going through `intermediates` assets.
+	fn max_quotes(intermediates: u64, hops: u32) -> u64 {
+		let mut quotes = 0u64;
+		// Number of routes using `k` distinct intermediates.
+		let mut routes = 1u64;
+		for k in 0..hops as u64 {
+			// Every route using `k` intermediates is quoted once to get there (unless `k` is 0),
+			// and once more for the last hop to the wanted asset.
+			quotes = quotes.saturating_add(if k == 0 { 1 } else { routes.saturating_mul(2) });
+			routes = routes.saturating_mul(intermediates.saturating_sub(k));
+		}
+		quotes
+	}
+
+	/// Quote a single hop of a route from `current` to `next`.
+	///
+	/// When `maximal`, routes are searched from the given asset: `amount` of `current` is swapped
+	/// and the result is the amount of `next` obtained. Otherwise, routes are searched backwards
+	/// from the wanted asset: `amount` of `current` is wanted and the result is the amount of
+	/// `next` required.
+	fn quote_hop(
+		current: &Fungibles::AssetId,
+		next: &Fungibles::AssetId,
+		amount: u128,
+		maximal: bool,
+	) -> Option<u128> {
+		if maximal {
+			<AssetConversion as QuotePrice>::quote_price_exact_tokens_for_tokens(
+				current.clone(),
+				next.clone(),
+				amount,
+				true, // Include fee.
+			)
+		} else {
+			<AssetConversion as QuotePrice>::quote_price_tokens_for_exact_tokens(
+				next.clone(),
+				current.clone(),
+				amount,
+				true, // Include fee.
+			)
+		}
+	}
+
+	/// Find the best route between `give` and `want`.
+	///
+	/// When `maximal`, `amount` of `give` is swapped and the route yielding the most of `want` is
+	/// returned with that amount. Otherwise, `amount` of `want` is wanted and the route requiring
+	/// the least of `give` is returned with that amount.
+	fn best_route(
+		give: Fungibles::AssetId,
+		want: Fungibles::AssetId,
+		amount: u128,
+		maximal: bool,
+	) -> Option<Route<Fungibles::AssetId>> {
+		if give == want || Self::max_hops() == 0 {
+			return None
+		}
+		let intermediates = Intermediates::get();
+		let (start, end) = if maximal { (give, want) } else { (want, give) };
+		let mut path = vec![start];
+		let mut best = None;
+		Self::search(&intermediates, &end, amount, maximal, &mut path, &mut best);
+		best.map(|(mut path, amount)| {
+			if !maximal {
+				path.reverse();
+			}
+			(path, amount)
+		})
+	}
+
+	/// Depth-first search of routes extending `path` to `end`, recording the best one in `best`.
+	fn search(
+		intermediates: &[Fungibles::AssetId],
+		end: &Fungibles::AssetId,
+		amount: u128,
+		maximal: bool,
+		path: &mut Vec<Fungibles::AssetId>,
+		best: &mut Option<Route<Fungibles::AssetId>>,
+	) {
+		let Some(current) = path.last().cloned() else { return };
+		if let Some(quoted) = Self::quote_hop(&current, end, amount, maximal) {
+			let is_better = best.as_ref().map_or(true, |(_, best_amount)| {
+				if maximal {
+					quoted > *best_amount
+				} else {
+					quoted < *best_amount
+				}
+			});
+			if is_better {
+				let mut route = path.clone();
+				route.push(end.clone());
+				*best = Some((route, quoted));
+			}
+		}
+		// `path` already spans `path.len() - 1` pools and reaching `end` takes one more.
+		if path.len() as u32 >= Self::max_hops() {
+			return
+		}
+		for intermediate in intermediates {
+			if intermediate == end || path.contains(intermediate) {
+				continue
+			}
+			let Some(quoted) = Self::quote_hop(&current, intermediate, amount, maximal) else {
+				continue
+			};
+			path.push(intermediate.clone());
+			Self::search(intermediates, end, quoted, maximal, path, best);
+			path.pop();
+		}
+	}
+}
+
+impl<AssetConversion, Fungibles, Matcher, Intermediates, MaxHops, AccountId> AssetExchange
+	for MultiHopExchangeAdapter<AssetConversion, Fungibles, Matcher, Intermediates, MaxHops, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediates: Get<Vec<Fungibles::AssetId>>,
+	MaxHops: Get<u32>,
+{
+	fn exchange_asset(
+		_: Option<&Location>,
+		give: AssetsInHolding,
+		want: &Assets,
+		maximal: bool,
+	) -> Result<AssetsInHolding, AssetsInHolding> {
+		let mut give_iter = give.fungible_assets_iter();
+		let give_asset = give_iter.next().ok_or_else(|| {
+			log::trace!(target: LOG_TARGET, "No fungible asset was in `give`.");
+			give.clone()
+		})?;
+		ensure!(give_iter.next().is_none(), give.clone()); // We only support 1 asset in `give`.
+		ensure!(give.non_fungible_assets_iter().next().is_none(), give.clone()); // We don't allow non-fungible assets.
+		ensure!(want.len() == 1, give.clone()); // We only support 1 asset in `want`.
+		let want_asset = want.get(0).ok_or_else(|| give.clone())?;
+		let (give_asset_id, give_amount) =
+			Matcher::matches_fungibles(&give_asset).map_err(|error| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Could not map XCM asset give {:?} to FRAME asset. Error: {:?}",
+					give_asset,
+					error,
+				);
+				give.clone()
+			})?;
+		let (want_asset_id, want_amount) =
+			Matcher::matches_fungibles(&want_asset).map_err(|error| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Could not map XCM asset want {:?} to FRAME asset. Error: {:?}",
+					want_asset,
+					error,
+				);
+				give.clone()
+			})?;
+
+		let amount = if maximal { give_amount } else { want_amount };
+		let (path, _) = Self::best_route(give_asset_id.clone(), want_asset_id, amount, maximal)
+			.ok_or_else(|| {
+				log::trace!(
+					target: LOG_TARGET,
+					"No route from {:?} to {:?} was found.",
+					give_asset,
+					want_asset,
+				);
+				give.clone()
+			})?;
+
+		// We have to do this to convert the XCM assets into credit the pools can use.
+		let credit_in = Fungibles::issue(give_asset_id, give_amount);
+
+		// Do the swap.
+		let (credit_out, maybe_credit_change) = if maximal {
+			// If `maximal`, then we swap exactly `credit_in` to get as much of `want_asset_id` as
+			// we can, with a minimum of `want_amount`.
+			let credit_out = <AssetConversion as SwapCredit<_>>::swap_exact_tokens_for_tokens(
+				path,
+				credit_in,
+				Some(want_amount),
+			)
+			.map_err(|(credit_in, error)| {
+				log::error!(target: LOG_TARGET, "Could not perform the swap, error: {:?}.", error);
+				drop(credit_in);
+				give.clone()
+			})?;
+
+			// We don't have leftover assets if exchange was maximal.
+			(credit_out, None)
+		} else {
+			// If `minimal`, then we swap as little of `credit_in` as we can to get exactly
+			// `want_amount` of `want_asset_id`.
+			let (credit_out, credit_change) =
+				<AssetConversion as SwapCredit<_>>::swap_tokens_for_exact_tokens(
+					path,
+					credit_in,
+					want_amount,
+				)
+				.map_err(|(credit_in, error)| {
+					log::error!(
+						target: LOG_TARGET,
+						"Could not perform the swap, error: {:?}.",
+						error
+					);
+					drop(credit_in);
+					give.clone()
+				})?;
+
+			(credit_out, if credit_change.peek() > 0 { Some(credit_change) } else { None })
+		};
+
+		// We create an `AssetsInHolding` instance by putting in the resulting asset
+		// of the exchange.
+		let resulting_asset: Asset = (want_asset.id.clone(), credit_out.peek()).into();
+		let mut result: AssetsInHolding = resulting_asset.into();
+
+		// If we have some leftover assets from the exchange, also put them in the result.
+		if let Some(credit_change) = maybe_credit_change {
+			let leftover_asset: Asset = (give_asset.id.clone(), credit_change.peek()).into();
+			result.subsume(leftover_asset);
+		}
+
+		Ok(result.into())
+	}
+
+	fn quote_exchange_price(give: &Assets, want: &Assets, maximal: bool) -> Option<Assets> {
+		if give.len() != 1 || want.len() != 1 {
+			return None;
+		} // We only support 1 asset in `give` or `want`.
+		let give_asset = give.get(0)?;
+		let want_asset = want.get(0)?;
+		// We first match both XCM assets to the asset ID types `AssetConversion` can handle.
+		let (give_asset_id, give_amount) = Matcher::matches_fungibles(give_asset)
+			.map_err(|error| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Could not map XCM asset {:?} to FRAME asset. Error: {:?}.",
+					give_asset,
+					error,
+				);
+			})
+			.ok()?;
+		let (want_asset_id, want_amount) = Matcher::matches_fungibles(want_asset)
+			.map_err(|error| {
+				log::trace!(
+					target: LOG_TARGET,
+					"Could not map XCM asset {:?} to FRAME asset. Error: {:?}.",
+					want_asset,
+					error,
+				);
+			})
+			.ok()?;
+		// We quote the price over the best route.
+		if maximal {
+			// The amount of `want` resulting from swapping `give`.
+			let (_, resulting_want) =
+				Self::best_route(give_asset_id, want_asset_id, give_amount, true)?;
+			Some((want_asset.id.clone(), resulting_want).into())
+		} else {
+			// The `give` amount required to obtain `want`.
+			let (_, necessary_give) =
+				Self::best_route(give_asset_id, want_asset_id, want_amount, false)?;
+			Some((give_asset.id.clone(), necessary_give).into())
+		}
+	}
+}
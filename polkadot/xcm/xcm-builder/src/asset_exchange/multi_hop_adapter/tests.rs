@@ -0,0 +1,201 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the [`MultiHopExchangeAdapter`] type.
+
+use crate::asset_exchange::mock::*;
+use frame_support::{parameter_types, weights::RuntimeDbWeight};
+use sp_core::ConstU32;
+use xcm::prelude::*;
+use xcm_executor::{traits::AssetExchange, AssetsInHolding};
+
+parameter_types! {
+	pub const TestDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 10, write: 100 };
+}
+
+/// Only allows swapping through a single pool.
+type SingleHopExchanger = crate::MultiHopExchangeAdapter<
+	AssetConversion,
+	NativeAndAssets,
+	PoolAssetsMatcher,
+	ExchangeIntermediates,
+	ConstU32<1>,
+	AccountId,
+>;
+
+fn asset_1() -> Location {
+	[PalletInstance(2), GeneralIndex(1)].into()
+}
+
+fn asset_2() -> Location {
+	[PalletInstance(2), GeneralIndex(2)].into()
+}
+
+// ========== Happy path ==========
+
+/// Scenario:
+/// There's no pool between assets 1 and 2, so the swap goes through the native token.
+#[test]
+fn maximal_exchange_through_intermediate() {
+	new_test_ext().execute_with(|| {
+		let assets = MultiHopPoolAssetsExchanger::exchange_asset(
+			None,
+			vec![(asset_1(), 10_000_000).into()].into(),
+			&vec![(asset_2(), 2_000_000).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		assert_eq!(amount_of(&assets, asset_2()), 2_030_328);
+		assert_eq!(amount_of(&assets, asset_1()), 0);
+	});
+}
+
+#[test]
+fn minimal_exchange_through_intermediate() {
+	new_test_ext().execute_with(|| {
+		let assets = MultiHopPoolAssetsExchanger::exchange_asset(
+			None,
+			vec![(asset_1(), 10_000_000).into()].into(),
+			&vec![(asset_2(), 2_000_000).into()].into(),
+			false, // Minimal
+		)
+		.unwrap();
+		assert_eq!(amount_of(&assets, asset_2()), 2_000_000);
+		// `9_817_782` were needed, the rest is given back.
+		assert_eq!(amount_of(&assets, asset_1()), 182_218);
+	});
+}
+
+#[test]
+fn direct_pool_is_used_when_available() {
+	new_test_ext().execute_with(|| {
+		let assets = MultiHopPoolAssetsExchanger::exchange_asset(
+			None,
+			vec![(asset_1(), 10_000_000).into()].into(),
+			&vec![(Here, 2_000_000).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		// Same as going through `SingleAssetExchangeAdapter`.
+		assert_eq!(amount_of(&assets, Here.into()), 4_533_054);
+	});
+}
+
+#[test]
+fn maximal_quote() {
+	new_test_ext().execute_with(|| {
+		let assets =
+			quote(&(asset_1(), 10_000_000).into(), &(asset_2(), 2_000_000).into(), true).unwrap();
+		// The amount of asset 2 resulting from swapping all `10_000_000` of asset 1.
+		assert_eq!(assets, (asset_2(), 2_030_328).into());
+	});
+}
+
+#[test]
+fn minimal_quote() {
+	new_test_ext().execute_with(|| {
+		let assets =
+			quote(&(asset_1(), 10_000_000).into(), &(asset_2(), 2_000_000).into(), false).unwrap();
+		// The amount of asset 1 needed to get `2_000_000` of asset 2.
+		assert_eq!(assets, (asset_1(), 9_817_782).into());
+	});
+}
+
+#[test]
+fn exchange_weight_accounts_for_route_search() {
+	// Routes of up to 2 hops through the native token: the direct route is quoted once, the route
+	// through the native token twice.
+	let reads = TestDbWeight::get().reads(3 * 2);
+	assert_eq!(
+		MultiHopPoolAssetsExchanger::exchange_weight::<(), TestDbWeight>(true),
+		<() as pallet_asset_conversion::WeightInfo>::swap_exact_tokens_for_tokens(3) + reads,
+	);
+	assert_eq!(
+		MultiHopPoolAssetsExchanger::exchange_weight::<(), TestDbWeight>(false),
+		<() as pallet_asset_conversion::WeightInfo>::swap_tokens_for_exact_tokens(3) + reads,
+	);
+	// Only the direct route is quoted with a single hop.
+	assert_eq!(
+		SingleHopExchanger::exchange_weight::<(), TestDbWeight>(true),
+		<() as pallet_asset_conversion::WeightInfo>::swap_exact_tokens_for_tokens(2) +
+			TestDbWeight::get().reads(2),
+	);
+}
+
+// ========== Unhappy path ==========
+
+#[test]
+fn no_route_within_max_hops() {
+	new_test_ext().execute_with(|| {
+		assert!(SingleHopExchanger::exchange_asset(
+			None,
+			vec![(asset_1(), 10_000_000).into()].into(),
+			&vec![(asset_2(), 2_000_000).into()].into(),
+			true, // Maximal
+		)
+		.is_err());
+		assert_eq!(
+			SingleHopExchanger::quote_exchange_price(
+				&(asset_1(), 10_000_000).into(),
+				&(asset_2(), 2_000_000).into(),
+				true,
+			),
+			None
+		);
+	});
+}
+
+#[test]
+fn same_asset_cannot_be_exchanged() {
+	new_test_ext().execute_with(|| {
+		assert!(MultiHopPoolAssetsExchanger::exchange_asset(
+			None,
+			vec![(asset_1(), 10_000_000).into()].into(),
+			&vec![(asset_1(), 2_000_000).into()].into(),
+			true, // Maximal
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn exchange_fails() {
+	new_test_ext().execute_with(|| {
+		let give: AssetsInHolding = vec![(asset_1(), 10_000_000).into()].into();
+		let result = MultiHopPoolAssetsExchanger::exchange_asset(
+			None,
+			give.clone(),
+			// We're asking for more than `10_000_000` of asset 1 can get.
+			&vec![(asset_2(), 3_000_000).into()].into(),
+			false, // Minimal
+		);
+		assert_eq!(result, Err(give));
+	});
+}
+
+// ========== Helper functions ==========
+
+fn amount_of(assets: &AssetsInHolding, id: Location) -> u128 {
+	assets.fungible.get(&AssetId(id)).copied().unwrap_or_default()
+}
+
+fn quote(asset_1: &Asset, asset_2: &Asset, maximal: bool) -> Option<Assets> {
+	MultiHopPoolAssetsExchanger::quote_exchange_price(
+		&asset_1.clone().into(),
+		&asset_2.clone().into(),
+		maximal,
+	)
+}
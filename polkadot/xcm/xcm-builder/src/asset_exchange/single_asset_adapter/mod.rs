@@ -19,7 +19,5 @@
 mod adapter;
 pub use adapter::SingleAssetExchangeAdapter;
 
-#[cfg(test)]
-mod mock;
 #[cfg(test)]
 mod tests;
@@ -16,7 +16,7 @@
 
 //! Tests for the [`SingleAssetExchangeAdapter`] type.
 
-use super::mock::*;
+use crate::asset_exchange::mock::*;
 use xcm::prelude::*;
 use xcm_executor::{traits::AssetExchange, AssetsInHolding};
 
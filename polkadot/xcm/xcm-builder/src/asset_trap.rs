@@ -0,0 +1,105 @@
+// Copyright Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use core::marker::PhantomData;
+use frame_support::traits::{Contains, Get};
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{DropAssets, ProcessTransaction, TransactAsset, WeightBounds},
+	AssetsInHolding,
+};
+
+/// A `DropAssets` implementation which returns assets to the location they're dropped for,
+/// instead of trapping them.
+///
+/// The executor drops assets for the asset claimer set by the message, if any, or its origin.
+/// Up to `MaxReturned` assets are deposited there with `AssetTransactor`, e.g. into the sovereign
+/// account of the origin, as long as the location is allowed by `Beneficiaries`. Assets which
+/// can't be deposited are handed over to `Fallback`, which usually traps them.
+///
+/// The executor doesn't charge the weight reported after execution, so the weight of returning
+/// the assets isn't reported. It must be charged up front, with [`WithReturnedAssetsWeight`].
+pub struct ReturnTrappedAssets<
+	AssetTransactor,
+	Beneficiaries,
+	Fallback,
+	TransactionalProcessor,
+	MaxReturned,
+>(PhantomData<(AssetTransactor, Beneficiaries, Fallback, TransactionalProcessor, MaxReturned)>);
+impl<
+		AssetTransactor: TransactAsset,
+		Beneficiaries: Contains<Location>,
+		Fallback: DropAssets,
+		TransactionalProcessor: ProcessTransaction,
+		MaxReturned: Get<u32>,
+	> DropAssets
+	for ReturnTrappedAssets<
+		AssetTransactor,
+		Beneficiaries,
+		Fallback,
+		TransactionalProcessor,
+		MaxReturned,
+	>
+{
+	fn drop_assets(origin: &Location, assets: AssetsInHolding, context: &XcmContext) -> Weight {
+		if !Beneficiaries::contains(origin) {
+			return Fallback::drop_assets(origin, assets, context)
+		}
+		let mut failed = AssetsInHolding::new();
+		for (index, asset) in assets.into_assets_iter().enumerate() {
+			if index >= MaxReturned::get() as usize {
+				failed.subsume(asset);
+				continue
+			}
+			let result = TransactionalProcessor::process(|| {
+				AssetTransactor::deposit_asset(&asset, origin, Some(context))
+			});
+			if let Err(error) = result {
+				log::debug!(
+					target: "xcm::drop_assets",
+					"ReturnTrappedAssets failed to return asset: {:?}, to: {:?}, error: {:?}",
+					asset, origin, error,
+				);
+				failed.subsume(asset);
+			}
+		}
+		if failed.is_empty() {
+			Weight::zero()
+		} else {
+			Fallback::drop_assets(origin, failed, context)
+		}
+	}
+}
+
+/// A `WeightBounds` implementation which adds the weight of returning `MaxReturned` dropped
+/// assets, each weighed as `DepositWeight`, to the weight of every message given by `Weigher`.
+///
+/// To be used along with [`ReturnTrappedAssets`].
+pub struct WithReturnedAssetsWeight<Weigher, MaxReturned, DepositWeight>(
+	PhantomData<(Weigher, MaxReturned, DepositWeight)>,
+);
+impl<Call, Weigher: WeightBounds<Call>, MaxReturned: Get<u32>, DepositWeight: Get<Weight>>
+	WeightBounds<Call> for WithReturnedAssetsWeight<Weigher, MaxReturned, DepositWeight>
+{
+	fn weight(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+		let return_weight = DepositWeight::get().saturating_mul(MaxReturned::get() as u64);
+		Weigher::weight(message).map(|weight| weight.saturating_add(return_weight))
+	}
+
+	fn instr_weight(instruction: &Instruction<Call>) -> Result<Weight, ()> {
+		Weigher::instr_weight(instruction)
+	}
+}
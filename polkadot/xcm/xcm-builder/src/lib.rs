@@ -36,7 +36,10 @@ pub use asset_conversion::{
 };
 
 mod asset_exchange;
-pub use asset_exchange::SingleAssetExchangeAdapter;
+pub use asset_exchange::{MultiHopExchangeAdapter, SingleAssetExchangeAdapter};
+
+mod asset_trap;
+pub use asset_trap::{ReturnTrappedAssets, WithReturnedAssetsWeight};
 
 mod barriers;
 pub use barriers::{
@@ -99,7 +102,8 @@ pub use matcher::{CreateMatcher, MatchXcm, Matcher};
 
 mod nonfungibles_adapter;
 pub use nonfungibles_adapter::{
-	NonFungiblesAdapter, NonFungiblesMutateAdapter, NonFungiblesTransferAdapter,
+	NonFungiblesAdapter, NonFungiblesLockAdapter, NonFungiblesMutateAdapter,
+	NonFungiblesTransferAdapter,
 };
 
 mod nonfungible_adapter;
@@ -22,7 +22,7 @@ use frame_support::traits::Get;
 use sp_io::hashing::blake2_256;
 use sp_runtime::traits::{AccountIdConversion, TrailingZeroInput, TryConvert};
 use xcm::latest::prelude::*;
-use xcm_executor::traits::ConvertLocation;
+use xcm_executor::traits::{ConvertLocation, ConvertLocationSteps};
 
 /// Means of converting a location into a stable and unique descriptive identifier.
 pub trait DescribeLocation {
@@ -152,6 +152,10 @@ impl<AccountId: From<[u8; 32]> + Clone, Describe: DescribeLocation> ConvertLocat
 		Some(blake2_256(&Describe::describe_location(value)?).into())
 	}
 }
+impl<AccountId: From<[u8; 32]> + Clone, Describe: DescribeLocation> ConvertLocationSteps<AccountId>
+	for HashedDescription<AccountId, Describe>
+{
+}
 
 /// This is a describer for legacy support of the `ForeignChainAliasAccount` preimage. New chains
 /// are recommended to use the more extensible `HashedDescription` type.
@@ -275,6 +279,10 @@ impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 32]> + Into<[u8; 32]>
 		Some(("multiloc", location).using_encoded(blake2_256).into())
 	}
 }
+impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 32]> + Into<[u8; 32]> + Clone>
+	ConvertLocationSteps<AccountId> for Account32Hash<Network, AccountId>
+{
+}
 
 /// A [`Location`] consisting of a single `Parent` [`Junction`] will be converted to the
 /// parent `AccountId`.
@@ -292,6 +300,7 @@ impl<AccountId: Decode + Eq + Clone> ConvertLocation<AccountId> for ParentIsPres
 		}
 	}
 }
+impl<AccountId: Decode + Eq + Clone> ConvertLocationSteps<AccountId> for ParentIsPreset<AccountId> {}
 
 pub struct ChildParachainConvertsVia<ParaId, AccountId>(PhantomData<(ParaId, AccountId)>);
 impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId: Clone>
@@ -304,6 +313,10 @@ impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId:
 		}
 	}
 }
+impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId: Clone>
+	ConvertLocationSteps<AccountId> for ChildParachainConvertsVia<ParaId, AccountId>
+{
+}
 
 pub struct SiblingParachainConvertsVia<ParaId, AccountId>(PhantomData<(ParaId, AccountId)>);
 impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId: Clone>
@@ -316,6 +329,10 @@ impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId:
 		}
 	}
 }
+impl<ParaId: From<u32> + Into<u32> + AccountIdConversion<AccountId>, AccountId: Clone>
+	ConvertLocationSteps<AccountId> for SiblingParachainConvertsVia<ParaId, AccountId>
+{
+}
 
 /// Extracts the `AccountId32` from the passed `location` if the network matches.
 pub struct AccountId32Aliases<Network, AccountId>(PhantomData<(Network, AccountId)>);
@@ -331,6 +348,10 @@ impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 32]> + Into<[u8; 32]>
 		Some((*id).into())
 	}
 }
+impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 32]> + Into<[u8; 32]> + Clone>
+	ConvertLocationSteps<AccountId> for AccountId32Aliases<Network, AccountId>
+{
+}
 
 /// Returns specified `TreasuryAccount` as `AccountId32` if passed `location` matches Treasury
 /// plurality.
@@ -348,6 +369,10 @@ impl<TreasuryAccount: Get<AccountId>, AccountId: From<[u8; 32]> + Into<[u8; 32]>
 		}
 	}
 }
+impl<TreasuryAccount: Get<AccountId>, AccountId: From<[u8; 32]> + Into<[u8; 32]> + Clone>
+	ConvertLocationSteps<AccountId> for LocalTreasuryVoiceConvertsVia<TreasuryAccount, AccountId>
+{
+}
 
 /// Conversion implementation which converts from a `[u8; 32]`-based `AccountId` into a
 /// `Location` consisting solely of a `AccountId32` junction with a fixed value for its
@@ -374,6 +399,10 @@ impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 20]> + Into<[u8; 20]>
 		Some((*key).into())
 	}
 }
+impl<Network: Get<Option<NetworkId>>, AccountId: From<[u8; 20]> + Into<[u8; 20]> + Clone>
+	ConvertLocationSteps<AccountId> for AccountKey20Aliases<Network, AccountId>
+{
+}
 
 /// Converts a location which is a top-level relay chain (which provides its own consensus) into a
 /// 32-byte `AccountId`.
@@ -406,6 +435,10 @@ impl<UniversalLocation: Get<InteriorLocation>, AccountId: From<[u8; 32]> + Clone
 		}
 	}
 }
+impl<UniversalLocation: Get<InteriorLocation>, AccountId: From<[u8; 32]> + Clone>
+	ConvertLocationSteps<AccountId> for GlobalConsensusConvertsFor<UniversalLocation, AccountId>
+{
+}
 impl<UniversalLocation, AccountId> GlobalConsensusConvertsFor<UniversalLocation, AccountId> {
 	fn from_params(network: &NetworkId) -> [u8; 32] {
 		(b"glblcnsnss_", network).using_encoded(blake2_256)
@@ -450,6 +483,11 @@ impl<UniversalLocation: Get<InteriorLocation>, AccountId: From<[u8; 32]> + Clone
 		}
 	}
 }
+impl<UniversalLocation: Get<InteriorLocation>, AccountId: From<[u8; 32]> + Clone>
+	ConvertLocationSteps<AccountId>
+	for GlobalConsensusParachainConvertsFor<UniversalLocation, AccountId>
+{
+}
 impl<UniversalLocation, AccountId>
 	GlobalConsensusParachainConvertsFor<UniversalLocation, AccountId>
 {
@@ -1010,4 +1048,38 @@ mod tests {
 			actual_description
 		);
 	}
+
+	#[test]
+	fn convert_location_steps_works() {
+		type Converter = (
+			ParentIsPreset<[u8; 32]>,
+			AccountId32Aliases<(), [u8; 32]>,
+			HashedDescription<[u8; 32], DescribeFamily<DescribeAllTerminal>>,
+		);
+
+		// Every converter is tried, in order.
+		let location = Location::new(0, [AccountId32 { network: None, id: [1; 32] }]);
+		let steps = Converter::convert_location_steps(&location);
+		let names: Vec<_> = steps.iter().map(|(name, _)| *name).collect();
+		assert_eq!(
+			names,
+			vec![
+				ParentIsPreset::<[u8; 32]>::name(),
+				AccountId32Aliases::<(), [u8; 32]>::name(),
+				HashedDescription::<[u8; 32], DescribeFamily<DescribeAllTerminal>>::name(),
+			]
+		);
+		assert!(names[1].contains("AccountId32Aliases"));
+		let results: Vec<_> = steps.into_iter().map(|(_, result)| result).collect();
+		assert_eq!(results, vec![None, Some([1; 32]), None]);
+		assert_eq!(Converter::convert_location(&location), Some([1; 32]));
+
+		// A location only the last converter handles.
+		let location = Location::new(1, [Parachain(1), account32()]);
+		let results: Vec<_> = Converter::convert_location_steps(&location)
+			.into_iter()
+			.map(|(_, result)| result.is_some())
+			.collect();
+		assert_eq!(results, vec![false, false, true]);
+	}
 }
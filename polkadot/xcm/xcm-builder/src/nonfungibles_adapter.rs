@@ -20,11 +20,15 @@ use crate::{AssetChecking, MintLocation};
 use core::{marker::PhantomData, result};
 use frame_support::{
 	ensure,
-	traits::{tokens::nonfungibles, Get},
+	traits::{
+		tokens::{nonfungibles, nonfungibles_v2},
+		Get,
+	},
 };
 use xcm::latest::prelude::*;
 use xcm_executor::traits::{
-	ConvertLocation, Error as MatchError, MatchesNonFungibles, TransactAsset,
+	ConvertLocation, Error as MatchError, LockError, LockNonFungible, MatchesNonFungibles,
+	TransactAsset,
 };
 
 const LOG_TARGET: &str = "xcm::nonfungibles_adapter";
@@ -353,3 +357,52 @@ impl<
 		)
 	}
 }
+
+/// [`LockNonFungible`] implementation that allows the use of a [`nonfungibles_v2`] implementation
+/// for locking assets through XCM. Items are locked by disabling their transfer.
+pub struct NonFungiblesLockAdapter<Assets, Matcher, AccountId>(
+	PhantomData<(Assets, Matcher, AccountId)>,
+);
+impl<
+		Assets: nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountId: Eq,
+	> NonFungiblesLockAdapter<Assets, Matcher, AccountId>
+{
+	/// Match `asset` to an item of `Assets` owned by `who`.
+	fn owned_item(
+		who: &AccountId,
+		asset: &Asset,
+	) -> result::Result<(Assets::CollectionId, Assets::ItemId), LockError> {
+		let (class, instance) =
+			Matcher::matches_nonfungibles(asset).map_err(|_| LockError::UnknownAsset)?;
+		ensure!(Assets::owner(&class, &instance).as_ref() == Some(who), LockError::AssetNotOwned);
+		Ok((class, instance))
+	}
+}
+impl<
+		Assets: nonfungibles_v2::Transfer<AccountId>,
+		Matcher: MatchesNonFungibles<Assets::CollectionId, Assets::ItemId>,
+		AccountId: Eq,
+	> LockNonFungible<AccountId> for NonFungiblesLockAdapter<Assets, Matcher, AccountId>
+{
+	fn can_lock(who: &AccountId, asset: &Asset) -> result::Result<(), LockError> {
+		let (class, instance) = Self::owned_item(who, asset)?;
+		// Items which can't be transferred are either locked already or frozen otherwise.
+		ensure!(Assets::can_transfer(&class, &instance), LockError::InUse);
+		Ok(())
+	}
+
+	fn lock(who: &AccountId, asset: &Asset) -> result::Result<(), LockError> {
+		log::trace!(target: LOG_TARGET, "lock asset: {:?}", asset);
+		let (class, instance) = Self::owned_item(who, asset)?;
+		ensure!(Assets::can_transfer(&class, &instance), LockError::InUse);
+		Assets::disable_transfer(&class, &instance).map_err(|_| LockError::UnexpectedState)
+	}
+
+	fn unlock(who: &AccountId, asset: &Asset) -> result::Result<(), LockError> {
+		log::trace!(target: LOG_TARGET, "unlock asset: {:?}", asset);
+		let (class, instance) = Self::owned_item(who, asset)?;
+		Assets::enable_transfer(&class, &instance).map_err(|_| LockError::UnexpectedState)
+	}
+}
@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
+use frame_support::BoundedVec;
 
 #[test]
 fn exchange_asset_should_work() {
@@ -386,6 +387,76 @@ fn basic_asset_trap_should_work() {
 	);
 }
 
+#[test]
+fn return_trapped_assets_should_work() {
+	use frame_support::traits::Equals;
+	use xcm_executor::traits::WeightBounds;
+
+	// Only the native asset can be deposited.
+	struct NativeTransactor;
+	impl TransactAsset for NativeTransactor {
+		fn deposit_asset(what: &Asset, who: &Location, context: Option<&XcmContext>) -> XcmResult {
+			match what.id.0.unpack() {
+				(0, []) => TestAssetTransactor::deposit_asset(what, who, context),
+				_ => Err(XcmError::AssetNotFound),
+			}
+		}
+	}
+	parameter_types! {
+		pub ReturnTo: Location = Parachain(1).into();
+		pub const ReturnWeight: Weight = Weight::from_parts(3, 3);
+	}
+	type AssetReturn = ReturnTrappedAssets<
+		NativeTransactor,
+		Equals<ReturnTo>,
+		TestAssetTrap,
+		(),
+		frame_support::traits::ConstU32<2>,
+	>;
+	type NoAssetReturn = ReturnTrappedAssets<
+		NativeTransactor,
+		Equals<ReturnTo>,
+		TestAssetTrap,
+		(),
+		frame_support::traits::ConstU32<0>,
+	>;
+
+	let context = XcmContext::with_message_id([0; 32]);
+	let dropped =
+		|| -> AssetsInHolding { vec![(Here, 100u128).into(), (Parent, 50u128).into()].into() };
+
+	// Assets which can be deposited are returned, the others trapped. Only trapping is weighed.
+	let weight = AssetReturn::drop_assets(&Parachain(1).into(), dropped(), &context);
+	assert_eq!(weight, Weight::from_parts(5, 5));
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 100u128).into()]);
+	let trapped: Vec<(Location, Assets)> = vec![(Parachain(1).into(), (Parent, 50u128).into())];
+	assert_eq!(TrappedAssets::get(), trapped);
+
+	// Nothing is returned beyond the limit.
+	let weight = NoAssetReturn::drop_assets(&Parachain(1).into(), dropped(), &context);
+	assert_eq!(weight, Weight::from_parts(5, 5));
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 100u128).into()]);
+	let trapped: (Location, Assets) = (Parachain(1).into(), dropped().into());
+	assert_eq!(TrappedAssets::get().last(), Some(&trapped));
+
+	// Assets dropped for other locations are only trapped.
+	let weight = AssetReturn::drop_assets(&Parachain(2).into(), dropped(), &context);
+	assert_eq!(weight, Weight::from_parts(5, 5));
+	assert_eq!(asset_list(Parachain(2)), vec![]);
+	let trapped: (Location, Assets) = (Parachain(2).into(), dropped().into());
+	assert_eq!(TrappedAssets::get().last(), Some(&trapped));
+
+	// Returning the assets is charged up front.
+	type Weigher = WithReturnedAssetsWeight<
+		FixedWeightBounds<UnitWeightCost, TestCall, MaxInstructions>,
+		frame_support::traits::ConstU32<2>,
+		ReturnWeight,
+	>;
+	let mut message = Xcm::<TestCall>(vec![ClearOrigin, ClearOrigin]);
+	assert_eq!(Weigher::weight(&mut message), Ok(Weight::from_parts(26, 26)));
+	assert_eq!(Weigher::instr_weight(&ClearOrigin), Ok(Weight::from_parts(10, 10)));
+}
+
 #[test]
 fn max_assets_limit_should_work() {
 	// we'll let them have message execution for free.
@@ -537,3 +608,146 @@ fn max_assets_limit_should_work() {
 		}
 	);
 }
+
+#[test]
+fn pay_fees_should_work() {
+	AllowPaidFrom::set(vec![Parent.into()]);
+	add_asset(Parent, (Here, 1000u128));
+	let claimer: Location = AccountIndex64 { index: 3, network: None }.into();
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 200u128).into()),
+		PayFees { asset: (Here, 100u128).into() },
+		SetAssetClaimer { location: claimer.clone() },
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: Parent.into() },
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parent,
+		message,
+		&mut hash,
+		Weight::from_parts(40, 40),
+		Weight::zero(),
+	);
+	// 40 units of weight for the message and 5 to trap unused fees.
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(45, 45) });
+	// 80 units (40 for ref time and 40 for proof size) have been paid for execution.
+	assert_eq!(asset_list(Parent), vec![(Here, 900u128).into()]);
+	// Unused fees are trapped under the asset claimer.
+	assert_eq!(TrappedAssets::get(), vec![(claimer, (Here, 20u128).into())]);
+}
+
+#[test]
+fn pay_fees_should_fail_when_not_holding_fees() {
+	AllowPaidFrom::set(vec![Parent.into()]);
+	add_asset(Parent, (Here, 1000u128));
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 10u128).into()),
+		PayFees { asset: (Here, 100u128).into() },
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: Parent.into() },
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parent,
+		message,
+		&mut hash,
+		Weight::from_parts(30, 30),
+		Weight::zero(),
+	);
+	assert_eq!(
+		r,
+		Outcome::Incomplete { used: Weight::from_parts(25, 25), error: XcmError::NotHoldingFees }
+	);
+	// Withdrawn assets are trapped under the origin.
+	assert_eq!(TrappedAssets::get(), vec![(Parent.into(), (Here, 10u128).into())]);
+}
+
+#[test]
+fn initiate_transfer_with_remote_fees_should_work() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	add_asset(Parachain(1), (Here, 1000u128));
+	let three: Location = [AccountIndex64 { index: 3, network: None }].into();
+
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 300u128).into()),
+		InitiateTransfer {
+			destination: Parachain(2).into(),
+			remote_fees: Some(AssetTransferFilter::ReserveDeposit(Definite(
+				(Here, 100u128).into(),
+			))),
+			preserve_origin: false,
+			assets: BoundedVec::truncate_from(vec![AssetTransferFilter::Teleport(Wild(
+				AllCounted(1),
+			))]),
+			remote_xcm: Xcm(vec![DepositAsset {
+				assets: AllCounted(2).into(),
+				beneficiary: three.clone(),
+			}]),
+		},
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(50, 50),
+		Weight::zero(),
+	);
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(20, 20) });
+
+	let expected_msg = Xcm::<()>(vec![
+		ReserveAssetDeposited((Parent, 100u128).into()),
+		PayFees { asset: (Parent, 100u128).into() },
+		ReceiveTeleportedAsset((Parent, 200u128).into()),
+		ClearOrigin,
+		DepositAsset { assets: AllCounted(2).into(), beneficiary: three },
+	]);
+	let expected_hash = fake_message_hash(&expected_msg);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 700u128).into()]);
+	// Only the reserve-transferred fees are deposited to the sovereign account of the
+	// destination, teleported assets are checked out.
+	assert_eq!(asset_list(Parachain(2)), vec![(Here, 100u128).into()]);
+	assert_eq!(sent_xcm(), vec![(Parachain(2).into(), expected_msg, expected_hash)]);
+}
+
+#[test]
+fn initiate_transfer_without_remote_fees_should_work() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	add_asset(Parachain(1), (Here, 1000u128));
+	let three: Location = [AccountIndex64 { index: 3, network: None }].into();
+
+	let message = Xcm(vec![
+		WithdrawAsset((Here, 300u128).into()),
+		InitiateTransfer {
+			destination: Parachain(2).into(),
+			remote_fees: None,
+			preserve_origin: true,
+			assets: BoundedVec::truncate_from(vec![AssetTransferFilter::ReserveWithdraw(Wild(
+				AllCounted(1),
+			))]),
+			remote_xcm: Xcm(vec![DepositAsset {
+				assets: AllCounted(1).into(),
+				beneficiary: three.clone(),
+			}]),
+		},
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(50, 50),
+		Weight::zero(),
+	);
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(20, 20) });
+
+	let expected_msg = Xcm::<()>(vec![
+		UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+		WithdrawAsset((Parent, 300u128).into()),
+		AliasOrigin((Parent, Parachain(1)).into()),
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: three },
+	]);
+	let expected_hash = fake_message_hash(&expected_msg);
+	assert_eq!(asset_list(Parachain(1)), vec![(Here, 700u128).into()]);
+	assert_eq!(asset_list(Parachain(2)), vec![]);
+	assert_eq!(sent_xcm(), vec![(Parachain(2).into(), expected_msg, expected_hash)]);
+}
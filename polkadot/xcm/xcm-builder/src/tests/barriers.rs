@@ -283,6 +283,50 @@ fn allow_paid_should_work() {
 	assert_eq!(r, Ok(()))
 }
 
+#[test]
+fn allow_paid_with_pay_fees_should_work() {
+	AllowPaidFrom::set(vec![Parent.into()]);
+
+	let mut paying_message = Xcm::<()>(vec![
+		ReserveAssetDeposited((Parent, 100).into()),
+		PayFees { asset: (Parent, 1).into() },
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: Here.into() },
+	]);
+
+	let r = AllowTopLevelPaidExecutionFrom::<IsInVec<AllowPaidFrom>>::should_execute(
+		&Parent.into(),
+		paying_message.inner_mut(),
+		Weight::from_parts(30, 30),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+
+	let mut paying_message_after_clear_origin = Xcm::<()>(vec![
+		WithdrawAsset((Parent, 100).into()),
+		ClearOrigin,
+		PayFees { asset: (Parent, 1).into() },
+	]);
+
+	let r = AllowTopLevelPaidExecutionFrom::<IsInVec<AllowPaidFrom>>::should_execute(
+		&Parent.into(),
+		paying_message_after_clear_origin.inner_mut(),
+		Weight::from_parts(30, 30),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+
+	let mut message_without_assets =
+		Xcm::<()>(vec![PayFees { asset: (Parent, 1).into() }, ClearOrigin]);
+
+	let r = AllowTopLevelPaidExecutionFrom::<IsInVec<AllowPaidFrom>>::should_execute(
+		&Parent.into(),
+		message_without_assets.inner_mut(),
+		Weight::from_parts(30, 30),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::BadFormat));
+}
+
 #[test]
 fn suspension_should_work() {
 	TestSuspender::set_suspended(true);
@@ -154,3 +154,49 @@ fn unpaid_execution_should_work() {
 	);
 	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(10, 10) });
 }
+
+#[test]
+fn execute_with_origin_should_work() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	let account = AccountIndex64 { index: 3, network: None };
+	let message = Xcm(vec![
+		ExecuteWithOrigin {
+			descendant_origin: Some(account.into()),
+			xcm: Xcm(vec![ExpectOrigin(Some(Location::new(0, [Parachain(1), account])))]),
+		},
+		ExpectOrigin(Some(Parachain(1).into())),
+		ExecuteWithOrigin { descendant_origin: None, xcm: Xcm(vec![ExpectOrigin(None)]) },
+		ExpectOrigin(Some(Parachain(1).into())),
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(100, 100),
+		Weight::zero(),
+	);
+	// Inner instructions are weighed too.
+	assert_eq!(r, Outcome::Complete { used: Weight::from_parts(60, 60) });
+}
+
+#[test]
+fn execute_with_origin_restores_origin_on_error() {
+	AllowUnpaidFrom::set(vec![[Parachain(1)].into()]);
+	let message = Xcm(vec![
+		SetErrorHandler(Xcm(vec![ExpectOrigin(Some(Parachain(1).into()))])),
+		ExecuteWithOrigin { descendant_origin: None, xcm: Xcm(vec![Trap(1)]) },
+	]);
+	let mut hash = fake_message_hash(&message);
+	let r = XcmExecutor::<TestConfig>::prepare_and_execute(
+		Parachain(1),
+		message,
+		&mut hash,
+		Weight::from_parts(100, 100),
+		Weight::zero(),
+	);
+	assert_eq!(
+		r,
+		Outcome::Incomplete { used: Weight::from_parts(40, 40), error: XcmError::Trap(1) }
+	);
+}
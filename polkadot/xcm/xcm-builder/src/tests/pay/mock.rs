@@ -263,6 +263,7 @@ impl pallet_xcm::Config for Test {
 	type SovereignAccountOf = SovereignAccountOf;
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<RelayLocation>;
+	type NonFungibleLocker = ();
 	type MaxLockers = frame_support::traits::ConstU32<8>;
 	type MaxRemoteLockConsumers = frame_support::traits::ConstU32<0>;
 	type RemoteLockConsumerIdentifier = ();
@@ -219,6 +219,7 @@ impl pallet_xcm::Config for Runtime {
 	type SovereignAccountOf = ();
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<KsmLocation>;
+	type NonFungibleLocker = ();
 	type MaxLockers = frame_support::traits::ConstU32<8>;
 	type MaxRemoteLockConsumers = frame_support::traits::ConstU32<0>;
 	type RemoteLockConsumerIdentifier = ();
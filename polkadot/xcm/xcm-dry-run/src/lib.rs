@@ -0,0 +1,437 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-hop dry-running of XCM programs across a network of chains.
+//!
+//! The [`DryRunApi`](xcm_runtime_apis::dry_run::DryRunApi) only returns the effects of a call or
+//! an XCM program on a single chain, together with the messages it forwarded to other chains.
+//! [`Network`] follows each of these forwarded messages to the chain it was sent to and dry-runs
+//! it there, until no more messages are produced. The result is a [`Trace`] of every hop, with
+//! the execution and delivery fees paid on each of them.
+//!
+//! Chains are added to the network as implementations of [`Chain`]. [`WasmChain`] executes the
+//! runtime APIs of a runtime WASM blob on top of a state snapshot, so a whole network can be
+//! dry-run offline, e.g. in tests.
+//!
+//! Every hop is dry-run against the original state snapshot of its chain: effects of previous hops
+//! on the same chain are not visible to later ones. Messages sent to chains which are not part of
+//! the network are recorded in the trace, but not followed.
+
+use std::collections::VecDeque;
+
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use sp_weights::Weight;
+use xcm::{latest::prelude::*, VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm};
+use xcm_runtime_apis::{
+	dry_run::{
+		CallDryRunEffects, Error as DryRunError, InstructionTrace, TracedDryRunEffects,
+		XcmDryRunEffects,
+	},
+	fees::Error as FeesError,
+};
+
+mod wasm;
+
+pub use wasm::WasmChain;
+
+/// A SCALE-encoded runtime event of any chain of the network.
+pub type EncodedEvent = Vec<u8>;
+
+/// Default limit on the number of hops executed by a single dry-run.
+pub const DEFAULT_MAX_HOPS: usize = 32;
+
+/// Errors of a multi-hop dry-run.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Executing a runtime API failed.
+	#[error("Runtime API {0} failed: {1}")]
+	RuntimeApi(&'static str, String),
+	/// The result of a runtime API couldn't be decoded.
+	#[error("Failed to decode the result of runtime API {0}: {1}")]
+	Decode(&'static str, codec::Error),
+	/// The state snapshot of a chain is unusable.
+	#[error("Invalid state snapshot: {0}")]
+	InvalidState(&'static str),
+	/// The dry-run API returned an error.
+	#[error("Dry-run API error: {0:?}")]
+	DryRun(DryRunError),
+	/// The XCM payment API returned an error.
+	#[error("XCM payment API error: {0:?}")]
+	Fees(FeesError),
+	/// The chain the dry-run should start on isn't part of the network.
+	#[error("Chain {0:?} is not part of the network")]
+	UnknownChain(InteriorLocation),
+	/// A destination of a forwarded message couldn't be resolved.
+	#[error("Invalid destination {0:?}")]
+	InvalidDestination(VersionedLocation),
+	/// Forwarded messages didn't reach quiescence within the allowed number of hops.
+	#[error("Exceeded the limit of {0} hops")]
+	TooManyHops(usize),
+}
+
+/// A chain which is able to dry-run calls and XCM programs.
+///
+/// All methods mirror the runtime APIs of [`xcm_runtime_apis`]. Events are returned in their
+/// encoded form, since every chain of the network has its own event type.
+pub trait Chain {
+	/// Location of the chain within the universal consensus, e.g.
+	/// `[GlobalConsensus(Polkadot), Parachain(1000)]`.
+	fn universal_location(&self) -> InteriorLocation;
+
+	/// Dry-run `call` dispatched from `origin`, both of them SCALE-encoded runtime types.
+	///
+	/// The trace is empty if the chain doesn't support tracing.
+	fn dry_run_call(
+		&self,
+		origin: &[u8],
+		call: &[u8],
+	) -> Result<TracedDryRunEffects<CallDryRunEffects<EncodedEvent>>, Error>;
+
+	/// Dry-run `xcm` coming from `origin_location`.
+	///
+	/// The trace is empty if the chain doesn't support tracing.
+	fn dry_run_xcm(
+		&self,
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<TracedDryRunEffects<XcmDryRunEffects<EncodedEvent>>, Error>;
+
+	/// Returns the weight needed to execute `message`.
+	fn query_xcm_weight(&self, message: VersionedXcm<()>) -> Result<Weight, Error>;
+
+	/// Converts `weight` into a fee in `asset`.
+	fn query_weight_to_asset_fee(
+		&self,
+		weight: Weight,
+		asset: VersionedAssetId,
+	) -> Result<u128, Error>;
+
+	/// Returns the fees for delivering `message` to `destination`.
+	fn query_delivery_fees(
+		&self,
+		destination: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<VersionedAssets, Error>;
+}
+
+/// A message forwarded by a hop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forwarded {
+	/// The destination, as seen by the sending chain.
+	pub destination: Location,
+	/// The forwarded message.
+	pub message: VersionedXcm<()>,
+	/// Fees charged by the sending chain for delivering the message, if they could be queried.
+	pub delivery_fees: Option<VersionedAssets>,
+	/// Index of the [`Hop`] executing the message in [`Trace::hops`], `None` if the destination
+	/// isn't part of the network.
+	pub hop: Option<usize>,
+}
+
+/// The dry-run call a trace started with.
+#[derive(Debug)]
+pub struct CallHop {
+	/// Universal location of the chain which dispatched the call.
+	pub chain: InteriorLocation,
+	/// The result of dispatching the call.
+	pub execution_result: DispatchResultWithPostInfo,
+	/// Events emitted by the call.
+	pub emitted_events: Vec<EncodedEvent>,
+	/// The local XCM program executed by the call, if any.
+	pub local_xcm: Option<VersionedXcm<()>>,
+	/// The trace of the instructions executed by the call, if the chain supports it.
+	pub instructions: Vec<InstructionTrace>,
+	/// Messages forwarded by the call.
+	pub forwarded_xcms: Vec<Forwarded>,
+}
+
+/// The execution of a single message on a chain of the network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hop {
+	/// Universal location of the chain which executed the message.
+	pub chain: InteriorLocation,
+	/// The origin of the message, as seen by `chain`.
+	pub origin: Location,
+	/// The executed message.
+	pub message: VersionedXcm<()>,
+	/// The outcome of executing the message.
+	pub execution_result: Outcome,
+	/// Events emitted by executing the message.
+	pub emitted_events: Vec<EncodedEvent>,
+	/// Fees for executing the message, priced in the asset it uses to pay for its execution.
+	///
+	/// `None` if the message doesn't pay for its execution or the chain couldn't price it.
+	pub execution_fees: Option<Asset>,
+	/// The trace of the executed instructions, if the chain supports it.
+	pub instructions: Vec<InstructionTrace>,
+	/// Messages forwarded by executing the message.
+	pub forwarded_xcms: Vec<Forwarded>,
+}
+
+/// The trace of a multi-hop dry-run.
+#[derive(Debug)]
+pub struct Trace {
+	/// The initial call, if the dry-run started with one.
+	pub call: Option<CallHop>,
+	/// Every executed message, in the order they were executed in.
+	///
+	/// If the dry-run started with an XCM program, it is the first hop.
+	pub hops: Vec<Hop>,
+}
+
+impl Trace {
+	/// Returns whether every hop of the trace executed successfully.
+	pub fn is_complete(&self) -> bool {
+		self.call.as_ref().map_or(true, |call| call.execution_result.is_ok()) &&
+			self.hops
+				.iter()
+				.all(|hop| matches!(hop.execution_result, Outcome::Complete { .. }))
+	}
+
+	/// Returns all messages which were sent to chains that are not part of the network, together
+	/// with the universal location of the sending chain.
+	pub fn unrouted(&self) -> Vec<(&InteriorLocation, &Forwarded)> {
+		let call = self.call.iter().map(|call| (&call.chain, &call.forwarded_xcms));
+		let hops = self.hops.iter().map(|hop| (&hop.chain, &hop.forwarded_xcms));
+		call.chain(hops)
+			.flat_map(|(chain, forwarded)| forwarded.iter().map(move |f| (chain, f)))
+			.filter(|(_, forwarded)| forwarded.hop.is_none())
+			.collect()
+	}
+
+	/// Universal location of the chain which executed `parent`, `None` being the initial call.
+	fn sender(&self, parent: Option<usize>) -> &InteriorLocation {
+		match parent {
+			Some(index) => &self.hops[index].chain,
+			None =>
+				&self
+					.call
+					.as_ref()
+					.expect("`None` is only followed if there is a call; qed")
+					.chain,
+		}
+	}
+
+	/// Messages forwarded by `parent`, `None` being the initial call.
+	fn forwarded_mut(&mut self, parent: Option<usize>) -> &mut Vec<Forwarded> {
+		match parent {
+			Some(index) => &mut self.hops[index].forwarded_xcms,
+			None =>
+				&mut self
+					.call
+					.as_mut()
+					.expect("`None` is only followed if there is a call; qed")
+					.forwarded_xcms,
+		}
+	}
+}
+
+/// A network of chains, following forwarded messages from one chain to another.
+pub struct Network {
+	chains: Vec<Box<dyn Chain>>,
+	max_hops: usize,
+}
+
+impl Default for Network {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Network {
+	/// Create an empty network.
+	pub fn new() -> Self {
+		Self { chains: Vec::new(), max_hops: DEFAULT_MAX_HOPS }
+	}
+
+	/// Add `chain` to the network.
+	pub fn with_chain(mut self, chain: impl Chain + 'static) -> Self {
+		self.chains.push(Box::new(chain));
+		self
+	}
+
+	/// Limit the number of hops executed by a single dry-run to `max_hops`.
+	pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+		self.max_hops = max_hops;
+		self
+	}
+
+	/// Dry-run `call` dispatched from `origin` on `chain`, and every message it results in.
+	///
+	/// Both `origin` and `call` are SCALE-encoded runtime types of `chain`.
+	pub fn dry_run_call(
+		&self,
+		chain: &InteriorLocation,
+		origin: &[u8],
+		call: &[u8],
+	) -> Result<Trace, Error> {
+		let chain = self.chain(chain).ok_or_else(|| Error::UnknownChain(chain.clone()))?;
+		let TracedDryRunEffects { effects, trace: instructions } =
+			chain.dry_run_call(origin, call)?;
+		let universal_location = chain.universal_location();
+		let forwarded_xcms = self.forwarded(chain, effects.forwarded_xcms)?;
+		let mut trace = Trace {
+			call: Some(CallHop {
+				chain: universal_location,
+				execution_result: effects.execution_result,
+				emitted_events: effects.emitted_events,
+				local_xcm: effects.local_xcm,
+				instructions,
+				forwarded_xcms,
+			}),
+			hops: Vec::new(),
+		};
+		self.follow(&mut trace, None)?;
+		Ok(trace)
+	}
+
+	/// Dry-run `xcm` coming from `origin` on `chain`, and every message it results in.
+	pub fn dry_run_xcm(
+		&self,
+		chain: &InteriorLocation,
+		origin: Location,
+		xcm: VersionedXcm<()>,
+	) -> Result<Trace, Error> {
+		let chain = self.chain(chain).ok_or_else(|| Error::UnknownChain(chain.clone()))?;
+		let hop = self.execute(chain, origin, xcm)?;
+		let mut trace = Trace { call: None, hops: vec![hop] };
+		self.follow(&mut trace, Some(0))?;
+		Ok(trace)
+	}
+
+	/// Execute all messages forwarded by `start` and the hops following it, breadth-first.
+	fn follow(&self, trace: &mut Trace, start: Option<usize>) -> Result<(), Error> {
+		let mut pending = VecDeque::from([start]);
+		while let Some(parent) = pending.pop_front() {
+			let sender = trace.sender(parent).clone();
+			for index in 0..trace.forwarded_mut(parent).len() {
+				let Forwarded { destination, message, .. } =
+					trace.forwarded_mut(parent)[index].clone();
+				let Some(chain) = self.destination_chain(&sender, &destination)? else { continue };
+				if trace.hops.len() >= self.max_hops {
+					return Err(Error::TooManyHops(self.max_hops))
+				}
+				// The sender, as seen by the destination.
+				let origin = Location::here()
+					.reanchored(&destination, &sender)
+					.map_err(|_| Error::InvalidDestination(destination.clone().into()))?;
+				let hop = self.execute(chain, origin, message)?;
+				trace.hops.push(hop);
+				let hop_index = trace.hops.len() - 1;
+				trace.forwarded_mut(parent)[index].hop = Some(hop_index);
+				pending.push_back(Some(hop_index));
+			}
+		}
+		Ok(())
+	}
+
+	/// Dry-run `xcm` on `chain` and price its execution.
+	fn execute(
+		&self,
+		chain: &dyn Chain,
+		origin: Location,
+		xcm: VersionedXcm<()>,
+	) -> Result<Hop, Error> {
+		let TracedDryRunEffects { effects, trace: instructions } =
+			chain.dry_run_xcm(origin.clone().into(), xcm.clone())?;
+		let forwarded_xcms = self.forwarded(chain, effects.forwarded_xcms)?;
+		let execution_fees = Self::execution_fees(chain, &xcm);
+		Ok(Hop {
+			chain: chain.universal_location(),
+			origin,
+			message: xcm,
+			execution_result: effects.execution_result,
+			emitted_events: effects.emitted_events,
+			execution_fees,
+			instructions,
+			forwarded_xcms,
+		})
+	}
+
+	/// Flatten the messages forwarded by `chain` and price their delivery.
+	fn forwarded(
+		&self,
+		chain: &dyn Chain,
+		forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+	) -> Result<Vec<Forwarded>, Error> {
+		let mut forwarded = Vec::new();
+		for (destination, messages) in forwarded_xcms {
+			let location = Location::try_from(destination.clone())
+				.map_err(|()| Error::InvalidDestination(destination.clone()))?;
+			for message in messages {
+				let delivery_fees =
+					chain.query_delivery_fees(destination.clone(), message.clone()).ok();
+				forwarded.push(Forwarded {
+					destination: location.clone(),
+					message,
+					delivery_fees,
+					hop: None,
+				});
+			}
+		}
+		Ok(forwarded)
+	}
+
+	/// Price the execution of `xcm` on `chain` in the asset the message pays its fees with.
+	fn execution_fees(chain: &dyn Chain, xcm: &VersionedXcm<()>) -> Option<Asset> {
+		let message = Xcm::<()>::try_from(xcm.clone()).ok()?;
+		let fee_asset_id = message.iter().find_map(|instruction| match instruction {
+			BuyExecution { fees, .. } => Some(fees.id.clone()),
+			PayFees { asset } => Some(asset.id.clone()),
+			_ => None,
+		})?;
+		let weight = chain.query_xcm_weight(xcm.clone()).ok()?;
+		let amount = chain.query_weight_to_asset_fee(weight, fee_asset_id.clone().into()).ok()?;
+		Some((fee_asset_id, amount).into())
+	}
+
+	/// Find the chain `destination` refers to, as seen by `sender`.
+	fn destination_chain(
+		&self,
+		sender: &InteriorLocation,
+		destination: &Location,
+	) -> Result<Option<&dyn Chain>, Error> {
+		let target = Location::new(0, sender.clone())
+			.appended_with(destination.clone())
+			.map_err(|_| Error::InvalidDestination(destination.clone().into()))?;
+		if target.parent_count() > 0 {
+			return Err(Error::InvalidDestination(destination.clone().into()))
+		}
+		Ok(self.chain(target.interior()))
+	}
+
+	fn chain(&self, universal_location: &InteriorLocation) -> Option<&dyn Chain> {
+		self.chains
+			.iter()
+			.find(|chain| &chain.universal_location() == universal_location)
+			.map(|chain| chain.as_ref())
+	}
+}
+
+impl From<DryRunError> for Error {
+	fn from(error: DryRunError) -> Self {
+		Error::DryRun(error)
+	}
+}
+
+impl From<FeesError> for Error {
+	fn from(error: FeesError) -> Self {
+		Error::Fees(error)
+	}
+}
+
+#[cfg(test)]
+mod tests;
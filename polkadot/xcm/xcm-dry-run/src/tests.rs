@@ -0,0 +1,302 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use codec::{Decode, Encode};
+use frame_support::dispatch::PostDispatchInfo;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::BuildStorage;
+use westend_runtime_constants::currency::UNITS;
+
+/// A chain which forwards a fixed set of messages whenever it executes anything.
+struct MockChain {
+	universal_location: InteriorLocation,
+	forwards: Vec<(Location, Xcm<()>)>,
+}
+
+impl MockChain {
+	fn new(universal_location: impl Into<InteriorLocation>) -> Self {
+		Self { universal_location: universal_location.into(), forwards: Vec::new() }
+	}
+
+	fn forwarding(mut self, destination: impl Into<Location>, message: Xcm<()>) -> Self {
+		self.forwards.push((destination.into(), message));
+		self
+	}
+
+	fn forwarded_xcms(&self) -> Vec<(VersionedLocation, Vec<VersionedXcm<()>>)> {
+		self.forwards
+			.iter()
+			.map(|(destination, message)| {
+				(destination.clone().into(), vec![VersionedXcm::from(message.clone())])
+			})
+			.collect()
+	}
+}
+
+impl Chain for MockChain {
+	fn universal_location(&self) -> InteriorLocation {
+		self.universal_location.clone()
+	}
+
+	fn dry_run_call(
+		&self,
+		_origin: &[u8],
+		call: &[u8],
+	) -> Result<TracedDryRunEffects<CallDryRunEffects<EncodedEvent>>, Error> {
+		let effects = CallDryRunEffects {
+			execution_result: Ok(PostDispatchInfo::default()),
+			emitted_events: vec![call.to_vec()],
+			local_xcm: None,
+			forwarded_xcms: self.forwarded_xcms(),
+		};
+		Ok(TracedDryRunEffects { effects, trace: Vec::new() })
+	}
+
+	fn dry_run_xcm(
+		&self,
+		_origin_location: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<TracedDryRunEffects<XcmDryRunEffects<EncodedEvent>>, Error> {
+		let weight = self.query_xcm_weight(xcm)?;
+		let effects = XcmDryRunEffects {
+			execution_result: Outcome::Complete { used: weight },
+			emitted_events: Vec::new(),
+			forwarded_xcms: self.forwarded_xcms(),
+		};
+		Ok(TracedDryRunEffects { effects, trace: Vec::new() })
+	}
+
+	fn query_xcm_weight(&self, message: VersionedXcm<()>) -> Result<Weight, Error> {
+		let message =
+			Xcm::<()>::try_from(message).map_err(|()| FeesError::VersionedConversionFailed)?;
+		Ok(Weight::from_parts(10 * message.len() as u64, 0))
+	}
+
+	fn query_weight_to_asset_fee(
+		&self,
+		weight: Weight,
+		_asset: VersionedAssetId,
+	) -> Result<u128, Error> {
+		Ok(weight.ref_time() as u128)
+	}
+
+	fn query_delivery_fees(
+		&self,
+		_destination: VersionedLocation,
+		_message: VersionedXcm<()>,
+	) -> Result<VersionedAssets, Error> {
+		Ok((Here, 1u128).into())
+	}
+}
+
+fn relay() -> InteriorLocation {
+	[GlobalConsensus(Polkadot)].into()
+}
+
+fn para(id: u32) -> InteriorLocation {
+	[GlobalConsensus(Polkadot), Parachain(id)].into()
+}
+
+fn paid_message() -> Xcm<()> {
+	Xcm(vec![
+		WithdrawAsset((Parent, 100u128).into()),
+		PayFees { asset: (Parent, 10u128).into() },
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: Parent.into() },
+	])
+}
+
+#[test]
+fn dry_run_follows_forwarded_messages_until_quiescence() {
+	// relay -> 1000 -> 2000
+	let network = Network::new()
+		.with_chain(MockChain::new(relay()).forwarding(Parachain(1000), Xcm(vec![ClearOrigin])))
+		.with_chain(
+			MockChain::new(para(1000)).forwarding((Parent, Parachain(2000)), paid_message()),
+		)
+		.with_chain(MockChain::new(para(2000)));
+
+	let trace = network.dry_run_call(&relay(), &[], &[42]).unwrap();
+	assert!(trace.is_complete());
+
+	let call = trace.call.as_ref().unwrap();
+	assert_eq!(call.chain, relay());
+	assert_eq!(call.emitted_events, vec![vec![42]]);
+	assert_eq!(call.forwarded_xcms.len(), 1);
+	assert_eq!(call.forwarded_xcms[0].delivery_fees, Some((Here, 1u128).into()));
+	assert_eq!(call.forwarded_xcms[0].hop, Some(0));
+
+	assert_eq!(trace.hops.len(), 2);
+	let first = &trace.hops[0];
+	assert_eq!(first.chain, para(1000));
+	assert_eq!(first.origin, Parent.into());
+	// `ClearOrigin` doesn't pay for execution.
+	assert_eq!(first.execution_fees, None);
+	assert_eq!(first.forwarded_xcms[0].hop, Some(1));
+
+	let second = &trace.hops[1];
+	assert_eq!(second.chain, para(2000));
+	assert_eq!(second.origin, (Parent, Parachain(1000)).into());
+	assert_eq!(second.message, VersionedXcm::from(paid_message()));
+	assert_eq!(second.execution_fees, Some((Parent, 30u128).into()));
+	assert!(second.forwarded_xcms.is_empty());
+	assert!(trace.unrouted().is_empty());
+}
+
+#[test]
+fn dry_run_records_messages_to_unknown_chains() {
+	let network = Network::new().with_chain(
+		MockChain::new(para(1000))
+			.forwarding((Parent, Parachain(3000)), paid_message())
+			.forwarding(Location::new(2, [GlobalConsensus(Kusama)]), Xcm(vec![ClearOrigin])),
+	);
+
+	let trace = network.dry_run_xcm(&para(1000), Parent.into(), paid_message().into()).unwrap();
+	assert_eq!(trace.hops.len(), 1);
+	assert_eq!(trace.hops[0].execution_fees, Some((Parent, 30u128).into()));
+	let unrouted = trace.unrouted();
+	assert_eq!(unrouted.len(), 2);
+	assert_eq!(unrouted[0].0, &para(1000));
+	assert_eq!(unrouted[0].1.destination, (Parent, Parachain(3000)).into());
+	assert_eq!(unrouted[1].1.destination, Location::new(2, [GlobalConsensus(Kusama)]).into());
+}
+
+#[test]
+fn dry_run_on_unknown_chain_fails() {
+	let network = Network::new().with_chain(MockChain::new(relay()));
+	assert!(matches!(
+		network.dry_run_call(&para(1000), &[], &[]),
+		Err(Error::UnknownChain(chain)) if chain == para(1000)
+	));
+}
+
+#[test]
+fn dry_run_is_bounded_by_max_hops() {
+	// 1000 and 2000 keep pinging each other.
+	let network = Network::new()
+		.with_chain(
+			MockChain::new(para(1000))
+				.forwarding((Parent, Parachain(2000)), Xcm(vec![ClearOrigin])),
+		)
+		.with_chain(
+			MockChain::new(para(2000))
+				.forwarding((Parent, Parachain(1000)), Xcm(vec![ClearOrigin])),
+		)
+		.with_max_hops(5);
+
+	assert!(matches!(
+		network.dry_run_xcm(&para(1000), Parent.into(), Xcm::<()>(vec![ClearOrigin]).into()),
+		Err(Error::TooManyHops(5))
+	));
+}
+
+/// Westend at block 1, with Alice holding 1000 WND.
+///
+/// Events are not recorded at genesis, so the snapshot pretends the genesis block was imported.
+fn westend() -> WasmChain<westend_runtime::RuntimeEvent> {
+	let code = westend_runtime::WASM_BINARY.expect("Wasm binary must be built for testing");
+	let mut storage = westend_runtime::RuntimeGenesisConfig {
+		balances: westend_runtime::BalancesConfig {
+			balances: vec![(Sr25519Keyring::Alice.to_account_id(), 1000 * UNITS)],
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap();
+	storage.top.insert(
+		frame_system::Number::<westend_runtime::Runtime>::hashed_key().to_vec(),
+		1u32.encode(),
+	);
+	WasmChain::new([GlobalConsensus(Westend)], code.to_vec(), storage)
+}
+
+/// Balance transfers among `events` of Westend.
+fn transferred(
+	events: &[EncodedEvent],
+) -> Vec<(sp_runtime::AccountId32, sp_runtime::AccountId32, u128)> {
+	events
+		.iter()
+		.filter_map(|event| match westend_runtime::RuntimeEvent::decode(&mut &event[..]).unwrap() {
+			westend_runtime::RuntimeEvent::Balances(pallet_balances::Event::Transfer {
+				from,
+				to,
+				amount,
+			}) => Some((from, to, amount)),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn wasm_chain_dry_runs_transfer_call() {
+	let network = Network::new().with_chain(westend());
+	let origin = westend_runtime::OriginCaller::system(frame_system::RawOrigin::Signed(
+		Sr25519Keyring::Alice.to_account_id(),
+	));
+	let call = westend_runtime::RuntimeCall::Balances(
+		westend_runtime::BalancesCall::transfer_keep_alive {
+			dest: Sr25519Keyring::Bob.to_account_id().into(),
+			value: 10 * UNITS,
+		},
+	);
+
+	let trace = network
+		.dry_run_call(&[GlobalConsensus(Westend)].into(), &origin.encode(), &call.encode())
+		.unwrap();
+	assert!(trace.is_complete());
+	assert!(trace.hops.is_empty());
+	let call = trace.call.unwrap();
+	assert_eq!(call.chain, [GlobalConsensus(Westend)].into());
+	assert!(call.forwarded_xcms.is_empty());
+	assert_eq!(
+		transferred(&call.emitted_events),
+		vec![(
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			10 * UNITS
+		)]
+	);
+}
+
+#[test]
+fn wasm_chain_dry_runs_transfer_program() {
+	let network = Network::new().with_chain(westend());
+	let alice = Location::new(
+		0,
+		[AccountId32 { network: None, id: Sr25519Keyring::Alice.to_raw_public() }],
+	);
+	let bob =
+		Location::new(0, [AccountId32 { network: None, id: Sr25519Keyring::Bob.to_raw_public() }]);
+	let message = Xcm::<()>(vec![
+		WithdrawAsset((Here, 10 * UNITS).into()),
+		BuyExecution { fees: (Here, UNITS).into(), weight_limit: Unlimited },
+		DepositAsset { assets: AllCounted(1).into(), beneficiary: bob },
+	]);
+
+	let trace = network
+		.dry_run_xcm(&[GlobalConsensus(Westend)].into(), alice, message.into())
+		.unwrap();
+	assert!(trace.is_complete());
+	assert_eq!(trace.hops.len(), 1);
+	let hop = &trace.hops[0];
+	assert!(hop.forwarded_xcms.is_empty());
+	// Westend charges for the execution of the program in its native token.
+	let Some(Asset { id, fun: Fungible(fees) }) = hop.execution_fees.clone() else {
+		panic!("Execution fees should be priced in WND: {:?}", hop.execution_fees)
+	};
+	assert_eq!(id, Here.into());
+	assert!(fees > 0 && fees <= UNITS);
+}
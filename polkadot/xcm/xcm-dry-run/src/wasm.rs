@@ -0,0 +1,205 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A [`Chain`] executing the runtime APIs of a WASM runtime on top of a state snapshot.
+
+use core::marker::PhantomData;
+
+use codec::{Decode, Encode};
+use sc_executor::WasmExecutor;
+use sp_api::RuntimeApiInfo;
+use sp_core::{
+	storage::{well_known_keys, Storage},
+	traits::CallContext,
+};
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic, StateVersion};
+use sp_state_machine::{
+	backend::BackendRuntimeCode, InMemoryBackend, OverlayedChanges, StateMachine,
+};
+use sp_version::RuntimeVersion;
+use sp_weights::Weight;
+use xcm::{
+	latest::InteriorLocation, VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm,
+};
+use xcm_runtime_apis::{
+	dry_run::{
+		CallDryRunEffects, DryRunApi, Error as DryRunError, TracedDryRunEffects, XcmDryRunEffects,
+	},
+	fees::Error as FeesError,
+};
+
+use crate::{Chain, EncodedEvent, Error};
+
+/// Any block type, only used to name the runtime APIs.
+type AnyBlock = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// First version of the [`DryRunApi`] returning the trace of the executed instructions.
+const DRY_RUN_API_TRACING_VERSION: u32 = 2;
+
+/// A chain defined by its runtime WASM blob and a snapshot of its state.
+///
+/// `Event` is the runtime event type of the chain, used to decode the results of the dry-run API.
+///
+/// Runtime APIs are executed in an offchain context on a fresh overlay, so nothing is ever written
+/// to the snapshot.
+pub struct WasmChain<Event> {
+	universal_location: InteriorLocation,
+	backend: InMemoryBackend<BlakeTwo256>,
+	executor: WasmExecutor<sp_io::SubstrateHostFunctions>,
+	_phantom: PhantomData<Event>,
+}
+
+impl<Event> WasmChain<Event> {
+	/// Create a chain located at `universal_location`, running `code` on top of `storage`.
+	///
+	/// `code` replaces any runtime code found in `storage`.
+	pub fn new(
+		universal_location: impl Into<InteriorLocation>,
+		code: Vec<u8>,
+		mut storage: Storage,
+	) -> Self {
+		storage.top.insert(well_known_keys::CODE.to_vec(), code);
+		let backend = (storage, StateVersion::V1).into();
+		// Parachain runtimes import host functions which are only used while building blocks.
+		let executor = WasmExecutor::builder().with_allow_missing_host_functions(true).build();
+		Self {
+			universal_location: universal_location.into(),
+			backend,
+			executor,
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Call the runtime API `method` with SCALE-encoded `args` and decode its result.
+	fn call<R: Decode>(&self, method: &'static str, args: &[u8]) -> Result<R, Error> {
+		let runtime_code = BackendRuntimeCode::new(&self.backend)
+			.runtime_code()
+			.map_err(Error::InvalidState)?;
+		let mut overlay = OverlayedChanges::default();
+		let result = StateMachine::new(
+			&self.backend,
+			&mut overlay,
+			&self.executor,
+			method,
+			args,
+			&mut Default::default(),
+			&runtime_code,
+			CallContext::Offchain,
+		)
+		.execute()
+		.map_err(|error| Error::RuntimeApi(method, error.to_string()))?;
+		R::decode(&mut &result[..]).map_err(|error| Error::Decode(method, error))
+	}
+
+	/// Whether the runtime's [`DryRunApi`] returns the trace of the executed instructions.
+	fn supports_tracing(&self) -> Result<bool, Error> {
+		let version = self.call::<RuntimeVersion>("Core_version", &[])?;
+		Ok(version.has_api_with(&<dyn DryRunApi<AnyBlock, (), (), ()>>::ID, |version| {
+			version >= DRY_RUN_API_TRACING_VERSION
+		}))
+	}
+}
+
+impl<Event: Decode + Encode> Chain for WasmChain<Event> {
+	fn universal_location(&self) -> InteriorLocation {
+		self.universal_location.clone()
+	}
+
+	fn dry_run_call(
+		&self,
+		origin: &[u8],
+		call: &[u8],
+	) -> Result<TracedDryRunEffects<CallDryRunEffects<EncodedEvent>>, Error> {
+		// Arguments of runtime APIs are encoded as a tuple, which is their concatenation.
+		let args = [origin, call].concat();
+		let TracedDryRunEffects { effects, trace } = if self.supports_tracing()? {
+			self.call::<Result<TracedDryRunEffects<CallDryRunEffects<Event>>, DryRunError>>(
+				"DryRunApi_dry_run_call_with_trace",
+				&args,
+			)??
+		} else {
+			let effects = self.call::<Result<CallDryRunEffects<Event>, DryRunError>>(
+				"DryRunApi_dry_run_call",
+				&args,
+			)??;
+			TracedDryRunEffects { effects, trace: Vec::new() }
+		};
+		let effects = CallDryRunEffects {
+			execution_result: effects.execution_result,
+			emitted_events: effects.emitted_events.iter().map(Encode::encode).collect(),
+			local_xcm: effects.local_xcm,
+			forwarded_xcms: effects.forwarded_xcms,
+		};
+		Ok(TracedDryRunEffects { effects, trace })
+	}
+
+	fn dry_run_xcm(
+		&self,
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<TracedDryRunEffects<XcmDryRunEffects<EncodedEvent>>, Error> {
+		// `VersionedXcm<()>` has the same encoding as the `VersionedXcm<RuntimeCall>` expected by
+		// the runtime, since calls are kept encoded within `Transact`.
+		let args = (origin_location, xcm).encode();
+		let TracedDryRunEffects { effects, trace } = if self.supports_tracing()? {
+			self.call::<Result<TracedDryRunEffects<XcmDryRunEffects<Event>>, DryRunError>>(
+				"DryRunApi_dry_run_xcm_with_trace",
+				&args,
+			)??
+		} else {
+			let effects = self.call::<Result<XcmDryRunEffects<Event>, DryRunError>>(
+				"DryRunApi_dry_run_xcm",
+				&args,
+			)??;
+			TracedDryRunEffects { effects, trace: Vec::new() }
+		};
+		let effects = XcmDryRunEffects {
+			execution_result: effects.execution_result,
+			emitted_events: effects.emitted_events.iter().map(Encode::encode).collect(),
+			forwarded_xcms: effects.forwarded_xcms,
+		};
+		Ok(TracedDryRunEffects { effects, trace })
+	}
+
+	fn query_xcm_weight(&self, message: VersionedXcm<()>) -> Result<Weight, Error> {
+		Ok(self.call::<Result<Weight, FeesError>>(
+			"XcmPaymentApi_query_xcm_weight",
+			&message.encode(),
+		)??)
+	}
+
+	fn query_weight_to_asset_fee(
+		&self,
+		weight: Weight,
+		asset: VersionedAssetId,
+	) -> Result<u128, Error> {
+		Ok(self.call::<Result<u128, FeesError>>(
+			"XcmPaymentApi_query_weight_to_asset_fee",
+			&(weight, asset).encode(),
+		)??)
+	}
+
+	fn query_delivery_fees(
+		&self,
+		destination: VersionedLocation,
+		message: VersionedXcm<()>,
+	) -> Result<VersionedAssets, Error> {
+		Ok(self.call::<Result<VersionedAssets, FeesError>>(
+			"XcmPaymentApi_query_delivery_fees",
+			&(destination, message).encode(),
+		)??)
+	}
+}
@@ -40,7 +40,7 @@ use traits::{
 	XcmAssetTransfers,
 };
 
-pub use traits::RecordXcm;
+pub use traits::{InstructionTrace, RecordXcm};
 
 mod assets;
 pub use assets::AssetsInHolding;
@@ -86,6 +86,20 @@ pub struct XcmExecutor<Config: config::Config> {
 	/// Asset provided in last `BuyExecution` instruction (if any) in current XCM program. Same
 	/// asset type will be used for paying any potential delivery fees incurred by the program.
 	asset_used_for_fees: Option<AssetId>,
+	/// The weight of the whole message, which is paid for by the `PayFees` instruction.
+	message_weight: Weight,
+	/// The Fees Register. Holds the fees, left after paying for execution with `PayFees`. These
+	/// are used to pay delivery fees and are returned to Holding at the end of execution.
+	fees: AssetsInHolding,
+	/// Whether the `PayFees` instruction has already been processed.
+	already_paid_fees: bool,
+	/// The Asset Claimer Register. Assets trapped by the message are claimable by this location,
+	/// if set.
+	asset_claimer: Option<Location>,
+	/// Whether executed instructions are traced, see [`RecordXcm::should_trace`].
+	tracing: bool,
+	/// The fees charged by the instruction being executed. Only kept track of when tracing.
+	fees_charged: AssetsInHolding,
 	_config: PhantomData<Config>,
 }
 
@@ -181,6 +195,24 @@ impl<Config: config::Config> XcmExecutor<Config> {
 	pub fn set_topic(&mut self, v: Option<[u8; 32]>) {
 		self.context.topic = v;
 	}
+	pub fn fees(&self) -> &AssetsInHolding {
+		&self.fees
+	}
+	pub fn set_fees(&mut self, v: AssetsInHolding) {
+		self.fees = v
+	}
+	pub fn message_weight(&self) -> &Weight {
+		&self.message_weight
+	}
+	pub fn set_message_weight(&mut self, v: Weight) {
+		self.message_weight = v
+	}
+	pub fn already_paid_fees(&self) -> bool {
+		self.already_paid_fees
+	}
+	pub fn asset_claimer(&self) -> &Option<Location> {
+		&self.asset_claimer
+	}
 }
 
 pub struct WeighedMessage<Call>(Weight, Xcm<Call>);
@@ -249,6 +281,8 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 		*id = properties.message_id.unwrap_or(*id);
 
 		let mut vm = Self::new(origin, *id);
+		vm.message_weight = xcm_weight;
+		vm.tracing = Config::XcmRecorder::should_trace();
 
 		while !message.0.is_empty() {
 			let result = vm.process(message);
@@ -323,6 +357,12 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			transact_status: Default::default(),
 			fees_mode: FeesMode { jit_withdraw: false },
 			asset_used_for_fees: None,
+			message_weight: Weight::zero(),
+			fees: AssetsInHolding::new(),
+			already_paid_fees: false,
+			asset_claimer: None,
+			tracing: false,
+			fees_charged: AssetsInHolding::new(),
 			_config: PhantomData,
 		}
 	}
@@ -338,6 +378,10 @@ impl<Config: config::Config> XcmExecutor<Config> {
 
 		let mut weight_used = xcm_weight.saturating_sub(self.total_surplus);
 
+		// Any fees that haven't been used are returned to holding.
+		let unused_fees = core::mem::replace(&mut self.fees, AssetsInHolding::new());
+		self.holding.subsume_assets(unused_fees);
+
 		if !self.holding.is_empty() {
 			tracing::trace!(
 				target: "xcm::post_process",
@@ -346,7 +390,11 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				original_origin = ?self.original_origin,
 				"Trapping assets in holding register",
 			);
-			let effective_origin = self.context.origin.as_ref().unwrap_or(&self.original_origin);
+			let effective_origin = self
+				.asset_claimer
+				.as_ref()
+				.or(self.context.origin.as_ref())
+				.unwrap_or(&self.original_origin);
 			let trap_weight =
 				Config::AssetTrap::drop_assets(effective_origin, self.holding, &self.context);
 			weight_used.saturating_accrue(trap_weight);
@@ -505,8 +553,10 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			tracing::trace!(target: "xcm::fees", ?asset_needed_for_fees);
 			asset_to_pay_for_fees.clone().into()
 		} else {
-			let assets_taken_from_holding_to_pay_delivery_fees = self
-				.holding
+			// If `PayFees` was called, delivery fees are paid from the Fees Register.
+			let fees_register =
+				if self.fees.is_empty() { &mut self.holding } else { &mut self.fees };
+			let assets_taken_from_holding_to_pay_delivery_fees = fees_register
 				.try_take(asset_to_pay_for_fees.clone().into())
 				.map_err(|_| XcmError::NotHoldingFees)?;
 			tracing::trace!(target: "xcm::fees", ?assets_taken_from_holding_to_pay_delivery_fees);
@@ -538,10 +588,43 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			// We just use the assets withdrawn or taken from holding.
 			withdrawn_fee_asset.into()
 		};
+		if self.tracing {
+			self.fees_charged.subsume_assets(paid.clone().into());
+		}
 		Config::FeeManager::handle_fee(paid, Some(&self.context), reason);
 		Ok(())
 	}
 
+	/// Keep track of the fees charged out of `max_fee`, of which `unspent` was left.
+	///
+	/// `max_fee` is only given when tracing.
+	fn note_fees_charged(&mut self, max_fee: Option<AssetsInHolding>, unspent: &AssetsInHolding) {
+		let Some(mut max_fee) = max_fee else { return };
+		for asset in unspent.assets_iter() {
+			max_fee.saturating_take(asset.into());
+		}
+		self.fees_charged.subsume_assets(max_fee);
+	}
+
+	/// Record the trace of the instruction at `index`, which was just executed.
+	fn trace_instruction(
+		&mut self,
+		index: u32,
+		instruction: Instruction<()>,
+		weight_used: Weight,
+		error: Option<XcmError>,
+	) {
+		let fees_charged = core::mem::replace(&mut self.fees_charged, AssetsInHolding::new());
+		Config::XcmRecorder::record_instruction(InstructionTrace {
+			index,
+			instruction,
+			holding: self.holding.clone().into(),
+			weight_used,
+			fees_charged: fees_charged.into(),
+			error,
+		});
+	}
+
 	/// Calculates the amount of `self.asset_used_for_fees` required to swap for
 	/// `asset_needed_for_fees`.
 	///
@@ -651,6 +734,12 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		for (i, instr) in xcm.0.into_iter().enumerate() {
 			match &mut result {
 				r @ Ok(()) => {
+					// Only pay the cost of tracing when asked to, which should only be when
+					// dry-running.
+					let traced = self.tracing.then(|| {
+						let weight = Config::Weigher::instr_weight(&instr).unwrap_or_default();
+						(Instruction::<()>::from(instr.clone()), weight, self.total_surplus)
+					});
 					// Initialize the recursion count only the first time we hit this code in our
 					// potential recursive execution.
 					let inst_res = recursion_count::using_once(&mut 1, || {
@@ -674,6 +763,15 @@ impl<Config: config::Config> XcmExecutor<Config> {
 
 						self.process_instruction(instr)
 					});
+					if let Some((instruction, weight, surplus_before)) = traced {
+						let surplus = self.total_surplus.saturating_sub(surplus_before);
+						self.trace_instruction(
+							i as u32,
+							instruction,
+							weight.saturating_sub(surplus),
+							inst_res.clone().err(),
+						);
+					}
 					if let Err(e) = inst_res {
 						tracing::trace!(target: "xcm::execute", "!!! ERROR: {:?}", e);
 						*r = Err(ExecutorError {
@@ -917,13 +1015,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				);
 				Ok(())
 			},
-			DescendOrigin(who) => self
-				.context
-				.origin
-				.as_mut()
-				.ok_or(XcmError::BadOrigin)?
-				.append_with(who)
-				.map_err(|_| XcmError::LocationFull),
+			DescendOrigin(who) => self.do_descend_origin(who),
 			ClearOrigin => {
 				self.context.origin = None;
 				Ok(())
@@ -963,20 +1055,22 @@ impl<Config: config::Config> XcmExecutor<Config> {
 					message_to_weigh.extend(xcm.0.clone().into_iter());
 					let (_, fee) =
 						validate_send::<Config::XcmSender>(dest.clone(), Xcm(message_to_weigh))?;
-					let maybe_delivery_fee = fee.get(0).map(|asset_needed_for_fees| {
-						tracing::trace!(
-							target: "xcm::DepositReserveAsset",
-							"Asset provided to pay for fees {:?}, asset required for delivery fees: {:?}",
-							self.asset_used_for_fees, asset_needed_for_fees,
-						);
-						let asset_to_pay_for_fees =
-							self.calculate_asset_for_delivery_fees(asset_needed_for_fees.clone());
-						// set aside fee to be charged by XcmSender
-						let delivery_fee =
-							self.holding.saturating_take(asset_to_pay_for_fees.into());
-						tracing::trace!(target: "xcm::DepositReserveAsset", ?delivery_fee);
-						delivery_fee
-					});
+					// If `PayFees` was called, delivery fees are paid from the Fees Register.
+					let maybe_delivery_fee =
+						fee.get(0).filter(|_| self.fees.is_empty()).map(|asset_needed_for_fees| {
+							tracing::trace!(
+								target: "xcm::DepositReserveAsset",
+								"Asset provided to pay for fees {:?}, asset required for delivery fees: {:?}",
+								self.asset_used_for_fees, asset_needed_for_fees,
+							);
+							let asset_to_pay_for_fees = self
+								.calculate_asset_for_delivery_fees(asset_needed_for_fees.clone());
+							// set aside fee to be charged by XcmSender
+							let delivery_fee =
+								self.holding.saturating_take(asset_to_pay_for_fees.into());
+							tracing::trace!(target: "xcm::DepositReserveAsset", ?delivery_fee);
+							delivery_fee
+						});
 					// now take assets to deposit (after having taken delivery fees)
 					let deposited = self.holding.saturating_take(assets);
 					tracing::trace!(target: "xcm::DepositReserveAsset", ?deposited, "Assets except delivery fee");
@@ -1075,8 +1169,10 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				// pay for `weight` using up to `fees` of the holding register.
 				let max_fee =
 					self.holding.try_take(fees.into()).map_err(|_| XcmError::NotHoldingFees)?;
+				let traced_fee = self.tracing.then(|| max_fee.clone());
 				let result = || -> Result<(), XcmError> {
 					let unspent = self.trader.buy_weight(weight, max_fee, &self.context)?;
+					self.note_fees_charged(traced_fee, &unspent);
 					self.holding.subsume_assets(unspent);
 					Ok(())
 				}();
@@ -1374,7 +1470,154 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				Config::TransactionalProcessor::process(|| {
 					Config::HrmpChannelClosingHandler::handle(initiator, sender, recipient)
 				}),
+			PayFees { asset } => {
+				// Fees are only paid once per message.
+				if self.already_paid_fees {
+					return Ok(())
+				}
+				self.already_paid_fees = true;
+				let old_holding = self.holding.clone();
+				// Save the asset being used for execution fees, so we later know what should be
+				// used for delivery fees.
+				self.asset_used_for_fees = Some(asset.id.clone());
+				// Pay for the weight of the whole message using up to `asset` of the holding
+				// register.
+				let max_fee =
+					self.holding.try_take(asset.into()).map_err(|_| XcmError::NotHoldingFees)?;
+				let traced_fee = self.tracing.then(|| max_fee.clone());
+				let result = || -> Result<(), XcmError> {
+					let unspent =
+						self.trader.buy_weight(self.message_weight, max_fee, &self.context)?;
+					self.note_fees_charged(traced_fee, &unspent);
+					// Unspent fees are kept in the Fees Register to pay for delivery.
+					self.fees.subsume_assets(unspent);
+					Ok(())
+				}();
+				if result.is_err() {
+					self.holding = old_holding;
+				}
+				result
+			},
+			InitiateTransfer { destination, remote_fees, preserve_origin, assets, remote_xcm } => {
+				let old_holding = self.holding.clone();
+				let result = Config::TransactionalProcessor::process(|| {
+					let mut message = Vec::with_capacity(assets.len() + remote_xcm.len() + 3);
+					// Fees are transferred first, so the remote message is able to pay for its
+					// execution right after loading them.
+					match remote_fees {
+						Some(fees_filter) => {
+							let (fees, load_fees) = self
+								.transfer_from_holding(fees_filter, &destination)?
+								.ok_or(XcmError::NotHoldingFees)?;
+							// Only a single asset may be used to pay remote fees.
+							let fees = match fees.inner().as_slice() {
+								[fees] => fees.clone(),
+								_ => return Err(XcmError::NotHoldingFees),
+							};
+							message.push(load_fees);
+							message.push(PayFees { asset: fees });
+						},
+						None => message
+							.push(UnpaidExecution { weight_limit: Unlimited, check_origin: None }),
+					}
+					for filter in assets {
+						if let Some((_, load_assets)) =
+							self.transfer_from_holding(filter, &destination)?
+						{
+							message.push(load_assets);
+						}
+					}
+					if preserve_origin {
+						let origin = self.cloned_origin().ok_or(XcmError::BadOrigin)?;
+						let (remote_origin, _) = Self::try_reanchor(origin, &destination)?;
+						message.push(AliasOrigin(remote_origin));
+					} else {
+						message.push(ClearOrigin);
+					}
+					message.extend(remote_xcm.0.into_iter());
+					self.send(destination, Xcm(message), FeeReason::InitiateTransfer)?;
+					Ok(())
+				});
+				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
+					self.holding = old_holding;
+				}
+				result
+			},
+			ExecuteWithOrigin { descendant_origin, xcm } => {
+				let previous_origin = self.context.origin.clone();
+				match descendant_origin {
+					Some(who) => self.do_descend_origin(who)?,
+					None => self.context.origin = None,
+				}
+				let result = self.process(xcm).map_err(|error| {
+					tracing::trace!(
+						target: "xcm::process_instruction::execute_with_origin",
+						?error,
+						"Inner message failed",
+					);
+					error.xcm_error
+				});
+				// The origin is restored even if the inner message has failed.
+				self.context.origin = previous_origin;
+				result
+			},
+			SetAssetClaimer { location } => {
+				self.asset_claimer = Some(location);
+				Ok(())
+			},
+		}
+	}
+
+	/// Append `who` to the Origin register.
+	fn do_descend_origin(&mut self, who: InteriorLocation) -> XcmResult {
+		self.context
+			.origin
+			.as_mut()
+			.ok_or(XcmError::BadOrigin)?
+			.append_with(who)
+			.map_err(|_| XcmError::LocationFull)
+	}
+
+	/// Take `filter` assets from holding and transfer them to `destination` as specified by the
+	/// transfer type of `filter`. Returns the (reanchored to `destination`) transferred assets and
+	/// the instruction which loads them into holding at the `destination`.
+	///
+	/// Returns `None` if no assets in holding are matching the filter.
+	///
+	/// This function can write into storage and also return an error at the same time, it should
+	/// always be called within a transactional context.
+	fn transfer_from_holding(
+		&mut self,
+		filter: AssetTransferFilter,
+		destination: &Location,
+	) -> Result<Option<(Assets, Instruction<()>)>, XcmError> {
+		let assets = self.holding.saturating_take(filter.inner().clone());
+		if assets.is_empty() {
+			return Ok(None)
 		}
+		let load_instruction: fn(Assets) -> Instruction<()> = match filter {
+			AssetTransferFilter::Teleport(_) => {
+				for asset in assets.assets_iter() {
+					// We should check that the asset can actually be teleported out (for this
+					// to be in error, there would need to be an accounting violation by
+					// ourselves, so it's unlikely, but we don't want to allow that kind of bug
+					// to leak into a trusted chain.
+					Config::AssetTransactor::can_check_out(destination, &asset, &self.context)?;
+					Config::AssetTransactor::check_out(destination, &asset, &self.context);
+				}
+				ReceiveTeleportedAsset
+			},
+			AssetTransferFilter::ReserveDeposit(_) => {
+				self.deposit_assets_with_retry(&assets, destination)?;
+				ReserveAssetDeposited
+			},
+			// Assets are burnt here; they're withdrawn from our sovereign account at the reserve.
+			AssetTransferFilter::ReserveWithdraw(_) => WithdrawAsset,
+		};
+		// Note that we pass `None` as `maybe_failed_bin` and drop any assets which cannot be
+		// reanchored because we have already transferred all assets.
+		let reanchored_assets = Self::reanchored(assets, destination, None);
+		Ok(Some((reanchored_assets.clone(), load_instruction(reanchored_assets))))
 	}
 
 	/// Deposit `to_deposit` assets to `beneficiary`, without giving up on the first (transient)
@@ -138,3 +138,30 @@ impl AssetLock for () {
 		Err(LockError::NotApplicable)
 	}
 }
+
+/// Means of locking non-fungible assets owned by a local account, so they can't be moved until
+/// they're unlocked again.
+///
+/// Used by [`AssetLock`] implementations supporting non-fungible assets.
+pub trait LockNonFungible<AccountId> {
+	/// Ensure `asset` is a non-fungible owned by `who` which can currently be locked.
+	fn can_lock(who: &AccountId, asset: &Asset) -> Result<(), LockError>;
+
+	/// Lock `asset`, owned by `who`.
+	fn lock(who: &AccountId, asset: &Asset) -> Result<(), LockError>;
+
+	/// Unlock `asset`, owned by `who`.
+	fn unlock(who: &AccountId, asset: &Asset) -> Result<(), LockError>;
+}
+
+impl<AccountId> LockNonFungible<AccountId> for () {
+	fn can_lock(_: &AccountId, _: &Asset) -> Result<(), LockError> {
+		Err(LockError::Unimplemented)
+	}
+	fn lock(_: &AccountId, _: &Asset) -> Result<(), LockError> {
+		Err(LockError::Unimplemented)
+	}
+	fn unlock(_: &AccountId, _: &Asset) -> Result<(), LockError> {
+		Err(LockError::Unimplemented)
+	}
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use alloc::{vec, vec::Vec};
 use core::{marker::PhantomData, result::Result};
 use frame_support::traits::{Contains, OriginTrait};
 use sp_runtime::{traits::Dispatchable, DispatchErrorWithPostInfo};
@@ -38,6 +39,35 @@ impl<AccountId> ConvertLocation<AccountId> for Tuple {
 	}
 }
 
+/// A [`ConvertLocation`] which can tell which of its converters, if it's a tuple, converted a
+/// location.
+///
+/// Only meant for debugging, e.g. through
+/// `xcm_runtime_apis::conversions::LocationToAccountDebugApi`.
+pub trait ConvertLocationSteps<AccountId>: ConvertLocation<AccountId> {
+	/// The name of the converter, its type name by default.
+	fn name() -> &'static str {
+		core::any::type_name::<Self>()
+	}
+
+	/// Convert `location` with every converter, in the order they're tried, returning their names
+	/// and results.
+	fn convert_location_steps(location: &Location) -> Vec<(&'static str, Option<AccountId>)> {
+		vec![(Self::name(), Self::convert_location(location))]
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId> ConvertLocationSteps<AccountId> for Tuple {
+	fn convert_location_steps(location: &Location) -> Vec<(&'static str, Option<AccountId>)> {
+		let mut steps = Vec::new();
+		for_tuples!( #(
+			steps.extend(Tuple::convert_location_steps(location));
+		)* );
+		steps
+	}
+}
+
 /// A converter `trait` for origin types.
 ///
 /// Can be amalgamated into tuples. If any of the tuple elements returns `Ok(_)`, it short circuits.
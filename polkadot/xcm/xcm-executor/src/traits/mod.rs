@@ -17,13 +17,15 @@
 //! Various traits used in configuring the executor.
 
 mod conversion;
-pub use conversion::{CallDispatcher, ConvertLocation, ConvertOrigin, WithOriginFilter};
+pub use conversion::{
+	CallDispatcher, ConvertLocation, ConvertLocationSteps, ConvertOrigin, WithOriginFilter,
+};
 mod drop_assets;
 pub use drop_assets::{ClaimAssets, DropAssets};
 mod asset_exchange;
 pub use asset_exchange::AssetExchange;
 mod asset_lock;
-pub use asset_lock::{AssetLock, Enact, LockError};
+pub use asset_lock::{AssetLock, Enact, LockError, LockNonFungible};
 mod asset_transfer;
 pub use asset_transfer::{Error as AssetTransferError, TransferType, XcmAssetTransfers};
 mod export;
@@ -51,7 +53,7 @@ pub use hrmp::{
 };
 mod record_xcm;
 mod weight;
-pub use record_xcm::RecordXcm;
+pub use record_xcm::{InstructionTrace, RecordXcm};
 #[deprecated = "Use `sp_runtime::traits::` instead"]
 pub use sp_runtime::traits::{Identity, TryConvertInto as JustTry};
 pub use weight::{WeightBounds, WeightTrader};
@@ -59,10 +61,10 @@ pub use weight::{WeightBounds, WeightTrader};
 pub mod prelude {
 	pub use super::{
 		export_xcm, validate_export, AssetExchange, AssetLock, ClaimAssets, ConvertOrigin,
-		DropAssets, Enact, Error, ExportXcm, FeeManager, FeeReason, LockError, MatchesFungible,
-		MatchesFungibles, MatchesNonFungible, MatchesNonFungibles, OnResponse, ProcessTransaction,
-		ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
-		WithOriginFilter,
+		DropAssets, Enact, Error, ExportXcm, FeeManager, FeeReason, LockError, LockNonFungible,
+		MatchesFungible, MatchesFungibles, MatchesNonFungible, MatchesNonFungibles, OnResponse,
+		ProcessTransaction, ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds,
+		WeightTrader, WithOriginFilter,
 	};
 	#[allow(deprecated)]
 	pub use super::{Identity, JustTry};
@@ -16,7 +16,28 @@
 
 //! Trait for recording XCMs and a dummy implementation.
 
-use xcm::latest::Xcm;
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use xcm::latest::{Assets, Error as XcmError, Instruction, Weight, Xcm};
+
+/// What happened when executing a single instruction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct InstructionTrace {
+	/// The index of the instruction in the program or fragment (error handler, appendix, nested
+	/// program) it belongs to.
+	pub index: u32,
+	/// The instruction executed.
+	pub instruction: Instruction<()>,
+	/// The contents of the holding register after executing the instruction.
+	pub holding: Assets,
+	/// The weight consumed by the instruction, i.e. its weight minus any surplus it reported.
+	pub weight_used: Weight,
+	/// The fees charged by the instruction, for execution or delivery.
+	pub fees_charged: Assets,
+	/// The error returned by the instruction, if any.
+	pub error: Option<XcmError>,
+}
 
 /// Trait for recording XCMs.
 pub trait RecordXcm {
@@ -29,6 +50,19 @@ pub trait RecordXcm {
 	fn recorded_xcm() -> Option<Xcm<()>>;
 	/// Record `xcm`.
 	fn record(xcm: Xcm<()>);
+	/// Whether or not we should trace the instructions of executed XCMs.
+	fn should_trace() -> bool {
+		false
+	}
+	/// Enable or disable tracing. Enabling it discards any trace recorded so far.
+	fn set_trace_xcm(_enabled: bool) {}
+	/// Get the trace of all instructions executed since tracing was enabled, in order of
+	/// completion.
+	fn recorded_trace() -> Vec<InstructionTrace> {
+		Vec::new()
+	}
+	/// Record the trace of an executed instruction.
+	fn record_instruction(_trace: InstructionTrace) {}
 }
 
 impl RecordXcm for () {
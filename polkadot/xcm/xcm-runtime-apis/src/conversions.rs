@@ -16,10 +16,29 @@
 
 //! Contains runtime APIs for useful conversions, such as between XCM `Location` and `AccountId`.
 
+use alloc::{string::String, vec::Vec};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use xcm::VersionedLocation;
-use xcm_executor::traits::ConvertLocation;
+use xcm_executor::traits::{ConvertLocation, ConvertLocationSteps};
+
+/// The result of a single converter when converting a `Location`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct ConversionStep<AccountId> {
+	/// The name of the converter, usually its type name.
+	pub converter: String,
+	/// The account the converter converted the location to, if any.
+	pub account: Option<AccountId>,
+}
+
+/// Every step of converting a `Location` to an `AccountId`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct LocationConversion<AccountId> {
+	/// The result of every configured converter, in the order they're tried.
+	pub steps: Vec<ConversionStep<AccountId>>,
+	/// The index of the converter the location is actually converted with, if any.
+	pub matched: Option<u32>,
+}
 
 sp_api::decl_runtime_apis! {
 	/// API for useful conversions between XCM `Location` and `AccountId`.
@@ -27,6 +46,13 @@ sp_api::decl_runtime_apis! {
 		/// Converts `Location` to `AccountId`.
 		fn convert_location(location: VersionedLocation) -> Result<AccountId, Error>;
 	}
+
+	/// Companion of [`LocationToAccountApi`], to debug conversions between XCM `Location` and
+	/// `AccountId`.
+	pub trait LocationToAccountDebugApi<AccountId> where AccountId: Decode {
+		/// Converts `Location` with every configured converter, returning all of their results.
+		fn convert_location_steps(location: VersionedLocation) -> Result<LocationConversion<AccountId>, Error>;
+	}
 }
 
 #[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
@@ -54,3 +80,39 @@ impl<AccountId: Decode, Conversion: ConvertLocation<AccountId>>
 		Conversion::convert_location(&location).ok_or(Error::Unsupported)
 	}
 }
+
+impl<AccountId: Decode, Conversion: ConvertLocationSteps<AccountId>>
+	LocationToAccountHelper<AccountId, Conversion>
+{
+	pub fn convert_location_steps(
+		location: VersionedLocation,
+	) -> Result<LocationConversion<AccountId>, Error> {
+		let location = location.try_into().map_err(|_| Error::VersionedConversionFailed)?;
+		let steps: Vec<_> = Conversion::convert_location_steps(&location)
+			.into_iter()
+			.map(|(name, account)| ConversionStep { converter: short_type_name(name), account })
+			.collect();
+		let matched = steps.iter().position(|step| step.account.is_some()).map(|i| i as u32);
+		Ok(LocationConversion { steps, matched })
+	}
+}
+
+/// Strips module paths from a type name, e.g. `a::B<c::D>` becomes `B<D>`.
+fn short_type_name(name: &str) -> String {
+	let mut short = String::with_capacity(name.len());
+	// Start of the path currently being pushed to `short`.
+	let mut path_start = 0;
+	let mut chars = name.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c == ':' && chars.peek() == Some(&':') {
+			chars.next();
+			short.truncate(path_start);
+			continue
+		}
+		short.push(c);
+		if !(c.is_alphanumeric() || c == '_') {
+			path_start = short.len();
+		}
+	}
+	short
+}
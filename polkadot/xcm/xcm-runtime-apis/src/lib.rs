@@ -30,3 +30,7 @@ pub mod dry_run;
 /// Fee estimation API.
 /// Given an XCM program, it will return the fees needed to execute it properly or send it.
 pub mod fees;
+
+/// Trapped assets API.
+/// Lists the assets trapped by the XCM executor, so they can be claimed.
+pub mod trapped_assets;
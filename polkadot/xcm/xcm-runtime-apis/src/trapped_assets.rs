@@ -0,0 +1,73 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for enumerating assets trapped by the XCM executor.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use xcm::{VersionedAssets, VersionedLocation};
+
+/// Assets trapped by the XCM executor, which can be claimed back with `ClaimAsset`.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct TrappedAssets {
+	/// The hash identifying the trap, as found in `AssetsTrapped` events.
+	pub hash: [u8; 32],
+	/// The location which can claim the assets.
+	pub origin: VersionedLocation,
+	/// The trapped assets, in the exact form they have to be claimed with.
+	pub assets: VersionedAssets,
+	/// How many times these assets were trapped for `origin`, i.e. how many times they can be
+	/// claimed.
+	pub count: u32,
+}
+
+/// A page of the assets trapped by the XCM executor.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct TrappedAssetsPage {
+	/// The trapped assets found in this page.
+	pub assets: Vec<TrappedAssets>,
+	/// The hash to pass as `start` to get the next page, if there may be more trapped assets.
+	pub next_start: Option<[u8; 32]>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for listing trapped assets, so they can be claimed without having to look for the
+	/// events they were trapped in.
+	pub trait TrappedAssetsApi {
+		/// Returns the assets currently trapped, only for `origin` if given.
+		///
+		/// Looks at no more than `limit` traps, following the one with hash `start` if given.
+		/// Assets trapped before the runtime started keeping track of their details aren't
+		/// returned.
+		fn trapped_assets(
+			origin: Option<VersionedLocation>,
+			start: Option<[u8; 32]>,
+			limit: u32,
+		) -> Result<TrappedAssetsPage, Error>;
+	}
+}
+
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// An API call is unsupported.
+	#[codec(index = 0)]
+	Unimplemented,
+
+	/// Converting a versioned data structure from one version to another failed.
+	#[codec(index = 1)]
+	VersionedConversionFailed,
+}
@@ -28,7 +28,8 @@ use mock::*;
 use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
 use xcm_runtime_apis::conversions::{
-	Error as LocationToAccountApiError, LocationToAccountApi, LocationToAccountHelper,
+	ConversionStep, Error as LocationToAccountApiError, LocationConversion, LocationToAccountApi,
+	LocationToAccountDebugApi, LocationToAccountHelper,
 };
 
 #[test]
@@ -55,6 +56,47 @@ fn convert_location_to_account_works() {
 	})
 }
 
+#[test]
+fn convert_location_steps_works() {
+	sp_io::TestExternalities::default().execute_with(|| {
+		let client = TestClient {};
+		let runtime_api = client.runtime_api();
+		let step =
+			|converter: &str, account| ConversionStep { converter: converter.into(), account };
+
+		// No converter matches `Here`
+		assert_ok!(
+			runtime_api
+				.convert_location_steps(H256::zero(), VersionedLocation::from(Location::here()))
+				.unwrap(),
+			LocationConversion {
+				steps: vec![
+					step("AccountIndex64Aliases<AnyNetwork, u64>", None),
+					step("SiblingChainToIndex64", None),
+				],
+				matched: None,
+			}
+		);
+
+		// Only the second converter matches a sibling parachain
+		assert_ok!(
+			runtime_api
+				.convert_location_steps(
+					H256::zero(),
+					VersionedLocation::from((Parent, Parachain(1000)))
+				)
+				.unwrap(),
+			LocationConversion {
+				steps: vec![
+					step("AccountIndex64Aliases<AnyNetwork, u64>", None),
+					step("SiblingChainToIndex64", Some(1000_u64)),
+				],
+				matched: Some(1),
+			}
+		);
+	})
+}
+
 #[test]
 fn location_to_account_helper_with_multi_signature_works() {
 	type Signature = MultiSignature;
@@ -20,7 +20,10 @@ use frame_support::sp_runtime::testing::H256;
 use frame_system::RawOrigin;
 use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
-use xcm_runtime_apis::{dry_run::DryRunApi, fees::XcmPaymentApi};
+use xcm_runtime_apis::{
+	dry_run::{DryRunApi, TracedDryRunEffects},
+	fees::XcmPaymentApi,
+};
 
 mod mock;
 use mock::{
@@ -353,3 +356,63 @@ fn dry_run_xcm() {
 		);
 	});
 }
+
+// Same scenario as in `dry_run_xcm`, but through the tracing version of the API.
+#[test]
+fn dry_run_xcm_with_trace() {
+	sp_tracing::init_for_tests();
+	let who = 1; // AccountId = u64.
+	let transfer_amount = 100u128;
+	let inner_xcm = Xcm::<()>::builder_unsafe()
+		.buy_execution((Here, 1u128), Unlimited)
+		.deposit_asset(AllCounted(1), [0u8; 32])
+		.build();
+	let xcm_to_weigh = Xcm::<RuntimeCall>::builder_unsafe()
+		.withdraw_asset((Here, transfer_amount))
+		.clear_origin()
+		.buy_execution((Here, transfer_amount), Unlimited)
+		.deposit_reserve_asset(AllCounted(1), (Parent, Parachain(2100)), inner_xcm.clone())
+		.build();
+	let client = TestClient;
+	let runtime_api = client.runtime_api();
+	let xcm_weight = runtime_api
+		.query_xcm_weight(H256::zero(), VersionedXcm::from(xcm_to_weigh.clone().into()))
+		.unwrap()
+		.unwrap();
+	let execution_fees = runtime_api
+		.query_weight_to_asset_fee(
+			H256::zero(),
+			xcm_weight,
+			VersionedAssetId::from(AssetId(Here.into())),
+		)
+		.unwrap()
+		.unwrap();
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.withdraw_asset((Here, transfer_amount + execution_fees))
+		.clear_origin()
+		.buy_execution((Here, execution_fees), Unlimited)
+		.deposit_reserve_asset(AllCounted(1), (Parent, Parachain(2100)), inner_xcm)
+		.build();
+	let balances = vec![(
+		who,
+		transfer_amount + execution_fees + DeliveryFees::get() + ExistentialDeposit::get(),
+	)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let TracedDryRunEffects { effects, trace } = runtime_api
+			.dry_run_xcm_with_trace(
+				H256::zero(),
+				VersionedLocation::from([AccountIndex64 { index: 1, network: None }]),
+				VersionedXcm::from(xcm),
+			)
+			.unwrap()
+			.unwrap();
+		assert_eq!(effects.execution_result, Outcome::Complete { used: xcm_weight });
+		assert_eq!(effects.forwarded_xcms.len(), 1);
+
+		// Every instruction is traced, delivery fees being charged by the last one.
+		assert_eq!(trace.len(), 4);
+		assert!(trace.iter().enumerate().all(|(i, t)| t.index == i as u32 && t.error.is_none()));
+		assert!(trace[3].holding.is_none());
+		assert_eq!(trace[3].fees_charged, (Here, DeliveryFees::get()).into());
+	});
+}
@@ -40,13 +40,19 @@ use xcm_builder::{
 	TakeWeightCredit,
 };
 use xcm_executor::{
-	traits::{ConvertLocation, JustTry},
+	traits::{ConvertLocation, ConvertLocationSteps, JustTry},
 	XcmExecutor,
 };
 
 use xcm_runtime_apis::{
-	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
-	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
+	conversions::{
+		Error as LocationToAccountApiError, LocationConversion, LocationToAccountApi,
+		LocationToAccountDebugApi, LocationToAccountHelper,
+	},
+	dry_run::{
+		CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, TracedDryRunEffects,
+		XcmDryRunEffects,
+	},
 	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
 };
 
@@ -192,6 +198,10 @@ impl<Network: Get<Option<NetworkId>>, AccountId: From<u64>> ConvertLocation<Acco
 		Some((*index).into())
 	}
 }
+impl<Network: Get<Option<NetworkId>>, AccountId: From<u64>> ConvertLocationSteps<AccountId>
+	for AccountIndex64Aliases<Network, AccountId>
+{
+}
 
 /// Custom location converter to turn sibling chains into u64 accounts.
 pub struct SiblingChainToIndex64;
@@ -204,6 +214,7 @@ impl ConvertLocation<AccountId> for SiblingChainToIndex64 {
 		Some((*index).into())
 	}
 }
+impl ConvertLocationSteps<AccountId> for SiblingChainToIndex64 {}
 
 /// We alias local account locations to actual local accounts.
 /// We also allow sovereign accounts for other sibling chains.
@@ -347,6 +358,7 @@ impl pallet_xcm::Config for TestRuntime {
 	type SovereignAccountOf = ();
 	type Currency = Balances;
 	type CurrencyMatcher = IsConcrete<HereLocation>;
+	type NonFungibleLocker = ();
 	type MaxLockers = ConstU32<0>;
 	type MaxRemoteLockConsumers = ConstU32<0>;
 	type RemoteLockConsumerIdentifier = ();
@@ -422,6 +434,12 @@ sp_api::mock_impl_runtime_apis! {
 		}
 	}
 
+	impl LocationToAccountDebugApi<Block, AccountId> for RuntimeApi {
+		fn convert_location_steps(location: VersionedLocation) -> Result<LocationConversion<AccountId>, LocationToAccountApiError> {
+			LocationToAccountHelper::<AccountId, LocationToAccountId>::convert_location_steps(location)
+		}
+	}
+
 	impl XcmPaymentApi<Block> for RuntimeApi {
 		fn query_acceptable_payment_assets(xcm_version: XcmVersion) -> Result<Vec<VersionedAssetId>, XcmPaymentApiError> {
 			Ok(vec![
@@ -464,63 +482,93 @@ sp_api::mock_impl_runtime_apis! {
 
 	impl DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for RuntimeApi {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
-			use xcm_executor::RecordXcm;
-			pallet_xcm::Pallet::<TestRuntime>::set_record_xcm(true);
-			let result = call.dispatch(origin.into());
-			pallet_xcm::Pallet::<TestRuntime>::set_record_xcm(false);
-			let local_xcm = pallet_xcm::Pallet::<TestRuntime>::recorded_xcm();
-			let forwarded_xcms = sent_xcm()
-							   .into_iter()
-							   .map(|(location, message)| (
-									   VersionedLocation::from(location),
-									   vec![VersionedXcm::from(message)],
-							   )).collect();
-			let events: Vec<RuntimeEvent> = System::read_events_no_consensus().map(|record| record.event.clone()).collect();
-			Ok(CallDryRunEffects {
-				local_xcm: local_xcm.map(VersionedXcm::<()>::from),
-				forwarded_xcms,
-				emitted_events: events,
-				execution_result: result,
-			})
+			do_dry_run_call(origin, call)
 		}
 
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
-			let origin_location: Location = origin_location.try_into().map_err(|error| {
-				log::error!(
-					target: "xcm::DryRunApi::dry_run_xcm",
-					"Location version conversion failed with error: {:?}",
-					error,
-				);
-				XcmDryRunApiError::VersionedConversionFailed
-			})?;
-			let xcm: Xcm<RuntimeCall> = xcm.try_into().map_err(|error| {
-				log::error!(
-					target: "xcm::DryRunApi::dry_run_xcm",
-					"Xcm version conversion failed with error {:?}",
-					error,
-				);
-				XcmDryRunApiError::VersionedConversionFailed
-			})?;
-			let mut hash = fake_message_hash(&xcm);
-			let result = XcmExecutor::<XcmConfig>::prepare_and_execute(
-				origin_location,
-				xcm,
-				&mut hash,
-				Weight::MAX, // Max limit available for execution.
-				Weight::zero(),
-			);
-			let forwarded_xcms = sent_xcm()
-				.into_iter()
-				.map(|(location, message)| (
-					VersionedLocation::from(location),
-					vec![VersionedXcm::from(message)],
-				)).collect();
-			let events: Vec<RuntimeEvent> = System::events().iter().map(|record| record.event.clone()).collect();
-			Ok(XcmDryRunEffects {
-				forwarded_xcms,
-				emitted_events: events,
-				execution_result: result,
-			})
+			do_dry_run_xcm(origin_location, xcm)
+		}
+
+		fn dry_run_call_with_trace(origin: OriginCaller, call: RuntimeCall) -> Result<TracedDryRunEffects<CallDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			use xcm_executor::RecordXcm;
+			pallet_xcm::Pallet::<TestRuntime>::set_trace_xcm(true);
+			let effects = do_dry_run_call(origin, call);
+			pallet_xcm::Pallet::<TestRuntime>::set_trace_xcm(false);
+			let trace = pallet_xcm::Pallet::<TestRuntime>::recorded_trace();
+			Ok(TracedDryRunEffects { effects: effects?, trace })
+		}
+
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<TracedDryRunEffects<XcmDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			use xcm_executor::RecordXcm;
+			pallet_xcm::Pallet::<TestRuntime>::set_trace_xcm(true);
+			let effects = do_dry_run_xcm(origin_location, xcm);
+			pallet_xcm::Pallet::<TestRuntime>::set_trace_xcm(false);
+			let trace = pallet_xcm::Pallet::<TestRuntime>::recorded_trace();
+			Ok(TracedDryRunEffects { effects: effects?, trace })
 		}
 	}
 }
+
+fn do_dry_run_call(
+	origin: OriginCaller,
+	call: RuntimeCall,
+) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+	use xcm_executor::RecordXcm;
+	pallet_xcm::Pallet::<TestRuntime>::set_record_xcm(true);
+	let result = call.dispatch(origin.into());
+	pallet_xcm::Pallet::<TestRuntime>::set_record_xcm(false);
+	let local_xcm = pallet_xcm::Pallet::<TestRuntime>::recorded_xcm();
+	let forwarded_xcms = sent_xcm()
+		.into_iter()
+		.map(|(location, message)| {
+			(VersionedLocation::from(location), vec![VersionedXcm::from(message)])
+		})
+		.collect();
+	let events: Vec<RuntimeEvent> =
+		System::read_events_no_consensus().map(|record| record.event.clone()).collect();
+	Ok(CallDryRunEffects {
+		local_xcm: local_xcm.map(VersionedXcm::<()>::from),
+		forwarded_xcms,
+		emitted_events: events,
+		execution_result: result,
+	})
+}
+
+fn do_dry_run_xcm(
+	origin_location: VersionedLocation,
+	xcm: VersionedXcm<RuntimeCall>,
+) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+	let origin_location: Location = origin_location.try_into().map_err(|error| {
+		log::error!(
+			target: "xcm::DryRunApi::dry_run_xcm",
+			"Location version conversion failed with error: {:?}",
+			error,
+		);
+		XcmDryRunApiError::VersionedConversionFailed
+	})?;
+	let xcm: Xcm<RuntimeCall> = xcm.try_into().map_err(|error| {
+		log::error!(
+			target: "xcm::DryRunApi::dry_run_xcm",
+			"Xcm version conversion failed with error {:?}",
+			error,
+		);
+		XcmDryRunApiError::VersionedConversionFailed
+	})?;
+	let mut hash = fake_message_hash(&xcm);
+	let result = XcmExecutor::<XcmConfig>::prepare_and_execute(
+		origin_location,
+		xcm,
+		&mut hash,
+		Weight::MAX, // Max limit available for execution.
+		Weight::zero(),
+	);
+	let forwarded_xcms = sent_xcm()
+		.into_iter()
+		.map(|(location, message)| {
+			(VersionedLocation::from(location), vec![VersionedXcm::from(message)])
+		})
+		.collect();
+	let events: Vec<RuntimeEvent> =
+		System::events().iter().map(|record| record.event.clone()).collect();
+	Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
+}
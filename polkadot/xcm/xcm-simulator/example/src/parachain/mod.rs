@@ -159,6 +159,7 @@ impl pallet_xcm::Config for Runtime {
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
 	type Currency = Balances;
 	type CurrencyMatcher = ();
+	type NonFungibleLocker = ();
 	type TrustedLockers = TrustedLockers;
 	type SovereignAccountOf = location_converter::LocationConverter;
 	type MaxLockers = ConstU32<8>;
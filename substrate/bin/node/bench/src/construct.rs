@@ -35,7 +35,10 @@ use sc_transaction_pool_api::{
 };
 use sp_consensus::{Environment, Proposer};
 use sp_inherents::InherentDataProvider;
-use sp_runtime::{traits::NumberFor, OpaqueExtrinsic};
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor},
+	OpaqueExtrinsic,
+};
 
 use crate::{
 	common::SizeType,
@@ -274,6 +277,21 @@ impl sc_transaction_pool_api::TransactionPool for Transactions {
 		Box::pin(futures::future::ready(iter))
 	}
 
+	fn ready_at_block(
+		&self,
+		_at: <Self::Block as BlockT>::Hash,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		let iter: Box<dyn ReadyTransactions<Item = Arc<PoolTransaction>> + Send> =
+			Box::new(TransactionsIterator(self.0.clone().into_iter()));
+		Box::pin(futures::future::ready(iter))
+	}
+
 	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
 		unimplemented!()
 	}
@@ -0,0 +1,95 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference signer for nodes started with `--keystore-uri`.
+
+#[cfg(unix)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+	unix::run()
+}
+
+#[cfg(not(unix))]
+fn main() {
+	eprintln!("remote-signer listens on a Unix socket and is only supported on Unix platforms");
+	std::process::exit(1);
+}
+
+#[cfg(unix)]
+mod unix {
+	use clap::Parser;
+	use sc_keystore::{
+		remote::{RemoteSigner, SlashingProtection},
+		LocalKeystore,
+	};
+	use sp_core::crypto::SecretString;
+	use std::{fs, os::unix::net::UnixListener, path::PathBuf, sync::Arc};
+
+	/// Serve the keys of a keystore over a local Unix socket.
+	#[derive(Debug, Parser)]
+	#[command(name = "remote-signer", version)]
+	struct Cli {
+		/// Path of the Unix socket to listen on.
+		#[arg(long, value_name = "PATH")]
+		socket: PathBuf,
+
+		/// Path of the keystore holding the keys.
+		#[arg(long, value_name = "PATH")]
+		keystore_path: PathBuf,
+
+		/// File that contains the password used by the keystore.
+		#[arg(long, value_name = "PATH")]
+		password_filename: Option<PathBuf>,
+
+		/// File recording the signed votes.
+		///
+		/// If not given, signed votes are only tracked in memory and protection against
+		/// conflicting votes doesn't survive restarts.
+		#[arg(long, value_name = "PATH")]
+		slashing_protection: Option<PathBuf>,
+	}
+
+	pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+		sp_tracing::try_init_simple();
+		let cli = Cli::parse();
+
+		let password = cli
+			.password_filename
+			.map(|file| fs::read_to_string(file).map(SecretString::new))
+			.transpose()?;
+		let keystore = LocalKeystore::open(cli.keystore_path, password)?;
+		let protection = match cli.slashing_protection {
+			Some(path) => SlashingProtection::open(path)?,
+			None => {
+				log::warn!(
+					"No slashing protection file given, signed votes are kept in memory only"
+				);
+				SlashingProtection::in_memory()
+			},
+		};
+
+		// Remove the socket left over by a previous run.
+		if cli.socket.exists() {
+			fs::remove_file(&cli.socket)?;
+		}
+		let listener = UnixListener::bind(&cli.socket)?;
+		log::info!("Remote signer listening on {}", cli.socket.display());
+
+		Arc::new(RemoteSigner::new(Arc::new(keystore), protection)).serve(listener)?;
+		Ok(())
+	}
+}
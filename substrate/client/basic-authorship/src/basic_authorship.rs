@@ -20,6 +20,7 @@
 
 // FIXME #1021 move this into sp-consensus
 
+use crate::ordering::{BlockState, OrderingPolicy, PoolOrder};
 use codec::Encode;
 use futures::{
 	channel::oneshot,
@@ -40,6 +41,7 @@ use sp_runtime::{
 	traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT},
 	Digest, ExtrinsicInclusionMode, Percent, SaturatedConversion,
 };
+use sp_weights::Weight;
 use std::{marker::PhantomData, pin::Pin, sync::Arc, time};
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
@@ -59,7 +61,7 @@ const DEFAULT_SOFT_DEADLINE_PERCENT: Percent = Percent::from_percent(50);
 const LOG_TARGET: &'static str = "basic-authorship";
 
 /// [`Proposer`] factory.
-pub struct ProposerFactory<A, C, PR> {
+pub struct ProposerFactory<A, C, PR, O = PoolOrder> {
 	spawn_handle: Box<dyn SpawnNamed>,
 	/// The client instance.
 	client: Arc<C>,
@@ -83,11 +85,13 @@ pub struct ProposerFactory<A, C, PR> {
 	telemetry: Option<TelemetryHandle>,
 	/// When estimating the block size, should the proof be included?
 	include_proof_in_block_size_estimation: bool,
+	/// Policy deciding which transactions are included in a block and in which order.
+	ordering_policy: O,
 	/// phantom member to pin the `ProofRecording` type.
 	_phantom: PhantomData<PR>,
 }
 
-impl<A, C, PR> Clone for ProposerFactory<A, C, PR> {
+impl<A, C, PR, O: Clone> Clone for ProposerFactory<A, C, PR, O> {
 	fn clone(&self) -> Self {
 		Self {
 			spawn_handle: self.spawn_handle.clone(),
@@ -98,6 +102,7 @@ impl<A, C, PR> Clone for ProposerFactory<A, C, PR> {
 			soft_deadline_percent: self.soft_deadline_percent,
 			telemetry: self.telemetry.clone(),
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			ordering_policy: self.ordering_policy.clone(),
 			_phantom: self._phantom,
 		}
 	}
@@ -124,6 +129,7 @@ impl<A, C> ProposerFactory<A, C, DisableProofRecording> {
 			telemetry,
 			client,
 			include_proof_in_block_size_estimation: false,
+			ordering_policy: PoolOrder,
 			_phantom: PhantomData,
 		}
 	}
@@ -152,6 +158,7 @@ impl<A, C> ProposerFactory<A, C, EnableProofRecording> {
 			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			include_proof_in_block_size_estimation: true,
+			ordering_policy: PoolOrder,
 			_phantom: PhantomData,
 		}
 	}
@@ -162,7 +169,7 @@ impl<A, C> ProposerFactory<A, C, EnableProofRecording> {
 	}
 }
 
-impl<A, C, PR> ProposerFactory<A, C, PR> {
+impl<A, C, PR, O> ProposerFactory<A, C, PR, O> {
 	/// Set the default block size limit in bytes.
 	///
 	/// The default value for the block size limit is:
@@ -189,20 +196,41 @@ impl<A, C, PR> ProposerFactory<A, C, PR> {
 	pub fn set_soft_deadline(&mut self, percent: Percent) {
 		self.soft_deadline_percent = percent;
 	}
+
+	/// Use the given policy to decide which transactions are included in a block and in which
+	/// order.
+	///
+	/// The default policy is [`PoolOrder`], which tries the ready transactions in the order of the
+	/// transaction pool.
+	pub fn with_ordering_policy<O2>(self, ordering_policy: O2) -> ProposerFactory<A, C, PR, O2> {
+		ProposerFactory {
+			spawn_handle: self.spawn_handle,
+			client: self.client,
+			transaction_pool: self.transaction_pool,
+			metrics: self.metrics,
+			default_block_size_limit: self.default_block_size_limit,
+			soft_deadline_percent: self.soft_deadline_percent,
+			telemetry: self.telemetry,
+			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			ordering_policy,
+			_phantom: self._phantom,
+		}
+	}
 }
 
-impl<Block, C, A, PR> ProposerFactory<A, C, PR>
+impl<Block, C, A, PR, O> ProposerFactory<A, C, PR, O>
 where
 	A: TransactionPool<Block = Block> + 'static,
 	Block: BlockT,
 	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
 	C::Api: ApiExt<Block> + BlockBuilderApi<Block>,
+	O: OrderingPolicy<A>,
 {
 	fn init_with_now(
 		&mut self,
 		parent_header: &<Block as BlockT>::Header,
 		now: Box<dyn Fn() -> time::Instant + Send + Sync>,
-	) -> Proposer<Block, C, A, PR> {
+	) -> Proposer<Block, C, A, PR, O> {
 		let parent_hash = parent_header.hash();
 
 		info!(
@@ -211,7 +239,7 @@ where
 			parent_header.number()
 		);
 
-		let proposer = Proposer::<_, _, _, PR> {
+		let proposer = Proposer::<_, _, _, PR, O> {
 			spawn_handle: self.spawn_handle.clone(),
 			client: self.client.clone(),
 			parent_hash,
@@ -224,22 +252,24 @@ where
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 			include_proof_in_block_size_estimation: self.include_proof_in_block_size_estimation,
+			ordering_policy: self.ordering_policy.clone(),
 		};
 
 		proposer
 	}
 }
 
-impl<A, Block, C, PR> sp_consensus::Environment<Block> for ProposerFactory<A, C, PR>
+impl<A, Block, C, PR, O> sp_consensus::Environment<Block> for ProposerFactory<A, C, PR, O>
 where
 	A: TransactionPool<Block = Block> + 'static,
 	Block: BlockT,
 	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + CallApiAt<Block> + Send + Sync + 'static,
 	C::Api: ApiExt<Block> + BlockBuilderApi<Block>,
 	PR: ProofRecording,
+	O: OrderingPolicy<A>,
 {
 	type CreateProposer = future::Ready<Result<Self::Proposer, Self::Error>>;
-	type Proposer = Proposer<Block, C, A, PR>;
+	type Proposer = Proposer<Block, C, A, PR, O>;
 	type Error = sp_blockchain::Error;
 
 	fn init(&mut self, parent_header: &<Block as BlockT>::Header) -> Self::CreateProposer {
@@ -248,7 +278,7 @@ where
 }
 
 /// The proposer logic.
-pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR> {
+pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR, O = PoolOrder> {
 	spawn_handle: Box<dyn SpawnNamed>,
 	client: Arc<C>,
 	parent_hash: Block::Hash,
@@ -260,16 +290,18 @@ pub struct Proposer<Block: BlockT, C, A: TransactionPool, PR> {
 	include_proof_in_block_size_estimation: bool,
 	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
+	ordering_policy: O,
 	_phantom: PhantomData<PR>,
 }
 
-impl<A, Block, C, PR> sp_consensus::Proposer<Block> for Proposer<Block, C, A, PR>
+impl<A, Block, C, PR, O> sp_consensus::Proposer<Block> for Proposer<Block, C, A, PR, O>
 where
 	A: TransactionPool<Block = Block> + 'static,
 	Block: BlockT,
 	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + CallApiAt<Block> + Send + Sync + 'static,
 	C::Api: ApiExt<Block> + BlockBuilderApi<Block>,
 	PR: ProofRecording,
+	O: OrderingPolicy<A>,
 {
 	type Proposal =
 		Pin<Box<dyn Future<Output = Result<Proposal<Block, PR::Proof>, Self::Error>> + Send>>;
@@ -314,13 +346,14 @@ where
 /// It allows us to increase block utilization.
 const MAX_SKIPPED_TRANSACTIONS: usize = 8;
 
-impl<A, Block, C, PR> Proposer<Block, C, A, PR>
+impl<A, Block, C, PR, O> Proposer<Block, C, A, PR, O>
 where
 	A: TransactionPool<Block = Block>,
 	Block: BlockT,
 	C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + CallApiAt<Block> + Send + Sync + 'static,
 	C::Api: ApiExt<Block> + BlockBuilderApi<Block>,
 	PR: ProofRecording,
+	O: OrderingPolicy<A>,
 {
 	async fn propose_with(
 		self,
@@ -416,11 +449,11 @@ where
 		let mut skipped = 0;
 		let mut unqueue_invalid = Vec::new();
 
-		let mut t1 = self.transaction_pool.ready_at(self.parent_number).fuse();
+		let mut t1 = self.transaction_pool.ready_at_block(self.parent_hash).fuse();
 		let mut t2 =
 			futures_timer::Delay::new(deadline.saturating_duration_since((self.now)()) / 8).fuse();
 
-		let mut pending_iterator = select! {
+		let pending_iterator = select! {
 			res = t1 => res,
 			_ = t2 => {
 				warn!(target: LOG_TARGET,
@@ -431,16 +464,26 @@ where
 				self.transaction_pool.ready()
 			},
 		};
+		let mut selection = self.ordering_policy.select(pending_iterator);
 
 		let block_size_limit = block_size_limit.unwrap_or(self.default_block_size_limit);
 
 		debug!(target: LOG_TARGET, "Attempting to push transactions from the pool.");
 		debug!(target: LOG_TARGET, "Pool status: {:?}", self.transaction_pool.status());
-		let mut transaction_pushed = false;
+		let mut transactions_pushed = 0;
+		let mut weight_pushed = Weight::zero();
 
 		let end_reason = loop {
-			let pending_tx = if let Some(pending_tx) = pending_iterator.next() {
-				pending_tx
+			let block_size =
+				block_builder.estimate_block_size(self.include_proof_in_block_size_estimation);
+			let block_state = BlockState {
+				size: block_size,
+				size_limit: block_size_limit,
+				transactions: transactions_pushed,
+				weight: weight_pushed,
+			};
+			let candidate = if let Some(candidate) = selection.next(&block_state) {
+				candidate
 			} else {
 				debug!(
 					target: LOG_TARGET,
@@ -460,13 +503,12 @@ where
 				break EndProposingReason::HitDeadline
 			}
 
-			let pending_tx_data = pending_tx.data().clone();
-			let pending_tx_hash = pending_tx.hash().clone();
+			let candidate_data: Vec<_> = candidate.iter().map(|tx| tx.data().clone()).collect();
+			let candidate_hashes: Vec<_> = candidate.iter().map(|tx| tx.hash().clone()).collect();
+			let candidate_size: usize = candidate_data.iter().map(|xt| xt.encoded_size()).sum();
 
-			let block_size =
-				block_builder.estimate_block_size(self.include_proof_in_block_size_estimation);
-			if block_size + pending_tx_data.encoded_size() > block_size_limit {
-				pending_iterator.report_invalid(&pending_tx);
+			if block_size + candidate_size > block_size_limit {
+				selection.report_invalid(&candidate);
 				if skipped < MAX_SKIPPED_TRANSACTIONS {
 					skipped += 1;
 					debug!(
@@ -493,14 +535,21 @@ where
 				}
 			}
 
-			trace!(target: LOG_TARGET, "[{:?}] Pushing to the block.", pending_tx_hash);
-			match sc_block_builder::BlockBuilder::push(block_builder, pending_tx_data) {
+			trace!(target: LOG_TARGET, "{:?} Pushing to the block.", candidate_hashes);
+			let result = match <[_; 1]>::try_from(candidate_data) {
+				Ok([xt]) => sc_block_builder::BlockBuilder::push(block_builder, xt),
+				Err(bundle) => block_builder.push_all(bundle),
+			};
+			match result {
 				Ok(()) => {
-					transaction_pushed = true;
-					debug!(target: LOG_TARGET, "[{:?}] Pushed to the block.", pending_tx_hash);
+					transactions_pushed += candidate.len();
+					for tx in &candidate {
+						weight_pushed.saturating_accrue(self.ordering_policy.weight(tx));
+					}
+					debug!(target: LOG_TARGET, "{:?} Pushed to the block.", candidate_hashes);
 				},
 				Err(ApplyExtrinsicFailed(Validity(e))) if e.exhausted_resources() => {
-					pending_iterator.report_invalid(&pending_tx);
+					selection.report_invalid(&candidate);
 					if skipped < MAX_SKIPPED_TRANSACTIONS {
 						skipped += 1;
 						debug!(target: LOG_TARGET,
@@ -521,17 +570,21 @@ where
 					}
 				},
 				Err(e) => {
-					pending_iterator.report_invalid(&pending_tx);
+					selection.report_invalid(&candidate);
 					debug!(
 						target: LOG_TARGET,
-						"[{:?}] Invalid transaction: {}", pending_tx_hash, e
+						"{:?} Invalid transaction: {}", candidate_hashes, e
 					);
-					unqueue_invalid.push(pending_tx_hash);
+					// We don't know which transaction of a bundle is invalid, so only single
+					// transactions are removed from the pool.
+					if let [hash] = &candidate_hashes[..] {
+						unqueue_invalid.push(hash.clone());
+					}
 				},
 			}
 		};
 
-		if matches!(end_reason, EndProposingReason::HitBlockSizeLimit) && !transaction_pushed {
+		if matches!(end_reason, EndProposingReason::HitBlockSizeLimit) && transactions_pushed == 0 {
 			warn!(
 				target: LOG_TARGET,
 				"Hit block size limit of `{}` without including any transaction!", block_size_limit,
@@ -598,6 +651,7 @@ where
 mod tests {
 	use super::*;
 
+	use crate::{Candidate, ReadyIterator, Selection};
 	use futures::executor::block_on;
 	use parking_lot::Mutex;
 	use sc_client_api::Backend;
@@ -895,6 +949,165 @@ mod tests {
 		assert_eq!(txpool.ready().count(), 0);
 	}
 
+	/// Tries the first N ready transactions as a single bundle.
+	#[derive(Clone)]
+	struct BundleFirst(usize);
+
+	impl<A> OrderingPolicy<A> for BundleFirst
+	where
+		A: TransactionPool + 'static,
+		A::InPoolTransaction: Send + Sync,
+	{
+		fn select(&self, ready: ReadyIterator<A>) -> Box<dyn Selection<A>> {
+			Box::new(BundleSelection::<A>(Some(ready.take(self.0).collect())))
+		}
+	}
+
+	struct BundleSelection<A: TransactionPool>(Option<Candidate<A>>);
+
+	impl<A> Selection<A> for BundleSelection<A>
+	where
+		A: TransactionPool,
+		A::InPoolTransaction: Send + Sync,
+	{
+		fn next(&mut self, _block: &BlockState) -> Option<Candidate<A>> {
+			self.0.take()
+		}
+
+		fn report_invalid(&mut self, _candidate: &Candidate<A>) {}
+	}
+
+	#[test]
+	fn should_include_bundles_atomically() {
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		);
+
+		let medium = |nonce| {
+			ExtrinsicBuilder::new_fill_block(Perbill::from_parts(MEDIUM))
+				.nonce(nonce)
+				.build()
+		};
+		let huge = |nonce| {
+			ExtrinsicBuilder::new_fill_block(Perbill::from_parts(HUGE)).nonce(nonce).build()
+		};
+
+		let genesis_hash = client.info().genesis_hash;
+		block_on(txpool.submit_at(genesis_hash, SOURCE, vec![medium(0), medium(1), huge(2)]))
+			.unwrap();
+		block_on(txpool.maintain(chain_event(
+			client.expect_header(genesis_hash).expect("there should be header"),
+		)));
+
+		let propose_block = |bundle_size| {
+			let mut proposer_factory =
+				ProposerFactory::new(spawner.clone(), client.clone(), txpool.clone(), None, None)
+					.with_ordering_policy(BundleFirst(bundle_size));
+			let proposer = proposer_factory.init_with_now(
+				&client.expect_header(genesis_hash).unwrap(),
+				Box::new(move || time::Instant::now()),
+			);
+
+			let deadline = time::Duration::from_secs(900);
+			block_on(proposer.propose(Default::default(), Default::default(), deadline, None))
+				.map(|r| r.block)
+				.unwrap()
+		};
+
+		// when / then
+		// the huge transaction does not fit next to the medium ones, so nothing is included.
+		assert_eq!(propose_block(3).extrinsics().len(), 0);
+		// the medium transactions fit together.
+		assert_eq!(propose_block(2).extrinsics(), &[medium(0), medium(1)]);
+		// the bundle was not removed from the pool.
+		assert_eq!(txpool.ready().count(), 3);
+	}
+
+	/// Tries the ready transactions in pool order until the weight of the block reaches the limit,
+	/// weighing every transaction one unit of `ref_time`.
+	#[derive(Clone)]
+	struct WeightLimited(u64);
+
+	impl<A> OrderingPolicy<A> for WeightLimited
+	where
+		A: TransactionPool + 'static,
+		A::InPoolTransaction: Send + Sync,
+	{
+		fn select(&self, ready: ReadyIterator<A>) -> Box<dyn Selection<A>> {
+			Box::new(WeightLimitedSelection::<A> { ready, limit: self.0 })
+		}
+
+		fn weight(&self, _transaction: &A::InPoolTransaction) -> Weight {
+			Weight::from_parts(1, 0)
+		}
+	}
+
+	struct WeightLimitedSelection<A: TransactionPool> {
+		ready: ReadyIterator<A>,
+		limit: u64,
+	}
+
+	impl<A> Selection<A> for WeightLimitedSelection<A>
+	where
+		A: TransactionPool,
+		A::InPoolTransaction: Send + Sync,
+	{
+		fn next(&mut self, block: &BlockState) -> Option<Candidate<A>> {
+			if block.weight.ref_time() >= self.limit {
+				return None
+			}
+			self.ready.next().map(|tx| vec![tx])
+		}
+
+		fn report_invalid(&mut self, _candidate: &Candidate<A>) {}
+	}
+
+	#[test]
+	fn should_report_weight_of_included_transactions() {
+		// given
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		);
+
+		let genesis_hash = client.info().genesis_hash;
+		block_on(txpool.submit_at(genesis_hash, SOURCE, (0..4).map(extrinsic).collect())).unwrap();
+		block_on(txpool.maintain(chain_event(
+			client.expect_header(genesis_hash).expect("there should be header"),
+		)));
+
+		let mut proposer_factory =
+			ProposerFactory::new(spawner.clone(), client.clone(), txpool.clone(), None, None)
+				.with_ordering_policy(WeightLimited(2));
+		let proposer = proposer_factory.init_with_now(
+			&client.expect_header(genesis_hash).unwrap(),
+			Box::new(move || time::Instant::now()),
+		);
+
+		// when
+		let deadline = time::Duration::from_secs(900);
+		let block =
+			block_on(proposer.propose(Default::default(), Default::default(), deadline, None))
+				.map(|r| r.block)
+				.unwrap();
+
+		// then
+		// the selection stops once the weight of the included transactions reaches the limit.
+		assert_eq!(block.extrinsics(), &[extrinsic(0), extrinsic(1)]);
+	}
+
 	#[test]
 	fn should_cease_building_block_when_block_limit_is_reached() {
 		let client = Arc::new(substrate_test_runtime_client::new());
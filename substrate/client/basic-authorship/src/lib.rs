@@ -71,5 +71,9 @@
 //! ```
 
 mod basic_authorship;
+mod ordering;
 
-pub use crate::basic_authorship::{Proposer, ProposerFactory, DEFAULT_BLOCK_SIZE_LIMIT};
+pub use crate::{
+	basic_authorship::{Proposer, ProposerFactory, DEFAULT_BLOCK_SIZE_LIMIT},
+	ordering::{BlockState, Candidate, OrderingPolicy, PoolOrder, ReadyIterator, Selection},
+};
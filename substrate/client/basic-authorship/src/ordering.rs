@@ -0,0 +1,109 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Policies deciding which transactions are included in a block and in which order.
+//!
+//! The [`Proposer`](crate::Proposer) asks the [`OrderingPolicy`] for a [`Selection`] when it
+//! starts to fill a block, and then tries to include the [`Candidate`]s returned by the selection
+//! one after another, until the selection is exhausted or the block is full. A candidate made of
+//! several transactions is a bundle: the transactions are included together, or not at all.
+
+use sc_transaction_pool_api::{ReadyTransactions, TransactionPool};
+use sp_weights::Weight;
+use std::sync::Arc;
+
+/// The ready transactions of the pool `A`, in the order of the pool.
+pub type ReadyIterator<A> =
+	Box<dyn ReadyTransactions<Item = Arc<<A as TransactionPool>::InPoolTransaction>> + Send>;
+
+/// Transactions that are included in a block together, or not at all.
+pub type Candidate<A> = Vec<Arc<<A as TransactionPool>::InPoolTransaction>>;
+
+/// State of the block being built, passed to [`Selection::next`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockState {
+	/// Estimated size of the block in bytes.
+	pub size: usize,
+	/// Size limit of the block in bytes.
+	pub size_limit: usize,
+	/// Number of transactions from the pool that are included in the block.
+	pub transactions: usize,
+	/// Weight of the transactions from the pool that are included in the block, as given by
+	/// [`OrderingPolicy::weight`].
+	pub weight: Weight,
+}
+
+/// Selection of the transactions of a single block.
+pub trait Selection<A: TransactionPool>: Send {
+	/// Returns the next candidate to include in the block, or `None` if there are no more
+	/// candidates.
+	fn next(&mut self, block: &BlockState) -> Option<Candidate<A>>;
+
+	/// Report that `candidate` could not be included in the block.
+	///
+	/// This is called when the candidate is invalid, exhausts the resources of the block or would
+	/// overflow its size limit.
+	fn report_invalid(&mut self, candidate: &Candidate<A>);
+}
+
+/// Policy deciding which ready transactions are included in a block and in which order.
+///
+/// This allows chains to implement e.g. fairness between senders, bundles of transactions that
+/// must be included atomically or block space reserved for some kind of transactions, without
+/// changing the [`Proposer`](crate::Proposer).
+pub trait OrderingPolicy<A: TransactionPool>: Clone + Send + Sync + 'static {
+	/// Start the selection of the transactions of a new block from the `ready` transactions.
+	fn select(&self, ready: ReadyIterator<A>) -> Box<dyn Selection<A>>;
+
+	/// Returns the weight of `transaction`, accounted in [`BlockState::weight`] once it is
+	/// included in the block.
+	///
+	/// The proposer doesn't know the weight of transactions, so policies relying on it (e.g. to
+	/// reserve a fraction of the block weight for operational transactions) have to provide it,
+	/// e.g. from the `TransactionPaymentApi` of the runtime. Defaults to zero.
+	fn weight(&self, _transaction: &A::InPoolTransaction) -> Weight {
+		Weight::zero()
+	}
+}
+
+/// The default [`OrderingPolicy`]: the ready transactions are tried one by one, in the order of
+/// the pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolOrder;
+
+impl<A: TransactionPool + 'static> OrderingPolicy<A> for PoolOrder {
+	fn select(&self, ready: ReadyIterator<A>) -> Box<dyn Selection<A>> {
+		Box::new(PoolOrderSelection::<A> { ready })
+	}
+}
+
+/// The [`Selection`] of [`PoolOrder`].
+struct PoolOrderSelection<A: TransactionPool> {
+	ready: ReadyIterator<A>,
+}
+
+impl<A: TransactionPool> Selection<A> for PoolOrderSelection<A> {
+	fn next(&mut self, _block: &BlockState) -> Option<Candidate<A>> {
+		self.ready.next().map(|tx| vec![tx])
+	}
+
+	fn report_invalid(&mut self, candidate: &Candidate<A>) {
+		for tx in candidate {
+			self.ready.report_invalid(tx);
+		}
+	}
+}
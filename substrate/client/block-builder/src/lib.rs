@@ -287,27 +287,36 @@ where
 		let version = self.version;
 
 		self.api.execute_in_transaction(|api| {
-			let res = if version < 6 {
-				#[allow(deprecated)]
-				api.apply_extrinsic_before_version_6(parent_hash, xt.clone())
-					.map(legacy::byte_sized_error::convert_to_latest)
-			} else {
-				api.apply_extrinsic(parent_hash, xt.clone())
-			};
-
-			match res {
-				Ok(Ok(_)) => {
+			match apply_extrinsic::<Block, C>(api, parent_hash, version, xt.clone()) {
+				Ok(()) => {
 					extrinsics.push(xt);
 					TransactionOutcome::Commit(Ok(()))
 				},
-				Ok(Err(tx_validity)) => TransactionOutcome::Rollback(Err(
-					ApplyExtrinsicFailed::Validity(tx_validity).into(),
-				)),
-				Err(e) => TransactionOutcome::Rollback(Err(Error::from(e))),
+				Err(e) => TransactionOutcome::Rollback(Err(e)),
 			}
 		})
 	}
 
+	/// Push all `xts` onto the block's list of extrinsics, or none of them.
+	///
+	/// The extrinsics are executed in order. If any of them can not be validly executed, the
+	/// changes of all of them are reverted and the error is returned.
+	pub fn push_all(&mut self, xts: Vec<<Block as BlockT>::Extrinsic>) -> Result<(), Error> {
+		let parent_hash = self.parent_hash;
+		let extrinsics = &mut self.extrinsics;
+		let version = self.version;
+
+		self.api.execute_in_transaction(|api| {
+			for xt in &xts {
+				if let Err(e) = apply_extrinsic::<Block, C>(api, parent_hash, version, xt.clone()) {
+					return TransactionOutcome::Rollback(Err(e))
+				}
+			}
+			extrinsics.extend(xts);
+			TransactionOutcome::Commit(Ok(()))
+		})
+	}
+
 	/// Consume the builder to build a valid `Block` containing all pushed extrinsics.
 	///
 	/// Returns the build `Block`, the changes to the storage and an optional `StorageProof`
@@ -372,6 +381,33 @@ where
 	}
 }
 
+/// Apply `xt` on top of the current state of `api`.
+fn apply_extrinsic<Block, C>(
+	api: &C::Api,
+	parent_hash: Block::Hash,
+	version: u32,
+	xt: <Block as BlockT>::Extrinsic,
+) -> Result<(), Error>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: BlockBuilderApi<Block>,
+{
+	let res = if version < 6 {
+		#[allow(deprecated)]
+		api.apply_extrinsic_before_version_6(parent_hash, xt)
+			.map(legacy::byte_sized_error::convert_to_latest)
+	} else {
+		api.apply_extrinsic(parent_hash, xt)
+	};
+
+	match res {
+		Ok(Ok(_)) => Ok(()),
+		Ok(Err(tx_validity)) => Err(ApplyExtrinsicFailed::Validity(tx_validity).into()),
+		Err(e) => Err(Error::from(e)),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -459,4 +495,36 @@ mod tests {
 		assert!(proof_without_panic > proof_empty_block);
 		assert_eq!(proof_empty_block, proof_with_panic);
 	}
+
+	#[test]
+	fn push_all_includes_all_or_none_of_the_extrinsics() {
+		let builder = substrate_test_runtime_client::TestClientBuilder::new();
+		let client = builder.build();
+		let genesis_hash = client.info().best_hash;
+
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+
+		block_builder
+			.push_all(vec![
+				ExtrinsicBuilder::new_read(8).build(),
+				ExtrinsicBuilder::new_read_and_panic(8).build(),
+			])
+			.unwrap_err();
+		block_builder
+			.push_all(vec![
+				ExtrinsicBuilder::new_read(8).build(),
+				ExtrinsicBuilder::new_read(16).build(),
+			])
+			.unwrap();
+
+		let block = block_builder.build().unwrap().block;
+		assert_eq!(
+			block.extrinsics(),
+			&[ExtrinsicBuilder::new_read(8).build(), ExtrinsicBuilder::new_read(16).build()],
+		);
+	}
 }
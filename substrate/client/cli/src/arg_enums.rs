@@ -329,3 +329,23 @@ impl Into<sc_network::config::NetworkBackendType> for NetworkBackendType {
 		}
 	}
 }
+
+/// Transaction pool implementation.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+#[value(rename_all = "kebab-case")]
+pub enum TransactionPoolType {
+	/// Use the single-state transaction pool, validating transactions at the best block only.
+	SingleState,
+
+	/// Use the fork-aware transaction pool, keeping a view of the pool for every fork.
+	ForkAware,
+}
+
+impl Into<sc_service::TransactionPoolType> for TransactionPoolType {
+	fn into(self) -> sc_service::TransactionPoolType {
+		match self {
+			Self::SingleState => sc_service::TransactionPoolType::SingleState,
+			Self::ForkAware => sc_service::TransactionPoolType::ForkAware,
+		}
+	}
+}
@@ -71,7 +71,13 @@ impl InsertKeyCmd {
 				let keystore: KeystorePtr = LocalKeystore::open(path, password)?.into();
 				(keystore, public)
 			},
-			_ => unreachable!("keystore_config always returns path and password; qed"),
+			#[cfg(unix)]
+			KeystoreConfig::Remote { uri } => {
+				let public = with_crypto_scheme!(self.scheme, to_vec(&suri, None))?;
+				let keystore: KeystorePtr = sc_keystore::RemoteKeystore::open(&uri)?.into();
+				(keystore, public)
+			},
+			_ => unreachable!("keystore_config always returns a path or a remote uri; qed"),
 		};
 
 		let key_type =
@@ -35,6 +35,17 @@ pub struct KeystoreParams {
 	#[arg(long, value_name = "PATH")]
 	pub keystore_path: Option<PathBuf>,
 
+	/// Use the keys of a remote signer listening on the given Unix socket.
+	///
+	/// The socket is given as a path, optionally prefixed with `unix://`. Secret keys never
+	/// enter the node, every signing request is forwarded to the signer.
+	#[arg(
+		long,
+		value_name = "URI",
+		conflicts_with_all = &["keystore_path", "password_interactive", "password", "password_filename"]
+	)]
+	pub keystore_uri: Option<String>,
+
 	/// Use interactive shell for entering the password used by the keystore.
 	#[arg(long, conflicts_with_all = &["password", "password_filename"])]
 	pub password_interactive: bool,
@@ -66,6 +77,10 @@ pub fn secret_string_from_str(s: &str) -> std::result::Result<SecretString, Stri
 impl KeystoreParams {
 	/// Get the keystore configuration for the parameters
 	pub fn keystore_config(&self, config_dir: &Path) -> Result<KeystoreConfig> {
+		if let Some(uri) = &self.keystore_uri {
+			return Ok(KeystoreConfig::Remote { uri: uri.clone() })
+		}
+
 		let password = if self.password_interactive {
 			Some(SecretString::new(input_keystore_password()?))
 		} else if let Some(ref file) = self.password_filename {
@@ -45,6 +45,13 @@ pub struct PruningParams {
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
 	pub state_pruning: Option<DatabasePruningMode>,
 
+	/// Keep the state of every finalized block whose number is a multiple of this interval.
+	///
+	/// The states of other blocks are pruned according to the NUMBER state pruning mode, which
+	/// this option requires. Only supported by ParityDb.
+	#[arg(long, value_name = "INTERVAL", value_parser = clap::value_parser!(u32).range(1..))]
+	pub state_snapshot_interval: Option<u32>,
+
 	/// Specify the blocks pruning mode.
 	///
 	/// This mode specifies when the block's body (including justifications)
@@ -69,7 +76,14 @@ pub struct PruningParams {
 impl PruningParams {
 	/// Get the pruning value from the parameters
 	pub fn state_pruning(&self) -> error::Result<Option<PruningMode>> {
-		Ok(self.state_pruning.map(|v| v.into()))
+		match (self.state_pruning, self.state_snapshot_interval) {
+			(state_pruning, None) => Ok(state_pruning.map(|v| v.into())),
+			(Some(DatabasePruningMode::Custom(n)), Some(interval)) =>
+				Ok(Some(PruningMode::blocks_pruning_with_snapshots(n, interval))),
+			(_, Some(_)) => Err(error::Error::Input(
+				"`--state-snapshot-interval` requires a NUMBER `--state-pruning` mode".into(),
+			)),
+		}
 	}
 
 	/// Get the block pruning value from the parameters
@@ -162,4 +176,21 @@ mod tests {
 		assert!(matches!(dbg!(pruning.state_pruning), Some(DatabasePruningMode::ArchiveCanonical)));
 		assert!(matches!(pruning.blocks_pruning, DatabasePruningMode::ArchiveCanonical));
 	}
+
+	#[test]
+	fn state_snapshot_interval_requires_number_pruning() {
+		let Cli { pruning } =
+			Cli::parse_from(["", "--state-pruning=1000", "--state-snapshot-interval=10000"]);
+		assert_eq!(
+			pruning.state_pruning().unwrap(),
+			Some(PruningMode::blocks_pruning_with_snapshots(1000, 10000))
+		);
+
+		let Cli { pruning } =
+			Cli::parse_from(["", "--state-pruning=archive", "--state-snapshot-interval=10000"]);
+		assert!(pruning.state_pruning().is_err());
+
+		let Cli { pruning } = Cli::parse_from(["", "--state-snapshot-interval=10000"]);
+		assert!(pruning.state_pruning().is_err());
+	}
 }
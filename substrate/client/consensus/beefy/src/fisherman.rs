@@ -16,21 +16,34 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{error::Error, keystore::BeefyKeystore, round::Rounds, LOG_TARGET};
+use crate::{
+	error::Error, justification::BeefyVersionedFinalityProof, keystore::BeefyKeystore,
+	round::Rounds, LOG_TARGET,
+};
 use log::{debug, error, warn};
 use sc_client_api::Backend;
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::RuntimeAppPublic;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_beefy::{
-	check_double_voting_proof, AuthorityIdBound, BeefyApi, BeefySignatureHasher, DoubleVotingProof,
-	OpaqueKeyOwnershipProof, ValidatorSetId,
+	check_commitment_signature, check_double_voting_proof, AuthorityIdBound, BeefyApi,
+	BeefySignatureHasher, Commitment, DoubleVotingProof, ForkVotingProof, FutureBlockVotingProof,
+	OpaqueKeyOwnershipProof, PayloadProvider, ValidatorSetId, VersionedFinalityProof, VoteMessage,
 };
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block, NumberFor},
+	traits::{Block, NumberFor, Saturating},
 };
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, slice, sync::Arc};
+
+type Vote<B, AuthorityId> =
+	VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>;
+
+/// The number of blocks a commitment must target above our best block to be reported as
+/// future block voting.
+///
+/// Honest validators vote on blocks they finalized, which we may not have imported yet.
+pub(crate) const FUTURE_BLOCK_VOTING_MARGIN: u32 = 32;
 
 /// Helper struct containing the id and the key ownership proof for a validator.
 pub struct ProvedValidator<'a, AuthorityId: AuthorityIdBound> {
@@ -38,18 +51,29 @@ pub struct ProvedValidator<'a, AuthorityId: AuthorityIdBound> {
 	pub key_owner_proof: OpaqueKeyOwnershipProof,
 }
 
+/// How a commitment deviates from the local view of the canonical chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Deviation {
+	/// The commitment targets a block that is not part of our chain yet.
+	FutureBlock,
+	/// The commitment payload differs from the one of the finalized block at the same height.
+	Fork,
+}
+
 /// Helper used to check and report equivocations.
-pub struct Fisherman<B, BE, RuntimeApi, AuthorityId: AuthorityIdBound> {
+pub struct Fisherman<B, BE, P, RuntimeApi, AuthorityId: AuthorityIdBound> {
 	backend: Arc<BE>,
 	runtime: Arc<RuntimeApi>,
 	key_store: Arc<BeefyKeystore<AuthorityId>>,
+	payload_provider: P,
 
 	_phantom: PhantomData<B>,
 }
 
-impl<B: Block, BE: Backend<B>, RuntimeApi: ProvideRuntimeApi<B>, AuthorityId>
-	Fisherman<B, BE, RuntimeApi, AuthorityId>
+impl<B: Block, BE: Backend<B>, P, RuntimeApi: ProvideRuntimeApi<B>, AuthorityId>
+	Fisherman<B, BE, P, RuntimeApi, AuthorityId>
 where
+	P: PayloadProvider<B>,
 	RuntimeApi::Api: BeefyApi<B, AuthorityId>,
 	AuthorityId: AuthorityIdBound,
 {
@@ -57,8 +81,43 @@ where
 		backend: Arc<BE>,
 		runtime: Arc<RuntimeApi>,
 		keystore: Arc<BeefyKeystore<AuthorityId>>,
+		payload_provider: P,
 	) -> Self {
-		Self { backend, runtime, key_store: keystore, _phantom: Default::default() }
+		Self {
+			backend,
+			runtime,
+			key_store: keystore,
+			payload_provider,
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Compare `commitment` against the local canonical chain.
+	///
+	/// Commitments for blocks that are not finalized yet can't be judged and are ignored,
+	/// unless they target a block more than [`FUTURE_BLOCK_VOTING_MARGIN`] blocks above our best
+	/// block. Callers must only check commitments once the node is synced.
+	fn canonical_deviation(
+		&self,
+		commitment: &Commitment<NumberFor<B>>,
+	) -> Result<Option<Deviation>, Error> {
+		let info = self.backend.blockchain().info();
+		let number = commitment.block_number;
+		if number > info.best_number.saturating_add(FUTURE_BLOCK_VOTING_MARGIN.into()) {
+			return Ok(Some(Deviation::FutureBlock))
+		}
+		if number > info.finalized_number {
+			return Ok(None)
+		}
+
+		let blockchain = self.backend.blockchain();
+		let hash = blockchain.expect_block_hash_from_id(&BlockId::Number(number))?;
+		let header = blockchain.expect_header(hash)?;
+
+		Ok(match self.payload_provider.payload(&header) {
+			Some(canonical) if canonical != commitment.payload => Some(Deviation::Fork),
+			_ => None,
+		})
 	}
 
 	fn prove_offenders<'a>(
@@ -165,4 +224,139 @@ where
 
 		Ok(())
 	}
+
+	/// Check `vote` against the local canonical chain and report the voter if it signed
+	/// a commitment for a block we don't know of or a payload that differs from ours.
+	pub fn check_vote(&self, vote: Vote<B, AuthorityId>) -> Result<(), Error> {
+		let Some(deviation) = self.canonical_deviation(&vote.commitment)? else { return Ok(()) };
+
+		if !check_commitment_signature::<_, _, BeefySignatureHasher>(
+			&vote.commitment,
+			&vote.id,
+			&vote.signature,
+		) {
+			debug!(target: LOG_TARGET, "🥩 Skipping report for badly signed vote {:?}", vote);
+			return Ok(());
+		}
+
+		self.report(vote, deviation)
+	}
+
+	/// Check the commitment of `proof` against the local canonical chain and report every
+	/// signatory if it deviates from it.
+	pub fn check_proof(
+		&self,
+		proof: BeefyVersionedFinalityProof<B, AuthorityId>,
+	) -> Result<(), Error> {
+		let VersionedFinalityProof::V1(signed_commitment) = proof;
+		let Some(deviation) = self.canonical_deviation(&signed_commitment.commitment)? else {
+			return Ok(())
+		};
+
+		let best_hash = self.backend.blockchain().info().best_hash;
+		let validator_set =
+			match self.runtime.runtime_api().validator_set(best_hash).map_err(Error::RuntimeApi)? {
+				Some(set) if set.id() == signed_commitment.commitment.validator_set_id => set,
+				_ => {
+					debug!(
+						target: LOG_TARGET,
+						"🥩 Unknown validator set {} for deviating proof, skipping report",
+						signed_commitment.commitment.validator_set_id
+					);
+					return Ok(())
+				},
+			};
+
+		let commitment = signed_commitment.commitment;
+		for (id, signature) in validator_set.validators().iter().zip(signed_commitment.signatures) {
+			let Some(signature) = signature else { continue };
+			if !check_commitment_signature::<_, _, BeefySignatureHasher>(
+				&commitment,
+				id,
+				&signature,
+			) {
+				continue
+			}
+			let vote = VoteMessage { commitment: commitment.clone(), id: id.clone(), signature };
+			self.report(vote, deviation)?;
+		}
+
+		Ok(())
+	}
+
+	/// Build the equivocation proof for `deviation` and submit it at the **best** block.
+	///
+	/// The key ownership proof is generated at the best block as well, so only votes of the
+	/// validator set active at the best block are reported.
+	fn report(&self, vote: Vote<B, AuthorityId>, deviation: Deviation) -> Result<(), Error> {
+		if self.key_store.authority_id(slice::from_ref(&vote.id)).is_some() {
+			warn!(target: LOG_TARGET, "🥩 Skipping report for own equivocation");
+			return Ok(());
+		}
+
+		let best_hash = self.backend.blockchain().info().best_hash;
+		let validator_set_id = vote.commitment.validator_set_id;
+		let runtime_api = self.runtime.runtime_api();
+		match runtime_api.validator_set(best_hash).map_err(Error::RuntimeApi)? {
+			Some(set) if set.id() == validator_set_id => {},
+			_ => {
+				debug!(
+					target: LOG_TARGET,
+					"🥩 Validator set {} not active at best block, skipping report",
+					validator_set_id
+				);
+				return Ok(())
+			},
+		}
+
+		let key_owner_proofs = self.prove_offenders(
+			BlockId::Hash(best_hash),
+			vec![&vote.id].into_iter(),
+			validator_set_id,
+		)?;
+		match deviation {
+			Deviation::FutureBlock => {
+				for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+					runtime_api
+						.submit_report_future_block_voting_unsigned_extrinsic(
+							best_hash,
+							FutureBlockVotingProof { vote: vote.clone() },
+							key_owner_proof,
+						)
+						.map_err(Error::RuntimeApi)?;
+				}
+			},
+			Deviation::Fork => {
+				let number = vote.commitment.block_number;
+				let Some(ancestry_proof) = runtime_api
+					.generate_ancestry_proof(best_hash, number, None)
+					.map_err(Error::RuntimeApi)?
+				else {
+					debug!(
+						target: LOG_TARGET,
+						"🥩 Couldn't generate ancestry proof for block #{:?}, skipping report",
+						number
+					);
+					return Ok(())
+				};
+				let header = self.backend.blockchain().expect_header(best_hash)?;
+
+				for ProvedValidator { key_owner_proof, .. } in key_owner_proofs {
+					runtime_api
+						.submit_report_fork_voting_unsigned_extrinsic(
+							best_hash,
+							ForkVotingProof {
+								vote: vote.clone(),
+								ancestry_proof: ancestry_proof.clone(),
+								header: header.clone(),
+							},
+							key_owner_proof,
+						)
+						.map_err(Error::RuntimeApi)?;
+				}
+			},
+		}
+
+		Ok(())
+	}
 }
@@ -134,6 +134,11 @@ where
 		self.mandatory_done
 	}
 
+	/// Whether a vote of `id` for round `round_num` was already imported.
+	pub(crate) fn has_voted(&self, id: &AuthorityId, round_num: NumberFor<B>) -> bool {
+		self.previous_votes.contains_key(&(id.clone(), round_num))
+	}
+
 	pub(crate) fn add_vote(
 		&mut self,
 		vote: VoteMessage<NumberFor<B>, AuthorityId, <AuthorityId as RuntimeAppPublic>::Signature>,
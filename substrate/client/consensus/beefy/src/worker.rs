@@ -387,7 +387,7 @@ pub(crate) struct BeefyWorker<B: Block, BE, P, RuntimeApi, S, N, AuthorityId: Au
 	pub key_store: Arc<BeefyKeystore<AuthorityId>>,
 	pub payload_provider: P,
 	pub sync: Arc<S>,
-	pub fisherman: Arc<Fisherman<B, BE, RuntimeApi, AuthorityId>>,
+	pub fisherman: Arc<Fisherman<B, BE, P, RuntimeApi, AuthorityId>>,
 
 	// communication (created once, but returned and reused if worker is restarted/reinitialized)
 	pub comms: BeefyComms<B, N, AuthorityId>,
@@ -548,6 +548,15 @@ where
 		&mut self,
 		justification: BeefyVersionedFinalityProof<B, AuthorityId>,
 	) -> Result<(), Error> {
+		if !self.sync.is_major_syncing() {
+			if let Err(e) = self.fisherman.check_proof(justification.clone()) {
+				debug!(
+					target: LOG_TARGET,
+					"🥩 Failed to check justification for equivocations: {:?}", e
+				);
+			}
+		}
+
 		let signed_commitment = match justification {
 			VersionedFinalityProof::V1(ref sc) => sc,
 		};
@@ -583,7 +592,19 @@ where
 		let rounds = self.persisted_state.voting_oracle.active_rounds_mut()?;
 
 		let block_number = vote.commitment.block_number;
-		match rounds.add_vote(vote) {
+		// Only the first vote of a validator for a round is checked against the canonical chain.
+		let to_check = (!rounds.has_voted(&vote.id, block_number)).then(|| vote.clone());
+		let import_result = rounds.add_vote(vote);
+		if let Some(vote) = to_check {
+			let accepted =
+				matches!(import_result, VoteImportResult::Ok | VoteImportResult::RoundConcluded(_));
+			if accepted && !self.sync.is_major_syncing() {
+				if let Err(e) = self.fisherman.check_vote(vote) {
+					debug!(target: LOG_TARGET, "🥩 Failed to check vote for equivocations: {:?}", e);
+				}
+			}
+		}
+		match import_result {
 			VoteImportResult::RoundConcluded(signed_commitment) => {
 				let finality_proof = VersionedFinalityProof::V1(signed_commitment);
 				debug!(
@@ -1044,7 +1065,7 @@ pub(crate) mod tests {
 		ecdsa_crypto, known_payloads,
 		known_payloads::MMR_ROOT_ID,
 		mmr::MmrRootProvider,
-		test_utils::{generate_double_voting_proof, Keyring},
+		test_utils::{generate_double_voting_proof, signed_vote, Keyring},
 		ConsensusLog, Payload, SignedCommitment,
 	};
 	use sp_runtime::traits::{Header as HeaderT, One};
@@ -1153,9 +1174,9 @@ pub(crate) mod tests {
 			runtime: api.clone(),
 			key_store: key_store.clone(),
 			metrics,
-			payload_provider,
+			payload_provider: payload_provider.clone(),
 			sync: Arc::new(sync),
-			fisherman: Arc::new(Fisherman::new(backend, api, key_store)),
+			fisherman: Arc::new(Fisherman::new(backend, api, key_store, payload_provider)),
 			links,
 			comms,
 			pending_justifications: BTreeMap::new(),
@@ -1582,6 +1603,7 @@ pub(crate) mod tests {
 			worker.backend.clone(),
 			worker.runtime.clone(),
 			worker.key_store.clone(),
+			MmrRootProvider::new(worker.runtime.clone()),
 		));
 
 		// let there be a block with num = 1:
@@ -1632,4 +1654,73 @@ pub(crate) mod tests {
 		// verify nothing reported to runtime
 		assert!(api_alice.reported_equivocations.as_ref().unwrap().lock().is_empty());
 	}
+
+	#[tokio::test]
+	async fn should_report_fork_and_future_block_votings() {
+		let set_id = 0;
+		let keys = [Keyring::Alice, Keyring::Bob];
+		let validator_set = ValidatorSet::new(make_beefy_ids(&keys), set_id).unwrap();
+
+		let mut net = BeefyTestNet::new(1);
+		let worker = create_beefy_worker(net.peer(0), &keys[0], 1, validator_set.clone());
+		let api = worker.runtime.clone();
+		// block #1 is finalized and its canonical payload is the good MMR root
+		let good_payload =
+			Payload::from_single_entry(MMR_ROOT_ID, crate::tests::GOOD_MMR_ROOT.encode());
+		let bad_payload = Payload::from_single_entry(MMR_ROOT_ID, vec![42]);
+
+		// votes matching the canonical chain are not reported
+		let vote = signed_vote(1, good_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(vote), Ok(()));
+		assert!(api.reported_fork_votings.lock().is_empty());
+
+		// Bob voting on a forked payload for finalized block #1 is reported
+		let fork_vote = signed_vote(1, bad_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(fork_vote.clone()), Ok(()));
+		{
+			let reported = api.reported_fork_votings.lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, fork_vote);
+		}
+
+		// badly signed votes are ignored
+		let mut bad_signature = fork_vote.clone();
+		bad_signature.id = Keyring::Charlie.public();
+		assert_eq!(worker.fisherman.check_vote(bad_signature), Ok(()));
+		assert_eq!(api.reported_fork_votings.lock().len(), 1);
+
+		// own votes are never reported
+		let self_vote = signed_vote(1, bad_payload.clone(), set_id, &Keyring::Alice);
+		assert_eq!(worker.fisherman.check_vote(self_vote), Ok(()));
+		assert_eq!(api.reported_fork_votings.lock().len(), 1);
+
+		// Bob voting for a block slightly above our best block is not reported, we may be behind
+		let best_number = worker.backend.blockchain().info().best_number;
+		let margin = crate::fisherman::FUTURE_BLOCK_VOTING_MARGIN as u64;
+		let near_vote =
+			signed_vote(best_number + margin, good_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(near_vote), Ok(()));
+		assert!(api.reported_future_block_votings.lock().is_empty());
+
+		// Bob voting for a block too far above our best block is reported
+		let future_vote =
+			signed_vote(best_number + margin + 1, good_payload.clone(), set_id, &Keyring::Bob);
+		assert_eq!(worker.fisherman.check_vote(future_vote.clone()), Ok(()));
+		{
+			let reported = api.reported_future_block_votings.lock();
+			assert_eq!(reported.len(), 1);
+			assert_eq!(reported[0].vote, future_vote);
+		}
+
+		// every other signatory of a forked justification is reported
+		let commitment = fork_vote.commitment.clone();
+		let signatures = keys.iter().map(|k| Some(k.sign(&commitment.encode()))).collect();
+		let proof = SignedCommitment { commitment, signatures }.into();
+		assert_eq!(worker.fisherman.check_proof(proof), Ok(()));
+		{
+			let reported = api.reported_fork_votings.lock();
+			assert_eq!(reported.len(), 2);
+			assert_eq!(reported[1].vote, fork_vote);
+		}
+	}
 }
@@ -25,7 +25,7 @@ use std::{
 use codec::{Decode, DecodeAll, Encode};
 use finality_grandpa::{voter_set::VoterSet, Error as GrandpaError};
 use sp_blockchain::{Error as ClientError, HeaderBackend};
-use sp_consensus_grandpa::AuthorityId;
+use sp_consensus_grandpa::{AuthorityId, AuthoritySignature};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
 
 use crate::{AuthorityList, Commit, Error};
@@ -168,6 +168,33 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 		set_id: u64,
 		voters: &VoterSet<AuthorityId>,
 	) -> Result<(), ClientError>
+	where
+		NumberFor<Block>: finality_grandpa::BlockNumberOps,
+	{
+		self.verify_votes(voters)?;
+
+		let mut buf = Vec::new();
+		for signed in self.justification.commit.precommits.iter() {
+			if !sp_consensus_grandpa::check_message_signature_with_buffer(
+				&finality_grandpa::Message::Precommit(signed.precommit.clone()),
+				&signed.id,
+				&signed.signature,
+				self.justification.round,
+				set_id,
+				&mut buf,
+			) {
+				return Err(ClientError::BadJustification(
+					"invalid signature for precommit in grandpa justification".to_string(),
+				))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Validate the commit and the votes' ancestry proofs, leaving the precommit signatures to
+	/// the caller, see [`Self::signed_payloads`].
+	pub(crate) fn verify_votes(&self, voters: &VoterSet<AuthorityId>) -> Result<(), ClientError>
 	where
 		NumberFor<Block>: finality_grandpa::BlockNumberOps,
 	{
@@ -202,22 +229,8 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 				 qed.",
 			);
 
-		let mut buf = Vec::new();
 		let mut visited_hashes = HashSet::new();
 		for signed in self.justification.commit.precommits.iter() {
-			if !sp_consensus_grandpa::check_message_signature_with_buffer(
-				&finality_grandpa::Message::Precommit(signed.precommit.clone()),
-				&signed.id,
-				&signed.signature,
-				self.justification.round,
-				set_id,
-				&mut buf,
-			) {
-				return Err(ClientError::BadJustification(
-					"invalid signature for precommit in grandpa justification".to_string(),
-				))
-			}
-
 			if base_hash == signed.precommit.target_hash {
 				continue
 			}
@@ -255,6 +268,19 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 		Ok(())
 	}
 
+	/// The signer, signature and signed payload of every precommit, e.g. for batch verification.
+	pub(crate) fn signed_payloads(
+		&self,
+		set_id: u64,
+	) -> impl Iterator<Item = (&AuthorityId, &AuthoritySignature, Vec<u8>)> + '_ {
+		self.justification.commit.precommits.iter().map(move |signed| {
+			let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
+			let payload =
+				sp_consensus_grandpa::localized_payload(self.justification.round, set_id, &message);
+			(&signed.id, &signed.signature, payload)
+		})
+	}
+
 	/// The target block number and hash that this justifications proves finality for.
 	pub fn target(&self) -> (NumberFor<Block>, Block::Hash) {
 		(self.justification.commit.target_number, self.justification.commit.target_hash)
@@ -15,6 +15,12 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Utilities for generating and verifying GRANDPA warp sync proofs.
+//!
+//! The proof format is negotiated through the warp proof protocol version of the request: version
+//! 1 is answered with a `WarpSyncProof` carrying full justifications and version 2 with a
+//! `CompactWarpSyncProof`. Both formats only rely on GRANDPA justifications. BEEFY-assisted
+//! proofs would need a BEEFY light client on the verifier side and are not supported; they can be
+//! added later as a protocol version of their own without affecting versions 1 and 2.
 
 use codec::{Decode, DecodeAll, Encode};
 
@@ -22,13 +28,16 @@ use crate::{
 	best_justification, find_scheduled_change, AuthoritySetChanges, AuthoritySetHardFork,
 	BlockNumberOps, GrandpaJustification, SharedAuthoritySet,
 };
+use finality_grandpa::voter_set::VoterSet;
 use sc_client_api::Backend as ClientBackend;
 use sc_network_sync::strategy::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
 use sp_blockchain::{Backend as BlockchainBackend, HeaderBackend};
-use sp_consensus_grandpa::{AuthorityList, SetId, GRANDPA_ENGINE_ID};
+use sp_consensus_grandpa::{
+	AuthorityId, AuthorityList, AuthoritySignature, SetId, GRANDPA_ENGINE_ID,
+};
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Zero},
 };
 
 use std::{collections::HashMap, sync::Arc};
@@ -56,6 +65,9 @@ pub enum Error {
 /// The maximum size in bytes of the `WarpSyncProof`.
 pub(super) const MAX_WARP_SYNC_PROOF_SIZE: usize = 8 * 1024 * 1024;
 
+/// First warp proof protocol version that is answered with a `CompactWarpSyncProof`.
+const COMPACT_WARP_SYNC_PROOF_VERSION: u8 = 2;
+
 /// A proof of an authority set change.
 #[derive(Decode, Encode, Debug)]
 pub struct WarpSyncFragment<Block: BlockT> {
@@ -86,103 +98,72 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 	where
 		Backend: ClientBackend<Block>,
 	{
-		// TODO: cache best response (i.e. the one with lowest begin_number)
-		let blockchain = backend.blockchain();
+		let (proofs, is_finished) =
+			generate_fragments(backend, begin, set_changes, |fragment| fragment)?;
 
-		let begin_number = blockchain
-			.block_number_from_id(&BlockId::Hash(begin))?
-			.ok_or_else(|| Error::InvalidRequest("Missing start block".to_string()))?;
+		let final_outcome = WarpSyncProof { proofs, is_finished };
+		debug_assert!(final_outcome.encoded_size() <= MAX_WARP_SYNC_PROOF_SIZE);
+		Ok(final_outcome)
+	}
 
-		if begin_number > blockchain.info().finalized_number {
-			return Err(Error::InvalidRequest("Start block is not finalized".to_string()))
-		}
+	/// Generates a proof of the finality of `target` starting at genesis: the authority set
+	/// changes that happened before `target`, followed by the justification of `target` itself.
+	/// Unlike [`Self::generate`], the proof is not capped in size.
+	fn generate_to<Backend>(
+		backend: &Backend,
+		target: Block::Hash,
+		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+	) -> Result<WarpSyncProof<Block>, Error>
+	where
+		Backend: ClientBackend<Block>,
+	{
+		let blockchain = backend.blockchain();
 
-		let canon_hash = blockchain.hash(begin_number)?.expect(
-			"begin number is lower than finalized number; \
-			 all blocks below finalized number must have been imported; \
-			 qed.",
-		);
+		let target_header = blockchain
+			.header(target)?
+			.ok_or_else(|| Error::InvalidRequest("Missing target block".to_string()))?;
+		let target_number = *target_header.number();
 
-		if canon_hash != begin {
-			return Err(Error::InvalidRequest(
-				"Start block is not in the finalized chain".to_string(),
-			))
+		if target_number > blockchain.info().finalized_number ||
+			blockchain.hash(target_number)? != Some(target)
+		{
+			return Err(Error::InvalidRequest("Target block is not finalized".to_string()))
 		}
 
-		let mut proofs = Vec::new();
-		let mut proofs_encoded_len = 0;
-		let mut proof_limit_reached = false;
-
-		let set_changes = set_changes.iter_from(begin_number).ok_or(Error::MissingData)?;
-
-		for (_, last_block) in set_changes {
-			let hash = blockchain.block_hash_from_id(&BlockId::Number(*last_block))?
-				.expect("header number comes from previously applied set changes; corresponding hash must exist in db; qed.");
-
-			let header = blockchain
-				.header(hash)?
-				.expect("header hash obtained from header number exists in db; corresponding header must exist in db too; qed.");
-
-			// the last block in a set is the one that triggers a change to the next set,
-			// therefore the block must have a digest that signals the authority set change
-			if find_scheduled_change::<Block>(&header).is_none() {
-				// if it doesn't contain a signal for standard change then the set must have changed
-				// through a forced changed, in which case we stop collecting proofs as the chain of
-				// trust in authority handoffs was broken.
-				break
-			}
-
+		let justification = |hash| -> Result<GrandpaJustification<Block>, Error> {
 			let justification = blockchain
-				.justifications(header.hash())?
+				.justifications(hash)?
 				.and_then(|just| just.into_justification(GRANDPA_ENGINE_ID))
-				.ok_or_else(|| Error::MissingData)?;
+				.ok_or(Error::MissingData)?;
+			Ok(GrandpaJustification::<Block>::decode_all(&mut &justification[..])?)
+		};
 
-			let justification = GrandpaJustification::<Block>::decode_all(&mut &justification[..])?;
+		let mut proofs = Vec::new();
+		let set_changes = set_changes.iter_from(Zero::zero()).ok_or(Error::MissingData)?;
 
-			let proof = WarpSyncFragment { header: header.clone(), justification };
-			let proof_size = proof.encoded_size();
+		for (_, last_block) in set_changes.take_while(|(_, last_block)| *last_block < target_number)
+		{
+			let hash = blockchain.hash(*last_block)?.ok_or(Error::MissingData)?;
+			let header = blockchain.header(hash)?.ok_or(Error::MissingData)?;
 
-			// Check for the limit. We remove some bytes from the maximum size, because we're only
-			// counting the size of the `WarpSyncFragment`s. The extra margin is here to leave
-			// room for rest of the data (the size of the `Vec` and the boolean).
-			if proofs_encoded_len + proof_size >= MAX_WARP_SYNC_PROOF_SIZE - 50 {
-				proof_limit_reached = true;
-				break
+			// Forced changes break the chain of trust in authority handoffs, see
+			// `generate_fragments`.
+			if find_scheduled_change::<Block>(&header).is_none() {
+				return Err(Error::InvalidRequest(format!(
+					"Authority set was forced to change at block #{}",
+					last_block,
+				)))
 			}
 
-			proofs_encoded_len += proof_size;
-			proofs.push(proof);
+			proofs.push(WarpSyncFragment { header, justification: justification(hash)? });
 		}
 
-		let is_finished = if proof_limit_reached {
-			false
-		} else {
-			let latest_justification = best_justification(backend)?.filter(|justification| {
-				// the existing best justification must be for a block higher than the
-				// last authority set change. if we didn't prove any authority set
-				// change then we fallback to make sure it's higher or equal to the
-				// initial warp sync block.
-				let limit = proofs
-					.last()
-					.map(|proof| proof.justification.target().0 + One::one())
-					.unwrap_or(begin_number);
-
-				justification.target().0 >= limit
-			});
-
-			if let Some(latest_justification) = latest_justification {
-				let header = blockchain.header(latest_justification.target().1)?
-					.expect("header hash corresponds to a justification in db; must exist in db as well; qed.");
-
-				proofs.push(WarpSyncFragment { header, justification: latest_justification })
-			}
-
-			true
-		};
+		proofs.push(WarpSyncFragment {
+			header: target_header,
+			justification: justification(target)?,
+		});
 
-		let final_outcome = WarpSyncProof { proofs, is_finished };
-		debug_assert!(final_outcome.encoded_size() <= MAX_WARP_SYNC_PROOF_SIZE);
-		Ok(final_outcome)
+		Ok(WarpSyncProof { proofs, is_finished: true })
 	}
 
 	/// Verifies the warp sync proof starting at the given set id and with the given authorities.
@@ -235,6 +216,344 @@ impl<Block: BlockT> WarpSyncProof<Block> {
 	}
 }
 
+/// The signer of a precommit in a `CompactWarpSyncFragment`.
+#[derive(Decode, Encode, Debug)]
+enum CompactSigner {
+	/// Index of the signer in the authority set the fragment is verified against.
+	Index(#[codec(compact)] u32),
+	/// A signer whose authority set was unknown to the prover.
+	Id(AuthorityId),
+}
+
+/// A precommit in a `CompactWarpSyncFragment`.
+#[derive(Decode, Encode, Debug)]
+struct CompactPrecommit<Block: BlockT> {
+	signer: CompactSigner,
+	/// The precommit target, unless it is the fragment header.
+	target: Option<(Block::Hash, NumberFor<Block>)>,
+	signature: AuthoritySignature,
+}
+
+/// A `WarpSyncFragment` without the data the verifier already knows: the commit targets the
+/// fragment header and precommit signers are referenced by their index in the authority set.
+#[derive(Decode, Encode, Debug)]
+struct CompactWarpSyncFragment<Block: BlockT> {
+	header: Block::Header,
+	round: u64,
+	precommits: Vec<CompactPrecommit<Block>>,
+	votes_ancestries: Vec<Block::Header>,
+}
+
+impl<Block: BlockT> CompactWarpSyncFragment<Block> {
+	/// Compacts the given fragment, referencing signers by their index in `authorities` if the
+	/// authority set of the fragment is known.
+	fn new(fragment: WarpSyncFragment<Block>, authorities: Option<&AuthorityList>) -> Self {
+		let WarpSyncFragment { header, justification } = fragment;
+		let sp_consensus_grandpa::GrandpaJustification { round, commit, votes_ancestries } =
+			justification.into();
+		let commit_target = (commit.target_hash, commit.target_number);
+
+		let precommits = commit
+			.precommits
+			.into_iter()
+			.map(|signed| {
+				let index = authorities.and_then(|authorities| {
+					authorities.iter().position(|(id, _)| *id == signed.id)
+				});
+				let signer = match index {
+					Some(index) => CompactSigner::Index(index as u32),
+					None => CompactSigner::Id(signed.id),
+				};
+				let target = (signed.precommit.target_hash, signed.precommit.target_number);
+
+				CompactPrecommit {
+					signer,
+					target: (target != commit_target).then_some(target),
+					signature: signed.signature,
+				}
+			})
+			.collect();
+
+		CompactWarpSyncFragment { header, round, precommits, votes_ancestries }
+	}
+
+	/// Restores the GRANDPA justification of this fragment, given the authority set it is
+	/// verified against.
+	fn justification(
+		&self,
+		authorities: &AuthorityList,
+	) -> Result<GrandpaJustification<Block>, Error> {
+		let (target_hash, target_number) = (self.header.hash(), *self.header.number());
+
+		let precommits = self
+			.precommits
+			.iter()
+			.map(|precommit| {
+				let id = match &precommit.signer {
+					CompactSigner::Index(index) => authorities
+						.get(*index as usize)
+						.map(|(id, _)| id.clone())
+						.ok_or_else(|| {
+							Error::InvalidProof("Precommit signer index out of bounds".to_string())
+						})?,
+					CompactSigner::Id(id) => id.clone(),
+				};
+				let (target_hash, target_number) =
+					precommit.target.unwrap_or((target_hash, target_number));
+
+				Ok(finality_grandpa::SignedPrecommit {
+					precommit: finality_grandpa::Precommit { target_hash, target_number },
+					signature: precommit.signature.clone(),
+					id,
+				})
+			})
+			.collect::<Result<_, Error>>()?;
+
+		let commit = finality_grandpa::Commit { target_hash, target_number, precommits };
+
+		Ok(sp_consensus_grandpa::GrandpaJustification {
+			round: self.round,
+			commit,
+			votes_ancestries: self.votes_ancestries.clone(),
+		}
+		.into())
+	}
+}
+
+/// A `WarpSyncProof` made of `CompactWarpSyncFragment`s, whose precommit signatures are verified
+/// in a single batch.
+#[derive(Decode, Encode)]
+pub struct CompactWarpSyncProof<Block: BlockT> {
+	proofs: Vec<CompactWarpSyncFragment<Block>>,
+	is_finished: bool,
+}
+
+impl<Block: BlockT> CompactWarpSyncProof<Block> {
+	/// Generates a compact warp sync proof starting at the given block, see
+	/// `WarpSyncProof::generate`. The hard forks are needed to follow the authority set the
+	/// verifier will use for each fragment.
+	fn generate<Backend>(
+		backend: &Backend,
+		begin: Block::Hash,
+		set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+		hard_forks: &HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+	) -> Result<CompactWarpSyncProof<Block>, Error>
+	where
+		Backend: ClientBackend<Block>,
+	{
+		// The authority set of the starting block is not known here, so the signers of the first
+		// fragment are sent in full.
+		let mut authorities = None;
+
+		let (proofs, is_finished) = generate_fragments(backend, begin, set_changes, |fragment| {
+			let fork = hard_forks.get(&(fragment.header.hash(), *fragment.header.number()));
+			if let Some((_, list)) = fork {
+				authorities = Some(list.clone());
+			}
+
+			let fragment = CompactWarpSyncFragment::new(fragment, authorities.as_ref());
+
+			if fork.is_none() {
+				if let Some(scheduled_change) = find_scheduled_change::<Block>(&fragment.header) {
+					authorities = Some(scheduled_change.next_authorities);
+				}
+			}
+
+			fragment
+		})?;
+
+		let final_outcome = CompactWarpSyncProof { proofs, is_finished };
+		debug_assert!(final_outcome.encoded_size() < MAX_WARP_SYNC_PROOF_SIZE);
+		Ok(final_outcome)
+	}
+
+	/// Verifies the compact warp sync proof, see `WarpSyncProof::verify`. The precommit
+	/// signatures of all fragments are checked together once the authority set handoffs have
+	/// been followed.
+	fn verify(
+		&self,
+		set_id: SetId,
+		authorities: AuthorityList,
+		hard_forks: &HashMap<(Block::Hash, NumberFor<Block>), (SetId, AuthorityList)>,
+	) -> Result<(SetId, AuthorityList), Error>
+	where
+		NumberFor<Block>: BlockNumberOps,
+	{
+		let mut current_set_id = set_id;
+		let mut current_authorities = authorities;
+		let mut signatures = ed25519_zebra::batch::Verifier::new();
+
+		for (fragment_num, proof) in self.proofs.iter().enumerate() {
+			let hash = proof.header.hash();
+			let number = *proof.header.number();
+
+			if let Some((set_id, list)) = hard_forks.get(&(hash, number)) {
+				current_set_id = *set_id;
+				current_authorities = list.clone();
+			} else {
+				let voters = VoterSet::new(current_authorities.iter().cloned())
+					.ok_or_else(|| Error::InvalidProof("Invalid authority set".to_string()))?;
+				let justification = proof.justification(&current_authorities)?;
+
+				justification
+					.verify_votes(&voters)
+					.map_err(|err| Error::InvalidProof(err.to_string()))?;
+
+				for (id, signature, payload) in justification.signed_payloads(current_set_id) {
+					let (id, signature): (&[u8], &[u8]) = (id.as_ref(), signature.as_ref());
+					let key = ed25519_zebra::VerificationKeyBytes::try_from(id)
+						.map_err(|_| Error::InvalidProof("Invalid authority id".to_string()))?;
+					let signature = ed25519_zebra::Signature::try_from(signature)
+						.map_err(|_| Error::InvalidProof("Invalid signature".to_string()))?;
+					signatures.queue((key, signature, &payload));
+				}
+
+				if let Some(scheduled_change) = find_scheduled_change::<Block>(&proof.header) {
+					current_authorities = scheduled_change.next_authorities;
+					current_set_id += 1;
+				} else if fragment_num != self.proofs.len() - 1 || !self.is_finished {
+					// Only the last fragment of the last proof message is allowed to be missing the
+					// authority set change.
+					return Err(Error::InvalidProof(
+						"Header is missing authority set change digest".to_string(),
+					))
+				}
+			}
+		}
+
+		signatures.verify(rand::thread_rng()).map_err(|_| {
+			Error::InvalidProof("invalid signature for precommit in warp sync proof".to_string())
+		})?;
+
+		Ok((current_set_id, current_authorities))
+	}
+}
+
+/// Collects authority set change fragments starting at the given block, converting each of them
+/// with `convert`. Fragments are collected until the current authority set is reached or the
+/// encoded fragments would exceed `MAX_WARP_SYNC_PROOF_SIZE`. Returns the fragments and whether
+/// the current authority set was reached.
+fn generate_fragments<Block, Backend, Fragment>(
+	backend: &Backend,
+	begin: Block::Hash,
+	set_changes: &AuthoritySetChanges<NumberFor<Block>>,
+	mut convert: impl FnMut(WarpSyncFragment<Block>) -> Fragment,
+) -> Result<(Vec<Fragment>, bool), Error>
+where
+	Block: BlockT,
+	Backend: ClientBackend<Block>,
+	Fragment: Encode,
+{
+	// TODO: cache best response (i.e. the one with lowest begin_number)
+	let blockchain = backend.blockchain();
+
+	let begin_number = blockchain
+		.block_number_from_id(&BlockId::Hash(begin))?
+		.ok_or_else(|| Error::InvalidRequest("Missing start block".to_string()))?;
+
+	if begin_number > blockchain.info().finalized_number {
+		return Err(Error::InvalidRequest("Start block is not finalized".to_string()))
+	}
+
+	let canon_hash = blockchain.hash(begin_number)?.expect(
+		"begin number is lower than finalized number; \
+		 all blocks below finalized number must have been imported; \
+		 qed.",
+	);
+
+	if canon_hash != begin {
+		return Err(Error::InvalidRequest("Start block is not in the finalized chain".to_string()))
+	}
+
+	let mut proofs = Vec::new();
+	let mut proofs_encoded_len = 0;
+	let mut proof_limit_reached = false;
+	let mut last_target = None;
+
+	let set_changes = set_changes.iter_from(begin_number).ok_or(Error::MissingData)?;
+
+	for (_, last_block) in set_changes {
+		let hash = blockchain.block_hash_from_id(&BlockId::Number(*last_block))?
+			.expect("header number comes from previously applied set changes; corresponding hash must exist in db; qed.");
+
+		let header = blockchain
+			.header(hash)?
+			.expect("header hash obtained from header number exists in db; corresponding header must exist in db too; qed.");
+
+		// the last block in a set is the one that triggers a change to the next set,
+		// therefore the block must have a digest that signals the authority set change
+		if find_scheduled_change::<Block>(&header).is_none() {
+			// if it doesn't contain a signal for standard change then the set must have changed
+			// through a forced changed, in which case we stop collecting proofs as the chain of
+			// trust in authority handoffs was broken.
+			break
+		}
+
+		let justification = blockchain
+			.justifications(header.hash())?
+			.and_then(|just| just.into_justification(GRANDPA_ENGINE_ID))
+			.ok_or_else(|| Error::MissingData)?;
+
+		let justification = GrandpaJustification::<Block>::decode_all(&mut &justification[..])?;
+
+		let target = justification.target().0;
+		let proof = convert(WarpSyncFragment { header: header.clone(), justification });
+		let proof_size = proof.encoded_size();
+
+		// Check for the limit. We remove some bytes from the maximum size, because we're only
+		// counting the size of the `WarpSyncFragment`s. The extra margin is here to leave
+		// room for rest of the data (the size of the `Vec` and the boolean).
+		if proofs_encoded_len + proof_size >= MAX_WARP_SYNC_PROOF_SIZE - 50 {
+			proof_limit_reached = true;
+			break
+		}
+
+		proofs_encoded_len += proof_size;
+		proofs.push(proof);
+		last_target = Some(target);
+	}
+
+	let is_finished = if proof_limit_reached {
+		false
+	} else {
+		let latest_justification = best_justification(backend)?.filter(|justification| {
+			// the existing best justification must be for a block higher than the
+			// last authority set change. if we didn't prove any authority set
+			// change then we fallback to make sure it's higher or equal to the
+			// initial warp sync block.
+			let limit = last_target.map(|target| target + One::one()).unwrap_or(begin_number);
+
+			justification.target().0 >= limit
+		});
+
+		if let Some(latest_justification) = latest_justification {
+			let header = blockchain.header(latest_justification.target().1)?.expect(
+				"header hash corresponds to a justification in db; must exist in db as well; qed.",
+			);
+
+			proofs.push(convert(WarpSyncFragment { header, justification: latest_justification }))
+		}
+
+		true
+	};
+
+	Ok((proofs, is_finished))
+}
+
+/// Turns the outcome of a successfully verified proof into a `VerificationResult`.
+fn verification_result<Block: BlockT>(
+	next_set_id: SetId,
+	next_authorities: AuthorityList,
+	last_header: Block::Header,
+	is_finished: bool,
+) -> VerificationResult<Block> {
+	if is_finished {
+		VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header)
+	} else {
+		VerificationResult::<Block>::Partial(next_set_id, next_authorities, last_header.hash())
+	}
+}
+
 /// Implements network API for warp sync.
 pub struct NetworkProvider<Block: BlockT, Backend: ClientBackend<Block>>
 where
@@ -284,6 +603,39 @@ where
 		Ok(EncodedProof(proof.encode()))
 	}
 
+	fn generate_for_version(
+		&self,
+		start: Block::Hash,
+		version: u8,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		if version < COMPACT_WARP_SYNC_PROOF_VERSION {
+			return self.generate(start)
+		}
+
+		let proof = CompactWarpSyncProof::<Block>::generate(
+			&*self.backend,
+			start,
+			&self.authority_set.authority_set_changes(),
+			&self.hard_forks,
+		)
+		.map_err(Box::new)?;
+
+		Ok(EncodedProof(proof.encode()))
+	}
+
+	fn generate_from_genesis(
+		&self,
+		target: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		let proof = WarpSyncProof::<Block>::generate_to(
+			&*self.backend,
+			target,
+			&self.authority_set.authority_set_changes(),
+		)
+		.map_err(Box::new)?;
+		Ok(EncodedProof(proof.encode()))
+	}
+
 	fn verify(
 		&self,
 		proof: &EncodedProof,
@@ -300,15 +652,31 @@ where
 			.ok_or_else(|| "Empty proof".to_string())?;
 		let (next_set_id, next_authorities) =
 			proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
-		if proof.is_finished {
-			Ok(VerificationResult::<Block>::Complete(next_set_id, next_authorities, last_header))
-		} else {
-			Ok(VerificationResult::<Block>::Partial(
-				next_set_id,
-				next_authorities,
-				last_header.hash(),
-			))
+		Ok(verification_result(next_set_id, next_authorities, last_header, proof.is_finished))
+	}
+
+	fn verify_for_version(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: AuthorityList,
+		version: u8,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		if version < COMPACT_WARP_SYNC_PROOF_VERSION {
+			return self.verify(proof, set_id, authorities)
 		}
+
+		let EncodedProof(proof) = proof;
+		let proof = CompactWarpSyncProof::<Block>::decode_all(&mut proof.as_slice())
+			.map_err(|e| format!("Proof decoding error: {:?}", e))?;
+		let last_header = proof
+			.proofs
+			.last()
+			.map(|p| p.header.clone())
+			.ok_or_else(|| "Empty proof".to_string())?;
+		let (next_set_id, next_authorities) =
+			proof.verify(set_id, authorities, &self.hard_forks).map_err(Box::new)?;
+		Ok(verification_result(next_set_id, next_authorities, last_header, proof.is_finished))
 	}
 
 	fn current_authorities(&self) -> AuthorityList {
@@ -318,30 +686,33 @@ where
 
 #[cfg(test)]
 mod tests {
-	use super::WarpSyncProof;
+	use super::{CompactWarpSyncProof, WarpSyncProof};
 	use crate::{AuthoritySetChanges, GrandpaJustification};
-	use codec::Encode;
+	use codec::{Decode, Encode};
 	use rand::prelude::*;
 	use sc_block_builder::BlockBuilderBuilder;
+	use sc_client_api::Backend as _;
 	use sp_blockchain::HeaderBackend;
 	use sp_consensus::BlockOrigin;
 	use sp_consensus_grandpa::GRANDPA_ENGINE_ID;
 	use sp_keyring::Ed25519Keyring;
+	use sp_runtime::traits::Header as _;
 	use std::sync::Arc;
 	use substrate_test_runtime_client::{
-		BlockBuilderExt, ClientBlockImportExt, ClientExt, DefaultTestClientBuilderExt,
-		TestClientBuilder, TestClientBuilderExt,
+		runtime::Block, Backend, BlockBuilderExt, ClientBlockImportExt, ClientExt,
+		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
 	};
 
-	#[test]
-	fn warp_sync_proof_generate_verify() {
+	/// Builds a chain of 100 blocks with an authority set change every 10 blocks. Returns the
+	/// backend, the authority set changes, and the last set id and authorities.
+	fn chain_with_authority_set_changes(
+	) -> (Arc<Backend>, AuthoritySetChanges<u64>, u64, sp_consensus_grandpa::AuthorityList) {
 		let mut rng = rand::rngs::StdRng::from_seed([0; 32]);
 		let builder = TestClientBuilder::new();
 		let backend = builder.backend();
 		let client = Arc::new(builder.build());
 
 		let available_authorities = Ed25519Keyring::iter().collect::<Vec<_>>();
-		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
 
 		let mut current_authorities = vec![Ed25519Keyring::Alice];
 		let mut current_set_id = 0;
@@ -428,8 +799,22 @@ mod tests {
 
 		let authority_set_changes = AuthoritySetChanges::from(authority_set_changes);
 
+		let current_authorities = current_authorities
+			.iter()
+			.map(|keyring| (keyring.public().into(), 1))
+			.collect::<Vec<_>>();
+
+		(backend, authority_set_changes, current_set_id, current_authorities)
+	}
+
+	#[test]
+	fn warp_sync_proof_generate_verify() {
+		let (backend, authority_set_changes, current_set_id, expected_authorities) =
+			chain_with_authority_set_changes();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+
 		// generate a warp sync proof
-		let genesis_hash = client.hash(0).unwrap().unwrap();
+		let genesis_hash = backend.blockchain().hash(0).unwrap().unwrap();
 
 		let warp_sync_proof =
 			WarpSyncProof::generate(&*backend, genesis_hash, &authority_set_changes).unwrap();
@@ -438,12 +823,65 @@ mod tests {
 		let (new_set_id, new_authorities) =
 			warp_sync_proof.verify(0, genesis_authorities, &Default::default()).unwrap();
 
-		let expected_authorities = current_authorities
-			.iter()
-			.map(|keyring| (keyring.public().into(), 1))
-			.collect::<Vec<_>>();
+		assert_eq!(new_set_id, current_set_id);
+		assert_eq!(new_authorities, expected_authorities);
+	}
+
+	#[test]
+	fn warp_sync_proof_generate_to_target_verify() {
+		let (backend, authority_set_changes, _, _) = chain_with_authority_set_changes();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+
+		// block #50 is finalized by set 4 and signals the change to set 5
+		let target = backend.blockchain().hash(50).unwrap().unwrap();
+		let warp_sync_proof =
+			WarpSyncProof::<Block>::generate_to(&*backend, target, &authority_set_changes).unwrap();
+		assert_eq!(warp_sync_proof.proofs.len(), 5);
+		assert_eq!(warp_sync_proof.proofs.last().unwrap().header.hash(), target);
+
+		let (new_set_id, _) =
+			warp_sync_proof.verify(0, genesis_authorities, &Default::default()).unwrap();
+		assert_eq!(new_set_id, 5);
+
+		// block #55 has no justification to end the proof with
+		let target = backend.blockchain().hash(55).unwrap().unwrap();
+		assert!(matches!(
+			WarpSyncProof::<Block>::generate_to(&*backend, target, &authority_set_changes),
+			Err(super::Error::MissingData),
+		));
+	}
+
+	#[test]
+	fn compact_warp_sync_proof_generate_verify() {
+		let (backend, authority_set_changes, current_set_id, expected_authorities) =
+			chain_with_authority_set_changes();
+		let genesis_authorities = vec![(Ed25519Keyring::Alice.public().into(), 1)];
+		let genesis_hash = backend.blockchain().hash(0).unwrap().unwrap();
 
+		let warp_sync_proof =
+			WarpSyncProof::<Block>::generate(&*backend, genesis_hash, &authority_set_changes)
+				.unwrap();
+		let compact_proof = CompactWarpSyncProof::<Block>::generate(
+			&*backend,
+			genesis_hash,
+			&authority_set_changes,
+			&Default::default(),
+		)
+		.unwrap();
+		assert!(compact_proof.encoded_size() < warp_sync_proof.encoded_size());
+
+		let (new_set_id, new_authorities) = compact_proof
+			.verify(0, genesis_authorities.clone(), &Default::default())
+			.unwrap();
 		assert_eq!(new_set_id, current_set_id);
 		assert_eq!(new_authorities, expected_authorities);
+
+		// a single bad signature fails the whole batch. the encoded proof ends with the last
+		// signature, the empty votes ancestries and `is_finished`.
+		let mut encoded = compact_proof.encode();
+		let last_signature = encoded.len() - 2 - 64;
+		encoded[last_signature] ^= 1;
+		let tampered_proof = CompactWarpSyncProof::<Block>::decode(&mut &encoded[..]).unwrap();
+		assert!(tampered_proof.verify(0, genesis_authorities, &Default::default()).is_err());
 	}
 }
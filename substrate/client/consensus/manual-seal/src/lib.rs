@@ -23,7 +23,7 @@ use futures::prelude::*;
 use futures_timer::Delay;
 use prometheus_endpoint::Registry;
 use sc_client_api::{
-	backend::{Backend as ClientBackend, Finalizer},
+	backend::{Backend as ClientBackend, Finalizer, LockImportRun},
 	client::BlockchainEvents,
 };
 use sc_consensus::{
@@ -39,6 +39,7 @@ use std::{marker::PhantomData, sync::Arc, time::Duration};
 
 mod error;
 mod finalize_block;
+mod revert_blocks;
 mod seal_block;
 
 pub mod consensus;
@@ -48,6 +49,7 @@ pub use self::{
 	consensus::ConsensusDataProvider,
 	error::Error,
 	finalize_block::{finalize_block, FinalizeBlockParams},
+	revert_blocks::{revert_blocks, AuxRevert, RevertBlocksParams},
 	rpc::{CreatedBlock, EngineCommand},
 	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
 };
@@ -87,7 +89,7 @@ where
 }
 
 /// Params required to start the manual sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
+pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P, CB> {
 	/// Block import instance.
 	pub block_import: BI,
 
@@ -112,6 +114,13 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, C
 
 	/// Something that can create the inherent data providers.
 	pub create_inherent_data_providers: CIDP,
+
+	/// Client backend, required to revert blocks with [`EngineCommand::RevertBlocks`].
+	pub backend: Option<Arc<CB>>,
+
+	/// Reverts the consensus data kept in the auxiliary storage along with the blocks reverted
+	/// with [`EngineCommand::RevertBlocks`].
+	pub aux_revert: Option<AuxRevert<B, C, CB>>,
 }
 
 /// Params required to start the instant sealing authorship task.
@@ -161,11 +170,13 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
-	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+		backend,
+		aux_revert,
+	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P, CB>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + LockImportRun<B, CB> + ProvideRuntimeApi<B> + 'static,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
@@ -177,10 +188,21 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 {
 	while let Some(command) = commands_stream.next().await {
 		match command {
-			EngineCommand::SealNewBlock { create_empty, finalize, parent_hash, sender } => {
+			EngineCommand::SealNewBlock {
+				create_empty,
+				finalize,
+				parent_hash,
+				timestamp,
+				slot,
+				set_best,
+				sender,
+			} => {
 				seal_block(SealBlockParams {
 					sender,
 					parent_hash,
+					timestamp,
+					slot,
+					set_best,
 					finalize,
 					create_empty,
 					env: &mut env,
@@ -204,6 +226,21 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 				})
 				.await
 			},
+			EngineCommand::RevertBlocks { count, mut sender } => match &backend {
+				Some(backend) =>
+					revert_blocks(RevertBlocksParams {
+						count: count.into(),
+						sender,
+						client: client.clone(),
+						backend: backend.clone(),
+						aux_revert: aux_revert.as_ref(),
+					})
+					.await,
+				None => rpc::send_result(
+					&mut sender,
+					Err(Error::StringError("Reverting blocks requires the client backend".into())),
+				),
+			},
 		}
 	}
 }
@@ -224,7 +261,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + LockImportRun<B, CB> + ProvideRuntimeApi<B> + 'static,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
@@ -239,6 +276,9 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		create_empty: true,
 		finalize: false,
 		parent_hash: None,
+		timestamp: None,
+		slot: None,
+		set_best: false,
 		sender: None,
 	});
 
@@ -251,6 +291,8 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
+		aux_revert: None,
 	})
 	.await
 }
@@ -274,7 +316,7 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error> + Send + Sync + 'static,
-	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + LockImportRun<B, CB> + ProvideRuntimeApi<B> + 'static,
 	CB: ClientBackend<B> + 'static,
 	E: Environment<B> + 'static,
 	E::Proposer: Proposer<B, Proof = P>,
@@ -289,6 +331,9 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		create_empty: false,
 		finalize: true,
 		parent_hash: None,
+		timestamp: None,
+		slot: None,
+		set_best: false,
 		sender: None,
 	});
 
@@ -301,6 +346,8 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
+		backend: None,
+		aux_revert: None,
 	})
 	.await
 }
@@ -350,6 +397,7 @@ mod tests {
 	use sc_consensus::ImportedAux;
 	use sc_transaction_pool::{BasicPool, FullChainApi, Options, RevalidationType};
 	use sc_transaction_pool_api::{MaintainedTransactionPool, TransactionPool, TransactionSource};
+	use sp_core::H256;
 	use sp_inherents::InherentData;
 	use sp_runtime::generic::{Digest, DigestItem};
 	use substrate_test_runtime_client::{
@@ -426,6 +474,9 @@ mod tests {
 					create_empty: false,
 					finalize: true,
 					parent_hash: None,
+					timestamp: None,
+					slot: None,
+					set_best: false,
 					sender,
 				}
 			});
@@ -440,6 +491,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
+			aux_revert: None,
 		}));
 
 		// submit a transaction to pool.
@@ -504,6 +557,9 @@ mod tests {
 					// set to `false`, expecting to be finalized by delayed finalize
 					finalize: false,
 					parent_hash: None,
+					timestamp: None,
+					slot: None,
+					set_best: false,
 					sender,
 				}
 			});
@@ -518,6 +574,8 @@ mod tests {
 			select_chain,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
 			consensus_data_provider: None,
+			backend: None,
+			aux_revert: None,
 		}));
 
 		let delay_sec = 5;
@@ -594,6 +652,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			aux_revert: None,
 		}));
 
 		// submit a transaction to pool.
@@ -603,6 +663,9 @@ mod tests {
 		let (tx, rx) = futures::channel::oneshot::channel();
 		sink.send(EngineCommand::SealNewBlock {
 			parent_hash: None,
+			timestamp: None,
+			slot: None,
+			set_best: false,
 			sender: Some(tx),
 			create_empty: false,
 			finalize: false,
@@ -678,6 +741,8 @@ mod tests {
 			select_chain,
 			consensus_data_provider: None,
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			aux_revert: None,
 		}));
 
 		// submit a transaction to pool.
@@ -688,6 +753,9 @@ mod tests {
 		let (tx, rx) = futures::channel::oneshot::channel();
 		sink.send(EngineCommand::SealNewBlock {
 			parent_hash: None,
+			timestamp: None,
+			slot: None,
+			set_best: false,
 			sender: Some(tx),
 			create_empty: false,
 			finalize: false,
@@ -726,6 +794,9 @@ mod tests {
 		assert!(sink
 			.send(EngineCommand::SealNewBlock {
 				parent_hash: Some(created_block.hash),
+				timestamp: None,
+				slot: None,
+				set_best: false,
 				sender: Some(tx1),
 				create_empty: false,
 				finalize: false,
@@ -739,6 +810,9 @@ mod tests {
 		assert!(sink
 			.send(EngineCommand::SealNewBlock {
 				parent_hash: Some(created_block.hash),
+				timestamp: None,
+				slot: None,
+				set_best: false,
 				sender: Some(tx2),
 				create_empty: false,
 				finalize: false,
@@ -783,11 +857,16 @@ mod tests {
 			// use a provider that pushes some post digest data
 			consensus_data_provider: Some(Box::new(TestDigestProvider { _client: client.clone() })),
 			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: None,
+			aux_revert: None,
 		}));
 
 		let (tx, rx) = futures::channel::oneshot::channel();
 		sink.send(EngineCommand::SealNewBlock {
 			parent_hash: None,
+			timestamp: None,
+			slot: None,
+			set_best: false,
 			sender: Some(tx),
 			create_empty: true,
 			finalize: false,
@@ -800,4 +879,107 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_fork_set_best_and_revert() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let aux_reverted = Arc::new(std::sync::Mutex::new(Vec::new()));
+		let aux_revert_calls = aux_reverted.clone();
+
+		// spawn the background authorship task
+		tokio::spawn(run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+			backend: Some(backend),
+			aux_revert: Some(Box::new(move |_, _, count| {
+				aux_revert_calls.lock().unwrap().push(count);
+				Ok(())
+			})),
+		}));
+
+		async fn seal(
+			sink: &mut futures::channel::mpsc::Sender<EngineCommand<H256>>,
+			parent_hash: Option<H256>,
+			set_best: bool,
+		) -> CreatedBlock<H256> {
+			let (tx, rx) = futures::channel::oneshot::channel();
+			sink.send(EngineCommand::SealNewBlock {
+				parent_hash,
+				timestamp: None,
+				slot: None,
+				set_best,
+				sender: Some(tx),
+				create_empty: true,
+				finalize: false,
+			})
+			.await
+			.unwrap();
+			rx.await.unwrap().unwrap()
+		}
+
+		// build `genesis <- a1 <- a2`
+		let a1 = seal(&mut sink, None, false).await;
+		let a2 = seal(&mut sink, None, false).await;
+		assert_eq!(client.info().best_hash, a2.hash);
+
+		// a fork block on genesis becomes the best block although the chain is shorter.
+		assert!(pool.submit_one(genesis_hash, SOURCE, uxt(Alice, 0)).await.is_ok());
+		let b1 = seal(&mut sink, Some(genesis_hash), true).await;
+		assert!(b1.aux.is_new_best);
+		assert_eq!(client.info().best_hash, b1.hash);
+		assert_ne!(b1.hash, a1.hash);
+
+		// reverting the fork block takes us back to genesis.
+		let mut import_notifications = client.import_notification_stream();
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::RevertBlocks { count: 1, sender: Some(tx) })
+			.await
+			.unwrap();
+		assert_eq!(rx.await.unwrap().unwrap(), 1);
+		assert_eq!(client.info().best_number, 0);
+		assert_eq!(*aux_reverted.lock().unwrap(), vec![1]);
+
+		// the re-org is announced, so that the pool and subscribers observe it.
+		let notification = import_notifications.next().await.unwrap();
+		assert_eq!(notification.hash, genesis_hash);
+		assert!(notification.is_new_best);
+		let tree_route = notification.tree_route.unwrap();
+		assert_eq!(
+			tree_route.retracted().iter().map(|block| block.hash).collect::<Vec<_>>(),
+			vec![b1.hash],
+		);
+		assert!(tree_route.enacted().is_empty());
+
+		// finalized blocks are not reverted.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::RevertBlocks { count: 1, sender: Some(tx) })
+			.await
+			.unwrap();
+		assert_eq!(rx.await.unwrap().unwrap(), 0);
+	}
 }
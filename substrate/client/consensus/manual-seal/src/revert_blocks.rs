@@ -0,0 +1,117 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Block reverting utilities
+
+use crate::{rpc, Error};
+use sc_client_api::backend::{
+	Backend as ClientBackend, ImportNotificationAction, ImportSummary, LockImportRun,
+};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_runtime::traits::{Block as BlockT, NumberFor, UniqueSaturatedInto, Zero};
+use std::sync::Arc;
+
+/// Reverts the consensus data kept in the auxiliary storage (e.g. the BABE epoch changes) for the
+/// given number of blocks. It is called before the blocks are reverted.
+pub type AuxRevert<B, C, CB> =
+	Box<dyn Fn(Arc<C>, Arc<CB>, NumberFor<B>) -> sp_blockchain::Result<()> + Send + Sync>;
+
+/// params for reverting blocks.
+pub struct RevertBlocksParams<'a, B: BlockT, C, CB> {
+	/// number of unfinalized blocks to revert
+	pub count: NumberFor<B>,
+	/// sender to report the number of reverted blocks to the rpc.
+	pub sender: rpc::Sender<u32>,
+	/// Client instance
+	pub client: Arc<C>,
+	/// Client backend
+	pub backend: Arc<CB>,
+	/// reverts the consensus data kept in the auxiliary storage
+	pub aux_revert: Option<&'a AuxRevert<B, C, CB>>,
+}
+
+/// reverts unfinalized blocks with the given params.
+///
+/// Once the blocks are reverted, an import notification for the new best block is sent with the
+/// reverted blocks as the retracted part of its tree route, so that the transaction pool and
+/// subscribers observe the re-org.
+pub async fn revert_blocks<B, C, CB>(params: RevertBlocksParams<'_, B, C, CB>)
+where
+	B: BlockT,
+	C: HeaderBackend<B> + LockImportRun<B, CB>,
+	CB: ClientBackend<B>,
+{
+	let RevertBlocksParams { count, mut sender, client, backend, aux_revert } = params;
+
+	match revert(count, client, backend, aux_revert) {
+		Err(e) => {
+			log::warn!("Failed to revert blocks {}", e);
+			rpc::send_result(&mut sender, Err(e))
+		},
+		Ok(reverted) => {
+			log::info!("✅ Successfully reverted {} blocks", reverted);
+			rpc::send_result(&mut sender, Ok(reverted.unique_saturated_into()))
+		},
+	}
+}
+
+fn revert<B, C, CB>(
+	count: NumberFor<B>,
+	client: Arc<C>,
+	backend: Arc<CB>,
+	aux_revert: Option<&AuxRevert<B, C, CB>>,
+) -> Result<NumberFor<B>, Error>
+where
+	B: BlockT,
+	C: HeaderBackend<B> + LockImportRun<B, CB>,
+	CB: ClientBackend<B>,
+{
+	// the consensus data is written through the client, so it may not be reverted while the
+	// import lock is held
+	if let Some(aux_revert) = aux_revert {
+		aux_revert(client.clone(), backend.clone(), count)?;
+	}
+
+	client.lock_import_and_run(|operation| {
+		// finalized blocks are never reverted
+		let info = client.info();
+		let best_number =
+			std::cmp::max(info.best_number.saturating_sub(count), info.finalized_number);
+		let best_hash = client.hash(best_number)?.ok_or_else(|| {
+			sp_blockchain::Error::UnknownBlock(format!("Missing canonical block #{best_number}"))
+		})?;
+		let tree_route =
+			sp_blockchain::tree_route(backend.blockchain(), info.best_hash, best_hash)?;
+
+		let (reverted, _) = backend.revert(count, false)?;
+		if !reverted.is_zero() {
+			operation.notify_imported = Some(ImportSummary {
+				hash: best_hash,
+				origin: BlockOrigin::Own,
+				header: client.expect_header(best_hash)?,
+				is_new_best: true,
+				storage_changes: None,
+				tree_route: Some(tree_route),
+				import_notification_action: ImportNotificationAction::Both,
+			});
+		}
+
+		Ok(reverted)
+	})
+}
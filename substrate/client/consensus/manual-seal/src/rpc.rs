@@ -47,6 +47,12 @@ pub enum EngineCommand<Hash> {
 		finalize: bool,
 		/// specify the parent hash of the about-to-created block
 		parent_hash: Option<Hash>,
+		/// override the timestamp inherent (in milliseconds) of the block
+		timestamp: Option<u64>,
+		/// override the BABE or AURA slot inherent of the block
+		slot: Option<u64>,
+		/// make the block the new best block even if it is not on the longest chain
+		set_best: bool,
 		/// sender to report errors/success to the rpc.
 		sender: Sender<CreatedBlock<Hash>>,
 	},
@@ -59,6 +65,13 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to revert the given number of unfinalized blocks
+	RevertBlocks {
+		/// number of blocks to revert
+		count: u32,
+		/// sender to report the number of reverted blocks to the rpc.
+		sender: Sender<u32>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -80,6 +93,37 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> Result<bool, Error>;
+
+	/// Instructs the manual-seal authorship task to create a new block with the given timestamp
+	/// (in milliseconds) and optionally slot.
+	///
+	/// The values must satisfy the checks of the runtime, e.g. the timestamp must increase and
+	/// match the slot for runtimes using BABE or AURA.
+	#[method(name = "engine_createBlockAtTime")]
+	async fn create_block_at_time(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		timestamp: u64,
+		slot: Option<u64>,
+	) -> Result<CreatedBlock<Hash>, Error>;
+
+	/// Instructs the manual-seal authorship task to create a new block on top of `parent_hash`.
+	///
+	/// If `set_best` is true, the block becomes the new best block even if it is not on the
+	/// longest chain, which triggers a re-org.
+	#[method(name = "engine_createForkBlock")]
+	async fn create_fork_block(
+		&self,
+		parent_hash: Hash,
+		create_empty: bool,
+		set_best: bool,
+	) -> Result<CreatedBlock<Hash>, Error>;
+
+	/// Instructs the manual-seal authorship task to revert up to `count` unfinalized blocks.
+	/// Returns the number of reverted blocks.
+	#[method(name = "engine_revertBlocks")]
+	async fn revert_blocks(&self, count: u32) -> Result<u32, Error>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
@@ -103,6 +147,24 @@ impl<Hash> ManualSeal<Hash> {
 	pub fn new(import_block_channel: mpsc::Sender<EngineCommand<Hash>>) -> Self {
 		Self { import_block_channel }
 	}
+
+	/// Send the command built by `command` and wait for the result reported by the authorship
+	/// task.
+	async fn send_command<T>(
+		&self,
+		command: impl FnOnce(Sender<T>) -> EngineCommand<Hash>,
+	) -> Result<T, Error> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		// NOTE: this sends a Result over the channel.
+		sink.send(command(Some(sender))).await?;
+
+		match receiver.await {
+			Ok(Ok(rx)) => Ok(rx),
+			Ok(Err(e)) => Err(e.into()),
+			Err(e) => Err(e.into()),
+		}
+	}
 }
 
 #[async_trait]
@@ -113,23 +175,16 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		finalize: bool,
 		parent_hash: Option<Hash>,
 	) -> Result<CreatedBlock<Hash>, Error> {
-		let mut sink = self.import_block_channel.clone();
-		let (sender, receiver) = oneshot::channel();
-		// NOTE: this sends a Result over the channel.
-		let command = EngineCommand::SealNewBlock {
+		self.send_command(|sender| EngineCommand::SealNewBlock {
 			create_empty,
 			finalize,
 			parent_hash,
-			sender: Some(sender),
-		};
-
-		sink.send(command).await?;
-
-		match receiver.await {
-			Ok(Ok(rx)) => Ok(rx),
-			Ok(Err(e)) => Err(e.into()),
-			Err(e) => Err(e.into()),
-		}
+			timestamp: None,
+			slot: None,
+			set_best: false,
+			sender,
+		})
+		.await
 	}
 
 	async fn finalize_block(
@@ -143,6 +198,47 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(Into::into)
 	}
+
+	async fn create_block_at_time(
+		&self,
+		create_empty: bool,
+		finalize: bool,
+		timestamp: u64,
+		slot: Option<u64>,
+	) -> Result<CreatedBlock<Hash>, Error> {
+		self.send_command(|sender| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize,
+			parent_hash: None,
+			timestamp: Some(timestamp),
+			slot,
+			set_best: false,
+			sender,
+		})
+		.await
+	}
+
+	async fn create_fork_block(
+		&self,
+		parent_hash: Hash,
+		create_empty: bool,
+		set_best: bool,
+	) -> Result<CreatedBlock<Hash>, Error> {
+		self.send_command(|sender| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize: false,
+			parent_hash: Some(parent_hash),
+			timestamp: None,
+			slot: None,
+			set_best,
+			sender,
+		})
+		.await
+	}
+
+	async fn revert_blocks(&self, count: u32) -> Result<u32, Error> {
+		self.send_command(|sender| EngineCommand::RevertBlocks { count, sender }).await
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
@@ -25,7 +25,8 @@ use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::{self, BlockOrigin, Environment, Proposer, SelectChain};
-use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
+use sp_consensus_slots::Slot;
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use std::{sync::Arc, time::Duration};
 
@@ -41,6 +42,12 @@ pub struct SealBlockParams<'a, B: BlockT, BI, SC, C: ProvideRuntimeApi<B>, E, TP
 	pub finalize: bool,
 	/// specify the parent hash of the about-to-created block
 	pub parent_hash: Option<<B as BlockT>::Hash>,
+	/// override the timestamp inherent (in milliseconds) of the block
+	pub timestamp: Option<u64>,
+	/// override the BABE or AURA slot inherent of the block
+	pub slot: Option<u64>,
+	/// make the block the new best block even if it is not on the longest chain
+	pub set_best: bool,
 	/// sender to report errors/success to the rpc.
 	pub sender: rpc::Sender<CreatedBlock<<B as BlockT>::Hash>>,
 	/// transaction pool
@@ -66,6 +73,9 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		finalize,
 		pool,
 		parent_hash,
+		timestamp,
+		slot,
+		set_best,
 		client,
 		select_chain,
 		block_import,
@@ -104,7 +114,8 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 			.await
 			.map_err(|e| Error::Other(e))?;
 
-		let inherent_data = inherent_data_providers.create_inherent_data().await?;
+		let mut inherent_data = inherent_data_providers.create_inherent_data().await?;
+		override_time_inherents(&mut inherent_data, timestamp, slot)?;
 
 		let proposer = env.init(&parent).map_err(|err| Error::StringError(err.to_string())).await?;
 		let inherents_len = inherent_data.len();
@@ -135,7 +146,11 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 		let mut params = BlockImportParams::new(BlockOrigin::Own, header.clone());
 		params.body = Some(body);
 		params.finalized = finalize;
-		params.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+		params.fork_choice = Some(if set_best {
+			ForkChoiceStrategy::Custom(true)
+		} else {
+			ForkChoiceStrategy::LongestChain
+		});
 		params.state_action = StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(
 			proposal.storage_changes,
 		));
@@ -161,3 +176,97 @@ pub async fn seal_block<B, BI, SC, C, E, TP, CIDP, P>(
 
 	rpc::send_result(&mut sender, future.await)
 }
+
+/// Replace the timestamp and slot inherents with the values supplied by the caller.
+///
+/// The slot is only replaced for the slot inherents (BABE or AURA) that are already provided.
+fn override_time_inherents(
+	inherent_data: &mut InherentData,
+	timestamp: Option<u64>,
+	slot: Option<u64>,
+) -> Result<(), Error> {
+	if let Some(timestamp) = timestamp {
+		inherent_data.replace_data(
+			sp_timestamp::INHERENT_IDENTIFIER,
+			&sp_timestamp::InherentType::new(timestamp),
+		);
+	}
+
+	if let Some(slot) = slot {
+		let slot = Slot::from(slot);
+		for identifier in [
+			sp_consensus_babe::inherents::INHERENT_IDENTIFIER,
+			sp_consensus_aura::inherents::INHERENT_IDENTIFIER,
+		] {
+			if inherent_data.get_data::<Slot>(&identifier)?.is_some() {
+				inherent_data.replace_data(identifier, &slot);
+			}
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn override_time_inherents_replaces_provided_inherents() {
+		let mut inherent_data = InherentData::new();
+		inherent_data
+			.put_data(sp_timestamp::INHERENT_IDENTIFIER, &sp_timestamp::InherentType::new(1_000))
+			.unwrap();
+		inherent_data
+			.put_data(sp_consensus_babe::inherents::INHERENT_IDENTIFIER, &Slot::from(1))
+			.unwrap();
+
+		override_time_inherents(&mut inherent_data, Some(6_000), Some(42)).unwrap();
+
+		assert_eq!(
+			inherent_data
+				.get_data::<sp_timestamp::InherentType>(&sp_timestamp::INHERENT_IDENTIFIER)
+				.unwrap(),
+			Some(sp_timestamp::InherentType::new(6_000)),
+		);
+		assert_eq!(
+			inherent_data
+				.get_data::<Slot>(&sp_consensus_babe::inherents::INHERENT_IDENTIFIER)
+				.unwrap(),
+			Some(Slot::from(42)),
+		);
+		// slot inherents of other engines are not added
+		assert_eq!(
+			inherent_data
+				.get_data::<Slot>(&sp_consensus_aura::inherents::INHERENT_IDENTIFIER)
+				.unwrap(),
+			None,
+		);
+	}
+
+	#[test]
+	fn override_time_inherents_keeps_inherents_when_not_overridden() {
+		let mut inherent_data = InherentData::new();
+		inherent_data
+			.put_data(sp_timestamp::INHERENT_IDENTIFIER, &sp_timestamp::InherentType::new(1_000))
+			.unwrap();
+		inherent_data
+			.put_data(sp_consensus_aura::inherents::INHERENT_IDENTIFIER, &Slot::from(1))
+			.unwrap();
+
+		override_time_inherents(&mut inherent_data, None, None).unwrap();
+
+		assert_eq!(
+			inherent_data
+				.get_data::<sp_timestamp::InherentType>(&sp_timestamp::INHERENT_IDENTIFIER)
+				.unwrap(),
+			Some(sp_timestamp::InherentType::new(1_000)),
+		);
+		assert_eq!(
+			inherent_data
+				.get_data::<Slot>(&sp_consensus_aura::inherents::INHERENT_IDENTIFIER)
+				.unwrap(),
+			Some(Slot::from(1)),
+		);
+	}
+}
@@ -26,6 +26,11 @@ use std::io;
 /// Local keystore implementation
 mod local;
 pub use local::LocalKeystore;
+/// Remote keystore implementation
+#[cfg(unix)]
+pub mod remote;
+#[cfg(unix)]
+pub use remote::RemoteKeystore;
 pub use sp_keystore::Keystore;
 
 /// Keystore error.
@@ -55,6 +60,9 @@ pub enum Error {
 	/// Keystore unavailable
 	#[error("Keystore unavailable")]
 	Unavailable,
+	/// Invalid remote keystore URI
+	#[error("Invalid remote keystore URI: {0}")]
+	InvalidUri(String),
 }
 
 /// Keystore Result
@@ -64,8 +72,10 @@ impl From<Error> for TraitError {
 	fn from(error: Error) -> Self {
 		match error {
 			Error::KeyNotSupported(id) => TraitError::KeyNotSupported(id),
-			Error::InvalidSeed | Error::InvalidPhrase | Error::PublicKeyMismatch =>
-				TraitError::ValidationError(error.to_string()),
+			Error::InvalidSeed |
+			Error::InvalidPhrase |
+			Error::PublicKeyMismatch |
+			Error::InvalidUri(_) => TraitError::ValidationError(error.to_string()),
 			Error::Unavailable => TraitError::Unavailable,
 			Error::Io(e) => TraitError::Other(e.to_string()),
 			Error::Json(e) => TraitError::Other(e.to_string()),
@@ -140,6 +140,26 @@ impl LocalKeystore {
 			.map(|pair| pair.vrf_pre_output(input));
 		Ok(pre_output)
 	}
+
+	/// Schnorrkel key pair of the sr25519 key `public`.
+	///
+	/// Used by the [`RemoteSigner`](crate::remote::RemoteSigner) to sign VRF input points.
+	#[cfg(unix)]
+	pub(crate) fn sr25519_keypair(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+	) -> std::result::Result<Option<schnorrkel::Keypair>, TraitError> {
+		self.0
+			.read()
+			.key_pair_by_type::<sr25519::Pair>(public, key_type)?
+			.map(|pair| {
+				schnorrkel::SecretKey::from_bytes(&pair.to_raw_vec())
+					.map(|secret| secret.to_keypair())
+					.map_err(|e| TraitError::Other(e.to_string()))
+			})
+			.transpose()
+	}
 }
 
 impl Keystore for LocalKeystore {
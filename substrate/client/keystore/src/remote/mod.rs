@@ -0,0 +1,676 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Remote keystore implementation.
+//!
+//! [`RemoteKeystore`] holds no secret material: every key listing and signing request is
+//! forwarded over a local Unix socket to a separate signer process, which answers it with the
+//! help of a [`RemoteSigner`]. The signer can enforce rules on what it signs, see
+//! [`SlashingProtection`].
+
+use codec::Decode;
+use curve25519_dalek::{ristretto::CompressedRistretto, Scalar};
+use parking_lot::Mutex;
+use schnorrkel::context::SigningTranscript;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId, VrfPublic},
+	ecdsa, ed25519,
+	sr25519::{
+		self,
+		vrf::{VrfProof, VrfSignature},
+	},
+};
+use sp_keystore::{Error as TraitError, Keystore, KeystorePtr};
+use std::{io, os::unix::net::UnixStream, path::PathBuf, sync::Arc, time::Duration};
+
+sp_keystore::bandersnatch_experimental_enabled! {
+use sp_core::bandersnatch;
+}
+
+sp_keystore::bls_experimental_enabled! {
+use sp_core::{bls381, ecdsa_bls381};
+}
+
+use crate::{Error, Result};
+
+mod protocol;
+mod signer;
+
+pub use protocol::{
+	read_message, write_message, RemoteError, Request, Response, VrfCommitment, MAX_MESSAGE_SIZE,
+};
+pub use signer::{RemoteSigner, SlashingProtection};
+
+const LOG_TARGET: &str = "keystore";
+
+/// Maximum time to wait for the signer to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Label of the extra transcript signed by the VRF when no extra data is given.
+const DEFAULT_VRF_EXTRA_LABEL: &[u8] = b"VRF";
+
+/// A keystore forwarding all its operations to a remote signer.
+pub struct RemoteKeystore {
+	path: PathBuf,
+	stream: Mutex<Option<UnixStream>>,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signer listening at `uri`.
+	///
+	/// `uri` is the path of a Unix socket, optionally prefixed with `unix://`.
+	pub fn open(uri: &str) -> Result<Self> {
+		let path = match uri.split_once("://") {
+			None => uri,
+			Some(("unix", path)) => path,
+			Some(_) => return Err(Error::InvalidUri(uri.into())),
+		};
+		let keystore = Self { path: path.into(), stream: Mutex::new(None) };
+		*keystore.stream.lock() = Some(keystore.connect()?);
+		Ok(keystore)
+	}
+
+	fn connect(&self) -> io::Result<UnixStream> {
+		let stream = UnixStream::connect(&self.path)?;
+		stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+		stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+		Ok(stream)
+	}
+
+	/// Send `request` to the signer, reconnecting once if the connection was lost.
+	fn request(&self, request: Request) -> std::result::Result<Response, TraitError> {
+		self.with_connection(|stream| Ok(exchange(stream, &request)?.map_err(Into::into)))?
+	}
+
+	/// Run `exchange` over the connection to the signer, holding it for the whole exchange.
+	///
+	/// The exchange is run again from the start on a new connection if the connection was lost.
+	fn with_connection<T>(
+		&self,
+		mut exchange: impl FnMut(&mut UnixStream) -> io::Result<T>,
+	) -> std::result::Result<T, TraitError> {
+		let mut stream = self.stream.lock();
+		for attempt in 0..2 {
+			let result = match stream.take().map_or_else(|| self.connect(), Ok) {
+				Ok(mut s) => exchange(&mut s).map(|result| {
+					*stream = Some(s);
+					result
+				}),
+				Err(e) => Err(e),
+			};
+			match result {
+				Ok(result) => return Ok(result),
+				Err(e) => {
+					log::debug!(
+						target: LOG_TARGET,
+						"Request to remote signer at {:?} failed (attempt {}): {}",
+						self.path,
+						attempt,
+						e,
+					);
+				},
+			}
+		}
+		log::warn!(target: LOG_TARGET, "Remote signer at {:?} is unreachable", self.path);
+		Err(TraitError::Unavailable)
+	}
+
+	fn public_keys<T: Decode>(&self, key_type: KeyTypeId, crypto_id: CryptoTypeId) -> Vec<T> {
+		match self.request(Request::PublicKeys { key_type, crypto_id }) {
+			Ok(Response::Keys(keys)) =>
+				keys.into_iter().filter_map(|k| T::decode(&mut &k[..]).ok()).collect(),
+			Ok(_) => {
+				log::warn!(target: LOG_TARGET, "Unexpected response from remote signer");
+				Vec::new()
+			},
+			Err(_) => Vec::new(),
+		}
+	}
+
+	fn generate_new<T: Decode>(
+		&self,
+		key_type: KeyTypeId,
+		crypto_id: CryptoTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<T, TraitError> {
+		let seed = seed.map(Into::into);
+		match self.request(Request::Generate { key_type, crypto_id, seed })? {
+			Response::Public(public) => decode(&public),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn sign<T: Decode>(&self, request: Request) -> std::result::Result<Option<T>, TraitError> {
+		match self.request(request)? {
+			Response::Signature(signature) => signature.map(|s| decode(&s)).transpose(),
+			_ => Err(unexpected_response()),
+		}
+	}
+}
+
+/// Send `request` over `stream` and read the response.
+///
+/// Errors reported by the signer are returned in the inner result.
+fn exchange(
+	stream: &mut UnixStream,
+	request: &Request,
+) -> io::Result<std::result::Result<Response, RemoteError>> {
+	write_message(stream, request)?;
+	Ok(match read_message(stream)? {
+		Response::Error(e) => Err(e),
+		response => Ok(response),
+	})
+}
+
+fn decode<T: Decode>(encoded: &[u8]) -> std::result::Result<T, TraitError> {
+	T::decode(&mut &encoded[..])
+		.map_err(|e| TraitError::Other(format!("Invalid data from remote signer: {}", e)))
+}
+
+fn schnorrkel_public(
+	public: &sr25519::Public,
+) -> std::result::Result<schnorrkel::PublicKey, TraitError> {
+	schnorrkel::PublicKey::from_bytes(public.as_slice())
+		.map_err(|_| TraitError::ValidationError("Invalid public key format".into()))
+}
+
+/// Hash the VRF `input` of `public` to a compressed curve point, as the signer expects it.
+fn vrf_input_point(
+	public: &schnorrkel::PublicKey,
+	input: &sr25519::vrf::VrfInput,
+) -> CompressedRistretto {
+	*public.vrf_hash(input.0.clone()).as_compressed()
+}
+
+/// Challenge of the VRF proof of `input` signing `extra`, given the commitment of the signer.
+///
+/// Follows `schnorrkel::Keypair::dleq_proove`, as used by sr25519 VRF signatures.
+fn vrf_challenge(
+	mut extra: merlin::Transcript,
+	public: &schnorrkel::PublicKey,
+	input: &CompressedRistretto,
+	commitment: &VrfCommitment,
+) -> Scalar {
+	extra.proto_name(b"DLEQProof");
+	extra.commit_point(b"vrf:h", input);
+	extra.commit_point(b"vrf:R=g^r", &CompressedRistretto(commitment.base));
+	extra.commit_point(b"vrf:h^r", &CompressedRistretto(commitment.input));
+	extra.commit_point(b"vrf:pk", public.as_compressed());
+	extra.commit_point(b"vrf:h^sk", &CompressedRistretto(*commitment.pre_output.0.as_bytes()));
+	extra.challenge_scalar(b"prove")
+}
+
+fn unexpected_response() -> TraitError {
+	TraitError::Other("Unexpected response from remote signer".into())
+}
+
+impl Keystore for RemoteKeystore {
+	fn insert(
+		&self,
+		key_type: KeyTypeId,
+		suri: &str,
+		public: &[u8],
+	) -> std::result::Result<(), ()> {
+		let request = Request::Insert { key_type, suri: suri.into(), public: public.to_vec() };
+		match self.request(request) {
+			Ok(Response::Inserted(true)) => Ok(()),
+			_ => Err(()),
+		}
+	}
+
+	fn keys(&self, key_type: KeyTypeId) -> std::result::Result<Vec<Vec<u8>>, TraitError> {
+		match self.request(Request::Keys { key_type })? {
+			Response::Keys(keys) => Ok(keys),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		matches!(self.request(Request::HasKeys(public_keys.to_vec())), Ok(Response::HasKeys(true)))
+	}
+
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(key_type, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		self.generate_new(key_type, sr25519::CRYPTO_ID, seed)
+	}
+
+	fn sr25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<sr25519::Signature>, TraitError> {
+		let crypto_id = sr25519::CRYPTO_ID;
+		self.sign(Request::Sign {
+			key_type,
+			crypto_id,
+			public: public.to_raw_vec(),
+			msg: msg.to_vec(),
+		})
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		data: &sr25519::vrf::VrfSignData,
+	) -> std::result::Result<Option<sr25519::vrf::VrfSignature>, TraitError> {
+		let schnorrkel_public = schnorrkel_public(public)?;
+		let input = vrf_input_point(&schnorrkel_public, data.as_ref());
+		let extra = data
+			.extra()
+			.map(|extra| extra.0.clone())
+			.unwrap_or_else(|| merlin::Transcript::new(DEFAULT_VRF_EXTRA_LABEL));
+		let commit = Request::Sr25519VrfCommit { key_type, public: *public, input: input.0 };
+
+		let proof = self.with_connection(|stream| {
+			let commitment = match exchange(stream, &commit)? {
+				Ok(Response::VrfCommitment(Some(commitment))) => commitment,
+				Ok(Response::VrfCommitment(None)) => return Ok(Ok(None)),
+				Ok(_) => return Ok(Err(unexpected_response())),
+				Err(e) => return Ok(Err(e.into())),
+			};
+			let challenge = vrf_challenge(extra.clone(), &schnorrkel_public, &input, &commitment);
+			let prove = Request::Sr25519VrfProve { challenge: challenge.to_bytes() };
+			Ok(match exchange(stream, &prove)? {
+				Ok(Response::VrfProof(scalar)) =>
+					Ok(Some((commitment.pre_output, challenge, scalar))),
+				Ok(_) => Err(unexpected_response()),
+				Err(e) => Err(e.into()),
+			})
+		})??;
+
+		let Some((pre_output, challenge, scalar)) = proof else { return Ok(None) };
+		let proof = schnorrkel::vrf::VRFProof::from_bytes(&[challenge.to_bytes(), scalar].concat())
+			.map_err(|_| TraitError::Other("Invalid VRF proof from remote signer".into()))?;
+		let signature = VrfSignature { pre_output, proof: VrfProof(proof) };
+		if !public.vrf_verify(data, &signature) {
+			return Err(TraitError::Other("Invalid VRF proof from remote signer".into()))
+		}
+		Ok(Some(signature))
+	}
+
+	fn sr25519_vrf_pre_output(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &sr25519::vrf::VrfInput,
+	) -> std::result::Result<Option<sr25519::vrf::VrfPreOutput>, TraitError> {
+		let input = vrf_input_point(&schnorrkel_public(public)?, input).0;
+		match self.request(Request::Sr25519VrfPreOutput { key_type, public: *public, input })? {
+			Response::VrfPreOutput(pre_output) => Ok(pre_output),
+			_ => Err(unexpected_response()),
+		}
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(key_type, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		self.generate_new(key_type, ed25519::CRYPTO_ID, seed)
+	}
+
+	fn ed25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ed25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ed25519::Signature>, TraitError> {
+		let crypto_id = ed25519::CRYPTO_ID;
+		self.sign(Request::Sign {
+			key_type,
+			crypto_id,
+			public: public.to_raw_vec(),
+			msg: msg.to_vec(),
+		})
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(key_type, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		self.generate_new(key_type, ecdsa::CRYPTO_ID, seed)
+	}
+
+	fn ecdsa_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		let crypto_id = ecdsa::CRYPTO_ID;
+		self.sign(Request::Sign {
+			key_type,
+			crypto_id,
+			public: public.to_raw_vec(),
+			msg: msg.to_vec(),
+		})
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign(Request::EcdsaSignPrehashed { key_type, public: *public, msg: *msg })
+	}
+
+	fn ecdsa_sign_with_keccak256(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign(Request::EcdsaSignWithKeccak256 { key_type, public: *public, msg: msg.to_vec() })
+	}
+
+	sp_keystore::bandersnatch_experimental_enabled! {
+		fn bandersnatch_public_keys(&self, key_type: KeyTypeId) -> Vec<bandersnatch::Public> {
+			self.public_keys(key_type, bandersnatch::CRYPTO_ID)
+		}
+
+		fn bandersnatch_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bandersnatch::Public, TraitError> {
+			self.generate_new(key_type, bandersnatch::CRYPTO_ID, seed)
+		}
+
+		fn bandersnatch_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bandersnatch::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bandersnatch::Signature>, TraitError> {
+			let crypto_id = bandersnatch::CRYPTO_ID;
+			self.sign(Request::Sign { key_type, crypto_id, public: public.to_raw_vec(), msg: msg.to_vec() })
+		}
+
+		// Bandersnatch VRF inputs can't be transferred to the signer yet.
+		fn bandersnatch_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			_public: &bandersnatch::Public,
+			_data: &bandersnatch::vrf::VrfSignData,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfSignature>, TraitError> {
+			Err(TraitError::KeyNotSupported(key_type))
+		}
+
+		fn bandersnatch_vrf_pre_output(
+			&self,
+			key_type: KeyTypeId,
+			_public: &bandersnatch::Public,
+			_input: &bandersnatch::vrf::VrfInput,
+		) -> std::result::Result<Option<bandersnatch::vrf::VrfPreOutput>, TraitError> {
+			Err(TraitError::KeyNotSupported(key_type))
+		}
+
+		fn bandersnatch_ring_vrf_sign(
+			&self,
+			key_type: KeyTypeId,
+			_public: &bandersnatch::Public,
+			_data: &bandersnatch::vrf::VrfSignData,
+			_prover: &bandersnatch::ring_vrf::RingProver,
+		) -> std::result::Result<Option<bandersnatch::ring_vrf::RingVrfSignature>, TraitError> {
+			Err(TraitError::KeyNotSupported(key_type))
+		}
+	}
+
+	sp_keystore::bls_experimental_enabled! {
+		fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+			self.public_keys(key_type, bls381::CRYPTO_ID)
+		}
+
+		fn bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<bls381::Public, TraitError> {
+			self.generate_new(key_type, bls381::CRYPTO_ID, seed)
+		}
+
+		fn bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<bls381::Signature>, TraitError> {
+			let crypto_id = bls381::CRYPTO_ID;
+			self.sign(Request::Sign { key_type, crypto_id, public: public.to_raw_vec(), msg: msg.to_vec() })
+		}
+
+		fn ecdsa_bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa_bls381::Public> {
+			self.public_keys(key_type, ecdsa_bls381::CRYPTO_ID)
+		}
+
+		fn ecdsa_bls381_generate_new(
+			&self,
+			key_type: KeyTypeId,
+			seed: Option<&str>,
+		) -> std::result::Result<ecdsa_bls381::Public, TraitError> {
+			self.generate_new(key_type, ecdsa_bls381::CRYPTO_ID, seed)
+		}
+
+		fn ecdsa_bls381_sign(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			let crypto_id = ecdsa_bls381::CRYPTO_ID;
+			self.sign(Request::Sign { key_type, crypto_id, public: public.to_raw_vec(), msg: msg.to_vec() })
+		}
+
+		fn ecdsa_bls381_sign_with_keccak256(
+			&self,
+			key_type: KeyTypeId,
+			public: &ecdsa_bls381::Public,
+			msg: &[u8],
+		) -> std::result::Result<Option<ecdsa_bls381::Signature>, TraitError> {
+			let public = public.to_raw_vec();
+			self.sign(Request::EcdsaBls381SignWithKeccak256 { key_type, public, msg: msg.to_vec() })
+		}
+	}
+}
+
+impl Into<KeystorePtr> for RemoteKeystore {
+	fn into(self) -> KeystorePtr {
+		Arc::new(self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::LocalKeystore;
+	use codec::Encode;
+	use sp_core::{
+		crypto::{key_types, Pair},
+		sr25519::vrf::{VrfSignData, VrfTranscript},
+	};
+	use std::os::unix::net::UnixListener;
+	use tempfile::TempDir;
+
+	fn start_signer(dir: &TempDir, protection: SlashingProtection) -> RemoteKeystore {
+		let socket = dir.path().join("signer.sock");
+		let listener = UnixListener::bind(&socket).unwrap();
+		let signer = Arc::new(RemoteSigner::new(Arc::new(LocalKeystore::in_memory()), protection));
+		std::thread::spawn(move || signer.serve(listener));
+		RemoteKeystore::open(&format!("unix://{}", socket.display())).unwrap()
+	}
+
+	fn grandpa_vote(stage: u8, target: u8, round: u64, set_id: u64) -> Vec<u8> {
+		((stage, [target; 32], 10u32), round, set_id).encode()
+	}
+
+	fn beefy_commitment(root: u8, block_number: u32, set_id: u64) -> Vec<u8> {
+		(vec![(*b"mh", vec![root; 32])], block_number, set_id).encode()
+	}
+
+	#[test]
+	fn sign_and_vrf_sign_work() {
+		let dir = TempDir::new().unwrap();
+		let store = start_signer(&dir, SlashingProtection::in_memory());
+
+		let public = store.sr25519_generate_new(key_types::BABE, None).unwrap();
+		assert_eq!(store.sr25519_public_keys(key_types::BABE), vec![public]);
+		assert!(store.has_keys(&[(public.to_raw_vec(), key_types::BABE)]));
+
+		let signature = store.sr25519_sign(key_types::BABE, &public, b"msg").unwrap().unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"msg", &public));
+
+		let input = VrfTranscript::new(b"label", &[(b"domain", b"data")]);
+		let data = VrfSignData::new(input.clone());
+		let signature = store.sr25519_vrf_sign(key_types::BABE, &public, &data).unwrap().unwrap();
+		assert!(public.vrf_verify(&data, &signature));
+		let pre_output = store.sr25519_vrf_pre_output(key_types::BABE, &public, &input).unwrap();
+		assert_eq!(pre_output, Some(signature.pre_output));
+
+		// Extra data is signed too.
+		let data = data.with_extra(VrfTranscript::new(b"extra", &[(b"core", b"1")]));
+		let signature = store.sr25519_vrf_sign(key_types::BABE, &public, &data).unwrap().unwrap();
+		assert!(public.vrf_verify(&data, &signature));
+		assert_eq!(signature.pre_output, pre_output.unwrap());
+		assert!(!public.vrf_verify(&VrfSignData::new(input), &signature));
+
+		// Committing to VRF proofs would allow forging votes.
+		let grandpa = store.sr25519_generate_new(key_types::GRANDPA, None).unwrap();
+		let data = VrfSignData::new(VrfTranscript::new(b"label", &[]));
+		assert!(store.sr25519_vrf_sign(key_types::GRANDPA, &grandpa, &data).is_err());
+
+		let other = sr25519::Pair::from_seed(&[1; 32]).public();
+		assert_eq!(store.sr25519_sign(key_types::BABE, &other, b"msg").unwrap(), None);
+	}
+
+	#[test]
+	fn conflicting_votes_are_refused() {
+		let dir = TempDir::new().unwrap();
+		let protection = SlashingProtection::open(dir.path().join("votes")).unwrap();
+		let store = start_signer(&dir, protection);
+		let public = store.ed25519_generate_new(key_types::GRANDPA, Some("//Alice")).unwrap();
+		let sign = |msg: Vec<u8>| store.ed25519_sign(key_types::GRANDPA, &public, &msg);
+
+		assert!(sign(grandpa_vote(0, 1, 5, 0)).unwrap().is_some());
+		// Signing the same vote again or a different stage of the round is fine.
+		assert!(sign(grandpa_vote(0, 1, 5, 0)).unwrap().is_some());
+		assert!(sign(grandpa_vote(1, 2, 5, 0)).unwrap().is_some());
+		// Same stage of the same round on another target is an equivocation.
+		assert!(sign(grandpa_vote(0, 2, 5, 0)).is_err());
+		assert!(sign(grandpa_vote(0, 2, 6, 0)).unwrap().is_some());
+		assert!(sign(b"not a vote".to_vec()).is_err());
+
+		// Recorded votes survive a restart.
+		let protection = SlashingProtection::open(dir.path().join("votes")).unwrap();
+		let public = public.to_raw_vec();
+		assert!(protection
+			.check(key_types::GRANDPA, &public, &grandpa_vote(0, 2, 5, 0))
+			.is_err());
+		assert!(protection.check(key_types::GRANDPA, &public, &grandpa_vote(0, 1, 5, 0)).is_ok());
+	}
+
+	#[test]
+	fn conflicting_beefy_ecdsa_votes_are_refused() {
+		let dir = TempDir::new().unwrap();
+		let store = start_signer(&dir, SlashingProtection::in_memory());
+		let public = store.ecdsa_generate_new(key_types::BEEFY, None).unwrap();
+		let sign = |msg: Vec<u8>| store.ecdsa_sign_with_keccak256(key_types::BEEFY, &public, &msg);
+
+		let signature = sign(beefy_commitment(1, 10, 0)).unwrap().unwrap();
+		let hash = sp_core::keccak_256(&beefy_commitment(1, 10, 0));
+		assert!(ecdsa::Pair::verify_prehashed(&signature, &hash, &public));
+		assert!(sign(beefy_commitment(1, 10, 0)).unwrap().is_some());
+		assert!(sign(beefy_commitment(2, 10, 0)).is_err());
+		assert!(sign(beefy_commitment(2, 11, 0)).unwrap().is_some());
+		// Bare hashes can't be checked.
+		assert!(store.ecdsa_sign_prehashed(key_types::BEEFY, &public, &hash).is_err());
+	}
+
+	#[test]
+	fn old_votes_are_forgotten() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().join("votes");
+		let store = start_signer(&dir, SlashingProtection::open(&path).unwrap());
+		let public = store.ed25519_generate_new(key_types::GRANDPA, Some("//Alice")).unwrap();
+		let sign = |msg: Vec<u8>| store.ed25519_sign(key_types::GRANDPA, &public, &msg);
+
+		let latest = signer::KEPT_ROUNDS + 20;
+		for round in (1..=10).chain(Some(latest)) {
+			assert!(sign(grandpa_vote(0, 1, round, 1)).unwrap().is_some());
+		}
+		// Rounds too far behind the latest vote and previous sets are refused.
+		assert!(sign(grandpa_vote(0, 1, 5, 1)).is_err());
+		assert!(sign(grandpa_vote(0, 1, latest, 0)).is_err());
+		assert!(sign(grandpa_vote(0, 1, latest - 1, 1)).unwrap().is_some());
+
+		// Forgotten votes are dropped from the file on restart, and still refused.
+		let protection = SlashingProtection::open(&path).unwrap();
+		assert_eq!(protection.remembered_votes(), 2);
+		let public = public.to_raw_vec();
+		assert!(protection
+			.check(key_types::GRANDPA, &public, &grandpa_vote(0, 2, 5, 1))
+			.is_err());
+		assert!(protection.check(key_types::GRANDPA, &public, &grandpa_vote(0, 2, 5, 2)).is_ok());
+	}
+
+	#[test]
+	fn rounds_restart_with_new_sets() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().join("votes");
+		let store = start_signer(&dir, SlashingProtection::open(&path).unwrap());
+		let public = store.ed25519_generate_new(key_types::GRANDPA, Some("//Alice")).unwrap();
+		let sign = |msg: Vec<u8>| store.ed25519_sign(key_types::GRANDPA, &public, &msg);
+
+		let latest = signer::KEPT_ROUNDS + 20;
+		for round in [1, latest] {
+			assert!(sign(grandpa_vote(0, 1, round, 1)).unwrap().is_some());
+		}
+		// The first rounds of the next set are far behind the latest round of the previous one.
+		for round in 1..=3 {
+			assert!(sign(grandpa_vote(0, 1, round, 2)).unwrap().is_some());
+		}
+		assert!(sign(grandpa_vote(0, 2, 1, 2)).is_err());
+		// The previous set is over.
+		assert!(sign(grandpa_vote(0, 1, latest + 1, 1)).is_err());
+
+		let protection = SlashingProtection::open(&path).unwrap();
+		let public = public.to_raw_vec();
+		assert!(protection
+			.check(key_types::GRANDPA, &public, &grandpa_vote(0, 2, 2, 2))
+			.is_err());
+		assert!(protection.check(key_types::GRANDPA, &public, &grandpa_vote(0, 1, 4, 2)).is_ok());
+	}
+}
@@ -0,0 +1,189 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Wire protocol spoken between a [`RemoteKeystore`](super::RemoteKeystore) and a
+//! [`RemoteSigner`](super::RemoteSigner).
+//!
+//! Every message is a SCALE encoded [`Request`] or [`Response`] prefixed by its length as a
+//! little endian `u32`. A connection carries one request at a time, each request being answered
+//! by exactly one response.
+//!
+//! VRF transcripts can't be sent over the wire. The node hashes the VRF input transcript to a
+//! curve point, which the signer multiplies by the secret key. VRF signatures are produced in two
+//! steps on the same connection: the signer first commits to a fresh nonce with
+//! [`Request::Sr25519VrfCommit`], then the node derives the challenge of the proof from the
+//! extra transcript and sends it with [`Request::Sr25519VrfProve`], which the signer answers
+//! with the proof scalar. A commitment is only valid for the request immediately following it.
+
+use codec::{Decode, Encode};
+use sp_core::{
+	crypto::{CryptoTypeId, KeyTypeId},
+	ecdsa,
+	sr25519::{self, vrf::VrfPreOutput},
+};
+use sp_keystore::Error as TraitError;
+use std::io::{self, Read, Write};
+
+/// Maximum size of a single encoded message.
+pub const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Request sent to the remote signer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Request {
+	/// Public keys of the given crypto type stored under `key_type`.
+	PublicKeys { key_type: KeyTypeId, crypto_id: CryptoTypeId },
+	/// Generate a new key pair of the given crypto type, optionally from `seed`.
+	Generate { key_type: KeyTypeId, crypto_id: CryptoTypeId, seed: Option<String> },
+	/// Insert a secret key.
+	Insert { key_type: KeyTypeId, suri: String, public: Vec<u8> },
+	/// Raw public keys of any crypto type stored under `key_type`.
+	Keys { key_type: KeyTypeId },
+	/// Check if all the given keys are available.
+	HasKeys(Vec<(Vec<u8>, KeyTypeId)>),
+	/// Sign `msg` with the key of the given crypto type.
+	Sign { key_type: KeyTypeId, crypto_id: CryptoTypeId, public: Vec<u8>, msg: Vec<u8> },
+	/// Sign an already hashed message with an ecdsa key.
+	EcdsaSignPrehashed { key_type: KeyTypeId, public: ecdsa::Public, msg: [u8; 32] },
+	/// Sign the keccak256 hash of `msg` with an ecdsa key.
+	EcdsaSignWithKeccak256 { key_type: KeyTypeId, public: ecdsa::Public, msg: Vec<u8> },
+	/// Sign `msg` with an `(ecdsa,bls381)` key, hashing the message with keccak256.
+	EcdsaBls381SignWithKeccak256 { key_type: KeyTypeId, public: Vec<u8>, msg: Vec<u8> },
+	/// Commit to a nonce for a sr25519 VRF proof of the compressed VRF input point `input`.
+	Sr25519VrfCommit { key_type: KeyTypeId, public: sr25519::Public, input: [u8; 32] },
+	/// Answer `challenge` for the VRF proof committed to by the previous request.
+	Sr25519VrfProve { challenge: [u8; 32] },
+	/// Produce a sr25519 VRF pre-output of the compressed VRF input point `input`.
+	Sr25519VrfPreOutput { key_type: KeyTypeId, public: sr25519::Public, input: [u8; 32] },
+}
+
+impl Request {
+	/// Key type of the keys this request operates on, if any.
+	pub fn key_type(&self) -> Option<KeyTypeId> {
+		match self {
+			Self::PublicKeys { key_type, .. } |
+			Self::Generate { key_type, .. } |
+			Self::Insert { key_type, .. } |
+			Self::Keys { key_type } |
+			Self::Sign { key_type, .. } |
+			Self::EcdsaSignPrehashed { key_type, .. } |
+			Self::EcdsaSignWithKeccak256 { key_type, .. } |
+			Self::EcdsaBls381SignWithKeccak256 { key_type, .. } |
+			Self::Sr25519VrfCommit { key_type, .. } |
+			Self::Sr25519VrfPreOutput { key_type, .. } => Some(*key_type),
+			Self::HasKeys(_) | Self::Sr25519VrfProve { .. } => None,
+		}
+	}
+}
+
+/// Response sent back by the remote signer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum Response {
+	/// Encoded public keys.
+	Keys(Vec<Vec<u8>>),
+	/// Encoded public key of a newly generated key pair.
+	Public(Vec<u8>),
+	/// Outcome of an insert request.
+	Inserted(bool),
+	/// Outcome of a has keys request.
+	HasKeys(bool),
+	/// Encoded signature, `None` if the key was not found.
+	Signature(Option<Vec<u8>>),
+	/// VRF proof commitment, `None` if the key was not found.
+	VrfCommitment(Option<VrfCommitment>),
+	/// Scalar of a VRF proof.
+	VrfProof([u8; 32]),
+	/// VRF pre-output, `None` if the key was not found.
+	VrfPreOutput(Option<VrfPreOutput>),
+	/// The request failed.
+	Error(RemoteError),
+}
+
+/// Commitment of the signer to the nonce of a VRF proof.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct VrfCommitment {
+	/// VRF pre-output of the input point.
+	pub pre_output: VrfPreOutput,
+	/// Compressed nonce times the base point.
+	pub base: [u8; 32],
+	/// Compressed nonce times the input point.
+	pub input: [u8; 32],
+}
+
+/// Error reported by the remote signer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum RemoteError {
+	/// Public key type is not supported.
+	KeyNotSupported(KeyTypeId),
+	/// Validation error.
+	ValidationError(String),
+	/// Keystore unavailable.
+	Unavailable,
+	/// Signing was refused because it could lead to an equivocation.
+	SlashingProtection(String),
+	/// Programming errors.
+	Other(String),
+}
+
+impl From<TraitError> for RemoteError {
+	fn from(error: TraitError) -> Self {
+		match error {
+			TraitError::KeyNotSupported(id) => Self::KeyNotSupported(id),
+			TraitError::ValidationError(e) => Self::ValidationError(e),
+			TraitError::Unavailable => Self::Unavailable,
+			TraitError::Other(e) => Self::Other(e),
+		}
+	}
+}
+
+impl From<RemoteError> for TraitError {
+	fn from(error: RemoteError) -> Self {
+		match error {
+			RemoteError::KeyNotSupported(id) => Self::KeyNotSupported(id),
+			RemoteError::ValidationError(e) => Self::ValidationError(e),
+			RemoteError::Unavailable => Self::Unavailable,
+			RemoteError::SlashingProtection(e) =>
+				Self::Other(format!("Refused by slashing protection: {}", e)),
+			RemoteError::Other(e) => Self::Other(e),
+		}
+	}
+}
+
+/// Write a length prefixed message.
+pub fn write_message<T: Encode>(stream: &mut impl Write, message: &T) -> io::Result<()> {
+	let encoded = message.encode();
+	let len = u32::try_from(encoded.len())
+		.ok()
+		.filter(|len| *len <= MAX_MESSAGE_SIZE)
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Message too large"))?;
+	stream.write_all(&len.to_le_bytes())?;
+	stream.write_all(&encoded)?;
+	stream.flush()
+}
+
+/// Read a length prefixed message.
+pub fn read_message<T: Decode>(stream: &mut impl Read) -> io::Result<T> {
+	let mut len = [0u8; 4];
+	stream.read_exact(&mut len)?;
+	let len = u32::from_le_bytes(len);
+	if len > MAX_MESSAGE_SIZE {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Message too large"))
+	}
+	let mut buf = vec![0u8; len as usize];
+	stream.read_exact(&mut buf)?;
+	T::decode(&mut &buf[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
@@ -0,0 +1,507 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Reference signer answering the requests of a [`RemoteKeystore`](super::RemoteKeystore).
+
+use codec::{Decode, Encode};
+use curve25519_dalek::{RistrettoPoint, Scalar};
+use parking_lot::Mutex;
+use schnorrkel::context::SigningTranscript;
+use sp_core::{
+	blake2_256,
+	crypto::{key_types, ByteArray, CryptoTypeId, KeyTypeId},
+	ecdsa, ed25519, keccak_256,
+	sr25519::{self, vrf::VrfPreOutput},
+};
+use sp_keystore::Keystore;
+use std::{
+	collections::HashMap,
+	fs::{self, File, OpenOptions},
+	io::{self, Read, Write},
+	os::unix::net::{UnixListener, UnixStream},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+sp_keystore::bandersnatch_experimental_enabled! {
+use sp_core::bandersnatch;
+}
+
+sp_keystore::bls_experimental_enabled! {
+use sp_core::{bls381, ecdsa_bls381};
+}
+
+use super::{
+	protocol::{read_message, write_message, RemoteError, Request, Response, VrfCommitment},
+	LOG_TARGET,
+};
+use crate::{LocalKeystore, Result};
+
+/// Number of rounds below the latest voted one for which votes are remembered.
+///
+/// Older votes of the same authority set, as well as votes of previous sets, are forgotten
+/// and signing them is refused.
+pub(super) const KEPT_ROUNDS: u64 = 4096;
+
+/// Identifies a vote which must be signed at most once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+struct VoteId {
+	key_type: KeyTypeId,
+	public: Vec<u8>,
+	set_id: u64,
+	round: u64,
+	/// Vote kind within the round (e.g. GRANDPA prevote or precommit).
+	stage: u8,
+}
+
+/// Refuses to sign conflicting GRANDPA and BEEFY votes.
+///
+/// A GRANDPA key signs at most one primary proposal, one prevote and one precommit per round
+/// and a BEEFY key at most one commitment per block number, for every authority set. Signing
+/// the very same payload again is allowed. Payloads using the GRANDPA or BEEFY key types which
+/// are not recognized as votes are refused, and so are BEEFY ECDSA signatures of bare hashes.
+///
+/// Only the votes of the latest authority set, at most [`KEPT_ROUNDS`] rounds behind the latest
+/// vote, are remembered. Votes older than that are refused.
+///
+/// Votes are optionally persisted to a file, so that protection survives restarts. The file is
+/// compacted whenever old votes are forgotten.
+pub struct SlashingProtection {
+	votes: HashMap<VoteId, [u8; 32]>,
+	/// Latest `(set_id, round)` voted by every key.
+	latest: HashMap<(KeyTypeId, Vec<u8>), (u64, u64)>,
+	/// Number of votes recorded since old votes were last forgotten.
+	recorded: u64,
+	file: Option<(PathBuf, File)>,
+}
+
+impl SlashingProtection {
+	/// Create a slashing protection keeping track of the votes in memory only.
+	pub fn in_memory() -> Self {
+		Self { votes: HashMap::new(), latest: HashMap::new(), recorded: 0, file: None }
+	}
+
+	/// Create a slashing protection persisting the votes to the file at `path`.
+	///
+	/// Votes previously recorded to the file are loaded.
+	pub fn open<T: AsRef<Path>>(path: T) -> Result<Self> {
+		let path = path.as_ref().to_path_buf();
+		let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+		let mut content = Vec::new();
+		file.read_to_end(&mut content)?;
+
+		let mut protection = Self::in_memory();
+		let mut input = &content[..];
+		while !input.is_empty() {
+			match <(VoteId, [u8; 32])>::decode(&mut input) {
+				Ok((id, hash)) => protection.insert(id, hash),
+				Err(e) => {
+					log::warn!(
+						target: LOG_TARGET,
+						"Ignoring truncated slashing protection record in {:?}: {}",
+						path,
+						e,
+					);
+					break
+				},
+			}
+		}
+
+		protection.file = Some((path, file));
+		protection.prune()?;
+		Ok(protection)
+	}
+
+	/// Check that signing `msg` with the given key doesn't conflict with a previous vote.
+	///
+	/// Returns the vote to [`record`](Self::record) once signed, if `msg` is a vote.
+	pub(super) fn check(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		msg: &[u8],
+	) -> std::result::Result<Option<(VoteId, [u8; 32])>, RemoteError> {
+		if key_type != key_types::GRANDPA && key_type != key_types::BEEFY {
+			return Ok(None)
+		}
+		let id = vote_id(key_type, public, msg).ok_or_else(|| {
+			RemoteError::SlashingProtection(format!(
+				"Unrecognized {} payload",
+				key_type_name(key_type)
+			))
+		})?;
+		if self.is_forgotten(&id) {
+			return Err(RemoteError::SlashingProtection(format!(
+				"{} vote for set {} in round {} is older than the remembered votes",
+				key_type_name(key_type),
+				id.set_id,
+				id.round,
+			)))
+		}
+		let hash = blake2_256(msg);
+		match self.votes.get(&id) {
+			Some(signed) if *signed != hash => Err(RemoteError::SlashingProtection(format!(
+				"Conflicting {} vote for set {} in round {}",
+				key_type_name(key_type),
+				id.set_id,
+				id.round,
+			))),
+			_ => Ok(Some((id, hash))),
+		}
+	}
+
+	/// Record a signed vote.
+	pub(super) fn record(&mut self, (id, hash): (VoteId, [u8; 32])) -> io::Result<()> {
+		if self.votes.get(&id) == Some(&hash) {
+			return Ok(())
+		}
+		if let Some((_, file)) = &mut self.file {
+			file.write_all(&(&id, hash).encode())?;
+			file.sync_data()?;
+		}
+		self.insert(id, hash);
+		self.recorded += 1;
+		if self.recorded >= KEPT_ROUNDS {
+			self.prune()?;
+		}
+		Ok(())
+	}
+
+	/// Number of votes currently remembered.
+	#[cfg(test)]
+	pub(super) fn remembered_votes(&self) -> usize {
+		self.votes.len()
+	}
+
+	fn insert(&mut self, id: VoteId, hash: [u8; 32]) {
+		let latest = self.latest.entry((id.key_type, id.public.clone())).or_insert((0, 0));
+		*latest = (*latest).max((id.set_id, id.round));
+		self.votes.insert(id, hash);
+	}
+
+	/// Whether `id` is too old to be remembered.
+	///
+	/// Rounds restart in every set, so they are only compared within the same set.
+	fn is_forgotten(&self, id: &VoteId) -> bool {
+		self.latest
+			.get(&(id.key_type, id.public.clone()))
+			.map_or(false, |(set_id, round)| {
+				id.set_id < *set_id ||
+					(id.set_id == *set_id && id.round.saturating_add(KEPT_ROUNDS) < *round)
+			})
+	}
+
+	/// Forget the old votes and rewrite the file with the remaining ones.
+	fn prune(&mut self) -> io::Result<()> {
+		let votes = std::mem::take(&mut self.votes);
+		self.votes = votes.into_iter().filter(|(id, _)| !self.is_forgotten(id)).collect();
+		self.recorded = 0;
+
+		let Some((path, file)) = &mut self.file else { return Ok(()) };
+		let compacted = path.with_extension("tmp");
+		{
+			let mut out = File::create(&compacted)?;
+			for vote in &self.votes {
+				out.write_all(&vote.encode())?;
+			}
+			out.sync_all()?;
+		}
+		fs::rename(&compacted, &*path)?;
+		*file = OpenOptions::new().append(true).open(&*path)?;
+		Ok(())
+	}
+}
+
+fn key_type_name(key_type: KeyTypeId) -> &'static str {
+	if key_type == key_types::GRANDPA {
+		"GRANDPA"
+	} else {
+		"BEEFY"
+	}
+}
+
+/// Extract the vote identifier from a GRANDPA or BEEFY payload.
+fn vote_id(key_type: KeyTypeId, public: &[u8], msg: &[u8]) -> Option<VoteId> {
+	let (stage, round, set_id) = if key_type == key_types::GRANDPA {
+		// `(Message, round, set_id)`, `Message` being an enum of votes on a target.
+		let (message, rest) = msg.split_at(msg.len().checked_sub(16)?);
+		let stage = *message.first().filter(|stage| **stage <= 2)?;
+		if message.len() < 2 {
+			return None
+		}
+		let (round, set_id) = rest.split_at(8);
+		(stage, u64::from_le_bytes(round.try_into().ok()?), set_id)
+	} else {
+		// `Commitment { payload, block_number, validator_set_id }`.
+		let mut input = msg;
+		<Vec<([u8; 2], Vec<u8>)>>::decode(&mut input).ok()?;
+		let (round, set_id) = input.split_at(input.len().checked_sub(8)?);
+		let round = match round.len() {
+			4 => u32::from_le_bytes(round.try_into().ok()?).into(),
+			8 => u64::from_le_bytes(round.try_into().ok()?),
+			_ => return None,
+		};
+		(0, round, set_id)
+	};
+	let set_id = u64::from_le_bytes(set_id.try_into().ok()?);
+	Some(VoteId { key_type, public: public.to_vec(), set_id, round, stage })
+}
+
+/// Secret key and nonce of a VRF proof waiting for its challenge.
+struct PendingVrfProof {
+	secret: Scalar,
+	nonce: Scalar,
+}
+
+/// Answers [`Request`]s using a local keystore.
+pub struct RemoteSigner {
+	keystore: Arc<LocalKeystore>,
+	protection: Mutex<SlashingProtection>,
+}
+
+impl RemoteSigner {
+	/// Create a new signer using the keys of `keystore`.
+	pub fn new(keystore: Arc<LocalKeystore>, protection: SlashingProtection) -> Self {
+		Self { keystore, protection: Mutex::new(protection) }
+	}
+
+	/// Serve the connections accepted by `listener`, each one from its own thread.
+	///
+	/// Only returns if accepting a connection fails.
+	pub fn serve(self: Arc<Self>, listener: UnixListener) -> io::Result<()> {
+		loop {
+			let (stream, _) = listener.accept()?;
+			let signer = self.clone();
+			std::thread::spawn(move || {
+				if let Err(e) = signer.serve_connection(stream) {
+					log::debug!(target: LOG_TARGET, "Remote keystore connection closed: {}", e);
+				}
+			});
+		}
+	}
+
+	fn serve_connection(&self, mut stream: UnixStream) -> io::Result<()> {
+		let mut vrf_proof = None;
+		loop {
+			let request = read_message(&mut stream)?;
+			let response = self.handle(&mut vrf_proof, request);
+			write_message(&mut stream, &response)?;
+		}
+	}
+
+	/// Answer a single request.
+	///
+	/// `vrf_proof` is the VRF proof committed to by the previous request of the connection, if
+	/// any.
+	fn handle(&self, vrf_proof: &mut Option<PendingVrfProof>, request: Request) -> Response {
+		let pending = vrf_proof.take();
+		self.process(request, pending, vrf_proof).unwrap_or_else(Response::Error)
+	}
+
+	fn process(
+		&self,
+		request: Request,
+		pending: Option<PendingVrfProof>,
+		committed: &mut Option<PendingVrfProof>,
+	) -> std::result::Result<Response, RemoteError> {
+		let keystore = &*self.keystore;
+		let response = match request {
+			Request::PublicKeys { key_type, crypto_id } => Response::Keys(match crypto_id {
+				sr25519::CRYPTO_ID => encode_all(keystore.sr25519_public_keys(key_type)),
+				ed25519::CRYPTO_ID => encode_all(keystore.ed25519_public_keys(key_type)),
+				ecdsa::CRYPTO_ID => encode_all(keystore.ecdsa_public_keys(key_type)),
+				#[cfg(feature = "bandersnatch-experimental")]
+				bandersnatch::CRYPTO_ID => encode_all(keystore.bandersnatch_public_keys(key_type)),
+				#[cfg(feature = "bls-experimental")]
+				bls381::CRYPTO_ID => encode_all(keystore.bls381_public_keys(key_type)),
+				#[cfg(feature = "bls-experimental")]
+				ecdsa_bls381::CRYPTO_ID => encode_all(keystore.ecdsa_bls381_public_keys(key_type)),
+				_ => return Err(RemoteError::KeyNotSupported(key_type)),
+			}),
+			Request::Generate { key_type, crypto_id, seed } => {
+				let seed = seed.as_deref();
+				Response::Public(match crypto_id {
+					sr25519::CRYPTO_ID => keystore.sr25519_generate_new(key_type, seed)?.encode(),
+					ed25519::CRYPTO_ID => keystore.ed25519_generate_new(key_type, seed)?.encode(),
+					ecdsa::CRYPTO_ID => keystore.ecdsa_generate_new(key_type, seed)?.encode(),
+					#[cfg(feature = "bandersnatch-experimental")]
+					bandersnatch::CRYPTO_ID => keystore.bandersnatch_generate_new(key_type, seed)?.encode(),
+					#[cfg(feature = "bls-experimental")]
+					bls381::CRYPTO_ID => keystore.bls381_generate_new(key_type, seed)?.encode(),
+					#[cfg(feature = "bls-experimental")]
+					ecdsa_bls381::CRYPTO_ID => keystore.ecdsa_bls381_generate_new(key_type, seed)?.encode(),
+					_ => return Err(RemoteError::KeyNotSupported(key_type)),
+				})
+			},
+			Request::Insert { key_type, suri, public } =>
+				Response::Inserted(keystore.insert(key_type, &suri, &public).is_ok()),
+			Request::Keys { key_type } => Response::Keys(keystore.keys(key_type)?),
+			Request::HasKeys(public_keys) => Response::HasKeys(keystore.has_keys(&public_keys)),
+			Request::Sign { key_type, crypto_id, public, msg } =>
+				Response::Signature(self.sign_vote(key_type, &public, &msg, || {
+					Ok(keystore.sign_with(key_type, crypto_id, &public, &msg)?)
+				})?),
+			Request::EcdsaSignPrehashed { key_type, public, msg } => {
+				if key_type == key_types::GRANDPA || key_type == key_types::BEEFY {
+					return Err(RemoteError::SlashingProtection(format!(
+						"Refusing to sign a bare {} hash",
+						key_type_name(key_type)
+					)))
+				}
+				Response::Signature(
+					keystore.ecdsa_sign_prehashed(key_type, &public, &msg)?.map(|s| s.encode()),
+				)
+			},
+			Request::EcdsaSignWithKeccak256 { key_type, public, msg } =>
+				Response::Signature(self.sign_vote(key_type, public.as_slice(), &msg, || {
+					Ok(keystore
+						.ecdsa_sign_prehashed(key_type, &public, &keccak_256(&msg))?
+						.map(|s| s.encode()))
+				})?),
+			Request::EcdsaBls381SignWithKeccak256 { key_type, public, msg } =>
+				Response::Signature(self.sign_vote(key_type, &public, &msg, || {
+					ecdsa_bls381_sign_with_keccak256(keystore, key_type, &public, &msg)
+				})?),
+			Request::Sr25519VrfCommit { key_type, public, input } => {
+				let commitment =
+					self.vrf_commit(key_type, &public, &input)?.map(|(commitment, pending)| {
+						*committed = Some(pending);
+						commitment
+					});
+				Response::VrfCommitment(commitment)
+			},
+			Request::Sr25519VrfProve { challenge } => {
+				let pending = pending.ok_or_else(|| {
+					RemoteError::ValidationError("No VRF proof was committed to".into())
+				})?;
+				let challenge = Option::<Scalar>::from(Scalar::from_canonical_bytes(challenge))
+					.ok_or_else(|| RemoteError::ValidationError("Invalid VRF challenge".into()))?;
+				Response::VrfProof((pending.nonce - challenge * pending.secret).to_bytes())
+			},
+			Request::Sr25519VrfPreOutput { key_type, public, input } => Response::VrfPreOutput(
+				self.vrf_inout(key_type, &public, &input)?
+					.map(|(_, inout)| VrfPreOutput(inout.to_preout())),
+			),
+		};
+		Ok(response)
+	}
+
+	/// VRF input and output of the input point `input` with the secret key of `public`.
+	fn vrf_inout(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &[u8; 32],
+	) -> std::result::Result<Option<(schnorrkel::Keypair, schnorrkel::vrf::VRFInOut)>, RemoteError>
+	{
+		let Some(keypair) = self.keystore.sr25519_keypair(key_type, public)? else {
+			return Ok(None)
+		};
+		let input = schnorrkel::vrf::VRFPreOut::from_bytes(input)
+			.map_err(|_| RemoteError::ValidationError("Invalid VRF input point".into()))?;
+		let inout = keypair
+			.secret
+			.vrf_create_from_compressed_point(&input)
+			.map_err(|_| RemoteError::ValidationError("Invalid VRF input point".into()))?;
+		Ok(Some((keypair, inout)))
+	}
+
+	/// Commit to a fresh nonce for a VRF proof of the input point `input`.
+	///
+	/// Answering the challenge of a proof amounts to a Schnorr signature of any message, hence
+	/// this is refused for the key types under slashing protection.
+	fn vrf_commit(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		input: &[u8; 32],
+	) -> std::result::Result<Option<(VrfCommitment, PendingVrfProof)>, RemoteError> {
+		if key_type == key_types::GRANDPA || key_type == key_types::BEEFY {
+			return Err(RemoteError::SlashingProtection(format!(
+				"Refusing to produce a VRF proof with a {} key",
+				key_type_name(key_type)
+			)))
+		}
+		let Some((keypair, inout)) = self.vrf_inout(key_type, public, input)? else {
+			return Ok(None)
+		};
+		let secret = keypair.secret.to_bytes();
+		let (key, nonce_seed) = secret.split_at(32);
+		let secret = Option::<Scalar>::from(Scalar::from_canonical_bytes(
+			key.try_into().expect("secret keys start with a 32 bytes scalar; qed"),
+		))
+		.ok_or_else(|| RemoteError::Other("Invalid secret key".into()))?;
+
+		let mut transcript = merlin::Transcript::new(b"RemoteVRFNonce");
+		transcript.commit_point(b"vrf:h", inout.input.as_compressed());
+		let nonce = transcript.witness_scalar(b"proving\x00", &[nonce_seed]);
+		let commitment = VrfCommitment {
+			pre_output: VrfPreOutput(inout.to_preout()),
+			base: RistrettoPoint::mul_base(&nonce).compress().to_bytes(),
+			input: (nonce * inout.input.as_point()).compress().to_bytes(),
+		};
+		Ok(Some((commitment, PendingVrfProof { secret, nonce })))
+	}
+
+	/// Sign `msg` using `sign`, unless it conflicts with a previously signed vote.
+	fn sign_vote(
+		&self,
+		key_type: KeyTypeId,
+		public: &[u8],
+		msg: &[u8],
+		sign: impl FnOnce() -> std::result::Result<Option<Vec<u8>>, RemoteError>,
+	) -> std::result::Result<Option<Vec<u8>>, RemoteError> {
+		let mut protection = self.protection.lock();
+		let vote = protection.check(key_type, public, msg)?;
+		let signature = sign()?;
+		if let (Some(vote), Some(_)) = (vote, &signature) {
+			protection.record(vote).map_err(|e| {
+				RemoteError::Other(format!("Failed to record vote, not releasing signature: {}", e))
+			})?;
+		}
+		Ok(signature)
+	}
+}
+
+fn encode_all<T: ByteArray>(keys: Vec<T>) -> Vec<Vec<u8>> {
+	keys.into_iter().map(|k| k.to_raw_vec()).collect()
+}
+
+#[cfg(feature = "bls-experimental")]
+fn ecdsa_bls381_sign_with_keccak256(
+	keystore: &LocalKeystore,
+	key_type: KeyTypeId,
+	public: &[u8],
+	msg: &[u8],
+) -> std::result::Result<Option<Vec<u8>>, RemoteError> {
+	let public = ecdsa_bls381::Public::from_slice(public)
+		.map_err(|_| RemoteError::ValidationError("Invalid public key format".into()))?;
+	Ok(keystore
+		.ecdsa_bls381_sign_with_keccak256(key_type, &public, msg)?
+		.map(|s| s.encode()))
+}
+
+#[cfg(not(feature = "bls-experimental"))]
+fn ecdsa_bls381_sign_with_keccak256(
+	_keystore: &LocalKeystore,
+	key_type: KeyTypeId,
+	_public: &[u8],
+	_msg: &[u8],
+) -> std::result::Result<Option<Vec<u8>>, RemoteError> {
+	Err(RemoteError::KeyNotSupported(key_type))
+}
@@ -19,6 +19,7 @@
 //! Helpers for outgoing and incoming light client requests.
 
 use sc_network::{config::ProtocolId, request_responses::IncomingRequest, NetworkBackend};
+use sp_crypto_hashing::{blake2_128, twox_128};
 use sp_runtime::traits::Block;
 
 use std::time::Duration;
@@ -41,6 +42,17 @@ fn generate_legacy_protocol_name(protocol_id: &ProtocolId) -> String {
 	format!("/{}/light/2", protocol_id.as_ref())
 }
 
+/// Storage key of the `System::Events` storage value, proven by events requests.
+pub fn events_storage_key() -> Vec<u8> {
+	[twox_128(b"System"), twox_128(b"Events")].concat()
+}
+
+/// Storage key of the `System::EventTopics` entry of the given SCALE-encoded topic, proven by
+/// events requests along with [`events_storage_key`].
+pub fn event_topic_storage_key(topic: &[u8]) -> Vec<u8> {
+	[&twox_128(b"System")[..], &twox_128(b"EventTopics"), &blake2_128(topic), topic].concat()
+}
+
 /// Generates a `RequestResponseProtocolConfig` for the light client request protocol, refusing
 /// incoming requests.
 pub fn generate_protocol_config<
@@ -62,3 +74,25 @@ pub fn generate_protocol_config<
 		Some(inbound_queue),
 	)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn system_events_storage_keys() {
+		assert_eq!(
+			array_bytes::bytes2hex("", events_storage_key()),
+			"26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7",
+		);
+
+		let topic = [1u8; 32];
+		let key = event_topic_storage_key(&topic);
+		assert_eq!(
+			array_bytes::bytes2hex("", &key[..32]),
+			"26aa394eea5630e07c48ae0c9558cef7bb94e1c21adab714983cf06622e1de76",
+		);
+		assert_eq!(&key[32..48], &blake2_128(&topic));
+		assert_eq!(&key[48..], &topic);
+	}
+}
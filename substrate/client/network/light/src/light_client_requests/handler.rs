@@ -27,19 +27,20 @@ use codec::{self, Decode, Encode};
 use futures::prelude::*;
 use log::{debug, trace};
 use prost::Message;
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{BlockBackend, ProofProvider, StorageProof};
 use sc_network::{
 	config::ProtocolId,
 	request_responses::{IncomingRequest, OutgoingResponse},
 	NetworkBackend, ReputationChange,
 };
 use sc_network_types::PeerId;
+use sp_api::{ApiExt, CallApiAt, Core, ProvideRuntimeApi};
 use sp_core::{
 	hexdisplay::HexDisplay,
 	storage::{ChildInfo, ChildType, PrefixedStorageKey},
 };
-use sp_runtime::traits::Block;
-use std::{marker::PhantomData, sync::Arc};
+use sp_runtime::traits::{Block, Header};
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc};
 
 const LOG_TARGET: &str = "light-client-request-handler";
 
@@ -47,6 +48,9 @@ const LOG_TARGET: &str = "light-client-request-handler";
 /// handling in production systems, this value is chosen to match the block request limit.
 const MAX_LIGHT_REQUEST_QUEUE: usize = 20;
 
+/// Maximum number of blocks executed to answer a storage diff request.
+const MAX_STORAGE_DIFF_BLOCKS: usize = 16;
+
 /// Handler for incoming light client requests from a remote peer.
 pub struct LightClientRequestHandler<B, Client> {
 	request_receiver: async_channel::Receiver<IncomingRequest>,
@@ -58,7 +62,14 @@ pub struct LightClientRequestHandler<B, Client> {
 impl<B, Client> LightClientRequestHandler<B, Client>
 where
 	B: Block,
-	Client: BlockBackend<B> + ProofProvider<B> + Send + Sync + 'static,
+	Client: BlockBackend<B>
+		+ ProofProvider<B>
+		+ ProvideRuntimeApi<B>
+		+ CallApiAt<B>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: Core<B>,
 {
 	/// Create a new [`LightClientRequestHandler`].
 	pub fn new<N: NetworkBackend<B, <B as Block>::Hash>>(
@@ -155,6 +166,10 @@ where
 				self.on_remote_read_request(&peer, r)?,
 			Some(schema::v1::light::request::Request::RemoteReadChildRequest(r)) =>
 				self.on_remote_read_child_request(&peer, r)?,
+			Some(schema::v1::light::request::Request::RemoteEventsRequest(r)) =>
+				self.on_remote_events_request(&peer, r)?,
+			Some(schema::v1::light::request::Request::RemoteStorageDiffRequest(r)) =>
+				self.on_remote_storage_diff_request(&peer, r)?,
 			None =>
 				return Err(HandleRequestError::BadRequest("Remote request without request data.")),
 		};
@@ -282,6 +297,156 @@ where
 			response: Some(schema::v1::light::response::Response::RemoteReadResponse(response)),
 		})
 	}
+
+	fn on_remote_events_request(
+		&mut self,
+		peer: &PeerId,
+		request: &schema::v1::light::RemoteEventsRequest,
+	) -> Result<schema::v1::light::Response, HandleRequestError> {
+		trace!(
+			"Remote events request from {} ({} topics at {:?}).",
+			peer,
+			request.topics.len(),
+			request.block,
+		);
+
+		let block = Decode::decode(&mut request.block.as_ref())?;
+
+		let keys = std::iter::once(super::events_storage_key())
+			.chain(request.topics.iter().map(|topic| super::event_topic_storage_key(topic)))
+			.collect::<Vec<_>>();
+
+		let response = match self.client.read_proof(block, &mut keys.iter().map(AsRef::as_ref)) {
+			Ok(proof) => schema::v1::light::RemoteReadResponse { proof: Some(proof.encode()) },
+			Err(error) => {
+				trace!(
+					"remote events request from {} ({} topics at {:?}) failed with: {}",
+					peer,
+					request.topics.len(),
+					request.block,
+					error,
+				);
+				schema::v1::light::RemoteReadResponse { proof: None }
+			},
+		};
+
+		Ok(schema::v1::light::Response {
+			response: Some(schema::v1::light::response::Response::RemoteReadResponse(response)),
+		})
+	}
+
+	fn on_remote_storage_diff_request(
+		&mut self,
+		peer: &PeerId,
+		request: &schema::v1::light::RemoteStorageDiffRequest,
+	) -> Result<schema::v1::light::Response, HandleRequestError> {
+		trace!(
+			"Remote storage diff request from {} ({} from {:?} to {:?}).",
+			peer,
+			fmt_keys(request.key_prefixes.first(), request.key_prefixes.last()),
+			request.from_block,
+			request.to_block,
+		);
+
+		let from_block = Decode::decode(&mut request.from_block.as_ref())?;
+		let to_block = Decode::decode(&mut request.to_block.as_ref())?;
+
+		let Some(blocks) = self.blocks_after(from_block, to_block).transpose() else {
+			debug!("Invalid remote storage diff request sent by {}.", peer);
+			return Err(HandleRequestError::BadRequest(
+				"Remote storage diff request over too many blocks.",
+			))
+		};
+
+		let diff = blocks
+			.and_then(|blocks| self.changed_storage_keys(blocks, &request.key_prefixes))
+			.and_then(|keys| {
+				// Both proofs are merged into one, which can be checked against either state root.
+				let proofs = [from_block, to_block]
+					.into_iter()
+					.map(|block| self.client.read_proof(block, &mut keys.iter().map(AsRef::as_ref)))
+					.collect::<Result<Vec<_>, _>>()?;
+				Ok((keys, StorageProof::merge(proofs)))
+			});
+
+		let response = match diff {
+			Ok((keys, proof)) =>
+				schema::v1::light::RemoteStorageDiffResponse { keys, proof: Some(proof.encode()) },
+			Err(error) => {
+				trace!(
+					"remote storage diff request from {} ({} from {:?} to {:?}) failed with: {}",
+					peer,
+					fmt_keys(request.key_prefixes.first(), request.key_prefixes.last()),
+					request.from_block,
+					request.to_block,
+					error,
+				);
+				schema::v1::light::RemoteStorageDiffResponse { keys: Vec::new(), proof: None }
+			},
+		};
+
+		Ok(schema::v1::light::Response {
+			response: Some(schema::v1::light::response::Response::RemoteStorageDiffResponse(
+				response,
+			)),
+		})
+	}
+
+	/// Blocks after `from_block` up to and including `to_block`, oldest first.
+	///
+	/// Returns `None` if `from_block` is not one of the last [`MAX_STORAGE_DIFF_BLOCKS`] ancestors
+	/// of `to_block`.
+	fn blocks_after(
+		&self,
+		from_block: B::Hash,
+		to_block: B::Hash,
+	) -> sp_blockchain::Result<Option<Vec<B>>> {
+		let mut blocks = Vec::new();
+		let mut hash = to_block;
+		while hash != from_block {
+			if blocks.len() == MAX_STORAGE_DIFF_BLOCKS {
+				return Ok(None)
+			}
+			let block = self
+				.client
+				.block(hash)?
+				.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("{hash:?}")))?
+				.block;
+			hash = *block.header().parent_hash();
+			blocks.push(block);
+		}
+		blocks.reverse();
+		Ok(Some(blocks))
+	}
+
+	/// Keys of the main storage changed by executing `blocks` which start with one of `prefixes`,
+	/// or all changed keys if `prefixes` is empty.
+	fn changed_storage_keys(
+		&self,
+		blocks: Vec<B>,
+		prefixes: &[Vec<u8>],
+	) -> sp_blockchain::Result<Vec<Vec<u8>>> {
+		let mut keys = BTreeSet::new();
+		for block in blocks {
+			let (mut header, extrinsics) = block.deconstruct();
+			let parent_hash = *header.parent_hash();
+			// Remove all `Seal`s as they are added by the consensus engines after building the
+			// block. On import they are normally removed by the consensus engine.
+			header.digest_mut().logs.retain(|d| d.as_seal().is_none());
+
+			let runtime_api = self.client.runtime_api();
+			runtime_api.execute_block(parent_hash, B::new(header, extrinsics))?;
+			let state = self.client.state_at(parent_hash)?;
+			let changes = runtime_api
+				.into_storage_changes(&state, parent_hash)
+				.map_err(sp_blockchain::Error::StorageChanges)?;
+
+			keys.extend(changes.main_storage_changes.into_iter().map(|(key, _)| key).filter(
+				|key| prefixes.is_empty() || prefixes.iter().any(|prefix| key.starts_with(prefix)),
+			));
+		}
+		Ok(keys.into_iter().collect())
+	}
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -311,3 +476,182 @@ fn fmt_keys(first: Option<&Vec<u8>>, last: Option<&Vec<u8>>) -> String {
 		String::from("n/a")
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::channel::oneshot;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sc_network::NetworkWorker;
+	use sp_blockchain::HeaderBackend;
+	use sp_consensus::BlockOrigin;
+	use sp_crypto_hashing::twox_128;
+	use substrate_test_runtime_client::{
+		prelude::*,
+		runtime::{Block as TestBlock, Hash},
+		TestClient,
+	};
+
+	/// Sends `request` to a [`LightClientRequestHandler`] serving `client`, and returns the
+	/// response.
+	async fn send_request(
+		client: Arc<TestClient>,
+		request: schema::v1::light::request::Request,
+	) -> OutgoingResponse {
+		let (handler, config) = LightClientRequestHandler::new::<NetworkWorker<TestBlock, Hash>>(
+			&ProtocolId::from("test"),
+			None,
+			client,
+		);
+
+		tokio::spawn(async move { handler.run().await });
+
+		let (tx, rx) = oneshot::channel();
+		config
+			.inbound_queue
+			.unwrap()
+			.send(IncomingRequest {
+				peer: PeerId::random(),
+				payload: schema::v1::light::Request { request: Some(request) }.encode_to_vec(),
+				pending_response: tx,
+			})
+			.await
+			.unwrap();
+
+		rx.await.unwrap()
+	}
+
+	fn read_response(proof: StorageProof) -> Result<Vec<u8>, ()> {
+		let response = schema::v1::light::RemoteReadResponse { proof: Some(proof.encode()) };
+		Ok(schema::v1::light::Response {
+			response: Some(schema::v1::light::response::Response::RemoteReadResponse(response)),
+		}
+		.encode_to_vec())
+	}
+
+	#[tokio::test]
+	async fn events_are_proven_with_their_topics() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let genesis_hash = client.info().genesis_hash;
+		let topic = [1u8; 32].encode();
+
+		let response = send_request(
+			client.clone(),
+			schema::v1::light::request::Request::RemoteEventsRequest(
+				schema::v1::light::RemoteEventsRequest {
+					block: genesis_hash.encode(),
+					topics: vec![topic.clone()],
+				},
+			),
+		)
+		.await;
+
+		let keys =
+			[super::super::events_storage_key(), super::super::event_topic_storage_key(&topic)];
+		let expected_proof =
+			client.read_proof(genesis_hash, &mut keys.iter().map(AsRef::as_ref)).unwrap();
+		assert_eq!(response.result, read_response(expected_proof));
+		assert_eq!(response.reputation_changes, Vec::new());
+	}
+
+	/// Builds and imports `count` empty blocks on top of the best block of `client`, and returns
+	/// the hash of the last one.
+	async fn build_blocks(client: &Arc<TestClient>, count: usize) -> Hash {
+		let mut hash = client.info().best_hash;
+		for _ in 0..count {
+			let info = client.info();
+			let block = BlockBuilderBuilder::new(&**client)
+				.on_parent_block(info.best_hash)
+				.with_parent_block_number(info.best_number)
+				.build()
+				.unwrap()
+				.build()
+				.unwrap()
+				.block;
+			hash = block.hash();
+			client.import(BlockOrigin::File, block).await.unwrap();
+		}
+		hash
+	}
+
+	fn storage_diff_request(
+		from_block: Hash,
+		to_block: Hash,
+		key_prefixes: Vec<Vec<u8>>,
+	) -> schema::v1::light::request::Request {
+		schema::v1::light::request::Request::RemoteStorageDiffRequest(
+			schema::v1::light::RemoteStorageDiffRequest {
+				from_block: from_block.encode(),
+				to_block: to_block.encode(),
+				key_prefixes,
+			},
+		)
+	}
+
+	fn storage_diff_response(
+		response: OutgoingResponse,
+	) -> schema::v1::light::RemoteStorageDiffResponse {
+		let response = schema::v1::light::Response::decode(&response.result.unwrap()[..]).unwrap();
+		match response.response {
+			Some(schema::v1::light::response::Response::RemoteStorageDiffResponse(diff)) => diff,
+			response => panic!("Unexpected response: {response:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn storage_diff_proves_changed_keys_at_both_blocks() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let genesis_hash = client.info().genesis_hash;
+		let block_hash = build_blocks(&client, 2).await;
+
+		let system_prefix = twox_128(b"System").to_vec();
+		let response = send_request(
+			client.clone(),
+			storage_diff_request(genesis_hash, block_hash, vec![system_prefix.clone()]),
+		)
+		.await;
+
+		let diff = storage_diff_response(response);
+		// the block number and parent hash are changed by every block
+		for item in [&b"Number"[..], b"ParentHash"] {
+			assert!(diff.keys.contains(&[twox_128(b"System"), twox_128(item)].concat()));
+		}
+		assert!(diff.keys.iter().all(|key| key.starts_with(&system_prefix)));
+		assert!(diff.keys.windows(2).all(|keys| keys[0] < keys[1]));
+
+		let expected_proof = StorageProof::merge([genesis_hash, block_hash].map(|hash| {
+			client.read_proof(hash, &mut diff.keys.iter().map(AsRef::as_ref)).unwrap()
+		}));
+		assert_eq!(diff.proof, Some(expected_proof.encode()));
+	}
+
+	#[tokio::test]
+	async fn storage_diff_without_changes_is_empty() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let genesis_hash = client.info().genesis_hash;
+
+		let response =
+			send_request(client, storage_diff_request(genesis_hash, genesis_hash, Vec::new()))
+				.await;
+
+		let diff = storage_diff_response(response);
+		assert!(diff.keys.is_empty());
+		assert!(diff.proof.is_some());
+	}
+
+	#[tokio::test]
+	async fn storage_diff_over_too_many_blocks_is_rejected() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let genesis_hash = client.info().genesis_hash;
+		let block_hash = build_blocks(&client, MAX_STORAGE_DIFF_BLOCKS + 1).await;
+
+		let response =
+			send_request(client, storage_diff_request(genesis_hash, block_hash, Vec::new())).await;
+
+		assert_eq!(response.result, Err(()));
+		assert_eq!(
+			response.reputation_changes,
+			vec![ReputationChange::new(-(1 << 12), "bad request")]
+		);
+	}
+}
@@ -0,0 +1,338 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bitswap client for Substrate.
+//!
+//! Fetches blocks by CID from connected peers and verifies that their content matches the CID
+//! before handing it out.
+
+use super::{
+	schema::bitswap::{
+		message::{
+			wantlist::{Entry, WantType},
+			Wantlist,
+		},
+		Message as BitswapMessage,
+	},
+	BitswapError, Prefix, LOG_TARGET, PROTOCOL_NAME,
+};
+use crate::{
+	config::NetworkBackendType, request_responses::IfDisconnected, service::traits::NetworkRequest,
+};
+
+use cid::{multihash::Code, Cid};
+use futures::{stream::FuturesUnordered, StreamExt};
+use log::{debug, trace};
+use prost::Message;
+use sc_network_types::PeerId;
+use std::sync::Arc;
+
+/// Max number of peers queried for a single CID.
+const MAX_QUERIED_PEERS: usize = 8;
+
+/// Provides the peers which are queried for content.
+#[async_trait::async_trait]
+pub trait BitswapPeers: Send + Sync {
+	/// Peers we are currently connected to.
+	async fn peers(&self) -> Vec<PeerId>;
+}
+
+/// Bitswap client.
+///
+/// Requests are sent using request-response semantics, the way they are answered by the
+/// [`BitswapRequestHandler`](super::BitswapRequestHandler). The bitswap protocol must
+/// therefore be registered as a request-response protocol: this is the case when the libp2p
+/// backend serves bitswap, otherwise [`PROTOCOL_NAME`] has to be registered for outbound
+/// requests, with either backend.
+///
+/// When litep2p serves bitswap, the protocol is handled by litep2p's bitswap server, which
+/// can't send requests: every request then fails with [`BitswapError::Unsupported`]. Peers
+/// answering on a separate substream, as litep2p nodes do, are never heard: if none of the
+/// queried peers answers, [`BitswapError::NoResponse`] is returned.
+pub struct BitswapClient<N> {
+	network: N,
+	peers: Arc<dyn BitswapPeers>,
+	backend: NetworkBackendType,
+	serves_bitswap: bool,
+}
+
+impl<N: NetworkRequest + Send + Sync> BitswapClient<N> {
+	/// Create a new [`BitswapClient`] sending requests through `network`, which uses `backend`
+	/// and serves bitswap if `serves_bitswap` is true.
+	pub fn new(
+		network: N,
+		peers: Arc<dyn BitswapPeers>,
+		backend: NetworkBackendType,
+		serves_bitswap: bool,
+	) -> Self {
+		Self { network, peers, backend, serves_bitswap }
+	}
+
+	/// Fetch the content referenced by `cid`.
+	///
+	/// Up to [`MAX_QUERIED_PEERS`] connected peers are queried at once, the first block
+	/// matching `cid` is returned.
+	pub async fn get(&self, cid: &Cid) -> Result<Vec<u8>, BitswapError> {
+		if self.serves_bitswap && matches!(self.backend, NetworkBackendType::Litep2p) {
+			return Err(BitswapError::Unsupported)
+		}
+
+		if cid.version() != cid::Version::V1 ||
+			hasher(cid.hash().code()).is_none() ||
+			cid.hash().size() != 32
+		{
+			return Err(BitswapError::UnsupportedCid)
+		}
+
+		let request = BitswapMessage {
+			wantlist: Some(Wantlist {
+				entries: vec![Entry {
+					block: cid.to_bytes(),
+					priority: 1,
+					cancel: false,
+					want_type: WantType::Block as i32,
+					send_dont_have: true,
+				}],
+				full: true,
+			}),
+			..Default::default()
+		}
+		.encode_to_vec();
+
+		let mut requests = self
+			.peers
+			.peers()
+			.await
+			.into_iter()
+			.take(MAX_QUERIED_PEERS)
+			.map(|peer| {
+				let request = self.network.request(
+					peer,
+					PROTOCOL_NAME.into(),
+					request.clone(),
+					None,
+					IfDisconnected::ImmediateError,
+				);
+				async move { (peer, request.await) }
+			})
+			.collect::<FuturesUnordered<_>>();
+
+		let mut answered = false;
+		while let Some((peer, response)) = requests.next().await {
+			let response = match response {
+				Ok((response, _)) => response,
+				Err(e) => {
+					debug!(target: LOG_TARGET, "Bitswap request to {peer} failed: {e}");
+					continue
+				},
+			};
+			answered = true;
+			match block_from_response(cid, &response) {
+				Ok(Some(data)) => {
+					trace!(target: LOG_TARGET, "Fetched CID {cid} from {peer}");
+					return Ok(data)
+				},
+				Ok(None) => trace!(target: LOG_TARGET, "Peer {peer} doesn't have CID {cid}"),
+				Err(e) => debug!(target: LOG_TARGET, "Invalid bitswap response from {peer}: {e}"),
+			}
+		}
+
+		Err(if answered { BitswapError::NotFound } else { BitswapError::NoResponse })
+	}
+}
+
+/// Hash function of the multihash `code`, if supported.
+fn hasher(code: u64) -> Option<fn(&[u8]) -> [u8; 32]> {
+	match code {
+		c if c == u64::from(Code::Blake2b256) => Some(sp_crypto_hashing::blake2_256),
+		c if c == u64::from(Code::Sha2_256) => Some(sp_crypto_hashing::sha2_256),
+		c if c == u64::from(Code::Keccak256) => Some(sp_crypto_hashing::keccak_256),
+		_ => None,
+	}
+}
+
+/// Extract the block referenced by `cid` from a bitswap response.
+///
+/// Returns `None` if the response has no such block, and an error if a block claiming to be
+/// referenced by `cid` doesn't match it.
+fn block_from_response(cid: &Cid, response: &[u8]) -> Result<Option<Vec<u8>>, BitswapError> {
+	let response = BitswapMessage::decode(response)?;
+	let prefix = Prefix {
+		version: cid.version(),
+		codec: cid.codec(),
+		mh_type: cid.hash().code(),
+		mh_len: cid.hash().size(),
+	}
+	.to_bytes();
+
+	let hash = hasher(cid.hash().code()).ok_or(BitswapError::UnsupportedCid)?;
+
+	match response.payload.into_iter().find(|block| block.prefix == prefix) {
+		Some(block) if hash(&block.data)[..] == *cid.hash().digest() => Ok(Some(block.data)),
+		Some(_) => Err(BitswapError::InvalidBlock),
+		None => Ok(None),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		bitswap::BitswapRequestHandler,
+		request_responses::{IncomingRequest, OutgoingResponse},
+		types::ProtocolName,
+		RequestFailure,
+	};
+	use futures::channel::oneshot;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::BlockOrigin;
+	use sp_runtime::codec::Encode;
+	use substrate_test_runtime::ExtrinsicBuilder;
+	use substrate_test_runtime_client::{self, prelude::*, TestClientBuilder};
+
+	/// Forwards requests to a local bitswap request handler, optionally tampering with the
+	/// returned blocks.
+	struct LocalNetwork {
+		inbound: async_channel::Sender<IncomingRequest>,
+		tamper: bool,
+	}
+
+	#[async_trait::async_trait]
+	impl NetworkRequest for LocalNetwork {
+		async fn request(
+			&self,
+			target: PeerId,
+			_protocol: ProtocolName,
+			request: Vec<u8>,
+			_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+			_connect: IfDisconnected,
+		) -> Result<(Vec<u8>, ProtocolName), RequestFailure> {
+			let (tx, rx) = oneshot::channel();
+			self.inbound
+				.send(IncomingRequest { peer: target, payload: request, pending_response: tx })
+				.await
+				.unwrap();
+			let OutgoingResponse { result, .. } = rx.await.map_err(|_| RequestFailure::Refused)?;
+			let mut response = result.map_err(|_| RequestFailure::Refused)?;
+			if self.tamper {
+				let mut message = BitswapMessage::decode(&response[..]).unwrap();
+				message.payload.iter_mut().for_each(|block| block.data.push(0));
+				response = message.encode_to_vec();
+			}
+			Ok((response, PROTOCOL_NAME.into()))
+		}
+
+		fn start_request(
+			&self,
+			_target: PeerId,
+			_protocol: ProtocolName,
+			_request: Vec<u8>,
+			_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+			_tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+			_connect: IfDisconnected,
+		) {
+			unimplemented!()
+		}
+	}
+
+	struct Peers(Vec<PeerId>);
+
+	#[async_trait::async_trait]
+	impl BitswapPeers for Peers {
+		async fn peers(&self) -> Vec<PeerId> {
+			self.0.clone()
+		}
+	}
+
+	async fn client_with_indexed_data(data: Vec<u8>, tamper: bool) -> BitswapClient<LocalNetwork> {
+		let client = TestClientBuilder::with_tx_storage(u32::MAX).build();
+		let mut block_builder = BlockBuilderBuilder::new(&client)
+			.on_parent_block(client.chain_info().genesis_hash)
+			.with_parent_block_number(0)
+			.build()
+			.unwrap();
+		block_builder.push(ExtrinsicBuilder::new_indexed_call(data).build()).unwrap();
+		let block = block_builder.build().unwrap().block;
+		client.import(BlockOrigin::File, block).await.unwrap();
+
+		let (bitswap, config) = BitswapRequestHandler::new(Arc::new(client));
+		tokio::spawn(async move { bitswap.run().await });
+
+		let network = LocalNetwork { inbound: config.inbound_queue.unwrap(), tamper };
+		BitswapClient::new(
+			network,
+			Arc::new(Peers(vec![PeerId::random()])),
+			NetworkBackendType::Libp2p,
+			false,
+		)
+	}
+
+	fn blake2_cid(data: &[u8]) -> Cid {
+		Cid::new_v1(
+			0x70,
+			cid::multihash::Multihash::wrap(
+				u64::from(Code::Blake2b256),
+				&sp_crypto_hashing::blake2_256(data),
+			)
+			.unwrap(),
+		)
+	}
+
+	#[tokio::test]
+	async fn fetches_indexed_data() {
+		let data = vec![0x13, 0x37, 0x13, 0x38];
+		let bitswap = client_with_indexed_data(data.clone(), false).await;
+
+		assert_eq!(bitswap.get(&blake2_cid(&data)).await.unwrap(), data);
+		assert!(matches!(bitswap.get(&blake2_cid(&[1, 2, 3])).await, Err(BitswapError::NotFound)));
+	}
+
+	#[tokio::test]
+	async fn rejects_blocks_not_matching_cid() {
+		let data = vec![0x13, 0x37, 0x13, 0x38];
+		let bitswap = client_with_indexed_data(data.clone(), true).await;
+
+		assert!(matches!(bitswap.get(&blake2_cid(&data)).await, Err(BitswapError::NotFound)));
+	}
+
+	#[tokio::test]
+	async fn rejects_unsupported_cid() {
+		let bitswap = client_with_indexed_data(vec![1], false).await;
+		let cid = Cid::new_v0(
+			cid::multihash::Multihash::wrap(u64::from(Code::Sha2_256), &[0; 32]).unwrap(),
+		)
+		.unwrap();
+
+		assert!(matches!(bitswap.get(&cid).await, Err(BitswapError::UnsupportedCid)));
+	}
+
+	#[tokio::test]
+	async fn fails_without_answers_or_when_litep2p_serves_bitswap() {
+		let network = LocalNetwork { inbound: async_channel::bounded(1).0, tamper: false };
+		let cid = blake2_cid(&[1]);
+
+		let bitswap =
+			BitswapClient::new(network, Arc::new(Peers(vec![])), NetworkBackendType::Litep2p, true);
+		assert!(matches!(bitswap.get(&cid).await, Err(BitswapError::Unsupported)));
+
+		let bitswap = BitswapClient { serves_bitswap: false, ..bitswap };
+		assert!(matches!(bitswap.get(&cid).await, Err(BitswapError::NoResponse)));
+
+		let bitswap =
+			BitswapClient { backend: NetworkBackendType::Libp2p, serves_bitswap: true, ..bitswap };
+		assert!(matches!(bitswap.get(&cid).await, Err(BitswapError::NoResponse)));
+	}
+}
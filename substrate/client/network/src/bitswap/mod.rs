@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Bitswap server for Substrate.
+//! Bitswap server and client for Substrate.
 //!
 //! Allows querying transactions by hash over standard bitswap protocol
 //! Only supports bitswap 1.2.0.
@@ -39,15 +39,18 @@ use sp_runtime::traits::Block as BlockT;
 use std::{io, sync::Arc, time::Duration};
 use unsigned_varint::encode as varint_encode;
 
+mod client;
 mod schema;
 
+pub use client::{BitswapClient, BitswapPeers};
+
 const LOG_TARGET: &str = "bitswap";
 
 // Undocumented, but according to JS the bitswap messages have a max size of 512*1024 bytes
 // https://github.com/ipfs/js-ipfs-bitswap/blob/
 // d8f80408aadab94c962f6b88f343eb9f39fa0fcc/src/decision-engine/index.js#L16
 // We set it to the same value as max substrate protocol message
-const MAX_PACKET_SIZE: u64 = 16 * 1024 * 1024;
+pub const MAX_PACKET_SIZE: u64 = 16 * 1024 * 1024;
 
 /// Max number of queued responses before denying requests.
 const MAX_REQUEST_QUEUE: usize = 20;
@@ -56,7 +59,10 @@ const MAX_REQUEST_QUEUE: usize = 20;
 const MAX_WANTED_BLOCKS: usize = 16;
 
 /// Bitswap protocol name
-const PROTOCOL_NAME: &'static str = "/ipfs/bitswap/1.2.0";
+pub const PROTOCOL_NAME: &'static str = "/ipfs/bitswap/1.2.0";
+
+/// Bitswap request timeout.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
 
 /// Prefix represents all metadata of a CID, without the actual content.
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -107,7 +113,7 @@ impl<B: BlockT> BitswapRequestHandler<B> {
 			fallback_names: vec![],
 			max_request_size: MAX_PACKET_SIZE,
 			max_response_size: MAX_PACKET_SIZE,
-			request_timeout: Duration::from_secs(15),
+			request_timeout: REQUEST_TIMEOUT,
 			inbound_queue: Some(tx),
 		};
 
@@ -285,6 +291,26 @@ pub enum BitswapError {
 	/// Too many blocks requested.
 	#[error("Too many block entries in the request.")]
 	TooManyEntries,
+
+	/// CID version or hash function not supported.
+	#[error("Unsupported CID.")]
+	UnsupportedCid,
+
+	/// Received block doesn't match its CID.
+	#[error("Block doesn't match its CID.")]
+	InvalidBlock,
+
+	/// None of the queried peers returned the block.
+	#[error("Block not found.")]
+	NotFound,
+
+	/// None of the queried peers answered.
+	#[error("No peer answered the request.")]
+	NoResponse,
+
+	/// The network backend doesn't support sending bitswap requests while serving bitswap.
+	#[error("Bitswap requests are not supported by the network backend.")]
+	Unsupported,
 }
 
 #[cfg(test)]
@@ -17,17 +17,22 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+	bitswap::{self, BitswapClient, BitswapPeers, BitswapRequestHandler},
+	config::NetworkBackendType,
 	litep2p::{
 		peerstore::peerstore_handle_test,
+		service::Litep2pNetworkService,
 		shim::request_response::{OutboundRequest, RequestResponseProtocol},
 	},
 	request_responses::{IfDisconnected, IncomingRequest, OutgoingResponse},
 	ProtocolName, RequestFailure,
 };
 
+use cid::{multihash::Code, Cid};
 use futures::{channel::oneshot, StreamExt};
 use litep2p::{
 	config::ConfigBuilder as Litep2pConfigBuilder,
+	crypto::ed25519::Keypair,
 	protocol::request_response::{
 		ConfigBuilder, DialOptions, RequestResponseError, RequestResponseEvent,
 		RequestResponseHandle,
@@ -36,15 +41,27 @@ use litep2p::{
 	Litep2p, Litep2pEvent,
 };
 
+use sc_block_builder::BlockBuilderBuilder;
 use sc_network_types::PeerId;
 use sc_utils::mpsc::tracing_unbounded;
+use sp_consensus::BlockOrigin;
+use substrate_test_runtime::ExtrinsicBuilder;
+use substrate_test_runtime_client::{prelude::*, TestClientBuilder};
 
 use std::{collections::HashMap, sync::Arc, task::Poll};
 
 /// Create `litep2p` for testing.
 async fn make_litep2p() -> (Litep2p, RequestResponseHandle) {
-	let (config, handle) = ConfigBuilder::new(litep2p::ProtocolName::from("/protocol/1"))
-		.with_max_size(1024)
+	make_litep2p_with_protocol("/protocol/1", 1024).await
+}
+
+/// Create `litep2p` running the request-response protocol `protocol` for testing.
+async fn make_litep2p_with_protocol(
+	protocol: &'static str,
+	max_size: usize,
+) -> (Litep2p, RequestResponseHandle) {
+	let (config, handle) = ConfigBuilder::new(litep2p::ProtocolName::from(protocol))
+		.with_max_size(max_size)
 		.build();
 
 	(
@@ -904,3 +921,101 @@ async fn old_protocol_supported_but_no_fallback_provided() {
 		event => panic!("invalid event: {event:?}"),
 	}
 }
+
+#[tokio::test]
+async fn bitswap_request_through_network_service() {
+	let max_size = bitswap::MAX_PACKET_SIZE as usize;
+	let (mut litep2p1, handle1) =
+		make_litep2p_with_protocol(bitswap::PROTOCOL_NAME, max_size).await;
+	let (mut litep2p2, handle2) =
+		make_litep2p_with_protocol(bitswap::PROTOCOL_NAME, max_size).await;
+
+	let peer1 = *litep2p1.local_peer_id();
+	let peer2 = *litep2p2.local_peer_id();
+	let public_addresses = litep2p1.public_addresses();
+
+	connect_peers(&mut litep2p1, &mut litep2p2).await;
+
+	// The second peer serves indexed transaction data.
+	let data = vec![0x13, 0x37, 0x13, 0x38];
+	let client = TestClientBuilder::with_tx_storage(u32::MAX).build();
+	let mut block_builder = BlockBuilderBuilder::new(&client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	block_builder
+		.push(ExtrinsicBuilder::new_indexed_call(data.clone()).build())
+		.unwrap();
+	let block = block_builder.build().unwrap().block;
+	client.import(BlockOrigin::File, block).await.unwrap();
+
+	let (handler, config) = BitswapRequestHandler::new(Arc::new(client));
+	tokio::spawn(handler.run());
+
+	let (_, outbound_rx2) = tracing_unbounded("outbound-request", 1000);
+	let server = RequestResponseProtocol::new(
+		ProtocolName::from(bitswap::PROTOCOL_NAME),
+		handle2,
+		Arc::new(peerstore_handle_test()),
+		config.inbound_queue,
+		outbound_rx2,
+		HashMap::new(),
+		None,
+	);
+
+	// The first peer fetches it through its network service.
+	let (outbound_tx1, outbound_rx1) = tracing_unbounded("outbound-request", 1000);
+	let senders = HashMap::from_iter([(ProtocolName::from(bitswap::PROTOCOL_NAME), outbound_tx1)]);
+	let client = RequestResponseProtocol::new(
+		ProtocolName::from(bitswap::PROTOCOL_NAME),
+		handle1,
+		Arc::new(peerstore_handle_test()),
+		None,
+		outbound_rx1,
+		senders.clone(),
+		None,
+	);
+	let network = Litep2pNetworkService::new(
+		peer1,
+		Keypair::generate(),
+		tracing_unbounded("network-service-command", 1000).0,
+		Arc::new(peerstore_handle_test()),
+		HashMap::new(),
+		ProtocolName::from("/block-announces/1"),
+		senders,
+		Default::default(),
+		public_addresses,
+	);
+
+	tokio::spawn(server.run());
+	tokio::spawn(client.run());
+	tokio::spawn(async move { while let Some(_) = litep2p1.next_event().await {} });
+	tokio::spawn(async move { while let Some(_) = litep2p2.next_event().await {} });
+
+	struct Peers(PeerId);
+
+	#[async_trait::async_trait]
+	impl BitswapPeers for Peers {
+		async fn peers(&self) -> Vec<PeerId> {
+			vec![self.0]
+		}
+	}
+
+	let bitswap = BitswapClient::new(
+		network,
+		Arc::new(Peers(peer2.into())),
+		NetworkBackendType::Litep2p,
+		false,
+	);
+	let cid = Cid::new_v1(
+		0x70,
+		cid::multihash::Multihash::wrap(
+			u64::from(Code::Blake2b256),
+			&sp_crypto_hashing::blake2_256(&data),
+		)
+		.unwrap(),
+	);
+
+	assert_eq!(bitswap.get(&cid).await.unwrap(), data);
+}
@@ -47,9 +47,7 @@ use sc_network_common::role::ObservedRole;
 use sc_network_sync::{SyncEvent, SyncEventStream};
 use sc_network_types::PeerId;
 use sp_runtime::traits::Block as BlockT;
-use sp_statement_store::{
-	Hash, NetworkPriority, Statement, StatementSource, StatementStore, SubmitResult,
-};
+use sp_statement_store::{Hash, NetworkPriority, Statement, StatementStore, SubmitResult};
 use std::{
 	collections::{hash_map::Entry, HashMap, HashSet},
 	iter,
@@ -168,12 +166,12 @@ impl StatementHandlerPrototype {
 		executor(
 			async move {
 				loop {
-					let task: Option<(Statement, oneshot::Sender<SubmitResult>)> =
+					let task: Option<(Statement, PeerId, oneshot::Sender<SubmitResult>)> =
 						queue_receiver.next().await;
 					match task {
 						None => return,
-						Some((statement, completion)) => {
-							let result = store.submit(statement, StatementSource::Network);
+						Some((statement, peer, completion)) => {
+							let result = store.submit_from_peer(statement, &peer.to_bytes());
 							if completion.send(result).is_err() {
 								log::debug!(
 									target: LOG_TARGET,
@@ -239,7 +237,7 @@ pub struct StatementHandler<
 	// All connected peers
 	peers: HashMap<PeerId, Peer>,
 	statement_store: Arc<dyn StatementStore>,
-	queue_sender: async_channel::Sender<(Statement, oneshot::Sender<SubmitResult>)>,
+	queue_sender: async_channel::Sender<(Statement, PeerId, oneshot::Sender<SubmitResult>)>,
 	/// Prometheus metrics.
 	metrics: Option<Metrics>,
 }
@@ -391,7 +389,7 @@ where
 				match self.pending_statements_peers.entry(hash) {
 					Entry::Vacant(entry) => {
 						let (completion_sender, completion_receiver) = oneshot::channel();
-						match self.queue_sender.try_send((s, completion_sender)) {
+						match self.queue_sender.try_send((s, who, completion_sender)) {
 							Ok(()) => {
 								self.pending_statements.push(
 									async move {
@@ -32,7 +32,7 @@ use crate::{
 		syncing_service::{SyncingService, ToServiceCommand},
 	},
 	strategy::{
-		warp::{EncodedProof, WarpProofRequest, WarpSyncConfig},
+		warp::{EncodedProof, WarpProofProtocolNames, WarpProofRequest, WarpSyncConfig},
 		PolkadotSyncingStrategy, StrategyKey, SyncingAction, SyncingConfig, SyncingStrategy,
 	},
 	types::{
@@ -274,8 +274,8 @@ pub struct SyncingEngine<B: BlockT, Client> {
 	/// Protocol name used to send out state requests
 	state_request_protocol_name: ProtocolName,
 
-	/// Protocol name used to send out warp sync requests
-	warp_sync_protocol_name: Option<ProtocolName>,
+	/// Protocol names used to send out warp sync requests
+	warp_sync_protocol_names: Option<WarpProofProtocolNames>,
 
 	/// Handle to import queue.
 	import_queue: Box<dyn ImportQueueService<B>>,
@@ -306,7 +306,7 @@ where
 		import_queue: Box<dyn ImportQueueService<B>>,
 		block_downloader: Arc<dyn BlockDownloader<B>>,
 		state_request_protocol_name: ProtocolName,
-		warp_sync_protocol_name: Option<ProtocolName>,
+		warp_sync_protocol_names: Option<WarpProofProtocolNames>,
 		peer_store_handle: Arc<dyn PeerStoreProvider>,
 	) -> Result<(Self, SyncingService<B>, N::NotificationProtocolConfig), ClientError>
 	where
@@ -451,7 +451,7 @@ where
 				pending_responses: PendingResponses::new(),
 				block_downloader,
 				state_request_protocol_name,
-				warp_sync_protocol_name,
+				warp_sync_protocol_names,
 				import_queue,
 			},
 			SyncingService::new(tx, num_connected, is_major_syncing),
@@ -1101,14 +1101,19 @@ where
 
 		self.pending_responses.insert(peer_id, key, PeerRequest::WarpProof, rx.boxed());
 
-		match &self.warp_sync_protocol_name {
-			Some(name) => self.network_service.start_request(
-				peer_id,
-				name.clone(),
-				request.encode(),
-				tx,
-				IfDisconnected::ImmediateError,
-			),
+		match &self.warp_sync_protocol_names {
+			Some(names) => {
+				// Peers not supporting the latest protocol version are asked for version 1 proofs.
+				let request = request.encode();
+				self.network_service.start_request_with_fallback(
+					peer_id,
+					names.latest.clone(),
+					request.clone(),
+					(request, names.fallback.clone()),
+					tx,
+					IfDisconnected::ImmediateError,
+				)
+			},
 			None => {
 				log::warn!(
 					target: LOG_TARGET,
@@ -1139,7 +1144,7 @@ where
 		let ResponseEvent { peer_id, key, request, response } = response_event;
 
 		match response {
-			Ok(Ok((resp, _))) => match request {
+			Ok(Ok((resp, protocol_name))) => match request {
 				PeerRequest::Block(req) => {
 					match self.block_downloader.block_response_into_blocks(&req, resp) {
 						Ok(blocks) => {
@@ -1191,7 +1196,16 @@ where
 					self.strategy.on_state_response(peer_id, key, response);
 				},
 				PeerRequest::WarpProof => {
-					self.strategy.on_warp_proof_response(&peer_id, key, EncodedProof(resp));
+					let version = self
+						.warp_sync_protocol_names
+						.as_ref()
+						.map_or(1, |names| names.version(&protocol_name));
+					self.strategy.on_warp_proof_response(
+						&peer_id,
+						key,
+						EncodedProof(resp),
+						version,
+					);
 				},
 			},
 			Ok(Err(e)) => {
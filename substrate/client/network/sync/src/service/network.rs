@@ -54,6 +54,7 @@ pub enum ToServiceCommand {
 		PeerId,
 		ProtocolName,
 		Vec<u8>,
+		Option<(Vec<u8>, ProtocolName)>,
 		oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
 		IfDisconnected,
 	),
@@ -91,9 +92,32 @@ impl NetworkServiceHandle {
 		tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
 		connect: IfDisconnected,
 	) {
-		let _ = self
-			.tx
-			.unbounded_send(ToServiceCommand::StartRequest(who, protocol, request, tx, connect));
+		let _ = self.tx.unbounded_send(ToServiceCommand::StartRequest(
+			who, protocol, request, None, tx, connect,
+		));
+	}
+
+	/// Send request to peer, falling back to `fallback_request` over its protocol if the peer
+	/// doesn't support `protocol`.
+	///
+	/// The name of the protocol the response was received over is passed to `tx`.
+	pub fn start_request_with_fallback(
+		&self,
+		who: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		fallback_request: (Vec<u8>, ProtocolName),
+		tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+		connect: IfDisconnected,
+	) {
+		let _ = self.tx.unbounded_send(ToServiceCommand::StartRequest(
+			who,
+			protocol,
+			request,
+			Some(fallback_request),
+			tx,
+			connect,
+		));
 	}
 }
 
@@ -113,8 +137,8 @@ impl NetworkServiceProvider {
 					service.disconnect_peer(peer, protocol_name),
 				ToServiceCommand::ReportPeer(peer, reputation_change) =>
 					service.report_peer(peer, reputation_change),
-				ToServiceCommand::StartRequest(peer, protocol, request, tx, connect) =>
-					service.start_request(peer, protocol, request, None, tx, connect),
+				ToServiceCommand::StartRequest(peer, protocol, request, fallback, tx, connect) =>
+					service.start_request(peer, protocol, request, fallback, tx, connect),
 			}
 		}
 	}
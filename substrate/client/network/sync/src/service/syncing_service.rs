@@ -22,7 +22,7 @@ use futures::{channel::oneshot, Stream};
 use sc_network_types::PeerId;
 
 use sc_consensus::{BlockImportError, BlockImportStatus, JustificationSyncLink, Link};
-use sc_network::{NetworkBlock, NetworkSyncForkRequest};
+use sc_network::{bitswap::BitswapPeers, NetworkBlock, NetworkSyncForkRequest};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 
@@ -175,6 +175,17 @@ impl<B: BlockT> SyncStatusProvider<B> for SyncingService<B> {
 	}
 }
 
+#[async_trait::async_trait]
+impl<B: BlockT> BitswapPeers for SyncingService<B> {
+	/// Get the peers we are syncing with.
+	async fn peers(&self) -> Vec<PeerId> {
+		self.peers_info()
+			.await
+			.map(|peers| peers.into_iter().map(|(peer, _)| peer).collect())
+			.unwrap_or_default()
+	}
+}
+
 impl<B: BlockT> Link<B> for SyncingService<B> {
 	fn blocks_processed(
 		&mut self,
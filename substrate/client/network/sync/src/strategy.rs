@@ -116,12 +116,13 @@ where
 		response: OpaqueStateResponse,
 	);
 
-	/// Process warp proof response.
+	/// Process warp proof response received over warp proof protocol `version`.
 	fn on_warp_proof_response(
 		&mut self,
 		peer_id: &PeerId,
 		key: StrategyKey,
 		response: EncodedProof,
+		version: u8,
 	);
 
 	/// A batch of blocks that have been processed, with or without errors.
@@ -397,9 +398,10 @@ where
 		peer_id: &PeerId,
 		key: StrategyKey,
 		response: EncodedProof,
+		version: u8,
 	) {
 		if let (StrategyKey::Warp, Some(ref mut warp)) = (key, &mut self.warp) {
-			warp.on_warp_proof_response(peer_id, response);
+			warp.on_warp_proof_response(peer_id, response, version);
 		} else {
 			error!(
 				target: LOG_TARGET,
@@ -632,6 +632,7 @@ where
 		_peer_id: &PeerId,
 		_key: StrategyKey,
 		_response: EncodedProof,
+		_version: u8,
 	) {
 		error!(
 			target: LOG_TARGET,
@@ -27,6 +27,7 @@ use crate::{
 };
 use codec::{Decode, Encode};
 use log::{debug, error, trace};
+use sc_network::types::ProtocolName;
 use sc_network_common::sync::message::{
 	BlockAnnounce, BlockAttributes, BlockData, BlockRequest, Direction, FromBlock,
 };
@@ -44,6 +45,35 @@ const MIN_PEERS_TO_START_WARP_SYNC: usize = 3;
 /// Scale-encoded warp sync proof response.
 pub struct EncodedProof(pub Vec<u8>);
 
+/// Highest warp proof protocol version supported by this node.
+///
+/// Every version is served over its own request-response protocol, see
+/// [`crate::warp_request_handler`]. Version 1 proofs carry full GRANDPA justifications. Version 2
+/// allows the provider to answer with a compact proof format, see
+/// [`WarpSyncProvider::generate_for_version`].
+pub const WARP_PROOF_PROTOCOL_VERSION: u8 = 2;
+
+/// Names of the warp proof request protocols used to request proofs from peers.
+#[derive(Debug, Clone)]
+pub struct WarpProofProtocolNames {
+	/// Protocol of [`WARP_PROOF_PROTOCOL_VERSION`], requested first.
+	pub latest: ProtocolName,
+	/// Version 1 protocol, supported by every node and requested when the peer doesn't support
+	/// `latest`.
+	pub fallback: ProtocolName,
+}
+
+impl WarpProofProtocolNames {
+	/// Warp proof protocol version of the protocol `name` a response was received over.
+	pub fn version(&self, name: &ProtocolName) -> u8 {
+		if name == &self.latest {
+			WARP_PROOF_PROTOCOL_VERSION
+		} else {
+			1
+		}
+	}
+}
+
 /// Warp sync request
 #[derive(Encode, Decode, Debug, Clone)]
 pub struct WarpProofRequest<B: BlockT> {
@@ -67,6 +97,30 @@ pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
 		&self,
 		start: Block::Hash,
 	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>>;
+	/// Generate proof starting at given block hash for a request received over warp proof
+	/// protocol `version`.
+	///
+	/// Providers without a more compact format for newer versions answer with
+	/// [`Self::generate`], which [`Self::verify_for_version`] has to accept for every version.
+	fn generate_for_version(
+		&self,
+		start: Block::Hash,
+		_version: u8,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		self.generate(start)
+	}
+	/// Generate a proof of the finality of `target` starting at the genesis authority set.
+	///
+	/// Unlike [`Self::generate`], the proof is not capped in size and ends at `target`, so that
+	/// [`Self::verify`] run from the genesis authority set returns
+	/// [`VerificationResult::Complete`] with the header of `target`. It is used to make state
+	/// snapshots verifiable. Providers not supporting it return an error.
+	fn generate_from_genesis(
+		&self,
+		_target: Block::Hash,
+	) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+		Err("Finality proofs from genesis are not supported".into())
+	}
 	/// Verify warp proof against current set of authorities.
 	fn verify(
 		&self,
@@ -74,6 +128,17 @@ pub trait WarpSyncProvider<Block: BlockT>: Send + Sync {
 		set_id: SetId,
 		authorities: AuthorityList,
 	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>>;
+	/// Verify warp proof received over warp proof protocol `version` against current set of
+	/// authorities.
+	fn verify_for_version(
+		&self,
+		proof: &EncodedProof,
+		set_id: SetId,
+		authorities: AuthorityList,
+		_version: u8,
+	) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+		self.verify(proof, set_id, authorities)
+	}
 	/// Get current list of authorities. This is supposed to be genesis authorities when starting
 	/// sync.
 	fn current_authorities(&self) -> AuthorityList;
@@ -317,8 +382,13 @@ where
 		trace!(target: LOG_TARGET, "Started warp sync with {} peers.", self.peers.len());
 	}
 
-	/// Process warp proof response.
-	pub fn on_warp_proof_response(&mut self, peer_id: &PeerId, response: EncodedProof) {
+	/// Process warp proof response received over warp proof protocol `version`.
+	pub fn on_warp_proof_response(
+		&mut self,
+		peer_id: &PeerId,
+		response: EncodedProof,
+		version: u8,
+	) {
 		if let Some(peer) = self.peers.get_mut(peer_id) {
 			peer.state = PeerState::Available;
 		}
@@ -332,7 +402,12 @@ where
 			return
 		};
 
-		match warp_sync_provider.verify(&response, *set_id, authorities.clone()) {
+		match warp_sync_provider.verify_for_version(
+			&response,
+			*set_id,
+			authorities.clone(),
+			version,
+		) {
 			Err(e) => {
 				debug!(target: LOG_TARGET, "Bad warp proof response: {}", e);
 				self.actions
@@ -644,6 +719,11 @@ mod test {
 				&self,
 				start: B::Hash,
 			) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>>;
+			fn generate_for_version(
+				&self,
+				start: B::Hash,
+				version: u8,
+			) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>>;
 			fn verify(
 				&self,
 				proof: &EncodedProof,
@@ -992,7 +1072,7 @@ mod test {
 			panic!("Invalid action");
 		};
 
-		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()));
+		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()), 1);
 
 		// We only interested in already generated actions, not new requests.
 		let actions = std::mem::take(&mut warp_sync.actions);
@@ -1033,7 +1113,7 @@ mod test {
 			panic!("Invalid action");
 		};
 
-		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()));
+		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()), 1);
 
 		assert!(warp_sync.actions.is_empty(), "No extra actions generated");
 		assert!(matches!(warp_sync.phase, Phase::WarpProof { .. }));
@@ -1077,7 +1157,7 @@ mod test {
 			panic!("Invalid action.");
 		};
 
-		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()));
+		warp_sync.on_warp_proof_response(&request_peer_id, EncodedProof(Vec::new()), 1);
 
 		assert!(warp_sync.actions.is_empty(), "No extra actions generated.");
 		assert!(
@@ -1462,4 +1542,15 @@ mod test {
 		assert_eq!(result.target_body, body);
 		assert_eq!(result.target_justifications, justifications);
 	}
+
+	#[test]
+	fn warp_proof_response_version_follows_protocol_name() {
+		let names = WarpProofProtocolNames {
+			latest: ProtocolName::from("/genesis/sync/warp/2"),
+			fallback: ProtocolName::from("/genesis/sync/warp"),
+		};
+
+		assert_eq!(names.version(&names.latest), WARP_PROOF_PROTOCOL_VERSION);
+		assert_eq!(names.version(&names.fallback), 1);
+	}
 }
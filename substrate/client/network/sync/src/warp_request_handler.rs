@@ -15,6 +15,10 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Helper for handling (i.e. answering) grandpa warp sync requests from a remote peer.
+//!
+//! Every warp proof protocol version is served over its own request-response protocol. Version 1
+//! keeps the original protocol names, newer versions append the version to them, so that peers
+//! not supporting a version refuse to negotiate it and requesters fall back to version 1.
 
 use codec::Decode;
 use futures::{channel::oneshot, stream::StreamExt};
@@ -50,9 +54,38 @@ pub fn generate_request_response_config<
 	fork_id: Option<&str>,
 	inbound_queue: async_channel::Sender<IncomingRequest>,
 ) -> N::RequestResponseProtocolConfig {
+	generate_versioned_request_response_config::<_, B, N>(
+		protocol_id,
+		genesis_hash,
+		fork_id,
+		1,
+		inbound_queue,
+	)
+}
+
+/// Generates a `RequestResponseProtocolConfig` for warp proof protocol `version` of the grandpa
+/// warp sync request protocol, refusing incoming requests.
+pub fn generate_versioned_request_response_config<
+	Hash: AsRef<[u8]>,
+	B: BlockT,
+	N: NetworkBackend<B, <B as BlockT>::Hash>,
+>(
+	protocol_id: ProtocolId,
+	genesis_hash: Hash,
+	fork_id: Option<&str>,
+	version: u8,
+	inbound_queue: async_channel::Sender<IncomingRequest>,
+) -> N::RequestResponseProtocolConfig {
+	// The legacy protocol name predates protocol versions.
+	let fallback_names = if version > 1 {
+		Vec::new()
+	} else {
+		std::iter::once(generate_legacy_protocol_name(protocol_id).into()).collect()
+	};
+
 	N::request_response_config(
-		generate_protocol_name(genesis_hash, fork_id).into(),
-		std::iter::once(generate_legacy_protocol_name(protocol_id).into()).collect(),
+		generate_protocol_name(genesis_hash, fork_id, version).into(),
+		fallback_names,
 		32,
 		MAX_RESPONSE_SIZE,
 		Duration::from_secs(10),
@@ -60,13 +93,24 @@ pub fn generate_request_response_config<
 	)
 }
 
-/// Generate the grandpa warp sync protocol name from the genesis hash and fork id.
-fn generate_protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash, fork_id: Option<&str>) -> String {
+/// Generate the grandpa warp sync protocol name of warp proof protocol `version` from the genesis
+/// hash and fork id.
+fn generate_protocol_name<Hash: AsRef<[u8]>>(
+	genesis_hash: Hash,
+	fork_id: Option<&str>,
+	version: u8,
+) -> String {
 	let genesis_hash = genesis_hash.as_ref();
-	if let Some(fork_id) = fork_id {
+	let name = if let Some(fork_id) = fork_id {
 		format!("/{}/{}/sync/warp", array_bytes::bytes2hex("", genesis_hash), fork_id)
 	} else {
 		format!("/{}/sync/warp", array_bytes::bytes2hex("", genesis_hash))
+	};
+
+	if version > 1 {
+		format!("{name}/{version}")
+	} else {
+		name
 	}
 }
 
@@ -79,26 +123,42 @@ fn generate_legacy_protocol_name(protocol_id: ProtocolId) -> String {
 pub struct RequestHandler<TBlock: BlockT> {
 	backend: Arc<dyn WarpSyncProvider<TBlock>>,
 	request_receiver: async_channel::Receiver<IncomingRequest>,
+	version: u8,
 }
 
 impl<TBlock: BlockT> RequestHandler<TBlock> {
-	/// Create a new [`RequestHandler`].
+	/// Create a new [`RequestHandler`] of warp proof protocol version 1.
 	pub fn new<Hash: AsRef<[u8]>, N: NetworkBackend<TBlock, <TBlock as BlockT>::Hash>>(
 		protocol_id: ProtocolId,
 		genesis_hash: Hash,
 		fork_id: Option<&str>,
 		backend: Arc<dyn WarpSyncProvider<TBlock>>,
+	) -> (Self, N::RequestResponseProtocolConfig) {
+		Self::new_with_version::<_, N>(protocol_id, genesis_hash, fork_id, backend, 1)
+	}
+
+	/// Create a new [`RequestHandler`] of warp proof protocol `version`.
+	pub fn new_with_version<
+		Hash: AsRef<[u8]>,
+		N: NetworkBackend<TBlock, <TBlock as BlockT>::Hash>,
+	>(
+		protocol_id: ProtocolId,
+		genesis_hash: Hash,
+		fork_id: Option<&str>,
+		backend: Arc<dyn WarpSyncProvider<TBlock>>,
+		version: u8,
 	) -> (Self, N::RequestResponseProtocolConfig) {
 		let (tx, request_receiver) = async_channel::bounded(MAX_WARP_REQUEST_QUEUE);
 
-		let request_response_config = generate_request_response_config::<_, TBlock, N>(
+		let request_response_config = generate_versioned_request_response_config::<_, TBlock, N>(
 			protocol_id,
 			genesis_hash,
 			fork_id,
+			version,
 			tx,
 		);
 
-		(Self { backend, request_receiver }, request_response_config)
+		(Self { backend, request_receiver, version }, request_response_config)
 	}
 
 	fn handle_request(
@@ -110,7 +170,7 @@ impl<TBlock: BlockT> RequestHandler<TBlock> {
 
 		let EncodedProof(proof) = self
 			.backend
-			.generate(request.begin)
+			.generate_for_version(request.begin, self.version)
 			.map_err(HandleRequestError::InvalidRequest)?;
 
 		pending_response
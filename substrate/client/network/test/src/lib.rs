@@ -67,7 +67,8 @@ use sc_network_sync::{
 	service::{network::NetworkServiceProvider, syncing_service::SyncingService},
 	state_request_handler::StateRequestHandler,
 	strategy::warp::{
-		AuthorityList, EncodedProof, SetId, VerificationResult, WarpSyncConfig, WarpSyncProvider,
+		AuthorityList, EncodedProof, SetId, VerificationResult, WarpProofProtocolNames,
+		WarpSyncConfig, WarpSyncProvider, WARP_PROOF_PROTOCOL_VERSION,
 	},
 	warp_request_handler,
 };
@@ -870,21 +871,34 @@ pub trait TestNetFactory: Default + Sized + Send {
 			_ => WarpSyncConfig::WithProvider(warp_sync.clone()),
 		};
 
+		let genesis_hash = client
+			.block_hash(0u32.into())
+			.ok()
+			.flatten()
+			.expect("Genesis block exists; qed");
 		let warp_protocol_config = {
 			let (handler, protocol_config) =
 				warp_request_handler::RequestHandler::new::<_, NetworkWorker<_, _>>(
 					protocol_id.clone(),
-					client
-						.block_hash(0u32.into())
-						.ok()
-						.flatten()
-						.expect("Genesis block exists; qed"),
+					genesis_hash,
 					None,
 					warp_sync.clone(),
 				);
 			self.spawn_task(handler.run().boxed());
 			protocol_config
 		};
+		let latest_warp_protocol_config = {
+			let (handler, protocol_config) =
+				warp_request_handler::RequestHandler::new_with_version::<_, NetworkWorker<_, _>>(
+					protocol_id.clone(),
+					genesis_hash,
+					None,
+					warp_sync.clone(),
+					WARP_PROOF_PROTOCOL_VERSION,
+				);
+			self.spawn_task(handler.run().boxed());
+			protocol_config
+		};
 
 		let peer_store = PeerStore::new(
 			network_config
@@ -920,7 +934,10 @@ pub trait TestNetFactory: Default + Sized + Send {
 				import_queue.service(),
 				block_relay_params.downloader,
 				state_request_protocol_config.name.clone(),
-				Some(warp_protocol_config.name.clone()),
+				Some(WarpProofProtocolNames {
+					latest: latest_warp_protocol_config.name.clone(),
+					fallback: warp_protocol_config.name.clone(),
+				}),
 				peer_store_handle.clone(),
 			)
 			.unwrap();
@@ -935,6 +952,7 @@ pub trait TestNetFactory: Default + Sized + Send {
 			state_request_protocol_config,
 			light_client_request_protocol_config,
 			warp_protocol_config,
+			latest_warp_protocol_config,
 		] {
 			full_net_config.add_request_response_protocol(config);
 		}
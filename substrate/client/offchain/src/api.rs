@@ -217,6 +217,96 @@ impl AsyncApi {
 	}
 }
 
+/// Offchain externalities performing real HTTP requests and nothing else.
+///
+/// Meant for tests that record HTTP traffic against a local stand-in, for instance by wrapping
+/// it into [`sp_core::offchain::testing::RecordingOffchainExt`]. The returned future has to be
+/// polled for requests to make progress. The node is never a validator, has no network state
+/// and ignores authorized nodes.
+pub struct HttpOffchainExt {
+	http: http::HttpApi,
+}
+
+impl HttpOffchainExt {
+	/// Create the externalities and the future processing their requests.
+	pub fn new(shared_http_client: SharedClient) -> (Self, impl Future<Output = ()>) {
+		let (http, http_worker) = http::http(shared_http_client);
+		(Self { http }, http_worker)
+	}
+}
+
+impl offchain::Externalities for HttpOffchainExt {
+	fn is_validator(&self) -> bool {
+		false
+	}
+
+	fn network_state(&self) -> Result<OpaqueNetworkState, ()> {
+		Err(())
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		timestamp::now()
+	}
+
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		sleep(timestamp::timestamp_from_now(deadline));
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		rand::random()
+	}
+
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		_meta: &[u8],
+	) -> Result<HttpRequestId, ()> {
+		self.http.request_start(method, uri)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: HttpRequestId,
+		name: &str,
+		value: &str,
+	) -> Result<(), ()> {
+		self.http.request_add_header(request_id, name, value)
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: HttpRequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		self.http.request_write_body(request_id, chunk, deadline)
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[HttpRequestId],
+		deadline: Option<Timestamp>,
+	) -> Vec<HttpRequestStatus> {
+		self.http.response_wait(ids, deadline)
+	}
+
+	fn http_response_headers(&mut self, request_id: HttpRequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		self.http.response_headers(request_id)
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: HttpRequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		self.http.response_read_body(request_id, buffer, deadline)
+	}
+
+	fn set_authorized_nodes(&mut self, _nodes: Vec<OpaquePeerId>, _authorized_only: bool) {}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -448,4 +538,67 @@ mod tests {
 		// then
 		assert_ne!(seed, [0; 32]);
 	}
+
+	#[test]
+	fn http_offchain_ext_exchanges_can_be_recorded_and_replayed() {
+		use sp_core::offchain::{
+			testing::{RecordingOffchainExt, TestOffchainExt},
+			Duration,
+		};
+
+		fn post(ext: &mut impl Externalities, uri: &str) -> (HttpRequestStatus, Vec<u8>) {
+			let deadline = timestamp::now().add(Duration::from_millis(10_000));
+			let id = ext.http_request_start("POST", uri, &[]).unwrap();
+			ext.http_request_write_body(id, b"ping", Some(deadline)).unwrap();
+			ext.http_request_write_body(id, &[], Some(deadline)).unwrap();
+			let status = ext.http_response_wait(&[id], Some(deadline))[0];
+			let mut response = Vec::new();
+			let mut buf = [0; 2];
+			loop {
+				match ext.http_response_read_body(id, &mut buf, Some(deadline)).unwrap() {
+					0 => break (status, response),
+					n => response.extend_from_slice(&buf[..n]),
+				}
+			}
+		}
+
+		// given
+		let (ext, worker) = HttpOffchainExt::new(SharedClient::new());
+		let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+		std::thread::spawn(move || {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			let worker = rt.spawn(worker);
+			let server = rt.spawn(async move {
+				// Echoes the request body back.
+				let server = hyper::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(
+					hyper::service::make_service_fn(|_| async move {
+						Ok::<_, std::convert::Infallible>(hyper::service::service_fn(
+							|req: hyper::Request<hyper::Body>| async move {
+								let body = hyper::body::to_bytes(req.into_body()).await?;
+								Ok::<_, hyper::Error>(hyper::Response::new(hyper::Body::from(body)))
+							},
+						))
+					}),
+				);
+				let _ = addr_tx.send(server.local_addr());
+				server.await.map_err(drop)
+			});
+			let _ = rt.block_on(futures::future::join(worker, server));
+		});
+		let uri = format!("http://{}", addr_rx.recv().unwrap());
+		let (mut recorder, fixture) = RecordingOffchainExt::new(ext);
+
+		// when
+		let recorded = post(&mut recorder, &uri);
+
+		// then
+		assert_eq!(recorded, (HttpRequestStatus::Finished(200), b"ping".to_vec()));
+		let fixture = fixture.read().clone();
+		assert_eq!(fixture.exchanges.len(), 1);
+		assert_eq!(fixture.exchanges[0].body, b"ping".to_vec());
+
+		let (mut replayed, state) = TestOffchainExt::new();
+		state.write().replay(fixture);
+		assert_eq!(post(&mut replayed, &uri), recorded);
+	}
 }
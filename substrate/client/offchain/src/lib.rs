@@ -54,6 +54,7 @@ use threadpool::ThreadPool;
 
 mod api;
 
+pub use api::{HttpOffchainExt, SharedClient};
 pub use sp_core::offchain::storage::OffchainDb;
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
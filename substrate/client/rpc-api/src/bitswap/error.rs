@@ -0,0 +1,60 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bitswap RPC module errors.
+
+use jsonrpsee::types::error::{ErrorObject, ErrorObjectOwned};
+
+/// Bitswap RPC errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// CID couldn't be parsed.
+	#[error("Invalid CID: {0}")]
+	InvalidCid(String),
+	/// CID version or hash function not supported.
+	#[error("Unsupported CID")]
+	UnsupportedCid,
+	/// None of the connected peers returned the content.
+	#[error("Content not found")]
+	NotFound,
+	/// None of the connected peers answered.
+	#[error("No peer answered")]
+	NoResponse,
+	/// The network backend of the node can't fetch content over bitswap while serving it.
+	#[error("Bitswap requests are not supported by the network backend")]
+	Unsupported,
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] crate::policy::UnsafeRpcError),
+}
+
+/// Base code for all bitswap errors.
+const BASE_ERROR: i32 = crate::error::base::BITSWAP;
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(e: Error) -> Self {
+		match e {
+			Error::InvalidCid(_) => ErrorObject::owned(BASE_ERROR + 1, e.to_string(), None::<()>),
+			Error::UnsupportedCid => ErrorObject::owned(BASE_ERROR + 2, e.to_string(), None::<()>),
+			Error::NotFound => ErrorObject::owned(BASE_ERROR + 3, e.to_string(), None::<()>),
+			Error::NoResponse => ErrorObject::owned(BASE_ERROR + 4, e.to_string(), None::<()>),
+			Error::Unsupported => ErrorObject::owned(BASE_ERROR + 5, e.to_string(), None::<()>),
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
@@ -28,6 +28,7 @@ mod policy;
 pub use policy::{check_if_safe, DenyUnsafe, UnsafeRpcError};
 
 pub mod author;
+pub mod bitswap;
 pub mod chain;
 pub mod child_state;
 pub mod dev;
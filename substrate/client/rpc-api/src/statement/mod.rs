@@ -19,10 +19,21 @@
 //! Substrate Statement Store RPC API.
 
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 
 pub mod error;
 
+/// Filter for the topics of the statements pushed by `statement_subscribeStatement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TopicFilter {
+	/// Match statements which include all of the given topics.
+	MatchAll(Vec<[u8; 32]>),
+	/// Match statements which include any of the given topics.
+	MatchAny(Vec<[u8; 32]>),
+}
+
 /// Substrate statement RPC API
 #[rpc(client, server)]
 pub trait StatementApi {
@@ -57,4 +68,16 @@ pub trait StatementApi {
 	/// Remove a statement from the store.
 	#[method(name = "statement_remove")]
 	fn remove(&self, statement_hash: [u8; 32]) -> RpcResult<()>;
+
+	/// Subscribe to the statements newly accepted by the store, SCALE-encoded.
+	///
+	/// Only statements matching `topic_filter` are pushed. If `dest` is given, only statements
+	/// whose decryption key is `dest` are pushed, otherwise only statements without a decryption
+	/// key are pushed.
+	#[subscription(
+		name = "statement_subscribeStatement" => "statement_statement",
+		unsubscribe = "statement_unsubscribeStatement",
+		item = Bytes,
+	)]
+	fn subscribe_statement(&self, topic_filter: TopicFilter, dest: Option<[u8; 32]>);
 }
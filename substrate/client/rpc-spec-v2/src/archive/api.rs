@@ -19,7 +19,10 @@
 //! API trait of the archive methods.
 
 use crate::{
-	common::events::{ArchiveStorageResult, PaginatedStorageQuery},
+	common::events::{
+		ArchiveFollowEvent, ArchiveStorageDiffItem, ArchiveStorageDiffResult, ArchiveStorageResult,
+		PaginatedStorageQuery,
+	},
 	MethodResult,
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
@@ -104,4 +107,36 @@ pub trait ArchiveApi<Hash> {
 		items: Vec<PaginatedStorageQuery<String>>,
 		child_trie: Option<String>,
 	) -> RpcResult<ArchiveStorageResult>;
+
+	/// Returns the storage differences of the given items between two blocks.
+	///
+	/// The differences of every item key and of all its descendants are reported as added,
+	/// modified or deleted keys of the block `hash` compared to the block `previous_hash`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[method(name = "archive_unstable_storageDiff", blocking)]
+	fn archive_unstable_storage_diff(
+		&self,
+		hash: Hash,
+		previous_hash: Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+	) -> RpcResult<ArchiveStorageDiffResult>;
+
+	/// Follows the blocks imported and finalized by the node.
+	///
+	/// The first event reports the finalized block the subscription starts from. Blocks are not
+	/// pinned, since the node keeps all of them. The subscription is stopped if the client does
+	/// not keep up with the events.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "archive_unstable_follow" => "archive_unstable_followEvent",
+		unsubscribe = "archive_unstable_unfollow",
+		item = ArchiveFollowEvent<Hash>,
+	)]
+	fn archive_unstable_follow(&self);
 }
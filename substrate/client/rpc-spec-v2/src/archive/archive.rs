@@ -20,16 +20,25 @@
 
 use crate::{
 	archive::{error::Error as ArchiveError, ArchiveApiServer},
-	common::events::{ArchiveStorageResult, PaginatedStorageQuery},
-	hex_string, MethodResult,
+	common::events::{
+		ArchiveFollowEvent, ArchiveFollowFinalized, ArchiveFollowInitialized,
+		ArchiveFollowNewBlock, ArchiveStorageDiffItem, ArchiveStorageDiffResult,
+		ArchiveStorageResult, PaginatedStorageQuery,
+	},
+	hex_string, MethodResult, SubscriptionTaskExecutor,
 };
 
 use codec::Encode;
-use jsonrpsee::core::{async_trait, RpcResult};
+use futures::{future, stream, StreamExt};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	PendingSubscriptionSink,
+};
 use sc_client_api::{
 	Backend, BlockBackend, BlockchainEvents, CallExecutor, ChildInfo, ExecutorProvider, StorageKey,
 	StorageProvider,
 };
+use sc_rpc::utils::{BoundedVecDeque, PendingSubscription};
 use sp_api::{CallApiAt, CallContext};
 use sp_blockchain::{
 	Backend as BlockChainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
@@ -41,7 +50,7 @@ use sp_runtime::{
 };
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 
-use super::archive_storage::ArchiveStorage;
+use super::{archive_storage::ArchiveStorage, archive_storage_diff::ArchiveStorageDiff};
 
 /// The configuration of [`Archive`].
 pub struct ArchiveConfig {
@@ -50,6 +59,9 @@ pub struct ArchiveConfig {
 	pub max_descendant_responses: usize,
 	/// The maximum number of queried items allowed for the `archive_storage` at a time.
 	pub max_queried_items: usize,
+	/// The maximum number of keys the `archive_storageDiff` can iterate at a time, over all the
+	/// queried items.
+	pub max_diff_iterated_keys: usize,
 }
 
 /// The maximum number of items the `archive_storage` can return for a descendant query before
@@ -64,11 +76,21 @@ const MAX_DESCENDANT_RESPONSES: usize = 5;
 /// `MAX_DESCENDANT_RESPONSES`.
 const MAX_QUERIED_ITEMS: usize = 8;
 
+/// The maximum number of keys the `archive_storageDiff` can iterate at a time.
+///
+/// Note: Identical subtries are skipped without iterating their keys.
+const MAX_DIFF_ITERATED_KEYS: usize = 256;
+
+/// The maximum number of events buffered for an `archive_unstable_follow` subscription, before the
+/// subscription is stopped.
+const MAX_FOLLOW_BUFFERED_EVENTS: usize = 512;
+
 impl Default for ArchiveConfig {
 	fn default() -> Self {
 		Self {
 			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
 			max_queried_items: MAX_QUERIED_ITEMS,
+			max_diff_iterated_keys: MAX_DIFF_ITERATED_KEYS,
 		}
 	}
 }
@@ -79,6 +101,8 @@ pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
 	client: Arc<Client>,
 	/// Backend of the chain.
 	backend: Arc<BE>,
+	/// Executor to spawn subscriptions.
+	executor: SubscriptionTaskExecutor,
 	/// The hexadecimal encoded hash of the genesis block.
 	genesis_hash: String,
 	/// The maximum number of items the `archive_storage` can return for a descendant query before
@@ -86,6 +110,8 @@ pub struct Archive<BE: Backend<Block>, Block: BlockT, Client> {
 	storage_max_descendant_responses: usize,
 	/// The maximum number of queried items allowed for the `archive_storage` at a time.
 	storage_max_queried_items: usize,
+	/// The maximum number of keys the `archive_storageDiff` can iterate at a time.
+	storage_max_diff_iterated_keys: usize,
 	/// Phantom member to pin the block type.
 	_phantom: PhantomData<Block>,
 }
@@ -95,6 +121,7 @@ impl<BE: Backend<Block>, Block: BlockT, Client> Archive<BE, Block, Client> {
 	pub fn new<GenesisHash: AsRef<[u8]>>(
 		client: Arc<Client>,
 		backend: Arc<BE>,
+		executor: SubscriptionTaskExecutor,
 		genesis_hash: GenesisHash,
 		config: ArchiveConfig,
 	) -> Self {
@@ -102,9 +129,11 @@ impl<BE: Backend<Block>, Block: BlockT, Client> Archive<BE, Block, Client> {
 		Self {
 			client,
 			backend,
+			executor,
 			genesis_hash,
 			storage_max_descendant_responses: config.max_descendant_responses,
 			storage_max_queried_items: config.max_queried_items,
+			storage_max_diff_iterated_keys: config.max_diff_iterated_keys,
 			_phantom: PhantomData,
 		}
 	}
@@ -277,4 +306,76 @@ where
 		);
 		Ok(storage_client.handle_query(hash, items, child_trie))
 	}
+
+	fn archive_unstable_storage_diff(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		items: Vec<ArchiveStorageDiffItem<String>>,
+	) -> RpcResult<ArchiveStorageDiffResult> {
+		let items = items
+			.into_iter()
+			.map(|item| {
+				Ok(ArchiveStorageDiffItem {
+					key: StorageKey(parse_hex_param(item.key)?),
+					return_type: item.return_type,
+					child_trie_key: item
+						.child_trie_key
+						.map(|key| parse_hex_param(key).map(StorageKey))
+						.transpose()?,
+					pagination_start_key: item
+						.pagination_start_key
+						.map(|key| parse_hex_param(key).map(StorageKey))
+						.transpose()?,
+				})
+			})
+			.collect::<Result<Vec<_>, ArchiveError>>()?;
+
+		let storage_diff = ArchiveStorageDiff::new(
+			self.client.clone(),
+			self.storage_max_descendant_responses,
+			self.storage_max_queried_items,
+			self.storage_max_diff_iterated_keys,
+		);
+		Ok(storage_diff.handle_query(hash, previous_hash, items))
+	}
+
+	fn archive_unstable_follow(&self, pending: PendingSubscriptionSink) {
+		// Subscribe before reading the finalized block, so no block is missed in between.
+		let new_blocks = self.client.import_notification_stream().map(|notification| {
+			ArchiveFollowEvent::NewBlock(ArchiveFollowNewBlock {
+				block_hash: notification.hash,
+				parent_block_hash: *notification.header.parent_hash(),
+				block_number: (*notification.header.number()).saturated_into(),
+			})
+		});
+		let finalized_blocks = self.client.finality_notification_stream().map(|notification| {
+			ArchiveFollowEvent::Finalized(ArchiveFollowFinalized {
+				finalized_block_hashes: notification
+					.tree_route
+					.iter()
+					.copied()
+					.chain(std::iter::once(notification.hash))
+					.collect(),
+			})
+		});
+		let info = self.client.info();
+		let initialized = ArchiveFollowEvent::Initialized(ArchiveFollowInitialized {
+			finalized_block_hash: info.finalized_hash,
+			finalized_block_number: info.finalized_number.saturated_into(),
+		});
+
+		let stream = stream::once(future::ready(initialized))
+			.chain(stream::select(new_blocks, finalized_blocks))
+			.boxed();
+		let fut = async move {
+			// A client which doesn't keep up gets its subscription stopped, rather than missing
+			// blocks.
+			PendingSubscription::from(pending)
+				.pipe_from_stream(stream, BoundedVecDeque::new(MAX_FOLLOW_BUFFERED_EVENTS))
+				.await;
+		};
+
+		sc_rpc::utils::spawn_subscription_task(&self.executor, fut);
+	}
 }
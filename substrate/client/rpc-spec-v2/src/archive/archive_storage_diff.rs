@@ -0,0 +1,307 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `archive_storageDiff` method.
+//!
+//! The two tries are descended together: the subtries whose closest descendant merkle values
+//! are identical in both blocks are skipped, so the work done is proportional to the number of
+//! differences rather than to the number of keys under the queried prefix.
+
+use std::{cmp, sync::Arc};
+
+use sc_client_api::{Backend, ChildInfo, StorageKey, StorageProvider};
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{
+	common::{
+		events::{
+			ArchiveStorageDiffItem, ArchiveStorageDiffOperationType, ArchiveStorageDiffResult,
+			ArchiveStorageDiffResultItem, ArchiveStorageDiffType,
+		},
+		storage::Storage,
+	},
+	hex_string,
+};
+
+/// Generates the events of the `archive_storageDiff` method.
+pub struct ArchiveStorageDiff<Client, Block, BE> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Storage client.
+	storage: Storage<Client, Block, BE>,
+	/// The maximum number of differences the API can return for an item at a time.
+	storage_max_descendant_responses: usize,
+	/// The maximum number of queried items allowed for the `archive_storageDiff` at a time.
+	storage_max_queried_items: usize,
+	/// The maximum number of keys the `archive_storageDiff` can iterate at a time.
+	storage_max_iterated_keys: usize,
+}
+
+impl<Client, Block, BE> ArchiveStorageDiff<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorageDiff`].
+	pub fn new(
+		client: Arc<Client>,
+		storage_max_descendant_responses: usize,
+		storage_max_queried_items: usize,
+		storage_max_iterated_keys: usize,
+	) -> Self {
+		Self {
+			storage: Storage::new(client.clone()),
+			client,
+			storage_max_descendant_responses,
+			storage_max_queried_items,
+			storage_max_iterated_keys,
+		}
+	}
+}
+
+/// The position of the iteration over the keys of an item.
+enum Cursor {
+	/// The iteration resumes at the given key, included.
+	From(StorageKey),
+	/// The iteration resumes after the given key.
+	After(StorageKey),
+}
+
+impl<Client, Block, BE> ArchiveStorageDiff<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + 'static,
+{
+	/// Generate the response of the `archive_storageDiff` method.
+	///
+	/// For every item, at most `storage_max_descendant_responses` differences are reported, in
+	/// the lexicographic order of the keys, and at most `storage_max_iterated_keys` keys are
+	/// iterated over all the items. The items which are not fully iterated are returned with the
+	/// pagination start key to resume them from.
+	pub fn handle_query(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		mut items: Vec<ArchiveStorageDiffItem<StorageKey>>,
+	) -> ArchiveStorageDiffResult {
+		let discarded_items = items.len().saturating_sub(self.storage_max_queried_items);
+		items.truncate(self.storage_max_queried_items);
+
+		let mut remaining_keys = self.storage_max_iterated_keys;
+		let mut diff_results = Vec::new();
+		let mut paginated_items = Vec::new();
+		for item in items {
+			match self.handle_item(hash, previous_hash, item, &mut remaining_keys) {
+				Ok((results, paginated_item)) => {
+					diff_results.extend(results);
+					paginated_items.extend(paginated_item.map(|item| ArchiveStorageDiffItem {
+						key: hex_string(&item.key.0),
+						return_type: item.return_type,
+						child_trie_key: item.child_trie_key.map(|key| hex_string(&key.0)),
+						pagination_start_key:
+							item.pagination_start_key.map(|key| hex_string(&key.0)),
+					}));
+				},
+				Err(error) => return ArchiveStorageDiffResult::err(error),
+			}
+		}
+
+		ArchiveStorageDiffResult::ok(diff_results, discarded_items, paginated_items)
+	}
+
+	/// Compute the differences of the key and its descendants between the two blocks.
+	///
+	/// Returns the differences, and the item to query to resume the iteration if it stopped
+	/// before all the differences were reported.
+	fn handle_item(
+		&self,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		item: ArchiveStorageDiffItem<StorageKey>,
+		remaining_keys: &mut usize,
+	) -> Result<
+		(Vec<ArchiveStorageDiffResultItem>, Option<ArchiveStorageDiffItem<StorageKey>>),
+		String,
+	> {
+		let child_info = item.child_trie_key.as_ref().map(|key| ChildInfo::new_default(&key.0));
+		let child_info = child_info.as_ref();
+
+		// The closest descendant merkle values are identical if and only if the subtries
+		// are identical, in which case there is no difference to report.
+		let merkle_value = |at: Block::Hash, key: &StorageKey| {
+			match child_info {
+				Some(child_info) => self.client.child_closest_merkle_value(at, child_info, key),
+				None => self.client.closest_merkle_value(at, key),
+			}
+			.map_err(|error| error.to_string())
+		};
+		let differs = |key: &StorageKey| -> Result<bool, String> {
+			Ok(merkle_value(hash, key)? != merkle_value(previous_hash, key)?)
+		};
+		if !differs(&item.key)? {
+			return Ok((Vec::new(), None))
+		}
+
+		let child_trie_key = item.child_trie_key.as_ref().map(|key| hex_string(&key.0));
+		let mut results = Vec::new();
+		let mut cursor = match &item.pagination_start_key {
+			Some(key) => Cursor::After(key.clone()),
+			None => Cursor::From(item.key.clone()),
+		};
+		// The key reported to the caller to resume the iteration after.
+		let mut resume_key = item.pagination_start_key.clone();
+		loop {
+			if results.len() >= self.storage_max_descendant_responses || *remaining_keys == 0 {
+				let paginated_item =
+					ArchiveStorageDiffItem { pagination_start_key: resume_key, ..item };
+				return Ok((results, Some(paginated_item)))
+			}
+			*remaining_keys -= 1;
+
+			let key = match (
+				self.next_key(hash, &item.key, &cursor, child_info)?,
+				self.next_key(previous_hash, &item.key, &cursor, child_info)?,
+			) {
+				(Some(key), Some(previous_key)) => cmp::min(key, previous_key),
+				(Some(key), None) | (None, Some(key)) => key,
+				(None, None) => return Ok((results, None)),
+			};
+
+			// Skip the largest subtrie holding the key which is identical in both blocks.
+			if let Some(prefix) = identical_prefix(&item.key, &key, &differs)? {
+				match next_prefix(&prefix).filter(|next| next.0.starts_with(&item.key.0)) {
+					Some(next) => cursor = Cursor::From(next),
+					None => return Ok((results, None)),
+				}
+				// Resuming after the prefix iterates the subtrie again, but skips it right away.
+				resume_key = Some(prefix);
+				continue
+			}
+
+			cursor = Cursor::After(key.clone());
+			resume_key = Some(key.clone());
+			let operation_type = match (
+				self.storage_hash(hash, &key, child_info)?,
+				self.storage_hash(previous_hash, &key, child_info)?,
+			) {
+				(Some(value_hash), Some(previous_value_hash))
+					if value_hash == previous_value_hash =>
+					continue,
+				(Some(_), Some(_)) => ArchiveStorageDiffOperationType::Modified,
+				(Some(_), None) => ArchiveStorageDiffOperationType::Added,
+				(None, Some(_)) => ArchiveStorageDiffOperationType::Deleted,
+				(None, None) => continue,
+			};
+
+			let at = match operation_type {
+				ArchiveStorageDiffOperationType::Deleted => previous_hash,
+				_ => hash,
+			};
+			let result = match item.return_type {
+				ArchiveStorageDiffType::Value => self.storage.query_value(at, &key, child_info),
+				ArchiveStorageDiffType::Hash => self.storage.query_hash(at, &key, child_info),
+			};
+			if let Some(result) = result? {
+				results.push(ArchiveStorageDiffResultItem {
+					key: result.key,
+					result: result.result,
+					operation_type,
+					child_trie_key: child_trie_key.clone(),
+				});
+			}
+		}
+	}
+
+	/// Returns the first key under `prefix` at the cursor.
+	fn next_key(
+		&self,
+		at: Block::Hash,
+		prefix: &StorageKey,
+		cursor: &Cursor,
+		child_info: Option<&ChildInfo>,
+	) -> Result<Option<StorageKey>, String> {
+		let start_key = match cursor {
+			Cursor::From(key) if self.storage_hash(at, key, child_info)?.is_some() =>
+				return Ok(Some(key.clone())),
+			Cursor::From(key) | Cursor::After(key) => key,
+		};
+
+		let mut keys = match child_info {
+			Some(child_info) => self.client.child_storage_keys(
+				at,
+				child_info.clone(),
+				Some(prefix),
+				Some(start_key),
+			),
+			None => self.client.storage_keys(at, Some(prefix), Some(start_key)),
+		}
+		.map_err(|error| error.to_string())?;
+		Ok(keys.next())
+	}
+
+	/// Fetch the hash of the value of the key.
+	fn storage_hash(
+		&self,
+		at: Block::Hash,
+		key: &StorageKey,
+		child_info: Option<&ChildInfo>,
+	) -> Result<Option<Block::Hash>, String> {
+		match child_info {
+			Some(child_info) => self.client.child_storage_hash(at, child_info, key),
+			None => self.client.storage_hash(at, key),
+		}
+		.map_err(|error| error.to_string())
+	}
+}
+
+/// Returns the shortest prefix of `key`, longer than `item_key`, whose subtrie is identical in
+/// both blocks.
+///
+/// Once a prefix of `key` has an identical subtrie, all the longer prefixes have too, so the
+/// prefixes are binary searched.
+fn identical_prefix(
+	item_key: &StorageKey,
+	key: &StorageKey,
+	differs: impl Fn(&StorageKey) -> Result<bool, String>,
+) -> Result<Option<StorageKey>, String> {
+	let (mut low, mut high) = (item_key.0.len() + 1, key.0.len());
+	if low > high || differs(key)? {
+		return Ok(None)
+	}
+
+	// The subtrie at `key[..high]` is identical, and the ones at shorter prefixes than `low` are
+	// not.
+	while low < high {
+		let middle = low + (high - low) / 2;
+		if differs(&StorageKey(key.0[..middle].to_vec()))? {
+			low = middle + 1;
+		} else {
+			high = middle;
+		}
+	}
+	Ok(Some(StorageKey(key.0[..high].to_vec())))
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`, if any.
+fn next_prefix(prefix: &StorageKey) -> Option<StorageKey> {
+	let mut next = prefix.0.clone();
+	while let Some(last) = next.pop() {
+		if last != u8::MAX {
+			next.push(last + 1);
+			return Some(StorageKey(next))
+		}
+	}
+	None
+}
@@ -26,6 +26,7 @@
 mod tests;
 
 mod archive_storage;
+mod archive_storage_diff;
 
 pub mod api;
 pub mod archive;
@@ -18,8 +18,11 @@
 
 use crate::{
 	common::events::{
-		ArchiveStorageMethodOk, ArchiveStorageResult, PaginatedStorageQuery, StorageQueryType,
-		StorageResultType,
+		ArchiveFollowEvent, ArchiveFollowFinalized, ArchiveFollowInitialized,
+		ArchiveFollowNewBlock, ArchiveStorageDiffItem, ArchiveStorageDiffMethodOk,
+		ArchiveStorageDiffOperationType, ArchiveStorageDiffResult, ArchiveStorageDiffResultItem,
+		ArchiveStorageDiffType, ArchiveStorageMethodOk, ArchiveStorageResult,
+		PaginatedStorageQuery, StorageQueryType, StorageResultType,
 	},
 	hex_string, MethodResult,
 };
@@ -33,9 +36,11 @@ use assert_matches::assert_matches;
 use codec::{Decode, Encode};
 use jsonrpsee::{
 	core::EmptyServerParams as EmptyParams, rpc_params, MethodsError as Error, RpcModule,
+	Subscription as RpcSubscription,
 };
 use sc_block_builder::BlockBuilderBuilder;
-use sc_client_api::ChildInfo;
+use sc_client_api::{ChildInfo, Finalizer};
+use sc_rpc::testing::TaskExecutor;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
 use sp_core::{Blake2Hasher, Hasher};
@@ -46,7 +51,7 @@ use sp_runtime::{
 use std::{collections::HashMap, sync::Arc};
 use substrate_test_runtime::Transfer;
 use substrate_test_runtime_client::{
-	prelude::*, runtime, Backend, BlockBuilderExt, Client, ClientBlockImportExt,
+	prelude::*, runtime, Backend, BlockBuilderExt, BlocksPruning, Client, ClientBlockImportExt,
 };
 
 const CHAIN_GENESIS: [u8; 32] = [0; 32];
@@ -64,21 +69,48 @@ type Block = substrate_test_runtime_client::runtime::Block;
 fn setup_api(
 	max_descendant_responses: usize,
 	max_queried_items: usize,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	setup_api_with_builder(
+		TestClientBuilder::new(),
+		ArchiveConfig { max_descendant_responses, max_queried_items, ..Default::default() },
+	)
+}
+
+/// Setup the API with a backend keeping the state of all the blocks.
+fn setup_archive_api(
+	max_descendant_responses: usize,
+	max_queried_items: usize,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	setup_archive_api_with_config(ArchiveConfig {
+		max_descendant_responses,
+		max_queried_items,
+		..Default::default()
+	})
+}
+
+/// Setup the API with a backend keeping the state of all the blocks, and the given config.
+fn setup_archive_api_with_config(
+	config: ArchiveConfig,
+) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let backend = Arc::new(Backend::new_test_with_tx_storage(BlocksPruning::KeepAll, 0));
+	setup_api_with_builder(TestClientBuilder::with_backend(backend), config)
+}
+
+fn setup_api_with_builder(
+	builder: TestClientBuilder<ExecutorDispatch, Backend>,
+	config: ArchiveConfig,
 ) -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
 	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
-	let builder = TestClientBuilder::new().add_extra_child_storage(
-		&child_info,
-		KEY.to_vec(),
-		CHILD_VALUE.to_vec(),
-	);
+	let builder = builder.add_extra_child_storage(&child_info, KEY.to_vec(), CHILD_VALUE.to_vec());
 	let backend = builder.backend();
 	let client = Arc::new(builder.build());
 
 	let api = Archive::new(
 		client.clone(),
 		backend,
+		Arc::new(TaskExecutor::default()),
 		CHAIN_GENESIS,
-		ArchiveConfig { max_descendant_responses, max_queried_items },
+		config,
 	)
 	.into_rpc();
 
@@ -838,3 +870,392 @@ async fn archive_storage_discarded_items() {
 		_ => panic!("Unexpected result"),
 	};
 }
+
+/// Import two blocks on top of genesis, modifying the storage under the `KEY` prefix.
+///
+/// Returns the hashes of the imported blocks.
+async fn import_storage_diff_blocks(
+	client: &Arc<Client<Backend>>,
+) -> (runtime::Hash, runtime::Hash) {
+	let mut builder = BlockBuilderBuilder::new(&**client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	builder.push_storage_change(b":mock_a".to_vec(), Some(b"a".to_vec())).unwrap();
+	let block_1 = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(&**client)
+		.on_parent_block(block_1.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(b"new".to_vec())).unwrap();
+	builder.push_storage_change(b":mock_a".to_vec(), None).unwrap();
+	builder.push_storage_change(b":mock_b".to_vec(), Some(b"b".to_vec())).unwrap();
+	let block_2 = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	(block_1.hash(), block_2.hash())
+}
+
+#[tokio::test]
+async fn archive_storage_diff_main_trie() {
+	let (client, api) = setup_archive_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+	let (block_1, block_2) = import_storage_diff_blocks(&client).await;
+
+	let items = vec![
+		ArchiveStorageDiffItem {
+			key: hex_string(&KEY),
+			return_type: ArchiveStorageDiffType::Value,
+			child_trie_key: None,
+			pagination_start_key: None,
+		},
+		ArchiveStorageDiffItem {
+			key: hex_string(b":mock_a"),
+			return_type: ArchiveStorageDiffType::Hash,
+			child_trie_key: None,
+			pagination_start_key: None,
+		},
+	];
+	let result: ArchiveStorageDiffResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![format!("{:?}", block_2), format!("{:?}", block_1), items.clone()],
+		)
+		.await
+		.unwrap();
+
+	match result {
+		ArchiveStorageDiffResult::Ok(ArchiveStorageDiffMethodOk {
+			result,
+			discarded_items,
+			paginated_items,
+		}) => {
+			assert_eq!(discarded_items, 0);
+			assert!(paginated_items.is_empty());
+			assert_eq!(
+				result,
+				vec![
+					ArchiveStorageDiffResultItem {
+						key: hex_string(&KEY),
+						result: StorageResultType::Value(hex_string(b"new")),
+						operation_type: ArchiveStorageDiffOperationType::Modified,
+						child_trie_key: None,
+					},
+					ArchiveStorageDiffResultItem {
+						key: hex_string(b":mock_a"),
+						result: StorageResultType::Value(hex_string(b"a")),
+						operation_type: ArchiveStorageDiffOperationType::Deleted,
+						child_trie_key: None,
+					},
+					ArchiveStorageDiffResultItem {
+						key: hex_string(b":mock_b"),
+						result: StorageResultType::Value(hex_string(b"b")),
+						operation_type: ArchiveStorageDiffOperationType::Added,
+						child_trie_key: None,
+					},
+					// The deleted key is reported with the hash of its previous value.
+					ArchiveStorageDiffResultItem {
+						key: hex_string(b":mock_a"),
+						result: StorageResultType::Hash(format!("{:?}", Blake2Hasher::hash(b"a"))),
+						operation_type: ArchiveStorageDiffOperationType::Deleted,
+						child_trie_key: None,
+					},
+				]
+			);
+		},
+		_ => panic!("Unexpected result"),
+	};
+
+	// There is no difference between a block and itself.
+	let result: ArchiveStorageDiffResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![format!("{:?}", block_2), format!("{:?}", block_2), items],
+		)
+		.await
+		.unwrap();
+	assert_eq!(result, ArchiveStorageDiffResult::ok(vec![], 0, vec![]));
+}
+
+#[tokio::test]
+async fn archive_storage_diff_paginate_iterations() {
+	let (client, api) = setup_archive_api(1, MAX_QUERIED_LIMIT);
+	let (block_1, block_2) = import_storage_diff_blocks(&client).await;
+
+	let mut pagination_start_key = None;
+	let mut reported = Vec::new();
+	loop {
+		let items = vec![ArchiveStorageDiffItem {
+			key: hex_string(&KEY),
+			return_type: ArchiveStorageDiffType::Hash,
+			child_trie_key: None,
+			pagination_start_key: pagination_start_key.clone(),
+		}];
+		let result: ArchiveStorageDiffResult = api
+			.call(
+				"archive_unstable_storageDiff",
+				rpc_params![format!("{:?}", block_2), format!("{:?}", block_1), items],
+			)
+			.await
+			.unwrap();
+
+		let ArchiveStorageDiffResult::Ok(ArchiveStorageDiffMethodOk { result, .. }) = result else {
+			panic!("Unexpected result")
+		};
+		assert!(result.len() <= 1);
+		let Some(item) = result.into_iter().next() else { break };
+		pagination_start_key = Some(item.key.clone());
+		reported.push((item.key, item.operation_type));
+	}
+
+	assert_eq!(
+		reported,
+		vec![
+			(hex_string(&KEY), ArchiveStorageDiffOperationType::Modified),
+			(hex_string(b":mock_a"), ArchiveStorageDiffOperationType::Deleted),
+			(hex_string(b":mock_b"), ArchiveStorageDiffOperationType::Added),
+		]
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_diff_child_trie() {
+	let (client, api) = setup_archive_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+	let genesis_hash = format!("{:?}", client.chain_info().genesis_hash);
+	let (_, block_2) = import_storage_diff_blocks(&client).await;
+
+	let items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b""),
+		return_type: ArchiveStorageDiffType::Value,
+		child_trie_key: Some(hex_string(&CHILD_STORAGE_KEY)),
+		pagination_start_key: None,
+	}];
+
+	// The child trie is not modified by the imported blocks.
+	let result: ArchiveStorageDiffResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![format!("{:?}", block_2), &genesis_hash, items.clone()],
+		)
+		.await
+		.unwrap();
+	assert_eq!(result, ArchiveStorageDiffResult::ok(vec![], 0, vec![]));
+
+	// Compared to an invalid block, the child trie is reported as an error.
+	let result: ArchiveStorageDiffResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![format!("{:?}", block_2), hex_string(&INVALID_HASH), items],
+		)
+		.await
+		.unwrap();
+	assert_matches!(result, ArchiveStorageDiffResult::Err(_));
+}
+
+/// Import a block on top of genesis with 64 keys under the `:skip` prefix, and a block modifying
+/// one of them.
+///
+/// Returns the hashes of the imported blocks.
+async fn import_identical_subtries_blocks(
+	client: &Arc<Client<Backend>>,
+) -> (runtime::Hash, runtime::Hash) {
+	let mut builder = BlockBuilderBuilder::new(&**client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	for i in 0..8u8 {
+		for j in 0..8u8 {
+			builder
+				.push_storage_change([b":skip", &[i, j][..]].concat(), Some(vec![i, j]))
+				.unwrap();
+		}
+	}
+	let block_1 = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+
+	let mut builder = BlockBuilderBuilder::new(&**client)
+		.on_parent_block(block_1.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap();
+	builder
+		.push_storage_change(b":skip\x05\x03".to_vec(), Some(b"new".to_vec()))
+		.unwrap();
+	let block_2 = builder.build().unwrap().block;
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	(block_1.hash(), block_2.hash())
+}
+
+#[tokio::test]
+async fn archive_storage_diff_skips_identical_subtries() {
+	// Fewer keys than the 64 keys under the prefix can be iterated.
+	let (client, api) = setup_archive_api_with_config(ArchiveConfig {
+		max_descendant_responses: MAX_PAGINATION_LIMIT,
+		max_queried_items: MAX_QUERIED_LIMIT,
+		max_diff_iterated_keys: 20,
+	});
+	let (block_1, block_2) = import_identical_subtries_blocks(&client).await;
+
+	let items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b":skip"),
+		return_type: ArchiveStorageDiffType::Value,
+		child_trie_key: None,
+		pagination_start_key: None,
+	}];
+	let result: ArchiveStorageDiffResult = api
+		.call(
+			"archive_unstable_storageDiff",
+			rpc_params![format!("{:?}", block_2), format!("{:?}", block_1), items],
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(
+		result,
+		ArchiveStorageDiffResult::ok(
+			vec![ArchiveStorageDiffResultItem {
+				key: hex_string(b":skip\x05\x03"),
+				result: StorageResultType::Value(hex_string(b"new")),
+				operation_type: ArchiveStorageDiffOperationType::Modified,
+				child_trie_key: None,
+			}],
+			0,
+			vec![],
+		)
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_diff_paginate_iterated_keys() {
+	let (client, api) = setup_archive_api_with_config(ArchiveConfig {
+		max_descendant_responses: MAX_PAGINATION_LIMIT,
+		max_queried_items: MAX_QUERIED_LIMIT,
+		max_diff_iterated_keys: 2,
+	});
+	let (block_1, block_2) = import_identical_subtries_blocks(&client).await;
+
+	let mut items = vec![ArchiveStorageDiffItem {
+		key: hex_string(b":skip"),
+		return_type: ArchiveStorageDiffType::Hash,
+		child_trie_key: None,
+		pagination_start_key: None,
+	}];
+	let mut reported = Vec::new();
+	let mut calls = 0;
+	while !items.is_empty() {
+		calls += 1;
+		assert!(calls < 64, "The iteration must make progress");
+
+		let result: ArchiveStorageDiffResult = api
+			.call(
+				"archive_unstable_storageDiff",
+				rpc_params![format!("{:?}", block_2), format!("{:?}", block_1), items],
+			)
+			.await
+			.unwrap();
+		let ArchiveStorageDiffResult::Ok(ArchiveStorageDiffMethodOk {
+			result,
+			paginated_items,
+			..
+		}) = result
+		else {
+			panic!("Unexpected result")
+		};
+		reported.extend(result.into_iter().map(|item| (item.key, item.operation_type)));
+		// The iteration resumes from the returned items.
+		items = paginated_items;
+	}
+
+	assert!(calls > 1);
+	assert_eq!(
+		reported,
+		vec![(hex_string(b":skip\x05\x03"), ArchiveStorageDiffOperationType::Modified)]
+	);
+}
+
+async fn get_next_event<T: serde::de::DeserializeOwned>(sub: &mut RpcSubscription) -> T {
+	let (event, _sub_id) = tokio::time::timeout(std::time::Duration::from_secs(60), sub.next())
+		.await
+		.unwrap()
+		.unwrap()
+		.unwrap();
+	event
+}
+
+#[tokio::test]
+async fn archive_follow_reports_new_and_finalized_blocks() {
+	let (client, api) = setup_api(MAX_PAGINATION_LIMIT, MAX_QUERIED_LIMIT);
+	let genesis_hash = client.chain_info().genesis_hash;
+
+	let mut sub = api
+		.subscribe_unbounded("archive_unstable_follow", EmptyParams::new())
+		.await
+		.unwrap();
+
+	// Initialized must always be reported first.
+	let event: ArchiveFollowEvent<String> = get_next_event(&mut sub).await;
+	assert_eq!(
+		event,
+		ArchiveFollowEvent::Initialized(ArchiveFollowInitialized {
+			finalized_block_hash: format!("{:?}", genesis_hash),
+			finalized_block_number: 0,
+		})
+	);
+
+	let block_1 = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	client.import(BlockOrigin::Own, block_1.clone()).await.unwrap();
+	let block_2 = BlockBuilderBuilder::new(&*client)
+		.on_parent_block(block_1.hash())
+		.with_parent_block_number(1)
+		.build()
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let event: ArchiveFollowEvent<String> = get_next_event(&mut sub).await;
+	assert_eq!(
+		event,
+		ArchiveFollowEvent::NewBlock(ArchiveFollowNewBlock {
+			block_hash: format!("{:?}", block_1.hash()),
+			parent_block_hash: format!("{:?}", genesis_hash),
+			block_number: 1,
+		})
+	);
+	let event: ArchiveFollowEvent<String> = get_next_event(&mut sub).await;
+	assert_eq!(
+		event,
+		ArchiveFollowEvent::NewBlock(ArchiveFollowNewBlock {
+			block_hash: format!("{:?}", block_2.hash()),
+			parent_block_hash: format!("{:?}", block_1.hash()),
+			block_number: 2,
+		})
+	);
+
+	// Both blocks are reported when finalizing the second one.
+	client.finalize_block(block_2.hash(), None).unwrap();
+	let event: ArchiveFollowEvent<String> = get_next_event(&mut sub).await;
+	assert_eq!(
+		event,
+		ArchiveFollowEvent::Finalized(ArchiveFollowFinalized {
+			finalized_block_hashes: vec![
+				format!("{:?}", block_1.hash()),
+				format!("{:?}", block_2.hash()),
+			],
+		})
+	);
+}
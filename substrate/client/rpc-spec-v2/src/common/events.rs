@@ -143,6 +143,150 @@ pub struct ArchiveStorageMethodErr {
 	pub error: String,
 }
 
+/// The storage item to compute the difference of, between two blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffItem<Key> {
+	/// The provided key. The differences of the key and of all its descendants are reported.
+	pub key: Key,
+	/// The type of the reported differences.
+	pub return_type: ArchiveStorageDiffType,
+	/// The child trie key, if the difference should be computed in a child trie.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<Key>,
+	/// The pagination key from which the iteration should resume.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub pagination_start_key: Option<Key>,
+}
+
+/// The type of the reported storage differences.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffType {
+	/// Report the values of the keys.
+	Value,
+	/// Report the hashes of the values of the keys.
+	Hash,
+}
+
+/// The operation that changed a storage key between two blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveStorageDiffOperationType {
+	/// The key was added.
+	Added,
+	/// The value of the key was modified.
+	Modified,
+	/// The key was deleted.
+	Deleted,
+}
+
+/// A storage difference between two blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffResultItem {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The value or the hash of the key.
+	///
+	/// For deleted keys, this is the value or the hash of the key in the previous block.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+	/// The operation that changed the key.
+	#[serde(rename = "type")]
+	pub operation_type: ArchiveStorageDiffOperationType,
+	/// The hex-encoded child trie key, if the key belongs to a child trie.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	#[serde(default)]
+	pub child_trie_key: Option<String>,
+}
+
+/// The result of a storage difference call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArchiveStorageDiffResult {
+	/// Query generated a result.
+	Ok(ArchiveStorageDiffMethodOk),
+	/// Query encountered an error.
+	Err(ArchiveStorageMethodErr),
+}
+
+impl ArchiveStorageDiffResult {
+	/// Create a new `ArchiveStorageDiffResult::Ok` result.
+	pub fn ok(
+		result: Vec<ArchiveStorageDiffResultItem>,
+		discarded_items: usize,
+		paginated_items: Vec<ArchiveStorageDiffItem<String>>,
+	) -> Self {
+		Self::Ok(ArchiveStorageDiffMethodOk { result, discarded_items, paginated_items })
+	}
+
+	/// Create a new `ArchiveStorageDiffResult::Err` result.
+	pub fn err(error: String) -> Self {
+		Self::Err(ArchiveStorageMethodErr { error })
+	}
+}
+
+/// The result of a storage difference call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageDiffMethodOk {
+	/// Reported differences.
+	pub result: Vec<ArchiveStorageDiffResultItem>,
+	/// Number of discarded items.
+	pub discarded_items: usize,
+	/// The items whose differences were not all reported, with the pagination start key to
+	/// resume them from.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	#[serde(default)]
+	pub paginated_items: Vec<ArchiveStorageDiffItem<String>>,
+}
+
+/// The event generated by the `archive_unstable_follow` subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum ArchiveFollowEvent<Hash> {
+	/// The first event of the subscription, with the finalized block it starts from.
+	Initialized(ArchiveFollowInitialized<Hash>),
+	/// A new block was imported.
+	NewBlock(ArchiveFollowNewBlock<Hash>),
+	/// Blocks were finalized.
+	Finalized(ArchiveFollowFinalized<Hash>),
+}
+
+/// The finalized block the `archive_unstable_follow` subscription starts from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFollowInitialized<Hash> {
+	/// The hash of the finalized block.
+	pub finalized_block_hash: Hash,
+	/// The number of the finalized block.
+	pub finalized_block_number: u64,
+}
+
+/// A block imported while following the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFollowNewBlock<Hash> {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The hash of the parent of the block.
+	pub parent_block_hash: Hash,
+	/// The number of the block.
+	pub block_number: u64,
+}
+
+/// Blocks finalized while following the chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFollowFinalized<Hash> {
+	/// The hashes of the newly finalized blocks, in ascending order.
+	pub finalized_block_hashes: Vec<Hash>,
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -270,4 +414,93 @@ mod tests {
 		let dec: PaginatedStorageQuery<&str> = serde_json::from_str(exp).unwrap();
 		assert_eq!(dec, item);
 	}
+
+	#[test]
+	fn archive_storage_diff() {
+		let item = ArchiveStorageDiffItem {
+			key: "0x1",
+			return_type: ArchiveStorageDiffType::Hash,
+			child_trie_key: None,
+			pagination_start_key: None,
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","returnType":"hash"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffItem<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		let item = ArchiveStorageDiffItem {
+			key: "0x1",
+			return_type: ArchiveStorageDiffType::Value,
+			child_trie_key: Some("0x2"),
+			pagination_start_key: Some("0x3"),
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp =
+			r#"{"key":"0x1","returnType":"value","childTrieKey":"0x2","paginationStartKey":"0x3"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffItem<&str> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+
+		let item = ArchiveStorageDiffResultItem {
+			key: "0x1".into(),
+			result: StorageResultType::Value("res".into()),
+			operation_type: ArchiveStorageDiffOperationType::Deleted,
+			child_trie_key: None,
+		};
+		// Encode
+		let ser = serde_json::to_string(&item).unwrap();
+		let exp = r#"{"key":"0x1","value":"res","type":"deleted"}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveStorageDiffResultItem = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, item);
+	}
+
+	#[test]
+	fn archive_follow_event() {
+		let event: ArchiveFollowEvent<String> =
+			ArchiveFollowEvent::Initialized(ArchiveFollowInitialized {
+				finalized_block_hash: "0x1".into(),
+				finalized_block_number: 1,
+			});
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"initialized","finalizedBlockHash":"0x1","finalizedBlockNumber":1}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveFollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+
+		let event: ArchiveFollowEvent<String> =
+			ArchiveFollowEvent::NewBlock(ArchiveFollowNewBlock {
+				block_hash: "0x2".into(),
+				parent_block_hash: "0x1".into(),
+				block_number: 2,
+			});
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp =
+			r#"{"event":"newBlock","blockHash":"0x2","parentBlockHash":"0x1","blockNumber":2}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveFollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+
+		let event: ArchiveFollowEvent<String> =
+			ArchiveFollowEvent::Finalized(ArchiveFollowFinalized {
+				finalized_block_hashes: vec!["0x2".into(), "0x3".into()],
+			});
+		// Encode
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"finalized","finalizedBlockHashes":["0x2","0x3"]}"#;
+		assert_eq!(ser, exp);
+		// Decode
+		let dec: ArchiveFollowEvent<String> = serde_json::from_str(exp).unwrap();
+		assert_eq!(dec, event);
+	}
 }
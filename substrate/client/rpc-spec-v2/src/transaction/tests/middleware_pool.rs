@@ -177,6 +177,19 @@ impl TransactionPool for MiddlewarePool {
 		self.inner_pool.ready_at(at)
 	}
 
+	fn ready_at_block(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		self.inner_pool.ready_at_block(at)
+	}
+
 	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send> {
 		self.inner_pool.ready()
 	}
@@ -0,0 +1,61 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate bitswap API.
+
+#[cfg(test)]
+mod tests;
+
+use cid::Cid;
+use jsonrpsee::{core::async_trait, Extensions};
+use sc_network::{
+	bitswap::{BitswapClient, BitswapError},
+	NetworkRequest,
+};
+use sc_rpc_api::{bitswap::error::Error, check_if_safe};
+use sp_core::Bytes;
+
+pub use sc_rpc_api::bitswap::BitswapApiServer;
+
+/// Bitswap API.
+pub struct Bitswap<N>(BitswapClient<N>);
+
+impl<N> Bitswap<N> {
+	/// Create a new bitswap API instance.
+	pub fn new(client: BitswapClient<N>) -> Self {
+		Self(client)
+	}
+}
+
+#[async_trait]
+impl<N> BitswapApiServer for Bitswap<N>
+where
+	N: NetworkRequest + Send + Sync + 'static,
+{
+	async fn get(&self, ext: &Extensions, cid: String) -> Result<Bytes, Error> {
+		check_if_safe(ext)?;
+
+		let cid = Cid::try_from(cid.as_str()).map_err(|e| Error::InvalidCid(e.to_string()))?;
+		self.0.get(&cid).await.map(Into::into).map_err(|e| match e {
+			BitswapError::UnsupportedCid => Error::UnsupportedCid,
+			BitswapError::NoResponse => Error::NoResponse,
+			BitswapError::Unsupported => Error::Unsupported,
+			_ => Error::NotFound,
+		})
+	}
+}
@@ -0,0 +1,167 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use cid::multihash::{Code, Multihash};
+use futures::channel::oneshot;
+use jsonrpsee::{MethodsError as RpcError, RpcModule};
+use sc_block_builder::BlockBuilderBuilder;
+use sc_network::{
+	bitswap::{BitswapPeers, BitswapRequestHandler},
+	config::{IncomingRequest, NetworkBackendType, OutgoingResponse},
+	IfDisconnected, PeerId, ProtocolName, RequestFailure,
+};
+use sc_rpc_api::DenyUnsafe;
+use sp_consensus::BlockOrigin;
+use std::sync::Arc;
+use substrate_test_runtime_client::{prelude::*, runtime::ExtrinsicBuilder, TestClientBuilder};
+
+const DATA: &[u8] = &[0x13, 0x37, 0x13, 0x38];
+
+/// Forwards requests to a local bitswap request handler.
+struct LocalNetwork(async_channel::Sender<IncomingRequest>);
+
+#[async_trait]
+impl NetworkRequest for LocalNetwork {
+	async fn request(
+		&self,
+		target: PeerId,
+		protocol: ProtocolName,
+		request: Vec<u8>,
+		_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+		_connect: IfDisconnected,
+	) -> Result<(Vec<u8>, ProtocolName), RequestFailure> {
+		let (tx, rx) = oneshot::channel();
+		self.0
+			.send(IncomingRequest { peer: target, payload: request, pending_response: tx })
+			.await
+			.map_err(|_| RequestFailure::NotConnected)?;
+		let OutgoingResponse { result, .. } = rx.await.map_err(|_| RequestFailure::Refused)?;
+		result.map(|response| (response, protocol)).map_err(|_| RequestFailure::Refused)
+	}
+
+	fn start_request(
+		&self,
+		_target: PeerId,
+		_protocol: ProtocolName,
+		_request: Vec<u8>,
+		_fallback_request: Option<(Vec<u8>, ProtocolName)>,
+		_tx: oneshot::Sender<Result<(Vec<u8>, ProtocolName), RequestFailure>>,
+		_connect: IfDisconnected,
+	) {
+		unimplemented!()
+	}
+}
+
+struct Peers(Vec<PeerId>);
+
+#[async_trait]
+impl BitswapPeers for Peers {
+	async fn peers(&self) -> Vec<PeerId> {
+		self.0.clone()
+	}
+}
+
+/// RPC module fetching from a single peer which has `DATA` indexed.
+async fn setup(
+	backend: NetworkBackendType,
+	serves_bitswap: bool,
+	deny_unsafe: DenyUnsafe,
+) -> RpcModule<Bitswap<LocalNetwork>> {
+	let client = TestClientBuilder::with_tx_storage(u32::MAX).build();
+	let mut block_builder = BlockBuilderBuilder::new(&client)
+		.on_parent_block(client.chain_info().genesis_hash)
+		.with_parent_block_number(0)
+		.build()
+		.unwrap();
+	block_builder
+		.push(ExtrinsicBuilder::new_indexed_call(DATA.to_vec()).build())
+		.unwrap();
+	let block = block_builder.build().unwrap().block;
+	client.import(BlockOrigin::File, block).await.unwrap();
+
+	let (handler, config) = BitswapRequestHandler::new(Arc::new(client));
+	tokio::spawn(handler.run());
+
+	let network = LocalNetwork(config.inbound_queue.unwrap());
+	let client = BitswapClient::new(
+		network,
+		Arc::new(Peers(vec![PeerId::random()])),
+		backend,
+		serves_bitswap,
+	);
+	let mut module = Bitswap::new(client).into_rpc();
+	module.extensions_mut().insert(deny_unsafe);
+	module
+}
+
+fn blake2_cid(data: &[u8]) -> String {
+	let hash = Multihash::wrap(u64::from(Code::Blake2b256), &sp_crypto_hashing::blake2_256(data));
+	Cid::new_v1(0x70, hash.unwrap()).to_string()
+}
+
+#[tokio::test]
+async fn get_returns_indexed_data() {
+	let api = setup(NetworkBackendType::Libp2p, true, DenyUnsafe::No).await;
+
+	let data: Bytes = api.call("bitswap_get", [blake2_cid(DATA)]).await.unwrap();
+	assert_eq!(data.0, DATA);
+}
+
+#[tokio::test]
+async fn get_reports_errors() {
+	let api = setup(NetworkBackendType::Libp2p, true, DenyUnsafe::No).await;
+
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_get", [blake2_cid(&[1, 2, 3])]).await,
+		Err(RpcError::JsonRpc(err)) if err.code() == 9003
+	);
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_get", ["not a cid"]).await,
+		Err(RpcError::JsonRpc(err)) if err.code() == 9001
+	);
+}
+
+#[tokio::test]
+async fn get_works_on_litep2p() {
+	let api = setup(NetworkBackendType::Litep2p, false, DenyUnsafe::No).await;
+
+	let data: Bytes = api.call("bitswap_get", [blake2_cid(DATA)]).await.unwrap();
+	assert_eq!(data.0, DATA);
+}
+
+#[tokio::test]
+async fn get_fails_when_litep2p_serves_bitswap() {
+	let api = setup(NetworkBackendType::Litep2p, true, DenyUnsafe::No).await;
+
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_get", [blake2_cid(DATA)]).await,
+		Err(RpcError::JsonRpc(err)) if err.code() == 9005
+	);
+}
+
+#[tokio::test]
+async fn get_is_unsafe() {
+	let api = setup(NetworkBackendType::Libp2p, true, DenyUnsafe::Yes).await;
+
+	assert_matches!(
+		api.call::<_, Bytes>("bitswap_get", [blake2_cid(DATA)]).await,
+		Err(RpcError::JsonRpc(err)) if err.message() == "RPC call is unsafe to be called externally"
+	);
+}
@@ -29,6 +29,7 @@ pub use jsonrpsee::core::id_providers::{
 pub use sc_rpc_api::DenyUnsafe;
 
 pub mod author;
+pub mod bitswap;
 pub mod chain;
 pub mod dev;
 pub mod mixnet;
@@ -18,26 +18,54 @@
 
 //! Substrate statement store API.
 
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	utils::{spawn_subscription_task, BoundedVecDeque, PendingSubscription},
+	SubscriptionTaskExecutor,
+};
 use codec::{Decode, Encode};
+use futures::{future, StreamExt};
 use jsonrpsee::{
 	core::{async_trait, RpcResult},
-	Extensions,
+	Extensions, PendingSubscriptionSink,
 };
 /// Re-export the API for backward compatibility.
-pub use sc_rpc_api::statement::{error::Error, StatementApiServer};
+pub use sc_rpc_api::statement::{error::Error, StatementApiServer, TopicFilter};
 use sp_core::Bytes;
-use sp_statement_store::{StatementSource, SubmitResult};
+use sp_statement_store::{Statement, StatementSource, SubmitResult, Topic, MAX_TOPICS};
 use std::sync::Arc;
 
 /// Statement store API
 pub struct StatementStore {
 	store: Arc<dyn sp_statement_store::StatementStore>,
+	executor: SubscriptionTaskExecutor,
 }
 
 impl StatementStore {
 	/// Create new instance of Offchain API.
-	pub fn new(store: Arc<dyn sp_statement_store::StatementStore>) -> Self {
-		StatementStore { store }
+	pub fn new(
+		store: Arc<dyn sp_statement_store::StatementStore>,
+		executor: SubscriptionTaskExecutor,
+	) -> Self {
+		StatementStore { store, executor }
+	}
+}
+
+/// Check if the statement matches the topic filter and the decryption key.
+fn statement_matches(
+	statement: &Statement,
+	topic_filter: &TopicFilter,
+	dest: Option<[u8; 32]>,
+) -> bool {
+	if statement.decryption_key() != dest {
+		return false
+	}
+	let has_topic = |topic: &Topic| (0..MAX_TOPICS).any(|i| statement.topic(i) == Some(*topic));
+	match topic_filter {
+		TopicFilter::MatchAll(topics) => topics.iter().all(has_topic),
+		TopicFilter::MatchAny(topics) => topics.iter().any(has_topic),
 	}
 }
 
@@ -103,4 +131,27 @@ impl StatementApiServer for StatementStore {
 	fn remove(&self, hash: [u8; 32]) -> RpcResult<()> {
 		Ok(self.store.remove(&hash).map_err(|e| Error::StatementStore(e.to_string()))?)
 	}
+
+	fn subscribe_statement(
+		&self,
+		pending: PendingSubscriptionSink,
+		topic_filter: TopicFilter,
+		dest: Option<[u8; 32]>,
+	) {
+		let stream = self
+			.store
+			.statement_stream()
+			.filter(move |statement| {
+				future::ready(statement_matches(statement, &topic_filter, dest))
+			})
+			.map(|statement| Bytes(statement.encode()));
+
+		let fut = async move {
+			PendingSubscription::from(pending)
+				.pipe_from_stream(stream, BoundedVecDeque::default())
+				.await;
+		};
+
+		spawn_subscription_task(&self.executor, fut);
+	}
 }
@@ -0,0 +1,122 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::testing::{test_executor, timeout_secs};
+use sp_statement_store::{Hash, Result, StatementStream};
+
+/// Store only providing the statements sent to `receiver` as new statements.
+struct TestStore(async_channel::Receiver<Statement>);
+
+impl sp_statement_store::StatementStore for TestStore {
+	fn statements(&self) -> Result<Vec<(Hash, Statement)>> {
+		unimplemented!()
+	}
+
+	fn statement(&self, _hash: &Hash) -> Result<Option<Statement>> {
+		unimplemented!()
+	}
+
+	fn broadcasts(&self, _match_all_topics: &[Topic]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted(&self, _match_all_topics: &[Topic], _dest: [u8; 32]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn posted_clear(&self, _match_all_topics: &[Topic], _dest: [u8; 32]) -> Result<Vec<Vec<u8>>> {
+		unimplemented!()
+	}
+
+	fn submit(&self, _statement: Statement, _source: StatementSource) -> SubmitResult {
+		unimplemented!()
+	}
+
+	fn remove(&self, _hash: &Hash) -> Result<()> {
+		unimplemented!()
+	}
+
+	fn statement_stream(&self) -> StatementStream {
+		Box::pin(self.0.clone())
+	}
+}
+
+fn statement(topics: &[Topic], dest: Option<[u8; 32]>) -> Statement {
+	let mut statement = Statement::new();
+	for (i, topic) in topics.iter().enumerate() {
+		statement.set_topic(i, *topic);
+	}
+	if let Some(dest) = dest {
+		statement.set_decryption_key(dest);
+	}
+	statement
+}
+
+#[test]
+fn statement_matches_topic_filter() {
+	let statement = statement(&[[1; 32], [2; 32]], None);
+
+	assert!(statement_matches(&statement, &TopicFilter::MatchAll(vec![[1; 32], [2; 32]]), None));
+	assert!(!statement_matches(&statement, &TopicFilter::MatchAll(vec![[1; 32], [3; 32]]), None));
+	assert!(statement_matches(&statement, &TopicFilter::MatchAny(vec![[3; 32], [2; 32]]), None));
+	assert!(!statement_matches(&statement, &TopicFilter::MatchAny(vec![[3; 32]]), None));
+	// an empty `MatchAll` filter matches any statement, an empty `MatchAny` filter matches none
+	assert!(statement_matches(&statement, &TopicFilter::MatchAll(vec![]), None));
+	assert!(!statement_matches(&statement, &TopicFilter::MatchAny(vec![]), None));
+}
+
+#[test]
+fn statement_matches_decryption_key() {
+	let broadcast = statement(&[[1; 32]], None);
+	let posted = statement(&[[1; 32]], Some([9; 32]));
+	let filter = TopicFilter::MatchAll(vec![[1; 32]]);
+
+	assert!(statement_matches(&broadcast, &filter, None));
+	assert!(!statement_matches(&broadcast, &filter, Some([9; 32])));
+	assert!(statement_matches(&posted, &filter, Some([9; 32])));
+	assert!(!statement_matches(&posted, &filter, Some([8; 32])));
+	assert!(!statement_matches(&posted, &filter, None));
+}
+
+#[tokio::test]
+async fn subscription_pushes_matching_statements() {
+	let (sender, receiver) = async_channel::unbounded();
+	let api = StatementStore::new(Arc::new(TestStore(receiver)), test_executor()).into_rpc();
+
+	let params = [
+		serde_json::to_value(TopicFilter::MatchAny(vec![[1; 32]])).unwrap(),
+		serde_json::to_value([9u8; 32]).unwrap(),
+	];
+	let mut sub = api.subscribe_unbounded("statement_subscribeStatement", params).await.unwrap();
+
+	let expected = statement(&[[1; 32]], Some([9; 32]));
+	for statement in [
+		statement(&[[2; 32]], Some([9; 32])),
+		statement(&[[1; 32]], None),
+		statement(&[[1; 32]], Some([8; 32])),
+		expected.clone(),
+	] {
+		sender.send(statement).await.unwrap();
+	}
+
+	// only the last statement matches both the topics and the decryption key
+	let (item, sub_id) = timeout_secs(10, sub.next::<Bytes>()).await.unwrap().unwrap().unwrap();
+	assert_eq!(item, Bytes(expected.encode()));
+	assert_eq!(&sub_id, sub.subscription_id());
+}
@@ -53,10 +53,14 @@ use sc_network::{
 use sc_network_common::role::Roles;
 use sc_network_light::light_client_requests::handler::LightClientRequestHandler;
 use sc_network_sync::{
-	block_relay_protocol::BlockRelayParams, block_request_handler::BlockRequestHandler,
-	engine::SyncingEngine, service::network::NetworkServiceProvider,
+	block_relay_protocol::BlockRelayParams,
+	block_request_handler::BlockRequestHandler,
+	engine::SyncingEngine,
+	service::network::NetworkServiceProvider,
 	state_request_handler::StateRequestHandler,
-	warp_request_handler::RequestHandler as WarpSyncRequestHandler, SyncingService, WarpSyncConfig,
+	strategy::warp::{WarpProofProtocolNames, WARP_PROOF_PROTOCOL_VERSION},
+	warp_request_handler::RequestHandler as WarpSyncRequestHandler,
+	SyncingService, WarpSyncConfig,
 };
 use sc_rpc::{
 	author::AuthorApiServer,
@@ -99,28 +103,43 @@ type TFullParts<TBl, TRtApi, TExec> =
 	(TFullClient<TBl, TRtApi, TExec>, Arc<TFullBackend<TBl>>, KeystoreContainer, TaskManager);
 
 /// Construct a local keystore shareable container
-pub struct KeystoreContainer(Arc<LocalKeystore>);
+pub struct KeystoreContainer {
+	keystore: KeystorePtr,
+	local: Arc<LocalKeystore>,
+}
 
 impl KeystoreContainer {
 	/// Construct KeystoreContainer
 	pub fn new(config: &KeystoreConfig) -> Result<Self, Error> {
-		let keystore = Arc::new(match config {
+		let local = Arc::new(match config {
 			KeystoreConfig::Path { path, password } =>
 				LocalKeystore::open(path.clone(), password.clone())?,
-			KeystoreConfig::InMemory => LocalKeystore::in_memory(),
+			KeystoreConfig::InMemory | KeystoreConfig::Remote { .. } => LocalKeystore::in_memory(),
 		});
 
-		Ok(Self(keystore))
+		let keystore: KeystorePtr = match config {
+			#[cfg(unix)]
+			KeystoreConfig::Remote { uri } => Arc::new(sc_keystore::RemoteKeystore::open(uri)?),
+			#[cfg(not(unix))]
+			KeystoreConfig::Remote { .. } =>
+				return Err(Error::Other("Remote keystores are only supported on Unix".into())),
+			_ => local.clone(),
+		};
+
+		Ok(Self { keystore, local })
 	}
 
 	/// Returns a shared reference to a dynamic `Keystore` trait implementation.
 	pub fn keystore(&self) -> KeystorePtr {
-		self.0.clone()
+		self.keystore.clone()
 	}
 
 	/// Returns a shared reference to the local keystore .
+	///
+	/// When the keys are held by a remote signer, this is an empty in-memory keystore and
+	/// [`Self::keystore`] should be used to sign with the authority keys.
 	pub fn local_keystore(&self) -> Arc<LocalKeystore> {
-		self.0.clone()
+		self.local.clone()
 	}
 }
 
@@ -517,6 +536,10 @@ where
 			&config.state_pruning,
 			config.blocks_pruning,
 			backend.clone(),
+			network.clone(),
+			sync_service.clone(),
+			config.network.network_backend.clone(),
+			config.network.ipfs_server,
 			&*rpc_builder,
 		)
 	};
@@ -644,6 +667,10 @@ pub fn gen_rpc_module<TBl, TBackend, TCl, TRpc, TExPool>(
 	state_pruning: &Option<PruningMode>,
 	blocks_pruning: BlocksPruning,
 	backend: Arc<TBackend>,
+	network: Arc<dyn sc_network::service::traits::NetworkService>,
+	sync_service: Arc<SyncingService<TBl>>,
+	network_backend: sc_network::config::NetworkBackendType,
+	ipfs_server: bool,
 	rpc_builder: &(dyn Fn(SubscriptionTaskExecutor) -> Result<RpcModule<TRpc>, Error>),
 ) -> Result<RpcModule<()>, Error>
 where
@@ -723,6 +750,7 @@ where
 		let archive_v2 = sc_rpc_spec_v2::archive::Archive::new(
 			client.clone(),
 			backend.clone(),
+			task_executor.clone(),
 			genesis_hash,
 			// Defaults to sensible limits for the `Archive`.
 			sc_rpc_spec_v2::archive::ArchiveConfig::default(),
@@ -749,6 +777,14 @@ where
 
 	let system = sc_rpc::system::System::new(system_info, system_rpc_tx).into_rpc();
 
+	let bitswap = sc_rpc::bitswap::Bitswap::new(sc_network::bitswap::BitswapClient::new(
+		network,
+		sync_service,
+		network_backend,
+		ipfs_server,
+	))
+	.into_rpc();
+
 	if let Some(storage) = backend.offchain_storage() {
 		let offchain = sc_rpc::offchain::Offchain::new(storage).into_rpc();
 
@@ -769,6 +805,7 @@ where
 	rpc_api.merge(system).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(state).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(child_state).map_err(|e| Error::Application(e.into()))?;
+	rpc_api.merge(bitswap).map_err(|e| Error::Application(e.into()))?;
 	// Additional [`RpcModule`]s defined in the node to fit the specific blockchain
 	let extra_rpcs = rpc_builder(task_executor.clone())?;
 	rpc_api.merge(extra_rpcs).map_err(|e| Error::Application(e.into()))?;
@@ -825,6 +862,7 @@ pub fn build_network<TBl, TNet, TExPool, TImpQu, TCl>(
 where
 	TBl: BlockT,
 	TCl: ProvideRuntimeApi<TBl>
+		+ CallApiAt<TBl>
 		+ HeaderMetadata<TBl, Error = sp_blockchain::Error>
 		+ Chain<TBl>
 		+ BlockBackend<TBl>
@@ -833,6 +871,7 @@ where
 		+ HeaderBackend<TBl>
 		+ BlockchainEvents<TBl>
 		+ 'static,
+	<TCl as ProvideRuntimeApi<TBl>>::Api: sp_api::Core<TBl>,
 	TExPool: TransactionPool<Block = TBl, Hash = <TBl as BlockT>::Hash> + 'static,
 	TImpQu: ImportQueue<TBl> + 'static,
 	TNet: NetworkBackend<TBl, <TBl as BlockT>::Hash>,
@@ -913,21 +952,38 @@ where
 		(protocol_config, config_name)
 	};
 
-	let (warp_sync_protocol_config, warp_request_protocol_name) = match warp_sync_config.as_ref() {
+	let (warp_sync_protocol_configs, warp_request_protocol_names) = match warp_sync_config.as_ref()
+	{
 		Some(WarpSyncConfig::WithProvider(warp_with_provider)) => {
-			// Allow both outgoing and incoming requests.
+			// Allow both outgoing and incoming requests, serving every warp proof protocol version.
 			let (handler, protocol_config) = WarpSyncRequestHandler::new::<_, TNet>(
 				protocol_id.clone(),
 				genesis_hash,
 				config.chain_spec.fork_id(),
 				warp_with_provider.clone(),
 			);
-			let config_name = protocol_config.protocol_name().clone();
+			let (latest_handler, latest_protocol_config) =
+				WarpSyncRequestHandler::new_with_version::<_, TNet>(
+					protocol_id.clone(),
+					genesis_hash,
+					config.chain_spec.fork_id(),
+					warp_with_provider.clone(),
+					WARP_PROOF_PROTOCOL_VERSION,
+				);
+			let protocol_names = WarpProofProtocolNames {
+				latest: latest_protocol_config.protocol_name().clone(),
+				fallback: protocol_config.protocol_name().clone(),
+			};
 
 			spawn_handle.spawn("warp-sync-request-handler", Some("networking"), handler.run());
-			(Some(protocol_config), Some(config_name))
+			spawn_handle.spawn(
+				"warp-sync-request-handler-latest",
+				Some("networking"),
+				latest_handler.run(),
+			);
+			(vec![protocol_config, latest_protocol_config], Some(protocol_names))
 		},
-		_ => (None, None),
+		_ => (Vec::new(), None),
 	};
 
 	let light_client_request_protocol_config = {
@@ -946,7 +1002,7 @@ where
 	net_config.add_request_response_protocol(state_request_protocol_config);
 	net_config.add_request_response_protocol(light_client_request_protocol_config);
 
-	if let Some(config) = warp_sync_protocol_config {
+	for config in warp_sync_protocol_configs {
 		net_config.add_request_response_protocol(config);
 	}
 
@@ -957,6 +1013,19 @@ where
 		config
 	});
 
+	// Without the server, the bitswap protocol is registered for outbound requests only, so
+	// that content can still be fetched from peers.
+	if !config.network.ipfs_server {
+		net_config.add_request_response_protocol(TNet::request_response_config(
+			sc_network::bitswap::PROTOCOL_NAME.into(),
+			Vec::new(),
+			sc_network::bitswap::MAX_PACKET_SIZE,
+			sc_network::bitswap::MAX_PACKET_SIZE,
+			sc_network::bitswap::REQUEST_TIMEOUT,
+			None,
+		));
+	}
+
 	// create transactions protocol and add it to the list of supported protocols of
 	let peer_store_handle = net_config.peer_store_handle();
 	let (transactions_handler_proto, transactions_config) =
@@ -988,7 +1057,7 @@ where
 		import_queue.service(),
 		block_downloader,
 		state_request_protocol_name,
-		warp_request_protocol_name,
+		warp_request_protocol_names,
 		Arc::clone(&peer_store_handle),
 	)?;
 	let sync_service_import_queue = sync_service.clone();
@@ -0,0 +1,544 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export and import of state snapshots.
+//!
+//! A snapshot file contains the header and justifications of a finalized block followed by all
+//! the key-values of its state, split in chunks. The file is a sequence of SCALE encoded records,
+//! each one carrying the `blake2_256` hash of its payload so that corruption is detected on
+//! import. The first record is the [`SnapshotHeader`], the following ones are [`SnapshotChunk`]s
+//! and an empty record terminates the file.
+//!
+//! The header record carries a proof of the finality of the block starting at the genesis
+//! authority set, generated by [`WarpSyncProvider::generate_from_genesis`]. The proof is checked
+//! before the state is read, so that only the state of a block finalized by the chain known to
+//! the node is imported.
+//!
+//! Chunks use the same layout as the state sync protocol: the top trie comes first with an empty
+//! state root, followed by default child tries keyed by their prefixed storage key. This allows
+//! importing a snapshot through the import queue exactly as a state downloaded from the network.
+//! Like a state downloaded by state sync, the whole state is held in memory until it is imported,
+//! so importing is bounded by a maximum state size.
+
+use crate::error::Error;
+use codec::{Decode, Encode, IoReader as CodecIoReader};
+use futures::{future, prelude::*};
+use futures_timer::Delay;
+use log::{info, warn};
+use sc_client_api::{BlockBackend, HeaderBackend, StorageProvider};
+use sc_consensus::{
+	import_queue::{BlockImportError, BlockImportStatus, ImportQueue, IncomingBlock, Link},
+	ImportedState,
+};
+use sc_network_sync::strategy::warp::{EncodedProof, VerificationResult, WarpSyncProvider};
+use sp_consensus::BlockOrigin;
+use sp_core::storage::{well_known_keys, ChildInfo, StorageKey};
+use sp_crypto_hashing::blake2_256;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, Zero},
+	Justifications,
+};
+use sp_state_machine::{KeyValueStates, KeyValueStorageLevel};
+use std::{
+	io::{Read, Write},
+	pin::Pin,
+	sync::Arc,
+	task::Poll,
+	time::Duration,
+};
+
+/// Magic bytes identifying a snapshot file.
+const SNAPSHOT_MAGIC: [u8; 8] = *b"subsnap\0";
+
+/// Version of the snapshot file format.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Maximum number of key-values stored in a single chunk.
+const MAX_CHUNK_KEY_VALUES: usize = 16 * 1024;
+
+/// Number of milliseconds to wait until next poll.
+const DELAY_TIME: u64 = 200;
+
+/// First record of a snapshot file.
+#[derive(Encode, Decode)]
+struct SnapshotHeader<B: BlockT> {
+	/// Must be equal to [`SNAPSHOT_MAGIC`].
+	magic: [u8; 8],
+	/// Version of the file format.
+	version: u32,
+	/// Header of the block the state belongs to.
+	header: B::Header,
+	/// Justifications finalizing the block.
+	justifications: Option<Justifications>,
+	/// Proof of the finality of the block starting at the genesis authority set.
+	finality_proof: Vec<u8>,
+}
+
+/// A chunk of key-values of a single storage level.
+#[derive(Encode, Decode)]
+struct SnapshotChunk {
+	/// Root of the level, empty for the top trie.
+	state_root: Vec<u8>,
+	/// Prefixed storage keys of the level, empty for the top trie.
+	parent_storage_keys: Vec<Vec<u8>>,
+	/// Key-values of the chunk.
+	key_values: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Write a record with its hash to `output`. `None` marks the end of the file.
+fn write_record(output: &mut impl Write, payload: Option<Vec<u8>>) -> Result<(), Error> {
+	let record = payload.map(|payload| {
+		let hash = blake2_256(&payload);
+		(payload, hash)
+	});
+	output.write_all(&record.encode()).map_err(|e| Error::Other(e.to_string()))
+}
+
+/// Read a record from `input` and check its hash. Returns `None` at the end of the file.
+fn read_record(input: &mut impl Read, index: u64) -> Result<Option<Vec<u8>>, Error> {
+	let record = Option::<(Vec<u8>, [u8; 32])>::decode(&mut CodecIoReader(input))
+		.map_err(|e| Error::Other(format!("Error reading snapshot record #{}: {}", index, e)))?;
+	match record {
+		Some((payload, hash)) if blake2_256(&payload) != hash =>
+			Err(Error::Other(format!("Hash mismatch in snapshot record #{}", index))),
+		Some((payload, _)) => Ok(Some(payload)),
+		None => Ok(None),
+	}
+}
+
+/// Write all `key_values` of a storage level in chunks. Returns the number of key-values written.
+fn write_level(
+	output: &mut impl Write,
+	state_root: Vec<u8>,
+	parent_storage_keys: Vec<Vec<u8>>,
+	key_values: impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>>,
+) -> Result<usize, Error> {
+	let mut chunk = SnapshotChunk { state_root, parent_storage_keys, key_values: Vec::new() };
+	let mut written = 0;
+	for key_value in key_values {
+		chunk.key_values.push(key_value?);
+		if chunk.key_values.len() == MAX_CHUNK_KEY_VALUES {
+			written += chunk.key_values.len();
+			write_record(output, Some(chunk.encode()))?;
+			chunk.key_values.clear();
+		}
+	}
+	if !chunk.key_values.is_empty() {
+		written += chunk.key_values.len();
+		write_record(output, Some(chunk.encode()))?;
+	}
+	Ok(written)
+}
+
+/// Export a snapshot of the state at the block `hash` to `output`.
+///
+/// The block must be finalized and `warp_sync_provider` must be able to prove its finality, which
+/// usually requires the block to have a justification.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	hash: B::Hash,
+	mut output: impl Write,
+) -> Result<(), Error>
+where
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BA>,
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+{
+	let header = client
+		.header(hash)?
+		.ok_or_else(|| Error::Other(format!("Header for block {:?} not found", hash)))?;
+	let finalized_number = client.info().finalized_number;
+	if *header.number() > finalized_number {
+		return Err(Error::Other(format!(
+			"Block #{} is not finalized, last finalized block is #{}",
+			header.number(),
+			finalized_number,
+		)))
+	}
+	let number = *header.number();
+	if number.is_zero() {
+		return Err(Error::Other("The genesis state can not be exported as a snapshot".into()))
+	}
+	let justifications = client.justifications(hash)?;
+	let EncodedProof(finality_proof) =
+		warp_sync_provider.generate_from_genesis(hash).map_err(|e| {
+			Error::Other(format!("Error proving the finality of block #{}: {}", number, e))
+		})?;
+
+	let snapshot_header = SnapshotHeader::<B> {
+		magic: SNAPSHOT_MAGIC,
+		version: SNAPSHOT_VERSION,
+		header,
+		justifications,
+		finality_proof,
+	};
+	write_record(&mut output, Some(snapshot_header.encode()))?;
+
+	// Child trie roots are stored in the top trie, so collect them while exporting it.
+	let mut child_roots = Vec::new();
+	let top = client.storage_pairs(hash, None, None)?.map(|(key, value)| {
+		if key.0.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			child_roots.push((key.0.clone(), value.0.clone()));
+		}
+		Ok((key.0, value.0))
+	});
+	let mut exported = write_level(&mut output, Vec::new(), Vec::new(), top)?;
+
+	for (prefixed_storage_key, child_root) in child_roots {
+		let child_info = ChildInfo::new_default(
+			&prefixed_storage_key[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..],
+		);
+		let keys = client.child_storage_keys(hash, child_info.clone(), None, None)?;
+		let key_values = keys.filter_map(|key: StorageKey| {
+			match client.child_storage(hash, &child_info, &key) {
+				Ok(Some(value)) => Some(Ok((key.0, value.0))),
+				Ok(None) => None,
+				Err(e) => Some(Err(e.into())),
+			}
+		});
+		exported += write_level(&mut output, child_root, vec![prefixed_storage_key], key_values)?;
+	}
+
+	write_record(&mut output, None)?;
+	output.flush().map_err(|e| Error::Other(e.to_string()))?;
+	info!("📸 Exported {} key-values of the state at block #{} ({:?})", exported, number, hash);
+	Ok(())
+}
+
+/// Read the header of a snapshot from `input`.
+fn read_snapshot_header<B: BlockT>(input: &mut impl Read) -> Result<SnapshotHeader<B>, Error> {
+	let payload = read_record(input, 0)?.ok_or_else(|| Error::Other("Snapshot is empty".into()))?;
+	let header = SnapshotHeader::<B>::decode(&mut &payload[..])
+		.map_err(|e| Error::Other(format!("Error decoding snapshot header: {}", e)))?;
+	if header.magic != SNAPSHOT_MAGIC {
+		return Err(Error::Other("Input is not a snapshot file".into()))
+	}
+	if header.version != SNAPSHOT_VERSION {
+		return Err(Error::Other(format!(
+			"Unsupported snapshot version {}, expected {}",
+			header.version, SNAPSHOT_VERSION,
+		)))
+	}
+	Ok(header)
+}
+
+/// Check that `finality_proof` proves the finality of `header` starting at the genesis authority
+/// set.
+fn verify_finality<B: BlockT>(
+	warp_sync_provider: &dyn WarpSyncProvider<B>,
+	header: &B::Header,
+	finality_proof: Vec<u8>,
+) -> Result<(), Error> {
+	let number = *header.number();
+	let result = warp_sync_provider
+		.verify(&EncodedProof(finality_proof), 0, warp_sync_provider.current_authorities())
+		.map_err(|e| {
+			Error::Other(format!("Invalid finality proof for snapshot block #{}: {}", number, e))
+		})?;
+	match result {
+		VerificationResult::Complete(_, _, proven) if proven == *header => Ok(()),
+		_ => Err(Error::Other(format!(
+			"Finality proof does not prove the finality of snapshot block #{}",
+			number,
+		))),
+	}
+}
+
+/// Read the state of a snapshot from `input`, which must follow its header.
+///
+/// Fails if the key-values of the state add up to more than `max_state_size` bytes.
+fn read_snapshot_state(
+	input: &mut impl Read,
+	max_state_size: usize,
+) -> Result<KeyValueStates, Error> {
+	let mut levels: Vec<KeyValueStorageLevel> = Vec::new();
+	let mut state_size = 0usize;
+	let mut index = 1;
+	while let Some(payload) = read_record(input, index)? {
+		let chunk = SnapshotChunk::decode(&mut &payload[..]).map_err(|e| {
+			Error::Other(format!("Error decoding snapshot chunk #{}: {}", index, e))
+		})?;
+		state_size = chunk
+			.key_values
+			.iter()
+			.fold(state_size, |size, (key, value)| size.saturating_add(key.len() + value.len()));
+		if state_size > max_state_size {
+			return Err(Error::Other(format!(
+				"Snapshot state exceeds the maximum size of {} bytes",
+				max_state_size,
+			)))
+		}
+		match levels.last_mut() {
+			Some(level)
+				if level.state_root == chunk.state_root &&
+					level.parent_storage_keys == chunk.parent_storage_keys =>
+				level.key_values.extend(chunk.key_values),
+			_ => levels.push(KeyValueStorageLevel {
+				state_root: chunk.state_root,
+				parent_storage_keys: chunk.parent_storage_keys,
+				key_values: chunk.key_values,
+			}),
+		}
+		index += 1;
+	}
+
+	Ok(KeyValueStates(levels))
+}
+
+/// Import a snapshot from `input`.
+///
+/// The finality of the snapshot block is checked with `warp_sync_provider`, starting at the
+/// genesis authority set, before the state is read. The state, which must not exceed
+/// `max_state_size` bytes, is then imported together with the block header and justifications
+/// through the import queue, the same way as a state downloaded by state sync. The state root is
+/// checked against the header. The database must not contain any block besides genesis.
+pub fn import_snapshot<B, IQ, C>(
+	client: Arc<C>,
+	mut import_queue: IQ,
+	warp_sync_provider: Arc<dyn WarpSyncProvider<B>>,
+	mut input: impl Read + Send + 'static,
+	max_state_size: usize,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>
+where
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	B: BlockT,
+	IQ: ImportQueue<B> + 'static,
+{
+	struct WaitLink {
+		done: bool,
+		has_error: bool,
+	}
+
+	impl<B: BlockT> Link<B> for WaitLink {
+		fn blocks_processed(
+			&mut self,
+			_imported: usize,
+			_num_expected_blocks: usize,
+			results: Vec<(Result<BlockImportStatus<NumberFor<B>>, BlockImportError>, B::Hash)>,
+		) {
+			self.done = true;
+			for result in results {
+				if let (Err(err), hash) = result {
+					warn!("There was an error importing snapshot block {:?}: {}", hash, err);
+					self.has_error = true;
+				}
+			}
+		}
+	}
+
+	let info = client.info();
+	if !info.best_number.is_zero() {
+		return future::ready(Err(Error::Other(format!(
+			"Snapshots can only be imported into an empty database, best block is #{}",
+			info.best_number,
+		))))
+		.boxed()
+	}
+
+	let snapshot = read_snapshot_header::<B>(&mut input).and_then(|snapshot_header| {
+		let SnapshotHeader { header, justifications, finality_proof, .. } = snapshot_header;
+		verify_finality(&*warp_sync_provider, &header, finality_proof)?;
+		info!("📸 Importing state snapshot at block #{} ({:?})", header.number(), header.hash());
+		let state = read_snapshot_state(&mut input, max_state_size)?;
+		Ok((header, justifications, state))
+	});
+	let (header, justifications, state) = match snapshot {
+		Ok(snapshot) => snapshot,
+		Err(e) => return future::ready(Err(e)).boxed(),
+	};
+	let hash = header.hash();
+	let number = *header.number();
+
+	import_queue.service_ref().import_blocks(
+		BlockOrigin::File,
+		vec![IncomingBlock::<B> {
+			hash,
+			header: Some(header),
+			body: None,
+			indexed_body: None,
+			justifications,
+			origin: None,
+			allow_missing_state: true,
+			import_existing: true,
+			skip_execution: true,
+			state: Some(ImportedState { block: hash, state }),
+		}],
+	);
+
+	let mut link = WaitLink { done: false, has_error: false };
+	let mut delay = Delay::new(Duration::from_millis(DELAY_TIME));
+
+	future::poll_fn(move |cx| {
+		import_queue.poll_actions(cx, &mut link);
+
+		if link.has_error {
+			return Poll::Ready(Err(Error::Other(format!(
+				"Failed to import snapshot at block #{}",
+				number
+			))))
+		}
+		if link.done {
+			info!("🎉 Imported state snapshot. Finalized: #{}", client.info().finalized_number);
+			return Poll::Ready(Ok(()))
+		}
+
+		// Wait for the delay, because we know the queue is lagging behind.
+		while Pin::new(&mut delay).poll(cx).is_ready() {
+			delay.reset(Duration::from_millis(DELAY_TIME));
+		}
+		Poll::Pending
+	})
+	.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network_sync::strategy::warp::{AuthorityList, SetId};
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	/// Proves the finality of a single header.
+	struct TestWarpSyncProvider(Header);
+
+	impl WarpSyncProvider<Block> for TestWarpSyncProvider {
+		fn generate(
+			&self,
+			_start: <Block as BlockT>::Hash,
+		) -> Result<EncodedProof, Box<dyn std::error::Error + Send + Sync>> {
+			unimplemented!()
+		}
+
+		fn verify(
+			&self,
+			proof: &EncodedProof,
+			set_id: SetId,
+			authorities: AuthorityList,
+		) -> Result<VerificationResult<Block>, Box<dyn std::error::Error + Send + Sync>> {
+			match &proof.0[..] {
+				b"complete" =>
+					Ok(VerificationResult::Complete(set_id, authorities, self.0.clone())),
+				b"partial" => Ok(VerificationResult::Partial(set_id, authorities, self.0.hash())),
+				_ => Err("Bad proof".into()),
+			}
+		}
+
+		fn current_authorities(&self) -> AuthorityList {
+			Vec::new()
+		}
+	}
+
+	fn header(number: u64) -> Header {
+		Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)
+	}
+
+	fn chunk(state_root: &[u8], key_values: &[(&[u8], &[u8])]) -> Vec<u8> {
+		SnapshotChunk {
+			state_root: state_root.to_vec(),
+			parent_storage_keys: if state_root.is_empty() {
+				vec![]
+			} else {
+				vec![b"child".to_vec()]
+			},
+			key_values: key_values.iter().map(|(k, v)| (k.to_vec(), v.to_vec())).collect(),
+		}
+		.encode()
+	}
+
+	fn snapshot(chunks: Vec<Vec<u8>>) -> Vec<u8> {
+		let mut output = Vec::new();
+		let snapshot_header = SnapshotHeader::<Block> {
+			magic: SNAPSHOT_MAGIC,
+			version: SNAPSHOT_VERSION,
+			header: header(1),
+			justifications: None,
+			finality_proof: b"complete".to_vec(),
+		};
+		write_record(&mut output, Some(snapshot_header.encode())).unwrap();
+		for chunk in chunks {
+			write_record(&mut output, Some(chunk)).unwrap();
+		}
+		write_record(&mut output, None).unwrap();
+		output
+	}
+
+	#[test]
+	fn read_snapshot_merges_chunks_of_same_level() {
+		let input = snapshot(vec![
+			chunk(b"", &[(b"a", b"1")]),
+			chunk(b"", &[(b"b", b"2")]),
+			chunk(b"root", &[(b"c", b"3")]),
+		]);
+
+		let mut input = &input[..];
+		let header = read_snapshot_header::<Block>(&mut input).unwrap();
+		assert_eq!(header.header.number, 1);
+		assert_eq!(header.finality_proof, b"complete".to_vec());
+		let state = read_snapshot_state(&mut input, usize::MAX).unwrap();
+		assert_eq!(state.0.len(), 2);
+		assert_eq!(
+			state.0[0].key_values,
+			vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())]
+		);
+		assert_eq!(state.0[1].state_root, b"root".to_vec());
+		assert_eq!(state.0[1].parent_storage_keys, vec![b"child".to_vec()]);
+	}
+
+	#[test]
+	fn read_snapshot_detects_corruption() {
+		let mut input = snapshot(vec![chunk(b"", &[(b"key", b"value")])]);
+		// Flip the last byte of the value.
+		let position = input.windows(5).position(|w| w == b"value").unwrap() + 4;
+		input[position] ^= 1;
+
+		let mut input = &input[..];
+		read_snapshot_header::<Block>(&mut input).unwrap();
+		assert!(read_snapshot_state(&mut input, usize::MAX).is_err());
+	}
+
+	#[test]
+	fn read_snapshot_state_is_bounded() {
+		let input = snapshot(vec![chunk(b"", &[(b"a", b"1")]), chunk(b"", &[(b"b", b"2")])]);
+
+		let mut bounded = &input[..];
+		read_snapshot_header::<Block>(&mut bounded).unwrap();
+		assert!(read_snapshot_state(&mut bounded, 3).is_err());
+
+		let mut input = &input[..];
+		read_snapshot_header::<Block>(&mut input).unwrap();
+		assert_eq!(read_snapshot_state(&mut input, 4).unwrap().0[0].key_values.len(), 2);
+	}
+
+	#[test]
+	fn verify_finality_checks_the_proven_header() {
+		let provider = TestWarpSyncProvider(header(1));
+
+		assert!(verify_finality(&provider, &header(1), b"complete".to_vec()).is_ok());
+		// The proof is valid, but finalizes another block.
+		assert!(verify_finality(&provider, &header(2), b"complete".to_vec()).is_err());
+		// The proof does not reach the snapshot block.
+		assert!(verify_finality(&provider, &header(1), b"partial".to_vec()).is_err());
+		assert!(verify_finality(&provider, &header(1), b"invalid".to_vec()).is_err());
+	}
+}
@@ -40,13 +40,18 @@
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until
 //! pruning constraints are satisfied.
+//!
+//! # Snapshots.
+//! With `PruningMode::ConstrainedWithSnapshots`, the full state of every canonical block whose
+//! number is a multiple of the snapshot interval is kept in the database, while the states of
+//! other blocks are pruned as usual. This requires a database with reference counting.
 
 mod noncanonical;
 mod pruning;
 #[cfg(test)]
 mod test;
 
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use log::trace;
 use noncanonical::NonCanonicalOverlay;
 use parking_lot::RwLock;
@@ -62,6 +67,8 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MODE_CONSTRAINED_SNAPSHOTS: &[u8] = b"constrained_snapshots";
+const SNAPSHOT_INTERVAL: &[u8] = b"snapshot_interval";
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
 
 /// Database value type.
@@ -145,6 +152,8 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// Keeping snapshots requires a database with reference counting.
+	SnapshotsRequireRefCounting,
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -196,6 +205,10 @@ impl fmt::Debug for StateDbError {
 				write!(f, "Trying to get a block record from db while it is not commit to db yet")
 			},
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::SnapshotsRequireRefCounting => write!(
+				f,
+				"Pruning with snapshots requires a database with reference counting, such as ParityDb"
+			),
 		}
 	}
 }
@@ -235,6 +248,14 @@ pub enum PruningMode {
 	ArchiveAll,
 	/// Canonicalization discards non-canonical nodes. All the canonical nodes are kept in the DB.
 	ArchiveCanonical,
+	/// Maintain a pruning window, and keep the full state of every canonical block whose number is
+	/// a multiple of `snapshot_interval`.
+	ConstrainedWithSnapshots {
+		/// Constraints of the pruning window.
+		constraints: Constraints,
+		/// Interval between the blocks whose state is kept.
+		snapshot_interval: u32,
+	},
 }
 
 impl PruningMode {
@@ -243,11 +264,20 @@ impl PruningMode {
 		PruningMode::Constrained(Constraints { max_blocks: Some(n) })
 	}
 
+	/// Create a mode that keeps given number of blocks, and the state of every
+	/// `snapshot_interval`-th block.
+	pub fn blocks_pruning_with_snapshots(n: u32, snapshot_interval: u32) -> PruningMode {
+		PruningMode::ConstrainedWithSnapshots {
+			constraints: Constraints { max_blocks: Some(n) },
+			snapshot_interval,
+		}
+	}
+
 	/// Is this an archive (either ArchiveAll or ArchiveCanonical) pruning mode?
 	pub fn is_archive(&self) -> bool {
 		match *self {
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => true,
-			PruningMode::Constrained(_) => false,
+			PruningMode::Constrained(_) | PruningMode::ConstrainedWithSnapshots { .. } => false,
 		}
 	}
 
@@ -257,6 +287,7 @@ impl PruningMode {
 			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
 			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
 			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::ConstrainedWithSnapshots { .. } => PRUNING_MODE_CONSTRAINED_SNAPSHOTS,
 		}
 	}
 
@@ -319,6 +350,19 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
 			PruningMode::Constrained(Constraints { max_blocks }) =>
 				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
+			PruningMode::ConstrainedWithSnapshots {
+				constraints: Constraints { max_blocks },
+				snapshot_interval,
+			} => {
+				// Snapshots rely on deletions only removing one reference to a node.
+				if ref_counting {
+					return Err(StateDbError::SnapshotsRequireRefCounting.into())
+				}
+				Some(
+					RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?
+						.with_snapshots(snapshot_interval)?,
+				)
+			},
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -338,7 +382,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 				// write changes immediately
 				Ok(CommitSet { data: changeset, meta: Default::default() })
 			},
-			PruningMode::Constrained(_) | PruningMode::ArchiveCanonical => self
+			PruningMode::Constrained(_) |
+			PruningMode::ConstrainedWithSnapshots { .. } |
+			PruningMode::ArchiveCanonical => self
 				.non_canonical
 				.insert(hash, number, parent_hash, changeset)
 				.map_err(Into::into),
@@ -380,7 +426,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn is_pruned(&self, hash: &BlockHash, number: u64) -> IsPruned {
 		match self.mode {
 			PruningMode::ArchiveAll => IsPruned::NotPruned,
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ConstrainedWithSnapshots { .. } => {
 				if self
 					.non_canonical
 					.last_canonicalized_block_number()
@@ -408,8 +456,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	}
 
 	fn prune(&mut self, commit: &mut CommitSet<Key>) -> Result<(), Error<D::Error>> {
-		if let (&mut Some(ref mut pruning), PruningMode::Constrained(constraints)) =
-			(&mut self.pruning, &self.mode)
+		if let (
+			&mut Some(ref mut pruning),
+			PruningMode::Constrained(constraints) |
+			PruningMode::ConstrainedWithSnapshots { constraints, .. },
+		) = (&mut self.pruning, &self.mode)
 		{
 			loop {
 				if pruning.window_size() <= constraints.max_blocks.unwrap_or(0) as u64 {
@@ -442,16 +493,18 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	fn revert_one(&mut self) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.revert_one(),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ConstrainedWithSnapshots { .. } => self.non_canonical.revert_one(),
 		}
 	}
 
 	fn remove(&mut self, hash: &BlockHash) -> Option<CommitSet<Key>> {
 		match self.mode {
 			PruningMode::ArchiveAll => Some(CommitSet::default()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) =>
-				self.non_canonical.remove(hash),
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ConstrainedWithSnapshots { .. } => self.non_canonical.remove(hash),
 		}
 	}
 
@@ -461,7 +514,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 	{
 		match self.mode {
 			PruningMode::ArchiveAll => Ok(()),
-			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+			PruningMode::ArchiveCanonical |
+			PruningMode::Constrained(_) |
+			PruningMode::ConstrainedWithSnapshots { .. } => {
 				let have_block = self.non_canonical.have_block(hash) ||
 					self.pruning.as_ref().map_or_else(
 						|| hint(),
@@ -504,6 +559,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 
 	fn sync(&mut self) {
 		self.non_canonical.sync();
+		if let Some(pruning) = &mut self.pruning {
+			pruning.sync();
+		}
 	}
 
 	pub fn get<DB: NodeDb, Q: ?Sized>(
@@ -564,6 +622,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 
 			cs.meta.inserted.push((key, value));
 
+			if let PruningMode::ConstrainedWithSnapshots { snapshot_interval, .. } = selected_mode {
+				cs.meta
+					.inserted
+					.push((to_meta_key(SNAPSHOT_INTERVAL, &()), snapshot_interval.encode()));
+			}
+
 			cs
 		} else {
 			Default::default()
@@ -676,6 +740,18 @@ fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, E
 	if let Some(stored_mode) = db.get_meta(&meta_key_mode).map_err(Error::Db)? {
 		if let Some(mode) = PruningMode::from_id(&stored_mode) {
 			Ok(Some(mode))
+		} else if stored_mode == PRUNING_MODE_CONSTRAINED_SNAPSHOTS {
+			let meta_key_interval = to_meta_key(SNAPSHOT_INTERVAL, &());
+			match db.get_meta(&meta_key_interval).map_err(Error::Db)? {
+				Some(interval) => Ok(Some(PruningMode::ConstrainedWithSnapshots {
+					constraints: Default::default(),
+					snapshot_interval: u32::decode(&mut interval.as_slice())?,
+				})),
+				None => Err(StateDbError::Metadata(
+					"An existing StateDb keeping snapshots does not have SNAPSHOT_INTERVAL stored in its meta-data".into(),
+				)
+				.into()),
+			}
 		} else {
 			Err(StateDbError::Metadata(format!(
 				"Invalid value stored for PRUNING_MODE: {:02x?}",
@@ -698,6 +774,12 @@ fn choose_pruning_mode(
 			Ok(PruningMode::ArchiveCanonical),
 		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
 			Ok(PruningMode::Constrained(requested)),
+		// The states of the snapshots kept so far would be lost by changing the interval.
+		(
+			PruningMode::ConstrainedWithSnapshots { snapshot_interval: stored_interval, .. },
+			PruningMode::ConstrainedWithSnapshots { constraints, snapshot_interval },
+		) if stored_interval == snapshot_interval =>
+			Ok(PruningMode::ConstrainedWithSnapshots { constraints, snapshot_interval }),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
 }
@@ -847,6 +929,28 @@ mod tests {
 		assert!(db.data_eq(&make_db(&[1, 21, 3, 921, 922, 93, 94])));
 	}
 
+	#[test]
+	fn prune_window_0_keeps_snapshots() {
+		let (db, sdb) = make_test_db(PruningMode::blocks_pruning_with_snapshots(0, 2));
+		// The states of the genesis block and of block 2 are kept.
+		assert!(db.data_eq(&make_db(&[21, 3, 91, 921, 922, 93, 94])));
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(0), 0), IsPruned::MaybePruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::MaybePruned);
+		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(3), 3), IsPruned::Pruned);
+	}
+
+	#[test]
+	fn snapshots_require_ref_counting() {
+		let db = make_db(&[]);
+		let state_db_open_result: Result<(_, StateDb<H256, H256, TestDb>), _> =
+			StateDb::open(db, Some(PruningMode::blocks_pruning_with_snapshots(256, 2)), true, true);
+		assert!(matches!(
+			state_db_open_result,
+			Err(Error::StateDb(StateDbError::SnapshotsRequireRefCounting))
+		));
+	}
+
 	#[test]
 	fn detects_incompatible_mode() {
 		let mut db = make_db(&[]);
@@ -942,6 +1046,26 @@ mod tests {
 				Some(PruningMode::ArchiveCanonical),
 				Ok(PruningMode::ArchiveCanonical),
 			),
+			(
+				Some(PruningMode::blocks_pruning_with_snapshots(256, 2)),
+				None,
+				Ok(PruningMode::blocks_pruning_with_snapshots(256, 2)),
+			),
+			(
+				Some(PruningMode::blocks_pruning_with_snapshots(256, 2)),
+				Some(PruningMode::blocks_pruning_with_snapshots(128, 2)),
+				Ok(PruningMode::blocks_pruning_with_snapshots(128, 2)),
+			),
+			(
+				Some(PruningMode::blocks_pruning_with_snapshots(256, 2)),
+				Some(PruningMode::blocks_pruning_with_snapshots(256, 4)),
+				Err(()),
+			),
+			(
+				Some(PruningMode::blocks_pruning_with_snapshots(256, 2)),
+				Some(PruningMode::blocks_pruning(256)),
+				Err(()),
+			),
 		] {
 			check_stored_and_requested_mode_compatibility(created, reopened, expected);
 		}
@@ -23,6 +23,13 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! When snapshots are kept, a node deleted by a block after the latest snapshot is only deleted
+//! if a reference to it was inserted after that snapshot. Otherwise the node is part of the state
+//! of the snapshot and is kept. This relies on the database counting references, so that deleting
+//! a node only removes one of its references. The references inserted since the latest snapshot
+//! are counted in the journal, and the counts of a snapshot interval are removed along with the
+//! journal records of its blocks during the next interval.
 
 use crate::{
 	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError,
@@ -34,6 +41,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 pub(crate) const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
+const SNAPSHOT_INSERTED: &[u8] = b"snapshot_inserted";
 
 /// See module documentation.
 pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
@@ -42,6 +50,117 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
 	queue: DeathRowQueue<BlockHash, Key, D>,
 	/// Block number that is next to be pruned.
 	base: u64,
+	/// Snapshots kept by the window, if any.
+	snapshots: Option<Snapshots>,
+}
+
+/// Tracks the nodes which can be deleted without affecting the state of the latest snapshot.
+///
+/// The blocks pruned after a snapshot, up to and including the next snapshot, form its
+/// generation. The number of references to each node inserted by the pruned blocks of a
+/// generation, and not deleted yet, is stored in the journal under the number of the snapshot.
+struct Snapshots {
+	/// The state of every block whose number is a multiple of `interval` is kept.
+	interval: u64,
+	/// Reference counts written to the journal since the last sync, which may not be committed to
+	/// the database yet.
+	pending: HashMap<Vec<u8>, u32>,
+}
+
+impl Snapshots {
+	fn is_snapshot(&self, number: u64) -> bool {
+		number % self.interval == 0
+	}
+
+	/// The snapshot whose generation the pruned block `number` belongs to. The genesis block
+	/// doesn't belong to any generation.
+	fn generation(&self, number: u64) -> Option<u64> {
+		number.checked_sub(1).map(|parent| parent / self.interval * self.interval)
+	}
+
+	fn count_key<Key: Hash>(generation: u64, key: &Key) -> Vec<u8> {
+		to_meta_key(SNAPSHOT_INSERTED, &(generation, key.clone()))
+	}
+
+	fn count<D: MetaDb>(&self, db: &D, count_key: &[u8]) -> Result<u32, Error<D::Error>> {
+		if let Some(count) = self.pending.get(count_key) {
+			return Ok(*count)
+		}
+		match db.get_meta(count_key).map_err(Error::Db)? {
+			Some(count) => Ok(u32::decode(&mut count.as_slice())?),
+			None => Ok(0),
+		}
+	}
+
+	fn set_count<Key: Hash>(
+		&mut self,
+		count_key: Vec<u8>,
+		count: u32,
+		commit: &mut CommitSet<Key>,
+	) {
+		if count == 0 {
+			commit.meta.deleted.push(count_key.clone());
+		} else {
+			// Deletions are applied after insertions, so a removal of the count earlier in the
+			// same commit has to be dropped.
+			if self.pending.get(&count_key) == Some(&0) {
+				commit.meta.deleted.retain(|key| key != &count_key);
+			}
+			commit.meta.inserted.push((count_key.clone(), count.encode()));
+		}
+		self.pending.insert(count_key, count);
+	}
+
+	/// Count the references inserted by the pruned block `number`, and filter the nodes deleted by
+	/// it down to the ones not part of the state of the latest snapshot.
+	fn deletable<Key: Hash, D: MetaDb>(
+		&mut self,
+		db: &D,
+		number: u64,
+		inserted: Vec<Key>,
+		deleted: HashSet<Key>,
+		commit: &mut CommitSet<Key>,
+	) -> Result<Vec<Key>, Error<D::Error>> {
+		let Some(generation) = self.generation(number) else { return Ok(Vec::new()) };
+		for key in inserted {
+			let count_key = Self::count_key(generation, &key);
+			let count = self.count(db, &count_key)?;
+			self.set_count(count_key, count + 1, commit);
+		}
+		let mut deletable = Vec::new();
+		for key in deleted {
+			let count_key = Self::count_key(generation, &key);
+			match self.count(db, &count_key)? {
+				0 => {},
+				count => {
+					self.set_count(count_key, count - 1, commit);
+					deletable.push(key);
+				},
+			}
+		}
+		Ok(deletable)
+	}
+
+	/// Remove the journal record of the block pruned an interval before the pruned block `number`,
+	/// along with the reference counts of its generation which is over.
+	fn remove_expired<BlockHash: Hash, Key: Hash, D: MetaDb>(
+		&self,
+		db: &D,
+		number: u64,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<D::Error>> {
+		let Some(expired) = number.checked_sub(self.interval) else { return Ok(()) };
+		if let Some(generation) = self.generation(expired) {
+			if let Some(row) = load_death_row_from_db::<BlockHash, Key, D>(db, expired)? {
+				commit
+					.meta
+					.deleted
+					.extend(row.inserted.iter().map(|key| Self::count_key(generation, key)));
+			}
+		}
+		commit.meta.deleted.push(to_journal_key(expired));
+		Ok(())
+	}
 }
 
 /// `DeathRowQueue` used to keep track of blocks in the pruning window, there are two flavors:
@@ -136,7 +255,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				// cache.
 				if num == base + cache.len() as u64 && cache.len() < *cache_capacity {
 					trace!(target: LOG_TARGET, "Adding to DB backed cache {:?} (#{})", hash, num);
-					cache.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+					cache.push_back(DeathRow {
+						hash,
+						inserted,
+						deleted: deleted.into_iter().collect(),
+					});
 				}
 				*last = Some(num);
 			},
@@ -152,7 +275,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				for k in deleted.iter() {
 					death_index.insert(k.clone(), imported_block);
 				}
-				death_rows.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+				death_rows.push_back(DeathRow {
+					hash,
+					inserted: Vec::new(),
+					deleted: deleted.into_iter().collect(),
+				});
 			},
 		}
 	}
@@ -255,8 +382,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 	let journal_key = to_journal_key(block);
 	match db.get_meta(&journal_key).map_err(Error::Db)? {
 		Some(record) => {
-			let JournalRecord { hash, deleted, .. } = Decode::decode(&mut record.as_slice())?;
-			Ok(Some(DeathRow { hash, deleted: deleted.into_iter().collect() }))
+			let JournalRecord { hash, inserted, deleted } = Decode::decode(&mut record.as_slice())?;
+			Ok(Some(DeathRow { hash, inserted, deleted: deleted.into_iter().collect() }))
 		},
 		None => Ok(None),
 	}
@@ -265,6 +392,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct DeathRow<BlockHash: Hash, Key: Hash> {
 	hash: BlockHash,
+	/// Inserted keys, only tracked by the database-backed queue when snapshots are kept.
+	inserted: Vec<Key>,
 	deleted: HashSet<Key>,
 }
 
@@ -347,7 +476,26 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			DeathRowQueue::new_db_backed(db, base, last, window_size)?
 		};
 
-		Ok(RefWindow { queue, base })
+		Ok(RefWindow { queue, base, snapshots: None })
+	}
+
+	/// Keep the state of every block whose number is a multiple of `interval`.
+	///
+	/// Requires a database-backed queue, i.e. a database counting references.
+	pub fn with_snapshots(mut self, interval: u32) -> Result<Self, Error<D::Error>> {
+		if matches!(self.queue, DeathRowQueue::Mem { .. }) {
+			return Err(Error::StateDb(StateDbError::SnapshotsRequireRefCounting))
+		}
+		self.snapshots =
+			Some(Snapshots { interval: interval.max(1) as u64, pending: HashMap::new() });
+		Ok(self)
+	}
+
+	/// Notify the window that the commits returned so far were written to the database.
+	pub fn sync(&mut self) {
+		if let Some(snapshots) = self.snapshots.as_mut() {
+			snapshots.pending.clear();
+		}
 	}
 
 	pub fn window_size(&self) -> u64 {
@@ -374,6 +522,10 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 
 	// Check if a block is in the pruning window and not be pruned yet
 	pub fn have_block(&self, hash: &BlockHash, number: u64) -> HaveBlock {
+		// the state of a pruned snapshot block is kept if the block is canonical
+		if number < self.base && self.snapshots.as_ref().map_or(false, |s| s.is_snapshot(number)) {
+			return HaveBlock::Maybe
+		}
 		// if the queue is empty or the block number exceed the pruning window, we definitely
 		// do not have this block
 		if self.is_empty() || number < self.base || number >= self.base + self.window_size() {
@@ -387,9 +539,19 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		if let Some(pruned) = self.queue.pop_front(self.base)? {
 			trace!(target: LOG_TARGET, "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
 			let index = self.base;
-			commit.data.deleted.extend(pruned.deleted.into_iter());
+			match (self.snapshots.as_mut(), &self.queue) {
+				(Some(snapshots), DeathRowQueue::DbBacked { db, .. }) => {
+					let deletable =
+						snapshots.deletable(db, index, pruned.inserted, pruned.deleted, commit)?;
+					commit.data.deleted.extend(deletable);
+					snapshots.remove_expired::<BlockHash, _, _>(db, index, commit)?;
+				},
+				_ => {
+					commit.data.deleted.extend(pruned.deleted.into_iter());
+					commit.meta.deleted.push(to_journal_key(self.base));
+				},
+			}
 			commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), index.encode()));
-			commit.meta.deleted.push(to_journal_key(self.base));
 			self.base += 1;
 			Ok(())
 		} else {
@@ -424,11 +586,12 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			commit.data.inserted.len(),
 			commit.data.deleted.len(),
 		);
-		let inserted = if matches!(self.queue, DeathRowQueue::Mem { .. }) {
-			commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
-		} else {
-			Default::default()
-		};
+		let inserted =
+			if matches!(self.queue, DeathRowQueue::Mem { .. }) || self.snapshots.is_some() {
+				commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
+			} else {
+				Default::default()
+			};
 		let deleted = std::mem::take(&mut commit.data.deleted);
 		let journal_record = JournalRecord { hash: hash.clone(), inserted, deleted };
 		commit.meta.inserted.push((to_journal_key(number), journal_record.encode()));
@@ -439,11 +602,13 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 
 #[cfg(test)]
 mod tests {
-	use super::{to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow, LAST_PRUNED};
+	use super::{
+		to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow, Snapshots, LAST_PRUNED,
+	};
 	use crate::{
 		noncanonical::LAST_CANONICAL,
 		test::{make_commit, make_db, TestDb},
-		to_meta_key, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
+		to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
 	use codec::Encode;
 	use sp_core::H256;
@@ -907,4 +1072,85 @@ mod tests {
 			assert_eq!(HaveBlock::Yes, pruning.have_block(&block, block));
 		}
 	}
+
+	#[test]
+	fn snapshots_keep_inserted_references_across_restarts() {
+		let mut db = make_db(&[1]);
+		let mut pruning: RefWindow<H256, H256, TestDb> =
+			RefWindow::new(db.clone(), 0, false).unwrap().with_snapshots(2).unwrap();
+		let blocks: [(&[u64], &[u64]); 5] =
+			[(&[], &[]), (&[2], &[1]), (&[3], &[2]), (&[4], &[3]), (&[], &[])];
+		for (number, (inserted, deleted)) in blocks.into_iter().enumerate() {
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&H256::random(), number as u64, &mut commit).unwrap();
+			db.commit(&commit);
+		}
+		let count_key =
+			|generation, key| Snapshots::count_key(generation, &H256::from_low_u64_be(key));
+
+		// Node 1 is part of the state of the snapshot at block 0.
+		for _ in 0..2 {
+			let mut commit = CommitSet::default();
+			pruning.prune_one(&mut commit).unwrap();
+			db.commit(&commit);
+		}
+		assert!(db.data_eq(&make_db(&[1, 2, 3, 4])));
+		assert!(db.get_meta(&count_key(0, 2)).unwrap().is_some());
+
+		// Restart in between two snapshots.
+		let mut pruning: RefWindow<H256, H256, TestDb> =
+			RefWindow::new(db.clone(), 0, false).unwrap().with_snapshots(2).unwrap();
+		assert_eq!(pruning.base, 2);
+
+		// Node 2 was inserted after the snapshot, before the restart.
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit).unwrap();
+		db.commit(&commit);
+		assert!(db.data_eq(&make_db(&[1, 3, 4])));
+		assert!(db.get_meta(&count_key(0, 2)).unwrap().is_none());
+
+		// Node 3 is part of the state of the snapshot at block 2.
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit).unwrap();
+		db.commit(&commit);
+		assert!(db.data_eq(&make_db(&[1, 3, 4])));
+		assert!(db.get_meta(&to_journal_key(1)).unwrap().is_none());
+		assert!(db.get_meta(&to_journal_key(2)).unwrap().is_some());
+
+		// The counts of the generation of the snapshot at block 0 are removed with the journal
+		// records of its blocks.
+		assert!(db.get_meta(&count_key(0, 3)).unwrap().is_some());
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit).unwrap();
+		db.commit(&commit);
+		assert!(db.get_meta(&to_journal_key(2)).unwrap().is_none());
+		assert!(db.get_meta(&count_key(0, 3)).unwrap().is_none());
+		assert!(db.get_meta(&count_key(2, 4)).unwrap().is_some());
+	}
+
+	#[test]
+	fn snapshots_count_references_pruned_in_one_commit() {
+		let mut db = make_db(&[1]);
+		let mut pruning: RefWindow<H256, H256, TestDb> =
+			RefWindow::new(db.clone(), 0, false).unwrap().with_snapshots(8).unwrap();
+		let blocks: [(&[u64], &[u64]); 4] = [(&[], &[]), (&[2], &[1]), (&[], &[2]), (&[2], &[])];
+		for (number, (inserted, deleted)) in blocks.into_iter().enumerate() {
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&H256::random(), number as u64, &mut commit).unwrap();
+			db.commit(&commit);
+		}
+
+		// The count of node 2 drops to zero and is raised again by the last block.
+		let mut commit = CommitSet::default();
+		for _ in 0..4 {
+			pruning.prune_one(&mut commit).unwrap();
+		}
+		db.commit(&commit);
+		pruning.sync();
+		assert!(db.data_eq(&make_db(&[1])));
+		assert_eq!(
+			db.get_meta(&Snapshots::count_key(0, &H256::from_low_u64_be(2))).unwrap(),
+			Some(1u32.encode()),
+		);
+	}
 }
@@ -43,18 +43,37 @@
 //! explicitly with the `remove` function) the statement is marked as expired. Expired statements
 //! can't be added to the store for `Options::purge_after_sec` seconds. This is to prevent old
 //! statements from being propagated on the network.
+//!
+//! Statement time-to-live.
+//!
+//! If `Options::statement_ttl_sec` is set, statements are removed from the store and marked as
+//! expired once they have been in the store for that many seconds, regardless of their priority.
+//! The time-to-live deadlines are persisted and survive restarts.
+//!
+//! Quotas.
+//!
+//! The number and total data size of the statements submitted over a period of
+//! `Options::quota_period_sec` seconds can be limited per `StatementSource` with
+//! `Options::source_quotas` and per network peer with `Options::peer_quota`. Statements exceeding
+//! a quota are not validated and `Ignored` result is returned.
+//!
+//! The time-to-live and quotas may be configured from the command line with
+//! [`StatementStoreParams`].
 
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 
 mod metrics;
+mod params;
 
+pub use params::StatementStoreParams;
 pub use sp_statement_store::{Error, StatementStore, MAX_TOPICS};
 
 use metrics::MetricsLink as PrometheusMetrics;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use prometheus_endpoint::Registry as PrometheusRegistry;
 use sc_keystore::LocalKeystore;
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::{crypto::UncheckedFrom, hexdisplay::HexDisplay, traits::SpawnNamed, Decode, Encode};
@@ -64,10 +83,10 @@ use sp_statement_store::{
 		InvalidStatement, StatementSource, StatementStoreExt, ValidStatement, ValidateStatement,
 	},
 	AccountId, BlockHash, Channel, DecryptionKey, Hash, NetworkPriority, Proof, Result, Statement,
-	SubmitResult, Topic,
+	StatementStream, SubmitResult, Topic,
 };
 use std::{
-	collections::{BTreeMap, HashMap, HashSet},
+	collections::{BTreeMap, BTreeSet, HashMap, HashSet},
 	sync::Arc,
 };
 
@@ -79,10 +98,17 @@ const LOG_TARGET: &str = "statement-store";
 const DEFAULT_PURGE_AFTER_SEC: u64 = 2 * 24 * 60 * 60; //48h
 const DEFAULT_MAX_TOTAL_STATEMENTS: usize = 8192;
 const DEFAULT_MAX_TOTAL_SIZE: usize = 64 * 1024 * 1024;
+const DEFAULT_QUOTA_PERIOD_SEC: u64 = 60;
+const DEFAULT_MAX_TOPIC_METRICS: usize = 16;
+
+const TTL_KEY_PREFIX: &[u8] = b"ttl".as_slice();
 
 const MAINTENANCE_PERIOD: std::time::Duration = std::time::Duration::from_secs(30);
 
 mod col {
+	// Also holds the time-to-live deadlines, keyed by `TTL_KEY_PREFIX` and the statement hash.
+	// Their values start with `TTL_KEY_PREFIX` as well, since the iteration only exposes hashed
+	// keys.
 	pub const META: u8 = 0;
 	pub const STATEMENTS: u8 = 1;
 	pub const EXPIRED: u8 = 2;
@@ -127,16 +153,35 @@ struct StatementsForAccount {
 	data_size: usize,
 }
 
+/// Limit on the statements submitted over a quota period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+	/// Maximum number of statements.
+	pub max_count: usize,
+	/// Maximum total data size of the statements.
+	pub max_size: usize,
+}
+
 /// Store configuration
 pub struct Options {
 	/// Maximum statement allowed in the store. Once this limit is reached lower-priority
 	/// statements may be evicted.
-	max_total_statements: usize,
+	pub max_total_statements: usize,
 	/// Maximum total data size allowed in the store. Once this limit is reached lower-priority
 	/// statements may be evicted.
-	max_total_size: usize,
+	pub max_total_size: usize,
 	/// Number of seconds for which removed statements won't be allowed to be added back in.
-	purge_after_sec: u64,
+	pub purge_after_sec: u64,
+	/// Number of seconds after which statements are removed from the store. No limit if `None`.
+	pub statement_ttl_sec: Option<u64>,
+	/// Quotas on the statements submitted from each source over a quota period.
+	pub source_quotas: HashMap<StatementSource, Quota>,
+	/// Quota on the statements submitted by each network peer over a quota period.
+	pub peer_quota: Option<Quota>,
+	/// Length of the quota period in seconds.
+	pub quota_period_sec: u64,
+	/// Number of topics with the most statements that are reported in the per-topic metric.
+	pub max_topic_metrics: usize,
 }
 
 impl Default for Options {
@@ -145,10 +190,28 @@ impl Default for Options {
 			max_total_statements: DEFAULT_MAX_TOTAL_STATEMENTS,
 			max_total_size: DEFAULT_MAX_TOTAL_SIZE,
 			purge_after_sec: DEFAULT_PURGE_AFTER_SEC,
+			statement_ttl_sec: None,
+			source_quotas: HashMap::new(),
+			peer_quota: None,
+			quota_period_sec: DEFAULT_QUOTA_PERIOD_SEC,
+			max_topic_metrics: DEFAULT_MAX_TOPIC_METRICS,
 		}
 	}
 }
 
+#[derive(PartialEq, Eq, Hash)]
+enum QuotaKey {
+	Source(StatementSource),
+	Peer(Vec<u8>),
+}
+
+struct QuotaUsage {
+	// Start of the current quota period.
+	period_start: u64,
+	count: usize,
+	size: usize,
+}
+
 #[derive(Default)]
 struct Index {
 	by_topic: HashMap<Topic, HashSet<Hash>>,
@@ -156,11 +219,22 @@ struct Index {
 	topics_and_keys: HashMap<Hash, ([Option<Topic>; MAX_TOPICS], Option<DecryptionKey>)>,
 	entries: HashMap<Hash, (AccountId, Priority, usize)>,
 	expired: HashMap<Hash, u64>, // Value is expiration timestamp.
+	ttl: HashMap<Hash, u64>,     // Value is time-to-live deadline.
+	by_ttl: BTreeSet<(u64, Hash)>,
+	quota_usage: HashMap<QuotaKey, QuotaUsage>,
 	accounts: HashMap<AccountId, StatementsForAccount>,
 	options: Options,
 	total_size: usize,
 }
 
+fn ttl_key(hash: &Hash) -> Vec<u8> {
+	[TTL_KEY_PREFIX, hash.as_slice()].concat()
+}
+
+fn ttl_value(hash: &Hash, deadline: u64) -> Vec<u8> {
+	[TTL_KEY_PREFIX, &(hash, deadline).encode()].concat()
+}
+
 struct ClientWrapper<Block, Client> {
 	client: Arc<Client>,
 	_block: std::marker::PhantomData<Block>,
@@ -206,6 +280,8 @@ pub struct Store {
 	// Used for testing
 	time_override: Option<u64>,
 	metrics: PrometheusMetrics,
+	/// Senders of the statement streams, notified about new statements.
+	statement_sinks: Mutex<Vec<TracingUnboundedSender<Statement>>>,
 }
 
 enum IndexQuery {
@@ -264,6 +340,76 @@ impl Index {
 		self.expired.insert(hash, timestamp);
 	}
 
+	fn insert_ttl(&mut self, hash: Hash, deadline: u64) {
+		if let Some(previous) = self.ttl.insert(hash, deadline) {
+			self.by_ttl.remove(&(previous, hash));
+		}
+		self.by_ttl.insert((deadline, hash));
+	}
+
+	fn remove_ttl(&mut self, hash: &Hash) {
+		if let Some(deadline) = self.ttl.remove(hash) {
+			self.by_ttl.remove(&(deadline, *hash));
+		}
+	}
+
+	/// Account for a statement of `len` bytes submitted from `source`, or by `peer`. Returns
+	/// `false` without accounting for anything if a quota would be exceeded.
+	fn consume_quota(
+		&mut self,
+		source: StatementSource,
+		peer: Option<&[u8]>,
+		len: usize,
+		current_time: u64,
+	) -> bool {
+		let mut keys = Vec::with_capacity(2);
+		if let Some(quota) = self.options.source_quotas.get(&source) {
+			keys.push((QuotaKey::Source(source), *quota));
+		}
+		if let (Some(peer), Some(quota)) = (peer, self.options.peer_quota) {
+			keys.push((QuotaKey::Peer(peer.to_vec()), quota));
+		}
+
+		let period = self.options.quota_period_sec;
+		let within_quota = keys.iter().all(|(key, quota)| match self.quota_usage.get(key) {
+			Some(usage) if usage.period_start + period > current_time =>
+				usage.count < quota.max_count && usage.size + len <= quota.max_size,
+			_ => quota.max_count > 0 && len <= quota.max_size,
+		});
+		if !within_quota {
+			return false
+		}
+
+		for (key, _) in keys {
+			let usage = self.quota_usage.entry(key).or_insert(QuotaUsage {
+				period_start: current_time,
+				count: 0,
+				size: 0,
+			});
+			if usage.period_start + period <= current_time {
+				*usage = QuotaUsage { period_start: current_time, count: 0, size: 0 };
+			}
+			usage.count += 1;
+			usage.size += len;
+		}
+		true
+	}
+
+	/// Expire all statements whose time-to-live deadline has passed.
+	fn expire_ttl(&mut self, current_time: u64) -> Vec<Hash> {
+		let mut expired = Vec::new();
+		while let Some(&(deadline, hash)) = self.by_ttl.first() {
+			if deadline > current_time {
+				break
+			}
+			// Also removes the deadline from the time-to-live index.
+			self.make_expired(&hash, current_time);
+			log::trace!(target: LOG_TARGET, "Statement TTL elapsed {:?}", HexDisplay::from(&hash));
+			expired.push(hash);
+		}
+		expired
+	}
+
 	fn iterate_with(
 		&self,
 		key: Option<DecryptionKey>,
@@ -317,10 +463,14 @@ impl Index {
 				true
 			}
 		});
+		// Forget the usage of quota periods that have ended.
+		let period = self.options.quota_period_sec;
+		self.quota_usage.retain(|_, usage| usage.period_start + period > current_time);
 		purged
 	}
 
 	fn make_expired(&mut self, hash: &Hash, current_time: u64) -> bool {
+		self.remove_ttl(hash);
 		if let Some((account, priority, len)) = self.entries.remove(hash) {
 			self.total_size -= len;
 			if let Some((topics, key)) = self.topics_and_keys.remove(hash) {
@@ -470,6 +620,9 @@ impl Index {
 			self.make_expired(h, current_time);
 		}
 		self.insert_new(hash, *account, statement);
+		if let Some(ttl) = self.options.statement_ttl_sec {
+			self.insert_ttl(hash, current_time + ttl);
+		}
 		MaybeInserted::Inserted(evicted)
 	}
 }
@@ -573,6 +726,7 @@ impl Store {
 			keystore,
 			time_override: None,
 			metrics: PrometheusMetrics::new(prometheus),
+			statement_sinks: Default::default(),
 		};
 		store.populate()?;
 		Ok(store)
@@ -624,6 +778,34 @@ impl Store {
 					true
 				})
 				.map_err(|e| Error::Db(e.to_string()))?;
+			self.db
+				.iter_column_while(col::META, |item| {
+					// Skip other metadata, such as the database version.
+					let Some(mut ttl_info) = item.value.strip_prefix(TTL_KEY_PREFIX) else {
+						return true
+					};
+					if let Ok((hash, deadline)) = <(Hash, u64)>::decode(&mut ttl_info) {
+						if index.entries.contains_key(&hash) {
+							index.insert_ttl(hash, deadline);
+						}
+					}
+					true
+				})
+				.map_err(|e| Error::Db(e.to_string()))?;
+
+			// Statements stored before the time-to-live was configured start their time-to-live
+			// now.
+			if let Some(ttl) = index.options.statement_ttl_sec {
+				let deadline = self.timestamp() + ttl;
+				let missing: Vec<_> =
+					index.entries.keys().filter(|h| !index.ttl.contains_key(*h)).copied().collect();
+				let mut commit = Vec::with_capacity(missing.len());
+				for hash in missing {
+					index.insert_ttl(hash, deadline);
+					commit.push((col::META, ttl_key(&hash), Some(ttl_value(&hash, deadline))));
+				}
+				self.db.commit(commit).map_err(|e| Error::Db(e.to_string()))?;
+			}
 		}
 
 		self.maintain();
@@ -671,15 +853,43 @@ impl Store {
 	/// Perform periodic store maintenance
 	pub fn maintain(&self) {
 		log::trace!(target: LOG_TARGET, "Started store maintenance");
-		let deleted = self.index.write().maintain(self.timestamp());
-		let deleted: Vec<_> =
-			deleted.into_iter().map(|hash| (col::EXPIRED, hash.to_vec(), None)).collect();
+		let current_time = self.timestamp();
+		let (deleted, ttl_expired) = {
+			let mut index = self.index.write();
+			(index.maintain(current_time), index.expire_ttl(current_time))
+		};
 		let count = deleted.len() as u64;
-		if let Err(e) = self.db.commit(deleted) {
+		let ttl_count = ttl_expired.len() as u64;
+		let mut commit: Vec<_> =
+			deleted.into_iter().map(|hash| (col::EXPIRED, hash.to_vec(), None)).collect();
+		for hash in ttl_expired {
+			commit.push((col::STATEMENTS, hash.to_vec(), None));
+			commit.push((col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())));
+			commit.push((col::META, ttl_key(&hash), None));
+		}
+		if let Err(e) = self.db.commit(commit) {
 			log::warn!(target: LOG_TARGET, "Error writing to the statement database: {:?}", e);
 		} else {
-			self.metrics.report(|metrics| metrics.statements_pruned.inc_by(count));
+			self.metrics.report(|metrics| {
+				metrics.statements_pruned.inc_by(count);
+				metrics.statements_ttl_expired.inc_by(ttl_count);
+			});
 		}
+		self.metrics.report(|metrics| {
+			// Topics are chosen by submitters, so only the largest ones are reported to keep the
+			// number of label values bounded.
+			let index = self.index.read();
+			let mut by_topic: Vec<_> =
+				index.by_topic.iter().map(|(topic, hashes)| (hashes.len(), topic)).collect();
+			by_topic.sort_unstable_by(|a, b| b.cmp(a));
+			metrics.statements_by_topic.reset();
+			for (count, topic) in by_topic.into_iter().take(index.options.max_topic_metrics) {
+				metrics
+					.statements_by_topic
+					.with_label_values(&[&HexDisplay::from(topic).to_string()])
+					.set(count as u64);
+			}
+		});
 		log::trace!(
 			target: LOG_TARGET,
 			"Completed store maintenance. Purged: {}, Active: {}, Expired: {}",
@@ -703,6 +913,121 @@ impl Store {
 		self.time_override = Some(time);
 	}
 
+	/// Submit a statement to the store, received from `peer` if any. Validates the statement and
+	/// returns validation result.
+	fn submit_with_peer(
+		&self,
+		statement: Statement,
+		source: StatementSource,
+		peer: Option<&[u8]>,
+	) -> SubmitResult {
+		let hash = statement.hash();
+		match self.index.read().query(&hash) {
+			IndexQuery::Expired =>
+				if !source.can_be_resubmitted() {
+					return SubmitResult::KnownExpired
+				},
+			IndexQuery::Exists =>
+				if !source.can_be_resubmitted() {
+					return SubmitResult::Known
+				},
+			IndexQuery::Unknown => {},
+		}
+
+		if !self
+			.index
+			.write()
+			.consume_quota(source, peer, statement.data_len(), self.timestamp())
+		{
+			log::debug!(
+				target: LOG_TARGET,
+				"Ignored statement {:?} because a quota is exceeded",
+				HexDisplay::from(&hash),
+			);
+			self.metrics.report(|metrics| metrics.statements_over_quota.inc());
+			return SubmitResult::Ignored
+		}
+
+		let Some(account_id) = statement.account_id() else {
+			log::debug!(
+				target: LOG_TARGET,
+				"Statement validation failed: Missing proof ({:?})",
+				HexDisplay::from(&hash),
+			);
+			self.metrics.report(|metrics| metrics.validations_invalid.inc());
+			return SubmitResult::Bad("No statement proof")
+		};
+
+		// Validate.
+		let at_block = if let Some(Proof::OnChain { block_hash, .. }) = statement.proof() {
+			Some(*block_hash)
+		} else {
+			None
+		};
+		let validation_result = (self.validate_fn)(at_block, source, statement.clone());
+		let validation = match validation_result {
+			Ok(validation) => validation,
+			Err(InvalidStatement::BadProof) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Statement validation failed: BadProof, {:?}",
+					HexDisplay::from(&hash),
+				);
+				self.metrics.report(|metrics| metrics.validations_invalid.inc());
+				return SubmitResult::Bad("Bad statement proof")
+			},
+			Err(InvalidStatement::NoProof) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Statement validation failed: NoProof, {:?}",
+					HexDisplay::from(&hash),
+				);
+				self.metrics.report(|metrics| metrics.validations_invalid.inc());
+				return SubmitResult::Bad("Missing statement proof")
+			},
+			Err(InvalidStatement::InternalError) =>
+				return SubmitResult::InternalError(Error::Runtime),
+		};
+
+		let current_time = self.timestamp();
+		let mut commit = Vec::new();
+		{
+			let mut index = self.index.write();
+
+			let evicted =
+				match index.insert(hash, &statement, &account_id, &validation, current_time) {
+					MaybeInserted::Ignored => return SubmitResult::Ignored,
+					MaybeInserted::Inserted(evicted) => evicted,
+				};
+
+			commit.push((col::STATEMENTS, hash.to_vec(), Some(statement.encode())));
+			if let Some(deadline) = index.ttl.get(&hash) {
+				commit.push((col::META, ttl_key(&hash), Some(ttl_value(&hash, *deadline))));
+			}
+			for hash in evicted {
+				commit.push((col::STATEMENTS, hash.to_vec(), None));
+				commit.push((col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())));
+				commit.push((col::META, ttl_key(&hash), None));
+			}
+			if let Err(e) = self.db.commit(commit) {
+				log::debug!(
+					target: LOG_TARGET,
+					"Statement validation failed: database error {}, {:?}",
+					e,
+					statement
+				);
+				return SubmitResult::InternalError(Error::Db(e.to_string()))
+			}
+		} // Release index lock
+		self.metrics.report(|metrics| metrics.submitted_statements.inc());
+		self.statement_sinks
+			.lock()
+			.retain(|sink| sink.unbounded_send(statement.clone()).is_ok());
+		let network_priority = NetworkPriority::High;
+		log::trace!(target: LOG_TARGET, "Statement submitted: {:?}", HexDisplay::from(&hash));
+		SubmitResult::New(network_priority)
+	}
+
 	/// Returns `self` as [`StatementStoreExt`].
 	pub fn as_statement_store_ext(self: Arc<Self>) -> StatementStoreExt {
 		StatementStoreExt::new(self)
@@ -816,90 +1141,12 @@ impl StatementStore for Store {
 
 	/// Submit a statement to the store. Validates the statement and returns validation result.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult {
-		let hash = statement.hash();
-		match self.index.read().query(&hash) {
-			IndexQuery::Expired =>
-				if !source.can_be_resubmitted() {
-					return SubmitResult::KnownExpired
-				},
-			IndexQuery::Exists =>
-				if !source.can_be_resubmitted() {
-					return SubmitResult::Known
-				},
-			IndexQuery::Unknown => {},
-		}
-
-		let Some(account_id) = statement.account_id() else {
-			log::debug!(
-				target: LOG_TARGET,
-				"Statement validation failed: Missing proof ({:?})",
-				HexDisplay::from(&hash),
-			);
-			self.metrics.report(|metrics| metrics.validations_invalid.inc());
-			return SubmitResult::Bad("No statement proof")
-		};
-
-		// Validate.
-		let at_block = if let Some(Proof::OnChain { block_hash, .. }) = statement.proof() {
-			Some(*block_hash)
-		} else {
-			None
-		};
-		let validation_result = (self.validate_fn)(at_block, source, statement.clone());
-		let validation = match validation_result {
-			Ok(validation) => validation,
-			Err(InvalidStatement::BadProof) => {
-				log::debug!(
-					target: LOG_TARGET,
-					"Statement validation failed: BadProof, {:?}",
-					HexDisplay::from(&hash),
-				);
-				self.metrics.report(|metrics| metrics.validations_invalid.inc());
-				return SubmitResult::Bad("Bad statement proof")
-			},
-			Err(InvalidStatement::NoProof) => {
-				log::debug!(
-					target: LOG_TARGET,
-					"Statement validation failed: NoProof, {:?}",
-					HexDisplay::from(&hash),
-				);
-				self.metrics.report(|metrics| metrics.validations_invalid.inc());
-				return SubmitResult::Bad("Missing statement proof")
-			},
-			Err(InvalidStatement::InternalError) =>
-				return SubmitResult::InternalError(Error::Runtime),
-		};
-
-		let current_time = self.timestamp();
-		let mut commit = Vec::new();
-		{
-			let mut index = self.index.write();
-
-			let evicted =
-				match index.insert(hash, &statement, &account_id, &validation, current_time) {
-					MaybeInserted::Ignored => return SubmitResult::Ignored,
-					MaybeInserted::Inserted(evicted) => evicted,
-				};
+		self.submit_with_peer(statement, source, None)
+	}
 
-			commit.push((col::STATEMENTS, hash.to_vec(), Some(statement.encode())));
-			for hash in evicted {
-				commit.push((col::STATEMENTS, hash.to_vec(), None));
-				commit.push((col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())));
-			}
-			if let Err(e) = self.db.commit(commit) {
-				log::debug!(
-					target: LOG_TARGET,
-					"Statement validation failed: database error {}, {:?}",
-					e,
-					statement
-				);
-				return SubmitResult::InternalError(Error::Db(e.to_string()))
-			}
-		} // Release index lock
-		self.metrics.report(|metrics| metrics.submitted_statements.inc());
-		let network_priority = NetworkPriority::High;
-		log::trace!(target: LOG_TARGET, "Statement submitted: {:?}", HexDisplay::from(&hash));
-		SubmitResult::New(network_priority)
+	/// Submit a statement received from a network peer, applying the per-peer quota.
+	fn submit_from_peer(&self, statement: Statement, peer: &[u8]) -> SubmitResult {
+		self.submit_with_peer(statement, StatementSource::Network, Some(peer))
 	}
 
 	/// Remove a statement by hash.
@@ -911,6 +1158,7 @@ impl StatementStore for Store {
 				let commit = [
 					(col::STATEMENTS, hash.to_vec(), None),
 					(col::EXPIRED, hash.to_vec(), Some((hash, current_time).encode())),
+					(col::META, ttl_key(hash), None),
 				];
 				if let Err(e) = self.db.commit(commit) {
 					log::debug!(
@@ -925,11 +1173,17 @@ impl StatementStore for Store {
 		}
 		Ok(())
 	}
+
+	fn statement_stream(&self) -> StatementStream {
+		let (sink, stream) = tracing_unbounded("mpsc_statement_store_stream", 100_000);
+		self.statement_sinks.lock().push(sink);
+		Box::pin(stream)
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::Store;
+	use crate::{Options, Quota, Store};
 	use sc_keystore::Keystore;
 	use sp_core::Pair;
 	use sp_statement_store::{
@@ -1022,6 +1276,10 @@ mod tests {
 	}
 
 	fn test_store() -> (Store, tempfile::TempDir) {
+		test_store_with_options(Default::default())
+	}
+
+	fn test_store_with_options(options: Options) -> (Store, tempfile::TempDir) {
 		sp_tracing::init_for_tests();
 		let temp_dir = tempfile::Builder::new().tempdir().expect("Error creating test dir");
 
@@ -1029,7 +1287,7 @@ mod tests {
 		let mut path: std::path::PathBuf = temp_dir.path().into();
 		path.push("db");
 		let keystore = std::sync::Arc::new(sc_keystore::LocalKeystore::in_memory());
-		let store = Store::new(&path, Default::default(), client, keystore, None).unwrap();
+		let store = Store::new(&path, options, client, keystore, None).unwrap();
 		(store, temp_dir) // return order is important. Store must be dropped before TempDir
 	}
 
@@ -1293,4 +1551,131 @@ mod tests {
 		let posted_clear = store.posted_clear(&[], public.into()).unwrap();
 		assert_eq!(posted_clear, vec![plain]);
 	}
+
+	#[test]
+	fn statement_stream_receives_new_statements() {
+		let (store, _temp) = test_store();
+		let mut stream = futures::executor::block_on_stream(store.statement_stream());
+		let statement0 = signed_statement(0);
+		let statement1 = signed_statement(1);
+		store.submit(statement0.clone(), StatementSource::Network);
+		// Known statements are not notified again.
+		assert_eq!(store.submit(statement0.clone(), StatementSource::Network), SubmitResult::Known);
+		store.submit(statement1.clone(), StatementSource::Network);
+
+		assert_eq!(stream.next(), Some(statement0));
+		assert_eq!(stream.next(), Some(statement1));
+	}
+
+	#[test]
+	fn source_quota_limits_submissions() {
+		let mut options = Options::default();
+		options
+			.source_quotas
+			.insert(StatementSource::Network, Quota { max_count: 2, max_size: 1000 });
+		let (mut store, _temp) = test_store_with_options(options);
+		store.set_time(0);
+		let new = SubmitResult::New(NetworkPriority::High);
+		assert_eq!(store.submit(signed_statement(0), StatementSource::Network), new);
+		assert_eq!(store.submit(signed_statement(1), StatementSource::Network), new);
+		assert_eq!(
+			store.submit(signed_statement(2), StatementSource::Network),
+			SubmitResult::Ignored
+		);
+		// Other sources are not limited.
+		assert_eq!(store.submit(signed_statement(2), StatementSource::Local), new);
+
+		// The quota is renewed in the next period.
+		store.set_time(super::DEFAULT_QUOTA_PERIOD_SEC);
+		assert_eq!(store.submit(signed_statement(3), StatementSource::Network), new);
+	}
+
+	#[test]
+	fn peer_quota_limits_submissions() {
+		let options = Options {
+			peer_quota: Some(Quota { max_count: 10, max_size: 150 }),
+			..Default::default()
+		};
+		let (mut store, _temp) = test_store_with_options(options);
+		store.set_time(0);
+		let new = SubmitResult::New(NetworkPriority::High);
+		assert_eq!(store.submit_from_peer(statement(1, 1, None, 100), b"peer1"), new);
+		assert_eq!(
+			store.submit_from_peer(statement(2, 1, None, 100), b"peer1"),
+			SubmitResult::Ignored
+		);
+		assert_eq!(store.submit_from_peer(statement(2, 1, None, 100), b"peer2"), new);
+		// Submissions which are not from a peer are not limited.
+		assert_eq!(store.submit(statement(3, 1, None, 100), StatementSource::Network), new);
+	}
+
+	#[test]
+	fn statements_expire_after_ttl() {
+		let options = Options { statement_ttl_sec: Some(10), ..Default::default() };
+		let (mut store, temp) = test_store_with_options(options);
+		store.set_time(0);
+		let statement0 = signed_statement(0);
+		store.submit(statement0.clone(), StatementSource::Network);
+		store.set_time(5);
+		let statement1 = signed_statement(1);
+		store.submit(statement1.clone(), StatementSource::Network);
+
+		store.set_time(10);
+		store.maintain();
+		assert_eq!(store.statements().unwrap(), vec![(statement1.hash(), statement1.clone())]);
+		assert!(store.index.read().expired.contains_key(&statement0.hash()));
+		assert_eq!(store.submit(statement0, StatementSource::Network), SubmitResult::KnownExpired);
+
+		// The deadline is persisted, so the statement is not given a new time-to-live on restart.
+		let keystore = store.keystore.clone();
+		drop(store);
+		let client = std::sync::Arc::new(TestClient);
+		let mut path: std::path::PathBuf = temp.path().into();
+		path.push("db");
+		let options = Options { statement_ttl_sec: Some(10), ..Default::default() };
+		let store = Store::new(&path, options, client, keystore, None).unwrap();
+		assert_eq!(store.statements().unwrap().len(), 0);
+		assert!(store.index.read().expired.contains_key(&statement1.hash()));
+	}
+
+	#[test]
+	fn only_largest_topics_are_reported() {
+		sp_tracing::init_for_tests();
+		let temp_dir = tempfile::Builder::new().tempdir().expect("Error creating test dir");
+		let mut path: std::path::PathBuf = temp_dir.path().into();
+		path.push("db");
+		let registry = prometheus_endpoint::Registry::new();
+		let options = Options { max_topic_metrics: 1, ..Default::default() };
+		let store = Store::new(
+			&path,
+			options,
+			std::sync::Arc::new(TestClient),
+			std::sync::Arc::new(sc_keystore::LocalKeystore::in_memory()),
+			Some(&registry),
+		)
+		.unwrap();
+		store.submit(signed_statement_with_topics(1, &[topic(0)], None), StatementSource::Network);
+		store.submit(
+			signed_statement_with_topics(2, &[topic(0), topic(1)], None),
+			StatementSource::Network,
+		);
+		store.maintain();
+
+		let by_topic = registry
+			.gather()
+			.into_iter()
+			.find(|family| family.get_name() == "substrate_sub_statement_store_statements_by_topic")
+			.unwrap();
+		let reported: Vec<_> = by_topic
+			.get_metric()
+			.iter()
+			.map(|metric| {
+				(metric.get_label()[0].get_value().to_string(), metric.get_gauge().get_value())
+			})
+			.collect();
+		assert_eq!(
+			reported,
+			vec![(sp_core::hexdisplay::HexDisplay::from(&topic(0)).to_string(), 2.0)]
+		);
+	}
 }
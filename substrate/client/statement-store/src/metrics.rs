@@ -20,7 +20,7 @@
 
 use std::sync::Arc;
 
-use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use prometheus_endpoint::{register, Counter, GaugeVec, Opts, PrometheusError, Registry, U64};
 
 #[derive(Clone, Default)]
 pub struct MetricsLink(Arc<Option<Metrics>>);
@@ -48,6 +48,9 @@ pub struct Metrics {
 	pub submitted_statements: Counter<U64>,
 	pub validations_invalid: Counter<U64>,
 	pub statements_pruned: Counter<U64>,
+	pub statements_over_quota: Counter<U64>,
+	pub statements_ttl_expired: Counter<U64>,
+	pub statements_by_topic: GaugeVec<U64>,
 }
 
 impl Metrics {
@@ -74,6 +77,30 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			statements_over_quota: register(
+				Counter::new(
+					"substrate_sub_statement_store_over_quota",
+					"Total number of statements that were ignored because a quota was exceeded",
+				)?,
+				registry,
+			)?,
+			statements_ttl_expired: register(
+				Counter::new(
+					"substrate_sub_statement_store_ttl_expired",
+					"Total number of statements that were removed because their time-to-live elapsed",
+				)?,
+				registry,
+			)?,
+			statements_by_topic: register(
+				GaugeVec::new(
+					Opts::new(
+						"substrate_sub_statement_store_statements_by_topic",
+						"Number of statements in the store, per topic",
+					),
+					&["topic"],
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
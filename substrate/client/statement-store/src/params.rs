@@ -0,0 +1,135 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Command line parameters of the statement store.
+
+use crate::{
+	Options, Quota, DEFAULT_MAX_TOPIC_METRICS, DEFAULT_MAX_TOTAL_SIZE,
+	DEFAULT_MAX_TOTAL_STATEMENTS, DEFAULT_PURGE_AFTER_SEC, DEFAULT_QUOTA_PERIOD_SEC,
+};
+use clap::Args;
+use sp_statement_store::runtime_api::StatementSource;
+use std::collections::HashMap;
+
+/// Parameters used to configure the statement store.
+#[derive(Debug, Clone, Args)]
+pub struct StatementStoreParams {
+	/// Number of seconds after which statements are removed from the statement store.
+	///
+	/// Statements are kept until evicted by higher priority statements if not set.
+	#[arg(long = "statement-store-ttl", value_name = "SECONDS")]
+	pub statement_ttl_sec: Option<u64>,
+
+	/// Limit on the statements received from the network over a quota period, given as
+	/// `COUNT:SIZE`.
+	#[arg(long = "statement-store-network-quota", value_name = "COUNT:SIZE", value_parser = parse_quota)]
+	pub network_quota: Option<Quota>,
+
+	/// Limit on the statements submitted locally (e.g. over RPC) over a quota period, given as
+	/// `COUNT:SIZE`.
+	#[arg(long = "statement-store-local-quota", value_name = "COUNT:SIZE", value_parser = parse_quota)]
+	pub local_quota: Option<Quota>,
+
+	/// Limit on the statements received from each network peer over a quota period, given as
+	/// `COUNT:SIZE`.
+	#[arg(long = "statement-store-peer-quota", value_name = "COUNT:SIZE", value_parser = parse_quota)]
+	pub peer_quota: Option<Quota>,
+
+	/// Length of the statement store quota period.
+	#[arg(long = "statement-store-quota-period", value_name = "SECONDS", default_value_t = DEFAULT_QUOTA_PERIOD_SEC, value_parser = clap::value_parser!(u64).range(1..))]
+	pub quota_period_sec: u64,
+
+	/// Number of topics with the most statements that are reported in the per-topic metric.
+	#[arg(long = "statement-store-max-topic-metrics", value_name = "COUNT", default_value_t = DEFAULT_MAX_TOPIC_METRICS)]
+	pub max_topic_metrics: usize,
+}
+
+impl StatementStoreParams {
+	/// Returns the statement store options.
+	pub fn options(&self) -> Options {
+		let mut source_quotas = HashMap::new();
+		if let Some(quota) = self.network_quota {
+			source_quotas.insert(StatementSource::Network, quota);
+		}
+		if let Some(quota) = self.local_quota {
+			source_quotas.insert(StatementSource::Local, quota);
+		}
+
+		Options {
+			max_total_statements: DEFAULT_MAX_TOTAL_STATEMENTS,
+			max_total_size: DEFAULT_MAX_TOTAL_SIZE,
+			purge_after_sec: DEFAULT_PURGE_AFTER_SEC,
+			statement_ttl_sec: self.statement_ttl_sec,
+			source_quotas,
+			peer_quota: self.peer_quota,
+			quota_period_sec: self.quota_period_sec,
+			max_topic_metrics: self.max_topic_metrics,
+		}
+	}
+}
+
+fn parse_quota(s: &str) -> Result<Quota, String> {
+	let (max_count, max_size) =
+		s.split_once(':').ok_or_else(|| format!("Expected `COUNT:SIZE`, got `{s}`"))?;
+	Ok(Quota {
+		max_count: max_count.parse().map_err(|e| format!("Invalid statement count: {e}"))?,
+		max_size: max_size.parse().map_err(|e| format!("Invalid statement size: {e}"))?,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[clap(flatten)]
+		params: StatementStoreParams,
+	}
+
+	#[test]
+	fn quotas_are_parsed() {
+		let options = Cli::parse_from([
+			"node",
+			"--statement-store-network-quota",
+			"10:1024",
+			"--statement-store-peer-quota",
+			"2:256",
+			"--statement-store-ttl",
+			"600",
+		])
+		.params
+		.options();
+
+		assert_eq!(
+			options.source_quotas,
+			[(StatementSource::Network, Quota { max_count: 10, max_size: 1024 })].into(),
+		);
+		assert_eq!(options.peer_quota, Some(Quota { max_count: 2, max_size: 256 }));
+		assert_eq!(options.statement_ttl_sec, Some(600));
+		assert_eq!(options.quota_period_sec, DEFAULT_QUOTA_PERIOD_SEC);
+	}
+
+	#[test]
+	fn invalid_quota_is_rejected() {
+		assert!(parse_quota("10").is_err());
+		assert!(parse_quota("ten:1024").is_err());
+		assert!(Cli::try_parse_from(["node", "--statement-store-quota-period", "0"]).is_err());
+	}
+}
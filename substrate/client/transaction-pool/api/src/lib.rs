@@ -294,6 +294,23 @@ pub trait TransactionPool: Send + Sync {
 		>,
 	>;
 
+	/// Get an iterator for ready transactions ordered by priority, as seen at the given block.
+	///
+	/// Pools which don't track forks may return the ready transactions of their best block, which
+	/// is what the default implementation does.
+	fn ready_at_block(
+		&self,
+		_at: <Self::Block as BlockT>::Hash,
+	) -> Pin<
+		Box<
+			dyn Future<
+					Output = Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>,
+				> + Send,
+		>,
+	> {
+		Box::pin(futures::future::ready(self.ready()))
+	}
+
 	/// Get an iterator for ready transactions ordered by priority.
 	fn ready(&self) -> Box<dyn ReadyTransactions<Item = Arc<Self::InPoolTransaction>> + Send>;
 
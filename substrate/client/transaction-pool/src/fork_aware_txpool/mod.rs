@@ -0,0 +1,754 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fork-aware transaction pool.
+//!
+//! The [`BasicPool`](crate::BasicPool) keeps a single pool, validated against the best block,
+//! and drops the transactions which are only valid on a competing fork. The fork-aware pool
+//! instead keeps a view of the pool for every tracked fork: each view holds the transactions
+//! validated against the state of its block.
+//!
+//! All the submitted transactions are kept in a memory pool, from which new views are populated.
+//! Views are created for new best blocks, and on demand when the ready transactions at a tracked
+//! block are requested. A transaction leaves the pool once it's finalized, or once no view
+//! considers it valid anymore. On every finalized block, a batch of the memory pool is
+//! revalidated at the finalized block and the transactions found invalid are removed from all
+//! the views, along with the transactions beyond their longevity. The status streams of the
+//! watched transactions are aggregated over all the views.
+
+mod multi_view_listener;
+mod tx_mem_pool;
+mod view;
+mod view_store;
+
+use crate::{
+	api::FullChainApi,
+	graph::{self, ExtrinsicHash, IsValidator, ValidatedTransaction},
+	metrics::MetricsLink as PrometheusMetrics,
+	PolledIterator, ReadyIteratorFor, ReadyPoll, LOG_TARGET,
+};
+use async_trait::async_trait;
+use futures::{channel::mpsc::Sender, future, prelude::*};
+use multi_view_listener::MultiViewListener;
+use parking_lot::Mutex;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	error::Error as TxPoolError, ChainEvent, ImportNotificationStream, MaintainedTransactionPool,
+	PoolFuture, PoolStatus, TransactionFor, TransactionPool, TransactionSource,
+	TransactionStatusStreamFor, TxHash,
+};
+use sp_blockchain::HashAndNumber;
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, NumberFor, Zero},
+	transaction_validity::TransactionValidityError,
+};
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+use tx_mem_pool::TxMemPool;
+use view::View;
+use view_store::ViewStore;
+
+/// Maximal number of transactions of the memory pool revalidated on every finalized block.
+const MEMPOOL_REVALIDATION_BATCH_SIZE: usize = 1000;
+
+/// A fork-aware transaction pool for a full node.
+pub type ForkAwareFullPool<Block, Client> = ForkAwareTxPool<FullChainApi<Client, Block>, Block>;
+
+/// Fork-aware implementation of transaction pool that can be customized by providing PoolApi.
+pub struct ForkAwareTxPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: graph::ChainApi<Block = Block>,
+{
+	api: Arc<PoolApi>,
+	mempool: Arc<TxMemPool<PoolApi>>,
+	view_store: Arc<ViewStore<PoolApi>>,
+	listener: Arc<MultiViewListener<PoolApi>>,
+	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
+	import_notification_sinks: Arc<Mutex<Vec<Sender<ExtrinsicHash<PoolApi>>>>>,
+	metrics: PrometheusMetrics,
+}
+
+impl<PoolApi, Block> ForkAwareTxPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: graph::ChainApi<Block = Block> + 'static,
+{
+	/// Create new fork-aware transaction pool with provided api, for tests.
+	pub fn new_test(
+		pool_api: Arc<PoolApi>,
+		best_block_hash: Block::Hash,
+		finalized_hash: Block::Hash,
+		options: graph::Options,
+	) -> Self {
+		let block = |hash| HashAndNumber {
+			hash,
+			number: pool_api
+				.block_id_to_number(&BlockId::Hash(hash))
+				.ok()
+				.flatten()
+				.unwrap_or_else(Zero::zero),
+		};
+		let (best_block, finalized_block) = (block(best_block_hash), block(finalized_hash));
+		Self::new(options, true.into(), pool_api, None, best_block, finalized_block)
+	}
+
+	/// Create new fork-aware transaction pool with provided api, tracking `best_block`.
+	pub fn new(
+		options: graph::Options,
+		is_validator: IsValidator,
+		pool_api: Arc<PoolApi>,
+		prometheus: Option<&PrometheusRegistry>,
+		best_block: HashAndNumber<Block>,
+		finalized_block: HashAndNumber<Block>,
+	) -> Self {
+		let max_transactions_count = options.ready.count + options.future.count;
+		Self {
+			mempool: Arc::new(TxMemPool::new(max_transactions_count)),
+			view_store: Arc::new(ViewStore::new(
+				pool_api.clone(),
+				options,
+				is_validator,
+				best_block.clone(),
+				finalized_block,
+			)),
+			listener: Arc::new(MultiViewListener::new()),
+			ready_poll: Arc::new(Mutex::new(ReadyPoll::new(best_block.number))),
+			import_notification_sinks: Default::default(),
+			metrics: PrometheusMetrics::new(prometheus),
+			api: pool_api,
+		}
+	}
+
+	/// Get access to the underlying api
+	pub fn api(&self) -> &PoolApi {
+		&self.api
+	}
+
+	/// Returns the number of transactions in the memory pool, over all the views.
+	pub fn mempool_len(&self) -> usize {
+		self.mempool.len()
+	}
+
+	/// Returns the views transactions should be submitted to, which are the active views.
+	///
+	/// Creates a view at `at` if no view is active.
+	async fn views_for_submission(
+		api: &PoolApi,
+		view_store: &ViewStore<PoolApi>,
+		mempool: &TxMemPool<PoolApi>,
+		listener: &MultiViewListener<PoolApi>,
+		at: Block::Hash,
+	) -> Result<Vec<Arc<View<PoolApi>>>, PoolApi::Error> {
+		let views = view_store.active_views();
+		if !views.is_empty() {
+			return Ok(views)
+		}
+
+		let number = api
+			.block_id_to_number(&BlockId::Hash(at))?
+			.ok_or_else(|| TxPoolError::InvalidBlockId(format!("{:?}", at)))?;
+		let view = view_store
+			.get_or_create_view(HashAndNumber { hash: at, number }, mempool, listener)
+			.await;
+		Ok(vec![view])
+	}
+
+	/// Notifies the import notification streams about a new transaction.
+	fn notify_import(
+		sinks: &Mutex<Vec<Sender<ExtrinsicHash<PoolApi>>>>,
+		hash: ExtrinsicHash<PoolApi>,
+	) {
+		sinks.lock().retain_mut(|sink| match sink.try_send(hash) {
+			Ok(()) => true,
+			Err(e) =>
+				if e.is_full() {
+					log::warn!(
+						target: LOG_TARGET,
+						"[{:?}] Trying to notify an import but the channel is full",
+						hash,
+					);
+					true
+				} else {
+					false
+				},
+		});
+	}
+
+	/// Returns the ready transactions of the given view, or none if there is no view.
+	fn ready_of(view: Option<Arc<View<PoolApi>>>) -> ReadyIteratorFor<PoolApi> {
+		match view {
+			Some(view) => Box::new(view.pool.validated_pool().ready()),
+			None => Box::new(std::iter::empty()),
+		}
+	}
+
+	/// Creates a view at the new best block and reports the retracted blocks.
+	async fn handle_new_best_block(&self, hash: Block::Hash) {
+		let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+			Ok(Some(number)) => number,
+			Ok(None) => {
+				log::debug!(target: LOG_TARGET, "Could not find number of block {:?}.", hash);
+				return
+			},
+			Err(e) => {
+				log::debug!(target: LOG_TARGET, "Error retrieving number of {:?}: {}", hash, e);
+				return
+			},
+		};
+		let previous_best = self.view_store.most_recent_view().map(|view| view.at.hash);
+
+		let view = self
+			.view_store
+			.get_or_create_view(HashAndNumber { hash, number }, &self.mempool, &self.listener)
+			.await;
+		self.view_store.activate_best_view(view.clone());
+
+		if let Some(previous_best) = previous_best.filter(|previous| *previous != hash) {
+			match self.api.tree_route(previous_best, hash) {
+				Ok(tree_route) =>
+					for retracted in tree_route.retracted() {
+						self.listener.transactions_retracted(retracted.hash);
+					},
+				Err(e) => log::debug!(
+					target: LOG_TARGET,
+					"Error computing tree route from {:?} to {:?}: {}",
+					previous_best,
+					hash,
+					e,
+				),
+			}
+		}
+
+		self.ready_poll
+			.lock()
+			.trigger(number, move || Box::new(view.pool.validated_pool().ready()));
+
+		self.purge_transactions();
+	}
+
+	/// Reports the finalized transactions and removes the views made obsolete by finality.
+	async fn handle_finalized(&self, hash: Block::Hash, tree_route: &[Block::Hash]) {
+		for block in tree_route.iter().chain(std::iter::once(&hash)) {
+			let extrinsics = self
+				.api
+				.block_body(*block)
+				.await
+				.unwrap_or_else(|e| {
+					log::warn!(target: LOG_TARGET, "Failed to fetch block body: {}", e);
+					None
+				})
+				.unwrap_or_default();
+			let finalized = extrinsics
+				.iter()
+				.enumerate()
+				.map(|(index, xt)| (index, self.api.hash_and_length(xt).0))
+				.filter(|(_, tx_hash)| self.mempool.contains(tx_hash))
+				.collect::<Vec<_>>();
+			for (index, tx_hash) in &finalized {
+				self.listener.transaction_finalized(*tx_hash, *block, *index);
+			}
+			self.mempool.remove(finalized.iter().map(|(_, tx_hash)| tx_hash));
+		}
+
+		let number = match self.api.block_id_to_number(&BlockId::Hash(hash)) {
+			Ok(Some(number)) => number,
+			_ => {
+				log::debug!(target: LOG_TARGET, "Could not find number of block {:?}.", hash);
+				return
+			},
+		};
+		self.mempool.prune_inclusions(number);
+		for removed in self.view_store.handle_finalized(HashAndNumber { hash, number }) {
+			log::trace!(target: LOG_TARGET, "Removed view at {:?}", removed);
+			self.listener.remove_view(removed);
+		}
+
+		self.revalidate(HashAndNumber { hash, number }).await;
+		self.purge_transactions();
+	}
+
+	/// Revalidates a batch of the memory pool at the `finalized` block.
+	///
+	/// The transactions found invalid are removed from all the views, as no view descending from
+	/// the finalized block can accept them anymore. The transactions beyond their longevity are
+	/// removed from the views as well.
+	async fn revalidate(&self, finalized: HashAndNumber<Block>) {
+		let batch = self.mempool.transactions_to_revalidate(MEMPOOL_REVALIDATION_BATCH_SIZE);
+		let results = future::join_all(batch.iter().map(|(_, source, xt)| {
+			self.api.validate_transaction(finalized.hash, *source, xt.clone())
+		}))
+		.await;
+		self.mempool
+			.note_revalidated(batch.iter().map(|(hash, ..)| hash), finalized.number);
+
+		let invalid = batch
+			.iter()
+			.zip(results)
+			.filter_map(|((hash, ..), result)| match result {
+				Ok(Ok(_)) | Ok(Err(TransactionValidityError::Unknown(_))) => None,
+				Ok(Err(TransactionValidityError::Invalid(e))) => {
+					log::debug!(target: LOG_TARGET, "[{:?}]: Revalidation: invalid {:?}", hash, e);
+					Some(*hash)
+				},
+				Err(e) => {
+					log::debug!(
+						target: LOG_TARGET,
+						"[{:?}]: Removing due to error during revalidation: {}",
+						hash,
+						e,
+					);
+					Some(*hash)
+				},
+			})
+			.collect::<Vec<_>>();
+		if !invalid.is_empty() {
+			self.remove_invalid(&invalid);
+		}
+
+		for view in self.view_store.views() {
+			if let Err(e) = view.pool.validated_pool().clear_stale(&BlockId::Hash(finalized.hash)) {
+				log::debug!(target: LOG_TARGET, "Error clearing stale transactions: {}", e);
+			}
+		}
+	}
+
+	/// Removes the transactions which no view holds and which aren't included in any tracked
+	/// block.
+	fn purge_transactions(&self) {
+		let removed = self.mempool.retain(|tx_hash, tx| {
+			!tx.included_in.is_empty() || self.view_store.is_imported(tx_hash)
+		});
+		if !removed.is_empty() {
+			log::debug!(target: LOG_TARGET, "Removed transactions: {:?}", removed);
+			self.metrics
+				.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+			self.listener.remove_transactions(&removed);
+		}
+	}
+}
+
+impl<PoolApi, Block> TransactionPool for ForkAwareTxPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: 'static + graph::ChainApi<Block = Block>,
+{
+	type Block = PoolApi::Block;
+	type Hash = graph::ExtrinsicHash<PoolApi>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = PoolApi::Error;
+
+	fn submit_at(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		let api = self.api.clone();
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let listener = self.listener.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		self.metrics
+			.report(|metrics| metrics.submitted_transactions.inc_by(xts.len() as u64));
+
+		async move {
+			let views =
+				Self::views_for_submission(&api, &view_store, &mempool, &listener, at).await?;
+
+			// Transactions which can't enter the memory pool are dropped right away.
+			let is_full = mempool.is_full();
+			let mut results = Vec::with_capacity(xts.len());
+			let mut to_submit = Vec::new();
+			for xt in xts {
+				let hash = api.hash_and_length(&xt).0;
+				if is_full && !mempool.contains(&hash) {
+					results.push(Some(Err(TxPoolError::ImmediatelyDropped.into())));
+				} else {
+					results.push(None);
+					to_submit.push((hash, xt));
+				}
+			}
+
+			let view_results =
+				future::join_all(views.iter().map(|view| {
+					view.submit_many(source, to_submit.iter().map(|(_, xt)| xt.clone()))
+				}))
+				.await;
+			let mut outcomes = to_submit.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+			for (view, view_result) in views.iter().zip(view_results) {
+				match view_result {
+					Ok(view_result) =>
+						for (outcome, result) in outcomes.iter_mut().zip(view_result) {
+							outcome.push((view.at.hash, result));
+						},
+					Err(e) => log::debug!(
+						target: LOG_TARGET,
+						"Error submitting transactions to view at {:?}: {}",
+						view.at,
+						e,
+					),
+				}
+			}
+
+			let mut submitted = outcomes.into_iter().zip(to_submit);
+			Ok(results
+				.into_iter()
+				.map(|result| {
+					result.unwrap_or_else(|| {
+						let (outcome, (hash, xt)) =
+							submitted.next().expect("One outcome per submitted transaction; qed");
+						let result = reduce_view_results(outcome, at);
+						if result.is_ok() && mempool.push(hash, source, xt, false) {
+							Self::notify_import(&sinks, hash);
+						}
+						result
+					})
+				})
+				.collect())
+		}
+		.boxed()
+	}
+
+	fn submit_one(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		let submit = self.submit_at(at, source, vec![xt]);
+
+		async move {
+			let result = submit.await?.pop();
+			result.expect("One extrinsic passed; one result returned; qed")
+		}
+		.boxed()
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: <Self::Block as BlockT>::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		let api = self.api.clone();
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let listener = self.listener.clone();
+		let sinks = self.import_notification_sinks.clone();
+
+		self.metrics.report(|metrics| metrics.submitted_transactions.inc());
+
+		async move {
+			let hash = api.hash_and_length(&xt).0;
+			if mempool.is_full() && !mempool.contains(&hash) {
+				return Err(TxPoolError::ImmediatelyDropped.into())
+			}
+			let views =
+				Self::views_for_submission(&api, &view_store, &mempool, &listener, at).await?;
+			let Some(external_watcher) = listener.create_external_watcher_for_tx(hash) else {
+				return Err(TxPoolError::AlreadyImported(Box::new(hash)).into())
+			};
+
+			let view_results = future::join_all(
+				views.iter().map(|view| view.submit_and_watch(source, xt.clone())),
+			)
+			.await;
+			let mut outcome = Vec::with_capacity(views.len());
+			for (view, result) in views.iter().zip(view_results) {
+				let result = result.map(|watcher| {
+					listener.add_view_watcher_for_tx(
+						hash,
+						view.at.hash,
+						watcher.into_stream().boxed(),
+					);
+					hash
+				});
+				outcome.push((view.at.hash, result));
+			}
+
+			match reduce_view_results(outcome, at) {
+				Ok(hash) => {
+					if mempool.push(hash, source, xt, true) {
+						Self::notify_import(&sinks, hash);
+					}
+					Ok(external_watcher)
+				},
+				Err(e) => {
+					listener.remove_transactions(&[hash]);
+					Err(e)
+				},
+			}
+		}
+		.boxed()
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		let mut removed = HashMap::new();
+		for view in self.view_store.views() {
+			for tx in view.pool.validated_pool().remove_invalid(hashes) {
+				removed.entry(tx.hash).or_insert(tx);
+			}
+		}
+		self.mempool.remove(hashes);
+		self.listener.invalidate_transactions(hashes);
+		self.metrics
+			.report(|metrics| metrics.validations_invalid.inc_by(removed.len() as u64));
+		removed.into_values().collect()
+	}
+
+	fn status(&self) -> PoolStatus {
+		self.view_store
+			.most_recent_view()
+			.map(|view| view.status())
+			.unwrap_or(PoolStatus { ready: 0, ready_bytes: 0, future: 0, future_bytes: 0 })
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		const CHANNEL_BUFFER_SIZE: usize = 1024;
+
+		let (sink, stream) = futures::channel::mpsc::channel(CHANNEL_BUFFER_SIZE);
+		self.import_notification_sinks.lock().push(sink);
+		stream
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		self.api.hash_and_length(xt).0
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		self.listener.transactions_broadcasted(propagations)
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		self.view_store
+			.most_recent_view()
+			.and_then(|view| view.pool.validated_pool().ready_by_hash(hash))
+	}
+
+	fn ready_at(&self, at: NumberFor<Self::Block>) -> PolledIterator<PoolApi> {
+		if let Some(view) = self.view_store.most_recent_view().filter(|view| view.at.number >= at) {
+			let iterator: ReadyIteratorFor<PoolApi> = Self::ready_of(Some(view));
+			return async move { iterator }.boxed()
+		}
+
+		self.ready_poll
+			.lock()
+			.add(at)
+			.map(|received| {
+				received.unwrap_or_else(|e| {
+					log::warn!("Error receiving pending set: {:?}", e);
+					Box::new(std::iter::empty())
+				})
+			})
+			.boxed()
+	}
+
+	fn ready_at_block(&self, at: <Self::Block as BlockT>::Hash) -> PolledIterator<PoolApi> {
+		if let Some(view) = self.view_store.get(&at) {
+			let iterator: ReadyIteratorFor<PoolApi> = Self::ready_of(Some(view));
+			return async move { iterator }.boxed()
+		}
+
+		let number = match self.api.block_id_to_number(&BlockId::Hash(at)) {
+			Ok(Some(number)) => number,
+			_ => {
+				log::debug!(target: LOG_TARGET, "Ready transactions requested at unknown {:?}", at);
+				return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+			},
+		};
+		if number < self.view_store.finalized_block().number {
+			log::debug!(target: LOG_TARGET, "Ready transactions requested at stale {:?}", at);
+			return async { Box::new(std::iter::empty()) as Box<_> }.boxed()
+		}
+
+		let view_store = self.view_store.clone();
+		let mempool = self.mempool.clone();
+		let listener = self.listener.clone();
+		async move {
+			let view = view_store
+				.get_or_create_view(HashAndNumber { hash: at, number }, &mempool, &listener)
+				.await;
+			Self::ready_of(Some(view))
+		}
+		.boxed()
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
+		Self::ready_of(self.view_store.most_recent_view())
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		self.view_store
+			.most_recent_view()
+			.map(|view| view.pool.validated_pool().pool.read().futures().cloned().collect())
+			.unwrap_or_default()
+	}
+}
+
+/// Returns the result of submitting a transaction to all the views.
+///
+/// The transaction is accepted if any view accepted it. Otherwise the error of the view at `at`
+/// is returned, if there is such view.
+fn reduce_view_results<Hash: PartialEq, TxHash, Error: From<TxPoolError>>(
+	outcome: Vec<(Hash, Result<TxHash, Error>)>,
+	at: Hash,
+) -> Result<TxHash, Error> {
+	let mut error = None;
+	for (view_at, result) in outcome {
+		match result {
+			Ok(hash) => return Ok(hash),
+			Err(e) if view_at == at || error.is_none() => error = Some(e),
+			Err(_) => {},
+		}
+	}
+	Err(error.unwrap_or_else(|| TxPoolError::InvalidBlockId("no view at block".into()).into()))
+}
+
+impl<Block, Client> ForkAwareFullPool<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new fork-aware transaction pool for a full node with the provided api.
+	pub fn new_full(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
+		let info = client.usage_info().chain;
+		Arc::new(Self::new(
+			options,
+			is_validator,
+			pool_api,
+			prometheus,
+			HashAndNumber { hash: info.best_hash, number: info.best_number },
+			HashAndNumber { hash: info.finalized_hash, number: info.finalized_number },
+		))
+	}
+}
+
+impl<Block, Client> sc_transaction_pool_api::LocalTransactionPool
+	for ForkAwareTxPool<FullChainApi<Client, Block>, Block>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>,
+	Client: Send + Sync + 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = graph::ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: sc_transaction_pool_api::LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		use graph::ChainApi;
+		use sp_runtime::{
+			traits::SaturatedConversion, transaction_validity::TransactionValidityError,
+		};
+
+		let validate_local = |at: Block::Hash| -> Result<_, Self::Error> {
+			self.api
+				.validate_transaction_blocking(at, TransactionSource::Local, xt.clone())?
+				.map_err(|e| {
+					Self::Error::Pool(match e {
+						TransactionValidityError::Invalid(i) => TxPoolError::InvalidTransaction(i),
+						TransactionValidityError::Unknown(u) => TxPoolError::UnknownTransaction(u),
+					})
+				})
+		};
+		let validity = validate_local(at)?;
+
+		let (hash, bytes) = self.api.hash_and_length(&xt);
+
+		if self.mempool.is_full() && !self.mempool.contains(&hash) {
+			return Err(TxPoolError::ImmediatelyDropped.into())
+		}
+
+		// The transaction is validated at the block of every active view it is submitted to. The
+		// inactive views are left alone, the views created later revalidate it.
+		let outcome = self
+			.view_store
+			.active_views()
+			.iter()
+			.map(|view| {
+				let validity = if view.at.hash == at {
+					Ok(validity.clone())
+				} else {
+					validate_local(view.at.hash)
+				};
+				let result = validity.and_then(|validity| {
+					let validated = ValidatedTransaction::valid_at(
+						view.at.number.saturated_into::<u64>(),
+						hash,
+						TransactionSource::Local,
+						xt.clone(),
+						bytes,
+						validity,
+					);
+					view.pool.validated_pool().submit(vec![validated]).remove(0)
+				});
+				(view.at.hash, result)
+			})
+			.collect();
+
+		let hash = reduce_view_results(outcome, at)?;
+		if self.mempool.push(hash, TransactionSource::Local, xt, false) {
+			Self::notify_import(&self.import_notification_sinks, hash);
+		}
+		Ok(hash)
+	}
+}
+
+#[async_trait]
+impl<PoolApi, Block> MaintainedTransactionPool for ForkAwareTxPool<PoolApi, Block>
+where
+	Block: BlockT,
+	PoolApi: 'static + graph::ChainApi<Block = Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		log::trace!(target: LOG_TARGET, "maintain: {:?}", event);
+		match event {
+			ChainEvent::NewBestBlock { hash, .. } => self.handle_new_best_block(hash).await,
+			ChainEvent::Finalized { hash, tree_route } =>
+				self.handle_finalized(hash, &tree_route).await,
+		}
+	}
+}
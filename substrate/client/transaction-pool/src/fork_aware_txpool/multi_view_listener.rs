@@ -0,0 +1,341 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Listener aggregating the transaction status streams of all the views into a single stream.
+//!
+//! The `Ready` and `Future` events come from the views: the transaction is reported as `Ready` if
+//! any view has it ready. The block related events (`InBlock`, `Retracted`, `Finalized`) and the
+//! `Broadcast` events are reported by the pool itself, as the views are never pruned. The stream
+//! is finished by the pool once the transaction gets finalized, or once it's removed from the pool
+//! because no view considers it valid anymore.
+
+use crate::{
+	graph::{self, BlockHash, ExtrinsicHash},
+	LOG_TARGET,
+};
+use futures::{
+	stream::{self, SelectAll},
+	Stream, StreamExt,
+};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::{TransactionStatus, TransactionStatusStream, TxIndex};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use std::{
+	collections::{HashMap, HashSet},
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+/// Status of the transaction, as reported by the views and the pool.
+type TxStatus<PoolApi> = TransactionStatus<ExtrinsicHash<PoolApi>, BlockHash<PoolApi>>;
+
+/// Stream of status updates of a transaction in a single view.
+pub(super) type ViewStatusStream<PoolApi> = Pin<Box<dyn Stream<Item = TxStatus<PoolApi>> + Send>>;
+
+/// Aggregated stream of status updates of a transaction.
+pub(super) type TxStatusStream<PoolApi> =
+	Pin<Box<TransactionStatusStream<ExtrinsicHash<PoolApi>, BlockHash<PoolApi>>>>;
+
+/// Commands sent by the [`MultiViewListener`] to the aggregated stream of a transaction.
+enum ControllerCommand<PoolApi: graph::ChainApi> {
+	/// Starts listening to the status stream of the transaction in a new view.
+	AddView(BlockHash<PoolApi>, ViewStatusStream<PoolApi>),
+	/// The view at the given block was removed.
+	RemoveView(BlockHash<PoolApi>),
+	/// The transaction got included in the given block.
+	InBlock(BlockHash<PoolApi>, TxIndex),
+	/// The given block was retracted.
+	Retracted(BlockHash<PoolApi>),
+	/// The transaction got finalized in the given block.
+	Finalized(BlockHash<PoolApi>, TxIndex),
+	/// The transaction was broadcast to the given peers.
+	Broadcasted(Vec<String>),
+	/// The transaction was removed from the pool as invalid.
+	Invalidated,
+	/// The transaction was removed from the pool because no view holds it anymore.
+	Removed,
+}
+
+/// Whether a transaction is in the ready or in the future queue of a view.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ViewTxStatus {
+	Ready,
+	Future,
+}
+
+/// State of the aggregated status stream of a single transaction.
+struct ExternalWatcher<PoolApi: graph::ChainApi> {
+	tx_hash: ExtrinsicHash<PoolApi>,
+	controller: TracingUnboundedReceiver<ControllerCommand<PoolApi>>,
+	view_streams:
+		SelectAll<Pin<Box<dyn Stream<Item = (BlockHash<PoolApi>, TxStatus<PoolApi>)> + Send>>>,
+	/// The status of the transaction in every view holding it.
+	views: HashMap<BlockHash<PoolApi>, ViewTxStatus>,
+	/// The last `Ready` or `Future` status reported.
+	status: Option<ViewTxStatus>,
+	/// The last status a view reported when it removed the transaction.
+	rejection: Option<TxStatus<PoolApi>>,
+	/// The blocks the transaction was reported to be included in.
+	in_blocks: HashSet<BlockHash<PoolApi>>,
+	terminated: bool,
+}
+
+impl<PoolApi: graph::ChainApi> ExternalWatcher<PoolApi> {
+	fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<TxStatus<PoolApi>>> {
+		loop {
+			if self.terminated {
+				return Poll::Ready(None)
+			}
+
+			// Statuses reported by the views precede the commands of the pool.
+			if let Poll::Ready(Some((at, status))) = self.view_streams.poll_next_unpin(cx) {
+				if let Some(status) = self.handle_view_status(at, status) {
+					return Poll::Ready(Some(status))
+				}
+				continue
+			}
+
+			match self.controller.poll_next_unpin(cx) {
+				Poll::Ready(Some(command)) =>
+					if let Some(status) = self.handle_command(command) {
+						return Poll::Ready(Some(status))
+					},
+				// The pool is gone.
+				Poll::Ready(None) => {
+					self.terminated = true;
+					return Poll::Ready(None)
+				},
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+
+	fn handle_command(&mut self, command: ControllerCommand<PoolApi>) -> Option<TxStatus<PoolApi>> {
+		match command {
+			ControllerCommand::AddView(at, view_stream) => {
+				self.view_streams.push(view_stream.map(move |status| (at, status)).boxed());
+				None
+			},
+			ControllerCommand::RemoveView(at) => {
+				self.views.remove(&at);
+				self.aggregated_status()
+			},
+			ControllerCommand::InBlock(block, index) => self.in_blocks.insert(block).then(|| {
+				// Report readiness again, should the block get retracted.
+				self.status = None;
+				TransactionStatus::InBlock((block, index))
+			}),
+			ControllerCommand::Retracted(block) =>
+				self.in_blocks.remove(&block).then(|| TransactionStatus::Retracted(block)),
+			ControllerCommand::Finalized(block, index) => {
+				self.terminated = true;
+				Some(TransactionStatus::Finalized((block, index)))
+			},
+			ControllerCommand::Broadcasted(peers) => Some(TransactionStatus::Broadcast(peers)),
+			ControllerCommand::Invalidated => {
+				self.terminated = true;
+				Some(TransactionStatus::Invalid)
+			},
+			ControllerCommand::Removed => {
+				self.terminated = true;
+				Some(self.rejection.take().unwrap_or(TransactionStatus::Invalid))
+			},
+		}
+	}
+
+	fn handle_view_status(
+		&mut self,
+		at: BlockHash<PoolApi>,
+		status: TxStatus<PoolApi>,
+	) -> Option<TxStatus<PoolApi>> {
+		log::trace!(target: LOG_TARGET, "[{:?}] view {:?} status: {:?}", self.tx_hash, at, status);
+		match status {
+			TransactionStatus::Ready => {
+				self.views.insert(at, ViewTxStatus::Ready);
+			},
+			TransactionStatus::Future => {
+				self.views.insert(at, ViewTxStatus::Future);
+			},
+			TransactionStatus::Usurped(_) |
+			TransactionStatus::Dropped |
+			TransactionStatus::Invalid => {
+				self.views.remove(&at);
+				self.rejection = Some(status);
+			},
+			// Views are never pruned nor finalized, these events are reported by the pool.
+			_ => return None,
+		}
+		self.aggregated_status()
+	}
+
+	/// Returns the status of the transaction over all the views, if it changed.
+	fn aggregated_status(&mut self) -> Option<TxStatus<PoolApi>> {
+		let status = if self.views.values().any(|status| *status == ViewTxStatus::Ready) {
+			ViewTxStatus::Ready
+		} else if !self.views.is_empty() {
+			ViewTxStatus::Future
+		} else {
+			return None
+		};
+
+		(self.status != Some(status)).then(|| {
+			self.status = Some(status);
+			match status {
+				ViewTxStatus::Ready => TransactionStatus::Ready,
+				ViewTxStatus::Future => TransactionStatus::Future,
+			}
+		})
+	}
+}
+
+/// Aggregates the status streams of the watched transactions over all the views.
+pub(super) struct MultiViewListener<PoolApi: graph::ChainApi> {
+	controllers:
+		RwLock<HashMap<ExtrinsicHash<PoolApi>, TracingUnboundedSender<ControllerCommand<PoolApi>>>>,
+}
+
+impl<PoolApi> MultiViewListener<PoolApi>
+where
+	PoolApi: graph::ChainApi + 'static,
+{
+	/// Creates a new listener.
+	pub(super) fn new() -> Self {
+		Self { controllers: Default::default() }
+	}
+
+	/// Creates the aggregated status stream of the transaction.
+	///
+	/// Returns `None` if the transaction is already watched.
+	pub(super) fn create_external_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<PoolApi>,
+	) -> Option<TxStatusStream<PoolApi>> {
+		let mut controllers = self.controllers.write();
+		if controllers.get(&tx_hash).map_or(false, |controller| !controller.is_closed()) {
+			return None
+		}
+
+		let (sender, controller) = tracing_unbounded("mpsc_txpool_external_watcher", 100_000);
+		controllers.insert(tx_hash, sender);
+
+		let mut watcher = ExternalWatcher::<PoolApi> {
+			tx_hash,
+			controller,
+			view_streams: SelectAll::new(),
+			views: Default::default(),
+			status: None,
+			rejection: None,
+			in_blocks: Default::default(),
+			terminated: false,
+		};
+		Some(stream::poll_fn(move |cx| watcher.poll_next(cx)).boxed())
+	}
+
+	/// Adds the status stream of the transaction in the view at `at` to its aggregated stream.
+	pub(super) fn add_view_watcher_for_tx(
+		&self,
+		tx_hash: ExtrinsicHash<PoolApi>,
+		at: BlockHash<PoolApi>,
+		view_stream: ViewStatusStream<PoolApi>,
+	) {
+		self.send(&tx_hash, ControllerCommand::AddView(at, view_stream));
+	}
+
+	/// Notifies the watched transactions that the view at `at` was removed.
+	pub(super) fn remove_view(&self, at: BlockHash<PoolApi>) {
+		self.send_to_all(|| ControllerCommand::RemoveView(at));
+	}
+
+	/// Notifies the transaction that it got included in the given block.
+	pub(super) fn transaction_in_block(
+		&self,
+		tx_hash: ExtrinsicHash<PoolApi>,
+		block: BlockHash<PoolApi>,
+		index: TxIndex,
+	) {
+		self.send(&tx_hash, ControllerCommand::InBlock(block, index));
+	}
+
+	/// Notifies the watched transactions that the given block was retracted.
+	pub(super) fn transactions_retracted(&self, block: BlockHash<PoolApi>) {
+		self.send_to_all(|| ControllerCommand::Retracted(block));
+	}
+
+	/// Notifies the transaction that it got finalized in the given block, finishing its stream.
+	pub(super) fn transaction_finalized(
+		&self,
+		tx_hash: ExtrinsicHash<PoolApi>,
+		block: BlockHash<PoolApi>,
+		index: TxIndex,
+	) {
+		if let Some(controller) = self.controllers.write().remove(&tx_hash) {
+			let _ = controller.unbounded_send(ControllerCommand::Finalized(block, index));
+		}
+	}
+
+	/// Notifies the transactions that they were broadcast to the given peers.
+	pub(super) fn transactions_broadcasted(
+		&self,
+		propagations: HashMap<ExtrinsicHash<PoolApi>, Vec<String>>,
+	) {
+		for (tx_hash, peers) in propagations {
+			self.send(&tx_hash, ControllerCommand::Broadcasted(peers));
+		}
+	}
+
+	/// Notifies the transactions that they were removed from the pool as invalid, finishing their
+	/// streams.
+	pub(super) fn invalidate_transactions(&self, tx_hashes: &[ExtrinsicHash<PoolApi>]) {
+		self.finish(tx_hashes, || ControllerCommand::Invalidated);
+	}
+
+	/// Notifies the transactions that they were removed from the pool because no view holds them
+	/// anymore, finishing their streams.
+	pub(super) fn remove_transactions(&self, tx_hashes: &[ExtrinsicHash<PoolApi>]) {
+		self.finish(tx_hashes, || ControllerCommand::Removed);
+	}
+
+	fn finish(
+		&self,
+		tx_hashes: &[ExtrinsicHash<PoolApi>],
+		command: impl Fn() -> ControllerCommand<PoolApi>,
+	) {
+		let mut controllers = self.controllers.write();
+		for tx_hash in tx_hashes {
+			if let Some(controller) = controllers.remove(tx_hash) {
+				let _ = controller.unbounded_send(command());
+			}
+		}
+	}
+
+	fn send(&self, tx_hash: &ExtrinsicHash<PoolApi>, command: ControllerCommand<PoolApi>) {
+		let mut controllers = self.controllers.write();
+		if let Some(controller) = controllers.get(tx_hash) {
+			if controller.unbounded_send(command).is_err() {
+				log::trace!(target: LOG_TARGET, "[{:?}] external watcher dropped", tx_hash);
+				controllers.remove(tx_hash);
+			}
+		}
+	}
+
+	fn send_to_all(&self, command: impl Fn() -> ControllerCommand<PoolApi>) {
+		self.controllers
+			.write()
+			.retain(|_, controller| controller.unbounded_send(command()).is_ok());
+	}
+}
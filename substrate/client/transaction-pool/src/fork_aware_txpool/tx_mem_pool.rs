@@ -0,0 +1,187 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Memory pool of all the transactions submitted to the fork-aware pool.
+//!
+//! New views are populated with the transactions kept here. A transaction stays in the memory
+//! pool until it gets finalized, or until no view considers it valid anymore.
+
+use crate::graph::{self, BlockHash, ExtrinsicFor, ExtrinsicHash, NumberFor};
+use parking_lot::RwLock;
+use sc_transaction_pool_api::TransactionSource;
+use sp_blockchain::HashAndNumber;
+use std::collections::{HashMap, HashSet};
+
+/// A transaction kept in the [`TxMemPool`].
+pub(super) struct TxInMemPool<PoolApi: graph::ChainApi> {
+	/// The transaction itself.
+	pub(super) tx: ExtrinsicFor<PoolApi>,
+	/// Where the transaction comes from.
+	pub(super) source: TransactionSource,
+	/// Whether the transaction was submitted with `submit_and_watch`.
+	pub(super) watched: bool,
+	/// Not yet finalized blocks which include the transaction.
+	pub(super) included_in: Vec<HashAndNumber<PoolApi::Block>>,
+	/// The finalized block the transaction was last revalidated at, if any.
+	revalidated_at: Option<NumberFor<PoolApi>>,
+}
+
+/// Memory pool of the fork-aware transaction pool.
+pub(super) struct TxMemPool<PoolApi: graph::ChainApi> {
+	transactions: RwLock<HashMap<ExtrinsicHash<PoolApi>, TxInMemPool<PoolApi>>>,
+	max_transactions_count: usize,
+}
+
+impl<PoolApi: graph::ChainApi> TxMemPool<PoolApi> {
+	/// Creates a new memory pool holding at most `max_transactions_count` transactions.
+	pub(super) fn new(max_transactions_count: usize) -> Self {
+		Self { transactions: Default::default(), max_transactions_count }
+	}
+
+	/// Returns the number of transactions in the memory pool.
+	pub(super) fn len(&self) -> usize {
+		self.transactions.read().len()
+	}
+
+	/// Returns true if a new transaction can't be added to the memory pool.
+	pub(super) fn is_full(&self) -> bool {
+		self.len() >= self.max_transactions_count
+	}
+
+	/// Returns true if the transaction is in the memory pool.
+	pub(super) fn contains(&self, hash: &ExtrinsicHash<PoolApi>) -> bool {
+		self.transactions.read().contains_key(hash)
+	}
+
+	/// Adds a transaction to the memory pool.
+	///
+	/// Returns false if the transaction was already known.
+	pub(super) fn push(
+		&self,
+		hash: ExtrinsicHash<PoolApi>,
+		source: TransactionSource,
+		tx: ExtrinsicFor<PoolApi>,
+		watched: bool,
+	) -> bool {
+		let mut transactions = self.transactions.write();
+		if let Some(known) = transactions.get_mut(&hash) {
+			known.watched |= watched;
+			return false
+		}
+		transactions.insert(
+			hash,
+			TxInMemPool { tx, source, watched, included_in: Vec::new(), revalidated_at: None },
+		);
+		true
+	}
+
+	/// Removes the given transactions from the memory pool.
+	pub(super) fn remove<'a>(&self, hashes: impl IntoIterator<Item = &'a ExtrinsicHash<PoolApi>>) {
+		let mut transactions = self.transactions.write();
+		for hash in hashes {
+			transactions.remove(hash);
+		}
+	}
+
+	/// Removes the transactions for which `keep` returns false, returning their hashes.
+	pub(super) fn retain(
+		&self,
+		mut keep: impl FnMut(&ExtrinsicHash<PoolApi>, &TxInMemPool<PoolApi>) -> bool,
+	) -> Vec<ExtrinsicHash<PoolApi>> {
+		let mut removed = Vec::new();
+		self.transactions.write().retain(|hash, tx| {
+			let keep = keep(hash, tx);
+			if !keep {
+				removed.push(*hash);
+			}
+			keep
+		});
+		removed
+	}
+
+	/// Notes that the transaction is included in the given block.
+	///
+	/// Returns true if the transaction is in the memory pool and the inclusion wasn't known yet.
+	pub(super) fn note_included(
+		&self,
+		hash: &ExtrinsicHash<PoolApi>,
+		block: &HashAndNumber<PoolApi::Block>,
+	) -> bool {
+		let mut transactions = self.transactions.write();
+		let Some(tx) = transactions.get_mut(hash) else { return false };
+		if tx.included_in.iter().any(|included| included.hash == block.hash) {
+			return false
+		}
+		tx.included_in.push(block.clone());
+		true
+	}
+
+	/// Forgets about inclusions in blocks at or below the finalized block number.
+	///
+	/// Such blocks are either finalized, and their transactions removed from the memory pool, or
+	/// they were on a fork which got pruned.
+	pub(super) fn prune_inclusions(&self, finalized_number: NumberFor<PoolApi>) {
+		for tx in self.transactions.write().values_mut() {
+			tx.included_in.retain(|included| included.number > finalized_number);
+		}
+	}
+
+	/// Returns at most `count` transactions to revalidate, the least recently revalidated first.
+	pub(super) fn transactions_to_revalidate(
+		&self,
+		count: usize,
+	) -> Vec<(ExtrinsicHash<PoolApi>, TransactionSource, ExtrinsicFor<PoolApi>)> {
+		let transactions = self.transactions.read();
+		let mut candidates = transactions.iter().collect::<Vec<_>>();
+		candidates.sort_by_key(|(_, tx)| tx.revalidated_at);
+		candidates
+			.into_iter()
+			.take(count)
+			.map(|(hash, tx)| (*hash, tx.source, tx.tx.clone()))
+			.collect()
+	}
+
+	/// Notes that the given transactions were revalidated at the finalized block `at`.
+	pub(super) fn note_revalidated<'a>(
+		&self,
+		hashes: impl IntoIterator<Item = &'a ExtrinsicHash<PoolApi>>,
+		at: NumberFor<PoolApi>,
+	) {
+		let mut transactions = self.transactions.write();
+		for hash in hashes {
+			if let Some(tx) = transactions.get_mut(hash) {
+				tx.revalidated_at = Some(at);
+			}
+		}
+	}
+
+	/// Returns the transactions a view with the given ancestry should be populated with.
+	///
+	/// Transactions included in any of the `ancestry` blocks are skipped.
+	pub(super) fn transactions_for_view(
+		&self,
+		ancestry: &HashSet<BlockHash<PoolApi>>,
+	) -> Vec<(ExtrinsicHash<PoolApi>, TransactionSource, ExtrinsicFor<PoolApi>, bool)> {
+		self.transactions
+			.read()
+			.iter()
+			.filter(|(_, tx)| !tx.included_in.iter().any(|block| ancestry.contains(&block.hash)))
+			.map(|(hash, tx)| (*hash, tx.source, tx.tx.clone(), tx.watched))
+			.collect()
+	}
+}
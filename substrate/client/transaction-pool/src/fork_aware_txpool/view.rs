@@ -0,0 +1,87 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A view of the transaction pool at a given block.
+//!
+//! Every view keeps its own [`graph::Pool`], containing transactions validated against the state
+//! of the block the view was created at.
+
+use crate::graph::{self, watcher::Watcher, ExtrinsicFor, ExtrinsicHash, IsValidator};
+use sc_transaction_pool_api::{PoolStatus, TransactionSource};
+use sp_blockchain::HashAndNumber;
+use std::sync::Arc;
+
+/// The transaction pool as seen from the block it was created at.
+pub(super) struct View<PoolApi: graph::ChainApi> {
+	/// The pool holding transactions validated at `at`.
+	pub(super) pool: graph::Pool<PoolApi>,
+	/// The block the view was created at.
+	pub(super) at: HashAndNumber<PoolApi::Block>,
+}
+
+impl<PoolApi> View<PoolApi>
+where
+	PoolApi: graph::ChainApi + 'static,
+{
+	/// Creates an empty view at the given block.
+	pub(super) fn new(
+		api: Arc<PoolApi>,
+		at: HashAndNumber<PoolApi::Block>,
+		options: graph::Options,
+		is_validator: IsValidator,
+	) -> Self {
+		Self { pool: graph::Pool::new(options, is_validator, api), at }
+	}
+
+	/// Imports a bunch of unverified extrinsics to the view.
+	pub(super) async fn submit_many(
+		&self,
+		source: TransactionSource,
+		xts: impl IntoIterator<Item = ExtrinsicFor<PoolApi>>,
+	) -> Result<Vec<Result<ExtrinsicHash<PoolApi>, PoolApi::Error>>, PoolApi::Error> {
+		self.pool.submit_at(self.at.hash, source, xts).await
+	}
+
+	/// Imports one unverified extrinsic to the view.
+	pub(super) async fn submit_one(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<PoolApi>,
+	) -> Result<ExtrinsicHash<PoolApi>, PoolApi::Error> {
+		self.pool.submit_one(self.at.hash, source, xt).await
+	}
+
+	/// Imports a single extrinsic to the view and starts to watch its progress in the view.
+	pub(super) async fn submit_and_watch(
+		&self,
+		source: TransactionSource,
+		xt: ExtrinsicFor<PoolApi>,
+	) -> Result<Watcher<ExtrinsicHash<PoolApi>, ExtrinsicHash<PoolApi>>, PoolApi::Error> {
+		self.pool.submit_and_watch(self.at.hash, source, xt).await
+	}
+
+	/// Returns true if the transaction is in the ready or future queue of the view.
+	pub(super) fn is_imported(&self, hash: &ExtrinsicHash<PoolApi>) -> bool {
+		self.pool.validated_pool().pool.read().is_imported(hash)
+	}
+
+	/// Returns the status of the view.
+	pub(super) fn status(&self) -> PoolStatus {
+		self.pool.validated_pool().status()
+	}
+}
@@ -0,0 +1,306 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Store of the views of the fork-aware transaction pool.
+//!
+//! Views at the leaves of the tracked forks are active. Views at blocks which got a descendant
+//! view are kept inactive, so the pool can still serve their ready transactions, until finality
+//! moves past them.
+
+use super::{multi_view_listener::MultiViewListener, tx_mem_pool::TxMemPool, view::View};
+use crate::{
+	graph::{self, BlockHash, ExtrinsicHash, IsValidator, NumberFor},
+	LOG_TARGET,
+};
+use futures::{future, StreamExt};
+use parking_lot::RwLock;
+use sp_blockchain::HashAndNumber;
+use sp_runtime::traits::{Header as HeaderT, One};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+/// The views of the fork-aware transaction pool.
+pub(super) struct ViewStore<PoolApi: graph::ChainApi> {
+	api: Arc<PoolApi>,
+	options: graph::Options,
+	is_validator: IsValidator,
+	/// Views at the leaves of the tracked forks.
+	active_views: RwLock<HashMap<BlockHash<PoolApi>, Arc<View<PoolApi>>>>,
+	/// Views at blocks which are not leaves anymore, kept until finalized.
+	inactive_views: RwLock<HashMap<BlockHash<PoolApi>, Arc<View<PoolApi>>>>,
+	/// The view at the most recent best block.
+	most_recent_view: RwLock<Option<Arc<View<PoolApi>>>>,
+	/// The most recent finalized block.
+	finalized_block: RwLock<HashAndNumber<PoolApi::Block>>,
+}
+
+impl<PoolApi> ViewStore<PoolApi>
+where
+	PoolApi: graph::ChainApi + 'static,
+{
+	/// Creates a store with an empty, active view at `best_block`.
+	pub(super) fn new(
+		api: Arc<PoolApi>,
+		options: graph::Options,
+		is_validator: IsValidator,
+		best_block: HashAndNumber<PoolApi::Block>,
+		finalized_block: HashAndNumber<PoolApi::Block>,
+	) -> Self {
+		let view = Arc::new(View::new(
+			api.clone(),
+			best_block.clone(),
+			options.clone(),
+			is_validator.clone(),
+		));
+		Self {
+			api,
+			options,
+			is_validator,
+			active_views: RwLock::new(HashMap::from([(best_block.hash, view.clone())])),
+			inactive_views: Default::default(),
+			most_recent_view: RwLock::new(Some(view)),
+			finalized_block: RwLock::new(finalized_block),
+		}
+	}
+
+	/// Returns the view at the given block, if it's tracked.
+	pub(super) fn get(&self, at: &BlockHash<PoolApi>) -> Option<Arc<View<PoolApi>>> {
+		if let Some(view) = self.active_views.read().get(at) {
+			return Some(view.clone())
+		}
+		self.inactive_views.read().get(at).cloned()
+	}
+
+	/// Returns the view at the most recent best block.
+	pub(super) fn most_recent_view(&self) -> Option<Arc<View<PoolApi>>> {
+		self.most_recent_view.read().clone()
+	}
+
+	/// Returns the views at the leaves of the tracked forks.
+	///
+	/// New transactions are only submitted to these views: the inactive views are kept to serve
+	/// their ready transactions, and don't need to be revalidated until they are finalized.
+	pub(super) fn active_views(&self) -> Vec<Arc<View<PoolApi>>> {
+		self.active_views.read().values().cloned().collect()
+	}
+
+	/// Returns all the tracked views.
+	pub(super) fn views(&self) -> Vec<Arc<View<PoolApi>>> {
+		self.active_views
+			.read()
+			.values()
+			.chain(self.inactive_views.read().values())
+			.cloned()
+			.collect()
+	}
+
+	/// Returns the most recent finalized block.
+	pub(super) fn finalized_block(&self) -> HashAndNumber<PoolApi::Block> {
+		self.finalized_block.read().clone()
+	}
+
+	/// Returns true if any view holds the transaction.
+	pub(super) fn is_imported(&self, tx_hash: &ExtrinsicHash<PoolApi>) -> bool {
+		self.views().iter().any(|view| view.is_imported(tx_hash))
+	}
+
+	/// Returns the view at `at`, creating it if it's not tracked yet.
+	///
+	/// A new view is populated with the transactions of the memory pool, except the ones included
+	/// in its ancestry. The transactions included in the blocks between the nearest ancestor view
+	/// and `at` are reported to the `listener`. The new view is kept inactive.
+	pub(super) async fn get_or_create_view(
+		&self,
+		at: HashAndNumber<PoolApi::Block>,
+		mempool: &TxMemPool<PoolApi>,
+		listener: &MultiViewListener<PoolApi>,
+	) -> Arc<View<PoolApi>> {
+		if let Some(view) = self.get(&at.hash) {
+			return view
+		}
+
+		for block in self.enacted_since_nearest_view(&at) {
+			let extrinsics = self
+				.api
+				.block_body(block.hash)
+				.await
+				.unwrap_or_else(|e| {
+					log::warn!(target: LOG_TARGET, "Failed to fetch block body: {}", e);
+					None
+				})
+				.unwrap_or_default();
+			for (index, xt) in extrinsics.iter().enumerate() {
+				let tx_hash = self.api.hash_and_length(xt).0;
+				if mempool.note_included(&tx_hash, &block) {
+					listener.transaction_in_block(tx_hash, block.hash, index);
+				}
+			}
+		}
+
+		// Insert the view before populating it, so it doesn't miss transactions submitted
+		// meanwhile.
+		let view = {
+			let mut inactive_views = self.inactive_views.write();
+			if let Some(view) = inactive_views.get(&at.hash) {
+				return view.clone()
+			}
+			let view = Arc::new(View::new(
+				self.api.clone(),
+				at.clone(),
+				self.options.clone(),
+				self.is_validator.clone(),
+			));
+			inactive_views.insert(at.hash, view.clone());
+			view
+		};
+
+		let ancestry = self.ancestry(&at);
+		let (watched, unwatched): (Vec<_>, Vec<_>) =
+			mempool.transactions_for_view(&ancestry).into_iter().partition(|tx| tx.3);
+		log::debug!(
+			target: LOG_TARGET,
+			"Populating view at {:?} with {} transactions",
+			at,
+			watched.len() + unwatched.len(),
+		);
+
+		future::join_all(
+			unwatched.into_iter().map(|(_, source, xt, _)| view.submit_one(source, xt)),
+		)
+		.await;
+		future::join_all(watched.into_iter().map(|(tx_hash, source, xt, _)| {
+			let view = view.clone();
+			async move {
+				if let Ok(watcher) = view.submit_and_watch(source, xt).await {
+					listener.add_view_watcher_for_tx(
+						tx_hash,
+						view.at.hash,
+						watcher.into_stream().boxed(),
+					);
+				}
+			}
+		}))
+		.await;
+
+		view
+	}
+
+	/// Makes the view the active view at the most recent best block.
+	///
+	/// Active views at ancestors of the view are deactivated.
+	pub(super) fn activate_best_view(&self, view: Arc<View<PoolApi>>) {
+		let mut active_views = self.active_views.write();
+		let mut inactive_views = self.inactive_views.write();
+
+		inactive_views.remove(&view.at.hash);
+		let ancestors = active_views
+			.values()
+			.filter(|active| active.at.number < view.at.number)
+			.filter(|active| self.is_descendant_of(&view.at, &active.at))
+			.map(|active| active.at.hash)
+			.collect::<Vec<_>>();
+		for hash in ancestors {
+			if let Some(ancestor) = active_views.remove(&hash) {
+				inactive_views.insert(hash, ancestor);
+			}
+		}
+		active_views.insert(view.at.hash, view.clone());
+
+		*self.most_recent_view.write() = Some(view);
+	}
+
+	/// Removes the views which can't be used anymore once `finalized` is finalized.
+	///
+	/// These are the views below the finalized block, and the views on forks not descending from
+	/// it. Returns the blocks of the removed views.
+	pub(super) fn handle_finalized(
+		&self,
+		finalized: HashAndNumber<PoolApi::Block>,
+	) -> Vec<BlockHash<PoolApi>> {
+		let mut removed = Vec::new();
+		let mut keep = |view: &View<PoolApi>| {
+			let keep = view.at.hash == finalized.hash ||
+				(view.at.number > finalized.number &&
+					self.is_descendant_of(&view.at, &finalized));
+			if !keep {
+				removed.push(view.at.hash);
+			}
+			keep
+		};
+		self.active_views.write().retain(|_, view| keep(view));
+		self.inactive_views.write().retain(|_, view| keep(view));
+
+		let mut most_recent_view = self.most_recent_view.write();
+		if most_recent_view.as_ref().map_or(false, |view| removed.contains(&view.at.hash)) {
+			*most_recent_view = None;
+		}
+		*self.finalized_block.write() = finalized;
+
+		removed
+	}
+
+	/// Returns true if `block` is a descendant of `ancestor`.
+	fn is_descendant_of(
+		&self,
+		block: &HashAndNumber<PoolApi::Block>,
+		ancestor: &HashAndNumber<PoolApi::Block>,
+	) -> bool {
+		self.api
+			.tree_route(ancestor.hash, block.hash)
+			.map(|tree_route| tree_route.retracted().is_empty())
+			.unwrap_or(false)
+	}
+
+	/// Returns the blocks from the nearest ancestor view (excluded) to `at` (included).
+	///
+	/// Only `at` is returned if there is no ancestor view.
+	fn enacted_since_nearest_view(
+		&self,
+		at: &HashAndNumber<PoolApi::Block>,
+	) -> Vec<HashAndNumber<PoolApi::Block>> {
+		self.views()
+			.iter()
+			.filter(|view| view.at.number < at.number)
+			.filter_map(|view| self.api.tree_route(view.at.hash, at.hash).ok())
+			.filter(|tree_route| tree_route.retracted().is_empty())
+			.min_by_key(|tree_route| tree_route.enacted().len())
+			.map(|tree_route| tree_route.enacted().to_vec())
+			.unwrap_or_else(|| vec![at.clone()])
+	}
+
+	/// Returns `at` and its ancestors above the finalized block.
+	fn ancestry(&self, at: &HashAndNumber<PoolApi::Block>) -> HashSet<BlockHash<PoolApi>> {
+		let finalized_number: NumberFor<PoolApi> = self.finalized_block.read().number;
+		let mut ancestry = HashSet::new();
+		let mut current = at.clone();
+		while current.number > finalized_number {
+			ancestry.insert(current.hash);
+			match self.api.block_header(current.hash) {
+				Ok(Some(header)) =>
+					current = HashAndNumber {
+						hash: *header.parent_hash(),
+						number: current.number - One::one(),
+					},
+				_ => break,
+			}
+		}
+		ancestry
+	}
+}
@@ -108,6 +108,16 @@ pub trait ChainApi: Send + Sync {
 	) -> Result<TreeRoute<Self::Block>, Self::Error>;
 }
 
+/// The type of the transaction pool implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionPoolType {
+	/// A single state pool, validating transactions against the best block only.
+	#[default]
+	SingleState,
+	/// A fork-aware pool, maintaining a view of the pool for every tracked fork.
+	ForkAware,
+}
+
 /// Pool configuration options.
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -119,6 +129,8 @@ pub struct Options {
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// The type of the transaction pool implementation.
+	pub pool_type: TransactionPoolType,
 }
 
 impl Default for Options {
@@ -128,6 +140,7 @@ impl Default for Options {
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			pool_type: Default::default(),
 		}
 	}
 }
@@ -25,22 +25,26 @@
 mod api;
 mod enactment_state;
 pub mod error;
+mod fork_aware_txpool;
 mod graph;
 mod metrics;
 mod revalidation;
 #[cfg(test)]
 mod tests;
+mod transaction_pool_wrapper;
 
 pub use crate::api::FullChainApi;
 use async_trait::async_trait;
 use enactment_state::{EnactmentAction, EnactmentState};
+pub use fork_aware_txpool::{ForkAwareFullPool, ForkAwareTxPool};
 use futures::{
 	channel::oneshot,
 	future::{self, ready},
 	prelude::*,
 };
 pub use graph::{
-	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, Transaction, ValidatedTransaction,
+	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, Transaction, TransactionPoolType,
+	ValidatedTransaction,
 };
 use parking_lot::Mutex;
 use std::{
@@ -48,6 +52,7 @@ use std::{
 	pin::Pin,
 	sync::Arc,
 };
+pub use transaction_pool_wrapper::TransactionPoolWrapper;
 
 use graph::{ExtrinsicHash, IsValidator};
 use sc_transaction_pool_api::{
@@ -359,6 +364,16 @@ where
 			.boxed()
 	}
 
+	fn ready_at_block(&self, at: <Self::Block as BlockT>::Hash) -> PolledIterator<PoolApi> {
+		match self.api.block_id_to_number(&BlockId::Hash(at)) {
+			Ok(Some(number)) => self.ready_at(number),
+			_ => {
+				let iterator = self.ready();
+				async move { iterator }.boxed()
+			},
+		}
+	}
+
 	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
 		Box::new(self.pool.validated_pool().ready())
 	}
@@ -0,0 +1,276 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transaction pool for a full node, with the implementation selected at runtime.
+
+use crate::{
+	api::FullChainApi,
+	fork_aware_txpool::ForkAwareFullPool,
+	graph::{self, IsValidator, TransactionPoolType},
+	FullPool, PolledIterator, ReadyIteratorFor,
+};
+use async_trait::async_trait;
+use prometheus_endpoint::Registry as PrometheusRegistry;
+use sc_transaction_pool_api::{
+	ChainEvent, ImportNotificationStream, LocalTransactionFor, LocalTransactionPool,
+	MaintainedTransactionPool, PoolFuture, PoolStatus, TransactionFor, TransactionPool,
+	TransactionSource, TransactionStatusStreamFor, TxHash,
+};
+use sp_core::traits::SpawnEssentialNamed;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+
+/// A transaction pool for a full node, either single-state or fork-aware.
+///
+/// The implementation is selected with [`graph::Options::pool_type`].
+pub enum TransactionPoolWrapper<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// The single-state pool, validating transactions against the best block only.
+	SingleState(Arc<FullPool<Block, Client>>),
+	/// The fork-aware pool, keeping a view of the pool for every tracked fork.
+	ForkAware(Arc<ForkAwareFullPool<Block, Client>>),
+}
+
+impl<Block, Client> TransactionPoolWrapper<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sc_client_api::ExecutorProvider<Block>
+		+ sc_client_api::UsageProvider<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	/// Create new transaction pool for a full node, of the type given in `options`.
+	pub fn new_full(
+		options: graph::Options,
+		is_validator: IsValidator,
+		prometheus: Option<&PrometheusRegistry>,
+		spawner: impl SpawnEssentialNamed,
+		client: Arc<Client>,
+	) -> Arc<Self> {
+		Arc::new(match options.pool_type {
+			TransactionPoolType::SingleState => Self::SingleState(FullPool::new_full(
+				options,
+				is_validator,
+				prometheus,
+				spawner,
+				client,
+			)),
+			TransactionPoolType::ForkAware => Self::ForkAware(ForkAwareFullPool::new_full(
+				options,
+				is_validator,
+				prometheus,
+				spawner,
+				client,
+			)),
+		})
+	}
+}
+
+impl<Block, Client> TransactionPool for TransactionPoolWrapper<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = graph::ExtrinsicHash<FullChainApi<Client, Block>>;
+	type InPoolTransaction = graph::base_pool::Transaction<TxHash<Self>, TransactionFor<Self>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_at(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xts: Vec<TransactionFor<Self>>,
+	) -> PoolFuture<Vec<Result<TxHash<Self>, Self::Error>>, Self::Error> {
+		match self {
+			Self::SingleState(pool) => pool.submit_at(at, source, xts),
+			Self::ForkAware(pool) => pool.submit_at(at, source, xts),
+		}
+	}
+
+	fn submit_one(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<TxHash<Self>, Self::Error> {
+		match self {
+			Self::SingleState(pool) => pool.submit_one(at, source, xt),
+			Self::ForkAware(pool) => pool.submit_one(at, source, xt),
+		}
+	}
+
+	fn submit_and_watch(
+		&self,
+		at: Block::Hash,
+		source: TransactionSource,
+		xt: TransactionFor<Self>,
+	) -> PoolFuture<Pin<Box<TransactionStatusStreamFor<Self>>>, Self::Error> {
+		match self {
+			Self::SingleState(pool) => pool.submit_and_watch(at, source, xt),
+			Self::ForkAware(pool) => pool.submit_and_watch(at, source, xt),
+		}
+	}
+
+	fn remove_invalid(&self, hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
+		match self {
+			Self::SingleState(pool) => pool.remove_invalid(hashes),
+			Self::ForkAware(pool) => pool.remove_invalid(hashes),
+		}
+	}
+
+	fn status(&self) -> PoolStatus {
+		match self {
+			Self::SingleState(pool) => pool.status(),
+			Self::ForkAware(pool) => pool.status(),
+		}
+	}
+
+	fn import_notification_stream(&self) -> ImportNotificationStream<TxHash<Self>> {
+		match self {
+			Self::SingleState(pool) => pool.import_notification_stream(),
+			Self::ForkAware(pool) => pool.import_notification_stream(),
+		}
+	}
+
+	fn hash_of(&self, xt: &TransactionFor<Self>) -> TxHash<Self> {
+		match self {
+			Self::SingleState(pool) => pool.hash_of(xt),
+			Self::ForkAware(pool) => pool.hash_of(xt),
+		}
+	}
+
+	fn on_broadcasted(&self, propagations: HashMap<TxHash<Self>, Vec<String>>) {
+		match self {
+			Self::SingleState(pool) => pool.on_broadcasted(propagations),
+			Self::ForkAware(pool) => pool.on_broadcasted(propagations),
+		}
+	}
+
+	fn ready_transaction(&self, hash: &TxHash<Self>) -> Option<Arc<Self::InPoolTransaction>> {
+		match self {
+			Self::SingleState(pool) => pool.ready_transaction(hash),
+			Self::ForkAware(pool) => pool.ready_transaction(hash),
+		}
+	}
+
+	fn ready_at(&self, at: NumberFor<Block>) -> PolledIterator<FullChainApi<Client, Block>> {
+		match self {
+			Self::SingleState(pool) => pool.ready_at(at),
+			Self::ForkAware(pool) => pool.ready_at(at),
+		}
+	}
+
+	fn ready_at_block(&self, at: Block::Hash) -> PolledIterator<FullChainApi<Client, Block>> {
+		match self {
+			Self::SingleState(pool) => pool.ready_at_block(at),
+			Self::ForkAware(pool) => pool.ready_at_block(at),
+		}
+	}
+
+	fn ready(&self) -> ReadyIteratorFor<FullChainApi<Client, Block>> {
+		match self {
+			Self::SingleState(pool) => pool.ready(),
+			Self::ForkAware(pool) => pool.ready(),
+		}
+	}
+
+	fn futures(&self) -> Vec<Self::InPoolTransaction> {
+		match self {
+			Self::SingleState(pool) => pool.futures(),
+			Self::ForkAware(pool) => pool.futures(),
+		}
+	}
+}
+
+impl<Block, Client> LocalTransactionPool for TransactionPoolWrapper<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	type Block = Block;
+	type Hash = graph::ExtrinsicHash<FullChainApi<Client, Block>>;
+	type Error = <FullChainApi<Client, Block> as graph::ChainApi>::Error;
+
+	fn submit_local(
+		&self,
+		at: Block::Hash,
+		xt: LocalTransactionFor<Self>,
+	) -> Result<Self::Hash, Self::Error> {
+		match self {
+			Self::SingleState(pool) => pool.submit_local(at, xt),
+			Self::ForkAware(pool) => pool.submit_local(at, xt),
+		}
+	}
+}
+
+#[async_trait]
+impl<Block, Client> MaintainedTransactionPool for TransactionPoolWrapper<Block, Client>
+where
+	Block: BlockT,
+	Client: sp_api::ProvideRuntimeApi<Block>
+		+ sc_client_api::BlockBackend<Block>
+		+ sc_client_api::blockchain::HeaderBackend<Block>
+		+ sp_runtime::traits::BlockIdTo<Block>
+		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
+{
+	async fn maintain(&self, event: ChainEvent<Self::Block>) {
+		match self {
+			Self::SingleState(pool) => pool.maintain(event).await,
+			Self::ForkAware(pool) => pool.maintain(event).await,
+		}
+	}
+}
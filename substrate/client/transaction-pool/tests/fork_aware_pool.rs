@@ -0,0 +1,266 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tests for the fork-aware transaction pool
+
+use futures::executor::{block_on, block_on_stream};
+use sc_transaction_pool::{ChainApi, ForkAwareTxPool};
+use sc_transaction_pool_api::{
+	ChainEvent, MaintainedTransactionPool, TransactionPool, TransactionStatus,
+};
+use sp_runtime::transaction_validity::TransactionSource;
+use std::sync::Arc;
+use substrate_test_runtime_client::{
+	runtime::{Block, Hash, Header, TransferData},
+	AccountKeyring::*,
+};
+use substrate_test_runtime_transaction_pool::{uxt, TestApi};
+
+const SOURCE: TransactionSource = TransactionSource::External;
+
+fn pool() -> (ForkAwareTxPool<TestApi, Block>, Arc<TestApi>, Hash) {
+	let api = Arc::new(TestApi::with_alice_nonce(209));
+	let genesis_hash = api.expect_hash_from_number(0);
+	let pool =
+		ForkAwareTxPool::new_test(api.clone(), genesis_hash, genesis_hash, Default::default());
+	(pool, api, genesis_hash)
+}
+
+fn new_best_block_event(header: &Header) -> ChainEvent<Block> {
+	ChainEvent::NewBestBlock { hash: header.hash(), tree_route: None }
+}
+
+fn ready_nonces_at(pool: &ForkAwareTxPool<TestApi, Block>, at: Hash) -> Vec<u64> {
+	block_on(pool.ready_at_block(at))
+		.map(|tx| TransferData::try_from(&tx.data).unwrap().nonce)
+		.collect()
+}
+
+#[test]
+fn fap_submitted_transaction_is_ready_on_all_forks() {
+	let (pool, api, genesis) = pool();
+
+	let header_a = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_a)));
+	let header_b = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_b)));
+
+	block_on(pool.submit_one(header_b.hash(), SOURCE, uxt(Alice, 209))).unwrap();
+
+	assert_eq!(ready_nonces_at(&pool, header_a.hash()), vec![209]);
+	assert_eq!(ready_nonces_at(&pool, header_b.hash()), vec![209]);
+	assert_eq!(pool.mempool_len(), 1);
+}
+
+#[test]
+fn fap_transaction_included_on_fork_is_ready_on_other_fork() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	block_on(pool.submit_one(genesis, SOURCE, xt.clone())).unwrap();
+
+	let header_a = api.push_block_with_parent(genesis, vec![xt], true);
+	block_on(pool.maintain(new_best_block_event(&header_a)));
+	assert_eq!(ready_nonces_at(&pool, header_a.hash()), Vec::<u64>::new());
+
+	// The view at the competing fork is created on demand.
+	let header_b = api.push_block_with_parent(genesis, vec![], true);
+	assert_eq!(ready_nonces_at(&pool, header_b.hash()), vec![209]);
+}
+
+#[test]
+fn fap_watcher_reports_in_block_and_finalized() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+	let mut stream = block_on_stream(watcher);
+	assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+
+	let header = api.push_block_with_parent(genesis, vec![xt], true);
+	block_on(pool.maintain(new_best_block_event(&header)));
+	block_on(
+		pool.maintain(ChainEvent::Finalized { hash: header.hash(), tree_route: Arc::from(vec![]) }),
+	);
+
+	assert_eq!(
+		stream.collect::<Vec<_>>(),
+		vec![
+			TransactionStatus::InBlock((header.hash(), 0)),
+			TransactionStatus::Finalized((header.hash(), 0)),
+		]
+	);
+	assert_eq!(pool.mempool_len(), 0);
+}
+
+#[test]
+fn fap_invalid_transaction_is_removed_from_all_views() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	let header = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header)));
+	let watcher = block_on(pool.submit_and_watch(header.hash(), SOURCE, xt.clone())).unwrap();
+
+	pool.remove_invalid(&[api.hash_and_length(&xt).0]);
+
+	assert_eq!(ready_nonces_at(&pool, genesis), Vec::<u64>::new());
+	assert_eq!(ready_nonces_at(&pool, header.hash()), Vec::<u64>::new());
+	assert_eq!(pool.mempool_len(), 0);
+	assert_eq!(
+		block_on_stream(watcher).collect::<Vec<_>>(),
+		vec![TransactionStatus::Ready, TransactionStatus::Invalid]
+	);
+}
+
+#[test]
+fn fap_submission_is_validated_at_active_views_only() {
+	let (pool, api, genesis) = pool();
+
+	// The view at genesis gets inactive once its child is the best block.
+	let header = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header)));
+
+	let validations = api.validation_requests().len();
+	block_on(pool.submit_one(header.hash(), SOURCE, uxt(Alice, 209))).unwrap();
+
+	assert_eq!(api.validation_requests().len(), validations + 1);
+	assert_eq!(ready_nonces_at(&pool, header.hash()), vec![209]);
+	assert_eq!(ready_nonces_at(&pool, genesis), Vec::<u64>::new());
+}
+
+#[test]
+fn fap_transaction_is_ready_again_after_reorg() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	block_on(pool.submit_one(genesis, SOURCE, xt.clone())).unwrap();
+
+	let header_a1 = api.push_block_with_parent(genesis, vec![xt.clone()], true);
+	block_on(pool.maintain(new_best_block_event(&header_a1)));
+	assert_eq!(ready_nonces_at(&pool, header_a1.hash()), Vec::<u64>::new());
+
+	// The fork not including the transaction becomes the best chain.
+	let header_b1 = api.push_block_with_parent(genesis, vec![], true);
+	let header_b2 = api.push_block_with_parent(header_b1.hash(), vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_b2)));
+
+	assert_eq!(ready_nonces_at(&pool, header_b2.hash()), vec![209]);
+	assert_eq!(ready_nonces_at(&pool, header_a1.hash()), Vec::<u64>::new());
+	assert_eq!(pool.mempool_len(), 1);
+}
+
+#[test]
+fn fap_watcher_reports_retracted_and_finalized_on_new_fork() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	let watcher = block_on(pool.submit_and_watch(genesis, SOURCE, xt.clone())).unwrap();
+
+	let header_a1 = api.push_block_with_parent(genesis, vec![xt.clone()], true);
+	block_on(pool.maintain(new_best_block_event(&header_a1)));
+
+	// Reorg to a fork which includes the transaction later.
+	let header_b1 = api.push_block_with_parent(genesis, vec![], true);
+	let header_b2 = api.push_block_with_parent(header_b1.hash(), vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_b2)));
+	let header_b3 = api.push_block_with_parent(header_b2.hash(), vec![xt], true);
+	block_on(pool.maintain(new_best_block_event(&header_b3)));
+	block_on(pool.maintain(ChainEvent::Finalized {
+		hash: header_b3.hash(),
+		tree_route: Arc::from(vec![header_b1.hash(), header_b2.hash()]),
+	}));
+
+	let statuses = block_on_stream(watcher).collect::<Vec<_>>();
+	let position = |status: TransactionStatus<Hash, Hash>| {
+		statuses.iter().position(|s| *s == status).unwrap_or_else(|| {
+			panic!("{:?} not reported in {:?}", status, statuses);
+		})
+	};
+	assert_eq!(statuses.first(), Some(&TransactionStatus::Ready));
+	assert!(
+		position(TransactionStatus::InBlock((header_a1.hash(), 0))) <
+			position(TransactionStatus::Retracted(header_a1.hash()))
+	);
+	assert!(
+		position(TransactionStatus::Retracted(header_a1.hash())) <
+			position(TransactionStatus::InBlock((header_b3.hash(), 0)))
+	);
+	assert_eq!(statuses.last(), Some(&TransactionStatus::Finalized((header_b3.hash(), 0))));
+	assert_eq!(pool.mempool_len(), 0);
+}
+
+#[test]
+fn fap_finality_removes_views_of_other_forks() {
+	let (pool, api, genesis) = pool();
+
+	let header_a1 = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_a1)));
+	let header_b1 = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_b1)));
+	let header_b2 = api.push_block_with_parent(header_b1.hash(), vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header_b2)));
+
+	block_on(pool.submit_one(header_b2.hash(), SOURCE, uxt(Alice, 209))).unwrap();
+	// Both leaves are active, so the transaction is ready on both forks.
+	assert_eq!(ready_nonces_at(&pool, header_a1.hash()), vec![209]);
+	assert_eq!(ready_nonces_at(&pool, header_b2.hash()), vec![209]);
+
+	block_on(pool.maintain(ChainEvent::Finalized {
+		hash: header_b2.hash(),
+		tree_route: Arc::from(vec![header_b1.hash()]),
+	}));
+
+	// Only the view at the finalized block is left to validate new transactions.
+	let validations = api.validation_requests().len();
+	block_on(pool.submit_one(header_b2.hash(), SOURCE, uxt(Alice, 210))).unwrap();
+	assert_eq!(api.validation_requests().len(), validations + 1);
+	assert_eq!(ready_nonces_at(&pool, header_b2.hash()), vec![209, 210]);
+	assert_eq!(pool.mempool_len(), 2);
+}
+
+#[test]
+fn fap_finality_revalidates_views() {
+	let (pool, api, genesis) = pool();
+	let xt = uxt(Alice, 209);
+
+	let header = api.push_block_with_parent(genesis, vec![], true);
+	block_on(pool.maintain(new_best_block_event(&header)));
+	let watcher = block_on(pool.submit_and_watch(header.hash(), SOURCE, xt.clone())).unwrap();
+	block_on(pool.submit_one(header.hash(), SOURCE, uxt(Bob, 0))).unwrap();
+	let sorted_ready_nonces = || {
+		let mut nonces = ready_nonces_at(&pool, header.hash());
+		nonces.sort();
+		nonces
+	};
+	assert_eq!(sorted_ready_nonces(), vec![0, 209]);
+
+	// The transaction becomes invalid, which is only noticed once the block gets finalized.
+	api.add_invalid(&xt);
+	assert_eq!(sorted_ready_nonces(), vec![0, 209]);
+	block_on(
+		pool.maintain(ChainEvent::Finalized { hash: header.hash(), tree_route: Arc::from(vec![]) }),
+	);
+
+	assert_eq!(sorted_ready_nonces(), vec![0]);
+	assert_eq!(pool.mempool_len(), 1);
+	assert_eq!(
+		block_on_stream(watcher).collect::<Vec<_>>(),
+		vec![TransactionStatus::Ready, TransactionStatus::Invalid]
+	);
+}
@@ -19,6 +19,9 @@
 //!
 //! Namely all ExecutionExtensions that allow mocking
 //! the extra APIs.
+//!
+//! HTTP interactions can be captured once with [`RecordingOffchainExt`] into an
+//! [`HttpFixture`] and later replayed deterministically with [`OffchainState::replay`].
 
 use crate::{
 	offchain::{
@@ -30,10 +33,18 @@ use crate::{
 };
 use std::{
 	collections::{BTreeMap, VecDeque},
+	fmt::Write as _,
 	sync::Arc,
 };
+#[cfg(feature = "offchain-http-fixtures")]
+use std::{
+	fs::File,
+	io::{self, BufReader, BufWriter},
+	path::Path,
+};
 
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 
 /// Pending request.
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -56,6 +67,110 @@ pub struct PendingRequest {
 	pub read: usize,
 	/// Response headers
 	pub response_headers: Vec<(String, String)>,
+	/// Response status code, `200` if not set.
+	pub response_status: Option<u16>,
+}
+
+impl PendingRequest {
+	/// Describe how `self` differs from the `expected` request, one field per line.
+	fn diff(&self, expected: &PendingRequest) -> String {
+		fn text(bytes: &[u8]) -> String {
+			String::from_utf8_lossy(bytes).into_owned()
+		}
+
+		let mut diff = String::new();
+		let mut field = |name: &str, actual: String, expected: String| {
+			if actual != expected {
+				let _ =
+					writeln!(diff, "  {name}:\n    expected: {expected}\n    actual:   {actual}");
+			}
+		};
+		field("method", self.method.clone(), expected.method.clone());
+		field("uri", self.uri.clone(), expected.uri.clone());
+		field("meta", text(&self.meta), text(&expected.meta));
+		field("headers", format!("{:?}", self.headers), format!("{:?}", expected.headers));
+		field("body", text(&self.body), text(&expected.body));
+		field("sent", self.sent.to_string(), expected.sent.to_string());
+		field(
+			"response",
+			format!("{:?}", self.response.as_deref().map(text)),
+			format!("{:?}", expected.response.as_deref().map(text)),
+		);
+		field("read", self.read.to_string(), expected.read.to_string());
+		field(
+			"response_headers",
+			format!("{:?}", self.response_headers),
+			format!("{:?}", expected.response_headers),
+		);
+		field(
+			"response_status",
+			format!("{:?}", self.response_status),
+			format!("{:?}", expected.response_status),
+		);
+		diff
+	}
+}
+
+/// A single recorded HTTP request together with the response it received.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpExchange {
+	/// HTTP method
+	pub method: String,
+	/// URI
+	pub uri: String,
+	/// Request headers
+	pub headers: Vec<(String, String)>,
+	/// Request body
+	#[serde(with = "crate::bytes")]
+	pub body: Vec<u8>,
+	/// Response status code
+	pub response_status: u16,
+	/// Response headers
+	pub response_headers: Vec<(String, String)>,
+	/// Response body
+	#[serde(with = "crate::bytes")]
+	pub response: Vec<u8>,
+}
+
+impl From<HttpExchange> for PendingRequest {
+	fn from(exchange: HttpExchange) -> Self {
+		PendingRequest {
+			method: exchange.method,
+			uri: exchange.uri,
+			headers: exchange.headers,
+			body: exchange.body,
+			sent: true,
+			response: Some(exchange.response),
+			response_headers: exchange.response_headers,
+			response_status: Some(exchange.response_status),
+			..Default::default()
+		}
+	}
+}
+
+/// An ordered list of HTTP exchanges, stored as JSON.
+///
+/// Fixtures are produced by [`RecordingOffchainExt`] and consumed by
+/// [`OffchainState::replay`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpFixture {
+	/// Exchanges in the order their requests were sent.
+	pub exchanges: Vec<HttpExchange>,
+}
+
+#[cfg(feature = "offchain-http-fixtures")]
+impl HttpFixture {
+	/// Load a fixture from a JSON file.
+	pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+		let file = File::open(path)?;
+		serde_json::from_reader(BufReader::new(file)).map_err(Into::into)
+	}
+
+	/// Store the fixture as a JSON file, overwriting any existing file.
+	pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		let file = File::create(path)?;
+		serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(Into::into)
+	}
 }
 
 /// Sharable "persistent" offchain storage for test.
@@ -150,7 +265,13 @@ impl OffchainState {
 				panic!("Missing pending request: {:?}.\n\nAll: {:?}", id, self.requests);
 			},
 			Some(req) => {
-				assert_eq!(*req, expected);
+				if *req != expected {
+					panic!(
+						"Request {} does not match the expected one:\n{}",
+						id,
+						req.diff(&expected)
+					);
+				}
 				req.response = Some(response.into());
 				req.response_headers = response_headers.into_iter().collect();
 			},
@@ -161,7 +282,11 @@ impl OffchainState {
 		if let Some(mut req) = self.expected_requests.pop_back() {
 			let response = req.response.take().expect("Response checked when added.");
 			let headers = std::mem::take(&mut req.response_headers);
+			let status = req.response_status.take();
 			self.fulfill_pending_request(id, req, response, headers);
+			if let Some(req) = self.requests.get_mut(&RequestId(id)) {
+				req.response_status = status;
+			}
 		}
 	}
 
@@ -178,6 +303,16 @@ impl OffchainState {
 		}
 		self.expected_requests.push_front(expected);
 	}
+
+	/// Expect all requests recorded in `fixture`, in the order they were recorded.
+	///
+	/// Each exchange is registered with [`Self::expect_request`], so a request that does not
+	/// match the recording panics with a per-field diff.
+	pub fn replay(&mut self, fixture: HttpFixture) {
+		for exchange in fixture.exchanges {
+			self.expect_request(exchange.into());
+		}
+	}
 }
 
 impl Drop for OffchainState {
@@ -304,7 +439,7 @@ impl offchain::Externalities for TestOffchainExt {
 					panic!("No `response` provided for request with id: {:?}", id)
 				},
 				None => RequestStatus::Invalid,
-				_ => RequestStatus::Finished(200),
+				Some(req) => RequestStatus::Finished(req.response_status.unwrap_or(200)),
 			})
 			.collect()
 	}
@@ -397,6 +532,178 @@ impl offchain::DbExternalities for TestOffchainExt {
 	}
 }
 
+/// Offchain externalities recording every completed HTTP exchange into an [`HttpFixture`].
+///
+/// All calls are forwarded to the wrapped externalities, which would usually perform real
+/// requests against a local HTTP stand-in. Exchanges keep the order in which their requests
+/// were sent, matching [`OffchainState::replay`]. An exchange is added to the fixture once its
+/// response body has been read to the end and all exchanges sent before it have been added.
+pub struct RecordingOffchainExt<E> {
+	inner: E,
+	in_flight: BTreeMap<RequestId, HttpExchange>,
+	// Requests that have been sent but are not part of the fixture yet, in send order.
+	sent: VecDeque<RequestId>,
+	completed: BTreeMap<RequestId, HttpExchange>,
+	fixture: Arc<RwLock<HttpFixture>>,
+}
+
+impl<E: offchain::Externalities> RecordingOffchainExt<E> {
+	/// Wrap `inner` and return a reference to the fixture being recorded.
+	pub fn new(inner: E) -> (Self, Arc<RwLock<HttpFixture>>) {
+		let fixture = Arc::new(RwLock::new(HttpFixture::default()));
+		let ext = Self {
+			inner,
+			in_flight: Default::default(),
+			sent: Default::default(),
+			completed: Default::default(),
+			fixture: fixture.clone(),
+		};
+		(ext, fixture)
+	}
+
+	/// Drop a request that failed, so it does not hold back the ones sent after it.
+	fn discard(&mut self, id: &RequestId) {
+		self.in_flight.remove(id);
+		self.sent.retain(|sent| sent != id);
+		self.flush();
+	}
+
+	/// Move completed exchanges into the fixture, as long as no earlier request is pending.
+	fn flush(&mut self) {
+		while let Some(exchange) = self.sent.front().and_then(|id| self.completed.remove(id)) {
+			self.sent.pop_front();
+			self.fixture.write().exchanges.push(exchange);
+		}
+	}
+}
+
+impl<E: offchain::Externalities> offchain::Externalities for RecordingOffchainExt<E> {
+	fn is_validator(&self) -> bool {
+		self.inner.is_validator()
+	}
+
+	fn network_state(&self) -> Result<OpaqueNetworkState, ()> {
+		self.inner.network_state()
+	}
+
+	fn timestamp(&mut self) -> Timestamp {
+		self.inner.timestamp()
+	}
+
+	fn sleep_until(&mut self, deadline: Timestamp) {
+		self.inner.sleep_until(deadline)
+	}
+
+	fn random_seed(&mut self) -> [u8; 32] {
+		self.inner.random_seed()
+	}
+
+	fn http_request_start(
+		&mut self,
+		method: &str,
+		uri: &str,
+		meta: &[u8],
+	) -> Result<RequestId, ()> {
+		let id = self.inner.http_request_start(method, uri, meta)?;
+		self.in_flight.insert(
+			id,
+			HttpExchange { method: method.into(), uri: uri.into(), ..Default::default() },
+		);
+		Ok(id)
+	}
+
+	fn http_request_add_header(
+		&mut self,
+		request_id: RequestId,
+		name: &str,
+		value: &str,
+	) -> Result<(), ()> {
+		self.inner.http_request_add_header(request_id, name, value)?;
+		if let Some(exchange) = self.in_flight.get_mut(&request_id) {
+			exchange.headers.push((name.into(), value.into()));
+		}
+		Ok(())
+	}
+
+	fn http_request_write_body(
+		&mut self,
+		request_id: RequestId,
+		chunk: &[u8],
+		deadline: Option<Timestamp>,
+	) -> Result<(), HttpError> {
+		self.inner.http_request_write_body(request_id, chunk, deadline)?;
+		if let Some(exchange) = self.in_flight.get_mut(&request_id) {
+			exchange.body.extend(chunk);
+			// An empty chunk sends the request; that is where its replay is matched.
+			if chunk.is_empty() && !self.sent.contains(&request_id) {
+				self.sent.push_back(request_id);
+			}
+		}
+		Ok(())
+	}
+
+	fn http_response_wait(
+		&mut self,
+		ids: &[RequestId],
+		deadline: Option<Timestamp>,
+	) -> Vec<RequestStatus> {
+		let statuses = self.inner.http_response_wait(ids, deadline);
+		for (id, status) in ids.iter().zip(&statuses) {
+			match status {
+				RequestStatus::Finished(code) =>
+					if let Some(exchange) = self.in_flight.get_mut(id) {
+						exchange.response_status = *code;
+					},
+				RequestStatus::Invalid | RequestStatus::IoError => self.discard(id),
+				RequestStatus::DeadlineReached => {},
+			}
+		}
+		statuses
+	}
+
+	fn http_response_headers(&mut self, request_id: RequestId) -> Vec<(Vec<u8>, Vec<u8>)> {
+		let headers = self.inner.http_response_headers(request_id);
+		if let Some(exchange) = self.in_flight.get_mut(&request_id) {
+			exchange.response_headers = headers
+				.iter()
+				.map(|(k, v)| {
+					(
+						String::from_utf8_lossy(k).into_owned(),
+						String::from_utf8_lossy(v).into_owned(),
+					)
+				})
+				.collect();
+		}
+		headers
+	}
+
+	fn http_response_read_body(
+		&mut self,
+		request_id: RequestId,
+		buffer: &mut [u8],
+		deadline: Option<Timestamp>,
+	) -> Result<usize, HttpError> {
+		let result = self.inner.http_response_read_body(request_id, buffer, deadline);
+		match result {
+			Ok(0) =>
+				if let Some(exchange) = self.in_flight.remove(&request_id) {
+					self.completed.insert(request_id, exchange);
+					self.flush();
+				},
+			Ok(read) =>
+				if let Some(exchange) = self.in_flight.get_mut(&request_id) {
+					exchange.response.extend_from_slice(&buffer[..read]);
+				},
+			Err(_) => self.discard(&request_id),
+		}
+		result
+	}
+
+	fn set_authorized_nodes(&mut self, nodes: Vec<OpaquePeerId>, authorized_only: bool) {
+		self.inner.set_authorized_nodes(nodes, authorized_only)
+	}
+}
+
 /// The internal state of the fake transaction pool.
 #[derive(Default)]
 pub struct PoolState {
@@ -431,3 +738,117 @@ impl TransactionPool for TestTransactionPoolExt {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::offchain::Externalities;
+
+	fn stand_in() -> (TestOffchainExt, Arc<RwLock<OffchainState>>) {
+		let (ext, state) = TestOffchainExt::new();
+		state.write().expect_request(PendingRequest {
+			method: "POST".into(),
+			uri: "http://localhost:8080/price".into(),
+			headers: vec![("Content-Type".into(), "application/json".into())],
+			body: b"{\"asset\":\"DOT\"}".to_vec(),
+			sent: true,
+			response: Some(b"{\"price\":7}".to_vec()),
+			response_headers: vec![("Server".into(), "stand-in".into())],
+			response_status: Some(201),
+			..Default::default()
+		});
+		(ext, state)
+	}
+
+	fn fetch_price(ext: &mut impl Externalities, uri: &str) -> (RequestStatus, Vec<u8>) {
+		let id = ext.http_request_start("POST", uri, &[]).unwrap();
+		ext.http_request_add_header(id, "Content-Type", "application/json").unwrap();
+		ext.http_request_write_body(id, b"{\"asset\":\"DOT\"}", None).unwrap();
+		ext.http_request_write_body(id, &[], None).unwrap();
+		let status = ext.http_response_wait(&[id], None)[0];
+		ext.http_response_headers(id);
+		(status, read_body(ext, id))
+	}
+
+	#[test]
+	fn recorded_exchanges_replay() {
+		let (stand_in, _state) = stand_in();
+		let (mut recorder, fixture) = RecordingOffchainExt::new(stand_in);
+		let recorded = fetch_price(&mut recorder, "http://localhost:8080/price");
+		assert_eq!(recorded, (RequestStatus::Finished(201), b"{\"price\":7}".to_vec()));
+
+		let fixture = fixture.read().clone();
+		assert_eq!(fixture.exchanges.len(), 1);
+		assert_eq!(
+			fixture.exchanges[0].response_headers,
+			vec![("Server".into(), "stand-in".into())]
+		);
+		let json = serde_json::to_string(&fixture).unwrap();
+		let fixture: HttpFixture = serde_json::from_str(&json).unwrap();
+
+		let (mut ext, state) = TestOffchainExt::new();
+		state.write().replay(fixture);
+		assert_eq!(fetch_price(&mut ext, "http://localhost:8080/price"), recorded);
+	}
+
+	fn read_body(ext: &mut impl Externalities, id: RequestId) -> Vec<u8> {
+		let mut response = Vec::new();
+		let mut buffer = [0u8; 4];
+		loop {
+			match ext.http_response_read_body(id, &mut buffer, None).unwrap() {
+				0 => break response,
+				read => response.extend_from_slice(&buffer[..read]),
+			}
+		}
+	}
+
+	// Sends a request to each of `uris` in order, then reads the responses in reverse order.
+	fn fetch_all_reversed(ext: &mut impl Externalities, uris: &[&str]) -> Vec<Vec<u8>> {
+		let ids: Vec<_> = uris
+			.iter()
+			.map(|uri| {
+				let id = ext.http_request_start("GET", uri, &[]).unwrap();
+				ext.http_request_write_body(id, &[], None).unwrap();
+				id
+			})
+			.collect();
+		ext.http_response_wait(&ids, None);
+		ids.iter().rev().map(|id| read_body(ext, *id)).collect()
+	}
+
+	#[test]
+	fn exchanges_are_recorded_in_send_order() {
+		let uris = ["http://localhost:8080/price", "http://localhost:8080/volume"];
+		let (stand_in, state) = TestOffchainExt::new();
+		for (uri, response) in uris.iter().zip([b"7".to_vec(), b"1000".to_vec()]) {
+			state.write().expect_request(PendingRequest {
+				method: "GET".into(),
+				uri: (*uri).into(),
+				sent: true,
+				response: Some(response),
+				response_status: Some(200),
+				..Default::default()
+			});
+		}
+		let (mut recorder, fixture) = RecordingOffchainExt::new(stand_in);
+		let recorded = fetch_all_reversed(&mut recorder, &uris);
+		assert_eq!(recorded, vec![b"1000".to_vec(), b"7".to_vec()]);
+
+		let fixture = fixture.read().clone();
+		let recorded_uris: Vec<_> = fixture.exchanges.iter().map(|e| e.uri.as_str()).collect();
+		assert_eq!(recorded_uris, uris);
+
+		let (mut ext, state) = TestOffchainExt::new();
+		state.write().replay(fixture);
+		assert_eq!(fetch_all_reversed(&mut ext, &uris), recorded);
+	}
+
+	#[test]
+	#[should_panic(
+		expected = "uri:\n    expected: http://localhost:8080/price\n    actual:   http://localhost:8080/volume"
+	)]
+	fn mismatching_request_shows_diff() {
+		let (mut ext, _state) = stand_in();
+		fetch_price(&mut ext, "http://localhost:8080/volume");
+	}
+}
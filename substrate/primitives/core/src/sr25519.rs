@@ -374,6 +374,11 @@ pub mod vrf {
 			self.extra = Some(extra);
 			self
 		}
+
+		/// Extra data signed by the VRF, if any.
+		pub fn extra(&self) -> Option<&VrfTranscript> {
+			self.extra.as_ref()
+		}
 	}
 
 	/// VRF signature data
@@ -50,7 +50,7 @@ pub enum InvalidStatement {
 /// The source of the statement.
 ///
 /// Depending on the source we might apply different validation schemes.
-#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum StatementSource {
 	/// Statement is coming from the on-chain worker.
 	Chain,
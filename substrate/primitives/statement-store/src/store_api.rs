@@ -61,6 +61,9 @@ pub enum SubmitResult {
 /// Result type for `Error`
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Stream of the statements newly accepted by the store.
+pub type StatementStream = std::pin::Pin<Box<dyn futures::Stream<Item = Statement> + Send>>;
+
 /// Statement store API.
 pub trait StatementStore: Send + Sync {
 	/// Return all statements.
@@ -85,6 +88,22 @@ pub trait StatementStore: Send + Sync {
 	/// Submit a statement.
 	fn submit(&self, statement: Statement, source: StatementSource) -> SubmitResult;
 
+	/// Submit a statement received from the network peer identified by `peer`.
+	///
+	/// The store may apply per-peer quotas. By default, this is the same as submitting with
+	/// [`StatementSource::Network`].
+	fn submit_from_peer(&self, statement: Statement, peer: &[u8]) -> SubmitResult {
+		let _ = peer;
+		self.submit(statement, StatementSource::Network)
+	}
+
 	/// Remove a statement from the store.
 	fn remove(&self, hash: &Hash) -> Result<()>;
+
+	/// Return a stream of the statements accepted as new by the store from now on.
+	///
+	/// By default, the stream never yields, for stores that don't notify about new statements.
+	fn statement_stream(&self) -> StatementStream {
+		Box::pin(futures::stream::pending())
+	}
 }
@@ -45,7 +45,7 @@ pub type Service = sc_service::PartialComponents<
 	FullBackend,
 	FullSelectChain,
 	sc_consensus::DefaultImportQueue<Block>,
-	sc_transaction_pool::FullPool<Block, FullClient>,
+	sc_transaction_pool::TransactionPoolWrapper<Block, FullClient>,
 	Option<Telemetry>,
 >;
 
@@ -78,7 +78,7 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
-	let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+	let transaction_pool = sc_transaction_pool::TransactionPoolWrapper::new_full(
 		config.transaction_pool.clone(),
 		config.role.is_authority().into(),
 		config.prometheus_registry(),
@@ -186,7 +186,7 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		task_manager: &mut task_manager,
 		transaction_pool: transaction_pool.clone(),
 		rpc_builder: rpc_extensions_builder,
-		backend,
+		backend: backend.clone(),
 		system_rpc_tx,
 		tx_handler_controller,
 		sync_service,
@@ -233,6 +233,9 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 						create_empty: true,
 						finalize: true,
 						parent_hash: None,
+						timestamp: None,
+						slot: None,
+						set_best: false,
 						sender: None,
 					})
 					.unwrap();
@@ -250,6 +253,8 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 				create_inherent_data_providers: move |_, ()| async move {
 					Ok(sp_timestamp::InherentDataProvider::from_system_time())
 				},
+				backend: Some(backend),
+				aux_revert: None,
 			};
 			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 